@@ -15,6 +15,7 @@ use crate::{
     error::Error,
     evm::opcodes::{gen_associated_ops, gen_begin_tx_ops, gen_end_tx_ops},
     operation::{CallContextField, Operation, RWCounter, StartOp, RW},
+    precompile::{ecrecover_sign_data, PrecompileCalls},
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
 };
@@ -25,11 +26,12 @@ use core::fmt::Debug;
 use eth_types::{
     self, geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Address, GethExecStep, GethExecTrace, ToWord, Word,
+    Address, BigEndianHash, Bytes, GethExecStep, GethExecTrace, ToWord, Word, H256,
 };
 use ethers_providers::JsonRpcClient;
 pub use execution::{
     CopyDataType, CopyEvent, CopyStep, ExecState, ExecStep, ExpEvent, ExpStep, NumberOrHash,
+    PrecompileEvent,
 };
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
@@ -68,6 +70,41 @@ pub struct CircuitsParams {
     /// calculated, so the same circuit will not be able to prove different
     /// witnesses.
     pub max_keccak_rows: usize,
+    /// Pad the SHA-256 circuit with this number of rows to a static capacity.
+    /// When 0, the SHA-256 circuit number of rows will be dynamically
+    /// calculated, so the same circuit will not be able to prove different
+    /// witnesses.
+    pub max_sha256_rows: usize,
+    /// Pad the RIPEMD-160 circuit with this number of rows to a static
+    /// capacity.  When 0, the RIPEMD-160 circuit number of rows will be
+    /// dynamically calculated, so the same circuit will not be able to prove
+    /// different witnesses.
+    pub max_ripemd160_rows: usize,
+    /// Maximum number of ecrecover precompile calls that the EcRecover Circuit
+    /// can verify.
+    pub max_ecrecover: usize,
+    /// Maximum number of ecAdd precompile calls that the EC Circuit can
+    /// verify.
+    pub max_ec_add: usize,
+    /// Maximum number of ecMul precompile calls that the EC Circuit can
+    /// verify.
+    pub max_ec_mul: usize,
+    /// Maximum number of modexp precompile calls that the ModExp Circuit can
+    /// verify.  The base, exponent and modulus of every call are supported up
+    /// to [`MODEXP_SIZE_LIMIT`](crate::precompile::MODEXP_SIZE_LIMIT) bytes.
+    pub max_modexp: usize,
+    /// Maximum number of blake2f precompile calls that the BLAKE2F Circuit can
+    /// verify, counting the calls failing for lack of gas or on an invalid
+    /// final block flag.
+    pub max_blake2f: usize,
+    /// Maximum number of rounds of a blake2f precompile call that the BLAKE2F
+    /// Circuit can verify, every call being laid out for this number of
+    /// rounds.
+    pub max_blake2f_rounds: usize,
+    /// Pad the MPT circuit with this number of rows to a static capacity.
+    /// When 0, the MPT circuit number of rows will be dynamically calculated,
+    /// so the same circuit will not be able to prove different witnesses.
+    pub max_mpt_rows: usize,
 }
 
 impl Default for CircuitsParams {
@@ -84,6 +121,15 @@ impl Default for CircuitsParams {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_ecrecover: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_modexp: 0,
+            max_blake2f: 0,
+            max_blake2f_rounds: 0,
+            max_mpt_rows: 0,
         }
     }
 }
@@ -273,9 +319,10 @@ impl<'a> CircuitInputBuilder {
         // TODO: Move into gen_associated_steps with
         // - execution_state: BeginTx
         // - op: None
-        // Generate BeginTx step
-        let begin_tx_step = gen_begin_tx_ops(&mut self.state_ref(&mut tx, &mut tx_ctx))?;
-        tx.steps_mut().push(begin_tx_step);
+        // Generate BeginTx step, followed by the precompile step for a transaction
+        // to a precompiled contract
+        let begin_tx_steps = gen_begin_tx_ops(&mut self.state_ref(&mut tx, &mut tx_ctx))?;
+        tx.steps_mut().extend(begin_tx_steps);
 
         for (index, geth_step) in geth_trace.struct_logs.iter().enumerate() {
             let mut state_ref = self.state_ref(&mut tx, &mut tx_ctx);
@@ -291,9 +338,10 @@ impl<'a> CircuitInputBuilder {
         // TODO: Move into gen_associated_steps with
         // - execution_state: EndTx
         // - op: None
-        // Generate EndTx step
-        let end_tx_step = gen_end_tx_ops(&mut self.state_ref(&mut tx, &mut tx_ctx))?;
-        tx.steps_mut().push(end_tx_step);
+        // Generate EndTx step, followed by the steps destructing the accounts self
+        // destructed in the transaction
+        let end_tx_steps = gen_end_tx_ops(&mut self.state_ref(&mut tx, &mut tx_ctx))?;
+        tx.steps_mut().extend(end_tx_steps);
 
         self.sdb.commit_tx();
         self.block.txs.push(tx);
@@ -315,8 +363,10 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
     }
     // EVM Circuit
     keccak_inputs.extend_from_slice(&block.sha3_inputs);
-    // MPT Circuit
-    // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
+    // EcRecover Circuit
+    keccak_inputs.extend_from_slice(&keccak_inputs_ecrecover_circuit(&block.precompile_events));
+    // The inputs of the MPT Circuit, the trie nodes in the proofs of the MPT
+    // updates, are added along with the updates in the witness block.
     Ok(keccak_inputs)
 }
 
@@ -363,6 +413,17 @@ pub fn keccak_inputs_tx_circuit(
     Ok(inputs)
 }
 
+/// Generate the keccak inputs required by the EcRecover Circuit from the
+/// precompile events.
+pub fn keccak_inputs_ecrecover_circuit(precompile_events: &[PrecompileEvent]) -> Vec<Vec<u8>> {
+    let sign_datas: Vec<SignData> = precompile_events
+        .iter()
+        .filter(|event| event.precompile == PrecompileCalls::ECRecover)
+        .filter_map(|event| ecrecover_sign_data(&event.input))
+        .collect();
+    keccak_inputs_sign_verify(&sign_datas)
+}
+
 /// Retrieve the init_code from memory for {CREATE, CREATE2}
 pub fn get_create_init_code<'a>(
     call_ctx: &'a CallContext,
@@ -441,11 +502,27 @@ pub fn build_state_code_db(
         )
     }
 
+    (sdb, build_code_db(codes))
+}
+
+/// Build a partial StateDB from step 3, after verifying the proofs against the
+/// state root of the previous block.  The proofs are kept in the StateDB to
+/// build the MPT witness.
+pub fn build_verified_state_code_db(
+    prev_state_root: Word,
+    proofs: Vec<eth_types::EIP1186ProofResponse>,
+    codes: HashMap<Address, Vec<u8>>,
+) -> Result<(StateDB, CodeDB), Error> {
+    let sdb = StateDB::from_proofs(H256::from_uint(&prev_state_root), proofs)?;
+    Ok((sdb, build_code_db(codes)))
+}
+
+fn build_code_db(codes: HashMap<Address, Vec<u8>>) -> CodeDB {
     let mut code_db = CodeDB::new();
     for (_address, code) in codes {
         code_db.insert(code.clone());
     }
-    (sdb, code_db)
+    code_db
 }
 
 impl<P: JsonRpcClient> BuilderClient<P> {
@@ -559,6 +636,16 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         build_state_code_db(proofs, codes)
     }
 
+    /// Step 4, verifying the proofs of step 3 against the state root of the
+    /// previous block.
+    pub fn build_verified_state_code_db(
+        prev_state_root: Word,
+        proofs: Vec<eth_types::EIP1186ProofResponse>,
+        codes: HashMap<Address, Vec<u8>>,
+    ) -> Result<(StateDB, CodeDB), Error> {
+        build_verified_state_code_db(prev_state_root, proofs, codes)
+    }
+
     /// Step 5. For each step in TxExecTraces, gen the associated ops and state
     /// circuit inputs
     pub fn gen_inputs_from_state(
@@ -570,18 +657,29 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         history_hashes: Vec<Word>,
         prev_state_root: Word,
     ) -> Result<CircuitInputBuilder, Error> {
-        let block = Block::new(
+        let mut block = Block::new(
             self.chain_id,
             history_hashes,
             prev_state_root,
             eth_block,
             self.circuits_params,
         )?;
+        block.prestate_proofs = sdb.proofs().to_vec();
         let mut builder = CircuitInputBuilder::new(sdb, code_db, block);
         builder.handle_block(eth_block, geth_traces)?;
         Ok(builder)
     }
 
+    /// Step 6. Query geth for the nodes of the tries missing from the proofs
+    /// of step 3, which the deletions of the block merge into their parent.
+    pub async fn get_trie_nodes(&self, hashes: Vec<H256>) -> Result<Vec<Bytes>, Error> {
+        let mut nodes = Vec::new();
+        for hash in hashes {
+            nodes.push(self.cli.get_trie_node(hash).await?);
+        }
+        Ok(nodes)
+    }
+
     /// Perform all the steps to generate the circuit inputs
     pub async fn gen_inputs(
         &self,
@@ -597,8 +695,9 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             self.get_block(block_num).await?;
         let access_set = Self::get_state_accesses(&eth_block, &geth_traces)?;
         let (proofs, codes) = self.get_state(block_num, access_set).await?;
-        let (state_db, code_db) = Self::build_state_code_db(proofs, codes);
-        let builder = self.gen_inputs_from_state(
+        let (state_db, code_db) =
+            Self::build_verified_state_code_db(prev_state_root, proofs, codes)?;
+        let mut builder = self.gen_inputs_from_state(
             state_db,
             code_db,
             &eth_block,
@@ -606,6 +705,9 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             history_hashes,
             prev_state_root,
         )?;
+        builder.block.trie_nodes = self
+            .get_trie_nodes(builder.block.missing_trie_nodes()?)
+            .await?;
         Ok((builder, eth_block))
     }
 }
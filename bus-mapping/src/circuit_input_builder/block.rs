@@ -2,12 +2,17 @@
 
 use super::{
     execution::ExecState, transaction::Transaction, CircuitsParams, CopyEvent, ExecStep, ExpEvent,
+    PrecompileEvent,
 };
 use crate::{
-    operation::{OperationContainer, RWCounter},
+    operation::{AccountField, OperationContainer, RWCounter},
     Error,
 };
-use eth_types::{evm_unimplemented, Address, Hash, Word};
+use eth_types::{
+    evm_unimplemented,
+    mpt::{PartialTrie, TrieAccount},
+    Address, BigEndianHash, Bytes, Hash, ToBigEndian, Word, H256,
+};
 use std::collections::HashMap;
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
@@ -86,11 +91,19 @@ pub struct Block {
     pub sha3_inputs: Vec<Vec<u8>>,
     /// Exponentiation events in the block.
     pub exp_events: Vec<ExpEvent>,
+    /// Precompiled contract calls in the block.
+    pub precompile_events: Vec<PrecompileEvent>,
     code: HashMap<Hash, Vec<u8>>,
     /// Circuits Setup Paramteres
     pub circuits_params: CircuitsParams,
     /// Original block from geth
     pub eth_block: eth_types::Block<eth_types::Transaction>,
+    /// EIP-1186 proofs of the prestate against `prev_state_root`, which are
+    /// empty unless the StateDB was built from verified proofs.
+    pub prestate_proofs: Vec<eth_types::EIP1186ProofResponse>,
+    /// Nodes of the state and storage tries missing from `prestate_proofs`,
+    /// which the deletions of the block merge into their parent.
+    pub trie_nodes: Vec<Bytes>,
 }
 
 impl Block {
@@ -139,10 +152,13 @@ impl Block {
             },
             copy_events: Vec::new(),
             exp_events: Vec::new(),
+            precompile_events: Vec::new(),
             code: HashMap::new(),
             sha3_inputs: Vec::new(),
             circuits_params,
             eth_block: eth_block.clone(),
+            prestate_proofs: Vec::new(),
+            trie_nodes: Vec::new(),
         })
     }
 
@@ -155,6 +171,61 @@ impl Block {
     pub fn txs_mut(&mut self) -> &mut Vec<Transaction> {
         &mut self.txs
     }
+
+    /// Return the hashes of the nodes of the state and storage tries, missing
+    /// from `prestate_proofs`, which the deletions of accounts and storage
+    /// slots in the block may merge into their parent.  They must be added to
+    /// `trie_nodes` to compute the state roots of the MPT updates.
+    pub fn missing_trie_nodes(&self) -> Result<Vec<H256>, Error> {
+        let accounts = PartialTrie::new(
+            H256::from_uint(&self.prev_state_root),
+            self.prestate_proofs
+                .iter()
+                .flat_map(|proof| proof.account_proof.iter()),
+        );
+        let mut hashes = Vec::new();
+        // A code hash of 0 deletes the account.
+        for op in self.container.account.iter().map(|op| op.op()) {
+            if op.field == AccountField::CodeHash && op.value.is_zero() && !op.value_prev.is_zero()
+            {
+                hashes.extend(accounts.collapse_siblings(op.address.as_bytes())?);
+            }
+        }
+        for proof in self.prestate_proofs.iter() {
+            let deleted_keys: Vec<Word> = self
+                .container
+                .storage
+                .iter()
+                .map(|op| op.op())
+                .filter(|op| {
+                    op.address == proof.address && op.value.is_zero() && !op.value_prev.is_zero()
+                })
+                .map(|op| op.key)
+                .collect();
+            if deleted_keys.is_empty() {
+                continue;
+            }
+            let storage_root = accounts
+                .get(proof.address.as_bytes())?
+                .map(|bytes| TrieAccount::from_rlp(&bytes))
+                .transpose()?
+                .unwrap_or_default()
+                .storage_root;
+            let storage = PartialTrie::new(
+                storage_root,
+                proof
+                    .storage_proof
+                    .iter()
+                    .flat_map(|storage_proof| storage_proof.proof.iter()),
+            );
+            for key in deleted_keys {
+                hashes.extend(storage.collapse_siblings(&key.to_be_bytes())?);
+            }
+        }
+        hashes.sort();
+        hashes.dedup();
+        Ok(hashes)
+    }
 }
 
 impl Block {
@@ -166,4 +237,8 @@ impl Block {
     pub fn add_exp_event(&mut self, event: ExpEvent) {
         self.exp_events.push(event);
     }
+    /// Push a precompiled contract call event to the block.
+    pub fn add_precompile_event(&mut self, event: PrecompileEvent) {
+        self.precompile_events.push(event);
+    }
 }
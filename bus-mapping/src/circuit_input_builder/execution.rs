@@ -2,11 +2,11 @@
 
 use crate::{
     circuit_input_builder::CallContext, error::ExecError, exec_trace::OperationRef,
-    operation::RWCounter,
+    operation::RWCounter, precompile::PrecompileCalls,
 };
 use eth_types::{
     evm_types::{Gas, GasCost, OpcodeId, ProgramCounter},
-    GethExecStep, Word, H256,
+    Address, GethExecStep, Word, H256,
 };
 use gadgets::impl_expr;
 use halo2_proofs::plonk::Expression;
@@ -119,8 +119,12 @@ pub enum ExecState {
     BeginTx,
     /// Virtual step End Tx
     EndTx,
+    /// Virtual step destructing an account destructed in the tx
+    DestructAccount,
     /// Virtual step End Block
     EndBlock,
+    /// Virtual step executing a call to a precompiled contract
+    Precompile(PrecompileCalls),
 }
 
 impl ExecState {
@@ -159,6 +163,11 @@ impl ExecState {
             false
         }
     }
+
+    /// Returns `true` if `ExecState` is a call to a precompiled contract.
+    pub fn is_precompiled(&self) -> bool {
+        matches!(self, ExecState::Precompile(_))
+    }
 }
 
 /// Defines the various source/destination types for a copy event.
@@ -175,10 +184,22 @@ pub enum CopyDataType {
     TxCalldata,
     /// When the destination for the copy event is tx's log.
     TxLog,
-    /// When the destination rows are not directly for copying but for a special
-    /// scenario where we wish to accumulate the value (RLC) over all rows.
-    /// This is used for Copy Lookup from SHA3 opcode verification.
+    /// When the source/destination rows are not directly for copying but for a
+    /// special scenario where we wish to accumulate the value (RLC) over all
+    /// rows. This is used for Copy Lookup from SHA3 opcode verification, and
+    /// for the input and output of precompiled contract calls.
     RlcAcc,
+    /// When the source/destination for the copy event is the list of
+    /// addresses in a tx's access list, which are written as
+    /// `TxAccessListAccount` at the beginning of the tx. The read rows
+    /// accumulate the addresses (RLC) to be checked against the tx table.
+    AccessListAddresses,
+    /// When the source/destination for the copy event is the list of
+    /// storage keys in a tx's access list, which are written as
+    /// `TxAccessListAccountStorage` at the beginning of the tx. The read rows
+    /// hold the address of each storage key and accumulate the (address,
+    /// storage key) pairs (RLC) to be checked against the tx table.
+    AccessListStorageKeys,
 }
 
 impl From<CopyDataType> for usize {
@@ -241,9 +262,23 @@ pub struct CopyEvent {
     pub rw_counter_start: RWCounter,
     /// Represents the list of (bytes, is_code) copied during this copy event
     pub bytes: Vec<(u8, bool)>,
+    /// Represents the list of (address, storage key, is_warm_prev) written
+    /// during this copy event in case of an access list source. The storage
+    /// key is zero for [`CopyDataType::AccessListAddresses`].
+    pub access_list: Vec<(Address, Word, bool)>,
 }
 
 impl CopyEvent {
+    /// Number of copy steps (read-write pairs) in this copy event
+    pub fn copy_length(&self) -> usize {
+        match self.src_type {
+            CopyDataType::AccessListAddresses | CopyDataType::AccessListStorageKeys => {
+                self.access_list.len()
+            }
+            _ => self.bytes.len(),
+        }
+    }
+
     /// rw counter at step index
     pub fn rw_counter(&self, step_index: usize) -> u64 {
         u64::try_from(self.rw_counter_start.0).unwrap() + self.rw_counter_increase(step_index)
@@ -251,29 +286,36 @@ impl CopyEvent {
 
     /// rw counter increase left at step index
     pub fn rw_counter_increase_left(&self, step_index: usize) -> u64 {
-        self.rw_counter(self.bytes.len() * 2) - self.rw_counter(step_index)
+        self.rw_counter(self.copy_length() * 2) - self.rw_counter(step_index)
     }
 
     /// Number of rw operations performed by this copy event
     pub fn rw_counter_delta(&self) -> u64 {
-        self.rw_counter_increase(self.bytes.len() * 2)
+        self.rw_counter_increase(self.copy_length() * 2)
     }
 
     // increase in rw counter from the start of the copy event to step index
     fn rw_counter_increase(&self, step_index: usize) -> u64 {
         let source_rw_increase = match self.src_type {
-            CopyDataType::Bytecode | CopyDataType::TxCalldata => 0,
+            CopyDataType::RlcAcc
+            | CopyDataType::Bytecode
+            | CopyDataType::TxCalldata
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys => 0,
             CopyDataType::Memory => std::cmp::min(
                 u64::try_from(step_index + 1).unwrap() / 2,
                 self.src_addr_end
                     .checked_sub(self.src_addr)
                     .unwrap_or_default(),
             ),
-            CopyDataType::RlcAcc | CopyDataType::TxLog | CopyDataType::Padding => unreachable!(),
+            CopyDataType::TxLog | CopyDataType::Padding => unreachable!(),
         };
         let destination_rw_increase = match self.dst_type {
             CopyDataType::RlcAcc | CopyDataType::Bytecode => 0,
-            CopyDataType::TxLog | CopyDataType::Memory => u64::try_from(step_index).unwrap() / 2,
+            CopyDataType::TxLog
+            | CopyDataType::Memory
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys => u64::try_from(step_index).unwrap() / 2,
             CopyDataType::TxCalldata | CopyDataType::Padding => unreachable!(),
         };
         source_rw_increase + destination_rw_increase
@@ -331,3 +373,20 @@ impl Default for ExpEvent {
         }
     }
 }
+
+/// Event representing a call to a precompiled contract, with the output, gas
+/// cost and failure flag of the precompile on its input given enough gas.
+#[derive(Clone, Debug)]
+pub struct PrecompileEvent {
+    /// Precompiled contract that was called.
+    pub precompile: PrecompileCalls,
+    /// Input bytes passed to the precompile.
+    pub input: Vec<u8>,
+    /// Output bytes returned by the precompile, empty when it fails.
+    pub output: Vec<u8>,
+    /// Gas cost of the precompile.
+    pub gas_cost: u64,
+    /// Whether the precompile succeeds, as it fails on some invalid inputs
+    /// (e.g. an invalid elliptic curve point).
+    pub is_success: bool,
+}
@@ -2,7 +2,7 @@
 
 use super::{
     get_call_memory_offset_length, get_create_init_code, Block, BlockContext, Call, CallContext,
-    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, Transaction,
+    CallKind, CodeSource, CopyEvent, ExecState, ExecStep, ExpEvent, PrecompileEvent, Transaction,
     TransactionContext,
 };
 use crate::{
@@ -10,8 +10,8 @@ use crate::{
     exec_trace::OperationRef,
     operation::{
         AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
-        RW,
+        StackOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxLogField, TxLogOp,
+        TxReceiptField, TxReceiptOp, RW,
     },
     state_db::{CodeDB, StateDB},
     Error,
@@ -19,6 +19,7 @@ use crate::{
 use eth_types::{
     evm_types::{
         gas_utils::memory_expansion_gas_cost, Gas, GasCost, MemoryAddress, OpcodeId, StackAddress,
+        INVALID_INIT_CODE_FIRST_BYTE, MAX_CODE_SIZE,
     },
     Address, Bytecode, GethExecStep, ToAddress, ToBigEndian, ToWord, Word, H256, U256,
 };
@@ -93,6 +94,19 @@ impl<'a> CircuitInputStateRef<'a> {
         }
     }
 
+    /// Create a new step destructing an account after the EndTx step
+    /// `end_tx_step`
+    pub fn new_destruct_step(&self, exec_state: ExecState, end_tx_step: &ExecStep) -> ExecStep {
+        ExecStep {
+            exec_state,
+            gas_left: end_tx_step.gas_left,
+            rwc: self.block_ctx.rwc,
+            reversible_write_counter: end_tx_step.reversible_write_counter,
+            log_id: end_tx_step.log_id,
+            ..Default::default()
+        }
+    }
+
     /// Push an [`Operation`](crate::operation::Operation) into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter) and then adds a
@@ -342,6 +356,27 @@ impl<'a> CircuitInputStateRef<'a> {
         self.push_op(step, RW::READ, op);
     }
 
+    /// Push the read type [`AccountOp`]s needed to decide whether the account
+    /// at `address` is empty (EIP-161): its code hash and, when the account
+    /// exists, its nonce and balance.  Returns whether the account is empty.
+    pub fn account_empty_read(&mut self, step: &mut ExecStep, address: Address) -> bool {
+        let account = self.sdb.get_account(&address).1.clone();
+        if account.is_empty() {
+            // Empty accounts are encoded as non-existing (code_hash=0) in the State
+            // Circuit.
+            self.account_read(step, address, AccountField::CodeHash, Word::zero());
+            return true;
+        }
+        for (field, value) in [
+            (AccountField::CodeHash, account.code_hash.to_word()),
+            (AccountField::Nonce, account.nonce),
+            (AccountField::Balance, account.balance),
+        ] {
+            self.account_read(step, address, field, value);
+        }
+        false
+    }
+
     /// Push a write type [`AccountOp`] into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter), and then
@@ -461,6 +496,35 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok(())
     }
 
+    /// Push a write type [`TxAccessListAccountStorageOp`] into the
+    /// [`OperationContainer`](crate::operation::OperationContainer) with the
+    /// next [`RWCounter`](crate::operation::RWCounter), and then
+    /// adds a reference to the stored operation ([`OperationRef`]) inside
+    /// the bus-mapping instance of the current [`ExecStep`].  Then increase
+    /// the `block_ctx` [`RWCounter`](crate::operation::RWCounter)  by one.
+    pub fn tx_accesslist_account_storage_write(
+        &mut self,
+        step: &mut ExecStep,
+        tx_id: usize,
+        address: Address,
+        key: Word,
+        is_warm: bool,
+        is_warm_prev: bool,
+    ) -> Result<(), Error> {
+        self.push_op(
+            step,
+            RW::WRITE,
+            TxAccessListAccountStorageOp {
+                tx_id,
+                address,
+                key,
+                is_warm,
+                is_warm_prev,
+            },
+        );
+        Ok(())
+    }
+
     /// Push 2 reversible [`AccountOp`] to update `sender` and `receiver`'s
     /// balance by `value`. If `fee` is existing (not None), also need to push 1
     /// non-reversible [`AccountOp`] to update `sender` balance by `fee`.
@@ -833,6 +897,14 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TxDestructedAccount, idx) => {
+                let operation = &self.block.container.tx_destructed_account[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxDestructedAccount(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -865,12 +937,21 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
             }
+            OpEnum::TxDestructedAccount(op) => {
+                if !op.is_destructed_prev && op.is_destructed {
+                    self.sdb.destruct_account(op.address);
+                }
+                if op.is_destructed_prev && !op.is_destructed {
+                    self.sdb.undestruct_account(&op.address);
+                }
+            }
             _ => unreachable!(),
         };
     }
 
     /// Handle a reversion group
-    fn handle_reversion(&mut self) {
+    /// Apply the reversions of the current call, which halted in failure.
+    pub fn handle_reversion(&mut self) {
         let reversion_group = self
             .tx_ctx
             .reversion_groups
@@ -918,7 +999,7 @@ impl<'a> CircuitInputStateRef<'a> {
         let (return_data_offset, return_data_length) = {
             if !self.call()?.is_root {
                 let (offset, length) = match step.op {
-                    OpcodeId::RETURN | OpcodeId::REVERT => {
+                    OpcodeId::RETURN | OpcodeId::REVERT if exec_step.error.is_none() => {
                         let offset = step.stack.nth_last(0)?.as_usize();
                         let length = step.stack.nth_last(1)?.as_usize();
                         // At the moment it conflicts with `call_ctx` and `caller_ctx`.
@@ -931,6 +1012,14 @@ impl<'a> CircuitInputStateRef<'a> {
                         }
                         (offset, length)
                     }
+                    // A precompile writes its output to the start of its memory, which is
+                    // returned as a whole.
+                    _ if exec_step.exec_state.is_precompiled() => {
+                        let callee_memory = self.call_ctx()?.memory.clone();
+                        let caller_ctx = self.caller_ctx_mut()?;
+                        caller_ctx.return_data = callee_memory.0.clone();
+                        (0, callee_memory.len())
+                    }
                     _ => {
                         let caller_ctx = self.caller_ctx_mut()?;
                         caller_ctx.return_data.truncate(0);
@@ -1005,9 +1094,15 @@ impl<'a> CircuitInputStateRef<'a> {
         let geth_step = steps
             .get(0)
             .ok_or(Error::InternalError("invalid index 0"))?;
-        let is_return_revert = geth_step.op == OpcodeId::REVERT || geth_step.op == OpcodeId::RETURN;
-
-        if !is_return_revert && !call.is_success {
+        // RETURN and REVERT halting in an exception (e.g. a failed contract
+        // deployment) are handled as the other error cases.
+        let is_return_revert = (geth_step.op == OpcodeId::REVERT
+            || geth_step.op == OpcodeId::RETURN)
+            && exec_step.error.is_none();
+        let is_precompile = exec_step.exec_state.is_precompiled();
+
+        // A failing precompile reads the call failure fields in its own step.
+        if !is_return_revert && !call.is_success && !is_precompile {
             // add call failure ops for exception cases
             self.call_context_read(
                 exec_step,
@@ -1044,8 +1139,9 @@ impl<'a> CircuitInputStateRef<'a> {
         );
 
         let [last_callee_return_data_offset, last_callee_return_data_length] = match geth_step.op {
+            _ if is_precompile => [Word::zero(), self.call_ctx()?.memory.len().into()],
             OpcodeId::STOP => [Word::zero(); 2],
-            OpcodeId::REVERT | OpcodeId::RETURN => {
+            OpcodeId::REVERT | OpcodeId::RETURN if is_return_revert => {
                 let offset = geth_step.stack.nth_last(0)?;
                 let length = geth_step.stack.nth_last(1)?;
                 // This is the convention we are using for memory addresses so that there is no
@@ -1077,7 +1173,13 @@ impl<'a> CircuitInputStateRef<'a> {
         } else {
             0
         };
-        let gas_refund = geth_step.gas.0 - memory_expansion_gas_cost - code_deposit_cost;
+        // For a precompile, `geth_step` is the step of the CALL opcode into it, so the
+        // callee gas left is taken from the precompile step instead.
+        let gas_refund = if is_precompile {
+            exec_step.gas_left.0 - exec_step.gas_cost.0
+        } else {
+            geth_step.gas.0 - memory_expansion_gas_cost - code_deposit_cost
+        };
 
         let caller_gas_left = if is_return_revert || call.is_success {
             geth_step_next.gas.0 - gas_refund
@@ -1138,10 +1240,15 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Push a copy event to the state.
     pub fn push_copy(&mut self, step: &mut ExecStep, event: CopyEvent) {
-        step.copy_rw_counter_delta = event.rw_counter_delta();
+        step.copy_rw_counter_delta += event.rw_counter_delta();
         self.block.add_copy_event(event);
     }
 
+    /// Push a precompiled contract call event to the state.
+    pub fn push_precompile_event(&mut self, event: PrecompileEvent) {
+        self.block.add_precompile_event(event)
+    }
+
     /// Push a exponentiation event to the state.
     pub fn push_exponentiation(&mut self, event: ExpEvent) {
         self.block.add_exp_event(event)
@@ -1234,14 +1341,31 @@ impl<'a> CircuitInputStateRef<'a> {
                 if call.is_create() {
                     let offset = step.stack.nth_last(0)?;
                     let length = step.stack.nth_last(1)?;
-                    if length > Word::from(0x6000u64) {
+                    // The memory expansion of RETURN is charged before the
+                    // code deposit cost.
+                    let curr_memory_word_size = call_ctx.memory.word_size() as u64;
+                    let next_memory_word_size = if length.is_zero() {
+                        curr_memory_word_size
+                    } else {
+                        max(
+                            curr_memory_word_size,
+                            (offset.low_u64() + length.low_u64() + 31) / 32,
+                        )
+                    };
+                    let memory_expansion_gas_cost =
+                        memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
+                    if length > Word::from(MAX_CODE_SIZE) {
                         return Ok(Some(ExecError::MaxCodeSizeExceeded));
                     } else if length > Word::zero()
                         && !call_ctx.memory.is_empty()
-                        && call_ctx.memory.0.get(offset.low_u64() as usize) == Some(&0xef)
+                        && call_ctx.memory.0.get(offset.low_u64() as usize)
+                            == Some(&INVALID_INIT_CODE_FIRST_BYTE)
                     {
                         return Ok(Some(ExecError::InvalidCreationCode));
-                    } else if Word::from(200u64) * length > Word::from(step.gas.0) {
+                    } else if GasCost::CODE_DEPOSIT_BYTE_COST.as_u64() * length.low_u64()
+                        + memory_expansion_gas_cost
+                        > step.gas.0
+                    {
                         return Ok(Some(ExecError::CodeStoreOutOfGas));
                     } else {
                         return Err(Error::UnexpectedExecStepError(
@@ -1302,12 +1426,17 @@ impl<'a> CircuitInputStateRef<'a> {
                 return Ok(Some(ExecError::InsufficientBalance));
             }
 
-            // Address collision
-            if matches!(step.op, OpcodeId::CREATE2) {
-                let address = self.create2_address(step)?;
+            // Address collision, when the account at the contract address
+            // has a non-zero nonce or a non-empty code.
+            if matches!(step.op, OpcodeId::CREATE | OpcodeId::CREATE2) {
+                let address = if step.op == OpcodeId::CREATE {
+                    self.create_address()?
+                } else {
+                    self.create2_address(step)?
+                };
 
-                let (found, _) = self.sdb.get_account(&address);
-                if found {
+                let (_, account) = self.sdb.get_account(&address);
+                if !account.nonce.is_zero() || account.code_hash != CodeDB::empty_code_hash() {
                     return Ok(Some(ExecError::ContractAddressCollision));
                 }
             }
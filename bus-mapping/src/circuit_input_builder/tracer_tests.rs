@@ -466,10 +466,14 @@ fn tracer_err_address_collision() {
             ..Account::zero()
         },
     );
-    builder
-        .builder
-        .sdb
-        .set_account(&create2_address, Account::zero());
+    // The contract created by the first CREATE2 has its nonce set to 1.
+    builder.builder.sdb.set_account(
+        &create2_address,
+        Account {
+            nonce: Word::one(),
+            ..Account::zero()
+        },
+    );
     assert_eq!(
         builder.state_ref().get_step_err(step, next_step).unwrap(),
         Some(ExecError::ContractAddressCollision)
@@ -2,7 +2,11 @@
 
 use std::collections::BTreeMap;
 
-use eth_types::{evm_types::Memory, geth_types, Address, GethExecTrace, Signature, Word};
+use eth_types::{
+    evm_types::Memory,
+    geth_types::{self, DYNAMIC_FEE_TX_TYPE},
+    AccessList, Address, GethExecTrace, Signature, Word,
+};
 use ethers_core::utils::get_contract_address;
 
 use crate::{
@@ -179,12 +183,19 @@ impl TransactionContext {
 #[derive(Debug, Clone)]
 /// Result of the parsing of an Ethereum Transaction.
 pub struct Transaction {
+    /// Transaction type, which is 0 for legacy transactions
+    pub transaction_type: u64,
     /// Nonce
     pub nonce: u64,
     /// Gas
     pub gas: u64,
-    /// Gas price
+    /// Gas price, which is the effective gas price for dynamic fee
+    /// transactions
     pub gas_price: Word,
+    /// Max fee per gas
+    pub max_fee_per_gas: Word,
+    /// Max priority fee per gas
+    pub max_priority_fee_per_gas: Word,
     /// From / Caller Address
     pub from: Address,
     /// To / Callee Address
@@ -193,6 +204,8 @@ pub struct Transaction {
     pub value: Word,
     /// Input / Call Data
     pub input: Vec<u8>,
+    /// Access list (EIP 2930)
+    pub access_list: Option<AccessList>,
     /// Signature
     pub signature: Signature,
     /// Calls made in the transaction
@@ -204,13 +217,17 @@ pub struct Transaction {
 impl From<&Transaction> for geth_types::Transaction {
     fn from(tx: &Transaction) -> geth_types::Transaction {
         geth_types::Transaction {
+            transaction_type: tx.transaction_type,
             from: tx.from,
             to: Some(tx.to),
             nonce: Word::from(tx.nonce),
             gas_limit: Word::from(tx.gas),
             value: tx.value,
             gas_price: tx.gas_price,
+            gas_fee_cap: tx.max_fee_per_gas,
+            gas_tip_cap: tx.max_priority_fee_per_gas,
             call_data: tx.input.clone().into(),
+            access_list: tx.access_list.clone(),
             v: tx.signature.v,
             r: tx.signature.r,
             s: tx.signature.s,
@@ -223,13 +240,17 @@ impl Transaction {
     /// Create a dummy Transaction with zero values
     pub fn dummy() -> Self {
         Self {
+            transaction_type: 0,
             nonce: 0,
             gas: 0,
             gas_price: Word::zero(),
+            max_fee_per_gas: Word::zero(),
+            max_priority_fee_per_gas: Word::zero(),
             from: Address::zero(),
             to: Address::zero(),
             value: Word::zero(),
             input: Vec::new(),
+            access_list: None,
             signature: Signature {
                 r: Word::zero(),
                 s: Word::zero(),
@@ -295,16 +316,32 @@ impl Transaction {
             }
         };
 
+        let gas_price = eth_tx.gas_price.unwrap_or_default();
+        // Transactions before EIP-1559 pay the gas price as both fee caps.
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            if eth_tx.transaction_type == Some(DYNAMIC_FEE_TX_TYPE.into()) {
+                (
+                    eth_tx.max_fee_per_gas.unwrap_or_default(),
+                    eth_tx.max_priority_fee_per_gas.unwrap_or_default(),
+                )
+            } else {
+                (gas_price, gas_price)
+            };
+
         Ok(Self {
+            transaction_type: eth_tx.transaction_type.unwrap_or_default().as_u64(),
             nonce: eth_tx.nonce.as_u64(),
             gas: eth_tx.gas.as_u64(),
-            gas_price: eth_tx.gas_price.unwrap_or_default(),
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
             from: eth_tx.from,
             to: eth_tx
                 .to
                 .unwrap_or_else(|| get_contract_address(eth_tx.from, eth_tx.nonce)),
             value: eth_tx.value,
             input: eth_tx.input.to_vec(),
+            access_list: eth_tx.access_list.clone(),
             calls: vec![call],
             steps: Vec::new(),
             signature: Signature {
@@ -27,6 +27,15 @@ pub enum Error {
     AddressNotFound(Address),
     /// Code not found in the CodeDB
     CodeNotFound(H256),
+    /// The EIP-1186 proof of an account or of its storage doesn't match the
+    /// state root.
+    InvalidProof(Address),
+    /// The state root after the updates of the block, which doesn't match the
+    /// state root in its header.
+    InvalidStateRoot(H256),
+    /// The updates of the block can't be proved against the proofs of its
+    /// prestate, e.g. for a missing proof or trie node.
+    InvalidMptUpdates(eth_types::Error),
     /// Unable to figure out error at a [`GethExecStep`]
     UnexpectedExecStepError(&'static str, Box<GethExecStep>),
     /// Invalid [`eth_types::GethExecTrace`] due to an invalid/unexpected value
@@ -40,6 +49,9 @@ pub enum Error {
     ExecutionError(ExecError),
     /// Internal Code error
     InternalError(&'static str),
+    /// Call to the precompiled contract at [`Address`] that can't be proved
+    /// by the circuits, e.g. a successful ecPairing call.
+    UnsupportedPrecompileCall(Address),
 }
 
 impl From<eth_types::Error> for Error {
@@ -73,9 +85,12 @@ pub enum OogError {
     /// Out of Gas for CREATE, RETURN, REVERT, which have dynamic memory
     /// expansion gas cost
     DynamicMemoryExpansion,
-    /// Out of Gas for CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY,
-    /// which copy a specified chunk of memory
+    /// Out of Gas for CALLDATACOPY, CODECOPY, RETURNDATACOPY, which copy a
+    /// specified chunk of memory
     MemoryCopy,
+    /// Out of Gas for EXTCODECOPY, which copies a specified chunk of memory and
+    /// possibly touches an extra account
+    ExtCodeCopy,
     /// Out of Gas for BALANCE, EXTCODESIZE, EXTCODEHASH, which possibly touch
     /// an extra account
     AccountAccess,
@@ -142,10 +157,10 @@ pub(crate) fn get_step_reported_error(op: &OpcodeId, error: &str) -> ExecError {
             OpcodeId::CREATE | OpcodeId::RETURN | OpcodeId::REVERT => {
                 OogError::DynamicMemoryExpansion
             }
-            OpcodeId::CALLDATACOPY
-            | OpcodeId::CODECOPY
-            | OpcodeId::EXTCODECOPY
-            | OpcodeId::RETURNDATACOPY => OogError::MemoryCopy,
+            OpcodeId::CALLDATACOPY | OpcodeId::CODECOPY | OpcodeId::RETURNDATACOPY => {
+                OogError::MemoryCopy
+            }
+            OpcodeId::EXTCODECOPY => OogError::ExtCodeCopy,
             OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
                 OogError::AccountAccess
             }
@@ -1,19 +1,22 @@
 //! Definition of each opcode of the EVM.
 use crate::{
-    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
+    },
     error::{ExecError, OogError},
     evm::OpcodeId,
     operation::{
-        AccountField, AccountOp, CallContextField, TxAccessListAccountOp, TxReceiptField,
-        TxRefundOp, RW,
+        AccountField, AccountOp, CallContextField, StorageClearOp, TxDestructedAccountOp,
+        TxReceiptField, TxRefundOp, RW,
     },
+    precompile::{execute_precompiled, PrecompileCalls},
     state_db::CodeDB,
     Error,
 };
 use core::fmt::Debug;
 use eth_types::{
     evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED},
-    evm_unimplemented, GethExecStep, ToAddress, ToWord, Word,
+    evm_unimplemented, GethExecStep, ToWord, Word,
 };
 use ethers_core::utils::get_contract_address;
 
@@ -43,10 +46,12 @@ mod mload;
 mod mstore;
 mod number;
 mod origin;
+mod precompiles;
 mod return_revert;
 mod returndatacopy;
 mod returndatasize;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod sload;
 mod sstore;
@@ -54,12 +59,20 @@ mod stackonlyop;
 mod stop;
 mod swap;
 
+mod error_code_store;
+mod error_invalid_creation_code;
 mod error_invalid_jump;
+mod error_oog_account_access;
 mod error_oog_call;
+mod error_oog_dynamic_memory;
 mod error_oog_exp;
+mod error_oog_extcodecopy;
 mod error_oog_log;
 mod error_oog_memory_copy;
+mod error_oog_self_destruct;
+mod error_oog_sha3;
 mod error_oog_sload_sstore;
+mod error_oog_static_memory;
 mod error_return_data_outofbound;
 mod error_simple;
 mod error_write_protection;
@@ -78,14 +91,22 @@ use callop::CallOpcode;
 use callvalue::Callvalue;
 use codecopy::Codecopy;
 use codesize::Codesize;
-use create::DummyCreate;
+use create::Create;
 use dup::Dup;
+use error_code_store::ErrorCodeStore;
+use error_invalid_creation_code::ErrorInvalidCreationCode;
 use error_invalid_jump::InvalidJump;
+use error_oog_account_access::OOGAccountAccess;
 use error_oog_call::OOGCall;
+use error_oog_dynamic_memory::OOGDynamicMemory;
 use error_oog_exp::OOGExp;
+use error_oog_extcodecopy::OOGExtCodeCopy;
 use error_oog_log::ErrorOOGLog;
 use error_oog_memory_copy::OOGMemoryCopy;
+use error_oog_self_destruct::OOGSelfDestruct;
+use error_oog_sha3::OOGSha3;
 use error_oog_sload_sstore::OOGSloadSstore;
+use error_oog_static_memory::OOGStaticMemory;
 use error_return_data_outofbound::ErrorReturnDataOutOfBound;
 use error_simple::ErrorSimple;
 use error_write_protection::ErrorWriteProtection;
@@ -102,6 +123,7 @@ use return_revert::ReturnRevert;
 use returndatacopy::Returndatacopy;
 use returndatasize::Returndatasize;
 use selfbalance::Selfbalance;
+use selfdestruct::Selfdestruct;
 use sload::Sload;
 use sstore::Sstore;
 use stackonlyop::StackOnlyOpcode;
@@ -250,18 +272,9 @@ fn fn_gen_associated_ops(opcode_id: &OpcodeId) -> FnGenAssociatedOps {
         OpcodeId::CALL | OpcodeId::CALLCODE => CallOpcode::<7>::gen_associated_ops,
         OpcodeId::DELEGATECALL | OpcodeId::STATICCALL => CallOpcode::<6>::gen_associated_ops,
         OpcodeId::RETURN | OpcodeId::REVERT => ReturnRevert::gen_associated_ops,
-        OpcodeId::SELFDESTRUCT => {
-            evm_unimplemented!("Using dummy gen_selfdestruct_ops for opcode SELFDESTRUCT");
-            DummySelfDestruct::gen_associated_ops
-        }
-        OpcodeId::CREATE => {
-            evm_unimplemented!("Using dummy gen_create_ops for opcode {:?}", opcode_id);
-            DummyCreate::<false>::gen_associated_ops
-        }
-        OpcodeId::CREATE2 => {
-            evm_unimplemented!("Using dummy gen_create_ops for opcode {:?}", opcode_id);
-            DummyCreate::<true>::gen_associated_ops
-        }
+        OpcodeId::SELFDESTRUCT => Selfdestruct::gen_associated_ops,
+        OpcodeId::CREATE => Create::<false>::gen_associated_ops,
+        OpcodeId::CREATE2 => Create::<true>::gen_associated_ops,
         _ => {
             evm_unimplemented!("Using dummy gen_associated_ops for opcode {:?}", opcode_id);
             Dummy::gen_associated_ops
@@ -276,24 +289,43 @@ fn fn_gen_error_state_associated_ops(
     match error {
         ExecError::InvalidJump => Some(InvalidJump::gen_associated_ops),
         ExecError::InvalidOpcode => Some(ErrorSimple::gen_associated_ops),
+        ExecError::OutOfGas(OogError::AccountAccess) => Some(OOGAccountAccess::gen_associated_ops),
         ExecError::OutOfGas(OogError::Call) => Some(OOGCall::gen_associated_ops),
         ExecError::OutOfGas(OogError::Constant) => Some(ErrorSimple::gen_associated_ops),
+        ExecError::OutOfGas(OogError::DynamicMemoryExpansion) => {
+            Some(OOGDynamicMemory::gen_associated_ops)
+        }
         ExecError::OutOfGas(OogError::Exp) => Some(OOGExp::gen_associated_ops),
+        ExecError::OutOfGas(OogError::ExtCodeCopy) => Some(OOGExtCodeCopy::gen_associated_ops),
         ExecError::OutOfGas(OogError::Log) => Some(ErrorOOGLog::gen_associated_ops),
         ExecError::OutOfGas(OogError::MemoryCopy) => Some(OOGMemoryCopy::gen_associated_ops),
+        ExecError::OutOfGas(OogError::SelfDestruct) => Some(OOGSelfDestruct::gen_associated_ops),
+        ExecError::OutOfGas(OogError::Sha3) => Some(OOGSha3::gen_associated_ops),
         ExecError::OutOfGas(OogError::SloadSstore) => Some(OOGSloadSstore::gen_associated_ops),
+        ExecError::OutOfGas(OogError::StaticMemoryExpansion) => {
+            Some(OOGStaticMemory::gen_associated_ops)
+        }
         ExecError::StackOverflow => Some(ErrorSimple::gen_associated_ops),
         ExecError::StackUnderflow => Some(ErrorSimple::gen_associated_ops),
-        // call & callcode can encounter InsufficientBalance error, Use pop-7 generic CallOpcode
-        ExecError::InsufficientBalance => Some(CallOpcode::<7>::gen_associated_ops),
+        // call, callcode, create & create2 can encounter InsufficientBalance error
+        ExecError::InsufficientBalance => match geth_step.op {
+            OpcodeId::CREATE => Some(Create::<false>::gen_associated_ops),
+            OpcodeId::CREATE2 => Some(Create::<true>::gen_associated_ops),
+            // Use pop-7 generic CallOpcode
+            _ => Some(CallOpcode::<7>::gen_associated_ops),
+        },
         ExecError::WriteProtection => Some(ErrorWriteProtection::gen_associated_ops),
         ExecError::ReturnDataOutOfBounds => Some(ErrorReturnDataOutOfBound::gen_associated_ops),
-        ExecError::Depth => {
-            let op = geth_step.op;
-            if !op.is_call() {
-                evm_unimplemented!("TODO: ErrDepth for CREATE is not implemented yet");
-            }
-            Some(fn_gen_associated_ops(&op))
+        // call, callcode, delegatecall, staticcall, create & create2 can encounter Depth error,
+        // which is handled as a failed precheck by their own opcode handlers.
+        ExecError::Depth => Some(fn_gen_associated_ops(&geth_step.op)),
+        // create & create2 can encounter ContractAddressCollision error, which
+        // is handled by their own opcode handlers without entering the
+        // initialization call.
+        ExecError::ContractAddressCollision => Some(fn_gen_associated_ops(&geth_step.op)),
+        ExecError::InvalidCreationCode => Some(ErrorInvalidCreationCode::gen_associated_ops),
+        ExecError::CodeStoreOutOfGas | ExecError::MaxCodeSizeExceeded => {
+            Some(ErrorCodeStore::gen_associated_ops)
         }
         // more future errors place here
         _ => {
@@ -364,7 +396,85 @@ pub fn gen_associated_ops(
     fn_gen_associated_ops(state, geth_steps)
 }
 
-pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error> {
+/// Write the addresses and then the storage keys in the access list of the
+/// tx as warm, each group being bound to the tx table by a copy event.
+fn gen_tx_access_list_writes(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+) -> Result<(), Error> {
+    let tx_id = state.tx_ctx.id();
+    let access_list = state.tx.access_list.clone().unwrap_or_default();
+
+    let rw_counter_start = state.block_ctx.rwc;
+    let mut addresses = Vec::new();
+    for item in access_list.0.iter() {
+        let is_warm_prev = !state.sdb.add_account_to_access_list(item.address);
+        state.tx_accesslist_account_write(exec_step, tx_id, item.address, true, is_warm_prev)?;
+        addresses.push((item.address, Word::zero(), is_warm_prev));
+    }
+    let addresses_len = addresses.len() as u64;
+    if !addresses.is_empty() {
+        state.push_copy(
+            exec_step,
+            CopyEvent {
+                src_type: CopyDataType::AccessListAddresses,
+                src_id: NumberOrHash::Number(tx_id),
+                src_addr: 0,
+                src_addr_end: addresses_len,
+                dst_type: CopyDataType::AccessListAddresses,
+                dst_id: NumberOrHash::Number(tx_id),
+                dst_addr: 0,
+                log_id: None,
+                rw_counter_start,
+                bytes: vec![],
+                access_list: addresses,
+            },
+        );
+    }
+
+    let rw_counter_start = state.block_ctx.rwc;
+    let mut storage_keys = Vec::new();
+    for item in access_list.0.iter() {
+        for key in item.storage_keys.iter() {
+            let key = key.to_word();
+            let is_warm_prev = !state
+                .sdb
+                .add_account_storage_to_access_list((item.address, key));
+            state.tx_accesslist_account_storage_write(
+                exec_step,
+                tx_id,
+                item.address,
+                key,
+                true,
+                is_warm_prev,
+            )?;
+            storage_keys.push((item.address, key, is_warm_prev));
+        }
+    }
+    let storage_keys_len = storage_keys.len() as u64;
+    if !storage_keys.is_empty() {
+        state.push_copy(
+            exec_step,
+            CopyEvent {
+                src_type: CopyDataType::AccessListStorageKeys,
+                src_id: NumberOrHash::Number(tx_id),
+                src_addr: 0,
+                src_addr_end: storage_keys_len,
+                dst_type: CopyDataType::AccessListStorageKeys,
+                dst_id: NumberOrHash::Number(tx_id),
+                dst_addr: 0,
+                log_id: None,
+                rw_counter_start,
+                bytes: vec![],
+                access_list: storage_keys,
+            },
+        );
+    }
+
+    Ok(())
+}
+
+pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<Vec<ExecStep>, Error> {
     let mut exec_step = state.new_begin_tx_step();
     let call = state.call()?.clone();
 
@@ -407,6 +517,12 @@ pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
     }
 
     // Calculate intrinsic gas cost
+    let access_list_gas_cost = state.tx.access_list.as_ref().map_or(0, |access_list| {
+        access_list.0.iter().fold(0, |acc, item| {
+            acc + GasCost::ACCESS_LIST_ADDRESS.as_u64()
+                + item.storage_keys.len() as u64 * GasCost::ACCESS_LIST_STORAGE.as_u64()
+        })
+    });
     let call_data_gas_cost = state
         .tx
         .input
@@ -416,7 +532,8 @@ pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
         GasCost::CREATION_TX.as_u64()
     } else {
         GasCost::TX.as_u64()
-    } + call_data_gas_cost;
+    } + call_data_gas_cost
+        + access_list_gas_cost;
     exec_step.gas_cost = GasCost(intrinsic_gas_cost);
 
     // Get code_hash of callee
@@ -430,7 +547,7 @@ pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
     } else {
         (Word::zero(), true)
     };
-    if !state.is_precompiled(&call.address) && !call.is_create() {
+    if !call.is_create() {
         state.account_read(
             &mut exec_step,
             call.address,
@@ -468,12 +585,9 @@ pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
         });
     }
 
-    // There are 4 branches from here.
-    match (
-        call.is_create(),
-        state.is_precompiled(&call.address),
-        is_empty_code_hash,
-    ) {
+    // There are 3 branches from here.
+    let is_precompile = state.is_precompiled(&call.address);
+    match (call.is_create(), is_precompile, is_empty_code_hash) {
         // 1. Creation transaction.
         (true, _, _) => {
             state.push_op_reversible(
@@ -514,20 +628,9 @@ pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
             ] {
                 state.call_context_write(&mut exec_step, call.call_id, field, value);
             }
-            Ok(exec_step)
         }
-        // 2. Call to precompiled.
-        (_, true, _) => {
-            evm_unimplemented!("Call to precompiled is left unimplemented");
-            Ok(exec_step)
-        }
-        (_, _, is_empty_code_hash) => {
-            // 3. Call to account with empty code.
-            if is_empty_code_hash {
-                return Ok(exec_step);
-            }
-
-            // 4. Call to account with non-empty code.
+        // 2. Call to precompiled or to account with non-empty code.
+        (_, true, _) | (_, _, false) => {
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -554,13 +657,39 @@ pub fn gen_begin_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Er
             ] {
                 state.call_context_write(&mut exec_step, call.call_id, field, value);
             }
-
-            Ok(exec_step)
         }
+        // 3. Call to account with empty code.
+        _ => (),
+    }
+
+    // Add addresses and storage keys of the tx access list (EIP 2930) into
+    // access list, at the end of the step as they're written by the copy
+    // circuit.
+    gen_tx_access_list_writes(state, &mut exec_step)?;
+
+    if !call.is_create() && is_precompile {
+        let input = state.tx.input.clone();
+        let gas_left = state.tx.gas - exec_step.gas_cost.0;
+        let (output, gas_cost) = execute_precompiled(&call.address, &input, gas_left);
+        let precompile_step = precompiles::gen_associated_ops(
+            state,
+            &[],
+            PrecompileCalls::from(call.address),
+            &input,
+            &output,
+            gas_left,
+            gas_cost,
+        )?;
+        return Ok(vec![exec_step, precompile_step]);
     }
+
+    Ok(vec![exec_step])
 }
 
-pub fn gen_end_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error> {
+/// Generate the EndTx step, followed by the steps destructing the accounts
+/// self destructed in the tx.  The last of these steps writes the tx id of the
+/// next tx.
+pub fn gen_end_tx_ops(state: &mut CircuitInputStateRef) -> Result<Vec<ExecStep>, Error> {
     let mut exec_step = state.new_end_tx_step();
     let call = state.tx.calls()[0].clone();
 
@@ -588,8 +717,11 @@ pub fn gen_end_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         },
     );
 
-    let effective_refund =
-        refund.min((state.tx.gas - exec_step.gas_left.0) / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+    // The refund is capped to a fifth of the gas used (EIP-3529), and the gas used
+    // of the tx is reduced by the effective refund.
+    let gas_used = state.tx.gas - exec_step.gas_left.0;
+    let effective_refund = refund.min(gas_used / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+    let gas_used = gas_used - effective_refund;
     let (found, caller_account) = state.sdb.get_account(&call.caller_address);
     if !found {
         return Err(Error::AccountNotFound(call.caller_address));
@@ -611,8 +743,7 @@ pub fn gen_end_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         return Err(Error::AccountNotFound(state.block.coinbase));
     }
     let coinbase_balance_prev = coinbase_account.balance;
-    let coinbase_balance =
-        coinbase_balance_prev + effective_tip * (state.tx.gas - exec_step.gas_left.0);
+    let coinbase_balance = coinbase_balance_prev + effective_tip * gas_used;
     state.account_write(
         &mut exec_step,
         state.block.coinbase,
@@ -647,7 +778,7 @@ pub fn gen_end_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         )?;
     }
 
-    state.block_ctx.cumulative_gas_used += state.tx.gas - exec_step.gas_left.0;
+    state.block_ctx.cumulative_gas_used += gas_used;
     state.tx_receipt_write(
         &mut exec_step,
         state.tx_ctx.id(),
@@ -655,66 +786,68 @@ pub fn gen_end_tx_ops(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         state.block_ctx.cumulative_gas_used,
     )?;
 
+    let mut exec_steps = vec![exec_step];
+    gen_destruct_ops(state, &mut exec_steps)?;
+
     if !state.tx_ctx.is_last_tx() {
+        let exec_step = exec_steps.last_mut().expect("EndTx step should exist");
         state.call_context_write(
-            &mut exec_step,
+            exec_step,
             state.block_ctx.rwc.0 + 1,
             CallContextField::TxId,
             (state.tx_ctx.id() + 1).into(),
         );
     }
 
-    Ok(exec_step)
+    Ok(exec_steps)
 }
 
-#[derive(Debug, Copy, Clone)]
-struct DummySelfDestruct;
-
-impl Opcode for DummySelfDestruct {
-    fn gen_associated_ops(
-        state: &mut CircuitInputStateRef,
-        geth_steps: &[GethExecStep],
-    ) -> Result<Vec<ExecStep>, Error> {
-        dummy_gen_selfdestruct_ops(state, geth_steps)
-    }
-}
-fn dummy_gen_selfdestruct_ops(
+/// Generate the steps destructing the accounts self destructed in the tx,
+/// which geth does when finalising the state after the tx fees are paid.
+/// The whole storage of each account is cleared, which sets its storage root
+/// to the empty root, and then its balance, nonce and code hash are set to
+/// zero.
+fn gen_destruct_ops(
     state: &mut CircuitInputStateRef,
-    geth_steps: &[GethExecStep],
-) -> Result<Vec<ExecStep>, Error> {
-    let geth_step = &geth_steps[0];
-    let mut exec_step = state.new_step(geth_step)?;
-    let sender = state.call()?.address;
-    let receiver = geth_step.stack.last()?.to_address();
-
-    let is_warm = state.sdb.check_account_in_access_list(&receiver);
-    state.push_op_reversible(
-        &mut exec_step,
-        TxAccessListAccountOp {
-            tx_id: state.tx_ctx.id(),
-            address: receiver,
-            is_warm: true,
-            is_warm_prev: is_warm,
-        },
-    )?;
-
-    let (found, _) = state.sdb.get_account(&receiver);
-    if !found {
-        return Err(Error::AccountNotFound(receiver));
-    }
-    let (found, sender_account) = state.sdb.get_account(&sender);
-    if !found {
-        return Err(Error::AccountNotFound(sender));
-    }
-    let value = sender_account.balance;
-    // NOTE: In this dummy implementation we assume that the receiver already
-    // exists.
-    state.transfer(&mut exec_step, sender, receiver, true, false, value)?;
+    exec_steps: &mut Vec<ExecStep>,
+) -> Result<(), Error> {
+    let call = state.tx.calls()[0].clone();
+    let tx_id = state.tx_ctx.id();
 
-    if state.call()?.is_persistent {
-        state.sdb.destruct_account(sender);
+    for address in state.sdb.destructed_accounts() {
+        let mut exec_step = state.new_destruct_step(ExecState::DestructAccount, &exec_steps[0]);
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::TxId,
+            tx_id.into(),
+        );
+        state.push_op(
+            &mut exec_step,
+            RW::WRITE,
+            TxDestructedAccountOp {
+                tx_id,
+                address,
+                is_destructed: false,
+                is_destructed_prev: true,
+            },
+        );
+        // The storage of the account is wiped from the StateDB when the tx is
+        // committed.
+        state.push_op(&mut exec_step, RW::WRITE, StorageClearOp { tx_id, address });
+        // The account executed SELFDESTRUCT, so it has code or a non-zero nonce
+        // and is not empty.  Code hash is written last so that the account is
+        // never empty while its balance and nonce are being reset.
+        let account = state.sdb.get_account(&address).1.clone();
+        for (field, value_prev) in [
+            (AccountField::Balance, account.balance),
+            (AccountField::Nonce, account.nonce),
+            (AccountField::CodeHash, account.code_hash.to_word()),
+        ] {
+            state.account_write(&mut exec_step, address, field, Word::zero(), value_prev)?;
+        }
+        exec_steps.push(exec_step);
     }
 
-    state.handle_return(&mut exec_step, geth_steps, false)?;
-    Ok(vec![exec_step])
+    Ok(())
 }
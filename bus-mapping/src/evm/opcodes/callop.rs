@@ -1,19 +1,18 @@
-use super::Opcode;
+use super::{precompiles, Opcode};
 use crate::{
     circuit_input_builder::{CallKind, CircuitInputStateRef, CodeSource, ExecStep},
     operation::{AccountField, CallContextField, TxAccessListAccountOp},
-    precompile::{execute_precompiled, is_precompiled},
+    precompile::{execute_precompiled, is_precompiled, PrecompileCalls},
     state_db::CodeDB,
     Error,
 };
 use eth_types::{
     evm_types::{
         gas_utils::{eip150_gas, memory_expansion_gas_cost},
-        GasCost,
+        GasCost, GAS_STIPEND_CALL_WITH_VALUE,
     },
     GethExecStep, ToWord, Word,
 };
-use std::cmp::min;
 
 /// Placeholder structure used to implement [`Opcode`] trait over it
 /// corresponding to the `OpcodeId::CALL`, `OpcodeId::CALLCODE`,
@@ -157,9 +156,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         );
 
         // read balance of caller to compare to value for insufficient_balance checking
-        // in circuit, also use for callcode successful case check balance is
-        // indeed larger than transfer value. for call opcode, it does in
-        // tranfer gadget implicitly.
+        // in circuit.
         state.account_read(
             &mut exec_step,
             call.caller_address,
@@ -171,14 +168,15 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         let is_precompile = code_address
             .map(|ref addr| is_precompiled(addr))
             .unwrap_or(false);
-        // TODO: What about transfer for CALLCODE?
-        // Transfer value only for CALL opcode, is_precheck_ok = true.
-        if call.kind == CallKind::Call && is_precheck_ok {
+        // Transfer value only for CALL and CALLCODE opcodes, is_precheck_ok = true.
+        // For CALLCODE `call.address` is the caller address, so the value is
+        // transferred from the caller to itself.
+        if is_call_or_callcode && is_precheck_ok {
             state.transfer(
                 &mut exec_step,
                 call.caller_address,
                 call.address,
-                callee_exists || is_precompile,
+                callee_exists || call.kind == CallKind::CallCode,
                 false,
                 call.value,
             )?;
@@ -217,75 +215,11 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         let gas_specified = geth_step.stack.last()?;
         let callee_gas_left = eip150_gas(geth_step.gas.0 - gas_cost, gas_specified);
 
-        // There are 4 branches from here.
+        // There are 3 branches from here.
         // add failure case for insufficient balance or error depth in the future.
         match (!is_precheck_ok, is_precompile, is_empty_code_hash) {
-            // 1. Call to precompiled.
-            (false, true, _) => {
-                assert!(call.is_success, "call to precompile should not fail");
-                let caller_ctx = state.caller_ctx_mut()?;
-                let code_address = code_address.unwrap();
-                let (result, contract_gas_cost) = execute_precompiled(
-                    &code_address,
-                    if args_length != 0 {
-                        &caller_ctx.memory.0[args_offset..args_offset + args_length]
-                    } else {
-                        &[]
-                    },
-                    callee_gas_left,
-                );
-                log::trace!(
-                    "precompile return data len {} gas {}",
-                    result.len(),
-                    contract_gas_cost
-                );
-                caller_ctx.return_data = result.clone();
-                let length = min(result.len(), ret_length);
-                if length != 0 {
-                    caller_ctx.memory.extend_at_least(ret_offset + length);
-                }
-                caller_ctx.memory.0[ret_offset..ret_offset + length]
-                    .copy_from_slice(&result[..length]);
-                for (field, value) in [
-                    (CallContextField::LastCalleeId, call.call_id.into()),
-                    (CallContextField::LastCalleeReturnDataOffset, 0.into()),
-                    (
-                        CallContextField::LastCalleeReturnDataLength,
-                        result.len().into(),
-                    ),
-                ] {
-                    state.call_context_write(&mut exec_step, current_call.call_id, field, value);
-                }
-
-                log::warn!("missing circuit part of precompile");
-                state.handle_return(&mut exec_step, geth_steps, false)?;
-
-                let real_cost = geth_steps[0].gas.0 - geth_steps[1].gas.0;
-                if real_cost != exec_step.gas_cost.0 {
-                    log::warn!(
-                        "precompile gas fixed from {} to {}, step {:?}",
-                        exec_step.gas_cost.0,
-                        real_cost,
-                        geth_steps[0]
-                    );
-                }
-                exec_step.gas_cost = GasCost(real_cost);
-                Ok(vec![exec_step])
-            }
-            // 2. Call to account with empty code.
-            (false, _, true) => {
-                for (field, value) in [
-                    (CallContextField::LastCalleeId, 0.into()),
-                    (CallContextField::LastCalleeReturnDataOffset, 0.into()),
-                    (CallContextField::LastCalleeReturnDataLength, 0.into()),
-                ] {
-                    state.call_context_write(&mut exec_step, current_call.call_id, field, value);
-                }
-                state.handle_return(&mut exec_step, geth_steps, false)?;
-                Ok(vec![exec_step])
-            }
-            // 3. Call to account with non-empty code.
-            (false, _, false) => {
+            // 1. Call to precompiled or to account with non-empty code.
+            (false, true, _) | (false, _, false) => {
                 for (field, value) in [
                     (
                         CallContextField::ProgramCounter,
@@ -341,15 +275,54 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                     (CallContextField::LastCalleeReturnDataLength, 0.into()),
                     (CallContextField::IsRoot, 0.into()),
                     (CallContextField::IsCreate, 0.into()),
-                    (CallContextField::CodeHash, call.code_hash.to_word()),
+                    (CallContextField::CodeHash, callee_code_hash_word),
                 ] {
                     state.call_context_write(&mut exec_step, call.call_id, field, value);
                 }
 
+                if !is_precompile {
+                    return Ok(vec![exec_step]);
+                }
+
+                let code_address = code_address.unwrap();
+                let input = state.call_ctx()?.call_data.clone();
+                let callee_gas_left = callee_gas_left
+                    + if has_value {
+                        GAS_STIPEND_CALL_WITH_VALUE
+                    } else {
+                        0
+                    };
+                let (output, contract_gas_cost) =
+                    execute_precompiled(&code_address, &input, callee_gas_left);
+                log::trace!(
+                    "precompile return data len {} gas {}",
+                    output.len(),
+                    contract_gas_cost
+                );
+                let precompile_step = precompiles::gen_associated_ops(
+                    state,
+                    geth_steps,
+                    PrecompileCalls::from(code_address),
+                    &input,
+                    &output,
+                    callee_gas_left,
+                    contract_gas_cost,
+                )?;
+                Ok(vec![exec_step, precompile_step])
+            }
+            // 2. Call to account with empty code.
+            (false, _, true) => {
+                for (field, value) in [
+                    (CallContextField::LastCalleeId, 0.into()),
+                    (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+                    (CallContextField::LastCalleeReturnDataLength, 0.into()),
+                ] {
+                    state.call_context_write(&mut exec_step, current_call.call_id, field, value);
+                }
+                state.handle_return(&mut exec_step, geth_steps, false)?;
                 Ok(vec![exec_step])
             }
-
-            // 4. insufficient balance or error depth cases.
+            // 3. insufficient balance or error depth cases.
             (true, _, _) => {
                 for (field, value) in [
                     (CallContextField::LastCalleeId, 0.into()),
@@ -1,38 +1,71 @@
 use crate::{
-    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
     evm::Opcode,
-    operation::{AccountField, AccountOp, CallContextField, TxAccessListAccountOp},
+    operation::{AccountField, AccountOp, CallContextField, MemoryOp, TxAccessListAccountOp, RW},
     state_db::CodeDB,
     Error,
 };
-use eth_types::{evm_types::gas_utils::memory_expansion_gas_cost, GethExecStep, ToWord, Word};
+use eth_types::{
+    evm_types::{gas_utils::memory_expansion_gas_cost, GasCost},
+    Bytecode, GethExecStep, ToBigEndian, ToWord, Word, H160, H256,
+};
+use ethers_core::utils::{keccak256, rlp};
 
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the `OpcodeId::CREATE` and `OpcodeId::CREATE2`.
+/// - CREATE: IS_CREATE2 = false
+/// - CREATE2: IS_CREATE2 = true
 #[derive(Debug, Copy, Clone)]
-pub struct DummyCreate<const IS_CREATE2: bool>;
+pub(crate) struct Create<const IS_CREATE2: bool>;
 
-impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
+impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
     fn gen_associated_ops(
         state: &mut CircuitInputStateRef,
         geth_steps: &[GethExecStep],
     ) -> Result<Vec<ExecStep>, Error> {
-        // TODO: replace dummy create here
         let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let n_pop = if IS_CREATE2 { 4 } else { 3 };
 
-        let offset = geth_step.stack.nth_last(1)?.as_usize();
+        // Offset is only meaningful when length is non-zero, so we take the
+        // low u64 to avoid overflowing on an unused offset.
+        let offset = geth_step.stack.nth_last(1)?.low_u64() as usize;
         let length = geth_step.stack.nth_last(2)?.as_usize();
 
-        let curr_memory_word_size = (state.call_ctx()?.memory.len() as u64) / 32;
+        let curr_memory_word_size = state.call_ctx()?.memory.word_size() as u64;
         if length != 0 {
             state
                 .call_ctx_mut()?
                 .memory
                 .extend_at_least(offset + length);
         }
-        let next_memory_word_size = (state.call_ctx()?.memory.len() as u64) / 32;
+        let next_memory_word_size = state.call_ctx()?.memory.word_size() as u64;
 
-        let mut exec_step = state.new_step(geth_step)?;
+        let tx_id = state.tx_ctx.id();
+        // The memory must be expanded before parsing the call, since the
+        // initialization code is read from the caller's memory.
+        let callee = state.parse_call(geth_step)?;
+        let caller = state.call()?.clone();
+
+        for (field, value) in [
+            (CallContextField::TxId, tx_id.into()),
+            (CallContextField::Depth, caller.depth.into()),
+            (CallContextField::CalleeAddress, caller.address.to_word()),
+            // NOTE: For `RwCounterEndOfReversion` we use the `0` value as a
+            // placeholder, and later set the proper value in
+            // `CircuitInputBuilder::set_value_ops_call_context_rwc_eor`
+            (CallContextField::RwCounterEndOfReversion, 0.into()),
+            (
+                CallContextField::IsPersistent,
+                (caller.is_persistent as u64).into(),
+            ),
+        ] {
+            state.call_context_read(&mut exec_step, caller.call_id, field, value);
+        }
 
-        let n_pop = if IS_CREATE2 { 4 } else { 3 };
         for i in 0..n_pop {
             state.stack_read(
                 &mut exec_step,
@@ -42,145 +75,493 @@ impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
         }
 
         let address = if IS_CREATE2 {
-            state.create2_address(&geth_steps[0])?
+            state.create2_address(geth_step)?
         } else {
             state.create_address()?
         };
+        debug_assert_eq!(address, callee.address);
 
-        // TODO: rename this to initialization call?
-        let call = state.parse_call(geth_step)?;
         state.stack_write(
             &mut exec_step,
             geth_step.stack.nth_last_filled(n_pop - 1),
-            if call.is_success {
+            if callee.is_success {
                 address.to_word()
             } else {
                 Word::zero()
             },
         )?;
 
-        let tx_id = state.tx_ctx.id();
-        let current_call = state.call()?.clone();
-
-        // Quote from [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929)
-        // > When a CREATE or CREATE2 opcode is called,
-        // > immediately (i.e. before checks are done to determine
-        // > whether or not the address is unclaimed)
-        // > add the address being created to accessed_addresses,
-        // > but gas costs of CREATE and CREATE2 are unchanged
-        let is_warm = state.sdb.check_account_in_access_list(&address);
-        state.push_op_reversible(
+        let caller_balance = state.sdb.get_balance(&caller.address);
+        let caller_nonce = state.sdb.get_nonce(&caller.address);
+        state.account_read(
             &mut exec_step,
-            TxAccessListAccountOp {
-                tx_id: state.tx_ctx.id(),
-                address,
-                is_warm: true,
-                is_warm_prev: is_warm,
-            },
-        )?;
-
-        // Increase caller's nonce
-        let nonce_prev = state.sdb.get_nonce(&call.caller_address);
-        state.push_op_reversible(
+            caller.address,
+            AccountField::Balance,
+            caller_balance,
+        );
+        state.account_read(
             &mut exec_step,
-            AccountOp {
-                address: call.caller_address,
-                field: AccountField::Nonce,
-                value: (nonce_prev + 1).into(),
-                value_prev: nonce_prev.into(),
-            },
-        )?;
+            caller.address,
+            AccountField::Nonce,
+            caller_nonce.into(),
+        );
 
-        // Add callee into access list
-        let is_warm = state.sdb.check_account_in_access_list(&call.address);
-        state.push_op_reversible(
-            &mut exec_step,
-            TxAccessListAccountOp {
-                tx_id,
-                address: call.address,
-                is_warm: true,
-                is_warm_prev: is_warm,
-            },
-        )?;
+        // Precheck is OK when depth is in range, caller balance is sufficient
+        // and caller nonce doesn't overflow. Otherwise the initialization call
+        // is never entered and the caller's nonce is left untouched.
+        let is_precheck_ok =
+            caller.depth < 1025 && caller_balance >= callee.value && caller_nonce < u64::MAX;
 
-        state.push_call(call.clone());
+        // There's an address collision when the account at the contract
+        // address has a non-zero nonce or a non-empty code.
+        let callee_account = state.sdb.get_account(&address).1.clone();
+        let is_address_collision = callee_account.code_hash != CodeDB::empty_code_hash()
+            || !callee_account.nonce.is_zero();
 
-        state.transfer(
-            &mut exec_step,
-            call.caller_address,
-            call.address,
-            true,
-            true,
-            call.value,
-        )?;
+        if is_precheck_ok {
+            // Increase caller's nonce
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: caller.address,
+                    field: AccountField::Nonce,
+                    value: (caller_nonce + 1).into(),
+                    value_prev: caller_nonce.into(),
+                },
+            )?;
 
-        // Increase callee's nonce
-        let nonce_prev = state.sdb.get_nonce(&call.address);
-        debug_assert!(nonce_prev == 0);
-        state.push_op_reversible(
-            &mut exec_step,
-            AccountOp {
-                address: call.address,
-                field: AccountField::Nonce,
-                value: 1.into(),
-                value_prev: 0.into(),
-            },
-        )?;
+            // Quote from [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929)
+            // > When a CREATE or CREATE2 opcode is called,
+            // > immediately (i.e. before checks are done to determine
+            // > whether or not the address is unclaimed)
+            // > add the address being created to accessed_addresses,
+            // > but gas costs of CREATE and CREATE2 are unchanged
+            let is_warm = state.sdb.check_account_in_access_list(&address);
+            state.push_op_reversible(
+                &mut exec_step,
+                TxAccessListAccountOp {
+                    tx_id,
+                    address,
+                    is_warm: true,
+                    is_warm_prev: is_warm,
+                },
+            )?;
+
+            // Read the nonce and code hash of the contract address to check
+            // for an address collision.
+            let callee_code_hash = if callee_account.is_empty() {
+                Word::zero()
+            } else {
+                callee_account.code_hash.to_word()
+            };
+            state.account_read(
+                &mut exec_step,
+                address,
+                AccountField::Nonce,
+                callee_account.nonce,
+            );
+            state.account_read(
+                &mut exec_step,
+                address,
+                AccountField::CodeHash,
+                callee_code_hash,
+            );
+        }
+
+        // Calculate the initialization code hash, and the input to the keccak
+        // hash function that derives the contract address.
+        let init_code = state
+            .call_ctx()?
+            .memory
+            .read_chunk((offset as u64).into(), (length as u64).into());
+        let init_code_hash = CodeDB::hash(&init_code);
+        debug_assert_eq!(init_code_hash, callee.code_hash);
+        let keccak_input = if IS_CREATE2 {
+            let salt = geth_step.stack.nth_last(3)?;
+            std::iter::once(0xffu8)
+                .chain(caller.address.to_fixed_bytes())
+                .chain(salt.to_be_bytes())
+                .chain(init_code_hash.to_fixed_bytes())
+                .collect::<Vec<_>>()
+        } else {
+            let mut stream = rlp::RlpStream::new();
+            stream.begin_list(2);
+            stream.append(&caller.address);
+            stream.append(&Word::from(caller_nonce));
+            stream.out().to_vec()
+        };
+        debug_assert_eq!(
+            address,
+            H160(keccak256(&keccak_input)[12..].try_into().unwrap())
+        );
+        // We feed the address derivation input to the block's SHA3 inputs, so
+        // that the CreateGadget can do a lookup to the Keccak table and verify
+        // the contract address. The initialization code hash is covered by the
+        // Bytecode circuit, since the code is inserted in the code DB.
+        state.block.sha3_inputs.push(keccak_input);
 
         let memory_expansion_gas_cost =
             memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
+        let init_code_word_size = (length as u64 + 31) / 32;
+        let gas_cost = GasCost::CREATE.as_u64()
+            + memory_expansion_gas_cost
+            + if IS_CREATE2 {
+                GasCost::COPY_SHA3.as_u64() * init_code_word_size
+            } else {
+                0
+            };
+        // Per EIP-150, all but one 64th of the caller's gas is sent to the
+        // initialization call.
+        let caller_gas_left = (geth_step.gas.0 - gas_cost) / 64;
 
-        // EIP-150: all but one 64th of the caller's gas is sent to the callee.
-        let caller_gas_left =
-            (geth_step.gas.0 - geth_step.gas_cost.0 - memory_expansion_gas_cost) / 64;
+        // Switch to callee's call context
+        state.push_call(callee.clone());
 
         for (field, value) in [
+            (CallContextField::RwCounterEndOfReversion, 0.into()),
             (
-                CallContextField::ProgramCounter,
-                (geth_step.pc.0 + 1).into(),
-            ),
-            (
-                CallContextField::StackPointer,
-                geth_step.stack.nth_last_filled(n_pop - 1).0.into(),
-            ),
-            (CallContextField::GasLeft, caller_gas_left.into()),
-            (CallContextField::MemorySize, next_memory_word_size.into()),
-            (
-                CallContextField::ReversibleWriteCounter,
-                // +3 is because we do some transfers after pushing the call. can be just push the
-                // call later?
-                (exec_step.reversible_write_counter + 3).into(),
+                CallContextField::IsPersistent,
+                (callee.is_persistent as u64).into(),
             ),
         ] {
-            state.call_context_write(&mut exec_step, current_call.call_id, field, value);
+            state.call_context_write(&mut exec_step, callee.call_id, field, value);
         }
 
-        for (field, value) in [
-            (CallContextField::CallerId, current_call.call_id.into()),
-            (CallContextField::IsSuccess, call.is_success.to_word()),
-            (CallContextField::IsPersistent, call.is_persistent.to_word()),
-            (CallContextField::TxId, state.tx_ctx.id().into()),
-            (
-                CallContextField::CallerAddress,
-                current_call.address.to_word(),
-            ),
-            (CallContextField::CalleeAddress, call.address.to_word()),
-            (
-                CallContextField::RwCounterEndOfReversion,
-                call.rw_counter_end_of_reversion.to_word(),
-            ),
-            (CallContextField::IsPersistent, call.is_persistent.to_word()),
-        ] {
-            state.call_context_write(&mut exec_step, call.call_id, field, value);
+        let is_entering_init_call = is_precheck_ok && !is_address_collision;
+        if is_entering_init_call {
+            // Transfer value from caller to callee, the callee account is
+            // always created by writing the empty code hash.
+            let callee_exists = !state.sdb.get_account(&address).1.is_empty();
+            state.transfer(
+                &mut exec_step,
+                caller.address,
+                address,
+                callee_exists,
+                true,
+                callee.value,
+            )?;
+
+            // Increase callee's nonce
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address,
+                    field: AccountField::Nonce,
+                    value: 1.into(),
+                    value_prev: 0.into(),
+                },
+            )?;
         }
 
-        if call.code_hash == CodeDB::empty_code_hash() {
-            // 1. Create with empty initcode.
-            state.handle_return(&mut exec_step, geth_steps, false)?;
+        // There are 2 branches from here.
+        if is_entering_init_call && length > 0 {
+            // 1. Create with non-empty initialization code, copy it from the
+            // caller's memory into the bytecode table and enter the
+            // initialization call.
+            handle_copy(
+                state,
+                &mut exec_step,
+                caller.call_id,
+                offset,
+                &init_code,
+                init_code_hash,
+            )?;
+
+            for (field, value) in [
+                (
+                    CallContextField::ProgramCounter,
+                    (geth_step.pc.0 + 1).into(),
+                ),
+                (
+                    CallContextField::StackPointer,
+                    geth_step.stack.nth_last_filled(n_pop - 1).0.into(),
+                ),
+                (CallContextField::GasLeft, caller_gas_left.into()),
+                (CallContextField::MemorySize, next_memory_word_size.into()),
+                (
+                    CallContextField::ReversibleWriteCounter,
+                    // Caller's nonce and the access list of contract address.
+                    (exec_step.reversible_write_counter + 2).into(),
+                ),
+            ] {
+                state.call_context_write(&mut exec_step, caller.call_id, field, value);
+            }
+
+            for (field, value) in [
+                (CallContextField::CallerId, caller.call_id.into()),
+                (CallContextField::IsSuccess, callee.is_success.to_word()),
+                (CallContextField::TxId, tx_id.into()),
+                (CallContextField::CallerAddress, caller.address.to_word()),
+                (CallContextField::CalleeAddress, address.to_word()),
+                (CallContextField::Depth, callee.depth.into()),
+                (CallContextField::Value, callee.value),
+                (CallContextField::IsStatic, 0.into()),
+                (CallContextField::LastCalleeId, 0.into()),
+                (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+                (CallContextField::LastCalleeReturnDataLength, 0.into()),
+                (CallContextField::IsRoot, 0.into()),
+                (CallContextField::IsCreate, 1.into()),
+                (CallContextField::CodeHash, init_code_hash.to_word()),
+            ] {
+                state.call_context_write(&mut exec_step, callee.call_id, field, value);
+            }
+
             Ok(vec![exec_step])
         } else {
-            // 2. Create with non-empty initcode.
+            // 2. Create with empty initialization code, or failing in precheck
+            // (insufficient balance, depth, nonce overflow) or with a
+            // contract address collision. The initialization call returns
+            // immediately without any execution.
+            for (field, value) in [
+                (CallContextField::LastCalleeId, callee.call_id.into()),
+                (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+                (CallContextField::LastCalleeReturnDataLength, 0.into()),
+            ] {
+                state.call_context_write(&mut exec_step, caller.call_id, field, value);
+            }
+            state.handle_return(&mut exec_step, geth_steps, false)?;
             Ok(vec![exec_step])
         }
     }
 }
+
+/// Push the memory reads of the initialization code and the copy event which
+/// copies them from the caller's memory into the bytecode table.
+fn handle_copy(
+    state: &mut CircuitInputStateRef,
+    step: &mut ExecStep,
+    caller_id: usize,
+    offset: usize,
+    init_code: &[u8],
+    init_code_hash: H256,
+) -> Result<(), Error> {
+    let bytes: Vec<_> = Bytecode::from(init_code.to_vec())
+        .code
+        .iter()
+        .map(|element| (element.value, element.is_code))
+        .collect();
+
+    let rw_counter_start = state.block_ctx.rwc;
+    for (i, (byte, _)) in bytes.iter().enumerate() {
+        state.push_op(
+            step,
+            RW::READ,
+            MemoryOp::new(caller_id, (offset + i).into(), *byte),
+        );
+    }
+
+    state.push_copy(
+        step,
+        CopyEvent {
+            rw_counter_start,
+            src_type: CopyDataType::Memory,
+            src_id: NumberOrHash::Number(caller_id),
+            src_addr: offset.try_into().unwrap(),
+            src_addr_end: (offset + init_code.len()).try_into().unwrap(),
+            dst_type: CopyDataType::Bytecode,
+            dst_id: NumberOrHash::Hash(init_code_hash),
+            dst_addr: 0,
+            log_id: None,
+            bytes,
+            access_list: vec![],
+        },
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{AccountField, AccountOp, Target},
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Bytecode, Word};
+    use ethers_core::utils::keccak256;
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_ACCOUNTS, MOCK_DEPLOYED_CONTRACT_BYTECODE,
+    };
+
+    fn creator_code(is_create2: bool, init_code_length: u64) -> Bytecode {
+        let mut code = bytecode! {
+            PUSH21(*MOCK_DEPLOYED_CONTRACT_BYTECODE)
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH3(0x123456)}); // salt
+        }
+        code.append(&bytecode! {
+            PUSH1(init_code_length) // length
+            PUSH1(0xB) // offset
+            PUSH1(0) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        code.append(&bytecode! {STOP});
+        code
+    }
+
+    fn test_ok(is_create2: bool, init_code_length: u64) {
+        let code = creator_code(is_create2, init_code_length);
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let opcode = if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        };
+        let tx = &builder.block.txs()[0];
+        let step = tx
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(opcode))
+            .unwrap();
+        let caller_id = tx.calls()[0].call_id;
+        let created_address = tx.calls()[1].address;
+
+        let account_ops: Vec<_> = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| builder.block.container.account[op_ref.as_usize()].op())
+            .collect();
+
+        // Caller's nonce is increased and callee's nonce is set to 1.
+        let caller_nonce = account_ops
+            .iter()
+            .find(|op| op.address == MOCK_ACCOUNTS[0] && op.field == AccountField::Nonce)
+            .map(|op| op.value)
+            .unwrap();
+        assert!(account_ops.iter().any(|op| *op
+            == &AccountOp {
+                address: MOCK_ACCOUNTS[0],
+                field: AccountField::Nonce,
+                value: caller_nonce + 1,
+                value_prev: caller_nonce,
+            }));
+        assert!(account_ops.iter().any(|op| *op
+            == &AccountOp {
+                address: created_address,
+                field: AccountField::Nonce,
+                value: Word::one(),
+                value_prev: Word::zero(),
+            }));
+
+        // The keccak input of the address derivation is added to the block.
+        assert!(builder
+            .block
+            .sha3_inputs
+            .iter()
+            .any(|input| keccak256(input)[12..] == created_address.to_fixed_bytes()));
+
+        // The initialization code is copied from the caller's memory into the
+        // bytecode table unless it's empty.
+        assert_eq!(
+            builder
+                .block
+                .copy_events
+                .iter()
+                .filter(|event| event.src_id == NumberOrHash::Number(caller_id)
+                    && event.dst_type == CopyDataType::Bytecode)
+                .count(),
+            (init_code_length > 0) as usize
+        );
+    }
+
+    #[test]
+    fn create_ok() {
+        test_ok(false, 0x15);
+    }
+
+    #[test]
+    fn create_empty_init_code() {
+        test_ok(false, 0);
+    }
+
+    #[test]
+    fn create2_ok() {
+        test_ok(true, 0x15);
+    }
+
+    #[test]
+    fn create2_empty_init_code() {
+        test_ok(true, 0);
+    }
+
+    #[test]
+    fn create2_address_collision() {
+        // The second CREATE2 with the same salt and initialization code
+        // derives the address of the contract created by the first one.
+        let mut code = bytecode! {
+            PUSH21(*MOCK_DEPLOYED_CONTRACT_BYTECODE)
+            PUSH1(0)
+            MSTORE
+        };
+        for _ in 0..2 {
+            code.append(&bytecode! {
+                PUSH3(0x123456) // salt
+                PUSH1(0x15) // length
+                PUSH1(0xB) // offset
+                PUSH1(0) // value
+                CREATE2
+            });
+        }
+        code.append(&bytecode! {STOP});
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let tx = &builder.block.txs()[0];
+        let step = tx
+            .steps()
+            .iter()
+            .rev()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::CREATE2))
+            .unwrap();
+        let created_address = tx.calls()[1].address;
+        assert!(!tx.calls().last().unwrap().is_success);
+
+        // The callee's nonce and code hash are read, and the initialization
+        // call isn't entered, so its nonce is left untouched.
+        let account_ops: Vec<_> = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| builder.block.container.account[op_ref.as_usize()].op())
+            .filter(|op| op.address == created_address)
+            .collect();
+        assert_eq!(
+            account_ops.iter().map(|op| op.field).collect::<Vec<_>>(),
+            vec![AccountField::Nonce, AccountField::CodeHash]
+        );
+        assert_eq!(account_ops[0].value, Word::one());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::ExecError,
+    evm::Opcode,
+    Error,
+};
+use eth_types::{evm_types::OpcodeId, GethExecStep};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`ExecError::InvalidCreationCode`] error, which
+/// happens at `RETURN` of a contract creation when the deployed code starts
+/// with `0xef` ([EIP-3541](https://eips.ethereum.org/EIPS/eip-3541)).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ErrorInvalidCreationCode;
+
+impl Opcode for ErrorInvalidCreationCode {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        debug_assert_eq!(geth_step.op, OpcodeId::RETURN);
+        debug_assert!(state.call()?.is_create());
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::InvalidCreationCode);
+
+        let memory_offset = geth_step.stack.nth_last(0)?;
+        let length = geth_step.stack.nth_last(1)?;
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.nth_last_filled(0),
+            memory_offset,
+        )?;
+        state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(1), length)?;
+
+        // Read the first byte of the deployed code from memory.
+        let first_byte = state.call_ctx()?.memory.0[memory_offset.as_usize()];
+        state.memory_read(&mut exec_step, memory_offset.try_into()?, first_byte)?;
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::{ExecError, OogError},
+    evm::Opcode,
+    Error,
+};
+use eth_types::{evm_types::OpcodeId, GethExecStep};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OogError::DynamicMemoryExpansion`](crate::error::OogError::DynamicMemoryExpansion).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct OOGDynamicMemory;
+
+impl Opcode for OOGDynamicMemory {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        debug_assert!(
+            [OpcodeId::CREATE, OpcodeId::RETURN, OpcodeId::REVERT].contains(&geth_step.op)
+        );
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::OutOfGas(OogError::DynamicMemoryExpansion));
+
+        // CREATE has an extra stack pop for the value, before the memory
+        // offset and length.
+        let stack_read_num = if geth_step.op == OpcodeId::CREATE {
+            3
+        } else {
+            2
+        };
+        for i in 0..stack_read_num {
+            state.stack_read(
+                &mut exec_step,
+                geth_step.stack.nth_last_filled(i),
+                geth_step.stack.nth_last(i)?,
+            )?;
+        }
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
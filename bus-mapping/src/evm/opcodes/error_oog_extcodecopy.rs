@@ -0,0 +1,62 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::{ExecError, OogError},
+    evm::Opcode,
+    operation::{CallContextField, TxAccessListAccountOp, RW},
+    Error,
+};
+use eth_types::{evm_types::OpcodeId, GethExecStep, ToAddress};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OogError::ExtCodeCopy`](crate::error::OogError::ExtCodeCopy).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct OOGExtCodeCopy;
+
+impl Opcode for OOGExtCodeCopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        debug_assert_eq!(geth_step.op, OpcodeId::EXTCODECOPY);
+
+        let mut exec_step = state.new_step(geth_step)?;
+        exec_step.error = Some(ExecError::OutOfGas(OogError::ExtCodeCopy));
+
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            state.tx_ctx.id().into(),
+        );
+
+        // According to EIP-2929, EXTCODECOPY constant gas cost is different for cold
+        // and warm accounts.
+        let external_address = geth_step.stack.last()?.to_address();
+        let is_warm = state.sdb.check_account_in_access_list(&external_address);
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: external_address,
+                is_warm,
+                is_warm_prev: is_warm,
+            },
+        );
+
+        // EXTCODECOPY has 4 stack read values: external address, destination
+        // offset, code offset and size.
+        for i in 0..4 {
+            state.stack_read(
+                &mut exec_step,
+                geth_step.stack.nth_last_filled(i),
+                geth_step.stack.nth_last(i)?,
+            )?;
+        }
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
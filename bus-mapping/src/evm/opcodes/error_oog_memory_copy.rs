@@ -2,10 +2,9 @@ use crate::{
     circuit_input_builder::{CircuitInputStateRef, ExecStep},
     error::{ExecError, OogError},
     evm::Opcode,
-    operation::{CallContextField, TxAccessListAccountOp, RW},
     Error,
 };
-use eth_types::{evm_types::OpcodeId, GethExecStep, ToAddress};
+use eth_types::{evm_types::OpcodeId, GethExecStep};
 
 /// Placeholder structure used to implement [`Opcode`] trait over it
 /// corresponding to the
@@ -22,7 +21,6 @@ impl Opcode for OOGMemoryCopy {
         debug_assert!([
             OpcodeId::CALLDATACOPY,
             OpcodeId::CODECOPY,
-            OpcodeId::EXTCODECOPY,
             OpcodeId::RETURNDATACOPY
         ]
         .contains(&geth_step.op));
@@ -30,36 +28,8 @@ impl Opcode for OOGMemoryCopy {
         let mut exec_step = state.new_step(geth_step)?;
         exec_step.error = Some(ExecError::OutOfGas(OogError::MemoryCopy));
 
-        let is_extcodecopy = geth_step.op == OpcodeId::EXTCODECOPY;
-
-        // According to EIP-2929, EXTCODECOPY constant gas cost is different for cold
-        // and warm accounts.
-        if is_extcodecopy {
-            state.call_context_read(
-                &mut exec_step,
-                state.call()?.call_id,
-                CallContextField::TxId,
-                state.tx_ctx.id().into(),
-            );
-
-            let external_address = geth_step.stack.last()?.to_address();
-            let is_warm = state.sdb.check_account_in_access_list(&external_address);
-            state.push_op(
-                &mut exec_step,
-                RW::READ,
-                TxAccessListAccountOp {
-                    tx_id: state.tx_ctx.id(),
-                    address: external_address,
-                    is_warm,
-                    is_warm_prev: is_warm,
-                },
-            );
-        }
-
         // Each of CALLDATACOPY, CODECOPY and RETURNDATACOPY has 3 stack read values.
-        // But EXTCODECOPY has 4. It has an extra stack pop for external address.
-        let stack_read_num = if is_extcodecopy { 4 } else { 3 };
-        for i in 0..stack_read_num {
+        for i in 0..3 {
             state.stack_read(
                 &mut exec_step,
                 geth_step.stack.nth_last_filled(i),
@@ -18,6 +18,8 @@ impl Opcode for Log {
     ) -> Result<Vec<ExecStep>, Error> {
         let geth_step = &geth_steps[0];
         let mut exec_step = gen_log_step(state, geth_step)?;
+        // The log is only written when neither the current call nor any of its
+        // callers reverts, so it ends up in the receipt.
         if state.call()?.is_persistent {
             let copy_event = gen_copy_event(state, geth_step, &mut exec_step)?;
             state.push_copy(&mut exec_step, copy_event);
@@ -190,6 +192,7 @@ fn gen_copy_event(
         log_id: Some(state.tx_ctx.log_id as u64 + 1),
         rw_counter_start,
         bytes: steps,
+        access_list: vec![],
     })
 }
 
@@ -213,23 +216,45 @@ mod log_tests {
     #[test]
     fn logs_opcode_ok() {
         // zero topics
-        test_logs_opcode(&[]);
+        test_logs_opcode(&[], true);
         // one topics
-        test_logs_opcode(&[Word::from(0xA0)]);
+        test_logs_opcode(&[Word::from(0xA0)], true);
         // two topics
-        test_logs_opcode(&[Word::from(0xA0), Word::from(0xef)]);
+        test_logs_opcode(&[Word::from(0xA0), Word::from(0xef)], true);
         // three topics
-        test_logs_opcode(&[Word::from(0xA0), Word::from(0xef), Word::from(0xb0)]);
+        test_logs_opcode(
+            &[Word::from(0xA0), Word::from(0xef), Word::from(0xb0)],
+            true,
+        );
+        // four topics
+        test_logs_opcode(
+            &[
+                Word::from(0xA0),
+                Word::from(0xef),
+                Word::from(0xb0),
+                Word::from(0x37),
+            ],
+            true,
+        );
+    }
+
+    #[test]
+    fn logs_opcode_not_persistent() {
+        // zero topics
+        test_logs_opcode(&[], false);
         // four topics
-        test_logs_opcode(&[
-            Word::from(0xA0),
-            Word::from(0xef),
-            Word::from(0xb0),
-            Word::from(0x37),
-        ]);
+        test_logs_opcode(
+            &[
+                Word::from(0xA0),
+                Word::from(0xef),
+                Word::from(0xb0),
+                Word::from(0x37),
+            ],
+            false,
+        );
     }
 
-    fn test_logs_opcode(topics: &[Word]) {
+    fn test_logs_opcode(topics: &[Word], is_persistent: bool) {
         let log_codes = [
             OpcodeId::LOG0,
             OpcodeId::LOG1,
@@ -252,7 +277,16 @@ mod log_tests {
         code.push(32, Word::from(msize));
         code.push(32, Word::from(mstart));
         code.write_op(cur_op_code);
-        code.op_stop();
+        if is_persistent {
+            code.op_stop();
+        } else {
+            // revert the call to discard the log
+            code.append(&bytecode! {
+                PUSH1(0x00) // length
+                PUSH1(0x00) // offset
+                REVERT
+            });
+        }
 
         // prepare memory data
         let pushdata = hex::decode("1234567890abcdef1234567890abcdef").unwrap();
@@ -290,7 +324,10 @@ mod log_tests {
             .handle_block(&block.eth_block, &block.geth_traces)
             .unwrap();
 
-        let is_persistent = builder.block.txs()[0].calls()[0].is_persistent;
+        assert_eq!(
+            builder.block.txs()[0].calls()[0].is_persistent,
+            is_persistent
+        );
         let callee_address = builder.block.txs()[0].to;
 
         let step = builder.block.txs()[0]
@@ -353,31 +390,36 @@ mod log_tests {
                     &CallContextOp {
                         call_id: 1,
                         field: CallContextField::IsPersistent,
-                        value: Word::from(1),
+                        value: Word::from(is_persistent as u8),
                     },
                 ),
             ]
         );
 
-        // TODO: handle is_persistent = false conditions
-        if is_persistent {
-            assert_eq!(
-                [6].map(|idx| &builder.block.container.tx_log
-                    [step.bus_mapping_instance[idx].as_usize()])
-                    .map(|operation| (operation.rw(), operation.op())),
-                [(
-                    RW::WRITE,
-                    &TxLogOp {
-                        tx_id: 1,
-                        log_id: step.log_id + 1,
-                        field: TxLogField::Address,
-                        index: 0,
-                        value: callee_address.to_word(),
-                    }
-                ),]
-            );
+        // Logs of a reverted call are neither written nor copied.
+        if !is_persistent {
+            assert!(builder.block.container.tx_log.is_empty());
+            assert!(builder.block.copy_events.is_empty());
+            return;
         }
 
+        assert_eq!(
+            [6].map(
+                |idx| &builder.block.container.tx_log[step.bus_mapping_instance[idx].as_usize()]
+            )
+            .map(|operation| (operation.rw(), operation.op())),
+            [(
+                RW::WRITE,
+                &TxLogOp {
+                    tx_id: 1,
+                    log_id: step.log_id + 1,
+                    field: TxLogField::Address,
+                    index: 0,
+                    value: callee_address.to_word(),
+                }
+            ),]
+        );
+
         // log topic writes
         let mut log_topic_ops = Vec::with_capacity(topic_count);
         for (idx, topic) in topics.iter().rev().enumerate() {
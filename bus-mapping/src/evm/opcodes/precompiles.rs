@@ -0,0 +1,812 @@
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
+        PrecompileEvent,
+    },
+    operation::{CallContextField, MemoryOp, RW},
+    precompile::{execute_precompiled_unlimited, PrecompileCalls},
+    Error,
+};
+use eth_types::{
+    evm_types::{Gas, GasCost, Memory},
+    GethExecStep, ToWord,
+};
+use std::cmp::min;
+
+/// Generate the [`ExecStep`] of a call into a precompiled contract, executed
+/// right after the step that entered the precompile call context (either a
+/// `*CALL` opcode or the `BeginTx` of a transaction to a precompile).
+///
+/// The input of the identity precompile is copied from the caller memory into
+/// the precompile memory. For the other precompiles, the input is copied from
+/// the caller memory (or the tx calldata for a root call) into an RLC
+/// accumulator, and the output is written into the precompile memory from
+/// another RLC accumulator, so that the actual computation is left to a lookup
+/// to the precompile table.  For an internal call, the output is then copied
+/// into the caller memory as return data and the caller context is restored.
+///
+/// A failing call consumes all its gas and returns no data.  Its input is
+/// still copied when the precompile circuit proves the failure (see
+/// [`PrecompileCalls::needs_circuit`]), e.g. on an invalid elliptic curve
+/// point.  The calls that can't be proved by the precompile circuit (see
+/// [`PrecompileCalls::is_supported`]) return
+/// [`Error::UnsupportedPrecompileCall`].
+pub(crate) fn gen_associated_ops(
+    state: &mut CircuitInputStateRef,
+    geth_steps: &[GethExecStep],
+    precompile: PrecompileCalls,
+    input: &[u8],
+    output: &[u8],
+    gas_left: u64,
+    gas_cost: u64,
+) -> Result<ExecStep, Error> {
+    let call = state.call()?.clone();
+    let tx_id = state.tx_ctx.id();
+    let needs_circuit = precompile.needs_circuit(input.len() as u64, gas_left);
+    if (needs_circuit || call.is_success) && !precompile.is_supported(input) {
+        return Err(Error::UnsupportedPrecompileCall(call.address));
+    }
+    let mut exec_step = ExecStep {
+        exec_state: ExecState::Precompile(precompile),
+        gas_left: Gas(gas_left),
+        gas_cost: GasCost(gas_cost),
+        call_index: state.call_ctx()?.index,
+        rwc: state.block_ctx.rwc,
+        reversible_write_counter: state.call_ctx()?.reversible_write_counter,
+        log_id: state.tx_ctx.log_id,
+        ..Default::default()
+    };
+
+    let mut field_values = vec![
+        (CallContextField::TxId, tx_id.into()),
+        (CallContextField::IsSuccess, (call.is_success as u64).into()),
+        (
+            CallContextField::CallDataOffset,
+            call.call_data_offset.into(),
+        ),
+        (
+            CallContextField::CallDataLength,
+            call.call_data_length.into(),
+        ),
+    ];
+    if !call.is_root {
+        field_values.extend([
+            (
+                CallContextField::ReturnDataOffset,
+                call.return_data_offset.into(),
+            ),
+            (
+                CallContextField::ReturnDataLength,
+                call.return_data_length.into(),
+            ),
+        ]);
+    }
+    if !call.is_success {
+        field_values.push((
+            CallContextField::RwCounterEndOfReversion,
+            (call.rw_counter_end_of_reversion as u64).into(),
+        ));
+    }
+    for (field, value) in field_values {
+        state.call_context_read(&mut exec_step, call.call_id, field, value);
+    }
+
+    if needs_circuit {
+        gen_precompile_input_ops(state, &mut exec_step, precompile, input)?;
+    }
+
+    if !call.is_success {
+        if call.is_root {
+            state.handle_reversion();
+            state.tx_ctx.pop_call_ctx();
+            return Ok(exec_step);
+        }
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        return Ok(exec_step);
+    }
+
+    if precompile == PrecompileCalls::Identity {
+        // The output of the identity precompile is its input, so for an
+        // internal call the input is copied straight into the precompile
+        // memory.
+        if !call.is_root && !input.is_empty() {
+            let rw_counter_start = state.block_ctx.rwc;
+            for (i, byte) in input.iter().enumerate() {
+                state.push_op(
+                    &mut exec_step,
+                    RW::READ,
+                    MemoryOp::new(
+                        call.caller_id,
+                        (call.call_data_offset as usize + i).into(),
+                        *byte,
+                    ),
+                );
+                state.push_op(
+                    &mut exec_step,
+                    RW::WRITE,
+                    MemoryOp::new(call.call_id, i.into(), *byte),
+                );
+            }
+            state.push_copy(
+                &mut exec_step,
+                CopyEvent {
+                    rw_counter_start,
+                    src_type: CopyDataType::Memory,
+                    src_id: NumberOrHash::Number(call.caller_id),
+                    src_addr: call.call_data_offset,
+                    src_addr_end: call.call_data_offset + call.call_data_length,
+                    dst_type: CopyDataType::Memory,
+                    dst_id: NumberOrHash::Number(call.call_id),
+                    dst_addr: 0,
+                    log_id: None,
+                    bytes: input.iter().map(|byte| (*byte, false)).collect(),
+                    access_list: vec![],
+                },
+            );
+        }
+    } else if !call.is_root {
+        gen_precompile_output_ops(state, &mut exec_step, output)?;
+    }
+
+    // The output of a root call is discarded.
+    if call.is_root {
+        return Ok(exec_step);
+    }
+    state.call_ctx_mut()?.memory = Memory::from(output.to_vec());
+
+    // Copy the output into the caller memory as return data.
+    let copy_length = min(output.len(), call.return_data_length as usize);
+    if copy_length > 0 {
+        let return_offset = call.return_data_offset as usize;
+        state.caller_ctx_mut()?.memory.0[return_offset..return_offset + copy_length]
+            .copy_from_slice(&output[..copy_length]);
+
+        let rw_counter_start = state.block_ctx.rwc;
+        for (i, byte) in output[..copy_length].iter().enumerate() {
+            state.push_op(
+                &mut exec_step,
+                RW::READ,
+                MemoryOp::new(call.call_id, i.into(), *byte),
+            );
+            state.push_op(
+                &mut exec_step,
+                RW::WRITE,
+                MemoryOp::new(call.caller_id, (return_offset + i).into(), *byte),
+            );
+        }
+        state.push_copy(
+            &mut exec_step,
+            CopyEvent {
+                rw_counter_start,
+                src_type: CopyDataType::Memory,
+                src_id: NumberOrHash::Number(call.call_id),
+                src_addr: 0,
+                src_addr_end: output.len() as u64,
+                dst_type: CopyDataType::Memory,
+                dst_id: NumberOrHash::Number(call.caller_id),
+                dst_addr: return_offset as u64,
+                log_id: None,
+                bytes: output[..copy_length]
+                    .iter()
+                    .map(|byte| (*byte, false))
+                    .collect(),
+                access_list: vec![],
+            },
+        );
+    }
+
+    state.handle_return(&mut exec_step, geth_steps, true)?;
+    Ok(exec_step)
+}
+
+/// Copy the input of a call to a precompile other than identity into an RLC
+/// accumulator, and push the event of the precompile on it.  The precompile
+/// table holds the relation between the input RLC, the output RLC and the
+/// failure flag.  The input of the precompiles reading a fixed size input is
+/// right padded with zeros or truncated to that size.
+fn gen_precompile_input_ops(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    precompile: PrecompileCalls,
+    input: &[u8],
+) -> Result<(), Error> {
+    let call = state.call()?.clone();
+    let tx_id = state.tx_ctx.id();
+
+    // Only the bytes of the calldata are read, the padding is zeros.
+    let read_length = input.len();
+    let mut input = input.to_vec();
+    if let Some(input_len) = precompile.input_len() {
+        input.resize(input_len, 0);
+    }
+
+    if !input.is_empty() {
+        let rw_counter_start = state.block_ctx.rwc;
+        let (src_type, src_id) = if call.is_root {
+            (CopyDataType::TxCalldata, NumberOrHash::Number(tx_id))
+        } else {
+            for (i, byte) in input[..min(read_length, input.len())].iter().enumerate() {
+                state.push_op(
+                    exec_step,
+                    RW::READ,
+                    MemoryOp::new(
+                        call.caller_id,
+                        (call.call_data_offset as usize + i).into(),
+                        *byte,
+                    ),
+                );
+            }
+            (CopyDataType::Memory, NumberOrHash::Number(call.caller_id))
+        };
+        state.push_copy(
+            exec_step,
+            CopyEvent {
+                rw_counter_start,
+                src_type,
+                src_id,
+                src_addr: call.call_data_offset,
+                src_addr_end: call.call_data_offset + call.call_data_length,
+                dst_type: CopyDataType::RlcAcc,
+                dst_id: NumberOrHash::Number(call.call_id),
+                dst_addr: 0,
+                log_id: None,
+                bytes: input.iter().map(|byte| (*byte, false)).collect(),
+                access_list: vec![],
+            },
+        );
+    }
+
+    // The call may fail for lack of gas, so the event is the execution of the
+    // precompile given enough gas.
+    let (output, gas_cost, is_success) =
+        match execute_precompiled_unlimited(&precompile.into(), &input) {
+            Some((output, gas_cost)) => (output, gas_cost, true),
+            None => {
+                // Among the precompiles with an input dependent gas cost, only
+                // blake2f fails on an invalid input, whose gas cost is the
+                // number of rounds.
+                let gas_cost = precompile
+                    .gas_cost(read_length as u64)
+                    .unwrap_or_else(|| u32::from_be_bytes(input[..4].try_into().unwrap()) as u64);
+                (vec![], gas_cost, false)
+            }
+        };
+    state.push_precompile_event(PrecompileEvent {
+        precompile,
+        input,
+        output,
+        gas_cost,
+        is_success,
+    });
+
+    Ok(())
+}
+
+/// Write the output of a successful internal call to a precompile other than
+/// identity from an RLC accumulator into the precompile memory.
+fn gen_precompile_output_ops(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    output: &[u8],
+) -> Result<(), Error> {
+    if output.is_empty() {
+        return Ok(());
+    }
+
+    let call_id = state.call()?.call_id;
+    let rw_counter_start = state.block_ctx.rwc;
+    for (i, byte) in output.iter().enumerate() {
+        state.memory_write(exec_step, i.into(), *byte)?;
+    }
+    state.push_copy(
+        exec_step,
+        CopyEvent {
+            rw_counter_start,
+            src_type: CopyDataType::RlcAcc,
+            src_id: NumberOrHash::Number(call_id),
+            src_addr: 0,
+            src_addr_end: output.len() as u64,
+            dst_type: CopyDataType::Memory,
+            dst_id: NumberOrHash::Number(call_id),
+            dst_addr: 0,
+            log_id: None,
+            bytes: output.iter().map(|byte| (*byte, false)).collect(),
+            access_list: vec![],
+        },
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod precompiles_tests {
+    use crate::{
+        circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{CallContextField, RW},
+        precompile::{ecrecover_sign_data, PrecompileCalls, MODEXP_INPUT_LIMIT},
+        Error,
+    };
+    use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, word, Word};
+    use halo2_proofs::halo2curves::secp256k1;
+    use mock::TestContext;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn identity_call_impl() {
+        let code = bytecode! {
+            PUSH16(word!("0x0123456789ABCDEF0123456789ABCDEF"))
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x20) // retLength
+            PUSH1(0x20) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x04) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let steps = builder.block.txs()[0].steps();
+        let index = steps
+            .iter()
+            .position(|step| step.exec_state == ExecState::Op(OpcodeId::STATICCALL))
+            .unwrap();
+        let step = &steps[index + 1];
+        assert_eq!(
+            step.exec_state,
+            ExecState::Precompile(PrecompileCalls::Identity)
+        );
+        assert_eq!(
+            Some(step.gas_cost.0),
+            PrecompileCalls::Identity.gas_cost(0x20)
+        );
+
+        let container = &builder.block.container;
+        let operation = &container.call_context[step.bus_mapping_instance[3].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op().field, operation.op().value),
+            (RW::READ, CallContextField::CallDataLength, Word::from(0x20)),
+        );
+
+        // Input and return data copies, the identity precompile doesn't need
+        // the precompile table.
+        let copy_events = &builder.block.copy_events;
+        assert_eq!(
+            copy_events
+                .iter()
+                .map(|event| (event.src_type, event.dst_type, event.bytes.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                (CopyDataType::Memory, CopyDataType::Memory, 0x20),
+                (CopyDataType::Memory, CopyDataType::Memory, 0x20),
+            ]
+        );
+        assert_eq!(copy_events[1].dst_id, NumberOrHash::Number(1));
+        assert_eq!(copy_events[1].dst_addr, 0x20);
+        assert!(builder.block.precompile_events.is_empty());
+    }
+
+    #[test]
+    fn sha256_call_impl() {
+        let code = bytecode! {
+            PUSH1(0x61)
+            PUSH1(0x00)
+            MSTORE8
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x01) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x02) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // Input, output and return data copies.
+        let copy_events = &builder.block.copy_events;
+        assert_eq!(
+            copy_events
+                .iter()
+                .map(|event| (event.src_type, event.dst_type, event.bytes.len()))
+                .collect::<Vec<_>>(),
+            vec![
+                (CopyDataType::Memory, CopyDataType::RlcAcc, 0x01),
+                (CopyDataType::RlcAcc, CopyDataType::Memory, 0x20),
+                (CopyDataType::Memory, CopyDataType::Memory, 0x20),
+            ]
+        );
+
+        let precompile_event = &builder.block.precompile_events[0];
+        assert_eq!(precompile_event.precompile, PrecompileCalls::Sha256);
+        assert_eq!(precompile_event.input, b"a".to_vec());
+        assert_eq!(precompile_event.output.len(), 0x20);
+    }
+
+    #[test]
+    fn sha256_out_of_gas_call_impl() {
+        let code = bytecode! {
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x02) // address
+            PUSH1(0x10) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let steps = builder.block.txs()[0].steps();
+        let index = steps
+            .iter()
+            .position(|step| step.exec_state == ExecState::Op(OpcodeId::STATICCALL))
+            .unwrap();
+        let step = &steps[index + 1];
+        assert_eq!(
+            step.exec_state,
+            ExecState::Precompile(PrecompileCalls::Sha256)
+        );
+        // All the gas passed to the call is consumed.
+        assert_eq!(step.gas_cost.0, step.gas_left.0);
+
+        // The call has no reversible writes, so its writes are reverted right after
+        // the 7 call context reads and the 12 restoring the caller context.
+        let container = &builder.block.container;
+        assert_eq!(
+            [1, 6].map(|idx| {
+                let operation = &container.call_context[step.bus_mapping_instance[idx].as_usize()];
+                (operation.rw(), operation.op().field, operation.op().value)
+            }),
+            [
+                (RW::READ, CallContextField::IsSuccess, Word::zero()),
+                (
+                    RW::READ,
+                    CallContextField::RwCounterEndOfReversion,
+                    Word::from(step.rwc.0 + 7 + 12 - 1),
+                ),
+            ]
+        );
+
+        // Neither the input nor the output are copied.
+        assert!(builder.block.copy_events.is_empty());
+        assert!(builder.block.precompile_events.is_empty());
+    }
+
+    #[test]
+    fn ecrecover_invalid_signature_impl() {
+        let code = bytecode! {
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x01) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // The input is padded to 128 bytes, and the invalid signature leads to
+        // an empty output, so there's no output nor return data copy.
+        let copy_events = &builder.block.copy_events;
+        assert_eq!(
+            copy_events
+                .iter()
+                .map(|event| (event.src_type, event.dst_type, event.bytes.len()))
+                .collect::<Vec<_>>(),
+            vec![(CopyDataType::Memory, CopyDataType::RlcAcc, 0x80)]
+        );
+
+        let precompile_event = &builder.block.precompile_events[0];
+        assert_eq!(precompile_event.precompile, PrecompileCalls::ECRecover);
+        assert_eq!(precompile_event.input, vec![0; 0x80]);
+        assert!(precompile_event.output.is_empty());
+        assert!(ecrecover_sign_data(&precompile_event.input).is_none());
+    }
+
+    #[test]
+    fn ec_add_invalid_point_impl() {
+        // The point (1, 1) is not on the curve, so the call fails.
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x01)
+            PUSH1(0x20)
+            MSTORE
+            PUSH1(0x40) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x80) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x06) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let steps = builder.block.txs()[0].steps();
+        let index = steps
+            .iter()
+            .position(|step| step.exec_state == ExecState::Op(OpcodeId::STATICCALL))
+            .unwrap();
+        let step = &steps[index + 1];
+        assert_eq!(step.gas_cost.0, step.gas_left.0);
+
+        // The input is copied to prove the failure, and there's no output.
+        let copy_events = &builder.block.copy_events;
+        assert_eq!(
+            copy_events
+                .iter()
+                .map(|event| (event.src_type, event.dst_type, event.bytes.len()))
+                .collect::<Vec<_>>(),
+            vec![(CopyDataType::Memory, CopyDataType::RlcAcc, 0x80)]
+        );
+
+        let precompile_event = &builder.block.precompile_events[0];
+        assert_eq!(precompile_event.precompile, PrecompileCalls::Bn128Add);
+        assert!(precompile_event.output.is_empty());
+        assert_eq!(
+            Some(precompile_event.gas_cost),
+            PrecompileCalls::Bn128Add.gas_cost(0x80)
+        );
+        assert!(!precompile_event.is_success);
+    }
+
+    #[test]
+    fn modexp_out_of_gas_impl() {
+        // 8^9 mod 10, with 1 byte base, exponent and modulus.
+        let code = bytecode! {
+            PUSH1(0x01)
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(0x01)
+            PUSH1(0x20)
+            MSTORE
+            PUSH1(0x01)
+            PUSH1(0x40)
+            MSTORE
+            PUSH1(0x08)
+            PUSH1(0x60)
+            MSTORE8
+            PUSH1(0x09)
+            PUSH1(0x61)
+            MSTORE8
+            PUSH1(0x0A)
+            PUSH1(0x62)
+            MSTORE8
+            PUSH1(0x01) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x63) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x05) // address
+            PUSH1(0x10) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // The gas cost depends on the input, so the input is copied to look up
+        // the gas cost in the precompile table.
+        let copy_events = &builder.block.copy_events;
+        assert_eq!(
+            copy_events
+                .iter()
+                .map(|event| (event.src_type, event.dst_type, event.bytes.len()))
+                .collect::<Vec<_>>(),
+            vec![(
+                CopyDataType::Memory,
+                CopyDataType::RlcAcc,
+                MODEXP_INPUT_LIMIT
+            )]
+        );
+
+        let precompile_event = &builder.block.precompile_events[0];
+        assert_eq!(precompile_event.precompile, PrecompileCalls::Modexp);
+        assert_eq!(precompile_event.output, vec![8]);
+        assert_eq!(precompile_event.gas_cost, 200);
+        assert!(precompile_event.is_success);
+    }
+
+    #[test]
+    fn blake2f_invalid_final_flag_impl() {
+        // A blake2f call with 0 rounds and a final block flag of 2.
+        let code = bytecode! {
+            PUSH1(0x02)
+            PUSH1(0xD4)
+            MSTORE8
+            PUSH1(0x40) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0xD5) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x09) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let precompile_event = &builder.block.precompile_events[0];
+        assert_eq!(precompile_event.precompile, PrecompileCalls::Blake2F);
+        assert_eq!(precompile_event.input.len(), 0xD5);
+        assert!(precompile_event.output.is_empty());
+        assert_eq!(precompile_event.gas_cost, 0);
+        assert!(!precompile_event.is_success);
+    }
+
+    #[test]
+    fn ecrecover_unreduced_msg_hash_impl() {
+        // A valid signature of the message hash n + 1, where n is the secp256k1
+        // curve order, with r the x coordinate of the generator and s = 2.
+        let code = bytecode! {
+            PUSH32(word!("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364142"))
+            PUSH1(0x00)
+            MSTORE
+            PUSH1(27)
+            PUSH1(0x20)
+            MSTORE
+            PUSH32(word!("0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"))
+            PUSH1(0x40)
+            MSTORE
+            PUSH1(0x02)
+            PUSH1(0x60)
+            MSTORE
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x80) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x01) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // The message hash is reduced to 1 before the verification.
+        let precompile_event = &builder.block.precompile_events[0];
+        assert_eq!(precompile_event.precompile, PrecompileCalls::ECRecover);
+        assert_eq!(precompile_event.output.len(), 0x20);
+        assert!(precompile_event.is_success);
+        let sign_data = ecrecover_sign_data(&precompile_event.input).unwrap();
+        assert_eq!(sign_data.msg_hash, secp256k1::Fq::from(1));
+    }
+
+    #[test]
+    fn ec_pairing_unsupported() {
+        // The pairing check of the point at infinity in G1 and G2 succeeds.
+        let code = bytecode! {
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0xC0) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x08) // address
+            PUSH3(0x020000) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        assert!(matches!(
+            builder.handle_block(&block.eth_block, &block.geth_traces),
+            Err(Error::UnsupportedPrecompileCall(address))
+                if address == PrecompileCalls::Bn128Pairing.into()
+        ));
+    }
+
+    #[test]
+    fn blake2f_rounds_impl() {
+        // A blake2f call with 13 rounds on zeros.
+        let code = bytecode! {
+            PUSH1(13)
+            PUSH1(0x03)
+            MSTORE8
+            PUSH1(0x40) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0xD5) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x09) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            POP
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::simple_ctx_with_bytecode(code)
+            .unwrap()
+            .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // The number of rounds, which is also the gas cost, is only bounded by
+        // the capacity of the BLAKE2F Circuit.
+        let precompile_event = &builder.block.precompile_events[0];
+        assert_eq!(precompile_event.precompile, PrecompileCalls::Blake2F);
+        assert_eq!(precompile_event.output.len(), 0x40);
+        assert_eq!(precompile_event.gas_cost, 13);
+        assert!(precompile_event.is_success);
+    }
+}
@@ -188,6 +188,7 @@ fn handle_copy(
             dst_addr: destination.offset.try_into().unwrap(),
             log_id: None,
             bytes,
+            access_list: vec![],
         },
     );
 
@@ -230,6 +231,7 @@ fn handle_create(
             dst_addr: 0,
             log_id: None,
             bytes,
+            access_list: vec![],
         },
     );
 
@@ -0,0 +1,303 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{
+        AccountField, AccountOp, CallContextField, TxAccessListAccountOp, TxDestructedAccountOp,
+    },
+    state_db::CodeDB,
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT) `OpcodeId`.
+///
+/// The whole balance of the current account is moved to the beneficiary, and
+/// the current account is marked as destructed.  Like in geth, the account
+/// stays alive until the end of the transaction, and it's destructed (along
+/// with its storage) by the steps following `EndTx`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Selfdestruct;
+
+impl Opcode for Selfdestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let call = state.call()?.clone();
+        let sender = call.address;
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::IsStatic, (call.is_static as u64).into()),
+            (CallContextField::CalleeAddress, sender.to_word()),
+            (
+                CallContextField::RwCounterEndOfReversion,
+                (call.rw_counter_end_of_reversion as u64).into(),
+            ),
+            (
+                CallContextField::IsPersistent,
+                (call.is_persistent as u64).into(),
+            ),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value);
+        }
+
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        // The new account cost is charged when the beneficiary is empty.
+        let beneficiary_is_empty = state.account_empty_read(&mut exec_step, beneficiary);
+        let beneficiary_balance = state.sdb.get_account(&beneficiary).1.balance;
+
+        let (found, sender_account) = state.sdb.get_account(&sender);
+        if !found {
+            return Err(Error::AccountNotFound(sender));
+        }
+        let value = sender_account.balance;
+
+        // Credit the beneficiary, creating it first if it doesn't exist. When the
+        // beneficiary is the current account the balance is simply burnt.
+        if !value.is_zero() && beneficiary != sender {
+            if beneficiary_is_empty {
+                state.push_op_reversible(
+                    &mut exec_step,
+                    AccountOp {
+                        address: beneficiary,
+                        field: AccountField::CodeHash,
+                        value: CodeDB::empty_code_hash().to_word(),
+                        value_prev: Word::zero(),
+                    },
+                )?;
+            }
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: beneficiary,
+                    field: AccountField::Balance,
+                    value: beneficiary_balance + value,
+                    value_prev: beneficiary_balance,
+                },
+            )?;
+        }
+
+        state.push_op_reversible(
+            &mut exec_step,
+            AccountOp {
+                address: sender,
+                field: AccountField::Balance,
+                value: Word::zero(),
+                value_prev: value,
+            },
+        )?;
+
+        // The account is destructed after EndTx, unless the call is reverted.
+        let is_destructed = state.sdb.is_destructed(&sender);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxDestructedAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: sender,
+                is_destructed: true,
+                is_destructed_prev: is_destructed,
+            },
+        )?;
+
+        state.call_context_read(
+            &mut exec_step,
+            call.call_id,
+            CallContextField::IsSuccess,
+            1.into(),
+        );
+
+        state.handle_return(&mut exec_step, geth_steps, !call.is_root)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod selfdestruct_tests {
+    use crate::{
+        circuit_input_builder::ExecState,
+        mock::BlockData,
+        operation::{
+            AccountField, AccountOp, CallContextField, CallContextOp, StackOp, StorageClearOp,
+            TxDestructedAccountOp, RW,
+        },
+    };
+    use eth_types::{
+        address, bytecode,
+        evm_types::{OpcodeId, StackAddress},
+        geth_types::GethData,
+        ToWord, Word,
+    };
+    use mock::TestContext;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn selfdestruct_opcode_impl() {
+        let beneficiary = address!("0x00000000000000000000000000000000000000be");
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000000010"))
+                    .balance(Word::from(1u64 << 20))
+                    .code(code);
+                accs[1]
+                    .address(address!("0x0000000000000000000000000000000000000020"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+
+        let contract = address!("0x0000000000000000000000000000000000000010");
+        let container = &builder.block.container;
+
+        assert_eq!(
+            [2, 4].map(|idx| {
+                let operation = &container.call_context[step.bus_mapping_instance[idx].as_usize()];
+                (operation.rw(), operation.op().clone())
+            }),
+            [
+                (
+                    RW::READ,
+                    CallContextOp {
+                        call_id: 1,
+                        field: CallContextField::CalleeAddress,
+                        value: contract.to_word(),
+                    }
+                ),
+                (
+                    RW::READ,
+                    CallContextOp {
+                        call_id: 1,
+                        field: CallContextField::IsPersistent,
+                        value: Word::one(),
+                    }
+                ),
+            ]
+        );
+
+        let operation = &container.stack[step.bus_mapping_instance[5].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::READ,
+                &StackOp::new(1, StackAddress::from(1023), beneficiary.to_word())
+            )
+        );
+
+        // Beneficiary doesn't exist, so it's created before being credited.
+        assert_eq!(
+            (7..11)
+                .map(|idx| {
+                    let operation = &container.account[step.bus_mapping_instance[idx].as_usize()];
+                    (operation.rw(), operation.op().address, operation.op().field)
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                (RW::READ, beneficiary, AccountField::CodeHash),
+                (RW::WRITE, beneficiary, AccountField::CodeHash),
+                (RW::WRITE, beneficiary, AccountField::Balance),
+                (RW::WRITE, contract, AccountField::Balance),
+            ]
+        );
+
+        let operation = &container.tx_destructed_account[step.bus_mapping_instance[11].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::WRITE,
+                &TxDestructedAccountOp {
+                    tx_id: 1,
+                    address: contract,
+                    is_destructed: true,
+                    is_destructed_prev: false,
+                }
+            )
+        );
+
+        // The contract is only cleared by the step following EndTx.
+        let steps = builder.block.txs()[0].steps();
+        let destruct_step = steps.last().unwrap();
+        assert_eq!(destruct_step.exec_state, ExecState::DestructAccount);
+        let operation = &container.storage_clear[destruct_step.bus_mapping_instance[2].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::WRITE,
+                &StorageClearOp {
+                    tx_id: 1,
+                    address: contract,
+                }
+            )
+        );
+        assert_eq!(
+            (3..6)
+                .map(|idx| {
+                    let operation =
+                        &container.account[destruct_step.bus_mapping_instance[idx].as_usize()];
+                    (operation.rw(), operation.op().address, operation.op().field)
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                (RW::WRITE, contract, AccountField::Balance),
+                (RW::WRITE, contract, AccountField::Nonce),
+                (RW::WRITE, contract, AccountField::CodeHash),
+            ]
+        );
+
+        let operation = &container.account[step.bus_mapping_instance[9].as_usize()];
+        assert_eq!(
+            operation.op(),
+            &AccountOp {
+                address: beneficiary,
+                field: AccountField::Balance,
+                value: Word::from(1u64 << 20),
+                value_prev: Word::zero(),
+            }
+        );
+    }
+}
@@ -75,6 +75,7 @@ impl Opcode for Sha3 {
                 log_id: None,
                 rw_counter_start,
                 bytes: steps,
+                access_list: vec![],
             },
         );
 
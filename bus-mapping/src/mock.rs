@@ -4,7 +4,11 @@ use crate::{
     circuit_input_builder::{get_state_accesses, Block, CircuitInputBuilder, CircuitsParams},
     state_db::{self, CodeDB, StateDB},
 };
-use eth_types::{geth_types::GethData, Word};
+use eth_types::{
+    geth_types::{Account, GethData},
+    mpt::{storage_value_rlp, PartialTrie, TrieAccount, EMPTY_TRIE_ROOT},
+    EIP1186ProofResponse, StorageProof, ToBigEndian, ToWord, Word,
+};
 
 /// BlockData is a type that contains all the information from a block required
 /// to build the circuit inputs.
@@ -25,24 +29,26 @@ pub struct BlockData {
     pub geth_traces: Vec<eth_types::GethExecTrace>,
     /// Circuits setup parameters
     pub circuits_params: CircuitsParams,
+    /// Root of the state trie of the accounts before the block
+    pub prev_state_root: Word,
+    /// EIP-1186 proofs of the accounts against `prev_state_root`
+    pub prestate_proofs: Vec<EIP1186ProofResponse>,
 }
 
 impl BlockData {
     /// Generate a new CircuitInputBuilder initialized with the context of the
     /// BlockData.
     pub fn new_circuit_input_builder(&self) -> CircuitInputBuilder {
-        CircuitInputBuilder::new(
-            self.sdb.clone(),
-            self.code_db.clone(),
-            Block::new(
-                self.chain_id,
-                self.history_hashes.clone(),
-                Word::default(),
-                &self.eth_block,
-                self.circuits_params,
-            )
-            .unwrap(),
+        let mut block = Block::new(
+            self.chain_id,
+            self.history_hashes.clone(),
+            self.prev_state_root,
+            &self.eth_block,
+            self.circuits_params,
         )
+        .unwrap();
+        block.prestate_proofs = self.prestate_proofs.clone();
+        CircuitInputBuilder::new(self.sdb.clone(), self.code_db.clone(), block)
     }
     /// Create a new block from the given Geth data.
     pub fn new_from_geth_data_with_params(
@@ -59,6 +65,7 @@ impl BlockData {
             sdb.set_account(addr, state_db::Account::zero());
         }
 
+        let (prev_state_root, prestate_proofs) = prestate_proofs(&geth_data.accounts);
         for account in geth_data.accounts {
             let code_hash = code_db.insert(account.code.to_vec());
             sdb.set_account(
@@ -80,6 +87,8 @@ impl BlockData {
             eth_block: geth_data.eth_block,
             geth_traces: geth_data.geth_traces,
             circuits_params,
+            prev_state_root,
+            prestate_proofs,
         }
     }
 
@@ -88,3 +97,55 @@ impl BlockData {
         Self::new_from_geth_data_with_params(geth_data, CircuitsParams::default())
     }
 }
+
+/// Return the root of the state trie of the accounts and their EIP-1186
+/// proofs, with all their storage slots, as returned by `eth_getProof`.
+fn prestate_proofs(accounts: &[Account]) -> (Word, Vec<EIP1186ProofResponse>) {
+    let no_nodes = Vec::<Vec<u8>>::new;
+    let mut state_trie = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+    let mut storage_tries = Vec::new();
+    for account in accounts {
+        let mut storage_trie = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        for (key, value) in account.storage.iter().filter(|(_, value)| !value.is_zero()) {
+            storage_trie
+                .insert(&key.to_be_bytes(), storage_value_rlp(*value))
+                .expect("storage slot inserted");
+        }
+        let trie_account = TrieAccount {
+            nonce: account.nonce,
+            balance: account.balance,
+            storage_root: storage_trie.root(),
+            code_hash: CodeDB::hash(&account.code),
+        };
+        state_trie
+            .insert(account.address.as_bytes(), trie_account.rlp_bytes())
+            .expect("account inserted");
+        storage_tries.push(storage_trie);
+    }
+    let proofs = accounts
+        .iter()
+        .zip(storage_tries.iter())
+        .map(|(account, storage_trie)| EIP1186ProofResponse {
+            address: account.address,
+            balance: account.balance,
+            code_hash: CodeDB::hash(&account.code),
+            nonce: account.nonce,
+            storage_hash: storage_trie.root(),
+            account_proof: state_trie
+                .prove(account.address.as_bytes())
+                .expect("account proof"),
+            storage_proof: account
+                .storage
+                .iter()
+                .map(|(key, value)| StorageProof {
+                    key: *key,
+                    value: *value,
+                    proof: storage_trie
+                        .prove(&key.to_be_bytes())
+                        .expect("storage proof"),
+                })
+                .collect(),
+        })
+        .collect();
+    (state_trie.root().to_word(), proofs)
+}
@@ -92,6 +92,8 @@ pub enum Target {
     Stack,
     /// Means the target of the operation is the Storage.
     Storage,
+    /// Means the target of the operation is the whole Storage of an account.
+    StorageClear,
     /// Means the target of the operation is the TxAccessListAccount.
     TxAccessListAccount,
     /// Means the target of the operation is the TxAccessListAccountStorage.
@@ -106,6 +108,8 @@ pub enum Target {
     TxReceipt,
     /// Means the target of the operation is the TxLog.
     TxLog,
+    /// Means the target of the operation is the TxDestructedAccount.
+    TxDestructedAccount,
 }
 
 /// Trait used for Operation Kinds.
@@ -373,6 +377,51 @@ impl Ord for StorageOp {
     }
 }
 
+/// Represents the clearing of the whole storage of an account, which is
+/// destructed after the `EndTx` step of a transaction.  It sets the storage
+/// root of the account to the empty root, so that the slots which aren't
+/// accessed in the block are cleared along with the accessed ones.
+#[derive(Clone, PartialEq, Eq)]
+pub struct StorageClearOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+}
+
+impl fmt::Debug for StorageClearOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("StorageClearOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}",
+            self.tx_id, self.address
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl PartialOrd for StorageClearOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for StorageClearOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address).cmp(&(&other.tx_id, &other.address))
+    }
+}
+
+impl Op for StorageClearOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::StorageClear(self)
+    }
+
+    fn reverse(&self) -> Self {
+        unreachable!("StorageClearOp can't be reverted")
+    }
+}
+
 /// Represents a change in the Account AccessList implied by a `BeginTx`,
 /// `EXTCODECOPY`, `EXTCODESIZE`, `EXTCODEHASH` `BALANCE`, `SELFDESTRUCT`,
 /// `*CALL`* or `CREATE*` step.
@@ -898,6 +947,56 @@ impl Op for TxReceiptOp {
     }
 }
 
+/// Represents a change in the set of accounts destructed by a `SELFDESTRUCT`
+/// in a transaction.  The account is destructed after the `EndTx` step.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TxDestructedAccountOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Represents whether the account is destructed at the end of the tx.
+    pub is_destructed: bool,
+    /// Represents whether the account was destructed at the end of the tx
+    /// before this operation.
+    pub is_destructed_prev: bool,
+}
+
+impl fmt::Debug for TxDestructedAccountOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TxDestructedAccountOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, is_destructed_prev: {:?}, is_destructed: {:?}",
+            self.tx_id, self.address, self.is_destructed_prev, self.is_destructed
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl PartialOrd for TxDestructedAccountOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TxDestructedAccountOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address).cmp(&(&other.tx_id, &other.address))
+    }
+}
+
+impl Op for TxDestructedAccountOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TxDestructedAccount(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.is_destructed, &mut rev.is_destructed_prev);
+        rev
+    }
+}
+
 /// Generic enum that wraps over all the operation types possible.
 /// In particular [`StackOp`], [`MemoryOp`] and [`StorageOp`].
 #[derive(Debug, Clone)]
@@ -908,6 +1007,8 @@ pub enum OpEnum {
     Memory(MemoryOp),
     /// Storage
     Storage(StorageOp),
+    /// StorageClear
+    StorageClear(StorageClearOp),
     /// TxAccessListAccount
     TxAccessListAccount(TxAccessListAccountOp),
     /// TxAccessListAccountStorage
@@ -922,6 +1023,8 @@ pub enum OpEnum {
     TxReceipt(TxReceiptOp),
     /// TxLog
     TxLog(TxLogOp),
+    /// TxDestructedAccount
+    TxDestructedAccount(TxDestructedAccountOp),
     /// Start
     Start(StartOp),
 }
@@ -1,7 +1,26 @@
 //! precompile helpers
 
-use eth_types::Address;
+use eth_types::{
+    evm_types::GasCost,
+    sign_types::{sign_data, SignData, SECP256K1_Q},
+    Address, ToAddress, Word,
+};
 use revm_precompile::{Precompile, Precompiles};
+use strum_macros::EnumIter;
+
+/// Maximum byte size of the base, exponent and modulus of the modexp calls
+/// supported by the ModExp Circuit.
+pub const MODEXP_SIZE_LIMIT: usize = 32;
+
+/// Length of the modexp input read by the circuits: the 32 bytes sizes of the
+/// base, exponent and modulus followed by their bytes, up to
+/// [`MODEXP_SIZE_LIMIT`] each.
+pub const MODEXP_INPUT_LIMIT: usize = 3 * 32 + 3 * MODEXP_SIZE_LIMIT;
+
+/// Length of the input of the successful blake2f calls: the 4 bytes number of
+/// rounds, the 64 bytes state, the 128 bytes message, the 16 bytes offset
+/// counter and the 1 byte final block flag.
+pub const BLAKE2F_INPUT_LEN: usize = 213;
 
 /// Check if address is a precompiled or not.
 pub fn is_precompiled(address: &Address) -> bool {
@@ -21,3 +40,200 @@ pub(crate) fn execute_precompiled(address: &Address, input: &[u8], gas: u64) ->
         Err(_) => (vec![], gas),
     }
 }
+
+/// Execute the precompile at `address` on `input` without gas limit, returning
+/// its output and gas cost, or `None` when it fails on an invalid input.
+pub(crate) fn execute_precompiled_unlimited(
+    address: &Address,
+    input: &[u8],
+) -> Option<(Vec<u8>, u64)> {
+    let Some(Precompile::Standard(precompile_fn)) = Precompiles::berlin()
+        .get(address.as_fixed_bytes())  else {
+        panic!("calling non-exist precompiled contract address")
+    };
+
+    precompile_fn(input, u64::MAX)
+        .ok()
+        .map(|(gas_cost, return_value)| (return_value, gas_cost))
+}
+
+/// Return the [`SignData`] of the signature in the input of an ecrecover call,
+/// or `None` when the signature is invalid, in which case the call returns an
+/// empty output.  The input is expected to be already right padded to
+/// [`PrecompileCalls::input_len`].
+pub fn ecrecover_sign_data(input: &[u8]) -> Option<SignData> {
+    let msg_hash: [u8; 32] = input[..32].try_into().unwrap();
+    let v = Word::from_big_endian(&input[32..64]);
+    let r = Word::from_big_endian(&input[64..96]);
+    let s = Word::from_big_endian(&input[96..128]);
+    if v != Word::from(27) && v != Word::from(28) {
+        return None;
+    }
+    sign_data(v.as_u64() as u8 - 27, &r, &s, &msg_hash).ok()
+}
+
+/// Return whether the ecrecover call on `input` can be proved by the
+/// EcRecover Circuit.  A valid signature is always supported, its message hash
+/// being reduced modulo the secp256k1 curve order `n` by the circuit.  An
+/// invalid signature is supported when `v` is neither 27 nor 28, or `r` or `s`
+/// is zero or not lower than `n`, which are the invalidity conditions proved
+/// by the circuit.  This leaves out the signatures whose `r` isn't the x
+/// coordinate of a curve point.
+pub fn ecrecover_is_supported(input: &[u8]) -> bool {
+    let mut input = input.to_vec();
+    input.resize(128, 0);
+    let v = Word::from_big_endian(&input[32..64]);
+    let r = Word::from_big_endian(&input[64..96]);
+    let s = Word::from_big_endian(&input[96..128]);
+    let order = Word::from_little_endian(&SECP256K1_Q.to_bytes_le());
+    if (v != Word::from(27) && v != Word::from(28))
+        || r.is_zero()
+        || s.is_zero()
+        || r >= order
+        || s >= order
+    {
+        return true;
+    }
+    ecrecover_sign_data(&input).is_some()
+}
+
+/// Addresses of the precompiled contracts available since Berlin.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, EnumIter)]
+pub enum PrecompileCalls {
+    /// Elliptic Curve Recovery
+    ECRecover = 0x01,
+    /// SHA2-256 hash function
+    Sha256 = 0x02,
+    /// Ripemd-160 hash function
+    Ripemd160 = 0x03,
+    /// Identity function
+    Identity = 0x04,
+    /// Modular exponentiation
+    Modexp = 0x05,
+    /// Point addition
+    Bn128Add = 0x06,
+    /// Scalar multiplication
+    Bn128Mul = 0x07,
+    /// Bilinear function
+    Bn128Pairing = 0x08,
+    /// Compression function
+    Blake2F = 0x09,
+}
+
+impl From<PrecompileCalls> for Address {
+    fn from(value: PrecompileCalls) -> Self {
+        Word::from(value as u64).to_address()
+    }
+}
+
+impl From<PrecompileCalls> for u64 {
+    fn from(value: PrecompileCalls) -> Self {
+        value as u64
+    }
+}
+
+impl From<u8> for PrecompileCalls {
+    fn from(value: u8) -> Self {
+        match value {
+            0x01 => Self::ECRecover,
+            0x02 => Self::Sha256,
+            0x03 => Self::Ripemd160,
+            0x04 => Self::Identity,
+            0x05 => Self::Modexp,
+            0x06 => Self::Bn128Add,
+            0x07 => Self::Bn128Mul,
+            0x08 => Self::Bn128Pairing,
+            0x09 => Self::Blake2F,
+            _ => unreachable!("precompile contracts only from 0x01 to 0x09"),
+        }
+    }
+}
+
+impl From<Address> for PrecompileCalls {
+    fn from(address: Address) -> Self {
+        debug_assert!(is_precompiled(&address));
+        Self::from(address.0[19])
+    }
+}
+
+impl PrecompileCalls {
+    /// Length of the input read by the precompiles with a fixed size input,
+    /// which is right padded with zeros or truncated to it.  The modexp input
+    /// is truncated to [`MODEXP_INPUT_LIMIT`], as the bytes beyond the base,
+    /// exponent and modulus are ignored.
+    pub fn input_len(&self) -> Option<usize> {
+        match self {
+            Self::ECRecover | Self::Bn128Add => Some(128),
+            Self::Bn128Mul => Some(96),
+            Self::Modexp => Some(MODEXP_INPUT_LIMIT),
+            _ => None,
+        }
+    }
+
+    /// Return whether the call on an input of `input_length` bytes with `gas`
+    /// left is verified by the precompile circuit, which holds the output and
+    /// the failure flag of the precompile on the input given enough gas.  The
+    /// identity calls are proved by memory copies, and the calls failing for
+    /// lack of gas don't need the circuit unless their gas cost depends on the
+    /// input bytes.  The blake2f calls on an input of the wrong length and the
+    /// ecPairing calls always fail, consuming all the gas.
+    pub fn needs_circuit(&self, input_length: u64, gas: u64) -> bool {
+        match self {
+            Self::Identity | Self::Bn128Pairing => false,
+            Self::Blake2F => input_length == BLAKE2F_INPUT_LEN as u64,
+            _ => self
+                .gas_cost(input_length)
+                .map_or(true, |gas_cost| gas_cost <= gas),
+        }
+    }
+
+    /// Return whether the call on `input` can be proved by the circuits, when
+    /// it's successful or needs the precompile circuit (see
+    /// [`Self::needs_circuit`]).  See [`ecrecover_is_supported`] for the
+    /// ecrecover calls.  The pairing check isn't verified by any circuit, so
+    /// no successful ecPairing call is supported.  The modexp sizes are
+    /// supported up to [`MODEXP_SIZE_LIMIT`].
+    pub fn is_supported(&self, input: &[u8]) -> bool {
+        match self {
+            Self::ECRecover => ecrecover_is_supported(input),
+            Self::Bn128Pairing => false,
+            Self::Modexp => {
+                let mut input = input.to_vec();
+                input.resize(3 * 32, 0);
+                input
+                    .chunks(32)
+                    .all(|size| Word::from_big_endian(size) <= Word::from(MODEXP_SIZE_LIMIT))
+            }
+            _ => true,
+        }
+    }
+
+    /// Gas cost of the precompile call for an input of `input_length` bytes,
+    /// for the precompiles whose cost only depends on the input length.
+    /// Returns `None` for `Modexp` and `Blake2F`, whose cost depends on the
+    /// input content.
+    pub fn gas_cost(&self, input_length: u64) -> Option<u64> {
+        let words = (input_length + 31) / 32;
+        match self {
+            Self::ECRecover => Some(GasCost::PRECOMPILE_ECRECOVER_BASE.0),
+            Self::Sha256 => Some(
+                GasCost::PRECOMPILE_SHA256_BASE.0 + GasCost::PRECOMPILE_SHA256_PER_WORD.0 * words,
+            ),
+            Self::Ripemd160 => Some(
+                GasCost::PRECOMPILE_RIPEMD160_BASE.0
+                    + GasCost::PRECOMPILE_RIPEMD160_PER_WORD.0 * words,
+            ),
+            Self::Identity => Some(
+                GasCost::PRECOMPILE_IDENTITY_BASE.0
+                    + GasCost::PRECOMPILE_IDENTITY_PER_WORD.0 * words,
+            ),
+            Self::Bn128Add => Some(GasCost::PRECOMPILE_BN256ADD.0),
+            Self::Bn128Mul => Some(GasCost::PRECOMPILE_BN256MUL.0),
+            Self::Bn128Pairing => Some(
+                GasCost::PRECOMPILE_BN256PAIRING.0
+                    + GasCost::PRECOMPILE_BN256PAIRING_PER_PAIR.0 * (input_length / 192),
+            ),
+            Self::Modexp | Self::Blake2F => None,
+        }
+    }
+}
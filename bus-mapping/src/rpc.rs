@@ -166,6 +166,16 @@ impl<P: JsonRpcClient> GethClient<P> {
             .map_err(|e| Error::JSONRpcError(e.into()))
     }
 
+    /// Calls `debug_dbGet` via JSON-RPC returning the RLP encoded trie node
+    /// with the given hash, which is its key in the database of geth.
+    pub async fn get_trie_node(&self, hash: Hash) -> Result<Bytes, Error> {
+        let hash = serialize(&hash);
+        self.0
+            .request("debug_dbGet", [hash])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))
+    }
+
     /// Calls `miner_stop` via JSON-RPC, which makes the node stop mining
     /// blocks.  Useful for integration tests.
     pub async fn miner_stop(&self) -> Result<(), Error> {
@@ -1,7 +1,11 @@
 //! Implementation of an in-memory key-value database to represent the
 //! Ethereum State Trie.
 
-use eth_types::{Address, Hash, Word, H256, U256};
+use crate::Error;
+use eth_types::{
+    mpt::{storage_value_from_rlp, PartialTrie, TrieAccount, EMPTY_TRIE_ROOT},
+    Address, EIP1186ProofResponse, Hash, ToBigEndian, Word, H256, U256,
+};
 use ethers_core::utils::keccak256;
 use lazy_static::lazy_static;
 use std::collections::{HashMap, HashSet};
@@ -97,10 +101,13 @@ pub struct StateDB {
     // state before current transaction, to calculate gas cost for some opcodes like sstore.
     // So both dirty storage and committed storage are needed.
     dirty_storage: HashMap<(Address, Word), Word>,
-    // Accounts that have been through `SELFDESTRUCT` under the situation that `is_persistent` is
-    // `true`. These accounts will be reset once `commit_tx` is called.
+    // Accounts that have been through `SELFDESTRUCT` in the current transaction, which is
+    // unset when the call is reverted. These accounts will be reset once `commit_tx` is called.
     destructed_account: HashSet<Address>,
     refund: u64,
+    // EIP-1186 proofs of the prestate, verified against the state root of the
+    // parent block.
+    proofs: Vec<EIP1186ProofResponse>,
 }
 
 impl StateDB {
@@ -109,6 +116,37 @@ impl StateDB {
         Self::default()
     }
 
+    /// Create a Self with the accounts and storage of the EIP-1186 proofs,
+    /// after verifying them against `state_root`.  The proofs are kept to
+    /// build the MPT witness.
+    pub fn from_proofs(state_root: H256, proofs: Vec<EIP1186ProofResponse>) -> Result<Self, Error> {
+        let mut sdb = Self::new();
+        for proof in proofs.iter() {
+            verify_proof(state_root, proof)?;
+            sdb.set_account(
+                &proof.address,
+                Account {
+                    nonce: proof.nonce,
+                    balance: proof.balance,
+                    storage: proof
+                        .storage_proof
+                        .iter()
+                        .map(|storage_proof| (storage_proof.key, storage_proof.value))
+                        .collect(),
+                    code_hash: proof.code_hash,
+                },
+            );
+        }
+        sdb.proofs = proofs;
+        Ok(sdb)
+    }
+
+    /// EIP-1186 proofs of the prestate, which are only kept when Self is built
+    /// with [`StateDB::from_proofs`].
+    pub fn proofs(&self) -> &[EIP1186ProofResponse] {
+        &self.proofs
+    }
+
     /// Set an [`Account`] at `addr` in the StateDB.
     pub fn set_account(&mut self, addr: &Address, acc: Account) {
         self.state.insert(*addr, acc);
@@ -232,11 +270,29 @@ impl StateDB {
         debug_assert!(exist);
     }
 
+    /// Check whether `addr` is set as self destructed.
+    pub fn is_destructed(&self, addr: &Address) -> bool {
+        self.destructed_account.contains(addr)
+    }
+
+    /// Return the accounts set as self destructed, sorted by address.
+    pub fn destructed_accounts(&self) -> Vec<Address> {
+        let mut addrs: Vec<_> = self.destructed_account.iter().copied().collect();
+        addrs.sort();
+        addrs
+    }
+
     /// Set account as self destructed.
     pub fn destruct_account(&mut self, addr: Address) {
         self.destructed_account.insert(addr);
     }
 
+    /// Unset account as self destructed.
+    pub fn undestruct_account(&mut self, addr: &Address) {
+        let exist = self.destructed_account.remove(addr);
+        debug_assert!(exist);
+    }
+
     /// Retrieve refund.
     pub fn refund(&self) -> u64 {
         self.refund
@@ -247,7 +303,8 @@ impl StateDB {
         self.refund = value;
     }
 
-    /// Clear access list and refund, and commit dirty storage.
+    /// Clear access list and refund, commit dirty storage and reset destructed
+    /// accounts.
     /// It should be invoked before processing
     /// with new transaction with the same [`StateDB`].
     pub fn commit_tx(&mut self) {
@@ -262,10 +319,62 @@ impl StateDB {
             let (_, account) = self.get_account_mut(&addr);
             *account = ACCOUNT_ZERO.clone();
         }
+        self.destructed_account = HashSet::new();
         self.refund = 0;
     }
 }
 
+// Verify that the account and its storage slots in the proof are in the state
+// trie with root `state_root`.
+fn verify_proof(state_root: H256, proof: &EIP1186ProofResponse) -> Result<(), Error> {
+    let account = PartialTrie::new(state_root, &proof.account_proof)
+        .get(proof.address.as_bytes())?
+        .map(|bytes| TrieAccount::from_rlp(&bytes))
+        .transpose()?;
+    let storage_root = match account {
+        Some(account) => {
+            if (
+                account.nonce,
+                account.balance,
+                account.code_hash,
+                account.storage_root,
+            ) != (
+                proof.nonce,
+                proof.balance,
+                proof.code_hash,
+                proof.storage_hash,
+            ) {
+                return Err(Error::InvalidProof(proof.address));
+            }
+            account.storage_root
+        }
+        // geth reports a code hash and a storage hash of 0 for non-existing
+        // accounts.
+        None => {
+            if !(proof.nonce.is_zero()
+                && proof.balance.is_zero()
+                && (proof.code_hash.is_zero() || proof.code_hash == *EMPTY_CODE_HASH)
+                && (proof.storage_hash.is_zero() || proof.storage_hash == *EMPTY_TRIE_ROOT))
+            {
+                return Err(Error::InvalidProof(proof.address));
+            }
+            *EMPTY_TRIE_ROOT
+        }
+    };
+
+    for storage_proof in proof.storage_proof.iter() {
+        let value = PartialTrie::new(storage_root, &storage_proof.proof)
+            .get(&storage_proof.key.to_be_bytes())?
+            .map(|bytes| storage_value_from_rlp(&bytes))
+            .transpose()?
+            .unwrap_or_default();
+        if value != storage_proof.value {
+            return Err(Error::InvalidProof(proof.address));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod statedb_tests {
     use super::*;
@@ -325,4 +434,226 @@ mod statedb_tests {
         assert!(found);
         assert_eq!(value, &Word::from(102));
     }
+
+    // Build the proofs of the accounts `addr_a`, with a storage slot, and
+    // `addr_c`, which doesn't exist, against a state trie with `addr_a` and
+    // `addr_b`.
+    fn prestate_proofs() -> (H256, Vec<EIP1186ProofResponse>) {
+        let addr_a = address!("0x0000000000000000000000000000000000000001");
+        let addr_b = address!("0x0000000000000000000000000000000000000002");
+        let addr_c = address!("0x0000000000000000000000000000000000000003");
+
+        let mut storage_trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        for (key, value) in [(1, 10), (2, 20)] {
+            storage_trie
+                .insert(
+                    &Word::from(key).to_be_bytes(),
+                    eth_types::mpt::storage_value_rlp(Word::from(value)),
+                )
+                .unwrap();
+        }
+        let account_a = TrieAccount {
+            nonce: Word::from(1),
+            balance: Word::from(100),
+            storage_root: storage_trie.root(),
+            code_hash: CodeDB::hash(&[0x00]),
+        };
+        let account_b = TrieAccount {
+            balance: Word::from(200),
+            ..TrieAccount::default()
+        };
+
+        let mut state_trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        state_trie
+            .insert(addr_a.as_bytes(), account_a.rlp_bytes())
+            .unwrap();
+        state_trie
+            .insert(addr_b.as_bytes(), account_b.rlp_bytes())
+            .unwrap();
+
+        let proof_a = EIP1186ProofResponse {
+            address: addr_a,
+            balance: account_a.balance,
+            code_hash: account_a.code_hash,
+            nonce: account_a.nonce,
+            storage_hash: account_a.storage_root,
+            account_proof: state_trie.prove(addr_a.as_bytes()).unwrap(),
+            storage_proof: [1, 3]
+                .into_iter()
+                .map(|key| {
+                    let key = Word::from(key);
+                    eth_types::StorageProof {
+                        key,
+                        value: if key == Word::from(1) {
+                            Word::from(10)
+                        } else {
+                            Word::zero()
+                        },
+                        proof: storage_trie.prove(&key.to_be_bytes()).unwrap(),
+                    }
+                })
+                .collect(),
+        };
+        let proof_c = EIP1186ProofResponse {
+            address: addr_c,
+            account_proof: state_trie.prove(addr_c.as_bytes()).unwrap(),
+            ..EIP1186ProofResponse::default()
+        };
+
+        (state_trie.root(), vec![proof_a, proof_c])
+    }
+
+    #[test]
+    fn statedb_from_proofs() {
+        let (state_root, proofs) = prestate_proofs();
+        let statedb = StateDB::from_proofs(state_root, proofs.clone()).unwrap();
+        assert_eq!(statedb.proofs(), proofs.as_slice());
+
+        let (found, acc) = statedb.get_account(&proofs[0].address);
+        assert!(found);
+        assert_eq!(acc.nonce, Word::from(1));
+        assert_eq!(acc.balance, Word::from(100));
+        let (found, value) = statedb.get_storage(&proofs[0].address, &Word::from(1));
+        assert!(found);
+        assert_eq!(value, &Word::from(10));
+        let (found, value) = statedb.get_storage(&proofs[0].address, &Word::from(3));
+        assert!(found);
+        assert_eq!(value, &Word::zero());
+
+        let (found, acc) = statedb.get_account(&proofs[1].address);
+        assert!(found);
+        assert!(acc.balance.is_zero());
+    }
+
+    #[test]
+    fn statedb_from_proofs_bad_account() {
+        let (state_root, mut proofs) = prestate_proofs();
+        proofs[0].balance = Word::from(101);
+        assert!(matches!(
+            StateDB::from_proofs(state_root, proofs.clone()),
+            Err(Error::InvalidProof(address)) if address == proofs[0].address
+        ));
+    }
+
+    #[test]
+    fn statedb_from_proofs_bad_non_existing_account() {
+        let (state_root, mut proofs) = prestate_proofs();
+        proofs[1].nonce = Word::from(1);
+        assert!(matches!(
+            StateDB::from_proofs(state_root, proofs.clone()),
+            Err(Error::InvalidProof(address)) if address == proofs[1].address
+        ));
+    }
+
+    #[test]
+    fn statedb_from_proofs_bad_storage() {
+        let (state_root, mut proofs) = prestate_proofs();
+        proofs[0].storage_proof[1].value = Word::from(30);
+        assert!(matches!(
+            StateDB::from_proofs(state_root, proofs.clone()),
+            Err(Error::InvalidProof(address)) if address == proofs[0].address
+        ));
+    }
+
+    #[test]
+    fn statedb_from_proofs_bad_root() {
+        let (_, proofs) = prestate_proofs();
+        assert!(matches!(
+            StateDB::from_proofs(H256::repeat_byte(0xff), proofs),
+            Err(Error::EthTypeError(eth_types::Error::MissingTrieNode(_)))
+        ));
+    }
+
+    /// The state root of a parent block, the code of a contract and the
+    /// `eth_getProof` results, in the format returned by geth, of the contract
+    /// with two of its slots and a missing one, of an account and of a
+    /// non-existing account.  The tries of the fixture are built with an
+    /// implementation independent of [`PartialTrie`].
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct GetProofFixture {
+        prev_state_root: Word,
+        code: eth_types::Bytes,
+        proofs: Vec<EIP1186ProofResponse>,
+    }
+
+    fn get_proof_fixture() -> GetProofFixture {
+        serde_json::from_str(include_str!("../fixtures/eth_get_proof.json")).unwrap()
+    }
+
+    fn build_fixture_state_code_db(fixture: GetProofFixture) -> Result<(StateDB, CodeDB), Error> {
+        let codes = HashMap::from([(fixture.proofs[0].address, fixture.code.to_vec())]);
+        crate::circuit_input_builder::build_verified_state_code_db(
+            fixture.prev_state_root,
+            fixture.proofs,
+            codes,
+        )
+    }
+
+    #[test]
+    fn verified_state_code_db_from_get_proof() {
+        let fixture = get_proof_fixture();
+        let code_hash = CodeDB::hash(&fixture.code);
+        let proofs = fixture.proofs.clone();
+        let (statedb, code_db) = build_fixture_state_code_db(fixture).unwrap();
+        assert_eq!(statedb.proofs(), proofs.as_slice());
+        assert!(code_db.0.contains_key(&code_hash));
+
+        let contract = proofs[0].address;
+        let (found, acc) = statedb.get_account(&contract);
+        assert!(found);
+        assert_eq!(acc.nonce, Word::from(1));
+        assert_eq!(acc.code_hash, code_hash);
+        for (key, value) in [
+            (Word::from(3), Word::from(0x4003)),
+            (
+                Word::from(24),
+                Word::from_big_endian(&keccak256(b"large value")),
+            ),
+            (Word::from(100), Word::zero()),
+        ] {
+            let (found, slot) = statedb.get_storage(&contract, &key);
+            assert!(found);
+            assert_eq!(slot, &value);
+        }
+
+        let (found, acc) = statedb.get_account(&proofs[1].address);
+        assert!(found);
+        assert_eq!(acc.nonce, Word::from(2));
+        assert_eq!(acc.balance, Word::exp10(18) * 7 + 49);
+        assert_eq!(acc.code_hash, CodeDB::empty_code_hash());
+
+        let (found, acc) = statedb.get_account(&proofs[2].address);
+        assert!(found);
+        assert!(acc.nonce.is_zero() && acc.balance.is_zero());
+    }
+
+    #[test]
+    fn verified_state_code_db_from_bad_get_proof() {
+        let mut fixture = get_proof_fixture();
+        fixture.proofs[1].balance += Word::one();
+        let address = fixture.proofs[1].address;
+        assert!(matches!(
+            build_fixture_state_code_db(fixture),
+            Err(Error::InvalidProof(bad_address)) if bad_address == address
+        ));
+
+        let mut fixture = get_proof_fixture();
+        fixture.proofs[0].storage_proof[1].value = Word::from(0x4003);
+        let address = fixture.proofs[0].address;
+        assert!(matches!(
+            build_fixture_state_code_db(fixture),
+            Err(Error::InvalidProof(bad_address)) if bad_address == address
+        ));
+
+        // A node that doesn't match the hash in its parent isn't found.
+        let mut fixture = get_proof_fixture();
+        let mut node = fixture.proofs[2].account_proof[1].to_vec();
+        *node.last_mut().unwrap() ^= 1;
+        fixture.proofs[2].account_proof[1] = node.into();
+        assert!(matches!(
+            build_fixture_state_code_db(fixture),
+            Err(Error::EthTypeError(eth_types::Error::MissingTrieNode(_)))
+        ));
+    }
 }
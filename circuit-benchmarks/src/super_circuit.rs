@@ -90,6 +90,15 @@ mod tests {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_ecrecover: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_modexp: 0,
+            max_blake2f: 0,
+            max_blake2f_rounds: 0,
+            max_mpt_rows: 0,
         };
         let (_, circuit, instance, _) =
             SuperCircuit::<_, MAX_TXS, MAX_CALLDATA, 0x100>::build(block, circuits_params).unwrap();
@@ -1,6 +1,8 @@
 //! Error module for the eth-types crate
 
+use crate::H256;
 use core::fmt::{Display, Formatter, Result as FmtResult};
+use ethers_core::utils::rlp::DecoderError;
 use std::error::Error as StdError;
 
 /// Error type for any BusMapping related failure.
@@ -33,6 +35,12 @@ pub enum Error {
     WordToMemAddr,
     /// Signature parsing error.
     Signature(libsecp256k1::Error),
+    /// A node of a partial Merkle Patricia Trie is unknown.
+    MissingTrieNode(H256),
+    /// A node of a Merkle Patricia Trie is malformed.
+    InvalidTrieNode,
+    /// RLP decoding error.
+    RlpDecoding(DecoderError),
 }
 
 impl From<libsecp256k1::Error> for Error {
@@ -41,6 +49,12 @@ impl From<libsecp256k1::Error> for Error {
     }
 }
 
+impl From<DecoderError> for Error {
+    fn from(err: DecoderError) -> Self {
+        Error::RlpDecoding(err)
+    }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         write!(f, "{:?}", self)
@@ -64,6 +64,12 @@ impl fmt::Debug for Gas {
 pub const MAX_REFUND_QUOTIENT_OF_GAS_USED: usize = 5;
 /// Gas stipend when CALL or CALLCODE is attached with value.
 pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
+/// Maximum size of the deployed contract code in bytes, introduced in
+/// [EIP-170](https://eips.ethereum.org/EIPS/eip-170).
+pub const MAX_CODE_SIZE: u64 = 0x6000;
+/// First byte of the contract code which is rejected at deployment, introduced
+/// in [EIP-3541](https://eips.ethereum.org/EIPS/eip-3541).
+pub const INVALID_INIT_CODE_FIRST_BYTE: u8 = 0xef;
 
 /// Defines the gas consumption.
 #[derive(Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -122,6 +128,10 @@ impl GasCost {
     pub const TX: Self = Self(21000);
     /// Constant cost for a creation transaction
     pub const CREATION_TX: Self = Self(53000);
+    /// Constant cost for each address in the access list of a transaction
+    pub const ACCESS_LIST_ADDRESS: Self = Self(2400);
+    /// Constant cost for each storage key in the access list of a transaction
+    pub const ACCESS_LIST_STORAGE: Self = Self(1900);
     /// Constant cost for calling with non-zero value
     pub const CALL_WITH_VALUE: Self = Self(9000);
     /// Constant cost for turning empty account into non-empty account
@@ -137,6 +147,36 @@ impl GasCost {
     /// Times ceil exponent byte size for the EXP instruction, EIP-158 changed
     /// it from 10 to 50.
     pub const EXP_BYTE_TIMES: Self = Self(50);
+    /// Constant cost for the ecrecover precompile
+    pub const PRECOMPILE_ECRECOVER_BASE: Self = Self(3000);
+    /// Base cost for the SHA2-256 precompile
+    pub const PRECOMPILE_SHA256_BASE: Self = Self(60);
+    /// Cost per input word for the SHA2-256 precompile
+    pub const PRECOMPILE_SHA256_PER_WORD: Self = Self(12);
+    /// Base cost for the RIPEMD-160 precompile
+    pub const PRECOMPILE_RIPEMD160_BASE: Self = Self(600);
+    /// Cost per input word for the RIPEMD-160 precompile
+    pub const PRECOMPILE_RIPEMD160_PER_WORD: Self = Self(120);
+    /// Base cost for the identity precompile
+    pub const PRECOMPILE_IDENTITY_BASE: Self = Self(15);
+    /// Cost per input word for the identity precompile
+    pub const PRECOMPILE_IDENTITY_PER_WORD: Self = Self(3);
+    /// Constant cost for the bn256 point addition precompile, EIP-1108
+    /// changed it from 500 to 150.
+    pub const PRECOMPILE_BN256ADD: Self = Self(150);
+    /// Constant cost for the bn256 scalar multiplication precompile, EIP-1108
+    /// changed it from 40000 to 6000.
+    pub const PRECOMPILE_BN256MUL: Self = Self(6000);
+    /// Base cost for the bn256 pairing precompile
+    pub const PRECOMPILE_BN256PAIRING: Self = Self(45000);
+    /// Cost per pair for the bn256 pairing precompile
+    pub const PRECOMPILE_BN256PAIRING_PER_PAIR: Self = Self(34000);
+    /// Minimum cost for the modexp precompile, EIP-2565 changed it from 0 to
+    /// 200.
+    pub const PRECOMPILE_MODEXP_MIN: Self = Self(200);
+    /// Denominator of the modexp precompile gas cost, EIP-2565 changed it
+    /// from 20 to 3.
+    pub const PRECOMPILE_MODEXP_QUAD_DENOMINATOR: Self = Self(3);
 }
 
 impl GasCost {
@@ -47,3 +47,21 @@ pub fn eip150_gas(gas_left: u64, gas_specified: Word) -> u64 {
 
     capped_gas
 }
+
+/// Calculate EIP 2565 gas cost of a modexp precompile call, from the byte
+/// sizes of the base, exponent and modulus, and the first 32 bytes of the
+/// exponent.
+pub fn modexp_gas_cost(base_size: u64, exp_size: u64, mod_size: u64, exp_head: Word) -> u64 {
+    let words = base_size.max(mod_size).saturating_add(7) / 8;
+    let multiplication_complexity = words.saturating_mul(words);
+    let exp_head_bits = (exp_head.bits() as u64).saturating_sub(1);
+    let iteration_count = if exp_size <= 32 {
+        exp_head_bits
+    } else {
+        (exp_size - 32).saturating_mul(8).saturating_add(exp_head_bits)
+    };
+
+    (multiplication_complexity.saturating_mul(iteration_count.max(1))
+        / GasCost::PRECOMPILE_MODEXP_QUAD_DENOMINATOR.0)
+        .max(GasCost::PRECOMPILE_MODEXP_MIN.0)
+}
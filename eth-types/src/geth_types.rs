@@ -1,15 +1,14 @@
 //! Types needed for generating Ethereum traces
 
 use crate::{
-    sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
-    AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, ToLittleEndian,
-    Word, U64,
+    sign_types::{self, SignData},
+    AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, Word, U64,
+};
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip2930::Eip2930TransactionRequest},
+    Eip1559TransactionRequest, NameOrAddress, TransactionRequest,
 };
-use ethers_core::types::{NameOrAddress, TransactionRequest};
 use ethers_signers::{LocalWallet, Signer};
-use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1};
-use num::Integer;
-use num_bigint::BigUint;
 use serde::{Serialize, Serializer};
 use serde_with::serde_as;
 use sha3::{Digest, Keccak256};
@@ -107,9 +106,19 @@ impl BlockConstants {
     }
 }
 
+/// Type of the access list transactions introduced in
+/// [EIP-2930](https://eips.ethereum.org/EIPS/eip-2930).
+pub const ACCESS_LIST_TX_TYPE: u64 = 1;
+
+/// Type of the dynamic fee transactions introduced in
+/// [EIP-1559](https://eips.ethereum.org/EIPS/eip-1559).
+pub const DYNAMIC_FEE_TX_TYPE: u64 = 2;
+
 /// Definition of all of the constants related to an Ethereum transaction.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
+    /// Transaction type, which is 0 for legacy transactions
+    pub transaction_type: u64,
     /// Sender address
     pub from: Address,
     /// Recipient address (None for contract creation)
@@ -120,11 +129,12 @@ pub struct Transaction {
     pub gas_limit: Word,
     /// Transfered value
     pub value: Word,
-    /// Gas Price
+    /// Gas Price, which is the effective gas price for dynamic fee
+    /// transactions
     pub gas_price: Word,
-    /// Gas fee cap
+    /// Gas fee cap (max fee per gas)
     pub gas_fee_cap: Word,
-    /// Gas tip cap
+    /// Gas tip cap (max priority fee per gas)
     pub gas_tip_cap: Word,
     /// The compiled code of a contract OR the first 4 bytes of the hash of the
     /// invoked method signature and encoded parameters. For details see
@@ -150,10 +160,11 @@ impl From<&Transaction> for crate::Transaction {
             gas: tx.gas_limit,
             value: tx.value,
             gas_price: Some(tx.gas_price),
-            max_priority_fee_per_gas: Some(tx.gas_fee_cap),
-            max_fee_per_gas: Some(tx.gas_tip_cap),
+            max_priority_fee_per_gas: Some(tx.gas_tip_cap),
+            max_fee_per_gas: Some(tx.gas_fee_cap),
             input: tx.call_data.clone(),
             access_list: tx.access_list.clone(),
+            transaction_type: Some(tx.transaction_type.into()),
             v: tx.v.into(),
             r: tx.r,
             s: tx.s,
@@ -164,15 +175,27 @@ impl From<&Transaction> for crate::Transaction {
 
 impl From<&crate::Transaction> for Transaction {
     fn from(tx: &crate::Transaction) -> Transaction {
+        let gas_price = tx.gas_price.unwrap_or_default();
+        // Transactions before EIP-1559 pay the gas price as both fee caps.
+        let is_dynamic_fee_tx = tx.transaction_type == Some(DYNAMIC_FEE_TX_TYPE.into());
+        let (gas_fee_cap, gas_tip_cap) = if is_dynamic_fee_tx {
+            (
+                tx.max_fee_per_gas.unwrap_or_default(),
+                tx.max_priority_fee_per_gas.unwrap_or_default(),
+            )
+        } else {
+            (gas_price, gas_price)
+        };
         Transaction {
+            transaction_type: tx.transaction_type.unwrap_or_default().as_u64(),
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce,
             gas_limit: tx.gas,
             value: tx.value,
-            gas_price: tx.gas_price.unwrap_or_default(),
-            gas_fee_cap: tx.max_priority_fee_per_gas.unwrap_or_default(),
-            gas_tip_cap: tx.max_fee_per_gas.unwrap_or_default(),
+            gas_price,
+            gas_fee_cap,
+            gas_tip_cap,
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
             v: tx.v.as_u64(),
@@ -197,45 +220,65 @@ impl From<&Transaction> for TransactionRequest {
     }
 }
 
+impl From<&Transaction> for TypedTransaction {
+    fn from(tx: &Transaction) -> TypedTransaction {
+        match tx.transaction_type {
+            ACCESS_LIST_TX_TYPE => Eip2930TransactionRequest::new(
+                tx.into(),
+                tx.access_list.clone().unwrap_or_default(),
+            )
+            .into(),
+            DYNAMIC_FEE_TX_TYPE => Eip1559TransactionRequest {
+                from: Some(tx.from),
+                to: tx.to.map(NameOrAddress::Address),
+                gas: Some(tx.gas_limit),
+                value: Some(tx.value),
+                data: Some(tx.call_data.clone()),
+                nonce: Some(tx.nonce),
+                access_list: tx.access_list.clone().unwrap_or_default(),
+                max_priority_fee_per_gas: Some(tx.gas_tip_cap),
+                max_fee_per_gas: Some(tx.gas_fee_cap),
+                ..Default::default()
+            }
+            .into(),
+            _ => TransactionRequest::from(tx).into(),
+        }
+    }
+}
+
 impl Transaction {
     /// Return the SignData associated with this Transaction.
     pub fn sign_data(&self, chain_id: u64) -> Result<SignData, Error> {
-        let sig_r_le = self.r.to_le_bytes();
-        let sig_s_le = self.s.to_le_bytes();
-        let sig_r = ct_option_ok_or(
-            secp256k1::Fq::from_repr(sig_r_le),
-            Error::Signature(libsecp256k1::Error::InvalidSignature),
-        )?;
-        let sig_s = ct_option_ok_or(
-            secp256k1::Fq::from_repr(sig_s_le),
-            Error::Signature(libsecp256k1::Error::InvalidSignature),
-        )?;
-        // msg = rlp([nonce, gasPrice, gas, to, value, data, sig_v, r, s])
-        let req: TransactionRequest = self.into();
-        let msg = req.chain_id(chain_id).rlp();
+        // Legacy transactions sign
+        // msg = rlp([nonce, gasPrice, gas, to, value, data, chain_id, 0, 0])
+        // and typed transactions sign msg = tx_type || rlp(payload) (EIP-2718).
+        let mut tx: TypedTransaction = self.into();
+        tx.set_chain_id(chain_id);
+        let msg = tx.rlp();
         let msg_hash: [u8; 32] = Keccak256::digest(&msg)
             .as_slice()
             .to_vec()
             .try_into()
             .expect("hash length isn't 32 bytes");
-        let v = self
-            .v
-            .checked_sub(35 + chain_id * 2)
-            .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8;
-        let pk = recover_pk(v, &self.r, &self.s, &msg_hash)?;
-        // msg_hash = msg_hash % q
-        let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
-        let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
-        let msg_hash_le = biguint_to_32bytes_le(msg_hash);
-        let msg_hash = ct_option_ok_or(
-            secp256k1::Fq::from_repr(msg_hash_le),
-            libsecp256k1::Error::InvalidMessage,
-        )?;
-        Ok(SignData {
-            signature: (sig_r, sig_s),
-            pk,
-            msg_hash,
-        })
+        // The signature of a typed transaction has the y parity as v instead
+        // of the EIP-155 v.
+        let v = match self.transaction_type {
+            0 => self.v.checked_sub(35 + chain_id * 2),
+            _ => Some(self.v),
+        }
+        .filter(|v| *v <= 1)
+        .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8;
+        Ok(sign_types::sign_data(v, &self.r, &self.s, &msg_hash)?)
+    }
+
+    /// Return the v value of the signature of this Transaction from the
+    /// EIP-155 v value of a signature of its [`TypedTransaction`].
+    pub fn signature_v(&self, eip155_v: u64) -> u64 {
+        match self.transaction_type {
+            0 => eip155_v,
+            // The recovery id is the parity of the EIP-155 v value minus 35.
+            _ => (eip155_v + 1) % 2,
+        }
     }
 }
 
@@ -262,9 +305,10 @@ impl GethData {
             let wallet = wallets.get(&tx.from).unwrap();
             assert_eq!(Word::from(wallet.chain_id()), self.chain_id);
             let geth_tx: Transaction = (&*tx).into();
-            let req: TransactionRequest = (&geth_tx).into();
-            let sig = wallet.sign_transaction_sync(&req.chain_id(self.chain_id.as_u64()).into());
-            tx.v = U64::from(sig.v);
+            let mut typed_tx: TypedTransaction = (&geth_tx).into();
+            typed_tx.set_chain_id(self.chain_id.as_u64());
+            let sig = wallet.sign_transaction_sync(&typed_tx);
+            tx.v = U64::from(geth_tx.signature_v(sig.v));
             tx.r = sig.r;
             tx.s = sig.s;
         }
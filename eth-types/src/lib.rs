@@ -20,6 +20,7 @@ pub mod error;
 pub mod bytecode;
 pub mod evm_types;
 pub mod geth_types;
+pub mod mpt;
 pub mod sign_types;
 
 pub use bytecode::Bytecode;
@@ -611,7 +612,15 @@ mod tests {
 #[cfg(test)]
 mod eth_types_test {
     use super::*;
-    use crate::{Error, Word};
+    use crate::{
+        sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
+        Error, Word,
+    };
+    use ethers_core::{
+        types::transaction::{eip2718::TypedTransaction, eip2930::AccessListItem},
+        utils::{keccak256, rlp::RlpStream},
+    };
+    use ethers_signers::{LocalWallet, Signer};
     use std::str::FromStr;
 
     #[test]
@@ -707,4 +716,134 @@ mod eth_types_test {
         assert_eq!(req.to, None);
         Ok(())
     }
+
+    #[test]
+    fn dynamic_fee_tx_sign_data() -> Result<(), Error> {
+        let chain_id = 1337u64;
+        let wallet = LocalWallet::from_str(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap();
+        let mut tx = geth_types::Transaction {
+            transaction_type: geth_types::DYNAMIC_FEE_TX_TYPE,
+            from: wallet.address(),
+            to: Some(Address::repeat_byte(0x11)),
+            nonce: Word::from(7u64),
+            gas_limit: Word::from(21000u64),
+            value: Word::from(1_000_000_000u64),
+            gas_fee_cap: Word::from(30_000_000_000u64),
+            gas_tip_cap: Word::from(2_000_000_000u64),
+            call_data: Bytes::from(vec![0xde, 0xad]),
+            ..Default::default()
+        };
+        let sender = |sign_data: SignData| {
+            let pk = pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk));
+            Address::from_slice(&keccak256(pk)[12..])
+        };
+
+        // msg = 0x02 || rlp([chain_id, nonce, max_priority_fee_per_gas,
+        // max_fee_per_gas, gas_limit, to, value, data, access_list])
+        let mut stream = RlpStream::new_list(9);
+        stream
+            .append(&chain_id)
+            .append(&tx.nonce)
+            .append(&tx.gas_tip_cap)
+            .append(&tx.gas_fee_cap)
+            .append(&tx.gas_limit)
+            .append(&tx.to.unwrap())
+            .append(&tx.value)
+            .append(&tx.call_data.to_vec())
+            .begin_list(0);
+        let msg = [
+            &[geth_types::DYNAMIC_FEE_TX_TYPE as u8][..],
+            &stream.out()[..],
+        ]
+        .concat();
+        let sig = wallet.sign_hash(H256(keccak256(msg)));
+        // The signature of a typed transaction has the y parity as v.
+        tx.v = sig.v - 27;
+        tx.r = sig.r;
+        tx.s = sig.s;
+        assert_eq!(sender(tx.sign_data(chain_id)?), wallet.address());
+
+        // The EIP-155 v of the signer is converted to the y parity.
+        let mut typed_tx = TypedTransaction::from(&tx);
+        typed_tx.set_chain_id(chain_id);
+        let sig = wallet.sign_transaction_sync(&typed_tx);
+        tx.v = tx.signature_v(sig.v);
+        assert!(tx.v <= 1);
+        assert_eq!(sender(tx.sign_data(chain_id)?), wallet.address());
+
+        // A legacy v isn't valid for a typed transaction.
+        tx.v = sig.v;
+        assert!(tx.sign_data(chain_id).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn access_list_tx_sign_data() -> Result<(), Error> {
+        let chain_id = 1337u64;
+        let wallet = LocalWallet::from_str(
+            "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap();
+        let access_list = AccessList(vec![AccessListItem {
+            address: Address::repeat_byte(0x22),
+            storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+        }]);
+        let mut tx = geth_types::Transaction {
+            transaction_type: geth_types::ACCESS_LIST_TX_TYPE,
+            from: wallet.address(),
+            to: Some(Address::repeat_byte(0x11)),
+            nonce: Word::from(7u64),
+            gas_limit: Word::from(30000u64),
+            value: Word::from(1_000_000_000u64),
+            gas_price: Word::from(20_000_000_000u64),
+            gas_fee_cap: Word::from(20_000_000_000u64),
+            gas_tip_cap: Word::from(20_000_000_000u64),
+            call_data: Bytes::from(vec![0xde, 0xad]),
+            access_list: Some(access_list.clone()),
+            ..Default::default()
+        };
+        let sender = |sign_data: SignData| {
+            let pk = pk_bytes_swap_endianness(&pk_bytes_le(&sign_data.pk));
+            Address::from_slice(&keccak256(pk)[12..])
+        };
+
+        // msg = 0x01 || rlp([chain_id, nonce, gas_price, gas_limit, to, value,
+        // data, access_list])
+        let mut stream = RlpStream::new_list(8);
+        stream
+            .append(&chain_id)
+            .append(&tx.nonce)
+            .append(&tx.gas_price)
+            .append(&tx.gas_limit)
+            .append(&tx.to.unwrap())
+            .append(&tx.value)
+            .append(&tx.call_data.to_vec())
+            .begin_list(access_list.0.len());
+        for item in access_list.0.iter() {
+            stream.begin_list(2).append(&item.address);
+            stream.begin_list(item.storage_keys.len());
+            for key in item.storage_keys.iter() {
+                stream.append(key);
+            }
+        }
+        let msg = [
+            &[geth_types::ACCESS_LIST_TX_TYPE as u8][..],
+            &stream.out()[..],
+        ]
+        .concat();
+        let sig = wallet.sign_hash(H256(keccak256(msg)));
+        tx.v = sig.v - 27;
+        tx.r = sig.r;
+        tx.s = sig.s;
+        assert_eq!(sender(tx.sign_data(chain_id)?), wallet.address());
+
+        // The access list is signed.
+        let mut other_tx = tx.clone();
+        other_tx.access_list = None;
+        assert_ne!(sender(other_tx.sign_data(chain_id)?), wallet.address());
+        Ok(())
+    }
 }
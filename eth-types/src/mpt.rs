@@ -0,0 +1,739 @@
+//! Partial Merkle Patricia Tries of the Ethereum state, built from the nodes
+//! of the account and storage proofs returned by `eth_getProof`
+//! ([EIP-1186](https://eips.ethereum.org/EIPS/eip-1186)).
+//!
+//! A [`PartialTrie`] only knows the nodes on the paths of the proven keys, the
+//! rest of the trie is referenced by hash.  This is enough to read the proven
+//! keys and to update them, which only rehashes the nodes on their paths.  The
+//! only exception is the deletion of a key which leaves a branch with a single
+//! child: the child is merged into its parent, so it must be known too.
+
+use crate::{Bytes, Error, H256, U256};
+use ethers_core::utils::{
+    keccak256,
+    rlp::{Rlp, RlpStream},
+};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+lazy_static! {
+    /// Root of the empty trie, i.e. the hash of the RLP encoding of the empty
+    /// string.
+    pub static ref EMPTY_TRIE_ROOT: H256 = H256(keccak256([0x80u8]));
+    /// Hash of the empty code.
+    pub static ref EMPTY_CODE_HASH: H256 = H256(keccak256(b""));
+}
+
+/// A node of the trie, where paths are stored as nibbles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Node {
+    Empty,
+    Leaf(Vec<u8>, Vec<u8>),
+    Extension(Vec<u8>, Box<Node>),
+    Branch(Box<[Node; 16]>),
+    // A node which hasn't been decoded yet, referenced by the hash of its RLP
+    // encoding.
+    Hash(H256),
+}
+
+/// A Merkle Patricia Trie of which only the nodes on the paths of some keys
+/// are known.  Keys are hashed with keccak, as in the secure tries of the
+/// state.
+#[derive(Clone, Debug)]
+pub struct PartialTrie {
+    root: Node,
+    nodes: HashMap<H256, Vec<u8>>,
+}
+
+impl PartialTrie {
+    /// Create a trie with the given root from the RLP encoded nodes of proofs
+    /// against it.  Nodes that don't belong to the trie are never reached and
+    /// thus ignored.
+    pub fn new<T: AsRef<[u8]>>(root: H256, nodes: impl IntoIterator<Item = T>) -> Self {
+        let root = if root == *EMPTY_TRIE_ROOT {
+            Node::Empty
+        } else {
+            Node::Hash(root)
+        };
+        let mut trie = Self {
+            root,
+            nodes: HashMap::new(),
+        };
+        trie.add_nodes(nodes);
+        trie
+    }
+
+    /// Add RLP encoded nodes of the trie, such as the nodes returned by
+    /// [`PartialTrie::collapse_siblings`].
+    pub fn add_nodes<T: AsRef<[u8]>>(&mut self, nodes: impl IntoIterator<Item = T>) {
+        self.nodes.extend(
+            nodes
+                .into_iter()
+                .map(|node| (H256(keccak256(node.as_ref())), node.as_ref().to_vec())),
+        );
+    }
+
+    /// Root of the trie.
+    pub fn root(&self) -> H256 {
+        match &self.root {
+            Node::Hash(hash) => *hash,
+            node => H256(keccak256(encode(node))),
+        }
+    }
+
+    /// Return the value of the key, or `None` if the key isn't in the trie.
+    pub fn get(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let path = key_path(key);
+        let mut path = path.as_slice();
+        let mut node = self.resolve(&self.root)?;
+        loop {
+            match node {
+                Node::Empty => return Ok(None),
+                Node::Leaf(leaf_path, value) => {
+                    return Ok((leaf_path == path).then_some(value));
+                }
+                Node::Extension(extension_path, child) => {
+                    if !path.starts_with(&extension_path) {
+                        return Ok(None);
+                    }
+                    path = &path[extension_path.len()..];
+                    node = self.resolve(&child)?;
+                }
+                Node::Branch(children) => {
+                    let (index, rest) = path.split_first().ok_or(Error::InvalidTrieNode)?;
+                    path = rest;
+                    node = self.resolve(&children[*index as usize])?;
+                }
+                Node::Hash(_) => unreachable!("resolved node is never a hash"),
+            }
+        }
+    }
+
+    /// Return the proof of the key as in `eth_getProof`: the RLP encoded nodes
+    /// on the path of the key, from the root to the leaf.  Nodes shorter than
+    /// 32 bytes are embedded in their parent, so they aren't included.
+    pub fn prove(&self, key: &[u8]) -> Result<Vec<Bytes>, Error> {
+        self.prove_path(&key_path(key))
+    }
+
+    /// Return the RLP encoded nodes on a path of nibbles from the root, as in
+    /// [`PartialTrie::prove`], where the last node is the one reached by the
+    /// whole path if it isn't empty.
+    pub fn prove_path(&self, path: &[u8]) -> Result<Vec<Bytes>, Error> {
+        let mut path = path;
+        let mut node = self.resolve(&self.root)?;
+        let mut proof = vec![encode(&node).into()];
+        loop {
+            let child = match &node {
+                Node::Extension(extension_path, child) if path.starts_with(extension_path) => {
+                    path = &path[extension_path.len()..];
+                    child
+                }
+                Node::Branch(children) if !path.is_empty() => {
+                    let (index, rest) = path.split_first().expect("path isn't empty");
+                    path = rest;
+                    &children[*index as usize]
+                }
+                _ => return Ok(proof),
+            };
+            node = self.resolve(child)?;
+            let encoding = encode(&node);
+            if node != Node::Empty && encoding.len() >= 32 {
+                proof.push(encoding.into());
+            }
+        }
+    }
+
+    /// Set the value of the key, where an empty value deletes the key from
+    /// the trie.
+    pub fn insert(&mut self, key: &[u8], value: Vec<u8>) -> Result<(), Error> {
+        let path = key_path(key);
+        self.root = if value.is_empty() {
+            self.delete_at(&self.root, &path)?
+        } else {
+            self.insert_at(&self.root, &path, value)?
+        };
+        Ok(())
+    }
+
+    /// Return the hashes of the unknown nodes which may be merged into their
+    /// parent when the key is deleted, whatever the updates of the other keys
+    /// before.  The child of a branch is merged when all its siblings are
+    /// deleted, which requires them to be known.  The child of an extension is
+    /// merged when it becomes the child of a branch by the insertion of the
+    /// key, and the key is deleted.
+    pub fn collapse_siblings(&self, key: &[u8]) -> Result<Vec<H256>, Error> {
+        let path = key_path(key);
+        let mut path = path.as_slice();
+        let mut node = self.resolve(&self.root)?;
+        let mut hashes = Vec::new();
+        loop {
+            let child = match node {
+                Node::Extension(extension_path, child) if path.starts_with(&extension_path) => {
+                    path = &path[extension_path.len()..];
+                    *child
+                }
+                Node::Extension(extension_path, child) => {
+                    if common_prefix(&extension_path, path) + 1 == extension_path.len() {
+                        hashes.extend(self.unknown_hash(&child));
+                    }
+                    return Ok(hashes);
+                }
+                Node::Branch(children) => {
+                    let (index, rest) = path.split_first().ok_or(Error::InvalidTrieNode)?;
+                    path = rest;
+                    let unknown_siblings: Vec<H256> = children
+                        .iter()
+                        .enumerate()
+                        .filter(|(sibling, _)| *sibling != *index as usize)
+                        .filter_map(|(_, sibling)| self.unknown_hash(sibling))
+                        .collect();
+                    if let [hash] = unknown_siblings[..] {
+                        hashes.push(hash);
+                    }
+                    children[*index as usize].clone()
+                }
+                _ => return Ok(hashes),
+            };
+            node = self.resolve(&child)?;
+        }
+    }
+
+    // The hash of the node if it isn't known.
+    fn unknown_hash(&self, node: &Node) -> Option<H256> {
+        match node {
+            Node::Hash(hash) if !self.nodes.contains_key(hash) => Some(*hash),
+            _ => None,
+        }
+    }
+
+    fn resolve(&self, node: &Node) -> Result<Node, Error> {
+        match node {
+            Node::Hash(hash) => decode(self.nodes.get(hash).ok_or(Error::MissingTrieNode(*hash))?),
+            node => Ok(node.clone()),
+        }
+    }
+
+    fn insert_at(&self, node: &Node, path: &[u8], value: Vec<u8>) -> Result<Node, Error> {
+        Ok(match self.resolve(node)? {
+            Node::Empty => Node::Leaf(path.to_vec(), value),
+            Node::Leaf(leaf_path, leaf_value) => {
+                if leaf_path == path {
+                    Node::Leaf(leaf_path, value)
+                } else {
+                    // Keys have the same length, so the paths diverge before the end of
+                    // both.
+                    let common = common_prefix(&leaf_path, path);
+                    let mut children = empty_children();
+                    children[leaf_path[common] as usize] =
+                        Node::Leaf(leaf_path[common + 1..].to_vec(), leaf_value);
+                    children[path[common] as usize] =
+                        Node::Leaf(path[common + 1..].to_vec(), value);
+                    extension(&path[..common], Node::Branch(children))
+                }
+            }
+            Node::Extension(extension_path, child) => {
+                let common = common_prefix(&extension_path, path);
+                if common == extension_path.len() {
+                    Node::Extension(
+                        extension_path,
+                        Box::new(self.insert_at(&child, &path[common..], value)?),
+                    )
+                } else {
+                    let mut children = empty_children();
+                    children[extension_path[common] as usize] =
+                        extension(&extension_path[common + 1..], *child);
+                    children[path[common] as usize] =
+                        Node::Leaf(path[common + 1..].to_vec(), value);
+                    extension(&path[..common], Node::Branch(children))
+                }
+            }
+            Node::Branch(mut children) => {
+                let index = path[0] as usize;
+                children[index] = self.insert_at(&children[index], &path[1..], value)?;
+                Node::Branch(children)
+            }
+            Node::Hash(_) => unreachable!("resolved node is never a hash"),
+        })
+    }
+
+    fn delete_at(&self, node: &Node, path: &[u8]) -> Result<Node, Error> {
+        Ok(match self.resolve(node)? {
+            Node::Empty => Node::Empty,
+            Node::Leaf(leaf_path, leaf_value) => {
+                if leaf_path == path {
+                    Node::Empty
+                } else {
+                    Node::Leaf(leaf_path, leaf_value)
+                }
+            }
+            Node::Extension(extension_path, child) => {
+                if path.starts_with(&extension_path) {
+                    let child = self.delete_at(&child, &path[extension_path.len()..])?;
+                    self.merge(&extension_path, child)?
+                } else {
+                    Node::Extension(extension_path, child)
+                }
+            }
+            Node::Branch(mut children) => {
+                let index = path[0] as usize;
+                children[index] = self.delete_at(&children[index], &path[1..])?;
+                let non_empty: Vec<usize> = (0..16)
+                    .filter(|index| children[*index] != Node::Empty)
+                    .collect();
+                if let [index] = non_empty[..] {
+                    let child = std::mem::replace(&mut children[index], Node::Empty);
+                    self.merge(&[index as u8], child)?
+                } else {
+                    Node::Branch(children)
+                }
+            }
+            Node::Hash(_) => unreachable!("resolved node is never a hash"),
+        })
+    }
+
+    // Prefix the node with the path, merging it with the path of a leaf or an
+    // extension.
+    fn merge(&self, path: &[u8], node: Node) -> Result<Node, Error> {
+        Ok(match self.resolve(&node)? {
+            Node::Empty => Node::Empty,
+            Node::Leaf(leaf_path, value) => Node::Leaf([path, &leaf_path].concat(), value),
+            Node::Extension(extension_path, child) => {
+                Node::Extension([path, &extension_path].concat(), child)
+            }
+            Node::Branch(_) => Node::Extension(path.to_vec(), Box::new(node)),
+            Node::Hash(_) => unreachable!("resolved node is never a hash"),
+        })
+    }
+}
+
+/// A node of a proof, as returned by [`PartialTrie::prove`], whose children
+/// are referenced by hash.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofNode {
+    /// Branch with the hashes of its children, `None` for the empty ones
+    Branch([Option<H256>; 16]),
+    /// Extension with its path as nibbles and the hash of its child
+    Extension(Vec<u8>, H256),
+    /// Leaf with the end of the path of its key as nibbles and its value
+    Leaf(Vec<u8>, Vec<u8>),
+}
+
+impl ProofNode {
+    /// Decode a node from its RLP encoding.  Children embedded in their
+    /// parent aren't supported: they are shorter than 32 bytes, which never
+    /// happens in practice with the hashed keys of the state.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let reference = |node: &Node| match node {
+            Node::Empty => Ok(None),
+            Node::Hash(hash) => Ok(Some(*hash)),
+            _ => Err(Error::InvalidTrieNode),
+        };
+        Ok(match decode(bytes)? {
+            Node::Leaf(path, value) => Self::Leaf(path, value),
+            Node::Extension(path, child) => {
+                Self::Extension(path, reference(&child)?.ok_or(Error::InvalidTrieNode)?)
+            }
+            Node::Branch(children) => {
+                let mut hashes = [None; 16];
+                for (hash, child) in hashes.iter_mut().zip(children.iter()) {
+                    *hash = reference(child)?;
+                }
+                Self::Branch(hashes)
+            }
+            Node::Empty | Node::Hash(_) => unreachable!("decoded node is never empty or a hash"),
+        })
+    }
+}
+
+/// The nibbles of the path of a key in a trie, which is the keccak hash of the
+/// key.
+pub fn key_path(key: &[u8]) -> Vec<u8> {
+    nibbles(&keccak256(key))
+}
+
+/// The state of an account, as encoded in the leaves of the state trie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrieAccount {
+    /// Nonce
+    pub nonce: U256,
+    /// Balance
+    pub balance: U256,
+    /// Root of the storage trie
+    pub storage_root: H256,
+    /// Hash of the code
+    pub code_hash: H256,
+}
+
+impl Default for TrieAccount {
+    fn default() -> Self {
+        Self {
+            nonce: U256::zero(),
+            balance: U256::zero(),
+            storage_root: *EMPTY_TRIE_ROOT,
+            code_hash: *EMPTY_CODE_HASH,
+        }
+    }
+}
+
+impl TrieAccount {
+    /// Decode the account from the value of its leaf.
+    pub fn from_rlp(bytes: &[u8]) -> Result<Self, Error> {
+        let rlp = Rlp::new(bytes);
+        Ok(Self {
+            nonce: rlp.val_at(0)?,
+            balance: rlp.val_at(1)?,
+            storage_root: rlp.val_at(2)?,
+            code_hash: rlp.val_at(3)?,
+        })
+    }
+
+    /// Encode the account as the value of its leaf.
+    pub fn rlp_bytes(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(4);
+        stream
+            .append(&self.nonce)
+            .append(&self.balance)
+            .append(&self.storage_root)
+            .append(&self.code_hash);
+        stream.out().to_vec()
+    }
+}
+
+/// Encode a storage value as the value of its leaf, which is empty for 0.
+pub fn storage_value_rlp(value: U256) -> Vec<u8> {
+    if value.is_zero() {
+        vec![]
+    } else {
+        ethers_core::utils::rlp::encode(&value).to_vec()
+    }
+}
+
+/// Decode a storage value from the value of its leaf.
+pub fn storage_value_from_rlp(bytes: &[u8]) -> Result<U256, Error> {
+    Ok(Rlp::new(bytes).as_val()?)
+}
+
+fn empty_children() -> Box<[Node; 16]> {
+    Box::new(std::array::from_fn(|_| Node::Empty))
+}
+
+// An extension with an empty path is the node itself.
+fn extension(path: &[u8], node: Node) -> Node {
+    if path.is_empty() {
+        node
+    } else {
+        Node::Extension(path.to_vec(), Box::new(node))
+    }
+}
+
+fn common_prefix(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+fn nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+// Hex prefix encoding of a path, see appendix C of the yellow paper.
+fn hex_prefix(path: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 2 } else { 0 };
+    let (first, rest) = if path.len() % 2 == 1 {
+        (((flag + 1) << 4) | path[0], &path[1..])
+    } else {
+        (flag << 4, path)
+    };
+    std::iter::once(first)
+        .chain(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]))
+        .collect()
+}
+
+fn hex_prefix_decode(bytes: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+    let (first, rest) = bytes.split_first().ok_or(Error::InvalidTrieNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(Error::InvalidTrieNode);
+    }
+    let mut path = if flag & 1 == 1 {
+        vec![first & 0xf]
+    } else {
+        vec![]
+    };
+    path.extend(nibbles(rest));
+    Ok((path, flag & 2 == 2))
+}
+
+fn encode(node: &Node) -> Vec<u8> {
+    let mut stream = match node {
+        Node::Empty => return vec![0x80],
+        Node::Leaf(path, value) => {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(path, true)).append(value);
+            stream
+        }
+        Node::Extension(path, child) => {
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(path, false));
+            append_reference(&mut stream, child);
+            stream
+        }
+        Node::Branch(children) => {
+            let mut stream = RlpStream::new_list(17);
+            for child in children.iter() {
+                append_reference(&mut stream, child);
+            }
+            // Keys have the same length, so branches never hold values.
+            stream.append_empty_data();
+            stream
+        }
+        Node::Hash(_) => unreachable!("a hash is only encoded as a reference"),
+    };
+    stream.out().to_vec()
+}
+
+// Nodes are referenced by the hash of their encoding, unless it's shorter than
+// 32 bytes, in which case they are embedded in their parent.
+fn append_reference(stream: &mut RlpStream, node: &Node) {
+    match node {
+        Node::Empty => {
+            stream.append_empty_data();
+        }
+        Node::Hash(hash) => {
+            stream.append(hash);
+        }
+        node => {
+            let encoding = encode(node);
+            if encoding.len() < 32 {
+                stream.append_raw(&encoding, 1);
+            } else {
+                stream.append(&H256(keccak256(&encoding)));
+            }
+        }
+    }
+}
+
+fn decode(bytes: &[u8]) -> Result<Node, Error> {
+    let rlp = Rlp::new(bytes);
+    match rlp.item_count()? {
+        2 => {
+            let (path, is_leaf) = hex_prefix_decode(rlp.at(0)?.data()?)?;
+            if is_leaf {
+                Ok(Node::Leaf(path, rlp.at(1)?.data()?.to_vec()))
+            } else {
+                Ok(Node::Extension(
+                    path,
+                    Box::new(decode_reference(&rlp.at(1)?)?),
+                ))
+            }
+        }
+        17 => {
+            let mut children = empty_children();
+            for (index, child) in children.iter_mut().enumerate() {
+                *child = decode_reference(&rlp.at(index)?)?;
+            }
+            Ok(Node::Branch(children))
+        }
+        _ => Err(Error::InvalidTrieNode),
+    }
+}
+
+fn decode_reference(rlp: &Rlp) -> Result<Node, Error> {
+    if rlp.is_list() {
+        return decode(rlp.as_raw());
+    }
+    match rlp.data()? {
+        [] => Ok(Node::Empty),
+        hash if hash.len() == 32 => Ok(Node::Hash(H256::from_slice(hash))),
+        _ => Err(Error::InvalidTrieNode),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToWord;
+
+    #[test]
+    fn empty_trie() {
+        let trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        assert_eq!(trie.root(), *EMPTY_TRIE_ROOT);
+        assert_eq!(trie.get(&[1]).unwrap(), None);
+    }
+
+    #[test]
+    fn insert_and_delete() {
+        let mut trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        let keys: Vec<[u8; 1]> = (0..=255u8).map(|i| [i]).collect();
+        let mut roots = vec![trie.root()];
+        for (i, key) in keys.iter().enumerate() {
+            trie.insert(key, storage_value_rlp(U256::from(i + 1)))
+                .unwrap();
+            roots.push(trie.root());
+        }
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(
+                storage_value_from_rlp(&trie.get(key).unwrap().unwrap()).unwrap(),
+                U256::from(i + 1)
+            );
+        }
+
+        // Deleting the keys in reverse order goes through the same roots.
+        for key in keys.iter().rev() {
+            assert_eq!(trie.root(), roots.pop().unwrap());
+            trie.insert(key, vec![]).unwrap();
+            assert_eq!(trie.get(key).unwrap(), None);
+        }
+        assert_eq!(trie.root(), *EMPTY_TRIE_ROOT);
+    }
+
+    #[test]
+    fn single_leaf_root() {
+        // The storage trie of an account with a single slot 0 set to 1.
+        let mut trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        trie.insert(&[0; 32], storage_value_rlp(U256::one()))
+            .unwrap();
+
+        let mut stream = RlpStream::new_list(2);
+        stream
+            .append(&hex_prefix(&nibbles(&keccak256([0u8; 32])), true))
+            .append(&vec![1u8]);
+        assert_eq!(trie.root(), H256(keccak256(stream.out())));
+    }
+
+    #[test]
+    fn partial_trie_from_nodes() {
+        let mut trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        for i in 0..64u8 {
+            trie.insert(&[i], vec![i + 1]).unwrap();
+        }
+        let nodes: Vec<_> = (0..64u8).flat_map(|i| trie.prove(&[i]).unwrap()).collect();
+
+        let mut partial = PartialTrie::new(trie.root(), nodes);
+        for i in 0..64u8 {
+            assert_eq!(partial.get(&[i]).unwrap(), Some(vec![i + 1]));
+        }
+        partial.insert(&[3], vec![42]).unwrap();
+        trie.insert(&[3], vec![42]).unwrap();
+        assert_eq!(partial.root(), trie.root());
+
+        // Nodes off the path of the keys are unknown.
+        let partial = PartialTrie::new(trie.root(), Vec::<Vec<u8>>::new());
+        assert!(matches!(
+            partial.get(&[1]),
+            Err(Error::MissingTrieNode(root)) if root == trie.root()
+        ));
+    }
+
+    #[test]
+    fn delete_with_unknown_sibling() {
+        let mut trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        trie.insert(&[1], vec![1]).unwrap();
+        trie.insert(&[2], vec![2]).unwrap();
+
+        // Deleting [1] leaves a branch with the leaf of [2], which isn't in the
+        // proof of [1].
+        let mut partial = PartialTrie::new(trie.root(), trie.prove(&[1]).unwrap());
+        let siblings = partial.collapse_siblings(&[1]).unwrap();
+        assert_eq!(siblings.len(), 1);
+        assert!(matches!(
+            partial.clone().insert(&[1], vec![]),
+            Err(Error::MissingTrieNode(hash)) if hash == siblings[0]
+        ));
+
+        partial.add_nodes(trie.prove(&[2]).unwrap());
+        assert_eq!(partial.collapse_siblings(&[1]).unwrap(), vec![]);
+        partial.insert(&[1], vec![]).unwrap();
+        trie.insert(&[1], vec![]).unwrap();
+        assert_eq!(partial.root(), trie.root());
+    }
+
+    #[test]
+    fn insert_and_delete_under_extension() {
+        // The hashes of [2] and [4] only share their first nibble, so the root
+        // is an extension of one nibble, which the hash of [1] doesn't start
+        // with.
+        let mut trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        trie.insert(&[2], vec![2]).unwrap();
+        trie.insert(&[4], vec![4]).unwrap();
+
+        // Inserting [1] turns the extension into a branch with the unknown
+        // child of the extension, which is merged back into an extension when
+        // [1] is deleted.
+        let mut partial = PartialTrie::new(trie.root(), trie.prove(&[1]).unwrap());
+        let siblings = partial.collapse_siblings(&[1]).unwrap();
+        assert_eq!(siblings.len(), 1);
+        let mut updated = partial.clone();
+        updated.insert(&[1], vec![1]).unwrap();
+        assert!(matches!(
+            updated.insert(&[1], vec![]),
+            Err(Error::MissingTrieNode(hash)) if hash == siblings[0]
+        ));
+
+        partial.add_nodes(trie.prove(&[2]).unwrap());
+        partial.insert(&[1], vec![1]).unwrap();
+        partial.insert(&[1], vec![]).unwrap();
+        assert_eq!(partial.root(), trie.root());
+    }
+
+    #[test]
+    fn proof_nodes() {
+        let mut trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        for i in 0..64u8 {
+            trie.insert(&[i], vec![i; 32]).unwrap();
+        }
+        let path = key_path(&[7]);
+        let proof = trie.prove(&[7]).unwrap();
+        let mut depth = 0;
+        for (node, child) in proof.iter().zip(proof.iter().skip(1)) {
+            let child_hash = H256(keccak256(child));
+            match ProofNode::decode(node).unwrap() {
+                ProofNode::Branch(children) => {
+                    assert_eq!(children[path[depth] as usize], Some(child_hash));
+                    depth += 1;
+                }
+                ProofNode::Extension(extension_path, hash) => {
+                    assert_eq!(hash, child_hash);
+                    depth += extension_path.len();
+                }
+                ProofNode::Leaf(..) => unreachable!("leaf before the end of the proof"),
+            }
+        }
+        assert_eq!(
+            ProofNode::decode(proof.last().unwrap()).unwrap(),
+            ProofNode::Leaf(path[depth..].to_vec(), vec![7; 32])
+        );
+
+        // The proof of a prefix of the path ends at the node it reaches.
+        assert_eq!(trie.prove_path(&path[..depth]).unwrap(), proof);
+        assert_eq!(trie.prove_path(&[]).unwrap(), proof[..1]);
+    }
+
+    #[test]
+    fn secure_trie_test_vector() {
+        // The `emptyValues` test of `TrieTests/trietest_secureTrie.json` in
+        // https://github.com/ethereum/tests, where a null value deletes the key.
+        let mut trie = PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Vec<u8>>::new());
+        for (key, value) in [
+            ("do", "verb"),
+            ("ether", "wookiedoo"),
+            ("horse", "stallion"),
+            ("shaman", "horse"),
+            ("doge", "coin"),
+            ("ether", ""),
+            ("dog", "puppy"),
+            ("shaman", ""),
+        ] {
+            trie.insert(key.as_bytes(), value.as_bytes().to_vec())
+                .unwrap();
+        }
+        assert_eq!(
+            trie.root().to_word(),
+            crate::word!("0x29b235a58c3c25ab83010c327d5932bcf05324b7d6b1185e650798034783ca9d")
+        );
+        assert_eq!(trie.get(b"dog").unwrap(), Some(b"puppy".to_vec()));
+        assert_eq!(trie.get(b"ether").unwrap(), None);
+    }
+}
@@ -1,6 +1,6 @@
 //! secp256k1 signature types and helper functions.
 
-use crate::{ToBigEndian, Word};
+use crate::{ToBigEndian, ToLittleEndian, Word};
 use halo2_proofs::{
     arithmetic::{CurveAffine, FieldExt},
     halo2curves::{
@@ -13,6 +13,7 @@ use halo2_proofs::{
     },
 };
 use lazy_static::lazy_static;
+use num::Integer;
 use num_bigint::BigUint;
 use subtle::CtOption;
 
@@ -120,6 +121,38 @@ pub fn recover_pk(
     )
 }
 
+/// Return the [`SignData`] of a secp256k1 signature of `msg_hash`, recovering
+/// its public key.  The message hash is reduced modulo the curve order.
+pub fn sign_data(
+    v: u8,
+    r: &Word,
+    s: &Word,
+    msg_hash: &[u8; 32],
+) -> Result<SignData, libsecp256k1::Error> {
+    let sig_r = ct_option_ok_or(
+        secp256k1::Fq::from_repr(r.to_le_bytes()),
+        libsecp256k1::Error::InvalidSignature,
+    )?;
+    let sig_s = ct_option_ok_or(
+        secp256k1::Fq::from_repr(s.to_le_bytes()),
+        libsecp256k1::Error::InvalidSignature,
+    )?;
+    let pk = recover_pk(v, r, s, msg_hash)?;
+    // msg_hash = msg_hash % q
+    let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
+    let msg_hash = msg_hash.mod_floor(&*SECP256K1_Q);
+    let msg_hash_le = biguint_to_32bytes_le(msg_hash);
+    let msg_hash = ct_option_ok_or(
+        secp256k1::Fq::from_repr(msg_hash_le),
+        libsecp256k1::Error::InvalidMessage,
+    )?;
+    Ok(SignData {
+        signature: (sig_r, sig_s),
+        pk,
+        msg_hash,
+    })
+}
+
 lazy_static! {
     /// Secp256k1 Curve Scalar.  Referece: Section 2.4.1 (parameter `n`) in "SEC 2: Recommended
     /// Elliptic Curve Domain Parameters" document at http://www.secg.org/sec2-v2.pdf
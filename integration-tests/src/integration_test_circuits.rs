@@ -60,6 +60,24 @@ const MAX_EXP_STEPS: usize = 1000;
 
 const MAX_KECCAK_ROWS: usize = 15000;
 
+const MAX_SHA256_ROWS: usize = 0;
+
+const MAX_RIPEMD160_ROWS: usize = 0;
+
+const MAX_ECRECOVER: usize = 0;
+
+const MAX_EC_ADD: usize = 0;
+
+const MAX_EC_MUL: usize = 0;
+
+const MAX_MODEXP: usize = 0;
+
+const MAX_BLAKE2F: usize = 0;
+
+const MAX_BLAKE2F_ROUNDS: usize = 0;
+
+const MAX_MPT_ROWS: usize = 0;
+
 const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_rws: MAX_RWS,
     max_txs: MAX_TXS,
@@ -69,6 +87,15 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_evm_rows: MAX_EVM_ROWS,
     max_exp_steps: MAX_EXP_STEPS,
     max_keccak_rows: MAX_KECCAK_ROWS,
+    max_sha256_rows: MAX_SHA256_ROWS,
+    max_ripemd160_rows: MAX_RIPEMD160_ROWS,
+    max_ecrecover: MAX_ECRECOVER,
+    max_ec_add: MAX_EC_ADD,
+    max_ec_mul: MAX_EC_MUL,
+    max_modexp: MAX_MODEXP,
+    max_blake2f: MAX_BLAKE2F,
+    max_blake2f_rounds: MAX_BLAKE2F_ROUNDS,
+    max_mpt_rows: MAX_MPT_ROWS,
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 18;
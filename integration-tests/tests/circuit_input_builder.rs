@@ -1,7 +1,7 @@
 #![cfg(feature = "circuit_input_builder")]
 
 use bus_mapping::circuit_input_builder::{
-    build_state_code_db, get_state_accesses, BuilderClient, CircuitsParams,
+    build_verified_state_code_db, get_state_accesses, BuilderClient, CircuitsParams,
 };
 use integration_tests::{get_client, log_init, GenDataOutput};
 use lazy_static::lazy_static;
@@ -24,6 +24,15 @@ async fn test_circuit_input_builder_block(block_num: u64) {
             max_evm_rows: 0,
             max_exp_steps: 1000,
             max_keccak_rows: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_ecrecover: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_modexp: 0,
+            max_blake2f: 0,
+            max_blake2f_rounds: 0,
+            max_mpt_rows: 0,
         },
     )
     .await
@@ -40,13 +49,14 @@ async fn test_circuit_input_builder_block(block_num: u64) {
     // 3. Query geth for all accounts, storage keys, and codes from Accesses
     let (proofs, codes) = cli.get_state(block_num, access_set).await.unwrap();
 
-    // 4. Build a partial StateDB from step 3
-    let (state_db, code_db) = build_state_code_db(proofs, codes);
+    // 4. Build a partial StateDB from step 3, verifying the proofs against the
+    // state root of the previous block
+    let (state_db, code_db) = build_verified_state_code_db(prev_state_root, proofs, codes).unwrap();
     trace!("StateDB: {:#?}", state_db);
 
     // 5. For each step in TxExecTraces, gen the associated ops and state
     // circuit inputs
-    let builder = cli
+    let mut builder = cli
         .gen_inputs_from_state(
             state_db,
             code_db,
@@ -57,6 +67,10 @@ async fn test_circuit_input_builder_block(block_num: u64) {
         )
         .unwrap();
 
+    // 6. Query geth for the trie nodes missing from the proofs of step 3
+    let hashes = builder.block.missing_trie_nodes().unwrap();
+    builder.block.trie_nodes = cli.get_trie_nodes(hashes).await.unwrap();
+
     trace!("CircuitInputBuilder: {:#?}", builder);
 }
 
@@ -2,12 +2,12 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{Transaction as GethTransaction, DYNAMIC_FEE_TX_TYPE},
+    word, AccessList, Address, Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
-    types::{OtherFields, TransactionRequest},
+    types::{transaction::eip2718::TypedTransaction, OtherFields},
 };
 use ethers_signers::{LocalWallet, Signer};
 use lazy_static::lazy_static;
@@ -304,18 +304,23 @@ impl MockTransaction {
         self
     }
 
+    /// Set gas_price field of a dynamic fee MockTransaction to its effective
+    /// gas price under the base fee of the block.
+    pub(crate) fn effective_gas_price(&mut self, base_fee: Word) -> &mut Self {
+        if self.transaction_type == U64::from(DYNAMIC_FEE_TX_TYPE) {
+            self.gas_price = self
+                .max_fee_per_gas
+                .min(base_fee + self.max_priority_fee_per_gas);
+        }
+        self
+    }
+
     /// Consumes the mutable ref to the MockTransaction returning the structure
     /// by value.
     pub fn build(&mut self) -> Self {
-        let tx = TransactionRequest::new()
-            .from(self.from.address())
-            .to(self.to.clone().unwrap_or_default().address())
-            .nonce(self.nonce)
-            .value(self.value)
-            .data(self.input.clone())
-            .gas(self.gas)
-            .gas_price(self.gas_price)
-            .chain_id(self.chain_id.low_u64());
+        let tx = GethTransaction::from(self.to_owned());
+        let mut typed_tx = TypedTransaction::from(&tx);
+        typed_tx.set_chain_id(self.chain_id.low_u64());
 
         match (self.v, self.r, self.s) {
             (None, None, None) => {
@@ -325,9 +330,9 @@ impl MockTransaction {
                         .from
                         .as_wallet()
                         .with_chain_id(self.chain_id.low_u64())
-                        .sign_transaction_sync(&tx.into());
+                        .sign_transaction_sync(&typed_tx);
                     // Set sig parameters
-                    self.sig_data((sig.v, sig.r, sig.s));
+                    self.sig_data((tx.signature_v(sig.v), sig.r, sig.s));
                 }
             }
             (Some(_), Some(_), Some(_)) => (),
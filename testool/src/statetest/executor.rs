@@ -140,14 +140,15 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
             },
 
             transactions: vec![geth_types::Transaction {
+                transaction_type: 0,
                 from: st.from,
                 to: st.to,
                 nonce: st.nonce,
                 value: st.value,
                 gas_limit: U256::from(st.gas_limit),
                 gas_price: st.gas_price,
-                gas_fee_cap: U256::zero(),
-                gas_tip_cap: U256::zero(),
+                gas_fee_cap: st.gas_price,
+                gas_tip_cap: st.gas_price,
                 call_data: st.data,
                 access_list: None,
                 v: sig.v,
@@ -270,6 +271,15 @@ pub fn run_test(
             max_evm_rows: 0,
             max_exp_steps: 5000,
             max_keccak_rows: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_ecrecover: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_modexp: 0,
+            max_blake2f: 0,
+            max_blake2f_rounds: 0,
+            max_mpt_rows: 0,
         };
         let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
 
@@ -295,6 +305,15 @@ pub fn run_test(
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_sha256_rows: 0,
+            max_ripemd160_rows: 0,
+            max_ecrecover: 0,
+            max_ec_add: 0,
+            max_ec_mul: 0,
+            max_modexp: 0,
+            max_blake2f: 0,
+            max_blake2f_rounds: 0,
+            max_mpt_rows: 0,
         };
         let (k, circuit, instance, _builder) =
             SuperCircuit::<Fr, MAX_TXS, MAX_CALLDATA, 0x100>::build(geth_data, circuits_params)
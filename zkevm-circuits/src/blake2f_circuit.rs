@@ -0,0 +1,1095 @@
+//! The BLAKE2F circuit implementation, which verifies the compression function
+//! F computed by the calls to the blake2f precompiled contract and exposes
+//! them in the BLAKE2F table.
+//!
+//! Every call takes two sets of rows:
+//! - `NUM_DATA_ROWS_PER_CALL` data rows, one per byte of the input and the output, accumulating
+//!   their RLCs and composing the 64 bits words of the state, message, offset counter and output,
+//!   together with the number of rounds and the final block flag.
+//! - `num_ops_rows_per_call` operation rows, each doing an addition, a xor followed by a right
+//!   rotation, a selection or a round flag update on words copied from the previous operations or
+//!   the data rows.
+//!
+//! The calls are laid out for up to `max_blake2f_rounds` rounds, where a
+//! round beyond the number of rounds of the call is computed but keeps the
+//! work vector unchanged.  The round flags are decreasing and add up to the
+//! number of rounds, which is also the gas cost of the call.
+//!
+//! A call fails when the final block flag is neither 0 nor 1, which is proved
+//! with the inverse of `flag * (flag - 1)`.  Its output is then zeros in the
+//! data rows, whose RLC is the one of the empty output.
+
+mod param;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Blake2fCircuit as TestBlake2fCircuit;
+
+use self::param::*;
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    sha256_circuit::util::{decode, rotate},
+    table::{Blake2fTable, LookupTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{
+    circuit_input_builder::PrecompileEvent,
+    precompile::{PrecompileCalls, BLAKE2F_INPUT_LEN},
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, xor, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use std::marker::PhantomData;
+
+/// Blake2fCircuitConfig
+#[derive(Clone, Debug)]
+pub struct Blake2fCircuitConfig<F> {
+    q_call: Column<Fixed>,
+    q_data: Column<Fixed>,
+    q_data_first: Column<Fixed>,
+    q_rounds: Column<Fixed>,
+    q_word: Column<Fixed>,
+    q_final_flag: Column<Fixed>,
+    q_add: Column<Fixed>,
+    q_add3: Column<Fixed>,
+    q_xors: [Column<Fixed>; ROTATIONS.len()],
+    q_select: Column<Fixed>,
+    q_flag: Column<Fixed>,
+    constants: Column<Fixed>,
+    u8_table: TableColumn,
+    is_enabled: Column<Advice>,
+    byte: Column<Advice>,
+    data_rlc: Column<Advice>,
+    word: Column<Advice>,
+    is_success: Column<Advice>,
+    inv: Column<Advice>,
+    x: Column<Advice>,
+    y: Column<Advice>,
+    z: Column<Advice>,
+    out: Column<Advice>,
+    bits_x: [Column<Advice>; NUM_BITS_PER_WORD],
+    bits_y: [Column<Advice>; NUM_BITS_PER_WORD],
+    bits_sum: [Column<Advice>; NUM_BITS_PER_WORD_SUM],
+    /// The columns for other circuits to lookup BLAKE2F results
+    pub blake2f_table: Blake2fTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Blake2fCircuitConfigArgs<F: Field> {
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Blake2fCircuitConfig<F> {
+    type ConfigArgs = Blake2fCircuitConfigArgs<F>;
+
+    /// Return a new Blake2fCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            blake2f_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_call = meta.fixed_column();
+        let q_data = meta.fixed_column();
+        let q_data_first = meta.fixed_column();
+        let q_rounds = meta.fixed_column();
+        let q_word = meta.fixed_column();
+        let q_final_flag = meta.fixed_column();
+        let q_add = meta.fixed_column();
+        let q_add3 = meta.fixed_column();
+        let q_xors = array_init::array_init(|_| meta.fixed_column());
+        let q_select = meta.fixed_column();
+        let q_flag = meta.fixed_column();
+        let constants = meta.fixed_column();
+        let u8_table = meta.lookup_table_column();
+        let is_enabled = meta.advice_column();
+        let byte = meta.advice_column();
+        let data_rlc = meta.advice_column_in(SecondPhase);
+        let word = meta.advice_column();
+        let is_success = meta.advice_column();
+        let inv = meta.advice_column();
+        let x = meta.advice_column();
+        let y = meta.advice_column();
+        let z = meta.advice_column();
+        let out = meta.advice_column();
+        let bits_x = array_init::array_init(|_| meta.advice_column());
+        let bits_y = array_init::array_init(|_| meta.advice_column());
+        let bits_sum = array_init::array_init(|_| meta.advice_column());
+
+        // The words are copied between the data rows and the operations, and
+        // into the table.
+        meta.enable_constant(constants);
+        for column in [is_enabled, data_rlc, word, is_success, x, y, z, out] {
+            meta.enable_equality(column);
+        }
+
+        let query_bits = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>]| {
+            columns
+                .iter()
+                .map(|&column| meta.query_advice(column, Rotation::cur()))
+                .collect::<Vec<_>>()
+        };
+
+        meta.lookup("byte range", |meta| {
+            let q_data = meta.query_fixed(q_data, Rotation::cur());
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(q_data * byte, u8_table)]
+        });
+
+        // The data rows accumulate the RLC of the input and of the output
+        // bytes, starting again at the first byte of the output.
+        meta.create_gate("data rlc", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_data_first = meta.query_fixed(q_data_first, Rotation::cur());
+            let byte = meta.query_advice(byte, Rotation::cur());
+            let data_rlc_prev = meta.query_advice(data_rlc, Rotation::prev());
+            let data_rlc = meta.query_advice(data_rlc, Rotation::cur());
+            cb.require_equal(
+                "data_rlc = select(q_data_first, byte, data_rlc_prev * r + byte)",
+                data_rlc,
+                select::expr(
+                    q_data_first,
+                    byte.expr(),
+                    data_rlc_prev * challenges.keccak_input() + byte,
+                ),
+            );
+            cb.gate(meta.query_fixed(q_data, Rotation::cur()))
+        });
+
+        // The words are composed from the bytes up to their row, in little
+        // endian but for the big endian number of rounds.
+        meta.create_gate("data words", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let word = meta.query_advice(word, Rotation::cur());
+            let bytes = (0..NUM_BYTES_PER_WORD)
+                .map(|idx| {
+                    meta.query_advice(byte, Rotation((idx + 1) as i32 - NUM_BYTES_PER_WORD as i32))
+                })
+                .collect::<Vec<_>>();
+            let compose = |bytes: &[Expression<F>]| {
+                bytes
+                    .iter()
+                    .fold(0.expr(), |acc, byte| acc * 256.expr() + byte.expr())
+            };
+
+            cb.condition(meta.query_fixed(q_call, Rotation::cur()), |cb| {
+                cb.require_boolean(
+                    "is_enabled is boolean",
+                    meta.query_advice(is_enabled, Rotation::cur()),
+                );
+            });
+            cb.condition(meta.query_fixed(q_rounds, Rotation::cur()), |cb| {
+                cb.require_equal(
+                    "rounds from big endian bytes",
+                    word.expr(),
+                    compose(&bytes[NUM_BYTES_PER_WORD - NUM_ROUNDS_BYTES..]),
+                );
+            });
+            cb.condition(meta.query_fixed(q_word, Rotation::cur()), |cb| {
+                let bytes_be = bytes.iter().rev().cloned().collect::<Vec<_>>();
+                cb.require_equal(
+                    "word from little endian bytes",
+                    word.expr(),
+                    compose(&bytes_be),
+                );
+            });
+            cb.condition(meta.query_fixed(q_final_flag, Rotation::cur()), |cb| {
+                let byte = bytes.last().unwrap();
+                let is_success = meta.query_advice(is_success, Rotation::cur());
+                let inv = meta.query_advice(inv, Rotation::cur());
+                let is_not_boolean = byte.expr() * (byte.expr() - 1.expr());
+                cb.require_boolean("is_success is boolean", is_success.expr());
+                cb.condition(is_success.expr(), |cb| {
+                    cb.require_zero(
+                        "final block flag is boolean for a successful call",
+                        is_not_boolean.expr(),
+                    );
+                });
+                cb.condition(not::expr(is_success.expr()), |cb| {
+                    cb.require_equal(
+                        "final block flag isn't boolean for a failing call",
+                        is_not_boolean * inv,
+                        1.expr(),
+                    );
+                });
+                cb.require_equal(
+                    "final block flag from its byte",
+                    word.expr(),
+                    is_success * byte.expr(),
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("operations", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_add = meta.query_fixed(q_add, Rotation::cur());
+            let q_add3 = meta.query_fixed(q_add3, Rotation::cur());
+            let q_xors = q_xors.map(|q_xor| meta.query_fixed(q_xor, Rotation::cur()));
+            let q_select = meta.query_fixed(q_select, Rotation::cur());
+            let q_flag = meta.query_fixed(q_flag, Rotation::cur());
+            let [x, y, z, out] =
+                [x, y, z, out].map(|column| meta.query_advice(column, Rotation::cur()));
+            let bits_x = query_bits(meta, &bits_x);
+            let bits_y = query_bits(meta, &bits_y);
+            let bits_sum = query_bits(meta, &bits_sum);
+
+            let q_xor = sum::expr(q_xors.iter());
+            cb.condition(q_add.expr() + q_add3.expr() + q_xor.expr(), |cb| {
+                for bit in bits_x.iter().chain(bits_y.iter()).chain(bits_sum.iter()) {
+                    cb.require_boolean("bit boolean", bit.expr());
+                }
+            });
+
+            // Additions modulo 2**64 of 2 or 3 words
+            cb.condition(q_add.expr(), |cb| {
+                cb.require_equal("add", decode::expr(&bits_sum), x.expr() + y.expr());
+            });
+            cb.condition(q_add3.expr(), |cb| {
+                cb.require_equal(
+                    "add3",
+                    decode::expr(&bits_sum),
+                    x.expr() + y.expr() + z.expr(),
+                );
+            });
+            cb.condition(q_add + q_add3, |cb| {
+                cb.require_equal(
+                    "add result without carries",
+                    out.expr(),
+                    decode::expr(&bits_sum[..NUM_BITS_PER_WORD]),
+                );
+            });
+
+            // Xor of 2 words followed by a right rotation
+            cb.condition(q_xor, |cb| {
+                cb.require_equal("xor x bits", decode::expr(&bits_x), x.expr());
+                cb.require_equal("xor y bits", decode::expr(&bits_y), y.expr());
+            });
+            let bits_xor = bits_x
+                .iter()
+                .zip(bits_y.iter())
+                .map(|(x, y)| xor::expr(x, y))
+                .collect::<Vec<_>>();
+            for (q_xor, rotation) in q_xors.into_iter().zip(ROTATIONS) {
+                cb.condition(q_xor, |cb| {
+                    cb.require_equal(
+                        "xor and rotate right",
+                        out.expr(),
+                        decode::expr(&rotate::expr(&bits_xor, rotation)),
+                    );
+                });
+            }
+
+            // Selection of a word by a boolean
+            cb.condition(q_select, |cb| {
+                cb.require_boolean("select condition is boolean", z.expr());
+                cb.require_equal(
+                    "select",
+                    out.expr(),
+                    select::expr(z.expr(), x.expr(), y.expr()),
+                );
+            });
+
+            // Round flags, which can only be enabled after an enabled flag, and
+            // their count
+            cb.condition(q_flag, |cb| {
+                cb.require_boolean("round flag is boolean", out.expr());
+                cb.require_zero(
+                    "round flag is disabled after a disabled flag",
+                    out.expr() * not::expr(x),
+                );
+                cb.require_equal("round flags count", z, y + out);
+            });
+            cb.gate(1.expr())
+        });
+
+        blake2f_table.annotate_columns(meta);
+
+        Blake2fCircuitConfig {
+            q_call,
+            q_data,
+            q_data_first,
+            q_rounds,
+            q_word,
+            q_final_flag,
+            q_add,
+            q_add3,
+            q_xors,
+            q_select,
+            q_flag,
+            constants,
+            u8_table,
+            is_enabled,
+            byte,
+            data_rlc,
+            word,
+            is_success,
+            inv,
+            x,
+            y,
+            z,
+            out,
+            bits_x,
+            bits_y,
+            bits_sum,
+            blake2f_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Blake2fCircuitConfig<F> {
+    /// Load the byte range table
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "u8 table",
+            |mut table| {
+                for value in 0..256 {
+                    table.assign_cell(
+                        || format!("u8 table row {}", value),
+                        self.u8_table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// A 64 bits word of the compression function, with its assigned cell.
+#[derive(Clone, Debug)]
+struct Blake2fWord<F: Field> {
+    cell: AssignedCell<F, F>,
+    value: u64,
+}
+
+/// An operand of an operation row, either copied from a word computed before
+/// or witnessed in the row.
+enum Operand<'a, F: Field> {
+    Word(&'a Blake2fWord<F>),
+    Value(u64),
+}
+
+impl<F: Field> Operand<'_, F> {
+    fn value(&self) -> u64 {
+        match self {
+            Self::Word(word) => word.value,
+            Self::Value(value) => *value,
+        }
+    }
+}
+
+/// Assigned words of the data rows of a call.
+#[derive(Debug)]
+struct AssignedBlake2fData<F: Field> {
+    is_enabled: AssignedCell<F, F>,
+    input_rlc: AssignedCell<F, F>,
+    output_rlc: AssignedCell<F, F>,
+    rounds: Blake2fWord<F>,
+    state: Vec<Blake2fWord<F>>,
+    message: Vec<Blake2fWord<F>>,
+    offset_counter: Vec<Blake2fWord<F>>,
+    final_flag: Blake2fWord<F>,
+    is_success: Blake2fWord<F>,
+    output: Vec<Blake2fWord<F>>,
+}
+
+/// Returns the words of the input or output bytes starting at `offset`
+fn words_at(bytes: &[u8], offset: usize, num_words: usize) -> Vec<u64> {
+    bytes[offset..offset + num_words * NUM_BYTES_PER_WORD]
+        .chunks(NUM_BYTES_PER_WORD)
+        .map(|word| u64::from_le_bytes(word.try_into().unwrap()))
+        .collect()
+}
+
+/// Computes the output of the compression function F on a blake2f input.
+pub(crate) fn blake2f(input: &[u8]) -> Vec<u8> {
+    let rounds = u32::from_be_bytes(input[..NUM_ROUNDS_BYTES].try_into().unwrap());
+    let state = words_at(input, STATE_OFFSET, NUM_STATE_WORDS);
+    let message = words_at(input, MESSAGE_OFFSET, NUM_MESSAGE_WORDS);
+    let offset_counter = words_at(input, OFFSET_COUNTER_OFFSET, NUM_OFFSET_WORDS);
+
+    let mut v = [&state[..], &IV[..]].concat();
+    v[12] ^= offset_counter[0];
+    v[13] ^= offset_counter[1];
+    if input[FINAL_FLAG_OFFSET] == 1 {
+        v[14] = !v[14];
+    }
+    for round in 0..rounds as usize {
+        let s = SIGMA[round % SIGMA.len()];
+        for (g, &[a, b, c, d]) in G_WORDS.iter().enumerate() {
+            for (x, [r1, r2]) in [
+                (message[s[2 * g]], [32, 24]),
+                (message[s[2 * g + 1]], [16, 63]),
+            ] {
+                v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+                v[d] = (v[d] ^ v[a]).rotate_right(r1);
+                v[c] = v[c].wrapping_add(v[d]);
+                v[b] = (v[b] ^ v[c]).rotate_right(r2);
+            }
+        }
+    }
+
+    (0..NUM_STATE_WORDS)
+        .flat_map(|i| (state[i] ^ v[i] ^ v[i + NUM_STATE_WORDS]).to_le_bytes())
+        .collect()
+}
+
+/// BLAKE2F Circuit for verifying the calls to the blake2f precompiled
+/// contract
+#[derive(Clone, Default, Debug)]
+pub struct Blake2fCircuit<F: Field> {
+    /// Max number of supported blake2f calls
+    pub max_blake2f: usize,
+    /// Max number of rounds of the supported blake2f calls
+    pub max_blake2f_rounds: usize,
+    /// Precompile events of the blake2f calls
+    pub events: Vec<PrecompileEvent>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Blake2fCircuit<F> {
+    /// Return a new Blake2fCircuit
+    pub fn new(
+        max_blake2f: usize,
+        max_blake2f_rounds: usize,
+        events: Vec<PrecompileEvent>,
+    ) -> Self {
+        Self {
+            max_blake2f,
+            max_blake2f_rounds,
+            events,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(num_blake2f: usize, max_blake2f_rounds: usize) -> usize {
+        // The byte range table has a row per byte value, and the operation
+        // rows and the data rows of the calls are in distinct columns.
+        let num_rows_per_call = std::cmp::max(
+            num_ops_rows_per_call(max_blake2f_rounds),
+            NUM_DATA_ROWS_PER_CALL,
+        );
+        std::cmp::max(1 << 8, num_rows_per_call * num_blake2f)
+    }
+
+    /// Assign the data rows of a call, returning the words used by the
+    /// operations.
+    fn assign_data(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        is_enabled: bool,
+        bytes: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedBlake2fData<F>, Error> {
+        region.assign_fixed(
+            || format!("q_call {}", offset),
+            config.q_call,
+            offset,
+            || Value::known(F::one()),
+        )?;
+        let is_enabled_cell = region.assign_advice(
+            || format!("is_enabled {}", offset),
+            config.is_enabled,
+            offset,
+            || Value::known(F::from(is_enabled)),
+        )?;
+
+        let mut data_rlc = Value::known(F::zero());
+        let mut rlc_cells = Vec::new();
+        let mut words = Vec::new();
+        for (idx, &byte) in bytes.iter().enumerate() {
+            let row = offset + idx;
+            let is_first = idx == 0 || idx == OUTPUT_OFFSET;
+            // The rows composing a word, with its value
+            let word = if idx == ROUNDS_OFFSET + NUM_ROUNDS_BYTES - 1 {
+                let rounds = u32::from_be_bytes(bytes[..NUM_ROUNDS_BYTES].try_into().unwrap());
+                Some((config.q_rounds, rounds as u64))
+            } else if idx == FINAL_FLAG_OFFSET {
+                // The flag of a failing call is 0.
+                Some((config.q_final_flag, if byte <= 1 { byte as u64 } else { 0 }))
+            } else {
+                let word_start = if idx < OUTPUT_OFFSET {
+                    STATE_OFFSET
+                } else {
+                    OUTPUT_OFFSET
+                };
+                (idx >= word_start && (idx + 1 - word_start) % NUM_BYTES_PER_WORD == 0).then(|| {
+                    let start = idx + 1 - NUM_BYTES_PER_WORD;
+                    (config.q_word, words_at(bytes, start, 1)[0])
+                })
+            };
+
+            for (column, enabled) in [(config.q_data, true), (config.q_data_first, is_first)]
+                .into_iter()
+                .chain(word.map(|(q_word, _)| (q_word, true)))
+            {
+                if enabled {
+                    region.assign_fixed(
+                        || format!("data selector {}", row),
+                        column,
+                        row,
+                        || Value::known(F::one()),
+                    )?;
+                }
+            }
+
+            data_rlc = if is_first {
+                Value::known(F::from(byte as u64))
+            } else {
+                data_rlc
+                    .zip(challenges.keccak_input())
+                    .map(|(data_rlc, r)| data_rlc * r + F::from(byte as u64))
+            };
+            region.assign_advice(
+                || format!("byte {}", row),
+                config.byte,
+                row,
+                || Value::known(F::from(byte as u64)),
+            )?;
+            let rlc_cell = region.assign_advice(
+                || format!("data_rlc {}", row),
+                config.data_rlc,
+                row,
+                || data_rlc,
+            )?;
+            if idx + 1 == OUTPUT_OFFSET || idx + 1 == bytes.len() {
+                rlc_cells.push(rlc_cell);
+            }
+            let value = word.map_or(0, |(_, value)| value);
+            let cell = region.assign_advice(
+                || format!("word {}", row),
+                config.word,
+                row,
+                || Value::known(F::from(value)),
+            )?;
+            if word.is_some() {
+                words.push(Blake2fWord { cell, value });
+            }
+        }
+
+        // The call fails when the final block flag isn't boolean.
+        let row = offset + FINAL_FLAG_OFFSET;
+        let final_flag = F::from(bytes[FINAL_FLAG_OFFSET] as u64);
+        let is_success = bytes[FINAL_FLAG_OFFSET] <= 1;
+        let is_success_cell = region.assign_advice(
+            || format!("is_success {}", row),
+            config.is_success,
+            row,
+            || Value::known(F::from(is_success)),
+        )?;
+        region.assign_advice(
+            || format!("inv {}", row),
+            config.inv,
+            row,
+            || {
+                Value::known(
+                    (final_flag * (final_flag - F::one()))
+                        .invert()
+                        .unwrap_or(F::zero()),
+                )
+            },
+        )?;
+
+        // The words are in the order of the data rows
+        let [input_rlc, output_rlc]: [AssignedCell<F, F>; 2] = rlc_cells.try_into().unwrap();
+        let mut words = words.into_iter();
+        let rounds = words.next().unwrap();
+        let state = words.by_ref().take(NUM_STATE_WORDS).collect();
+        let message = words.by_ref().take(NUM_MESSAGE_WORDS).collect();
+        let offset_counter = words.by_ref().take(NUM_OFFSET_WORDS).collect();
+        let final_flag = words.next().unwrap();
+        let output = words.collect();
+
+        Ok(AssignedBlake2fData {
+            is_enabled: is_enabled_cell,
+            input_rlc,
+            output_rlc,
+            rounds,
+            state,
+            message,
+            offset_counter,
+            final_flag,
+            is_success: Blake2fWord {
+                cell: is_success_cell,
+                value: is_success as u64,
+            },
+            output,
+        })
+    }
+
+    /// Assign an operation row, returning its `[x, y, z, out]` words.
+    fn assign_op(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        selector: Column<Fixed>,
+        operands: [Operand<'_, F>; 3],
+        out: u64,
+    ) -> Result<[Blake2fWord<F>; 4], Error> {
+        let row = *offset;
+        *offset += 1;
+        region.assign_fixed(
+            || format!("operation selector {}", row),
+            selector,
+            row,
+            || Value::known(F::one()),
+        )?;
+
+        let [x, y, z] = [0, 1, 2].map(|idx| operands[idx].value());
+        let sum = x as u128 + y as u128 + z as u128;
+        for (name, columns, bits) in [
+            (
+                "x",
+                &config.bits_x[..],
+                &to_le_bits::<NUM_BITS_PER_WORD>(x as u128)[..],
+            ),
+            (
+                "y",
+                &config.bits_y[..],
+                &to_le_bits::<NUM_BITS_PER_WORD>(y as u128)[..],
+            ),
+            (
+                "sum",
+                &config.bits_sum[..],
+                &to_le_bits::<NUM_BITS_PER_WORD_SUM>(sum)[..],
+            ),
+        ] {
+            for (idx, (&column, &bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("bits {} {} {}", name, idx, row),
+                    column,
+                    row,
+                    || Value::known(F::from(bit)),
+                )?;
+            }
+        }
+
+        let mut words = Vec::new();
+        for (name, column, operand) in [
+            ("x", config.x, &operands[0]),
+            ("y", config.y, &operands[1]),
+            ("z", config.z, &operands[2]),
+            ("out", config.out, &Operand::Value(out)),
+        ] {
+            let cell = match operand {
+                Operand::Word(word) => word.cell.copy_advice(
+                    || format!("operand {} {}", name, row),
+                    region,
+                    column,
+                    row,
+                )?,
+                Operand::Value(value) => region.assign_advice(
+                    || format!("operand {} {}", name, row),
+                    column,
+                    row,
+                    || Value::known(F::from(*value)),
+                )?,
+            };
+            words.push(Blake2fWord {
+                cell,
+                value: operand.value(),
+            });
+        }
+        Ok(words.try_into().unwrap())
+    }
+
+    /// Assign a constant word, in an operation row without any operation.
+    fn assign_constant(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        value: u64,
+    ) -> Result<Blake2fWord<F>, Error> {
+        let row = *offset;
+        *offset += 1;
+        let cell = region.assign_advice_from_constant(
+            || format!("constant {}", row),
+            config.out,
+            row,
+            F::from(value),
+        )?;
+        Ok(Blake2fWord { cell, value })
+    }
+
+    /// Assign the addition modulo 2**64 of `x`, `y` and optionally `z`.
+    fn assign_add(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        x: &Blake2fWord<F>,
+        y: &Blake2fWord<F>,
+        z: Option<&Blake2fWord<F>>,
+    ) -> Result<Blake2fWord<F>, Error> {
+        let (selector, z) = match z {
+            Some(z) => (config.q_add3, Operand::Word(z)),
+            None => (config.q_add, Operand::Value(0)),
+        };
+        let out = x.value.wrapping_add(y.value).wrapping_add(z.value());
+        let [_, _, _, out] = self.assign_op(
+            config,
+            region,
+            offset,
+            selector,
+            [Operand::Word(x), Operand::Word(y), z],
+            out,
+        )?;
+        Ok(out)
+    }
+
+    /// Assign the xor of `x` and `y` rotated right by `rotation` bits.
+    fn assign_xor(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        x: &Blake2fWord<F>,
+        y: &Blake2fWord<F>,
+        rotation: usize,
+    ) -> Result<Blake2fWord<F>, Error> {
+        let idx = ROTATIONS.iter().position(|&r| r == rotation).unwrap();
+        let out = (x.value ^ y.value).rotate_right(rotation as u32);
+        let [_, _, _, out] = self.assign_op(
+            config,
+            region,
+            offset,
+            config.q_xors[idx],
+            [Operand::Word(x), Operand::Word(y), Operand::Value(0)],
+            out,
+        )?;
+        Ok(out)
+    }
+
+    /// Assign the selection of `x` when `condition` is 1, and `y` otherwise.
+    fn assign_select(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        condition: &Blake2fWord<F>,
+        x: &Blake2fWord<F>,
+        y: &Blake2fWord<F>,
+    ) -> Result<Blake2fWord<F>, Error> {
+        let out = if condition.value == 1 {
+            x.value
+        } else {
+            y.value
+        };
+        let [_, _, _, out] = self.assign_op(
+            config,
+            region,
+            offset,
+            config.q_select,
+            [Operand::Word(x), Operand::Word(y), Operand::Word(condition)],
+            out,
+        )?;
+        Ok(out)
+    }
+
+    /// Assign the flag of a round following a round with `flag` and a count
+    /// of enabled flags `count`, returning the new flag and count.
+    fn assign_flag(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        flag: &Blake2fWord<F>,
+        count: &Blake2fWord<F>,
+        is_active: bool,
+    ) -> Result<(Blake2fWord<F>, Blake2fWord<F>), Error> {
+        let [_, _, count, flag] = self.assign_op(
+            config,
+            region,
+            offset,
+            config.q_flag,
+            [
+                Operand::Word(flag),
+                Operand::Word(count),
+                Operand::Value(count.value + is_active as u64),
+            ],
+            is_active as u64,
+        )?;
+        Ok((flag, count))
+    }
+
+    /// Assign the mixing function G on the work words `[a, b, c, d]` with the
+    /// message words `x` and `y`.
+    #[allow(clippy::too_many_arguments)]
+    fn assign_g(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        v: &mut [Blake2fWord<F>],
+        [a, b, c, d]: [usize; 4],
+        x: &Blake2fWord<F>,
+        y: &Blake2fWord<F>,
+    ) -> Result<(), Error> {
+        for (m, [r1, r2]) in [(x, [32, 24]), (y, [16, 63])] {
+            v[a] = self.assign_add(config, region, offset, &v[a], &v[b], Some(m))?;
+            v[d] = self.assign_xor(config, region, offset, &v[d], &v[a], r1)?;
+            v[c] = self.assign_add(config, region, offset, &v[c], &v[d], None)?;
+            v[b] = self.assign_xor(config, region, offset, &v[b], &v[c], r2)?;
+        }
+        Ok(())
+    }
+
+    /// Assign the operation rows of the compression function of a call.
+    fn assign_compression(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        region: &mut Region<'_, F>,
+        offset: &mut usize,
+        data: &AssignedBlake2fData<F>,
+    ) -> Result<(), Error> {
+        let mut constant = |value| self.assign_constant(config, region, offset, value);
+        let iv = [IV[0], IV[1], IV[2], IV[3], IV[7]]
+            .into_iter()
+            .map(&mut constant)
+            .collect::<Result<Vec<_>, _>>()?;
+        let [iv4, iv5, iv6, not_iv6] = [IV[4], IV[5], IV[6], !IV[6]]
+            .map(&mut constant)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .unwrap();
+        let one = constant(1)?;
+        let zero = constant(0)?;
+
+        // Initialize the work vector from the state, the initialization vector,
+        // the offset counter and the final block flag.
+        let mut v = data.state.clone();
+        v.extend_from_slice(&iv[..4]);
+        v.push(self.assign_xor(config, region, offset, &iv4, &data.offset_counter[0], 0)?);
+        v.push(self.assign_xor(config, region, offset, &iv5, &data.offset_counter[1], 0)?);
+        v.push(self.assign_select(config, region, offset, &data.final_flag, &not_iv6, &iv6)?);
+        v.push(iv[4].clone());
+
+        let (mut flag, mut count) = (one, zero.clone());
+        for round in 0..self.max_blake2f_rounds {
+            let s = SIGMA[round % SIGMA.len()];
+            let mut w = v.clone();
+            for (g, &words) in G_WORDS.iter().enumerate() {
+                let [x, y] = [s[2 * g], s[2 * g + 1]].map(|idx| &data.message[idx]);
+                self.assign_g(config, region, offset, &mut w, words, x, y)?;
+            }
+            let is_active = (round as u64) < data.rounds.value;
+            (flag, count) = self.assign_flag(config, region, offset, &flag, &count, is_active)?;
+            v = v
+                .iter()
+                .zip(w.iter())
+                .map(|(v, w)| self.assign_select(config, region, offset, &flag, w, v))
+                .collect::<Result<_, _>>()?;
+        }
+        region.constrain_equal(count.cell.cell(), data.rounds.cell.cell())?;
+
+        for (i, output) in data.output.iter().enumerate() {
+            let h = self.assign_xor(config, region, offset, &data.state[i], &v[i], 0)?;
+            let h = self.assign_xor(config, region, offset, &h, &v[i + NUM_STATE_WORDS], 0)?;
+            // A failing call has an output of zeros.
+            let h = self.assign_select(config, region, offset, &data.is_success, &h, &zero)?;
+            region.constrain_equal(h.cell.cell(), output.cell.cell())?;
+        }
+        Ok(())
+    }
+
+    /// Assign the BLAKE2F table rows, copied from the data rows of the calls.
+    fn assign_blake2f_table(
+        &self,
+        config: &Blake2fCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_data: &[AssignedBlake2fData<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "blake2f table",
+            |mut region| {
+                let blake2f_table_columns =
+                    <Blake2fTable as LookupTable<F>>::advice_columns(&config.blake2f_table);
+                for &column in blake2f_table_columns.iter() {
+                    region.assign_advice(
+                        || "blake2f table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                for (offset, data) in assigned_data.iter().enumerate() {
+                    for (&column, assigned) in blake2f_table_columns.iter().zip_eq([
+                        &data.is_enabled,
+                        &data.input_rlc,
+                        &data.output_rlc,
+                        &data.rounds.cell,
+                        &data.is_success.cell,
+                    ]) {
+                        assigned.copy_advice(
+                            || format!("blake2f table row {}", offset + 1),
+                            &mut region,
+                            column,
+                            offset + 1,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Returns the little endian bits of a word, or of the sum of up to 3 words
+fn to_le_bits<const N: usize>(value: u128) -> [bool; N] {
+    debug_assert!(value >> N == 0, "value doesn't fit in {} bits", N);
+    array_init::array_init(|i| (value >> i) & 1 == 1)
+}
+
+/// Return the precompile events of the blake2f calls of the block.
+fn blake2f_events<F: Field>(block: &witness::Block<F>) -> Vec<PrecompileEvent> {
+    block
+        .precompile_events
+        .iter()
+        .filter(|event| event.precompile == PrecompileCalls::Blake2F)
+        .cloned()
+        .collect()
+}
+
+impl<F: Field> SubCircuit<F> for Blake2fCircuit<F> {
+    type Config = Blake2fCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Column byte is queried at 8 distinct rotations, so returns
+        // (8 - 3) + 6 unusable rows.
+        11
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_blake2f,
+            block.circuits_params.max_blake2f_rounds,
+            blake2f_events(block),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(
+                blake2f_events(block).len(),
+                block.circuits_params.max_blake2f_rounds,
+            ),
+            Self::min_num_rows(
+                block.circuits_params.max_blake2f,
+                block.circuits_params.max_blake2f_rounds,
+            ),
+        )
+    }
+
+    /// Make the assignments to the Blake2fCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.events.len() > self.max_blake2f {
+            error!(
+                "blake2f calls = {} > max_blake2f = {}",
+                self.events.len(),
+                self.max_blake2f
+            );
+            return Err(Error::Synthesis);
+        }
+        for event in self.events.iter() {
+            // The calls on other input lengths have no event.
+            if event.input.len() != BLAKE2F_INPUT_LEN {
+                error!("blake2f input of {} bytes", event.input.len());
+                return Err(Error::Synthesis);
+            }
+            let rounds = u32::from_be_bytes(event.input[..NUM_ROUNDS_BYTES].try_into().unwrap());
+            if rounds as usize > self.max_blake2f_rounds {
+                error!(
+                    "blake2f call rounds = {} > max_blake2f_rounds = {}",
+                    rounds, self.max_blake2f_rounds
+                );
+                return Err(Error::Synthesis);
+            }
+        }
+
+        config.load_aux_tables(layouter)?;
+
+        // The calls beyond the number of events up to the maximum are padding
+        // calls on zeros, in disabled rows.
+        let assigned_data = layouter.assign_region(
+            || "blake2f data",
+            |mut region| {
+                let mut assigned_data = Vec::new();
+                for idx in 0..self.max_blake2f {
+                    let (is_enabled, input, output) = match self.events.get(idx) {
+                        Some(event) => {
+                            // The output of a failing call is empty.
+                            let mut output = event.output.clone();
+                            output.resize(OUTPUT_LEN, 0);
+                            (true, event.input.clone(), output)
+                        }
+                        None => {
+                            let input = vec![0; BLAKE2F_INPUT_LEN];
+                            let output = blake2f(&input);
+                            (false, input, output)
+                        }
+                    };
+                    let bytes = [input, output].concat();
+                    assigned_data.push(self.assign_data(
+                        config,
+                        &mut region,
+                        idx * NUM_DATA_ROWS_PER_CALL,
+                        is_enabled,
+                        &bytes,
+                        challenges,
+                    )?);
+                }
+                Ok(assigned_data)
+            },
+        )?;
+
+        layouter.assign_region(
+            || "blake2f compression",
+            |mut region| {
+                let mut offset = 0;
+                for data in assigned_data.iter() {
+                    self.assign_compression(config, &mut region, &mut offset, data)?;
+                }
+                log::debug!("blake2f compression: {} rows", offset);
+                Ok(())
+            },
+        )?;
+
+        self.assign_blake2f_table(config, layouter, &assigned_data)
+    }
+}
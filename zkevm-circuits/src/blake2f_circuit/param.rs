@@ -0,0 +1,87 @@
+use bus_mapping::precompile::BLAKE2F_INPUT_LEN;
+
+pub(crate) const MAX_DEGREE: usize = 5;
+
+pub(crate) const NUM_BITS_PER_WORD: usize = 64;
+// An addition is the sum of up to 3 words, which needs 2 extra carry bits.
+pub(crate) const NUM_BITS_PER_WORD_SUM: usize = NUM_BITS_PER_WORD + 2;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 8;
+pub(crate) const NUM_STATE_WORDS: usize = 8;
+pub(crate) const NUM_MESSAGE_WORDS: usize = 16;
+pub(crate) const NUM_OFFSET_WORDS: usize = 2;
+pub(crate) const NUM_WORK_WORDS: usize = 2 * NUM_STATE_WORDS;
+
+// Offsets of the fields of the input, followed by the output
+pub(crate) const ROUNDS_OFFSET: usize = 0;
+pub(crate) const NUM_ROUNDS_BYTES: usize = 4;
+pub(crate) const STATE_OFFSET: usize = ROUNDS_OFFSET + NUM_ROUNDS_BYTES;
+pub(crate) const MESSAGE_OFFSET: usize = STATE_OFFSET + NUM_STATE_WORDS * NUM_BYTES_PER_WORD;
+pub(crate) const OFFSET_COUNTER_OFFSET: usize =
+    MESSAGE_OFFSET + NUM_MESSAGE_WORDS * NUM_BYTES_PER_WORD;
+pub(crate) const FINAL_FLAG_OFFSET: usize =
+    OFFSET_COUNTER_OFFSET + NUM_OFFSET_WORDS * NUM_BYTES_PER_WORD;
+pub(crate) const OUTPUT_OFFSET: usize = BLAKE2F_INPUT_LEN;
+pub(crate) const OUTPUT_LEN: usize = NUM_STATE_WORDS * NUM_BYTES_PER_WORD;
+// A call takes a row per byte of its input and output in the data region.
+pub(crate) const NUM_DATA_ROWS_PER_CALL: usize = BLAKE2F_INPUT_LEN + OUTPUT_LEN;
+
+// The mixing function G is made of 8 operations, and each round of 8 calls to
+// G is followed by a row updating the round flags and a row per work word
+// keeping it unchanged when the round isn't active.
+pub(crate) const NUM_G_OPS: usize = 8;
+pub(crate) const NUM_G_PER_ROUND: usize = 8;
+pub(crate) const NUM_OPS_ROWS_PER_ROUND: usize = NUM_G_PER_ROUND * NUM_G_OPS + 1 + NUM_WORK_WORDS;
+// The constant rows hold the initialization vector words used as is, the
+// initialization vector words combined with the offset counter and the final
+// block flag, and the starting round flag and count.
+pub(crate) const NUM_CONSTANT_ROWS: usize = 5 + 4 + 2;
+pub(crate) const NUM_INIT_ROWS: usize = 3;
+// Each output word is the xor of 3 words, selected by the failure flag.
+pub(crate) const NUM_OUTPUT_ROWS: usize = 3 * NUM_STATE_WORDS;
+
+// A call laid out for `max_rounds` rounds takes this number of operation rows.
+pub(crate) const fn num_ops_rows_per_call(max_rounds: usize) -> usize {
+    NUM_CONSTANT_ROWS + NUM_INIT_ROWS + max_rounds * NUM_OPS_ROWS_PER_ROUND + NUM_OUTPUT_ROWS
+}
+
+// Rotations to the right of the xor operations, the last one only xoring
+pub(crate) const ROTATIONS: [usize; 5] = [32, 24, 16, 63, 0];
+
+// Initialization vector
+pub(crate) const IV: [u64; NUM_STATE_WORDS] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+// Message word schedule of the rounds, repeated every 10 rounds
+pub(crate) const SIGMA: [[usize; NUM_MESSAGE_WORDS]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+// Work words mixed by the calls to G of a round, on the columns and then on
+// the diagonals
+pub(crate) const G_WORDS: [[usize; 4]; NUM_G_PER_ROUND] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
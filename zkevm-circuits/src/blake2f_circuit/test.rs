@@ -0,0 +1,163 @@
+#![allow(unused_imports)]
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn blake2f_circuit_unusable_rows() {
+    assert_eq!(
+        Blake2fCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Blake2fCircuit::<Fr>>(),
+    )
+}
+
+// Rounds of the EIP-152 test vectors, which the calls are laid out for.
+const MAX_BLAKE2F_ROUNDS: usize = 12;
+
+fn run<F: Field>(
+    events: Vec<PrecompileEvent>,
+    max_blake2f: usize,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        Blake2fCircuit::<Fr>::unusable_rows()
+            + Blake2fCircuit::<Fr>::min_num_rows(max_blake2f, MAX_BLAKE2F_ROUNDS),
+    );
+    let circuit = Blake2fCircuit::<F>::new(max_blake2f, MAX_BLAKE2F_ROUNDS, events);
+
+    let prover = match MockProver::run(k, &circuit, vec![]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Returns the input of a blake2f call, from the test vectors of EIP-152 with
+/// the message "abc".
+fn blake2f_input(rounds: u32, final_flag: u8) -> Vec<u8> {
+    let mut input = rounds.to_be_bytes().to_vec();
+    input.extend(
+        hex::decode(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+             d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        )
+        .unwrap(),
+    );
+    input.extend(b"abc");
+    input.resize(OFFSET_COUNTER_OFFSET, 0);
+    input.extend(3u64.to_le_bytes());
+    input.extend(0u64.to_le_bytes());
+    input.push(final_flag);
+    input
+}
+
+/// Returns the event of a blake2f call, which fails with an empty output when
+/// the final block flag is neither 0 nor 1.
+fn blake2f_event(rounds: u32, final_flag: u8) -> PrecompileEvent {
+    let input = blake2f_input(rounds, final_flag);
+    let is_success = final_flag <= 1;
+    PrecompileEvent {
+        precompile: PrecompileCalls::Blake2F,
+        output: if is_success { blake2f(&input) } else { vec![] },
+        input,
+        gas_cost: rounds as u64,
+        is_success,
+    }
+}
+
+#[test]
+fn blake2f_output_values() {
+    for (rounds, final_flag, output) in [
+        (
+            0,
+            1,
+            "08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+             d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b",
+        ),
+        (
+            12,
+            1,
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+        ),
+        (
+            12,
+            0,
+            "75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d28752\
+             98743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735",
+        ),
+        (
+            1,
+            1,
+            "b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fb\
+             a551b3a8333bcdf5f2f7e08993d53923de3d64fcc68c034e717b9293fed7a421",
+        ),
+    ] {
+        assert_eq!(
+            hex::encode(blake2f(&blake2f_input(rounds, final_flag))),
+            output
+        );
+    }
+}
+
+#[test]
+fn blake2f_circuit_max_rounds() {
+    let events = vec![blake2f_event(12, 1), blake2f_event(12, 0)];
+
+    assert_eq!(run::<Fr>(events, 2), Ok(()));
+}
+
+#[test]
+fn blake2f_circuit_few_rounds() {
+    let events = vec![blake2f_event(0, 1), blake2f_event(1, 1)];
+
+    assert_eq!(run::<Fr>(events, 2), Ok(()));
+}
+
+#[test]
+fn blake2f_circuit_padding() {
+    let events = vec![blake2f_event(5, 0)];
+
+    // The last call is padding.
+    assert_eq!(run::<Fr>(events, 2), Ok(()));
+}
+
+#[test]
+fn blake2f_circuit_invalid_final_flag() {
+    let events = vec![blake2f_event(12, 2), blake2f_event(0, 0xff)];
+
+    assert_eq!(run::<Fr>(events, 2), Ok(()));
+}
+
+#[test]
+fn blake2f_circuit_wrong_output() {
+    let mut event = blake2f_event(12, 1);
+    event.output[0] ^= 1;
+
+    assert!(run::<Fr>(vec![event], 1).is_err());
+}
+
+#[test]
+fn blake2f_circuit_rounds_over_max() {
+    let events = vec![blake2f_event(MAX_BLAKE2F_ROUNDS as u32 + 1, 1)];
+    let circuit = Blake2fCircuit::<Fr>::new(1, MAX_BLAKE2F_ROUNDS, events);
+
+    let k = log2_ceil(
+        Blake2fCircuit::<Fr>::unusable_rows()
+            + Blake2fCircuit::<Fr>::min_num_rows(1, MAX_BLAKE2F_ROUNDS),
+    );
+    assert!(MockProver::run(k, &circuit, vec![]).is_err());
+}
+
+#[test]
+fn blake2f_circuit_few_max_rounds() {
+    let events = vec![blake2f_event(0, 1), blake2f_event(1, 1)];
+    let circuit = Blake2fCircuit::<Fr>::new(2, 1, events);
+
+    let k =
+        log2_ceil(Blake2fCircuit::<Fr>::unusable_rows() + Blake2fCircuit::<Fr>::min_num_rows(2, 1));
+    let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+}
@@ -52,6 +52,12 @@ pub struct CopyCircuitConfig<F> {
     /// In case of a bytecode tag, this denotes whether or not the copied byte
     /// is an opcode or push data byte.
     pub is_code: Column<Advice>,
+    /// In case of an RlcAcc source, the random linear combination of the
+    /// bytes read so far.
+    pub value_acc: Column<Advice>,
+    /// In case of an access list destination, whether the written address or
+    /// storage key was warm before.
+    pub value_prev: Column<Advice>,
     /// Whether the row is enabled or not.
     pub q_enable: Column<Fixed>,
     /// The Copy Table contains the columns that are exposed via the lookup
@@ -107,6 +113,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         let value = meta.advice_column_in(SecondPhase);
         let is_code = meta.advice_column();
         let is_pad = meta.advice_column();
+        let value_acc = meta.advice_column_in(SecondPhase);
+        let value_prev = meta.advice_column();
         let is_first = copy_table.is_first;
         let id = copy_table.id;
         let addr = copy_table.addr;
@@ -194,6 +202,12 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta),
                 ]),
                 not::expr(meta.query_advice(is_pad, Rotation::cur())),
+            ]) + and::expr([
+                or::expr([
+                    tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta),
+                ]),
+                not::expr(meta.query_selector(q_step)),
             ]);
             cb.condition(
                 not::expr(meta.query_advice(is_last, Rotation::cur())),
@@ -262,10 +276,12 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     );
                 },
             );
+            let is_access_list_storage_keys =
+                tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta);
             cb.condition(
                 not::expr(tag.value_equals(CopyDataType::RlcAcc, Rotation::next())(
                     meta,
-                )),
+                )) * not::expr(is_access_list_storage_keys.clone()),
                 |cb| {
                     cb.require_equal(
                         "write value == read value (if not rlc acc)",
@@ -274,12 +290,83 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     );
                 },
             );
-            cb.condition(meta.query_advice(is_first, Rotation::cur()), |cb| {
-                cb.require_equal(
-                    "write value == read value (is_first == 1)",
-                    meta.query_advice(value, Rotation::cur()),
-                    meta.query_advice(value, Rotation::next()),
+            cb.condition(
+                meta.query_advice(is_first, Rotation::cur())
+                    * not::expr(is_access_list_storage_keys.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "write value == read value (is_first == 1)",
+                        meta.query_advice(value, Rotation::cur()),
+                        meta.query_advice(value, Rotation::next()),
+                    );
+                },
+            );
+            cb.condition(
+                or::expr([
+                    tag.value_equals(CopyDataType::RlcAcc, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta),
+                ]),
+                |cb| {
+                    cb.condition(meta.query_advice(is_first, Rotation::cur()), |cb| {
+                        cb.require_equal(
+                            "value_acc == value (is_first == 1) for RlcAcc read",
+                            meta.query_advice(value_acc, Rotation::cur()),
+                            meta.query_advice(value, Rotation::cur()),
+                        );
+                    });
+                    cb.condition(
+                        not::expr(meta.query_advice(is_last, Rotation::next())),
+                        |cb| {
+                            cb.require_equal(
+                                "rows[2].value_acc == rows[0].value_acc * r + rows[2].value",
+                                meta.query_advice(value_acc, Rotation(2)),
+                                meta.query_advice(value_acc, Rotation::cur())
+                                    * challenges.keccak_input()
+                                    + meta.query_advice(value, Rotation(2)),
+                            );
+                        },
+                    );
+                    cb.condition(meta.query_advice(is_last, Rotation::next()), |cb| {
+                        cb.require_equal(
+                            "value_acc == rlc_acc at the last step for RlcAcc read",
+                            meta.query_advice(value_acc, Rotation::cur()),
+                            meta.query_advice(rlc_acc, Rotation::cur()),
+                        );
+                    });
+                },
+            );
+            // The read rows of storage keys hold their addresses, and accumulate
+            // the (address, storage key) pairs.
+            cb.condition(is_access_list_storage_keys, |cb| {
+                cb.condition(meta.query_advice(is_first, Rotation::cur()), |cb| {
+                    cb.require_equal(
+                        "value_acc == value * r + value_next (is_first == 1) for storage keys",
+                        meta.query_advice(value_acc, Rotation::cur()),
+                        meta.query_advice(value, Rotation::cur()) * challenges.keccak_input()
+                            + meta.query_advice(value, Rotation::next()),
+                    );
+                });
+                cb.condition(
+                    not::expr(meta.query_advice(is_first, Rotation::cur())),
+                    |cb| {
+                        cb.require_equal(
+                            "value_acc == (rows[-2].value_acc * r + value) * r + value_next for storage keys",
+                            meta.query_advice(value_acc, Rotation::cur()),
+                            (meta.query_advice(value_acc, Rotation(-2))
+                                * challenges.keccak_input()
+                                + meta.query_advice(value, Rotation::cur()))
+                                * challenges.keccak_input()
+                                + meta.query_advice(value, Rotation::next()),
+                        );
+                    },
                 );
+                cb.condition(meta.query_advice(is_last, Rotation::next()), |cb| {
+                    cb.require_equal(
+                        "value_acc == rlc_acc at the last step for storage keys",
+                        meta.query_advice(value_acc, Rotation::cur()),
+                        meta.query_advice(rlc_acc, Rotation::cur()),
+                    );
+                });
             });
             cb.require_zero(
                 "value == 0 when is_pad == 1 for read",
@@ -365,6 +452,54 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             .collect()
         });
 
+        meta.lookup_any("TxAccessListAccount lookup", |meta| {
+            let cond = meta.query_fixed(q_enable, Rotation::cur())
+                * tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta)
+                * not::expr(meta.query_selector(q_step));
+            vec![
+                meta.query_advice(rw_counter, Rotation::cur()),
+                1.expr(),
+                RwTableTag::TxAccessListAccount.expr(),
+                meta.query_advice(id, Rotation::cur()), // tx_id
+                meta.query_advice(value, Rotation::cur()), // address
+                0.expr(),
+                0.expr(),
+                1.expr(),
+                meta.query_advice(value_prev, Rotation::cur()),
+                0.expr(),
+                0.expr(),
+            ]
+            .into_iter()
+            .zip(rw_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (cond.clone() * arg, table))
+            .collect()
+        });
+
+        // Done from the read row, which holds the address of the storage key
+        // written in the next row.
+        meta.lookup_any("TxAccessListAccountStorage lookup", |meta| {
+            let cond = meta.query_fixed(q_enable, Rotation::cur())
+                * tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta)
+                * meta.query_selector(q_step);
+            vec![
+                meta.query_advice(rw_counter, Rotation::next()),
+                1.expr(),
+                RwTableTag::TxAccessListAccountStorage.expr(),
+                meta.query_advice(id, Rotation::cur()), // tx_id
+                meta.query_advice(value, Rotation::cur()), // address
+                0.expr(),
+                meta.query_advice(value, Rotation::next()), // storage key
+                1.expr(),
+                meta.query_advice(value_prev, Rotation::next()),
+                0.expr(),
+                0.expr(),
+            ]
+            .into_iter()
+            .zip(rw_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (cond.clone() * arg, table))
+            .collect()
+        });
+
         meta.lookup_any("Bytecode lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
                 * tag.value_equals(CopyDataType::Bytecode, Rotation::cur())(meta)
@@ -404,6 +539,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             value,
             is_pad,
             is_code,
+            value_acc,
+            value_prev,
             q_enable,
             addr_lt_addr_end,
             copy_table,
@@ -462,10 +599,17 @@ impl<F: Field> CopyCircuitConfig<F> {
                 || Value::known(F::one()),
             )?;
 
-            // is_last, value, is_pad, is_code
-            for (column, &(value, label)) in [self.is_last, self.value, self.is_pad, self.is_code]
-                .iter()
-                .zip_eq(circuit_row)
+            // is_last, value, is_pad, is_code, value_acc, value_prev
+            for (column, &(value, label)) in [
+                self.is_last,
+                self.value,
+                self.is_pad,
+                self.is_code,
+                self.value_acc,
+                self.value_prev,
+            ]
+            .iter()
+            .zip_eq(circuit_row)
             {
                 region.assign_advice(
                     || format!("{} at row: {}", label, *offset),
@@ -502,7 +646,10 @@ impl<F: Field> CopyCircuitConfig<F> {
         max_copy_rows: usize,
         challenges: Challenges<Value<F>>,
     ) -> Result<(), Error> {
-        let copy_rows_needed = copy_events.iter().map(|c| c.bytes.len() * 2).sum::<usize>();
+        let copy_rows_needed = copy_events
+            .iter()
+            .map(|c| c.copy_length() * 2)
+            .sum::<usize>();
 
         // The `+ 2` is used to take into account the two extra empty copy rows needed
         // to satisfy the query at `Rotation(2)` performed inside of the
@@ -522,6 +669,8 @@ impl<F: Field> CopyCircuitConfig<F> {
                 region.name_column(|| "value", self.value);
                 region.name_column(|| "is_code", self.is_code);
                 region.name_column(|| "is_pad", self.is_pad);
+                region.name_column(|| "value_acc", self.value_acc);
+                region.name_column(|| "value_prev", self.value_prev);
 
                 let mut offset = 0;
                 for copy_event in copy_events.iter() {
@@ -639,6 +788,20 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::zero()),
         )?;
+        // value_acc
+        region.assign_advice(
+            || format!("assign value_acc {}", *offset),
+            self.value_acc,
+            *offset,
+            || Value::known(F::zero()),
+        )?;
+        // value_prev
+        region.assign_advice(
+            || format!("assign value_prev {}", *offset),
+            self.value_prev,
+            *offset,
+            || Value::known(F::zero()),
+        )?;
         // rw_counter
         region.assign_advice(
             || format!("assign rw_counter {}", *offset),
@@ -760,7 +923,7 @@ impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
             block
                 .copy_events
                 .iter()
-                .map(|c| c.bytes.len() * 2)
+                .map(|c| c.copy_length() * 2)
                 .sum::<usize>()
                 + 2,
             block.circuits_params.max_copy_rows,
@@ -813,7 +976,7 @@ mod copy_circuit_stats {
                 block
                     .copy_events
                     .iter()
-                    .map(|c| c.bytes.len() * 2)
+                    .map(|c| c.copy_length() * 2)
                     .sum::<usize>()
             },
         );
@@ -191,6 +191,29 @@ fn gen_tx_log_data() -> CircuitInputBuilder {
     builder
 }
 
+fn gen_precompile_data() -> CircuitInputBuilder {
+    let code = bytecode! {
+        PUSH32(Word::MAX)
+        PUSH1(0x00)
+        MSTORE
+        PUSH1(0x20) // retLength
+        PUSH1(0x20) // retOffset
+        PUSH1(0x20) // argsLength
+        PUSH1(0x00) // argsOffset
+        PUSH1(0x02) // address
+        PUSH2(0xFFFF) // gas
+        STATICCALL
+        STOP
+    };
+    let test_ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
 #[test]
 fn copy_circuit_valid_calldatacopy() {
     let builder = gen_calldatacopy_data();
@@ -226,6 +249,13 @@ fn copy_circuit_valid_tx_log() {
     assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_precompile() {
+    let builder = gen_precompile_data();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
+}
+
 #[test]
 fn copy_circuit_invalid_calldatacopy() {
     let mut builder = gen_calldatacopy_data();
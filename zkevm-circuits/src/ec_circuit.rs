@@ -0,0 +1,856 @@
+//! The EC circuit implementation, which verifies the calls to the BN254
+//! elliptic curve precompiled contracts ecAdd and ecMul and exposes them in
+//! the ec table.
+//!
+//! The point operations are done with the non-native `GeneralEccChip` of
+//! halo2wrong, which requires the operands of an addition to have distinct x
+//! coordinates and can't represent the point at infinity, encoded as `(0, 0)`
+//! in the precompile inputs and outputs.  Such a point is replaced by the
+//! generator in the chip operations, and the special cases of the addition are
+//! handled by selecting between the doubling and the incomplete addition of
+//! the operands.
+//!
+//! A call fails when a point has a coordinate not lower than the field modulus
+//! or isn't on the curve, which is proved by the circuit.  The invalid point is
+//! then replaced by the generator too, and the output is empty.
+//!
+//! The pairing check of the ecPairing precompile is not verified by this
+//! circuit, so that the blocks with a successful ecPairing call are rejected by
+//! the circuit input builder (see [`PrecompileCalls::is_supported`]).
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::EcCircuit as TestEcCircuit;
+
+use crate::{
+    table::{EcTable, LookupTable},
+    tx_circuit::sign_verify::{
+        assign_rlc_le, integer_to_bytes_le, limb_value, range_chip_table_rows, SignVerifyConfig,
+        Term, BIT_LEN_LIMB, LIMB_BIT_LENS, NUMBER_OF_LIMBS, RANGE_COMPOSITION_BIT_LENS,
+    },
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{circuit_input_builder::PrecompileEvent, precompile::PrecompileCalls};
+use ecc::{maingate, AssignedPoint, EccConfig, GeneralEccChip};
+use eth_types::{Field, Word};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{Layouter, Value},
+    halo2curves::{
+        bn256::{Fq, Fr, G1Affine},
+        group::{Curve, Group},
+    },
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+};
+use integer::{AssignedInteger, IntegerInstructions, Range};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedCondition, AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+    RangeConfig, RangeInstructions, RegionCtx,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::{iter, marker::PhantomData};
+
+type EcChip<F> = GeneralEccChip<G1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedFq<F> = AssignedInteger<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedG1<F> = AssignedPoint<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+
+// Upper bounds of the rows of the ecc chip aux region and of each operation in
+// the ec ops region, which don't depend on the inputs.  They're checked while
+// assigning the regions, so that a change of the layout can't be missed.
+const ROWS_ECC_CHIP_AUX: usize = 226;
+const ROWS_EC_ADD: usize = 5000;
+const ROWS_EC_MUL: usize = 81000;
+
+/// Config for EcCircuit
+#[derive(Clone, Debug)]
+pub struct EcCircuitConfig<F: Field> {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC
+    q_rlc: Selector,
+    rlc: Column<Advice>,
+    ec_table: EcTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct EcCircuitConfigArgs<F: Field> {
+    /// EcTable
+    pub ec_table: EcTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EcCircuitConfig<F> {
+    type ConfigArgs = EcCircuitConfigArgs<F>;
+
+    /// Return a new EcCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ec_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let (rns_base, rns_scalar) = EcChip::<F>::rns();
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(
+            meta,
+            &main_gate_config,
+            RANGE_COMPOSITION_BIT_LENS.to_vec(),
+            [rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+        );
+
+        let q_rlc = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        SignVerifyConfig::configure_rlc(
+            meta,
+            "ec_rlc",
+            main_gate_config.clone(),
+            q_rlc,
+            rlc,
+            challenges.keccak_input(),
+        );
+
+        ec_table.annotate_columns(meta);
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc,
+            rlc,
+            ec_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> EcCircuitConfig<F> {
+    /// Load ECC RangeChip table.
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        RangeChip::<F>::new(self.range_config.clone()).load_table(layouter)
+    }
+
+    fn ecc_chip_config(&self) -> EccConfig {
+        EccConfig::new(self.range_config.clone(), self.main_gate_config.clone())
+    }
+}
+
+/// Assigned coordinate of a precompile input point.  `value` is the encoded
+/// integer when it's lower than the field modulus and zero otherwise, while
+/// `limbs` are the limbs of the encoded integer.
+struct AssignedCoordinate<F: Field> {
+    value: AssignedFq<F>,
+    limbs: Vec<AssignedValue<F>>,
+    is_in_field: AssignedCondition<F>,
+    bytes_be: Vec<AssignedValue<F>>,
+}
+
+/// Assigned point of a precompile input.  The point at infinity and the
+/// invalid points are replaced by the generator in `point`, while `x` and `y`
+/// are the coordinates of `point` in their canonical representation.
+struct AssignedEcPoint<F: Field> {
+    point: AssignedG1<F>,
+    x: AssignedFq<F>,
+    y: AssignedFq<F>,
+    is_infinity: AssignedCondition<F>,
+    is_valid: AssignedCondition<F>,
+    bytes_be: Vec<AssignedValue<F>>,
+}
+
+/// Assigned row of the ec table.
+#[derive(Debug)]
+struct AssignedEcOp<F: Field> {
+    is_enabled: AssignedValue<F>,
+    op_type: AssignedValue<F>,
+    input_rlc: AssignedValue<F>,
+    input_len: AssignedValue<F>,
+    output_rlc: AssignedValue<F>,
+    is_success: AssignedValue<F>,
+}
+
+/// Helper structure pass around references to all the chips required for the
+/// elliptic curve operations.
+struct ChipsRef<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+    ecc_chip: &'a EcChip<F>,
+}
+
+/// EC Circuit for verifying the calls to the BN254 elliptic curve precompiled
+/// contracts
+#[derive(Clone, Default, Debug)]
+pub struct EcCircuit<F: Field> {
+    /// Max number of supported ecAdd calls
+    pub max_ec_add: usize,
+    /// Max number of supported ecMul calls
+    pub max_ec_mul: usize,
+    /// Aux generator for EccChip
+    pub aux_generator: G1Affine,
+    /// Window size for EccChip
+    pub window_size: usize,
+    /// Precompile events of the elliptic curve operations
+    pub events: Vec<PrecompileEvent>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> EcCircuit<F> {
+    /// Return a new EcCircuit
+    pub fn new(max_ec_add: usize, max_ec_mul: usize, events: Vec<PrecompileEvent>) -> Self {
+        // Like for the SignVerifyChip, the aux generator is a random point
+        // chosen by the prover.
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let aux_generator = <G1Affine as CurveAffine>::CurveExt::random(&mut rng).to_affine();
+        Self {
+            max_ec_add,
+            max_ec_mul,
+            aux_generator,
+            window_size: 2,
+            events,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(num_ec_add: usize, num_ec_mul: usize) -> usize {
+        // The range chip table is laid out next to the regions of the
+        // operations, which follow the ecc chip aux region.
+        let (rns_base, rns_scalar) = EcChip::<F>::rns();
+        let rows_range_chip_table = range_chip_table_rows(
+            &RANGE_COMPOSITION_BIT_LENS,
+            &[rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+        );
+        std::cmp::max(
+            rows_range_chip_table,
+            ROWS_ECC_CHIP_AUX + ROWS_EC_ADD * num_ec_add + ROWS_EC_MUL * num_ec_mul,
+        )
+    }
+
+    fn events_of(&self, precompile: PrecompileCalls) -> Vec<&PrecompileEvent> {
+        self.events
+            .iter()
+            .filter(|event| event.precompile == precompile)
+            .collect()
+    }
+
+    /// Assign a point coordinate from its 32 bytes big endian encoding, proving
+    /// whether the encoded integer is lower than the field modulus.
+    fn assign_coordinate(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        bytes: &[u8],
+    ) -> Result<AssignedCoordinate<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+            ecc_chip,
+        } = chips;
+        let base_chip = ecc_chip.base_field_chip();
+
+        let encoded = Word::from_big_endian(bytes);
+        let modulus = fq_modulus();
+        let limb_of = |value: Word, idx: usize| F::from_u128(limb_value(value, idx));
+
+        // The limbs of the encoded integer, decomposed into its bytes.
+        let mut limbs = Vec::new();
+        let mut bytes_le = Vec::new();
+        for (idx, bit_len) in LIMB_BIT_LENS.into_iter().enumerate() {
+            let (limb, limb_bytes) =
+                range_chip.decompose(ctx, Value::known(limb_of(encoded, idx)), 8, bit_len)?;
+            limbs.push(limb);
+            bytes_le.extend(limb_bytes);
+        }
+        let is_in_field = main_gate.assign_bit(
+            ctx,
+            Value::known(F::from(coordinate_value(bytes).is_some() as u64)),
+        )?;
+
+        // Below the modulus, the encoded integer is the coordinate.
+        let value = base_chip.assign_integer(
+            ctx,
+            ecc_chip
+                .new_unassigned_base(Value::known(coordinate_value(bytes).unwrap_or(Fq::zero()))),
+            Range::Remainder,
+        )?;
+        base_chip.assert_in_field(ctx, &value)?;
+        let zero = main_gate.assign_constant(ctx, F::zero())?;
+        for (value_limb, limb) in value.limbs().iter().zip_eq(limbs.iter()) {
+            let limb = main_gate.select(ctx, limb, &zero, &is_in_field)?;
+            main_gate.assert_equal(ctx, value_limb.as_ref(), &limb)?;
+        }
+
+        // Otherwise, the encoded integer is the sum of the modulus and of a non
+        // negative difference, added limb by limb with boolean carries.
+        let is_not_in_field = main_gate.not(ctx, &is_in_field)?;
+        let difference = if encoded < modulus {
+            Word::zero()
+        } else {
+            encoded - modulus
+        };
+        let mut carry_value = 0u128;
+        let mut carry: Option<AssignedCondition<F>> = None;
+        for (idx, bit_len) in LIMB_BIT_LENS.into_iter().enumerate() {
+            let (difference_limb, _) =
+                range_chip.decompose(ctx, Value::known(limb_of(difference, idx)), 8, bit_len)?;
+            let next_carry = if idx + 1 < NUMBER_OF_LIMBS {
+                let sum = limb_value(difference, idx) + limb_value(modulus, idx) + carry_value;
+                carry_value = sum >> BIT_LEN_LIMB;
+                Some(main_gate.assign_bit(ctx, Value::known(F::from_u128(carry_value)))?)
+            } else {
+                None
+            };
+
+            let mut terms = vec![
+                maingate::Term::Assigned(&difference_limb, F::one()),
+                maingate::Term::Assigned(&limbs[idx], -F::one()),
+            ];
+            if let Some(carry) = &carry {
+                terms.push(maingate::Term::Assigned(carry, F::one()));
+            }
+            if let Some(next_carry) = &next_carry {
+                terms.push(maingate::Term::Assigned(
+                    next_carry,
+                    -F::from_u128(1 << BIT_LEN_LIMB),
+                ));
+            }
+            let sum = main_gate.compose(ctx, &terms, limb_of(modulus, idx))?;
+            let sum = main_gate.mul(ctx, &sum, &is_not_in_field)?;
+            main_gate.assert_zero(ctx, &sum)?;
+            carry = next_carry;
+        }
+
+        Ok(AssignedCoordinate {
+            value,
+            limbs,
+            is_in_field,
+            bytes_be: bytes_le.into_iter().rev().collect(),
+        })
+    }
+
+    /// Assign a point from its 64 bytes big endian encoding, proving whether
+    /// it's a valid point, i.e. `(0, 0)` for the point at infinity or a point
+    /// on the curve.
+    fn assign_point(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        bytes: &[u8],
+    ) -> Result<AssignedEcPoint<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            ecc_chip,
+            ..
+        } = chips;
+        let base_chip = ecc_chip.base_field_chip();
+
+        let x = self.assign_coordinate(ctx, chips, &bytes[..32])?;
+        let y = self.assign_coordinate(ctx, chips, &bytes[32..])?;
+
+        // The limbs of the coordinates are range checked, so that their sum is
+        // zero only for the point (0, 0).
+        let limbs_sum = main_gate.compose(
+            ctx,
+            &x.limbs
+                .iter()
+                .chain(y.limbs.iter())
+                .map(|limb| maingate::Term::Assigned(limb, F::one()))
+                .collect_vec(),
+            F::zero(),
+        )?;
+        let is_infinity = main_gate.is_zero(ctx, &limbs_sum)?;
+
+        // Any other point must be on the curve y^2 = x^3 + 3.
+        let y_square = base_chip.mul(ctx, &y.value, &y.value)?;
+        let x_square = base_chip.mul(ctx, &x.value, &x.value)?;
+        let x_cube = base_chip.mul(ctx, &x_square, &x.value)?;
+        let b = base_chip.assign_constant(ctx, Fq::from(3))?;
+        let x_cube_plus_b = base_chip.add(ctx, &x_cube, &b)?;
+        let x_cube_plus_b = base_chip.reduce(ctx, &x_cube_plus_b)?;
+        base_chip.assert_in_field(ctx, &y_square)?;
+        base_chip.assert_in_field(ctx, &x_cube_plus_b)?;
+        let is_on_curve = self.is_equal(ctx, main_gate, &y_square, &x_cube_plus_b)?;
+
+        let is_in_field = main_gate.and(ctx, &x.is_in_field, &y.is_in_field)?;
+        let is_on_curve = main_gate.or(ctx, &is_infinity, &is_on_curve)?;
+        let is_valid = main_gate.and(ctx, &is_in_field, &is_on_curve)?;
+
+        let point_value = match (
+            coordinate_value(&bytes[..32]),
+            coordinate_value(&bytes[32..]),
+        ) {
+            (Some(x), Some(y)) if x != Fq::zero() || y != Fq::zero() => {
+                Option::from(G1Affine::from_xy(x, y)).unwrap_or(G1Affine::generator())
+            }
+            _ => G1Affine::generator(),
+        };
+        let is_not_valid = main_gate.not(ctx, &is_valid)?;
+        let is_replaced = main_gate.or(ctx, &is_infinity, &is_not_valid)?;
+        let generator = G1Affine::generator().coordinates().unwrap();
+        let generator_x = base_chip.assign_constant(ctx, *generator.x())?;
+        let generator_y = base_chip.assign_constant(ctx, *generator.y())?;
+        let x_value = base_chip.cond_select(ctx, &generator_x, &x.value, &is_replaced)?;
+        let y_value = base_chip.cond_select(ctx, &generator_y, &y.value, &is_replaced)?;
+        let point = ecc_chip.assign_point(ctx, Value::known(point_value))?;
+        base_chip.assert_equal(ctx, point.x(), &x_value)?;
+        base_chip.assert_equal(ctx, point.y(), &y_value)?;
+
+        Ok(AssignedEcPoint {
+            point,
+            x: x_value,
+            y: y_value,
+            is_infinity,
+            is_valid,
+            bytes_be: x.bytes_be.into_iter().chain(y.bytes_be).collect(),
+        })
+    }
+
+    /// Assign the 64 bytes big endian encoding of a point, which is zeros
+    /// when `is_zero`.
+    fn assign_point_bytes(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        point: &AssignedG1<F>,
+        is_zero: &AssignedCondition<F>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+            ecc_chip,
+        } = chips;
+        let base_chip = ecc_chip.base_field_chip();
+
+        let zero = main_gate.assign_constant(ctx, F::zero())?;
+        let mut bytes_be = Vec::new();
+        for coordinate in [point.x(), point.y()] {
+            let coordinate = base_chip.reduce(ctx, coordinate)?;
+            base_chip.assert_in_field(ctx, &coordinate)?;
+            for byte in integer_to_bytes_le(ctx, range_chip, &coordinate)?
+                .iter()
+                .rev()
+            {
+                bytes_be.push(main_gate.select(ctx, &zero, byte, is_zero)?);
+            }
+        }
+        Ok(bytes_be)
+    }
+
+    /// Assign the ec table row of an operation from its input and output bytes.
+    /// The output bytes of a failing operation are zeros, whose RLC is the one
+    /// of the empty output.
+    #[allow(clippy::too_many_arguments)]
+    fn assign_ec_op(
+        &self,
+        config: &EcCircuitConfig<F>,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        is_enabled: bool,
+        precompile: PrecompileCalls,
+        input_be: &[AssignedValue<F>],
+        output_be: &[AssignedValue<F>],
+        is_success: AssignedCondition<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedEcOp<F>, Error> {
+        let is_enabled = main_gate.assign_bit(ctx, Value::known(F::from(is_enabled as u64)))?;
+        let op_type = main_gate.assign_constant(ctx, F::from(u64::from(precompile)))?;
+        let input_len = main_gate.assign_constant(ctx, F::from(input_be.len() as u64))?;
+        let [input_rlc, output_rlc] =
+            [("ec_input", input_be), ("ec_output", output_be)].map(|(name, bytes_be)| {
+                assign_rlc_le(
+                    ctx,
+                    main_gate,
+                    config.main_gate_config.advices(),
+                    config.rlc,
+                    name,
+                    config.q_rlc,
+                    challenges.keccak_input(),
+                    bytes_be
+                        .iter()
+                        .rev()
+                        .map(|byte| Term::assigned(byte.cell(), byte.value().copied())),
+                )
+            });
+
+        Ok(AssignedEcOp {
+            is_enabled,
+            op_type,
+            input_rlc: input_rlc?,
+            input_len,
+            output_rlc: output_rlc?,
+            is_success,
+        })
+    }
+
+    /// Return whether two integers in their canonical representation are
+    /// equal.
+    fn is_equal(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        a: &AssignedFq<F>,
+        b: &AssignedFq<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let mut is_equal = main_gate.assign_constant(ctx, F::one())?;
+        for (a, b) in a.limbs().iter().zip_eq(b.limbs().iter()) {
+            let is_limb_equal = main_gate.is_equal(ctx, a.as_ref(), b.as_ref())?;
+            is_equal = main_gate.and(ctx, &is_equal, &is_limb_equal)?;
+        }
+        Ok(is_equal)
+    }
+
+    /// Assign an ecAdd call, whose input is the 128 bytes encoding of the two
+    /// points to add.
+    fn assign_ec_add(
+        &self,
+        config: &EcCircuitConfig<F>,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        is_enabled: bool,
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedEcOp<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            ecc_chip,
+            ..
+        } = chips;
+
+        let p = self.assign_point(ctx, chips, &input[..64])?;
+        let q = self.assign_point(ctx, chips, &input[64..])?;
+
+        // The incomplete addition requires distinct x coordinates, so q is
+        // replaced by the doubling of p when it's p or -p.  The doubling of p
+        // never has the x coordinate of p, as the group has prime order.
+        let is_x_equal = self.is_equal(ctx, main_gate, &p.x, &q.x)?;
+        let is_y_equal = self.is_equal(ctx, main_gate, &p.y, &q.y)?;
+        let double = ecc_chip.double(ctx, &p.point)?;
+        let addend = ecc_chip.select(ctx, &is_x_equal, &double, &q.point)?;
+        let sum = ecc_chip.add(ctx, &p.point, &addend)?;
+        let is_double = main_gate.and(ctx, &is_x_equal, &is_y_equal)?;
+        let sum = ecc_chip.select(ctx, &is_double, &double, &sum)?;
+        let sum = ecc_chip.select(ctx, &q.is_infinity, &p.point, &sum)?;
+        let sum = ecc_chip.select(ctx, &p.is_infinity, &q.point, &sum)?;
+
+        // The sum is the point at infinity when both points are, or when q is
+        // -p.
+        let is_both_infinity = main_gate.and(ctx, &p.is_infinity, &q.is_infinity)?;
+        let is_any_infinity = main_gate.or(ctx, &p.is_infinity, &q.is_infinity)?;
+        let is_none_infinity = main_gate.not(ctx, &is_any_infinity)?;
+        let is_y_not_equal = main_gate.not(ctx, &is_y_equal)?;
+        let is_opposite = main_gate.and(ctx, &is_x_equal, &is_y_not_equal)?;
+        let is_opposite = main_gate.and(ctx, &is_none_infinity, &is_opposite)?;
+        let is_sum_infinity = main_gate.or(ctx, &is_both_infinity, &is_opposite)?;
+
+        // The call fails on an invalid point, with an empty output.
+        let is_success = main_gate.and(ctx, &p.is_valid, &q.is_valid)?;
+        let is_not_success = main_gate.not(ctx, &is_success)?;
+        let is_output_zero = main_gate.or(ctx, &is_sum_infinity, &is_not_success)?;
+        let output_be = self.assign_point_bytes(ctx, chips, &sum, &is_output_zero)?;
+        let input_be = iter::empty()
+            .chain(p.bytes_be)
+            .chain(q.bytes_be)
+            .collect_vec();
+        self.assign_ec_op(
+            config,
+            ctx,
+            main_gate,
+            is_enabled,
+            PrecompileCalls::Bn128Add,
+            &input_be,
+            &output_be,
+            is_success,
+            challenges,
+        )
+    }
+
+    /// Assign an ecMul call, whose input is the 64 bytes encoding of the point
+    /// followed by the 32 bytes big endian scalar.
+    fn assign_ec_mul(
+        &self,
+        config: &EcCircuitConfig<F>,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        is_enabled: bool,
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedEcOp<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+            ecc_chip,
+        } = chips;
+        let scalar_chip = ecc_chip.scalar_field_chip();
+
+        let p = self.assign_point(ctx, chips, &input[..64])?;
+
+        // The scalar can be any 256 bits integer, which is reduced modulo the
+        // group order.  As the group order is the modulus of the circuit
+        // field, the reduced scalar is the composition of its bytes.
+        let scalar_be = input[64..]
+            .iter()
+            .map(|byte| range_chip.assign(ctx, Value::known(F::from(*byte as u64)), 8, 8))
+            .collect::<Result<Vec<_>, _>>()?;
+        let powers_of_256 =
+            iter::successors(Some(F::one()), |coeff| Some(F::from(256) * coeff)).take(32);
+        let scalar = main_gate.compose(
+            ctx,
+            &scalar_be
+                .iter()
+                .rev()
+                .zip(powers_of_256)
+                .map(|(byte, coeff)| maingate::Term::Assigned(byte, coeff))
+                .collect_vec(),
+            F::zero(),
+        )?;
+        let scalar_value = input[64..].iter().fold(Fr::zero(), |acc, byte| {
+            acc * Fr::from(256) + Fr::from(*byte as u64)
+        });
+        let scalar_integer = scalar_chip.assign_integer(
+            ctx,
+            ecc_chip.new_unassigned_scalar(Value::known(scalar_value)),
+            Range::Remainder,
+        )?;
+        main_gate.assert_equal(ctx, scalar_integer.native(), &scalar)?;
+
+        // The multiplication by zero, which is the point at infinity, is done
+        // with a scalar of one.
+        let is_scalar_zero = main_gate.is_zero(ctx, &scalar)?;
+        let one = scalar_chip.assign_constant(ctx, Fr::one())?;
+        let scalar_integer =
+            scalar_chip.cond_select(ctx, &one, &scalar_integer, &is_scalar_zero)?;
+        let product = ecc_chip.mul(ctx, &p.point, &scalar_integer, self.window_size)?;
+        let is_product_infinity = main_gate.or(ctx, &p.is_infinity, &is_scalar_zero)?;
+
+        // The call fails on an invalid point, with an empty output.
+        let is_success = p.is_valid;
+        let is_not_success = main_gate.not(ctx, &is_success)?;
+        let is_output_zero = main_gate.or(ctx, &is_product_infinity, &is_not_success)?;
+        let output_be = self.assign_point_bytes(ctx, chips, &product, &is_output_zero)?;
+        let input_be = iter::empty()
+            .chain(p.bytes_be)
+            .chain(scalar_be)
+            .collect_vec();
+        self.assign_ec_op(
+            config,
+            ctx,
+            main_gate,
+            is_enabled,
+            PrecompileCalls::Bn128Mul,
+            &input_be,
+            &output_be,
+            is_success,
+            challenges,
+        )
+    }
+
+    fn assign_ec_table(
+        &self,
+        config: &EcCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_ec_ops: Vec<AssignedEcOp<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ec table",
+            |mut region| {
+                let ec_table_columns =
+                    <EcTable as LookupTable<F>>::advice_columns(&config.ec_table);
+                for &column in ec_table_columns.iter() {
+                    region.assign_advice(
+                        || "ec table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                // Copy constraints between the ec table rows and the operations
+                for (offset, assigned_ec_op) in assigned_ec_ops.iter().enumerate() {
+                    for (&column, assigned) in ec_table_columns.iter().zip_eq([
+                        &assigned_ec_op.is_enabled,
+                        &assigned_ec_op.op_type,
+                        &assigned_ec_op.input_rlc,
+                        &assigned_ec_op.input_len,
+                        &assigned_ec_op.output_rlc,
+                        &assigned_ec_op.is_success,
+                    ]) {
+                        assigned.copy_advice(
+                            || format!("ec table row {}", offset + 1),
+                            &mut region,
+                            column,
+                            offset + 1,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Modulus of the base field, the upper bound of the point coordinates.
+fn fq_modulus() -> Word {
+    Word::from_little_endian(&(-Fq::one()).to_bytes()) + Word::one()
+}
+
+/// Return the coordinate encoded by 32 big endian bytes, or `None` when the
+/// encoded integer isn't lower than the field modulus.
+fn coordinate_value(bytes: &[u8]) -> Option<Fq> {
+    let mut bytes_le: [u8; 32] = bytes.try_into().unwrap();
+    bytes_le.reverse();
+    Option::from(Fq::from_bytes(&bytes_le))
+}
+
+/// Check that a region or an operation assigned in `rows` rows fits in the
+/// `max_rows` rows accounted for by [`EcCircuit::min_num_rows`].
+fn check_rows(name: &str, rows: usize, max_rows: usize) -> Result<(), Error> {
+    if rows > max_rows {
+        error!("{}: {} rows > {}", name, rows, max_rows);
+        return Err(Error::Synthesis);
+    }
+    Ok(())
+}
+
+/// Return the precompile events of the elliptic curve operations of the block.
+fn ec_events<F: Field>(block: &witness::Block<F>) -> Vec<PrecompileEvent> {
+    block
+        .precompile_events
+        .iter()
+        .filter(|event| EcTable::is_ec_op(event))
+        .cloned()
+        .collect()
+}
+
+impl<F: Field> SubCircuit<F> for EcCircuit<F> {
+    type Config = EcCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ec_add,
+            block.circuits_params.max_ec_mul,
+            ec_events(block),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let events = ec_events(block);
+        let count = |precompile| {
+            events
+                .iter()
+                .filter(|event| event.precompile == precompile)
+                .count()
+        };
+        (
+            Self::min_num_rows(
+                count(PrecompileCalls::Bn128Add),
+                count(PrecompileCalls::Bn128Mul),
+            ),
+            Self::min_num_rows(
+                block.circuits_params.max_ec_add,
+                block.circuits_params.max_ec_mul,
+            ),
+        )
+    }
+
+    /// Make the assignments to the EcCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let ec_adds = self.events_of(PrecompileCalls::Bn128Add);
+        let ec_muls = self.events_of(PrecompileCalls::Bn128Mul);
+        for (name, num_calls, max_calls) in [
+            ("ecAdd", ec_adds.len(), self.max_ec_add),
+            ("ecMul", ec_muls.len(), self.max_ec_mul),
+        ] {
+            if num_calls > max_calls {
+                error!("{} calls = {} > max = {}", name, num_calls, max_calls);
+                return Err(Error::Synthesis);
+            }
+        }
+
+        config.load_aux_tables(layouter)?;
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let mut ecc_chip = EcChip::<F>::new(config.ecc_chip_config());
+
+        layouter.assign_region(
+            || "ecc chip aux",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                ecc_chip.assign_aux_generator(&mut ctx, Value::known(self.aux_generator))?;
+                ecc_chip.assign_aux(&mut ctx, self.window_size, 1)?;
+                log::debug!("ecc chip aux: {} rows", ctx.offset());
+                check_rows("ecc chip aux", ctx.offset(), ROWS_ECC_CHIP_AUX)
+            },
+        )?;
+
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            ecc_chip: &ecc_chip,
+        };
+
+        // The calls beyond the number of events up to the maximum are padding
+        // operations on zeros, in disabled rows.
+        let assigned_ec_ops =
+            layouter.assign_region(
+                || "ec ops",
+                |region| {
+                    let mut assigned_ec_ops = Vec::new();
+                    let mut ctx = RegionCtx::new(region, 0);
+                    for i in 0..self.max_ec_add {
+                        let (is_enabled, input) = match ec_adds.get(i) {
+                            Some(event) => (true, event.input.clone()),
+                            None => (false, vec![0; 128]),
+                        };
+                        let offset = ctx.offset();
+                        assigned_ec_ops.push(self.assign_ec_add(
+                            config, &mut ctx, &chips, is_enabled, &input, challenges,
+                        )?);
+                        check_rows("ecAdd", ctx.offset() - offset, ROWS_EC_ADD)?;
+                    }
+                    for i in 0..self.max_ec_mul {
+                        let (is_enabled, input) = match ec_muls.get(i) {
+                            Some(event) => (true, event.input.clone()),
+                            None => (false, vec![0; 96]),
+                        };
+                        let offset = ctx.offset();
+                        assigned_ec_ops.push(self.assign_ec_mul(
+                            config, &mut ctx, &chips, is_enabled, &input, challenges,
+                        )?);
+                        check_rows("ecMul", ctx.offset() - offset, ROWS_EC_MUL)?;
+                    }
+                    log::debug!("ec ops: {} rows", ctx.offset());
+                    Ok(assigned_ec_ops)
+                },
+            )?;
+
+        self.assign_ec_table(config, layouter, assigned_ec_ops)
+    }
+
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![]]
+    }
+}
@@ -0,0 +1,162 @@
+#![allow(unused_imports)]
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::{evm_types::GasCost, ToBigEndian};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::G1,
+};
+
+#[test]
+fn ec_circuit_unusable_rows() {
+    assert_eq!(
+        EcCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, EcCircuit::<Fr>>(),
+    )
+}
+
+fn run<F: Field>(
+    events: Vec<PrecompileEvent>,
+    max_ec_add: usize,
+    max_ec_mul: usize,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        EcCircuit::<Fr>::unusable_rows() + EcCircuit::<Fr>::min_num_rows(max_ec_add, max_ec_mul),
+    );
+    // EccChip -> MainGate instance column
+    let circuit = EcCircuit::<F>::new(max_ec_add, max_ec_mul, events);
+
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Returns the 64 bytes big endian encoding of a point, which is `(0, 0)` for
+/// the point at infinity.
+fn point_bytes(point: G1Affine) -> Vec<u8> {
+    let coordinates = Option::from(point.coordinates());
+    match coordinates {
+        Some(coordinates) => [*coordinates.x(), *coordinates.y()]
+            .iter()
+            .flat_map(|coordinate| coordinate.to_bytes().into_iter().rev())
+            .collect(),
+        None => vec![0; 64],
+    }
+}
+
+fn ec_add_event(p: G1Affine, q: G1Affine) -> PrecompileEvent {
+    PrecompileEvent {
+        precompile: PrecompileCalls::Bn128Add,
+        input: [point_bytes(p), point_bytes(q)].concat(),
+        output: point_bytes((p + q).to_affine()),
+        gas_cost: GasCost::PRECOMPILE_BN256ADD.as_u64(),
+        is_success: true,
+    }
+}
+
+fn ec_mul_event(p: G1Affine, scalar_be: [u8; 32]) -> PrecompileEvent {
+    let mut scalar_le = scalar_be;
+    scalar_le.reverse();
+    // The scalar is reduced modulo the group order.
+    let mut scalar_wide = [0u8; 64];
+    scalar_wide[..32].copy_from_slice(&scalar_le);
+    let scalar = Fr::from_bytes_wide(&scalar_wide);
+    PrecompileEvent {
+        precompile: PrecompileCalls::Bn128Mul,
+        input: [point_bytes(p), scalar_be.to_vec()].concat(),
+        output: point_bytes((p * scalar).to_affine()),
+        gas_cost: GasCost::PRECOMPILE_BN256MUL.as_u64(),
+        is_success: true,
+    }
+}
+
+/// Returns the event of a call failing on an invalid point.
+fn invalid_point_event(precompile: PrecompileCalls, input: Vec<u8>) -> PrecompileEvent {
+    PrecompileEvent {
+        precompile,
+        gas_cost: precompile.gas_cost(input.len() as u64).unwrap(),
+        input,
+        output: vec![],
+        is_success: false,
+    }
+}
+
+/// Returns the 64 bytes big endian encoding of the coordinates `(x, y)`.
+fn coordinates_bytes(x: Word, y: Word) -> Vec<u8> {
+    [x.to_be_bytes(), y.to_be_bytes()].concat()
+}
+
+fn scalar_bytes(scalar: u64) -> [u8; 32] {
+    let mut scalar_be = [0u8; 32];
+    scalar_be[24..].copy_from_slice(&scalar.to_be_bytes());
+    scalar_be
+}
+
+#[test]
+fn ec_circuit_add() {
+    let g = G1Affine::generator();
+    let g2 = (G1::generator() * Fr::from(2)).to_affine();
+    let p = (G1::generator() * Fr::from(0xcafe)).to_affine();
+    let o = G1::identity().to_affine();
+    let events = vec![
+        ec_add_event(g, g2),
+        ec_add_event(p, p),
+        ec_add_event(p, -p),
+        ec_add_event(o, p),
+        ec_add_event(p, o),
+        ec_add_event(o, o),
+    ];
+
+    // The last row is padding.
+    assert_eq!(run::<Fr>(events, 7, 0), Ok(()));
+}
+
+#[test]
+fn ec_circuit_mul() {
+    let g = G1Affine::generator();
+    let p = (G1::generator() * Fr::from(0xcafe)).to_affine();
+    let o = G1::identity().to_affine();
+    let events = vec![
+        ec_mul_event(g, scalar_bytes(0xbeef)),
+        ec_mul_event(p, scalar_bytes(0)),
+        ec_mul_event(p, [0xff; 32]),
+        ec_mul_event(o, scalar_bytes(0xbeef)),
+    ];
+
+    // The last row is padding.
+    assert_eq!(run::<Fr>(events, 0, 5), Ok(()));
+}
+
+#[test]
+fn ec_circuit_invalid_points() {
+    let g = point_bytes(G1Affine::generator());
+    let not_on_curve = coordinates_bytes(Word::one(), Word::one());
+    let x_not_in_field = coordinates_bytes(fq_modulus(), Word::from(2));
+    let y_not_in_field = coordinates_bytes(Word::one(), Word::MAX);
+    let events = vec![
+        invalid_point_event(
+            PrecompileCalls::Bn128Add,
+            [not_on_curve.clone(), g.clone()].concat(),
+        ),
+        invalid_point_event(
+            PrecompileCalls::Bn128Add,
+            [g, x_not_in_field.clone()].concat(),
+        ),
+        invalid_point_event(
+            PrecompileCalls::Bn128Add,
+            [y_not_in_field, vec![0; 64]].concat(),
+        ),
+        invalid_point_event(
+            PrecompileCalls::Bn128Mul,
+            [not_on_curve, scalar_bytes(0xbeef).to_vec()].concat(),
+        ),
+        invalid_point_event(
+            PrecompileCalls::Bn128Mul,
+            [x_not_in_field, scalar_bytes(0).to_vec()].concat(),
+        ),
+    ];
+
+    assert_eq!(run::<Fr>(events, 3, 2), Ok(()));
+}
@@ -0,0 +1,229 @@
+//! The EcRecover circuit implementation, which verifies the calls to the
+//! ecrecover precompiled contract and exposes them in the ecrecover table.
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::EcRecoverCircuit as TestEcRecoverCircuit;
+
+use crate::{
+    table::{EcRecoverTable, KeccakTable, LookupTable},
+    tx_circuit::sign_verify::{AssignedEcRecover, SignVerifyChip, SignVerifyConfig},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{
+    circuit_input_builder::PrecompileEvent,
+    precompile::{ecrecover_sign_data, PrecompileCalls},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, Expression},
+};
+use itertools::Itertools;
+use log::error;
+use std::marker::PhantomData;
+
+/// Config for EcRecoverCircuit
+#[derive(Clone, Debug)]
+pub struct EcRecoverCircuitConfig<F: Field> {
+    sign_verify: SignVerifyConfig,
+    ecrecover_table: EcRecoverTable,
+    _marker: PhantomData<F>,
+    // External tables
+    keccak_table: KeccakTable,
+}
+
+/// Circuit configuration arguments
+pub struct EcRecoverCircuitConfigArgs<F: Field> {
+    /// EcRecoverTable
+    pub ecrecover_table: EcRecoverTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EcRecoverCircuitConfig<F> {
+    type ConfigArgs = EcRecoverCircuitConfigArgs<F>;
+
+    /// Return a new EcRecoverCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ecrecover_table,
+            keccak_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let sign_verify = SignVerifyConfig::new(meta, keccak_table.clone(), challenges);
+        ecrecover_table.annotate_columns(meta);
+
+        Self {
+            sign_verify,
+            ecrecover_table,
+            keccak_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> EcRecoverCircuitConfig<F> {
+    /// Load ECDSA RangeChip table.
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.sign_verify.load_range(layouter)
+    }
+}
+
+/// EcRecover Circuit for verifying the calls to the ecrecover precompiled
+/// contract
+#[derive(Clone, Default, Debug)]
+pub struct EcRecoverCircuit<F: Field> {
+    /// Max number of supported ecrecover calls
+    pub max_ecrecover: usize,
+    /// SignVerify chip
+    pub sign_verify: SignVerifyChip<F>,
+    /// Precompile events of the ecrecover calls
+    pub events: Vec<PrecompileEvent>,
+}
+
+impl<F: Field> EcRecoverCircuit<F> {
+    /// Return a new EcRecoverCircuit
+    pub fn new(max_ecrecover: usize, events: Vec<PrecompileEvent>) -> Self {
+        EcRecoverCircuit::<F> {
+            max_ecrecover,
+            sign_verify: SignVerifyChip::new(max_ecrecover),
+            events,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(num_ecrecover: usize) -> usize {
+        SignVerifyChip::<F>::min_num_rows(num_ecrecover)
+    }
+
+    fn assign_ecrecover_table(
+        &self,
+        config: &EcRecoverCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_ecrecovers: Vec<AssignedEcRecover<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ecrecover table",
+            |mut region| {
+                let ecrecover_table_columns =
+                    <EcRecoverTable as LookupTable<F>>::advice_columns(&config.ecrecover_table);
+                for &column in ecrecover_table_columns.iter() {
+                    region.assign_advice(
+                        || "ecrecover table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                // Ref. spec 0. Copy constraints between the ecrecover table rows
+                // and the SignVerifyChip
+                for (offset, assigned_ecrecover) in assigned_ecrecovers.iter().enumerate() {
+                    for (&column, assigned) in ecrecover_table_columns.iter().zip_eq([
+                        &assigned_ecrecover.is_enabled,
+                        &assigned_ecrecover.input_rlc,
+                        &assigned_ecrecover.is_valid,
+                        &assigned_ecrecover.output_rlc,
+                    ]) {
+                        assigned.copy_advice(
+                            || format!("ecrecover table row {}", offset + 1),
+                            &mut region,
+                            column,
+                            offset + 1,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> SubCircuit<F> for EcRecoverCircuit<F> {
+    type Config = EcRecoverCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ecrecover,
+            block
+                .precompile_events
+                .iter()
+                .filter(|event| event.precompile == PrecompileCalls::ECRecover)
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(
+                block
+                    .precompile_events
+                    .iter()
+                    .filter(|event| event.precompile == PrecompileCalls::ECRecover)
+                    .count(),
+            ),
+            Self::min_num_rows(block.circuits_params.max_ecrecover),
+        )
+    }
+
+    /// Make the assignments to the EcRecoverCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.events.len() > self.max_ecrecover {
+            error!(
+                "ecrecover calls = {} > max_ecrecover = {}",
+                self.events.len(),
+                self.max_ecrecover
+            );
+            return Err(Error::Synthesis);
+        }
+        if self
+            .events
+            .iter()
+            .any(|event| !PrecompileCalls::ECRecover.is_supported(&event.input))
+        {
+            error!("ecrecover calls with unsupported inputs");
+            return Err(Error::Synthesis);
+        }
+        let calls = self
+            .events
+            .iter()
+            .map(|event| (event.input.clone(), ecrecover_sign_data(&event.input)))
+            .collect_vec();
+
+        config.load_aux_tables(layouter)?;
+        let assigned_ecrecovers =
+            self.sign_verify
+                .assign_ecrecover(&config.sign_verify, layouter, &calls, challenges)?;
+        self.assign_ecrecover_table(config, layouter, assigned_ecrecovers)
+    }
+
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![]]
+    }
+}
@@ -0,0 +1,57 @@
+pub use super::EcRecoverCircuit;
+
+use crate::{
+    ecrecover_circuit::{EcRecoverCircuitConfig, EcRecoverCircuitConfigArgs},
+    table::{EcRecoverTable, KeccakTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use bus_mapping::circuit_input_builder::keccak_inputs_ecrecover_circuit;
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for EcRecoverCircuit<F> {
+    type Config = (EcRecoverCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let ecrecover_table = EcRecoverTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            EcRecoverCircuitConfig::new(
+                meta,
+                EcRecoverCircuitConfigArgs {
+                    ecrecover_table,
+                    keccak_table,
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+
+        config.keccak_table.dev_load(
+            &mut layouter,
+            &keccak_inputs_ecrecover_circuit(&self.events),
+            &challenges,
+        )?;
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
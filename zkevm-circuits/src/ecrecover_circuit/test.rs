@@ -0,0 +1,156 @@
+#![allow(unused_imports)]
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::{evm_types::GasCost, ToBigEndian, Word, H256};
+use ethers_core::utils::keccak256;
+use ethers_signers::{LocalWallet, Signer};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn ecrecover_circuit_unusable_rows() {
+    assert_eq!(
+        EcRecoverCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, EcRecoverCircuit::<Fr>>(),
+    )
+}
+
+fn run<F: Field>(
+    events: Vec<PrecompileEvent>,
+    max_ecrecover: usize,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        EcRecoverCircuit::<Fr>::unusable_rows()
+            + EcRecoverCircuit::<Fr>::min_num_rows(max_ecrecover),
+    );
+    // SignVerifyChip -> ECDSAChip -> MainGate instance column
+    let circuit = EcRecoverCircuit::<F>::new(max_ecrecover, events);
+
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+fn ecrecover_event(
+    msg_hash: [u8; 32],
+    v: u64,
+    r: Word,
+    s: Word,
+    output: Vec<u8>,
+) -> PrecompileEvent {
+    PrecompileEvent {
+        precompile: PrecompileCalls::ECRecover,
+        input: [
+            msg_hash,
+            Word::from(v).to_be_bytes(),
+            r.to_be_bytes(),
+            s.to_be_bytes(),
+        ]
+        .concat(),
+        output,
+        gas_cost: GasCost::PRECOMPILE_ECRECOVER_BASE.as_u64(),
+        is_success: true,
+    }
+}
+
+/// Ecrecover call on a signature of `msg` by `wallet`.
+fn signature_event(wallet: &LocalWallet, msg: &[u8]) -> PrecompileEvent {
+    let msg_hash = keccak256(msg);
+    let signature = wallet.sign_hash(H256(msg_hash));
+    ecrecover_event(
+        msg_hash,
+        signature.v,
+        signature.r,
+        signature.s,
+        [[0u8; 12].as_slice(), wallet.address().as_bytes()].concat(),
+    )
+}
+
+#[test]
+fn ecrecover_circuit_1call_1max() {
+    let event = signature_event(&mock::MOCK_WALLETS[0], b"zkevm");
+
+    assert_eq!(run::<Fr>(vec![event], 1), Ok(()));
+}
+
+#[test]
+fn ecrecover_circuit_invalid_signature() {
+    let valid = signature_event(&mock::MOCK_WALLETS[0], b"zkevm");
+    // v is neither 27 nor 28.
+    let mut invalid_v = valid.clone();
+    invalid_v.input[63] = 29;
+    invalid_v.output = vec![];
+    // s is zero.
+    let invalid_s = ecrecover_event(keccak256(b"zkevm"), 27, Word::one(), Word::zero(), vec![]);
+
+    // The last row is padding.
+    assert_eq!(run::<Fr>(vec![valid, invalid_v, invalid_s], 4), Ok(()));
+}
+
+#[test]
+fn ecrecover_circuit_recovery_ids() {
+    // v is 27 plus the parity of the y coordinate of the signature point.
+    let events = [27, 28].map(|v| {
+        (0..=u8::MAX)
+            .map(|i| signature_event(&mock::MOCK_WALLETS[0], &[i]))
+            .find(|event| event.input[63] == v)
+            .unwrap()
+    });
+
+    assert_eq!(run::<Fr>(events.to_vec(), 2), Ok(()));
+}
+
+/// The secp256k1 curve order.
+fn secp256k1_order() -> Word {
+    Word::from_little_endian(&eth_types::sign_types::SECP256K1_Q.to_bytes_le())
+}
+
+#[test]
+fn ecrecover_circuit_signature_not_lower_than_order() {
+    let valid = signature_event(&mock::MOCK_WALLETS[0], b"zkevm");
+    // r is the curve order.
+    let mut invalid_r = valid.clone();
+    invalid_r.input[64..96].copy_from_slice(&secp256k1_order().to_be_bytes());
+    invalid_r.output = vec![];
+    // s is the maximum 32 bytes integer.
+    let mut invalid_s = valid;
+    invalid_s.input[96..128].copy_from_slice(&[0xFF; 32]);
+    invalid_s.output = vec![];
+
+    assert_eq!(run::<Fr>(vec![invalid_r, invalid_s], 2), Ok(()));
+}
+
+#[test]
+fn ecrecover_circuit_unreduced_msg_hash() {
+    // The message hash n + 1, where n is the curve order, is signed and
+    // verified as 1.
+    let wallet = &mock::MOCK_WALLETS[0];
+    let msg_hash = (secp256k1_order() + Word::one()).to_be_bytes();
+    let signature = wallet.sign_hash(H256(msg_hash));
+    let event = ecrecover_event(
+        msg_hash,
+        signature.v,
+        signature.r,
+        signature.s,
+        [[0u8; 12].as_slice(), wallet.address().as_bytes()].concat(),
+    );
+
+    assert_eq!(run::<Fr>(vec![event], 1), Ok(()));
+}
+
+#[test]
+fn ecrecover_circuit_invalid_signature_unsupported() {
+    // r = 5 is not the x coordinate of a curve point, which is an invalid
+    // signature not proved by the circuit.
+    let event = ecrecover_event(keccak256(b"zkevm"), 27, Word::from(5), Word::one(), vec![]);
+    let circuit = EcRecoverCircuit::<Fr>::new(1, vec![event]);
+
+    let k = log2_ceil(
+        EcRecoverCircuit::<Fr>::unusable_rows() + EcRecoverCircuit::<Fr>::min_num_rows(1),
+    );
+    assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+}
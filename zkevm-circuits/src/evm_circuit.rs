@@ -21,7 +21,8 @@ pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable, TxTable,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EcRecoverTable, EcTable, ExpTable,
+        KeccakTable, LookupTable, ModExpTable, Ripemd160Table, RwTable, Sha256Table, TxTable,
     },
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
@@ -47,6 +48,12 @@ pub struct EvmCircuitConfig<F> {
     copy_table: CopyTable,
     keccak_table: KeccakTable,
     exp_table: ExpTable,
+    ecrecover_table: EcRecoverTable,
+    sha256_table: Sha256Table,
+    ripemd160_table: Ripemd160Table,
+    ec_table: EcTable,
+    modexp_table: ModExpTable,
+    blake2f_table: Blake2fTable,
 }
 
 /// Circuit configuration arguments
@@ -67,6 +74,18 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// ExpTable
     pub exp_table: ExpTable,
+    /// EcRecoverTable
+    pub ecrecover_table: EcRecoverTable,
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// EcTable
+    pub ec_table: EcTable,
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// Blake2fTable
+    pub blake2f_table: Blake2fTable,
 }
 
 impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
@@ -85,6 +104,12 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            ecrecover_table,
+            sha256_table,
+            ripemd160_table,
+            ec_table,
+            modexp_table,
+            blake2f_table,
         }: Self::ConfigArgs,
     ) -> Self {
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
@@ -101,6 +126,12 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &copy_table,
             &keccak_table,
             &exp_table,
+            &ecrecover_table,
+            &sha256_table,
+            &ripemd160_table,
+            &ec_table,
+            &modexp_table,
+            &blake2f_table,
         ));
 
         meta.annotate_lookup_any_column(byte_table[0], || "byte_range");
@@ -114,6 +145,12 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         copy_table.annotate_columns(meta);
         keccak_table.annotate_columns(meta);
         exp_table.annotate_columns(meta);
+        ecrecover_table.annotate_columns(meta);
+        sha256_table.annotate_columns(meta);
+        ripemd160_table.annotate_columns(meta);
+        ec_table.annotate_columns(meta);
+        modexp_table.annotate_columns(meta);
+        blake2f_table.annotate_columns(meta);
 
         Self {
             fixed_table,
@@ -126,6 +163,12 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            ecrecover_table,
+            sha256_table,
+            ripemd160_table,
+            ec_table,
+            modexp_table,
+            blake2f_table,
         }
     }
 }
@@ -373,6 +416,12 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let keccak_table = KeccakTable::construct(meta);
         let exp_table = ExpTable::construct(meta);
+        let ecrecover_table = EcRecoverTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let ec_table = EcTable::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenges_expr = challenges.exprs(meta);
 
@@ -388,6 +437,12 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     copy_table,
                     keccak_table,
                     exp_table,
+                    ecrecover_table,
+                    sha256_table,
+                    ripemd160_table,
+                    ec_table,
+                    modexp_table,
+                    blake2f_table,
                 },
             ),
             challenges,
@@ -429,6 +484,24 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             .keccak_table
             .dev_load(&mut layouter, &block.sha3_inputs, &challenges)?;
         config.exp_table.load(&mut layouter, block)?;
+        config
+            .ecrecover_table
+            .dev_load(&mut layouter, &block.precompile_events, &challenges)?;
+        config
+            .sha256_table
+            .dev_load(&mut layouter, &block.precompile_events, &challenges)?;
+        config
+            .ripemd160_table
+            .dev_load(&mut layouter, &block.precompile_events, &challenges)?;
+        config
+            .ec_table
+            .dev_load(&mut layouter, &block.precompile_events, &challenges)?;
+        config
+            .modexp_table
+            .dev_load(&mut layouter, &block.precompile_events, &challenges)?;
+        config
+            .blake2f_table
+            .dev_load(&mut layouter, &block.precompile_events, &challenges)?;
 
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
@@ -503,16 +576,7 @@ mod evm_circuit_stats {
     #[test]
     fn get_evm_states_stats() {
         print_circuit_stats_by_states(
-            |state| {
-                // TODO: Enable CREATE/CREATE2 once they are supported
-                !matches!(
-                    state,
-                    ExecutionState::ErrorInvalidOpcode
-                        | ExecutionState::CREATE
-                        | ExecutionState::CREATE2
-                        | ExecutionState::SELFDESTRUCT
-                )
-            },
+            |state| !matches!(state, ExecutionState::ErrorInvalidOpcode),
             |opcode| match opcode {
                 OpcodeId::RETURNDATACOPY => {
                     bytecode! {
@@ -529,6 +593,21 @@ mod evm_circuit_stats {
                     PUSH2(0x00) // destOffset
                     }
                 }
+                OpcodeId::CREATE => {
+                    bytecode! {
+                    PUSH1(0x00) // length
+                    PUSH1(0x00) // offset
+                    PUSH1(0x00) // value
+                    }
+                }
+                OpcodeId::CREATE2 => {
+                    bytecode! {
+                    PUSH1(0x00) // salt
+                    PUSH1(0x00) // length
+                    PUSH1(0x00) // offset
+                    PUSH1(0x00) // value
+                    }
+                }
                 _ => bytecode! {
                     PUSH2(0x40)
                     PUSH2(0x50)
@@ -611,7 +690,16 @@ mod evm_circuit_stats {
             keccak_table,
             LOOKUP_CONFIG[6].1,
             exp_table,
-            LOOKUP_CONFIG[7].1
+            LOOKUP_CONFIG[7].1,
+            LOOKUP_CONFIG[8].1,
+            ecrecover_table,
+            LOOKUP_CONFIG[9].1,
+            sha256_table,
+            LOOKUP_CONFIG[10].1,
+            ec_table,
+            LOOKUP_CONFIG[11].1,
+            modexp_table,
+            LOOKUP_CONFIG[12].1
         );
     }
     #[test]
@@ -5,23 +5,26 @@ use crate::{
         step::ExecutionState,
         util::{
             and,
-            common_gadget::TransferWithGasFeeGadget,
+            common_gadget::{IsPrecompileGadget, TransferWithGasFeeGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
             },
-            is_precompiled,
             math_gadget::{
-                ContractCreateGadget, IsEqualGadget, IsZeroGadget, MulWordByU64Gadget,
-                RangeCheckGadget,
+                AddWordsGadget, ContractCreateGadget, IsEqualGadget, IsZeroGadget, LtWordGadget,
+                MulWordByU64Gadget, RangeCheckGadget,
             },
             not, or, select, CachedRegion, Cell, StepRws, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
-    table::{AccountFieldTag, CallContextFieldTag, TxFieldTag as TxContextFieldTag},
+    table::{
+        AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, TxFieldTag as TxContextFieldTag,
+    },
     util::Expr,
+    witness::{access_list_len, access_list_rlc},
 };
+use bus_mapping::circuit_input_builder::CopyDataType;
 use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar};
 use ethers_core::utils::{get_contract_address, keccak256};
 use gadgets::util::expr_from_bytes;
@@ -33,6 +36,11 @@ pub(crate) struct BeginTxGadget<F> {
     tx_nonce: Cell<F>,
     tx_gas: Cell<F>,
     tx_gas_price: Word<F>,
+    tx_max_fee_per_gas: Word<F>,
+    add_base_fee_by_priority_fee: AddWordsGadget<F, 2, true>,
+    is_max_fee_lt_base_fee: LtWordGadget<F>,
+    is_max_fee_lt_priority_fee: LtWordGadget<F>,
+    is_max_fee_lt_fee: LtWordGadget<F>,
     mul_gas_fee_by_gas: MulWordByU64Gadget<F>,
     tx_caller_address: Cell<F>,
     tx_caller_address_is_zero: IsZeroGadget<F>,
@@ -42,6 +50,12 @@ pub(crate) struct BeginTxGadget<F> {
     tx_value: Word<F>,
     tx_call_data_length: Cell<F>,
     tx_call_data_gas_cost: Cell<F>,
+    tx_access_list_addresses_len: Cell<F>,
+    tx_access_list_storage_keys_len: Cell<F>,
+    tx_access_list_addresses_rlc: Cell<F>,
+    tx_access_list_storage_keys_rlc: Cell<F>,
+    is_access_list_addresses_empty: IsZeroGadget<F>,
+    is_access_list_storage_keys_empty: IsZeroGadget<F>,
     reversion_info: ReversionInfo<F>,
     sufficient_gas_left: RangeCheckGadget<F, N_BYTES_GAS>,
     transfer_with_gas_fee: TransferWithGasFeeGadget<F>,
@@ -50,6 +64,7 @@ pub(crate) struct BeginTxGadget<F> {
     caller_nonce_hash_bytes: [Cell<F>; N_BYTES_WORD],
     create: ContractCreateGadget<F, false>,
     callee_not_exists: IsZeroGadget<F>,
+    is_precompile: IsPrecompileGadget<F>,
     is_caller_callee_equal: Cell<F>,
 }
 
@@ -96,6 +111,20 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         );
         let [tx_gas_price, tx_value] = [TxContextFieldTag::GasPrice, TxContextFieldTag::Value]
             .map(|field_tag| cb.tx_context_as_word(tx_id.expr(), field_tag, None));
+        let [tx_access_list_addresses_len, tx_access_list_storage_keys_len] = [
+            TxContextFieldTag::AccessListAddressesLen,
+            TxContextFieldTag::AccessListStorageKeysLen,
+        ]
+        .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
+        let [tx_access_list_addresses_rlc, tx_access_list_storage_keys_rlc] = [
+            TxContextFieldTag::AccessListAddressesRlc,
+            TxContextFieldTag::AccessListStorageKeysRlc,
+        ]
+        .map(|field_tag| {
+            let rlc = cb.query_cell_phase2();
+            cb.tx_context_lookup(tx_id.expr(), field_tag, None, rlc.expr());
+            rlc
+        });
 
         let call_callee_address = cb.query_cell();
         cb.condition(not::expr(tx_is_create.expr()), |cb| {
@@ -121,19 +150,51 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
-        // TODO: Implement EIP 1559 (currently it only supports legacy
-        // transaction format)
+        // Check the gas price is the effective gas price of EIP 1559, which is
+        // min(max_fee_per_gas, base_fee + max_priority_fee_per_gas). Legacy
+        // transactions have both fee caps set to the gas price.
+        let [tx_max_fee_per_gas, tx_max_priority_fee_per_gas] = [
+            TxContextFieldTag::MaxFeePerGas,
+            TxContextFieldTag::MaxPriorityFeePerGas,
+        ]
+        .map(|field_tag| cb.tx_context_as_word(tx_id.expr(), field_tag, None));
+        let base_fee = cb.query_word_rlc();
+        cb.block_lookup(BlockContextFieldTag::BaseFee.expr(), None, base_fee.expr());
+        let is_max_fee_lt_base_fee = LtWordGadget::construct(cb, &tx_max_fee_per_gas, &base_fee);
+        cb.require_zero("max_fee_per_gas >= base_fee", is_max_fee_lt_base_fee.expr());
+        let is_max_fee_lt_priority_fee =
+            LtWordGadget::construct(cb, &tx_max_fee_per_gas, &tx_max_priority_fee_per_gas);
+        cb.require_zero(
+            "max_fee_per_gas >= max_priority_fee_per_gas",
+            is_max_fee_lt_priority_fee.expr(),
+        );
+        let fee = cb.query_word_rlc();
+        let add_base_fee_by_priority_fee =
+            AddWordsGadget::construct(cb, [base_fee, tx_max_priority_fee_per_gas], fee);
+        let is_max_fee_lt_fee =
+            LtWordGadget::construct(cb, &tx_max_fee_per_gas, add_base_fee_by_priority_fee.sum());
+        cb.require_equal(
+            "gas_price == min(max_fee_per_gas, base_fee + max_priority_fee_per_gas)",
+            tx_gas_price.expr(),
+            select::expr(
+                is_max_fee_lt_fee.expr(),
+                tx_max_fee_per_gas.expr(),
+                add_base_fee_by_priority_fee.sum().expr(),
+            ),
+        );
+
         // Calculate transaction gas fee
         let mul_gas_fee_by_gas =
             MulWordByU64Gadget::construct(cb, tx_gas_price.clone(), tx_gas.expr());
 
-        // TODO: Take gas cost of access list (EIP 2930) into consideration.
-        // Use intrinsic gas
+        // Use intrinsic gas, including the gas cost of access list (EIP 2930)
         let intrinsic_gas_cost = select::expr(
             tx_is_create.expr(),
             GasCost::CREATION_TX.expr(),
             GasCost::TX.expr(),
-        ) + tx_call_data_gas_cost.expr();
+        ) + tx_call_data_gas_cost.expr()
+            + tx_access_list_addresses_len.expr() * GasCost::ACCESS_LIST_ADDRESS.expr()
+            + tx_access_list_storage_keys_len.expr() * GasCost::ACCESS_LIST_STORAGE.expr();
 
         // Check gas_left is sufficient
         let gas_left = tx_gas.expr() - intrinsic_gas_cost;
@@ -158,6 +219,9 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
+        let access_list_rw_delta =
+            tx_access_list_addresses_len.expr() + tx_access_list_storage_keys_len.expr();
+
         // Read code_hash of callee
         let phase2_code_hash = cb.query_cell_phase2();
         let is_empty_code_hash =
@@ -168,7 +232,6 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         // code_hash = 0).
         let no_callee_code = is_empty_code_hash.expr() + callee_not_exists.expr();
 
-        // TODO: And not precompile
         cb.condition(not::expr(tx_is_create.expr()), |cb| {
             cb.account_read(
                 tx_callee_address.expr(),
@@ -256,7 +319,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             }
 
             cb.require_step_state_transition(StepStateTransition {
-                // 21 + a reads and writes:
+                // 21 + a + b reads and writes:
                 //   - Write CallContext TxId
                 //   - Write CallContext RwCounterEndOfReversion
                 //   - Write CallContext IsPersistent
@@ -264,6 +327,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write Account (Caller) Nonce
                 //   - Write TxAccessListAccount
                 //   - Write TxAccessListAccount
+                //   - b Write TxAccessListAccount(Storage) of the access list
                 //   - a TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
                 //   - Write CallContext Depth
@@ -279,7 +343,9 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsRoot
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
-                rw_counter: Delta(21.expr() + transfer_with_gas_fee.rw_delta()),
+                rw_counter: Delta(
+                    21.expr() + access_list_rw_delta.clone() + transfer_with_gas_fee.rw_delta(),
+                ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
                 is_create: To(tx_is_create.expr()),
@@ -294,11 +360,23 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             });
         });
 
-        // TODO: 2. Handle call to precompiled contracts.
+        // 2. Call to precompiled contract, which sets up the call context like
+        // a call to account with non-empty code (4.) and then goes to the
+        // precompile execution state.
+        let is_precompile = IsPrecompileGadget::construct(cb, tx_callee_address.expr());
+        IsPrecompileGadget::constrain_next_state(
+            cb,
+            tx_callee_address.expr(),
+            and::expr([not::expr(tx_is_create.expr()), is_precompile.expr()]),
+        );
 
         // 3. Call to account with empty code.
         cb.condition(
-            and::expr([not::expr(tx_is_create.expr()), no_callee_code.clone()]),
+            and::expr([
+                not::expr(tx_is_create.expr()),
+                no_callee_code.clone(),
+                not::expr(is_precompile.expr()),
+            ]),
             |cb| {
                 cb.require_equal(
                     "Tx to account with empty code should be persistent",
@@ -312,7 +390,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 );
 
                 cb.require_step_state_transition(StepStateTransition {
-                    // 8 reads and writes:
+                    // 8 + a + b reads and writes:
                     //   - Write CallContext TxId
                     //   - Write CallContext RwCounterEndOfReversion
                     //   - Write CallContext IsPersistent
@@ -320,9 +398,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write Account Nonce
                     //   - Write TxAccessListAccount
                     //   - Write TxAccessListAccount
+                    //   - b Write TxAccessListAccount(Storage) of the access list
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
-                    rw_counter: Delta(8.expr() + transfer_with_gas_fee.rw_delta()),
+                    rw_counter: Delta(
+                        8.expr() + access_list_rw_delta.clone() + transfer_with_gas_fee.rw_delta(),
+                    ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
                 });
@@ -331,7 +412,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
 
         // 4. Call to account with non-empty code.
         cb.condition(
-            and::expr([not::expr(tx_is_create.expr()), not::expr(no_callee_code)]),
+            and::expr([
+                not::expr(tx_is_create.expr()),
+                or::expr([not::expr(no_callee_code), is_precompile.expr()]),
+            ]),
             |cb| {
                 // Setup first call's context.
                 for (field_tag, value) in [
@@ -356,7 +440,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 }
 
                 cb.require_step_state_transition(StepStateTransition {
-                    // 21 reads and writes:
+                    // 21 + a + b reads and writes:
                     //   - Write CallContext TxId
                     //   - Write CallContext RwCounterEndOfReversion
                     //   - Write CallContext IsPersistent
@@ -364,6 +448,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write Account Nonce
                     //   - Write TxAccessListAccount
                     //   - Write TxAccessListAccount
+                    //   - b Write TxAccessListAccount(Storage) of the access list
                     //   - Read Account CodeHash
                     //   - a TransferWithGasFeeGadget
                     //   - Write CallContext Depth
@@ -379,7 +464,9 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsRoot
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
-                    rw_counter: Delta(21.expr() + transfer_with_gas_fee.rw_delta()),
+                    rw_counter: Delta(
+                        21.expr() + access_list_rw_delta + transfer_with_gas_fee.rw_delta(),
+                    ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
                     is_create: To(tx_is_create.expr()),
@@ -392,11 +479,52 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             },
         );
 
+        // Prepare access list of the addresses and then the storage keys in the
+        // tx access list (EIP 2930), which are written by the copy circuit at
+        // the end of the step.
+        let is_access_list_addresses_empty =
+            IsZeroGadget::construct(cb, tx_access_list_addresses_len.expr());
+        cb.condition(not::expr(is_access_list_addresses_empty.expr()), |cb| {
+            cb.copy_table_lookup(
+                tx_id.expr(),
+                CopyDataType::AccessListAddresses.expr(),
+                tx_id.expr(),
+                CopyDataType::AccessListAddresses.expr(),
+                0.expr(),
+                tx_access_list_addresses_len.expr(),
+                0.expr(),
+                tx_access_list_addresses_len.expr(),
+                tx_access_list_addresses_rlc.expr(),
+                tx_access_list_addresses_len.expr(),
+            ); // rwc_delta += access_list_addresses_len
+        });
+        let is_access_list_storage_keys_empty =
+            IsZeroGadget::construct(cb, tx_access_list_storage_keys_len.expr());
+        cb.condition(not::expr(is_access_list_storage_keys_empty.expr()), |cb| {
+            cb.copy_table_lookup(
+                tx_id.expr(),
+                CopyDataType::AccessListStorageKeys.expr(),
+                tx_id.expr(),
+                CopyDataType::AccessListStorageKeys.expr(),
+                0.expr(),
+                tx_access_list_storage_keys_len.expr(),
+                0.expr(),
+                tx_access_list_storage_keys_len.expr(),
+                tx_access_list_storage_keys_rlc.expr(),
+                tx_access_list_storage_keys_len.expr(),
+            ); // rwc_delta += access_list_storage_keys_len
+        });
+
         Self {
             tx_id,
             tx_nonce,
             tx_gas,
             tx_gas_price,
+            tx_max_fee_per_gas,
+            add_base_fee_by_priority_fee,
+            is_max_fee_lt_base_fee,
+            is_max_fee_lt_priority_fee,
+            is_max_fee_lt_fee,
             mul_gas_fee_by_gas,
             tx_caller_address,
             tx_caller_address_is_zero,
@@ -406,6 +534,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_value,
             tx_call_data_length,
             tx_call_data_gas_cost,
+            tx_access_list_addresses_len,
+            tx_access_list_storage_keys_len,
+            tx_access_list_addresses_rlc,
+            tx_access_list_storage_keys_rlc,
+            is_access_list_addresses_empty,
+            is_access_list_storage_keys_empty,
             reversion_info,
             sufficient_gas_left,
             transfer_with_gas_fee,
@@ -414,6 +548,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             caller_nonce_hash_bytes,
             create,
             callee_not_exists,
+            is_precompile,
             is_caller_callee_equal,
         }
     }
@@ -430,14 +565,16 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let gas_fee = tx.gas_price * tx.gas;
         let zero = eth_types::Word::zero();
 
+        let (access_list_addresses_len, access_list_storage_keys_len) =
+            access_list_len(&tx.access_list);
+
         let mut rws = StepRws::new(block, step);
         rws.offset_add(7);
         let mut callee_code_hash = zero;
-        if !is_precompiled(&tx.callee_address) && !tx.is_create {
+        if !tx.is_create {
             callee_code_hash = rws.next().account_value_pair().1;
         }
-        let callee_exists =
-            is_precompiled(&tx.callee_address) || (!tx.is_create && !callee_code_hash.is_zero());
+        let callee_exists = !tx.is_create && !callee_code_hash.is_zero();
         let caller_balance_sub_fee_pair = rws.next().account_value_pair();
         let must_create = tx.is_create;
         if (!callee_exists && !tx.value.is_zero()) || must_create {
@@ -458,6 +595,26 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             .assign(region, offset, Value::known(F::from(tx.gas)))?;
         self.tx_gas_price
             .assign(region, offset, Some(tx.gas_price.to_le_bytes()))?;
+        self.tx_max_fee_per_gas
+            .assign(region, offset, Some(tx.max_fee_per_gas.to_le_bytes()))?;
+        let base_fee = block.context.base_fee;
+        self.is_max_fee_lt_base_fee
+            .assign(region, offset, tx.max_fee_per_gas, base_fee)?;
+        self.is_max_fee_lt_priority_fee.assign(
+            region,
+            offset,
+            tx.max_fee_per_gas,
+            tx.max_priority_fee_per_gas,
+        )?;
+        let fee = base_fee + tx.max_priority_fee_per_gas;
+        self.add_base_fee_by_priority_fee.assign(
+            region,
+            offset,
+            [base_fee, tx.max_priority_fee_per_gas],
+            fee,
+        )?;
+        self.is_max_fee_lt_fee
+            .assign(region, offset, tx.max_fee_per_gas, fee)?;
         self.mul_gas_fee_by_gas
             .assign(region, offset, tx.gas_price, tx.gas, gas_fee)?;
         let caller_address = tx
@@ -504,6 +661,43 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             offset,
             Value::known(F::from(tx.call_data_gas_cost)),
         )?;
+        self.tx_access_list_addresses_len.assign(
+            region,
+            offset,
+            Value::known(F::from(access_list_addresses_len)),
+        )?;
+        self.tx_access_list_storage_keys_len.assign(
+            region,
+            offset,
+            Value::known(F::from(access_list_storage_keys_len)),
+        )?;
+        let access_list_rlc = region
+            .challenges()
+            .keccak_input()
+            .zip(region.challenges().evm_word())
+            .map(|(keccak_input, evm_word)| {
+                access_list_rlc(&tx.access_list, keccak_input, evm_word)
+            });
+        self.tx_access_list_addresses_rlc.assign(
+            region,
+            offset,
+            access_list_rlc.map(|(addresses_rlc, _)| addresses_rlc),
+        )?;
+        self.tx_access_list_storage_keys_rlc.assign(
+            region,
+            offset,
+            access_list_rlc.map(|(_, storage_keys_rlc)| storage_keys_rlc),
+        )?;
+        self.is_access_list_addresses_empty.assign(
+            region,
+            offset,
+            F::from(access_list_addresses_len),
+        )?;
+        self.is_access_list_storage_keys_empty.assign(
+            region,
+            offset,
+            F::from(access_list_storage_keys_len),
+        )?;
         self.reversion_info.assign(
             region,
             offset,
@@ -531,6 +725,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         )?;
         self.callee_not_exists
             .assign_value(region, offset, region.word_rlc(callee_code_hash))?;
+        self.is_precompile
+            .assign(region, offset, tx.callee_address)?;
 
         let untrimmed_contract_addr = {
             let mut stream = ethers_core::utils::rlp::RlpStream::new();
@@ -567,7 +763,8 @@ mod test {
 
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
     use bus_mapping::evm::OpcodeId;
-    use eth_types::{self, bytecode, evm_types::GasCost, word, Bytecode, Word};
+    use eth_types::{self, bytecode, evm_types::GasCost, word, AccessList, Bytecode, Word, H256};
+    use ethers_core::types::transaction::eip2930::AccessListItem;
 
     use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
 
@@ -759,6 +956,84 @@ mod test {
         CircuitTestBuilder::new_from_test_ctx(ctx).run();
     }
 
+    fn begin_tx_dynamic_fee(base_fee: Word, max_fee_per_gas: Word, max_priority_fee_per_gas: Word) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code_with_return());
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .transaction_type(2)
+                    .max_fee_per_gas(max_fee_per_gas)
+                    .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                    .value(eth(1));
+            },
+            |block, _tx| block.base_fee_per_gas(base_fee),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn begin_tx_dynamic_fee_priority_fee() {
+        // Effective gas price is base_fee + max_priority_fee_per_gas
+        begin_tx_dynamic_fee(gwei(1), gwei(3), gwei(1));
+    }
+
+    #[test]
+    fn begin_tx_dynamic_fee_max_fee() {
+        // Effective gas price is capped by max_fee_per_gas
+        begin_tx_dynamic_fee(gwei(1), gwei(2), gwei(2));
+    }
+
+    #[test]
+    fn begin_tx_access_list() {
+        // SLOAD of a storage key in the access list is warm
+        let code = bytecode! {
+            PUSH1(1)
+            SLOAD
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .gas_price(gwei(2))
+                    .access_list(AccessList(vec![
+                        AccessListItem {
+                            address: MOCK_ACCOUNTS[0],
+                            storage_keys: vec![H256::from_low_u64_be(0), H256::from_low_u64_be(1)],
+                        },
+                        AccessListItem {
+                            address: MOCK_ACCOUNTS[2],
+                            storage_keys: vec![],
+                        },
+                    ]));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     fn begin_tx_deploy(nonce: u64) {
         let code = bytecode! {
             // [ADDRESS, STOP]
@@ -4,7 +4,7 @@ use crate::evm_circuit::{
     step::ExecutionState,
     util::{
         and,
-        common_gadget::{CommonCallGadget, TransferGadget},
+        common_gadget::{CommonCallGadget, IsPrecompileGadget, TransferGadget},
         constraint_builder::{
             ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
             Transition::{Delta, To},
@@ -20,7 +20,9 @@ use crate::{
     util::Expr,
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::{evm_types::GAS_STIPEND_CALL_WITH_VALUE, Field, ToLittleEndian, ToScalar, U256};
+use eth_types::{
+    evm_types::GAS_STIPEND_CALL_WITH_VALUE, Field, ToAddress, ToLittleEndian, ToScalar, U256,
+};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget for call related opcodes. It supports `OpcodeId::CALL`,
@@ -53,6 +55,7 @@ pub(crate) struct CallOpGadget<F> {
     is_depth_ok: LtGadget<F, N_BYTES_U64>,
     one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
     capped_callee_gas_left: MinMaxGadget<F, N_BYTES_GAS>,
+    is_precompile: IsPrecompileGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
@@ -171,30 +174,30 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             );
         });
 
-        // Verify transfer only for CALL opcode in the successful case.  If value == 0,
-        // skip the transfer (this is necessary for non-existing accounts, which
-        // will not be crated when value is 0 and so the callee balance lookup
-        // would be invalid).
-        let transfer = cb.condition(and::expr(&[is_call.expr(), is_precheck_ok.expr()]), |cb| {
-            TransferGadget::construct(
-                cb,
-                caller_address.expr(),
-                callee_address.expr(),
-                not::expr(call_gadget.callee_not_exists.expr()),
-                call_gadget.value.clone(),
-                &mut callee_reversion_info,
-            )
-        });
-
-        // For CALLCODE opcode, verify caller balance is greater than or equal to stack
-        // `value` in successful case. that is `is_insufficient_balance` is false.
-        // for call opcode, this has been checked in transfer gadget implicitly.
-        cb.condition(is_callcode.expr() * call_gadget.is_success.expr(), |cb| {
-            cb.require_zero(
-                "transfer_value <= caller_balance for CALLCODE opcode in successful case ",
-                is_insufficient_balance.expr(),
-            );
-        });
+        // Verify transfer only for CALL and CALLCODE opcodes in the successful case.
+        // For CALLCODE the callee address is the caller's own address, so the
+        // value is transferred from the caller to itself.  If value == 0, skip
+        // the transfer (this is necessary for non-existing accounts, which will
+        // not be crated when value is 0 and so the callee balance lookup would be
+        // invalid).
+        let is_call_or_callcode = is_call.expr() + is_callcode.expr();
+        let transfer = cb.condition(
+            and::expr(&[is_call_or_callcode.expr(), is_precheck_ok.expr()]),
+            |cb| {
+                TransferGadget::construct(
+                    cb,
+                    caller_address.expr(),
+                    callee_address.expr(),
+                    or::expr([
+                        is_callcode.expr(),
+                        not::expr(call_gadget.callee_not_exists.expr()),
+                    ]),
+                    0.expr(),
+                    call_gadget.value.clone(),
+                    &mut callee_reversion_info,
+                )
+            },
+        );
 
         // no_callee_code is true when the account exists and has empty
         // code hash, or when the account doesn't exist (which we encode with
@@ -217,13 +220,23 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             all_but_one_64th_gas,
         );
 
-        // TODO: Handle precompiled
+        // A call to a precompiled contract is handled like a call to an account
+        // with code, except that the next step executes the precompile.
+        let is_precompile = IsPrecompileGadget::construct(cb, call_gadget.callee_address_expr());
+        IsPrecompileGadget::constrain_next_state(
+            cb,
+            call_gadget.callee_address_expr(),
+            and::expr([is_precompile.expr(), is_precheck_ok.expr()]),
+        );
 
-        let stack_pointer_delta =
-            select::expr(is_call.expr() + is_callcode.expr(), 6.expr(), 5.expr());
+        let stack_pointer_delta = select::expr(is_call_or_callcode.expr(), 6.expr(), 5.expr());
         let memory_expansion = call_gadget.memory_expansion.clone();
         cb.condition(
-            and::expr(&[no_callee_code.expr(), is_precheck_ok.expr()]),
+            and::expr(&[
+                no_callee_code.expr(),
+                not::expr(is_precompile.expr()),
+                is_precheck_ok.expr(),
+            ]),
             |cb| {
                 // Save caller's call state
                 for field_tag in [
@@ -234,22 +247,19 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     cb.call_context_lookup(true.expr(), None, field_tag, 0.expr());
                 }
 
-                // For CALL opcode, it has an extra stack pop `value` (+1) and if the value is
-                // not zero, two account write for `transfer` call (+2).
-                //
-                // For CALLCODE opcode, it has an extra stack pop `value` and one account read
-                // for caller balance (+2).
+                // For CALL and CALLCODE opcodes, it has an extra stack pop `value` (+1) and if
+                // the value is not zero, two account write for `transfer` call (+2).
                 //
                 // For DELEGATECALL opcode, it has two extra call context lookups for current
                 // caller address and value (+2).
                 //
                 // No extra lookups for STATICCALL opcode.
-                let transfer_rwc_delta =
-                    is_call.expr() * not::expr(transfer.value_is_zero.expr()) * 2.expr();
+                let transfer_rwc_delta = is_call_or_callcode.expr()
+                    * not::expr(transfer.value_is_zero.expr())
+                    * 2.expr();
                 let rw_counter_delta = 21.expr()
-                    + is_call.expr() * 1.expr()
+                    + is_call_or_callcode.expr()
                     + transfer_rwc_delta.clone()
-                    + is_callcode.expr()
                     + is_delegatecall.expr() * 2.expr();
                 cb.require_step_state_transition(StepStateTransition {
                     rw_counter: Delta(rw_counter_delta),
@@ -260,8 +270,8 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                             - gas_cost.clone(),
                     ),
                     memory_word_size: To(memory_expansion.next_memory_word_size()),
-                    // For CALL opcode, `transfer` invocation has two account write if value is not
-                    // zero.
+                    // For CALL and CALLCODE opcodes, `transfer` invocation has two account write
+                    // if value is not zero.
                     reversible_write_counter: Delta(1.expr() + transfer_rwc_delta),
                     ..StepStateTransition::default()
                 });
@@ -294,7 +304,10 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
         });
 
         cb.condition(
-            and::expr(&[not::expr(no_callee_code), is_precheck_ok.expr()]),
+            and::expr(&[
+                or::expr([not::expr(no_callee_code), is_precompile.expr()]),
+                is_precheck_ok.expr(),
+            ]),
             |cb| {
                 // Save caller's call state
                 for (field_tag, value) in [
@@ -373,22 +386,19 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                 let callee_gas_left = callee_gas_left
                     + call_gadget.has_value.clone() * GAS_STIPEND_CALL_WITH_VALUE.expr();
 
-                // For CALL opcode, it has an extra stack pop `value` (+1) and if the value is
-                // not zero, two account write for `transfer` call (+2).
-                //
-                // For CALLCODE opcode, it has an extra stack pop `value` and one account read
-                // for caller balance (+2).
+                // For CALL and CALLCODE opcodes, it has an extra stack pop `value` (+1) and if
+                // the value is not zero, two account write for `transfer` call (+2).
                 //
                 // For DELEGATECALL opcode, it has two extra call context lookups for current
                 // caller address and value (+2).
                 //
                 // No extra lookups for STATICCALL opcode.
-                let transfer_rwc_delta =
-                    is_call.expr() * not::expr(transfer.value_is_zero.expr()) * 2.expr();
+                let transfer_rwc_delta = is_call_or_callcode.expr()
+                    * not::expr(transfer.value_is_zero.expr())
+                    * 2.expr();
                 let rw_counter_delta = 41.expr()
-                    + is_call.expr() * 1.expr()
+                    + is_call_or_callcode.expr()
                     + transfer_rwc_delta.clone()
-                    + is_callcode.expr()
                     + is_delegatecall.expr() * 2.expr();
                 cb.require_step_state_transition(StepStateTransition {
                     rw_counter: Delta(rw_counter_delta),
@@ -397,8 +407,8 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     is_create: To(false.expr()),
                     code_hash: To(call_gadget.phase2_callee_code_hash.expr()),
                     gas_left: To(callee_gas_left),
-                    // For CALL opcode, `transfer` invocation has two account write if value is not
-                    // zero.
+                    // For CALL and CALLCODE opcodes, `transfer` invocation has two account write
+                    // if value is not zero.
                     reversible_write_counter: To(transfer_rwc_delta),
                     ..StepStateTransition::new_context()
                 });
@@ -428,6 +438,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             is_depth_ok,
             one_64th_gas,
             capped_callee_gas_left,
+            is_precompile,
         }
     }
 
@@ -509,17 +520,20 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             .assign(region, offset, caller_balance, value)?;
 
         let is_insufficient = (value > caller_balance) && (is_call || is_callcode);
-        // only call opcode do transfer in sucessful case.
-        let (caller_balance_pair, callee_balance_pair) =
-            if is_call && !is_insufficient && !is_error_depth && !value.is_zero() {
-                rw_offset += 2;
-                (
-                    block.rws[step.rw_indices[16 + rw_offset]].account_value_pair(),
-                    block.rws[step.rw_indices[17 + rw_offset]].account_value_pair(),
-                )
-            } else {
-                ((U256::zero(), U256::zero()), (U256::zero(), U256::zero()))
-            };
+        // only call and callcode opcodes do transfer in sucessful case.
+        let (caller_balance_pair, callee_balance_pair) = if (is_call || is_callcode)
+            && !is_insufficient
+            && !is_error_depth
+            && !value.is_zero()
+        {
+            rw_offset += 2;
+            (
+                block.rws[step.rw_indices[16 + rw_offset]].account_value_pair(),
+                block.rws[step.rw_indices[17 + rw_offset]].account_value_pair(),
+            )
+        } else {
+            ((U256::zero(), U256::zero()), (U256::zero(), U256::zero()))
+        };
 
         self.opcode
             .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
@@ -629,6 +643,8 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             F::from(gas.low_u64()),
             F::from(gas_available - gas_available / 64),
         )?;
+        self.is_precompile
+            .assign(region, offset, callee_address.to_address())?;
 
         Ok(())
     }
@@ -676,6 +692,20 @@ mod test {
         }
     }
 
+    #[test]
+    fn callop_callcode_with_value() {
+        // CALLCODE transfers the value from the caller to itself.
+        let stack = Stack {
+            value: Word::from(10).pow(18.into()),
+            ..Default::default()
+        };
+        let callees = [callee(bytecode! {}), callee(bytecode! { STOP })];
+
+        for callee in callees {
+            test_ok(caller(&OpcodeId::CALLCODE, stack, true), callee);
+        }
+    }
+
     #[test]
     fn callop_nested() {
         for opcode in TEST_CALL_OPCODES {
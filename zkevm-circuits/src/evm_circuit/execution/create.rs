@@ -0,0 +1,902 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{
+            N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+            N_BYTES_WORD,
+        },
+        step::ExecutionState,
+        util::{
+            and,
+            common_gadget::TransferGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            math_gadget::{
+                ConstantDivisionGadget, ContractCreateGadget, IsZeroGadget, LtGadget, LtWordGadget,
+            },
+            memory_gadget::{MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget},
+            not, CachedRegion, Cell, StepRws, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
+use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToLittleEndian, ToScalar, ToWord, U256};
+use ethers_core::utils::{keccak256, rlp};
+use gadgets::util::expr_from_bytes;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for CREATE and CREATE2 opcodes.
+///
+/// The contract address is derived with a lookup to the keccak table, and the
+/// initialization code is copied from the caller's memory into the bytecode
+/// table. If the precheck (depth, caller balance and caller nonce) fails, the
+/// contract address collides with an existing account, or the initialization
+/// code is empty, the initialization call returns immediately and the execution
+/// continues in the caller's context.
+#[derive(Clone, Debug)]
+pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionState> {
+    opcode: Cell<F>,
+    tx_id: Cell<F>,
+    depth: Cell<F>,
+    caller_address: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    value: Word<F>,
+    init_code: MemoryAddressGadget<F>,
+    is_success: Cell<F>,
+    caller_balance: Word<F>,
+    is_depth_in_range: LtGadget<F, N_BYTES_U64>,
+    is_insufficient_balance: LtWordGadget<F>,
+    is_nonce_in_range: LtGadget<F, N_BYTES_U64>,
+    was_warm: Cell<F>,
+    callee_nonce: Cell<F>,
+    callee_code_hash: Cell<F>,
+    is_callee_nonce_zero: IsZeroGadget<F>,
+    is_callee_code_empty: IsZeroGadget<F>,
+    create: ContractCreateGadget<F, IS_CREATE2>,
+    keccak_output: [Cell<F>; N_BYTES_WORD],
+    callee_reversion_info: ReversionInfo<F>,
+    transfer: TransferGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    init_code_hash_gas: MemoryCopierGasGadget<F, { GasCost::COPY_SHA3 }>,
+    gas_left: ConstantDivisionGadget<F, N_BYTES_GAS>,
+}
+
+impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<F>
+    for CreateGadget<F, IS_CREATE2, S>
+{
+    const NAME: &'static str = if IS_CREATE2 { "CREATE2" } else { "CREATE" };
+
+    const EXECUTION_STATE: ExecutionState = S;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.require_equal(
+            "Opcode is CREATE or CREATE2",
+            opcode.expr(),
+            if IS_CREATE2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            }
+            .expr(),
+        );
+
+        // Use rw_counter of the step which triggers next call as its call_id.
+        let callee_call_id = cb.curr.state.rw_counter.clone();
+
+        let [tx_id, depth, caller_address] = [
+            CallContextFieldTag::TxId,
+            CallContextFieldTag::Depth,
+            CallContextFieldTag::CalleeAddress,
+        ]
+        .map(|field_tag| cb.call_context(None, field_tag));
+        let mut reversion_info = cb.reversion_info_read(None);
+
+        let value = cb.query_word_rlc();
+        let init_code_offset = cb.query_cell_phase2();
+        let init_code_length = cb.query_word_rlc();
+        let create = ContractCreateGadget::construct(cb);
+
+        cb.stack_pop(value.expr());
+        cb.stack_pop(init_code_offset.expr());
+        cb.stack_pop(init_code_length.expr());
+        if IS_CREATE2 {
+            cb.stack_pop(create.salt_word_rlc(cb));
+        }
+        let init_code = MemoryAddressGadget::construct(cb, init_code_offset, init_code_length);
+
+        // Contract address is the lower 20 bytes of the keccak hash of either
+        // RLP([caller_address, caller_nonce]) for CREATE or
+        // 0xff ++ caller_address ++ salt ++ keccak256(init_code) for CREATE2.
+        let keccak_output = array_init::array_init(|_| cb.query_byte());
+        cb.keccak_table_lookup(
+            create.input_rlc(cb),
+            create.input_length(),
+            cb.word_rlc::<N_BYTES_WORD>(
+                keccak_output
+                    .iter()
+                    .map(Expr::expr)
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap(),
+            ),
+        );
+        let contract_address = expr_from_bytes(&keccak_output[..N_BYTES_ACCOUNT_ADDRESS]);
+        let contract_address_rlc = cb.word_rlc::<N_BYTES_ACCOUNT_ADDRESS>(
+            keccak_output[..N_BYTES_ACCOUNT_ADDRESS]
+                .iter()
+                .map(Expr::expr)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        );
+
+        // Stack push is the contract address when the initialization call
+        // succeeds, otherwise it's zero.
+        let is_success = cb.query_bool();
+        cb.stack_push(is_success.expr() * contract_address_rlc);
+
+        cb.require_equal(
+            "caller address equivalence",
+            caller_address.expr(),
+            create.caller_address(),
+        );
+        let caller_balance = cb.query_word_rlc();
+        cb.account_read(
+            caller_address.expr(),
+            AccountFieldTag::Balance,
+            caller_balance.expr(),
+        );
+        cb.account_read(
+            caller_address.expr(),
+            AccountFieldTag::Nonce,
+            create.caller_nonce(),
+        );
+
+        // Precheck is OK when depth is in range, caller balance is sufficient
+        // and caller nonce doesn't overflow.
+        let is_depth_in_range = LtGadget::construct(cb, depth.expr(), 1025.expr());
+        let is_insufficient_balance = LtWordGadget::construct(cb, &caller_balance, &value);
+        let is_nonce_in_range = LtGadget::construct(cb, create.caller_nonce(), u64::MAX.expr());
+        let is_precheck_ok = and::expr([
+            is_depth_in_range.expr(),
+            not::expr(is_insufficient_balance.expr()),
+            is_nonce_in_range.expr(),
+        ]);
+
+        let was_warm = cb.query_bool();
+        let callee_nonce = cb.query_cell();
+        let callee_code_hash = cb.query_cell_phase2();
+        cb.condition(is_precheck_ok.clone(), |cb| {
+            // Increase caller's nonce
+            cb.account_write(
+                caller_address.expr(),
+                AccountFieldTag::Nonce,
+                create.caller_nonce() + 1.expr(),
+                create.caller_nonce(),
+                Some(&mut reversion_info),
+            );
+
+            // Add contract address to access list
+            cb.account_access_list_write(
+                tx_id.expr(),
+                contract_address.clone(),
+                1.expr(),
+                was_warm.expr(),
+                Some(&mut reversion_info),
+            );
+
+            // Read the nonce and code hash at the contract address
+            cb.account_read(
+                contract_address.clone(),
+                AccountFieldTag::Nonce,
+                callee_nonce.expr(),
+            );
+            cb.account_read(
+                contract_address.clone(),
+                AccountFieldTag::CodeHash,
+                callee_code_hash.expr(),
+            );
+        });
+        cb.condition(not::expr(is_precheck_ok.clone()), |cb| {
+            cb.require_zero("is_success is false when precheck fails", is_success.expr());
+        });
+
+        // The contract address collides with an existing account when its
+        // nonce is non-zero or its code hash is neither 0 (non-existing
+        // account) nor the empty code hash.
+        let is_callee_nonce_zero = IsZeroGadget::construct(cb, callee_nonce.expr());
+        let is_callee_code_empty = IsZeroGadget::construct(
+            cb,
+            callee_code_hash.expr() * (callee_code_hash.expr() - cb.empty_code_hash_rlc()),
+        );
+        let is_address_collision = is_precheck_ok.clone()
+            * not::expr(is_callee_nonce_zero.expr() * is_callee_code_empty.expr());
+        cb.condition(is_address_collision.clone(), |cb| {
+            cb.require_zero(
+                "is_success is false when contract address collides",
+                is_success.expr(),
+            );
+        });
+        // The contract account is created when the precheck is OK and there's
+        // no address collision.
+        let is_callee_created = is_precheck_ok.clone() - is_address_collision.clone();
+
+        // Propagate rw_counter_end_of_reversion and is_persistent
+        let mut callee_reversion_info = cb.reversion_info_write(Some(callee_call_id.expr()));
+        cb.require_equal(
+            "callee_is_persistent == is_persistent ⋅ is_success",
+            callee_reversion_info.is_persistent(),
+            reversion_info.is_persistent() * is_success.expr(),
+        );
+        cb.condition(is_success.expr() * (1.expr() - reversion_info.is_persistent()), |cb| {
+            cb.require_equal(
+                "callee_rw_counter_end_of_reversion == rw_counter_end_of_reversion - (reversible_write_counter + 1)",
+                callee_reversion_info.rw_counter_end_of_reversion(),
+                reversion_info.rw_counter_of_reversion(1.expr()),
+            );
+        });
+
+        // Transfer value to the new contract account, which is always created
+        // by writing the empty code hash, and set its nonce to 1.
+        let transfer = cb.condition(is_callee_created.clone(), |cb| {
+            let transfer = TransferGadget::construct(
+                cb,
+                caller_address.expr(),
+                contract_address.clone(),
+                0.expr(),
+                1.expr(),
+                value.clone(),
+                &mut callee_reversion_info,
+            );
+            cb.account_write(
+                contract_address.clone(),
+                AccountFieldTag::Nonce,
+                1.expr(),
+                0.expr(),
+                Some(&mut callee_reversion_info),
+            );
+            transfer
+        });
+
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [init_code.address()]);
+        let init_code_hash_gas =
+            MemoryCopierGasGadget::construct(cb, init_code.length(), memory_expansion.gas_cost());
+        // CREATE2 additionally pays for hashing the initialization code.
+        let gas_cost = GasCost::CREATE.expr()
+            + if IS_CREATE2 {
+                init_code_hash_gas.gas_cost()
+            } else {
+                memory_expansion.gas_cost()
+            };
+        // Apply EIP 150
+        let gas_remaining = cb.curr.state.gas_left.expr() - gas_cost.clone();
+        let gas_left = ConstantDivisionGadget::construct(cb, gas_remaining.clone(), 64);
+        let callee_gas_left = gas_remaining - gas_left.quotient();
+
+        let code_hash = create.code_hash_word_rlc(cb);
+        cb.condition(not::expr(init_code.has_length()), |cb| {
+            cb.require_equal(
+                "code hash of empty initialization code",
+                code_hash.clone(),
+                cb.empty_code_hash_rlc(),
+            );
+        });
+
+        let stack_pointer_delta = if IS_CREATE2 { 3 } else { 2 };
+        let is_entering_init_call = is_callee_created.clone() * init_code.has_length();
+
+        // 1. Enter the initialization call with non-empty initialization code.
+        cb.condition(is_entering_init_call.clone(), |cb| {
+            // Copy the initialization code from memory into the bytecode table.
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                code_hash.clone(),
+                CopyDataType::Bytecode.expr(),
+                init_code.offset(),
+                init_code.address(),
+                0.expr(),
+                init_code.length(),
+                0.expr(),
+                init_code.length(),
+            );
+
+            // Save caller's call state
+            for (field_tag, value) in [
+                (
+                    CallContextFieldTag::ProgramCounter,
+                    cb.curr.state.program_counter.expr() + 1.expr(),
+                ),
+                (
+                    CallContextFieldTag::StackPointer,
+                    cb.curr.state.stack_pointer.expr() + stack_pointer_delta.expr(),
+                ),
+                (CallContextFieldTag::GasLeft, gas_left.quotient()),
+                (
+                    CallContextFieldTag::MemorySize,
+                    memory_expansion.next_memory_word_size(),
+                ),
+                (
+                    CallContextFieldTag::ReversibleWriteCounter,
+                    cb.curr.state.reversible_write_counter.expr() + 2.expr(),
+                ),
+            ] {
+                cb.call_context_lookup(true.expr(), None, field_tag, value);
+            }
+
+            // Setup next call's context.
+            for (field_tag, value) in [
+                (CallContextFieldTag::CallerId, cb.curr.state.call_id.expr()),
+                (CallContextFieldTag::IsSuccess, is_success.expr()),
+                (CallContextFieldTag::TxId, tx_id.expr()),
+                (CallContextFieldTag::CallerAddress, caller_address.expr()),
+                (CallContextFieldTag::CalleeAddress, contract_address.clone()),
+                (CallContextFieldTag::Depth, depth.expr() + 1.expr()),
+                (CallContextFieldTag::Value, value.expr()),
+                (CallContextFieldTag::IsStatic, 0.expr()),
+                (CallContextFieldTag::LastCalleeId, 0.expr()),
+                (CallContextFieldTag::LastCalleeReturnDataOffset, 0.expr()),
+                (CallContextFieldTag::LastCalleeReturnDataLength, 0.expr()),
+                (CallContextFieldTag::IsRoot, 0.expr()),
+                (CallContextFieldTag::IsCreate, 1.expr()),
+                (CallContextFieldTag::CodeHash, code_hash.clone()),
+            ] {
+                cb.call_context_lookup(true.expr(), Some(callee_call_id.expr()), field_tag, value);
+            }
+
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(cb.rw_counter_offset()),
+                call_id: To(callee_call_id.expr()),
+                is_root: To(false.expr()),
+                is_create: To(true.expr()),
+                code_hash: To(code_hash.clone()),
+                gas_left: To(callee_gas_left.clone()),
+                // Transfer and callee's nonce
+                reversible_write_counter: To(transfer.reversible_w_delta() + 1.expr()),
+                ..StepStateTransition::new_context()
+            });
+        });
+
+        // 2. Empty initialization code, failed precheck or address collision,
+        // the initialization call returns immediately and the execution
+        // continues in caller.
+        cb.condition(not::expr(is_entering_init_call), |cb| {
+            cb.condition(is_callee_created, |cb| {
+                cb.require_equal(
+                    "is_success is true for empty initialization code",
+                    is_success.expr(),
+                    1.expr(),
+                );
+            });
+
+            // Save caller's call state
+            for (field_tag, value) in [
+                (CallContextFieldTag::LastCalleeId, callee_call_id.expr()),
+                (CallContextFieldTag::LastCalleeReturnDataOffset, 0.expr()),
+                (CallContextFieldTag::LastCalleeReturnDataLength, 0.expr()),
+            ] {
+                cb.call_context_lookup(true.expr(), None, field_tag, value);
+            }
+
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(cb.rw_counter_offset()),
+                program_counter: Delta(1.expr()),
+                stack_pointer: Delta(stack_pointer_delta.expr()),
+                // The gas sent to the initialization call is consumed when the
+                // contract address collides.
+                gas_left: Delta(-gas_cost - is_address_collision * callee_gas_left),
+                memory_word_size: To(memory_expansion.next_memory_word_size()),
+                // Caller's nonce and access list, plus the transfer and the
+                // callee's nonce which are kept since the callee succeeds.
+                reversible_write_counter: Delta(
+                    is_precheck_ok.clone() * 2.expr()
+                        + is_success.expr() * (transfer.reversible_w_delta() + 1.expr()),
+                ),
+                ..StepStateTransition::default()
+            });
+        });
+
+        Self {
+            opcode,
+            tx_id,
+            depth,
+            caller_address,
+            reversion_info,
+            value,
+            init_code,
+            is_success,
+            caller_balance,
+            is_depth_in_range,
+            is_insufficient_balance,
+            is_nonce_in_range,
+            was_warm,
+            callee_nonce,
+            callee_code_hash,
+            is_callee_nonce_zero,
+            is_callee_code_empty,
+            create,
+            keccak_output,
+            callee_reversion_info,
+            transfer,
+            memory_expansion,
+            init_code_hash_gas,
+            gas_left,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let mut rws = StepRws::new(block, step);
+
+        let [tx_id, depth, caller_address] = [(); 3].map(|_| rws.next().call_context_value());
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.depth
+            .assign(region, offset, Value::known(F::from(depth.low_u64())))?;
+        self.caller_address.assign(
+            region,
+            offset,
+            Value::known(
+                caller_address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+        // rw_counter_end_of_reversion and is_persistent
+        rws.offset_add(5);
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+
+        let [value, init_code_offset, init_code_length] = [(); 3].map(|_| rws.next().stack_value());
+        let salt = if IS_CREATE2 {
+            Some(rws.next().stack_value())
+        } else {
+            None
+        };
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        let init_code_address =
+            self.init_code
+                .assign(region, offset, init_code_offset, init_code_length)?;
+        // Contract address pushed to stack
+        rws.next();
+
+        let caller_balance = rws.next().account_value_pair().0;
+        let caller_nonce = rws.next().account_value_pair().0.low_u64();
+        self.caller_balance
+            .assign(region, offset, Some(caller_balance.to_le_bytes()))?;
+        self.is_depth_in_range
+            .assign(region, offset, F::from(depth.low_u64()), F::from(1025))?;
+        self.is_insufficient_balance
+            .assign(region, offset, caller_balance, value)?;
+        self.is_nonce_in_range
+            .assign(region, offset, F::from(caller_nonce), F::from(u64::MAX))?;
+        let is_precheck_ok =
+            depth.low_u64() < 1025 && caller_balance >= value && caller_nonce < u64::MAX;
+
+        let (was_warm, callee_nonce, callee_code_hash) = if is_precheck_ok {
+            // Caller's nonce
+            rws.next();
+            (
+                rws.next().tx_access_list_value_pair().1,
+                rws.next().account_value_pair().0,
+                rws.next().account_value_pair().0,
+            )
+        } else {
+            (false, U256::zero(), U256::zero())
+        };
+        self.was_warm
+            .assign(region, offset, Value::known(F::from(was_warm as u64)))?;
+        self.callee_nonce.assign(
+            region,
+            offset,
+            Value::known(F::from(callee_nonce.low_u64())),
+        )?;
+        let callee_code_hash_rlc = region.word_rlc(callee_code_hash);
+        self.callee_code_hash
+            .assign(region, offset, callee_code_hash_rlc)?;
+        self.is_callee_nonce_zero
+            .assign(region, offset, F::from(callee_nonce.low_u64()))?;
+        self.is_callee_code_empty.assign_value(
+            region,
+            offset,
+            callee_code_hash_rlc * (callee_code_hash_rlc - region.empty_code_hash_rlc()),
+        )?;
+        let is_callee_code_empty =
+            callee_code_hash.is_zero() || callee_code_hash == CodeDB::empty_code_hash().to_word();
+        let is_address_collision = !callee_nonce.is_zero() || !is_callee_code_empty;
+        let is_callee_created = is_precheck_ok && !is_address_collision;
+
+        let [callee_rw_counter_end_of_reversion, callee_is_persistent] =
+            [(); 2].map(|_| rws.next().call_context_value());
+        self.callee_reversion_info.assign(
+            region,
+            offset,
+            callee_rw_counter_end_of_reversion.low_u64() as usize,
+            callee_is_persistent.low_u64() != 0,
+        )?;
+
+        let (caller_balance_pair, callee_balance_pair) = if is_callee_created && !value.is_zero() {
+            // Contract account creation
+            rws.next();
+            (
+                rws.next().account_value_pair(),
+                rws.next().account_value_pair(),
+            )
+        } else {
+            ((U256::zero(), U256::zero()), (U256::zero(), U256::zero()))
+        };
+        self.transfer.assign(
+            region,
+            offset,
+            caller_balance_pair,
+            callee_balance_pair,
+            value,
+        )?;
+
+        let callee = transaction
+            .calls
+            .iter()
+            .find(|callee| callee.id == step.rw_counter)
+            .expect("callee of CREATE/CREATE2 not found");
+        self.is_success.assign(
+            region,
+            offset,
+            Value::known(F::from(callee.is_success as u64)),
+        )?;
+
+        let init_code_hash = callee.code_hash;
+        self.create.assign(
+            region,
+            offset,
+            call.callee_address,
+            caller_nonce,
+            Some(init_code_hash),
+            salt,
+        )?;
+
+        let keccak_input = if let Some(salt) = salt {
+            std::iter::once(0xffu8)
+                .chain(call.callee_address.to_fixed_bytes())
+                .chain(salt.to_be_bytes())
+                .chain(init_code_hash.to_be_bytes())
+                .collect::<Vec<_>>()
+        } else {
+            let mut stream = rlp::RlpStream::new();
+            stream.begin_list(2);
+            stream.append(&call.callee_address);
+            stream.append(&U256::from(caller_nonce));
+            stream.out().to_vec()
+        };
+        for (c, v) in self
+            .keccak_output
+            .iter()
+            .rev()
+            .zip(keccak256(&keccak_input).iter())
+        {
+            c.assign(region, offset, Value::known(F::from(*v as u64)))?;
+        }
+
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [init_code_address],
+        )?;
+        let init_code_hash_gas_cost = self.init_code_hash_gas.assign(
+            region,
+            offset,
+            init_code_length.as_u64(),
+            memory_expansion_gas_cost,
+        )?;
+        let gas_cost = GasCost::CREATE.as_u64()
+            + if IS_CREATE2 {
+                init_code_hash_gas_cost
+            } else {
+                memory_expansion_gas_cost
+            };
+        self.gas_left
+            .assign(region, offset, (step.gas_left - gas_cost).into())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::Account, word, Address, Bytecode,
+        ToBigEndian, Word,
+    };
+    use ethers_core::utils::{get_contract_address, get_create2_address};
+    use itertools::Itertools;
+    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+
+    fn run_test_circuits(ctx: TestContext<2, 1>) {
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    // RETURN or REVERT with data of [0x60; 5]
+    fn initialization_bytecode(is_success: bool) -> Bytecode {
+        let memory_bytes = [0x60; 10];
+        let memory_address = 0;
+        let memory_value = Word::from_big_endian(&memory_bytes);
+        let mut code = bytecode! {
+            PUSH10(memory_value)
+            PUSH1(memory_address)
+            MSTORE
+            PUSH2(5)
+            PUSH2(32u64 - u64::try_from(memory_bytes.len()).unwrap())
+        };
+        code.write_op(if is_success {
+            OpcodeId::RETURN
+        } else {
+            OpcodeId::REVERT
+        });
+
+        code
+    }
+
+    fn creator_bytecode(
+        initialization_bytecode: Bytecode,
+        value: Word,
+        is_create2: bool,
+        is_persistent: bool,
+    ) -> Bytecode {
+        let initialization_bytes = initialization_bytecode.code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&initialization_bytes))
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(45)}); // salt
+        }
+        code.append(&bytecode! {
+            PUSH1(initialization_bytes.len()) // length
+            PUSH1(32 - initialization_bytes.len()) // offset
+            PUSH2(value) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        if !is_persistent {
+            code.append(&bytecode! {
+                PUSH1(0)
+                PUSH1(0)
+                REVERT
+            });
+        }
+
+        code
+    }
+
+    fn creator_bytecode_empty_init_code(value: Word, is_create2: bool) -> Bytecode {
+        let mut code = Bytecode::default();
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(45)}); // salt
+        }
+        code.append(&bytecode! {
+            PUSH1(0) // length
+            PUSH1(0) // offset
+            PUSH2(value) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+
+        code
+    }
+
+    fn test_context(caller: Account) -> TestContext<2, 1> {
+        TestContext::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(100000u64.into());
+            },
+            |block, _| block,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_create() {
+        for ((is_success, is_create2), is_persistent) in [true, false]
+            .iter()
+            .cartesian_product(&[true, false])
+            .cartesian_product(&[true, false])
+        {
+            let init_code = initialization_bytecode(*is_success);
+            let root_code = creator_bytecode(init_code, 23414.into(), *is_create2, *is_persistent);
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: root_code.into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+            run_test_circuits(test_context(caller));
+        }
+    }
+
+    #[test]
+    fn test_create_rlp_nonce() {
+        for nonce in [0, 1, 127, 128, 255, 256, 0x10000, u64::MAX - 1] {
+            let init_code = initialization_bytecode(true);
+            let root_code = creator_bytecode(init_code, 23414.into(), false, true);
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: root_code.into(),
+                nonce: nonce.into(),
+                balance: eth(10),
+                ..Default::default()
+            };
+            run_test_circuits(test_context(caller))
+        }
+    }
+
+    #[test]
+    fn test_create_empty_init_code() {
+        for is_create2 in [true, false] {
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: creator_bytecode_empty_init_code(23414.into(), is_create2).into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+            run_test_circuits(test_context(caller));
+        }
+    }
+
+    #[test]
+    fn test_create_address_collision() {
+        for is_create2 in [true, false] {
+            let init_code = initialization_bytecode(true);
+            let root_code = creator_bytecode(init_code.clone(), 23414.into(), is_create2, true);
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: root_code.into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+            let contract_address = if is_create2 {
+                get_create2_address(
+                    CALLER_ADDRESS,
+                    Word::from(45).to_be_bytes().to_vec(),
+                    init_code.code(),
+                )
+            } else {
+                get_contract_address(CALLER_ADDRESS, Word::one())
+            };
+
+            let ctx = TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x000000000000000000000000000000000000cafe"))
+                        .balance(eth(10));
+                    accs[1].account(&caller);
+                    // An existing account with a non-zero nonce at the
+                    // contract address.
+                    accs[2].address(contract_address).nonce(Word::one());
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .to(accs[1].address)
+                        .gas(100000u64.into());
+                },
+                |block, _| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn test_create_insufficient_balance() {
+        for is_create2 in [true, false] {
+            let init_code = initialization_bytecode(true);
+            let root_code = creator_bytecode(init_code, Word::from(0xffff), is_create2, true);
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: root_code.into(),
+                nonce: Word::one(),
+                balance: 0xfffe.into(),
+                ..Default::default()
+            };
+            run_test_circuits(test_context(caller));
+        }
+    }
+
+    #[test]
+    fn test_create_depth() {
+        for is_create2 in [true, false] {
+            // The initialization code copies itself into memory and creates a
+            // contract with it, recursing until the call depth limit is hit.
+            let mut code = bytecode! {
+                CODESIZE
+                PUSH1(0) // offset
+                PUSH1(0) // destOffset
+                CODECOPY
+            };
+            if is_create2 {
+                code.append(&bytecode! {PUSH1(45)}); // salt
+            }
+            code.append(&bytecode! {
+                CODESIZE // length
+                PUSH1(0) // offset
+                PUSH1(0) // value
+            });
+            code.write_op(if is_create2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            });
+
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(code),
+                |mut txs, accs| {
+                    txs[0]
+                        .to(accs[0].address)
+                        .from(accs[1].address)
+                        .gas(word!("0x2386F26FC10000"));
+                },
+                |block, _tx| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .params(CircuitsParams {
+                    max_rws: 300000,
+                    max_copy_rows: 100000,
+                    max_bytecode: 100000,
+                    ..Default::default()
+                })
+                .run();
+        }
+    }
+}
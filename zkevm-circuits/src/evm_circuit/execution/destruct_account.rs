@@ -0,0 +1,142 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            constraint_builder::{
+                EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            CachedRegion, Cell, StepRws,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use eth_types::{Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget destructing an account self destructed in the tx, after the EndTx
+/// step.  The whole storage of the account is cleared, which sets its storage
+/// root to the empty root, and its balance, nonce and code hash are set to
+/// zero, which removes the account from the state trie.
+#[derive(Clone, Debug)]
+pub(crate) struct DestructAccountGadget<F> {
+    tx_id: Cell<F>,
+    address: Cell<F>,
+    balance_prev: Cell<F>,
+    nonce_prev: Cell<F>,
+    code_hash_prev: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for DestructAccountGadget<F> {
+    const NAME: &'static str = "DestructAccount";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::DestructAccount;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        // Unset the account as destructed, which the State circuit requires for
+        // every account destructed in the tx.
+        let address = cb.query_cell();
+        cb.tx_destructed_account_write(tx_id.expr(), address.expr(), 0.expr(), 1.expr(), None);
+
+        // Clearing the storage through the storage root leaves no slot behind,
+        // including the ones which aren't accessed in the block.
+        cb.account_storage_clear(address.expr(), tx_id.expr());
+
+        let balance_prev = cb.query_cell_phase2();
+        let nonce_prev = cb.query_cell();
+        let code_hash_prev = cb.query_cell_phase2();
+        for (field_tag, value_prev) in [
+            (AccountFieldTag::Balance, balance_prev.expr()),
+            (AccountFieldTag::Nonce, nonce_prev.expr()),
+            (AccountFieldTag::CodeHash, code_hash_prev.expr()),
+        ] {
+            cb.account_write(address.expr(), field_tag, 0.expr(), value_prev, None);
+        }
+
+        cb.condition(
+            cb.next.execution_state_selector([ExecutionState::BeginTx]),
+            |cb| {
+                cb.call_context_lookup(
+                    true.expr(),
+                    Some(cb.next.state.rw_counter.expr()),
+                    CallContextFieldTag::TxId,
+                    tx_id.expr() + 1.expr(),
+                );
+
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(7.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        cb.condition(
+            cb.next.execution_state_selector([
+                ExecutionState::DestructAccount,
+                ExecutionState::EndBlock,
+            ]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(6.expr()),
+                    // We propagate call_id so that EndBlock can get the last tx_id
+                    // in order to count processed txs.
+                    call_id: Same,
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        Self {
+            tx_id,
+            address,
+            balance_prev,
+            nonce_prev,
+            code_hash_prev,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+
+        let mut rws = StepRws::new(block, step);
+        rws.offset_add(1);
+
+        let address = rws.next().address().expect("unexpected Rw without address");
+        self.address.assign(
+            region,
+            offset,
+            Value::known(
+                address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+        rws.offset_add(3);
+
+        let (_, balance_prev) = rws.next().account_value_pair();
+        self.balance_prev
+            .assign(region, offset, region.word_rlc(balance_prev))?;
+        let (_, nonce_prev) = rws.next().account_value_pair();
+        self.nonce_prev
+            .assign(region, offset, Value::known(F::from(nonce_prev.as_u64())))?;
+        let (_, code_hash_prev) = rws.next().account_value_pair();
+        self.code_hash_prev
+            .assign(region, offset, region.word_rlc(code_hash_prev))?;
+
+        Ok(())
+    }
+}
@@ -57,6 +57,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         let [tx_gas, tx_caller_address] =
             [TxContextFieldTag::Gas, TxContextFieldTag::CallerAddress]
                 .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
+        // The gas price is the effective gas price constrained in BeginTx
         let tx_gas_price = cb.tx_context_as_word(tx_id.expr(), TxContextFieldTag::GasPrice, None);
 
         // Calculate effective gas to refund
@@ -69,6 +70,8 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         let refund = cb.query_cell();
         cb.tx_refund_read(tx_id.expr(), refund.expr());
         let effective_refund = MinMaxGadget::construct(cb, max_refund.quotient(), refund.expr());
+        // The gas used of the tx is reduced by the effective refund (EIP-3529)
+        let gas_used = gas_used - effective_refund.min();
 
         // Add effective_refund * tx_gas_price back to caller's balance
         let mul_gas_price_by_refund = MulWordByU64Gadget::construct(
@@ -161,8 +164,13 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             },
         );
 
+        // The accounts self destructed in the tx are destructed by the steps following
+        // EndTx, the last of which writes the tx id of the next tx.
         cb.condition(
-            cb.next.execution_state_selector([ExecutionState::EndBlock]),
+            cb.next.execution_state_selector([
+                ExecutionState::EndBlock,
+                ExecutionState::DestructAccount,
+            ]),
             |cb| {
                 cb.require_step_state_transition(StepStateTransition {
                     rw_counter: Delta(9.expr() - is_first_tx.expr()),
@@ -221,6 +229,7 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             F::from(refund),
         )?;
         let effective_refund = refund.min(max_refund as u64);
+        let gas_used = gas_used - effective_refund;
         let gas_fee_refund = tx.gas_price * (effective_refund + step.gas_left);
         self.mul_gas_price_by_refund.assign(
             region,
@@ -309,37 +318,103 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
 
 #[cfg(test)]
 mod test {
-    use crate::test_util::CircuitTestBuilder;
-    use bus_mapping::circuit_input_builder::CircuitsParams;
-    use eth_types::{self, bytecode};
+    use crate::{test_util::CircuitTestBuilder, witness::block_convert};
+    use bus_mapping::{circuit_input_builder::CircuitsParams, mock::BlockData};
+    use eth_types::{self, bytecode, geth_types::GethData, Bytecode, Word};
+    use std::collections::HashMap;
 
-    use mock::{eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext};
+    use mock::{
+        eth, gwei, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
 
     fn test_ok<const NACC: usize, const NTX: usize>(ctx: TestContext<NACC, NTX>) {
-        CircuitTestBuilder::new_from_test_ctx(ctx)
-            .params(CircuitsParams {
+        let block: GethData = ctx.into();
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
                 max_txs: 5,
                 ..Default::default()
-            })
-            .run();
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // Check the balances after the block against the gas used reported by
+        // geth, which already has the effective refund deducted.
+        let balance = |address| {
+            block
+                .accounts
+                .iter()
+                .find(|account| account.address == address)
+                .map_or(Word::zero(), |account| account.balance)
+        };
+        let coinbase = builder.block.coinbase;
+        let mut coinbase_balance = balance(coinbase);
+        let mut caller_balances = HashMap::new();
+        for (tx, geth_trace) in builder.block.txs.iter().zip(block.geth_traces.iter()) {
+            let gas_used = Word::from(geth_trace.gas.0);
+            coinbase_balance += (tx.gas_price - builder.block.base_fee) * gas_used;
+            let caller_balance = caller_balances
+                .entry(tx.from)
+                .or_insert_with(|| balance(tx.from));
+            *caller_balance -= tx.value + tx.gas_price * gas_used;
+        }
+        assert_eq!(
+            builder.sdb.get_account(&coinbase).1.balance,
+            coinbase_balance
+        );
+        for (caller, caller_balance) in caller_balances {
+            assert_eq!(builder.sdb.get_account(&caller).1.balance, caller_balance);
+        }
+
+        let block = block_convert(&builder.block, &builder.code_db).unwrap();
+        CircuitTestBuilder::<NACC, NTX>::new_from_block(block).run();
+    }
+
+    // Clear `n` storage slots to get a refund of 4800 gas each.
+    fn refund_ctx(n: u64) -> TestContext<2, 1> {
+        let mut code = Bytecode::default();
+        for key in 0..n {
+            code.append(&bytecode! {
+                PUSH1(0) // value
+                PUSH1(key) // key
+                SSTORE
+            });
+        }
+        code.append(&bytecode! { STOP });
+        TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code)
+                    .storage((0..n).map(|key| (Word::from(key), Word::one())));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .to(accs[0].address)
+                    .from(accs[1].address)
+                    .gas_price(gwei(2));
+            },
+            |block, _tx| block.base_fee_per_gas(gwei(1)),
+        )
+        .unwrap()
     }
 
     #[test]
-    fn end_tx_gadget_simple() {
-        // TODO: Enable this with respective code when SSTORE is implemented.
-        // Tx with non-capped refund
-        // test_ok(vec![mock_tx(
-        //     address!("0x00000000000000000000000000000000000000fe"),
-        //     Some(27000),
-        //     None,
-        // )]);
-        // Tx with capped refund
-        // test_ok(vec![mock_tx(
-        //     address!("0x00000000000000000000000000000000000000fe"),
-        //     Some(65000),
-        //     None,
-        // )]);
+    fn end_tx_refund() {
+        // Tx with non-capped refund: 4800 < (21000 + 5006) / 5
+        test_ok(refund_ctx(1));
+        // Tx with capped refund: 9600 > (21000 + 2 * 5006) / 5
+        test_ok(refund_ctx(2));
+    }
 
+    #[test]
+    fn end_tx_gadget_simple() {
         // Multiple txs
         test_ok(
             // Get the execution steps from the external tracer
@@ -365,4 +440,27 @@ mod test {
             .unwrap(),
         );
     }
+
+    #[test]
+    fn end_tx_dynamic_fee() {
+        // Coinbase only receives the priority fee of 1 gwei per gas, while the
+        // caller gets back the unused gas at the effective gas price of 2 gwei.
+        test_ok(
+            TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(bytecode! { STOP }),
+                |mut txs, accs| {
+                    txs[0]
+                        .to(accs[0].address)
+                        .from(accs[1].address)
+                        .transaction_type(2)
+                        .max_fee_per_gas(gwei(3))
+                        .max_priority_fee_per_gas(gwei(1))
+                        .value(eth(1));
+                },
+                |block, _tx| block.base_fee_per_gas(gwei(1)),
+            )
+            .unwrap(),
+        );
+    }
 }
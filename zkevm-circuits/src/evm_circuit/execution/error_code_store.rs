@@ -0,0 +1,257 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::LtGadget,
+            memory_gadget::{MemoryAddressGadget, MemoryExpansionGadget},
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId, MAX_CODE_SIZE},
+    Field,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the errors storing the deployed code at [`OpcodeId::RETURN`] of
+/// a contract creation, which are:
+/// - [`ExecutionState::ErrorMaxCodeSizeExceeded`] when the code is longer than [`MAX_CODE_SIZE`]
+///   bytes.
+/// - [`ExecutionState::ErrorOutOfGasCodeStore`] when the gas left can't pay for the memory
+///   expansion and the code deposit cost of 200 gas per byte.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorCodeStoreGadget<F, const S: ExecutionState> {
+    opcode: Cell<F>,
+    memory_address: MemoryAddressGadget<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    is_max_code_size_exceeded: LtGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for ErrorCodeStoreGadget<F, S> {
+    const NAME: &'static str = "ErrorCodeStore";
+
+    const EXECUTION_STATE: ExecutionState = S;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorCodeStore opcode must be RETURN",
+            opcode.expr(),
+            OpcodeId::RETURN.expr(),
+        );
+        cb.require_equal(
+            "ErrorCodeStore only happens in a contract creation",
+            cb.curr.state.is_create.expr(),
+            1.expr(),
+        );
+
+        let memory_offset = cb.query_cell_phase2();
+        let memory_length = cb.query_word_rlc();
+        cb.stack_pop(memory_offset.expr());
+        cb.stack_pop(memory_length.expr());
+        let memory_address = MemoryAddressGadget::construct(cb, memory_offset, memory_length);
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [memory_address.address()]);
+
+        let is_max_code_size_exceeded =
+            LtGadget::construct(cb, MAX_CODE_SIZE.expr(), memory_address.length());
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            memory_expansion.gas_cost()
+                + GasCost::CODE_DEPOSIT_BYTE_COST.expr() * memory_address.length(),
+        );
+
+        if S == ExecutionState::ErrorMaxCodeSizeExceeded {
+            cb.require_equal(
+                "Code size exceeds the max code size",
+                is_max_code_size_exceeded.expr(),
+                1.expr(),
+            );
+        } else {
+            // The code size is checked before the code deposit cost.
+            cb.require_zero(
+                "Code size doesn't exceed the max code size",
+                is_max_code_size_exceeded.expr(),
+            );
+            cb.require_equal(
+                "Gas left is less than gas required",
+                insufficient_gas.expr(),
+                1.expr(),
+            );
+        }
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 4.expr());
+
+        Self {
+            opcode,
+            memory_address,
+            memory_expansion,
+            is_max_code_size_exceeded,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let [memory_offset, memory_length] =
+            [0, 1].map(|idx| block.rws[step.rw_indices[idx]].stack_value());
+        let memory_address =
+            self.memory_address
+                .assign(region, offset, memory_offset, memory_length)?;
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+
+        let length = memory_length.low_u64();
+        self.is_max_code_size_exceeded.assign(
+            region,
+            offset,
+            F::from(MAX_CODE_SIZE),
+            F::from(length),
+        )?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(memory_expansion_gas_cost + GasCost::CODE_DEPOSIT_BYTE_COST.as_u64() * length),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 4)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::Account, Address, Bytecode, Word,
+    };
+    use mock::{eth, TestContext};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+
+    // Initialization code returning `length` zero bytes.
+    fn initialization_bytecode(length: u64) -> Bytecode {
+        bytecode! {
+            PUSH2(length)
+            PUSH1(0) // offset
+            RETURN
+        }
+    }
+
+    fn creator_bytecode(initialization_bytecode: Bytecode, is_create2: bool) -> Bytecode {
+        let initialization_bytes = initialization_bytecode.code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&initialization_bytes))
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.append(&bytecode! {PUSH1(45)}); // salt
+        }
+        code.append(&bytecode! {
+            PUSH1(initialization_bytes.len()) // length
+            PUSH1(32 - initialization_bytes.len()) // offset
+            PUSH1(0) // value
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        code.write_op(OpcodeId::STOP);
+        code
+    }
+
+    fn test_internal(length: u64, gas: u64) {
+        for is_create2 in [false, true] {
+            let caller = Account {
+                address: CALLER_ADDRESS,
+                code: creator_bytecode(initialization_bytecode(length), is_create2).into(),
+                nonce: Word::one(),
+                balance: eth(10),
+                ..Default::default()
+            };
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x000000000000000000000000000000000000cafe"))
+                        .balance(eth(10));
+                    accs[1].account(&caller);
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .to(accs[1].address)
+                        .gas(gas.into());
+                },
+                |block, _| block,
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    fn test_root(length: u64, gas: u64) {
+        let ctx = TestContext::<1, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .gas(gas.into())
+                    .input(initialization_bytecode(length).into());
+            },
+            |block, _| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn test_max_code_size_exceeded() {
+        test_internal(0x6001, 200_000);
+        test_root(0x6001, 200_000);
+    }
+
+    #[test]
+    fn test_oog_code_store() {
+        // The code deposit cost of 0x100 bytes is 51200 gas.
+        test_internal(0x100, 100_000);
+        test_root(0x100, 100_000);
+    }
+}
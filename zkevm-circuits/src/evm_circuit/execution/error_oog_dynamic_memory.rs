@@ -0,0 +1,334 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget},
+            memory_gadget::{address_high, address_low, MemoryExpansionGadget},
+            sum, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian, U256,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::CREATE`], [`OpcodeId::RETURN`] and [`OpcodeId::REVERT`], whose
+/// memory expansion depends on the offset and length read from the stack.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGDynamicMemoryGadget<F> {
+    opcode: Cell<F>,
+    is_create: IsEqualGadget<F>,
+    /// Extra stack pop for `CREATE`
+    value: Word<F>,
+    memory_offset: Word<F>,
+    memory_length: Word<F>,
+    memory_length_is_zero: IsZeroGadget<F>,
+    address_in_range: IsZeroGadget<F>,
+    // As for the static memory expansion, the address is at most 2^41 - 2, so
+    // that the memory word size needs 5 bytes.
+    memory_expansion: MemoryExpansionGadget<F, 1, { N_BYTES_MEMORY_WORD_SIZE + 1 }>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGDynamicMemoryGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasDynamicMemoryExpansion";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasDynamicMemoryExpansion;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_in_set(
+            "ErrorOutOfGasDynamicMemoryExpansion opcode must be CREATE, RETURN or REVERT",
+            opcode.expr(),
+            vec![
+                OpcodeId::CREATE.expr(),
+                OpcodeId::RETURN.expr(),
+                OpcodeId::REVERT.expr(),
+            ],
+        );
+
+        let is_create = IsEqualGadget::construct(cb, opcode.expr(), OpcodeId::CREATE.expr());
+
+        // CREATE has an extra stack pop for the value.
+        let value = cb.query_word_rlc();
+        cb.condition(is_create.expr(), |cb| {
+            cb.stack_pop(value.expr());
+        });
+
+        // The offset and length are read as full words, as any of them can be
+        // out of the range of the memory addresses.
+        let memory_offset = cb.query_word_rlc();
+        let memory_length = cb.query_word_rlc();
+        cb.stack_pop(memory_offset.expr());
+        cb.stack_pop(memory_length.expr());
+
+        // The offset is ignored when the length is zero.
+        let memory_length_is_zero = IsZeroGadget::construct(cb, sum::expr(&memory_length.cells));
+        let has_length = 1.expr() - memory_length_is_zero.expr();
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [has_length.clone()
+                * (address_low::expr(&memory_offset) + address_low::expr(&memory_length))],
+        );
+
+        // Check if the amount of gas available is less than the amount of gas
+        // required, which is only needed when the accessed memory is in range:
+        // a larger offset or length always runs out of gas.  RETURN and REVERT
+        // have no constant gas cost.
+        let address_in_range = IsZeroGadget::construct(
+            cb,
+            has_length * (address_high::expr(&memory_offset) + address_high::expr(&memory_length)),
+        );
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            is_create.expr() * GasCost::CREATE.expr() + memory_expansion.gas_cost(),
+        );
+        cb.condition(address_in_range.expr(), |cb| {
+            cb.require_equal(
+                "Gas left is less than gas required",
+                insufficient_gas.expr(),
+                1.expr(),
+            );
+        });
+
+        let common_error_gadget =
+            CommonErrorGadget::construct(cb, opcode.expr(), 4.expr() + is_create.expr());
+
+        Self {
+            opcode,
+            is_create,
+            value,
+            memory_offset,
+            memory_length,
+            memory_length_is_zero,
+            address_in_range,
+            memory_expansion,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+        let is_create = opcode == OpcodeId::CREATE;
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+        self.is_create.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()),
+            F::from(OpcodeId::CREATE.as_u64()),
+        )?;
+
+        let (value, rw_offset) = if is_create {
+            (block.rws[step.rw_indices[0]].stack_value(), 1)
+        } else {
+            (U256::zero(), 0)
+        };
+        let [memory_offset, memory_length] =
+            [rw_offset, rw_offset + 1].map(|idx| block.rws[step.rw_indices[idx]].stack_value());
+
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        let [memory_offset, memory_length] =
+            [memory_offset, memory_length].map(|word| word.to_le_bytes());
+        self.memory_offset
+            .assign(region, offset, Some(memory_offset))?;
+        self.memory_length
+            .assign(region, offset, Some(memory_length))?;
+        let has_length = memory_length.iter().any(|byte| *byte != 0);
+        self.memory_length_is_zero
+            .assign(region, offset, sum::value(&memory_length))?;
+        self.address_in_range.assign(
+            region,
+            offset,
+            if has_length {
+                address_high::value::<F>(memory_offset) + address_high::value::<F>(memory_length)
+            } else {
+                F::zero()
+            },
+        )?;
+
+        let memory_address = if has_length {
+            address_low::value(memory_offset) + address_low::value(memory_length)
+        } else {
+            0
+        };
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+
+        let constant_gas_cost = if is_create {
+            GasCost::CREATE.as_u64()
+        } else {
+            0
+        };
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(constant_gas_cost + memory_expansion_gas_cost),
+        )?;
+
+        self.common_error_gadget.assign(
+            region,
+            offset,
+            block,
+            call,
+            step,
+            4 + is_create as usize,
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        bytecode, evm_types::OpcodeId, geth_types::Account, Address, Bytecode, ToWord, Word,
+    };
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS};
+
+    const TESTING_MEMORY_LENGTH: u64 = 0x10000;
+
+    fn memory_code(opcode: OpcodeId, offset: impl Into<Word>, length: impl Into<Word>) -> Bytecode {
+        let mut code = Bytecode::default();
+        code.push(32, length.into());
+        code.push(32, offset.into());
+        if opcode == OpcodeId::CREATE {
+            code.push(1, Word::zero()); // value
+        }
+        code.write_op(opcode);
+        code.write_op(OpcodeId::STOP);
+        code
+    }
+
+    fn test_root(code: Bytecode, gas: u64) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(Word::from(gas));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(code: Bytecode, gas: u64) {
+        let callee = Account {
+            address: Address::repeat_byte(0xff),
+            code: code.to_vec().into(),
+            nonce: Word::one(),
+            balance: Word::from(0xdeadbeefu64),
+            ..Default::default()
+        };
+        let caller = bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH32(callee.address.to_word())
+            PUSH32(Word::from(gas))
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)));
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .code(caller)
+                    .balance(Word::from(10u64.pow(19)));
+                accs[2]
+                    .address(callee.address)
+                    .code(callee.code)
+                    .nonce(callee.nonce)
+                    .balance(callee.balance);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(200_000));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn error_oog_dynamic_memory_return_revert() {
+        for opcode in [OpcodeId::RETURN, OpcodeId::REVERT] {
+            test_root(memory_code(opcode, 0x20u64, TESTING_MEMORY_LENGTH), 25_000);
+            test_internal(memory_code(opcode, 0x20u64, TESTING_MEMORY_LENGTH), 10_000);
+        }
+    }
+
+    #[test]
+    fn error_oog_dynamic_memory_create() {
+        // Enough gas for the constant gas cost of CREATE but not for the
+        // memory expansion.
+        test_root(
+            memory_code(OpcodeId::CREATE, 0u64, TESTING_MEMORY_LENGTH),
+            60_000,
+        );
+        test_internal(
+            memory_code(OpcodeId::CREATE, 0u64, TESTING_MEMORY_LENGTH),
+            40_000,
+        );
+    }
+
+    #[test]
+    fn error_oog_dynamic_memory_out_of_range() {
+        // Offsets and lengths not lower than 2^40, which can't be represented
+        // as memory addresses.
+        let large = Word::from(1u64 << 40);
+        for (offset, length) in [
+            (large, Word::one()),
+            (Word::zero(), large),
+            (Word::MAX, Word::MAX),
+        ] {
+            for opcode in [OpcodeId::RETURN, OpcodeId::REVERT, OpcodeId::CREATE] {
+                test_root(memory_code(opcode, offset, length), 100_000);
+                test_internal(memory_code(opcode, offset, length), 50_000);
+            }
+        }
+    }
+}
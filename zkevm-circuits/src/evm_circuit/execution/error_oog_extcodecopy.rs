@@ -0,0 +1,390 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{IsZeroGadget, LtGadget},
+            memory_gadget::{
+                address_high, address_low, MemoryExpansionGadget, MemoryWordSizeGadget,
+            },
+            select, sum, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::EXTCODECOPY`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGExtCodeCopyGadget<F> {
+    opcode: Cell<F>,
+    tx_id: Cell<F>,
+    /// Check if external address is warm
+    is_warm: Cell<F>,
+    external_address: Word<F>,
+    /// Code offset
+    code_offset: Word<F>,
+    /// Destination offset and size to copy
+    dst_offset: Word<F>,
+    copy_size: Word<F>,
+    copy_size_is_zero: IsZeroGadget<F>,
+    address_in_range: IsZeroGadget<F>,
+    // The address is at most 2^41 - 2 and the size at most 2^40 - 1, so that
+    // their memory word sizes need 5 bytes.
+    memory_expansion: MemoryExpansionGadget<F, 1, { N_BYTES_MEMORY_WORD_SIZE + 1 }>,
+    copy_size_word_size: MemoryWordSizeGadget<F, { N_BYTES_MEMORY_WORD_SIZE + 1 }>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGExtCodeCopyGadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasEXTCODECOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasEXTCODECOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasEXTCODECOPY opcode must be EXTCODECOPY",
+            opcode.expr(),
+            OpcodeId::EXTCODECOPY.expr(),
+        );
+
+        let external_address = cb.query_word_rlc();
+        let dst_offset = cb.query_word_rlc();
+        let code_offset = cb.query_word_rlc();
+        let copy_size = cb.query_word_rlc();
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let is_warm = cb.query_bool();
+        cb.account_access_list_read(
+            tx_id.expr(),
+            from_bytes::expr(&external_address.cells[..N_BYTES_ACCOUNT_ADDRESS]),
+            is_warm.expr(),
+        );
+
+        cb.stack_pop(external_address.expr());
+        cb.stack_pop(dst_offset.expr());
+        cb.stack_pop(code_offset.expr());
+        cb.stack_pop(copy_size.expr());
+
+        // The destination offset is ignored when the size is zero.
+        let copy_size_is_zero = IsZeroGadget::construct(cb, sum::expr(&copy_size.cells));
+        let has_size = 1.expr() - copy_size_is_zero.expr();
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [has_size.clone() * (address_low::expr(&dst_offset) + address_low::expr(&copy_size))],
+        );
+        let copy_size_word_size =
+            MemoryWordSizeGadget::construct(cb, address_low::expr(&copy_size));
+
+        // According to EIP-2929, EXTCODECOPY constant gas cost is different for cold and warm
+        // accounts.
+        let constant_gas_cost = select::expr(
+            is_warm.expr(),
+            GasCost::WARM_ACCESS.expr(),
+            GasCost::COLD_ACCOUNT_ACCESS.expr(),
+        );
+
+        // Check if the amount of gas available is less than the amount of gas
+        // required, which is only needed when the destination memory is in
+        // range: a larger offset or size always runs out of gas.
+        let address_in_range = IsZeroGadget::construct(
+            cb,
+            has_size * (address_high::expr(&dst_offset) + address_high::expr(&copy_size)),
+        );
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            constant_gas_cost
+                + copy_size_word_size.expr() * GasCost::COPY.expr()
+                + memory_expansion.gas_cost(),
+        );
+        cb.condition(address_in_range.expr(), |cb| {
+            cb.require_equal(
+                "Gas left is less than gas cost",
+                insufficient_gas.expr(),
+                1.expr(),
+            );
+        });
+
+        // 1 call context lookup (tx_id), 1 account access list read (is_warm)
+        // and 4 stack pops.
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 8.expr());
+
+        Self {
+            opcode,
+            tx_id,
+            is_warm,
+            external_address,
+            code_offset,
+            dst_offset,
+            copy_size,
+            copy_size_is_zero,
+            address_in_range,
+            memory_expansion,
+            copy_size_word_size,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        transaction: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+
+        log::debug!(
+            "ErrorOutOfGasEXTCODECOPY: gas_left = {}, gas_cost = {}",
+            step.gas_left,
+            step.gas_cost,
+        );
+
+        let is_warm = block.rws[step.rw_indices[1]].tx_access_list_value_pair().0;
+        let [external_address, dst_offset, code_offset, copy_size] =
+            [2, 3, 4, 5].map(|idx| block.rws[step.rw_indices[idx]].stack_value());
+
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(transaction.id as u64)))?;
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(u64::from(is_warm))))?;
+        self.external_address
+            .assign(region, offset, Some(external_address.to_le_bytes()))?;
+        self.code_offset
+            .assign(region, offset, Some(code_offset.to_le_bytes()))?;
+        let [dst_offset, copy_size] = [dst_offset, copy_size].map(|word| word.to_le_bytes());
+        self.dst_offset.assign(region, offset, Some(dst_offset))?;
+        self.copy_size.assign(region, offset, Some(copy_size))?;
+        let has_size = copy_size.iter().any(|byte| *byte != 0);
+        self.copy_size_is_zero
+            .assign(region, offset, sum::value(&copy_size))?;
+        self.address_in_range.assign(
+            region,
+            offset,
+            if has_size {
+                address_high::value::<F>(dst_offset) + address_high::value::<F>(copy_size)
+            } else {
+                F::zero()
+            },
+        )?;
+
+        let memory_address = if has_size {
+            address_low::value(dst_offset) + address_low::value(copy_size)
+        } else {
+            0
+        };
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+        let copy_size_word_size =
+            self.copy_size_word_size
+                .assign(region, offset, address_low::value(copy_size))?;
+        let constant_gas_cost = if is_warm {
+            GasCost::WARM_ACCESS
+        } else {
+            GasCost::COLD_ACCOUNT_ACCESS
+        };
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(
+                constant_gas_cost.0 + copy_size_word_size * GasCost::COPY.0 + memory_expansion_cost,
+            ),
+        )?;
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 8)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        evm_circuit::test::{rand_bytes, rand_word},
+        test_util::CircuitTestBuilder,
+    };
+    use eth_types::{
+        bytecode, evm_types::gas_utils::memory_copier_gas_cost, Bytecode, ToWord, U256,
+    };
+    use itertools::Itertools;
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
+
+    const TESTING_DST_OFFSET_COPY_SIZE_PAIRS: &[(u64, u64)] =
+        &[(0x20, 0), (0x40, 20), (0x2000, 0x200)];
+
+    #[test]
+    fn test_oog_extcodecopy() {
+        for (is_warm, (dst_offset, copy_size)) in [false, true]
+            .iter()
+            .cartesian_product(TESTING_DST_OFFSET_COPY_SIZE_PAIRS.iter())
+        {
+            let testing_data = TestingData::new(*is_warm, *dst_offset, *copy_size);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    #[test]
+    fn test_oog_extcodecopy_out_of_range() {
+        // Destination offsets and sizes not lower than 2^40, which can't be
+        // represented as memory addresses.
+        let large = U256::from(1u64 << 40);
+        for (dst_offset, copy_size) in [
+            (large, U256::one()),
+            (U256::zero(), large),
+            (U256::MAX, U256::MAX),
+        ] {
+            let testing_data = TestingData::out_of_range(dst_offset, copy_size);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    struct TestingData {
+        bytecode: Bytecode,
+        gas_cost: u64,
+    }
+
+    impl TestingData {
+        pub fn new(is_warm: bool, dst_offset: u64, copy_size: u64) -> Self {
+            let external_address = MOCK_ACCOUNTS[4];
+
+            let mut bytecode = bytecode! {
+                PUSH32(copy_size)
+                PUSH32(U256::zero())
+                PUSH32(dst_offset)
+                PUSH32(external_address.to_word())
+                EXTCODECOPY
+            };
+
+            let memory_word_size = (dst_offset + copy_size + 31) / 32;
+
+            let mut gas_cost = OpcodeId::PUSH32.constant_gas_cost().0 * 4
+                + GasCost::COLD_ACCOUNT_ACCESS.0
+                + memory_copier_gas_cost(0, memory_word_size, copy_size);
+
+            if is_warm {
+                bytecode.append(&bytecode! {
+                    PUSH32(copy_size)
+                    PUSH32(rand_word())
+                    PUSH32(dst_offset)
+                    PUSH32(external_address.to_word())
+                    EXTCODECOPY
+                });
+
+                gas_cost += OpcodeId::PUSH32.constant_gas_cost().0 * 4
+                    + GasCost::WARM_ACCESS.0
+                    + memory_copier_gas_cost(memory_word_size, memory_word_size, copy_size);
+            }
+
+            Self { bytecode, gas_cost }
+        }
+
+        /// The gas cost is arbitrary, as copying to out of range memory always
+        /// runs out of gas.
+        pub fn out_of_range(dst_offset: U256, copy_size: U256) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(copy_size)
+                PUSH32(U256::zero())
+                PUSH32(dst_offset)
+                PUSH32(MOCK_ACCOUNTS[4].to_word())
+                EXTCODECOPY
+            };
+
+            Self {
+                bytecode,
+                gas_cost: 100_000,
+            }
+        }
+    }
+
+    fn test_root(testing_data: &TestingData) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(testing_data.bytecode.clone()),
+            |mut txs, accs| {
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX.0 + testing_data.gas_cost - 1).into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(testing_data: &TestingData) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // code B gets called by code A, so the call is an internal call.
+        let code_b = testing_data.bytecode.clone();
+        let gas_cost_b = testing_data.gas_cost;
+
+        // Code A calls code B.
+        let code_a = bytecode! {
+            // populate memory in A's context.
+            PUSH8(U256::from_big_endian(&rand_bytes(8)))
+            PUSH1(0x00) // offset
+            MSTORE
+            // call ADDR_B.
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH32(0x00) // argsLength
+            PUSH32(0x20) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            // Decrease expected gas cost (by 1) to trigger out of gas error.
+            PUSH32(gas_cost_b - 1) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(code_b);
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
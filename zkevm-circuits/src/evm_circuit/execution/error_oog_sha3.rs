@@ -0,0 +1,325 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
+        step::ExecutionState,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsZeroGadget, LtGadget},
+            memory_gadget::{
+                address_high, address_low, MemoryExpansionGadget, MemoryWordSizeGadget,
+            },
+            sum, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToLittleEndian,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::SHA3`].
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorOOGSha3Gadget<F> {
+    opcode: Cell<F>,
+    memory_offset: Word<F>,
+    memory_size: Word<F>,
+    memory_size_is_zero: IsZeroGadget<F>,
+    address_in_range: IsZeroGadget<F>,
+    // The address is at most 2^41 - 2 and the size at most 2^40 - 1, so that
+    // their memory word sizes need 5 bytes.
+    memory_expansion: MemoryExpansionGadget<F, 1, { N_BYTES_MEMORY_WORD_SIZE + 1 }>,
+    memory_size_word_size: MemoryWordSizeGadget<F, { N_BYTES_MEMORY_WORD_SIZE + 1 }>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorOOGSha3Gadget<F> {
+    const NAME: &'static str = "ErrorOutOfGasSHA3";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasSHA3;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_equal(
+            "ErrorOutOfGasSHA3 opcode must be SHA3",
+            opcode.expr(),
+            OpcodeId::SHA3.expr(),
+        );
+
+        // The offset and size are read as full words, as any of them can be
+        // out of the range of the memory addresses.
+        let memory_offset = cb.query_word_rlc();
+        let memory_size = cb.query_word_rlc();
+        cb.stack_pop(memory_offset.expr());
+        cb.stack_pop(memory_size.expr());
+
+        // The offset is ignored when the size is zero.
+        let memory_size_is_zero = IsZeroGadget::construct(cb, sum::expr(&memory_size.cells));
+        let has_size = 1.expr() - memory_size_is_zero.expr();
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [has_size.clone()
+                * (address_low::expr(&memory_offset) + address_low::expr(&memory_size))],
+        );
+        // The dynamic gas cost of SHA3 is the memory expansion gas cost plus the
+        // per word hashing cost.
+        let memory_size_word_size =
+            MemoryWordSizeGadget::construct(cb, address_low::expr(&memory_size));
+
+        // Check if the amount of gas available is less than the amount of gas
+        // required, which is only needed when the hashed memory is in range:
+        // a larger offset or size always runs out of gas.
+        let address_in_range = IsZeroGadget::construct(
+            cb,
+            has_size * (address_high::expr(&memory_offset) + address_high::expr(&memory_size)),
+        );
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            OpcodeId::SHA3.constant_gas_cost().expr()
+                + memory_size_word_size.expr() * GasCost::COPY_SHA3.expr()
+                + memory_expansion.gas_cost(),
+        );
+        cb.condition(address_in_range.expr(), |cb| {
+            cb.require_equal(
+                "Gas left is less than gas cost",
+                insufficient_gas.expr(),
+                1.expr(),
+            );
+        });
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 4.expr());
+
+        Self {
+            opcode,
+            memory_offset,
+            memory_size,
+            memory_size_is_zero,
+            address_in_range,
+            memory_expansion,
+            memory_size_word_size,
+            insufficient_gas,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+
+        log::debug!(
+            "ErrorOutOfGasSHA3: gas_left = {}, gas_cost = {}",
+            step.gas_left,
+            step.gas_cost,
+        );
+
+        let [memory_offset, memory_size] =
+            [0, 1].map(|idx| block.rws[step.rw_indices[idx]].stack_value().to_le_bytes());
+
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+        self.memory_offset
+            .assign(region, offset, Some(memory_offset))?;
+        self.memory_size.assign(region, offset, Some(memory_size))?;
+        let has_size = memory_size.iter().any(|byte| *byte != 0);
+        self.memory_size_is_zero
+            .assign(region, offset, sum::value(&memory_size))?;
+        self.address_in_range.assign(
+            region,
+            offset,
+            if has_size {
+                address_high::value::<F>(memory_offset) + address_high::value::<F>(memory_size)
+            } else {
+                F::zero()
+            },
+        )?;
+
+        let memory_address = if has_size {
+            address_low::value(memory_offset) + address_low::value(memory_size)
+        } else {
+            0
+        };
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_address],
+        )?;
+        let memory_size_word_size =
+            self.memory_size_word_size
+                .assign(region, offset, address_low::value(memory_size))?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(
+                OpcodeId::SHA3.constant_gas_cost().0
+                    + memory_size_word_size * GasCost::COPY_SHA3.0
+                    + memory_expansion_cost,
+            ),
+        )?;
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 4)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
+    use eth_types::{
+        bytecode, evm_types::gas_utils::memory_expansion_gas_cost, Bytecode, ToWord, U256,
+    };
+    use mock::{
+        eth, test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS,
+    };
+
+    const TESTING_MEMORY_OFFSET_SIZE_PAIRS: &[(u64, u64)] =
+        &[(0x20, 0), (0x40, 20), (0x2000, 0x200)];
+
+    #[test]
+    fn test_oog_sha3() {
+        for (memory_offset, memory_size) in TESTING_MEMORY_OFFSET_SIZE_PAIRS {
+            let testing_data = TestingData::new(*memory_offset, *memory_size);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    #[test]
+    fn test_oog_sha3_out_of_range() {
+        // Offsets and sizes not lower than 2^40, which can't be represented as
+        // memory addresses.
+        let large = U256::from(1u64 << 40);
+        for (memory_offset, memory_size) in [
+            (large, U256::one()),
+            (U256::zero(), large),
+            (U256::MAX, U256::MAX),
+        ] {
+            let testing_data = TestingData::out_of_range(memory_offset, memory_size);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
+    struct TestingData {
+        bytecode: Bytecode,
+        gas_cost: u64,
+    }
+
+    impl TestingData {
+        pub fn new(memory_offset: u64, memory_size: u64) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(memory_size)
+                PUSH32(memory_offset)
+                SHA3
+            };
+
+            let memory_word_size = if memory_size == 0 {
+                0
+            } else {
+                (memory_offset + memory_size + 31) / 32
+            };
+
+            let gas_cost = OpcodeId::PUSH32.constant_gas_cost().0 * 2
+                + OpcodeId::SHA3.constant_gas_cost().0
+                + (memory_size + 31) / 32 * GasCost::COPY_SHA3.0
+                + memory_expansion_gas_cost(0, memory_word_size);
+
+            Self { bytecode, gas_cost }
+        }
+
+        /// The gas cost is arbitrary, as hashing out of range memory always
+        /// runs out of gas.
+        pub fn out_of_range(memory_offset: U256, memory_size: U256) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(memory_size)
+                PUSH32(memory_offset)
+                SHA3
+            };
+
+            Self {
+                bytecode,
+                gas_cost: 100_000,
+            }
+        }
+    }
+
+    fn test_root(testing_data: &TestingData) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(testing_data.bytecode.clone()),
+            |mut txs, accs| {
+                // Decrease expected gas cost (by 1) to trigger out of gas error.
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas((GasCost::TX.0 + testing_data.gas_cost - 1).into());
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(testing_data: &TestingData) {
+        let (addr_a, addr_b) = (MOCK_ACCOUNTS[0], MOCK_ACCOUNTS[1]);
+
+        // code B gets called by code A, so the call is an internal call.
+        let code_b = testing_data.bytecode.clone();
+        let gas_cost_b = testing_data.gas_cost;
+
+        // Code A calls code B.
+        let code_a = bytecode! {
+            // populate memory in A's context.
+            PUSH8(U256::from_big_endian(&rand_bytes(8)))
+            PUSH1(0x00) // offset
+            MSTORE
+            // call ADDR_B.
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH32(0x00) // argsLength
+            PUSH32(0x20) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(addr_b.to_word()) // addr
+            // Decrease expected gas cost (by 1) to trigger out of gas error.
+            PUSH32(gas_cost_b - 1) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(addr_b).code(code_b);
+                accs[1].address(addr_a).code(code_a);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
@@ -4,18 +4,21 @@ use crate::{
         param::{N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE},
         step::ExecutionState,
         util::{
-            constraint_builder::EVMConstraintBuilder,
-            math_gadget::{IsEqualGadget, IsZeroGadget, RangeCheckGadget},
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget},
             memory_gadget::{address_high, address_low, MemoryExpansionGadget},
-            CachedRegion, Cell, Word,
+            select, CachedRegion, Cell, Word,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
     util::Expr,
 };
 use eth_types::{evm_types::OpcodeId, Field, ToLittleEndian};
-use halo2_proofs::plonk::Error;
+use halo2_proofs::{circuit::Value, plonk::Error};
 
+/// Gadget to implement the corresponding out of gas errors for
+/// [`OpcodeId::MLOAD`], [`OpcodeId::MSTORE`] and [`OpcodeId::MSTORE8`].
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGStaticMemoryGadget<F> {
     opcode: Cell<F>,
@@ -31,8 +34,9 @@ pub(crate) struct ErrorOOGStaticMemoryGadget<F> {
     // Even memory size at most could be 2^35 - 1, the qudratic part of memory
     // expansion gas cost could be at most 2^61 - 2^27, due to the constant
     // division by 512, which still fits in 8 bytes.
-    insufficient_gas: RangeCheckGadget<F, N_BYTES_GAS>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_mstore8: IsEqualGadget<F>,
+    common_error_gadget: CommonErrorGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
@@ -40,41 +44,52 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
 
     const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorOutOfGasStaticMemoryExpansion;
 
-    // Support other OOG due to pure memory including CREATE, RETURN and REVERT
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
+        cb.require_in_set(
+            "ErrorOutOfGasStaticMemoryExpansion opcode must be MLOAD, MSTORE or MSTORE8",
+            opcode.expr(),
+            vec![
+                OpcodeId::MLOAD.expr(),
+                OpcodeId::MSTORE.expr(),
+                OpcodeId::MSTORE8.expr(),
+            ],
+        );
 
         // Query address by a full word
         let address = cb.query_word_rlc();
 
+        // Pop the address from the stack
+        // We still have to do this to verify the correctness of `address`
+        cb.stack_pop(address.expr());
+
         // Check if this is an MSTORE8
         let is_mstore8 = IsEqualGadget::construct(cb, opcode.expr(), OpcodeId::MSTORE8.expr());
-        let is_not_mstore8 = 1.expr() - is_mstore8.expr();
 
         // Get the next memory size and the gas cost for this memory access
         let memory_expansion = MemoryExpansionGadget::construct(
             cb,
-            [address_low::expr(&address) + 1.expr() + (is_not_mstore8 * 31.expr())],
+            [address_low::expr(&address) + select::expr(is_mstore8.expr(), 1.expr(), 32.expr())],
         );
 
-        // Check if the memory address is too large
-        let address_in_range = IsZeroGadget::construct(cb, address_high::expr(&address));
         // Check if the amount of gas available is less than the amount of gas
-        // required
-        let insufficient_gas = cb.condition(address_in_range.expr(), |cb| {
-            RangeCheckGadget::construct(
-                cb,
-                OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost()
-                    - cb.curr.state.gas_left.expr(),
-            )
+        // required, which is only needed when the memory address is in range:
+        // a larger address always runs out of gas.
+        let address_in_range = IsZeroGadget::construct(cb, address_high::expr(&address));
+        let insufficient_gas = LtGadget::construct(
+            cb,
+            cb.curr.state.gas_left.expr(),
+            OpcodeId::MLOAD.constant_gas_cost().expr() + memory_expansion.gas_cost(),
+        );
+        cb.condition(address_in_range.expr(), |cb| {
+            cb.require_equal(
+                "Gas left is less than gas required",
+                insufficient_gas.expr(),
+                1.expr(),
+            );
         });
 
-        // Pop the address from the stack
-        // We still have to do this to verify the correctness of `address`
-        cb.stack_pop(address.expr());
-
-        // TODO: Use ContextSwitchGadget to switch call context to caller's and
-        // consume all gas_left.
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 3.expr());
 
         Self {
             opcode,
@@ -83,6 +98,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
             memory_expansion,
             insufficient_gas,
             is_mstore8,
+            common_error_gadget,
         }
     }
 
@@ -92,10 +108,12 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
         offset: usize,
         block: &Block<F>,
         _: &Transaction,
-        _: &Call,
+        call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
         let opcode = step.opcode.unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
 
         // Inputs/Outputs
         let address = block.rws[step.rw_indices[0]].stack_value();
@@ -118,7 +136,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
         )?;
 
         // Memory expansion
-        self.memory_expansion.assign(
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
             region,
             offset,
             step.memory_word_size(),
@@ -127,10 +145,127 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGStaticMemoryGadget<F> {
         )?;
 
         // Gas insufficient check
-        // Get `gas_available` variable here once it's available
-        self.insufficient_gas
-            .assign(region, offset, F::from(step.gas_cost - step.gas_left))?;
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(OpcodeId::MLOAD.constant_gas_cost().0 + memory_expansion_gas_cost),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 3)?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        bytecode, evm_types::OpcodeId, geth_types::Account, Address, Bytecode, ToWord, Word,
+    };
+    use mock::{test_ctx::helpers::account_0_code_account_1_no_code, TestContext, MOCK_ACCOUNTS};
+
+    const TESTING_MEMORY_OFFSET: u64 = 0x10000;
+
+    fn memory_code(opcode: OpcodeId, address: Word) -> Bytecode {
+        let mut code = Bytecode::default();
+        if opcode != OpcodeId::MLOAD {
+            code.push(32, Word::from(0xff));
+        }
+        code.push(32, address);
+        code.write_op(opcode);
+        code.write_op(OpcodeId::STOP);
+        code
+    }
+
+    fn test_root(code: Bytecode) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .gas(Word::from(25_000));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal(code: Bytecode) {
+        let callee = Account {
+            address: Address::repeat_byte(0xff),
+            code: code.to_vec().into(),
+            nonce: Word::one(),
+            balance: Word::from(0xdeadbeefu64),
+            ..Default::default()
+        };
+        let caller = bytecode! {
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH1(0)
+            PUSH32(callee.address.to_word())
+            PUSH2(10_000) // gas
+            CALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(Word::from(10u64.pow(19)));
+                accs[1]
+                    .address(MOCK_ACCOUNTS[1])
+                    .code(caller)
+                    .balance(Word::from(10u64.pow(19)));
+                accs[2]
+                    .address(callee.address)
+                    .code(callee.code)
+                    .nonce(callee.nonce)
+                    .balance(callee.balance);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100_000));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn error_oog_static_memory_root() {
+        for opcode in [OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8] {
+            test_root(memory_code(opcode, Word::from(TESTING_MEMORY_OFFSET)));
+        }
+    }
+
+    #[test]
+    fn error_oog_static_memory_internal() {
+        for opcode in [OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8] {
+            test_internal(memory_code(opcode, Word::from(TESTING_MEMORY_OFFSET)));
+        }
+    }
+
+    #[test]
+    fn error_oog_static_memory_address_out_of_range() {
+        // The address doesn't fit in 5 bytes, so the memory expansion gas
+        // cost overflows.
+        for address in [Word::from(1u64 << 40), Word::MAX] {
+            test_root(memory_code(OpcodeId::MSTORE, address));
+        }
+    }
+}
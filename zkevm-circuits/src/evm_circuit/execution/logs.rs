@@ -264,8 +264,8 @@ impl<F: Field> ExecutionGadget<F> for LogGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::{evm_types::OpcodeId, Bytecode, Word};
-    use mock::TestContext;
+    use eth_types::{bytecode, evm_types::OpcodeId, Bytecode, ToWord, Word};
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
     use rand::Rng;
 
     #[test]
@@ -329,6 +329,51 @@ mod test {
         ]);
     }
 
+    #[test]
+    fn log_gadget_reverted_internal_call() {
+        // The log of the reverted internal call is discarded, so the log of the
+        // root call after it is the first one of the tx.
+        let callee_code = bytecode! {
+            PUSH32(Word::from(0xA0))
+            PUSH1(0x20) // length
+            PUSH1(0x00) // offset
+            LOG1
+            PUSH1(0x00) // length
+            PUSH1(0x00) // offset
+            REVERT
+        };
+        let caller_code = bytecode! {
+            PUSH1(0x00) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x00) // value
+            PUSH32(MOCK_ACCOUNTS[1].to_word()) // addr
+            PUSH2(0xffff) // gas
+            CALL
+            PUSH32(Word::from(0xef))
+            PUSH1(0x20) // length
+            PUSH1(0x00) // offset
+            LOG1
+            STOP
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).code(caller_code);
+                accs[1].address(MOCK_ACCOUNTS[1]).code(callee_code);
+                accs[2].address(MOCK_ACCOUNTS[2]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[0].address);
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
     // test single log code and single copy log step
     fn test_log_ok(topics: &[Word], is_persistent: bool) {
         let mut pushdata = [0u8; 320];
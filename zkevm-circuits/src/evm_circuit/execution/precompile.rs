@@ -0,0 +1,883 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_U64},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            math_gadget::{
+                ConstantDivisionGadget, IsEqualGadget, IsZeroGadget, LtGadget, MinMaxGadget,
+            },
+            not, rlc, select, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use bus_mapping::{
+    circuit_input_builder::CopyDataType,
+    precompile::{PrecompileCalls, BLAKE2F_INPUT_LEN},
+};
+use eth_types::{evm_types::GasCost, Field};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for a call into a precompiled contract other than the identity one,
+/// executed right after the step entering the call (a `*CALL` opcode, or
+/// `BeginTx` for a transaction to a precompile).
+///
+/// The input is copied from the caller memory (or the tx calldata for a root
+/// call) into an RLC accumulator and the output is copied from another RLC
+/// accumulator into the precompile memory. Both RLCs, and the gas cost when it
+/// depends on the input bytes, are looked up into the table of the precompile,
+/// exposed by the circuit verifying its computation. For an internal call, the
+/// output is then copied into the caller memory as return data and the caller
+/// context is restored.
+///
+/// A call fails when the gas left is lower than the gas cost or when the
+/// precompile fails on an invalid input, as read from the precompile table, in
+/// which case it consumes all the gas, returns no data and its writes are
+/// reverted.  As no circuit verifies the pairing check, the ecPairing calls
+/// can only fail.
+#[derive(Clone, Debug)]
+pub(crate) struct PrecompileGadget<F, const S: ExecutionState> {
+    tx_id: Cell<F>,
+    is_success: Cell<F>,
+    cd_offset: Cell<F>,
+    cd_length: Cell<F>,
+    rd_offset: Cell<F>,
+    rd_length: Cell<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+
+    input_read_length: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    input_len_is_zero: IsZeroGadget<F>,
+    input_rlc: Cell<F>,
+    output_rlc: Cell<F>,
+    output_len: Cell<F>,
+    table_is_success: Cell<F>,
+    return_len_is_zero: IsZeroGadget<F>,
+    return_data_copy_length: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_copy_length_is_zero: IsZeroGadget<F>,
+
+    gas_cost: Cell<F>,
+    input_words: ConstantDivisionGadget<F, N_BYTES_U64>,
+    is_blake2f_input_len: IsEqualGadget<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for PrecompileGadget<F, S> {
+    const NAME: &'static str = "PRECOMPILE";
+
+    const EXECUTION_STATE: ExecutionState = S;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let precompile = S.precompile().expect("execution state is not a precompile");
+        let is_root = cb.curr.state.is_root.expr();
+
+        let [tx_id, is_success, cd_offset, cd_length] = [
+            CallContextFieldTag::TxId,
+            CallContextFieldTag::IsSuccess,
+            CallContextFieldTag::CallDataOffset,
+            CallContextFieldTag::CallDataLength,
+        ]
+        .map(|field_tag| cb.call_context(None, field_tag));
+        let [rd_offset, rd_length] = cb.condition(not::expr(is_root.expr()), |cb| {
+            [
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag))
+        });
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        let input_rlc = cb.query_cell_phase2();
+        let output_rlc = cb.query_cell_phase2();
+        let output_len = cb.query_cell();
+        let gas_cost = cb.query_cell();
+        let table_is_success = cb.query_bool();
+
+        // Gas cost is a function of the input length for all the precompiles
+        // but modexp and blake2f, where it depends on the input bytes and is
+        // verified by the table lookup.  The ecPairing calls always fail and
+        // consume all the gas, whatever their gas cost.
+        let input_words = ConstantDivisionGadget::construct(cb, cd_length.expr() + 31.expr(), 32);
+        let is_blake2f_input_len =
+            IsEqualGadget::construct(cb, cd_length.expr(), BLAKE2F_INPUT_LEN.expr());
+        let expected_gas_cost = match precompile {
+            PrecompileCalls::ECRecover => Some(GasCost::PRECOMPILE_ECRECOVER_BASE.expr()),
+            PrecompileCalls::Sha256 => Some(
+                GasCost::PRECOMPILE_SHA256_BASE.expr()
+                    + GasCost::PRECOMPILE_SHA256_PER_WORD.expr() * input_words.quotient(),
+            ),
+            PrecompileCalls::Ripemd160 => Some(
+                GasCost::PRECOMPILE_RIPEMD160_BASE.expr()
+                    + GasCost::PRECOMPILE_RIPEMD160_PER_WORD.expr() * input_words.quotient(),
+            ),
+            PrecompileCalls::Identity => {
+                unreachable!("identity is handled by PrecompileIdentityGadget")
+            }
+            PrecompileCalls::Bn128Add => Some(GasCost::PRECOMPILE_BN256ADD.expr()),
+            PrecompileCalls::Bn128Mul => Some(GasCost::PRECOMPILE_BN256MUL.expr()),
+            PrecompileCalls::Modexp | PrecompileCalls::Blake2F | PrecompileCalls::Bn128Pairing => {
+                None
+            }
+        };
+        if let Some(expected_gas_cost) = expected_gas_cost {
+            cb.require_equal(
+                "Precompile gas cost is correct",
+                gas_cost.expr(),
+                expected_gas_cost,
+            );
+        }
+        if precompile == PrecompileCalls::Blake2F {
+            // The call fails on an input of the wrong length before reading the
+            // number of rounds.
+            cb.condition(not::expr(is_blake2f_input_len.expr()), |cb| {
+                cb.require_zero(
+                    "Blake2f gas cost is 0 for a wrong input length",
+                    gas_cost.expr(),
+                );
+            });
+        }
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost.expr());
+        let sufficient_gas = not::expr(insufficient_gas.expr());
+
+        // The precompile table holds the output, the gas cost and the failure
+        // flag of the precompile on the input given enough gas.  It's needed
+        // by the calls that don't fail for lack of gas, and by the calls whose
+        // gas cost depends on the input bytes.  The blake2f calls on an input
+        // of the wrong length and the ecPairing calls always fail, as no
+        // circuit verifies the pairing check.
+        let (is_table_lookup, expected_is_success) = match precompile {
+            PrecompileCalls::Modexp => (1.expr(), sufficient_gas),
+            PrecompileCalls::Blake2F => (
+                is_blake2f_input_len.expr(),
+                is_blake2f_input_len.expr() * sufficient_gas * table_is_success.expr(),
+            ),
+            PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul => (
+                sufficient_gas.clone(),
+                sufficient_gas * table_is_success.expr(),
+            ),
+            PrecompileCalls::Bn128Pairing => (0.expr(), 0.expr()),
+            _ => (sufficient_gas.clone(), sufficient_gas),
+        };
+        cb.require_equal(
+            "Call is successful iff the precompile succeeds and the gas left is sufficient",
+            is_success.expr(),
+            expected_is_success,
+        );
+
+        // Copy the input into an RLC accumulator, from the tx calldata for a
+        // root call and from the caller memory otherwise.  The precompiles
+        // reading a fixed size input get the calldata right padded with zeros
+        // or truncated to that size, so only the bytes within both the calldata
+        // and the input are read.
+        let input_len = precompile
+            .input_len()
+            .map_or(cd_length.expr(), |input_len| input_len.expr());
+        let input_read_length = MinMaxGadget::construct(cb, cd_length.expr(), input_len.clone());
+        let input_len_is_zero = IsZeroGadget::construct(cb, input_len.clone());
+        cb.condition(input_len_is_zero.expr(), |cb| {
+            cb.require_zero("input_rlc == 0 for empty input", input_rlc.expr());
+        });
+        cb.condition(
+            is_table_lookup.clone() * not::expr(input_len_is_zero.expr()),
+            |cb| {
+                cb.copy_table_lookup(
+                    select::expr(is_root.expr(), tx_id.expr(), cb.next.state.call_id.expr()),
+                    select::expr(
+                        is_root.expr(),
+                        CopyDataType::TxCalldata.expr(),
+                        CopyDataType::Memory.expr(),
+                    ),
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::RlcAcc.expr(),
+                    cd_offset.expr(),
+                    cd_offset.expr() + cd_length.expr(),
+                    0.expr(),
+                    input_len.clone(),
+                    input_rlc.expr(),
+                    not::expr(is_root.expr()) * input_read_length.min(),
+                );
+            },
+        );
+
+        cb.condition(is_table_lookup, |cb| match precompile {
+            PrecompileCalls::ECRecover => {
+                // The output is the 32 bytes recovered address when the
+                // signature is valid and empty otherwise.
+                cb.ecrecover_table_lookup(
+                    input_rlc.expr(),
+                    output_len.expr() * F::from(32).invert().unwrap(),
+                    output_rlc.expr(),
+                );
+            }
+            PrecompileCalls::Sha256 => {
+                cb.require_equal("output_len == 32 for sha256", output_len.expr(), 32.expr());
+                cb.sha256_table_lookup(input_rlc.expr(), input_len, output_rlc.expr());
+            }
+            PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul => {
+                // The operation fails on an invalid point, with no output.
+                cb.require_equal(
+                    "output_len == 64 for successful ecAdd and ecMul",
+                    output_len.expr(),
+                    64.expr() * table_is_success.expr(),
+                );
+                cb.ec_table_lookup(
+                    u64::from(precompile).expr(),
+                    input_rlc.expr(),
+                    input_len,
+                    output_rlc.expr(),
+                    table_is_success.expr(),
+                );
+            }
+            PrecompileCalls::Modexp => {
+                // The gas cost depends on the sizes and the exponent, so it's
+                // verified together with the output.
+                cb.modexp_table_lookup(
+                    input_rlc.expr(),
+                    output_rlc.expr(),
+                    output_len.expr(),
+                    gas_cost.expr(),
+                );
+            }
+            PrecompileCalls::Ripemd160 => {
+                cb.require_equal(
+                    "output_len == 32 for ripemd160",
+                    output_len.expr(),
+                    32.expr(),
+                );
+                cb.ripemd160_table_lookup(input_rlc.expr(), input_len, output_rlc.expr());
+            }
+            PrecompileCalls::Blake2F => {
+                // The gas cost is the number of rounds read from the input, so
+                // it's verified together with the output.  The call fails on an
+                // invalid final block flag, with no output.
+                cb.require_equal(
+                    "output_len == 64 for successful blake2f",
+                    output_len.expr(),
+                    64.expr() * table_is_success.expr(),
+                );
+                cb.blake2f_table_lookup(
+                    input_rlc.expr(),
+                    output_rlc.expr(),
+                    gas_cost.expr(),
+                    table_is_success.expr(),
+                );
+            }
+            PrecompileCalls::Bn128Pairing => {}
+            PrecompileCalls::Identity => {
+                unreachable!("identity is handled by PrecompileIdentityGadget")
+            }
+        });
+
+        // A failing call consumes all the gas left and returns no data.
+        let gas_consumed = select::expr(
+            is_success.expr(),
+            gas_cost.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+        let return_len = is_success.expr() * output_len.expr();
+
+        // The output of a root call is discarded.
+        cb.condition(is_root.expr(), |cb| {
+            cb.require_next_state(ExecutionState::EndTx);
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(
+                    cb.rw_counter_offset()
+                        + not::expr(is_success.expr())
+                            * cb.curr.state.reversible_write_counter.expr(),
+                ),
+                gas_left: Delta(-gas_consumed.expr()),
+                ..StepStateTransition::any()
+            });
+        });
+
+        // Write the output into the precompile memory, and copy it from there
+        // into the caller memory as return data.
+        let return_len_is_zero = IsZeroGadget::construct(cb, return_len.clone());
+        let (return_data_copy_length, return_data_copy_length_is_zero) =
+            cb.condition(not::expr(is_root.expr()), |cb| {
+                cb.condition(not::expr(return_len_is_zero.expr()), |cb| {
+                    cb.copy_table_lookup(
+                        cb.curr.state.call_id.expr(),
+                        CopyDataType::RlcAcc.expr(),
+                        cb.curr.state.call_id.expr(),
+                        CopyDataType::Memory.expr(),
+                        0.expr(),
+                        output_len.expr(),
+                        0.expr(),
+                        output_len.expr(),
+                        output_rlc.expr(),
+                        output_len.expr(),
+                    );
+                });
+
+                let return_data_copy_length =
+                    MinMaxGadget::construct(cb, rd_length.expr(), return_len.clone());
+                let return_data_copy_length_is_zero =
+                    IsZeroGadget::construct(cb, return_data_copy_length.min());
+                cb.condition(not::expr(return_data_copy_length_is_zero.expr()), |cb| {
+                    cb.copy_table_lookup(
+                        cb.curr.state.call_id.expr(),
+                        CopyDataType::Memory.expr(),
+                        cb.next.state.call_id.expr(),
+                        CopyDataType::Memory.expr(),
+                        0.expr(),
+                        output_len.expr(),
+                        rd_offset.expr(),
+                        return_data_copy_length.min(),
+                        0.expr(),
+                        2.expr() * return_data_copy_length.min(),
+                    );
+                });
+
+                (return_data_copy_length, return_data_copy_length_is_zero)
+            });
+
+        let restore_context = cb.condition(not::expr(is_root), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                is_success.expr(),
+                0.expr(),
+                0.expr(),
+                return_len,
+                gas_consumed,
+                0.expr(),
+            )
+        });
+
+        // The writes of a failing call are reverted right after the step.
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            tx_id,
+            is_success,
+            cd_offset,
+            cd_length,
+            rd_offset,
+            rd_length,
+            rw_counter_end_of_reversion,
+            input_read_length,
+            input_len_is_zero,
+            input_rlc,
+            output_rlc,
+            output_len,
+            table_is_success,
+            return_len_is_zero,
+            return_data_copy_length,
+            return_data_copy_length_is_zero,
+            gas_cost,
+            input_words,
+            is_blake2f_input_len,
+            insufficient_gas,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let precompile = S.precompile().unwrap();
+
+        let input_len = precompile
+            .input_len()
+            .map_or(call.call_data_length, |input_len| input_len as u64);
+        // The input is only read for the lookup to the precompile table.
+        let needs_circuit = precompile.needs_circuit(call.call_data_length, step.gas_left);
+        let input_read_length = if needs_circuit {
+            call.call_data_length.min(input_len)
+        } else {
+            0
+        };
+        let (input, output, gas_cost, table_is_success) = if needs_circuit {
+            let rw_offset = 6 + !call.is_success as usize;
+            let mut input: Vec<u8> = if call.is_root {
+                tx.call_data[..call.call_data_length as usize].to_vec()
+            } else {
+                (rw_offset..rw_offset + input_read_length as usize)
+                    .map(|i| block.rws[step.rw_indices[i]].memory_value())
+                    .collect()
+            };
+            input.resize(input_len as usize, 0);
+            // Precompiles are deterministic, so any event with the same input holds
+            // the output of this call.
+            let event = block
+                .precompile_events
+                .iter()
+                .find(|event| event.precompile == precompile && event.input == input)
+                .expect("could not find the precompile event of the call");
+            (
+                input,
+                event.output.clone(),
+                event.gas_cost,
+                event.is_success,
+            )
+        } else {
+            // A failing call consumes all the gas left, but the gas cost is still
+            // the one of the precompile to prove that it's insufficient.
+            let gas_cost = precompile.gas_cost(call.call_data_length).unwrap_or(0);
+            (vec![], vec![], gas_cost, false)
+        };
+        let output_len = output.len() as u64;
+        let return_len = if call.is_success { output_len } else { 0 };
+
+        for (cell, value) in [
+            (&self.tx_id, tx.id as u64),
+            (&self.is_success, call.is_success as u64),
+            (&self.cd_offset, call.call_data_offset),
+            (&self.cd_length, call.call_data_length),
+            (&self.rd_offset, call.return_data_offset),
+            (&self.rd_length, call.return_data_length),
+            (&self.output_len, output_len),
+            (&self.table_is_success, table_is_success as u64),
+            (&self.gas_cost, gas_cost),
+            (
+                &self.rw_counter_end_of_reversion,
+                call.rw_counter_end_of_reversion as u64,
+            ),
+        ] {
+            cell.assign(region, offset, Value::known(F::from(value)))?;
+        }
+        self.input_read_length.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(input_len),
+        )?;
+        self.input_len_is_zero
+            .assign(region, offset, F::from(input_len))?;
+        self.return_len_is_zero
+            .assign(region, offset, F::from(return_len))?;
+
+        for (cell, bytes) in [(&self.input_rlc, &input), (&self.output_rlc, &output)] {
+            cell.assign(
+                region,
+                offset,
+                region
+                    .challenges()
+                    .keccak_input()
+                    .map(|randomness| rlc::value(bytes.iter().rev(), randomness)),
+            )?;
+        }
+
+        self.input_words
+            .assign(region, offset, call.call_data_length as u128 + 31)?;
+        self.is_blake2f_input_len.assign(
+            region,
+            offset,
+            F::from(call.call_data_length),
+            F::from(BLAKE2F_INPUT_LEN as u64),
+        )?;
+        self.insufficient_gas
+            .assign(region, offset, F::from(step.gas_left), F::from(gas_cost))?;
+
+        if !call.is_root {
+            self.return_data_copy_length.assign(
+                region,
+                offset,
+                F::from(call.return_data_length),
+                F::from(return_len),
+            )?;
+
+            let copy_length = call.return_data_length.min(return_len);
+            self.return_data_copy_length_is_zero
+                .assign(region, offset, F::from(copy_length))?;
+            let rw_offset = 6
+                + !call.is_success as usize
+                + input_read_length as usize
+                + return_len as usize
+                + 2 * copy_length as usize;
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
+    use eth_types::{bytecode, word, Bytecode, ToBigEndian, Word};
+    use mock::TestContext;
+
+    /// Calls the precompile at `address` with `input` stored in memory at
+    /// offset 0, returning the output at offset 0x80.
+    fn precompile_call_code(
+        call: OpcodeId,
+        address: PrecompileCalls,
+        input: &[u8],
+        ret_length: usize,
+    ) -> Bytecode {
+        precompile_call_code_with_gas(call, address, input, ret_length, 0, 0xFFFFFF)
+    }
+
+    /// Like [`precompile_call_code`], passing `value` (for `CALL` and
+    /// `CALLCODE`) and `gas` to the call.
+    fn precompile_call_code_with_gas(
+        call: OpcodeId,
+        address: PrecompileCalls,
+        input: &[u8],
+        ret_length: usize,
+        value: u64,
+        gas: u64,
+    ) -> Bytecode {
+        let mut code = Bytecode::default();
+        for (i, chunk) in input.chunks(32).enumerate() {
+            let mut word = [0u8; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            code.push(32, Word::from_big_endian(&word));
+            code.push(2, i * 32);
+            code.write_op(OpcodeId::MSTORE);
+        }
+        code.push(2, ret_length);
+        code.push(1, 0x80);
+        code.push(2, input.len());
+        code.push(1, 0x00);
+        if call == OpcodeId::CALL || call == OpcodeId::CALLCODE {
+            code.push(1, value);
+        }
+        code.push(1, u64::from(address));
+        code.push(3, gas);
+        code.write_op(call);
+        code.write_op(OpcodeId::POP);
+        code.write_op(OpcodeId::STOP);
+        code
+    }
+
+    fn test_ok(code: Bytecode) {
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn precompile_sha256_ripemd160() {
+        let input = b"abc";
+        for precompile in [PrecompileCalls::Sha256, PrecompileCalls::Ripemd160] {
+            test_ok(precompile_call_code(
+                OpcodeId::STATICCALL,
+                precompile,
+                input,
+                0x20,
+            ));
+        }
+    }
+
+    /// Message hash, v, r and s of an ecrecover input.
+    fn ecrecover_input(v: Word) -> Vec<u8> {
+        [
+            word!("0x456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3"),
+            v,
+            word!("0x9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"),
+            word!("0x4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"),
+        ]
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect()
+    }
+
+    #[test]
+    fn precompile_ecrecover() {
+        let input = ecrecover_input(Word::from(28));
+        test_ok(precompile_call_code(
+            OpcodeId::STATICCALL,
+            PrecompileCalls::ECRecover,
+            &input,
+            0x20,
+        ));
+        // The input is truncated to 128 bytes.
+        let input = [input, vec![0xff; 0x20]].concat();
+        test_ok(precompile_call_code(
+            OpcodeId::STATICCALL,
+            PrecompileCalls::ECRecover,
+            &input,
+            0x20,
+        ));
+    }
+
+    #[test]
+    fn precompile_ecrecover_invalid_signature() {
+        // Invalid v, and a short input right padded with zeros, both return an
+        // empty output.
+        for input in [
+            ecrecover_input(Word::from(29)),
+            ecrecover_input(Word::from(28))[..0x50].to_vec(),
+        ] {
+            test_ok(precompile_call_code(
+                OpcodeId::CALL,
+                PrecompileCalls::ECRecover,
+                &input,
+                0x20,
+            ));
+        }
+    }
+
+    /// Sizes of the base, exponent and modulus followed by their bytes.
+    fn modexp_input(base: &[u8], exp: &[u8], modulus: &[u8]) -> Vec<u8> {
+        [base.len(), exp.len(), modulus.len()]
+            .iter()
+            .flat_map(|size| Word::from(*size).to_be_bytes())
+            .chain(base.iter().chain(exp).chain(modulus).copied())
+            .collect()
+    }
+
+    #[test]
+    fn precompile_modexp() {
+        for input in [
+            modexp_input(&[0x08], &[0x09], &[0x0a]),
+            modexp_input(&[0xff; 32], &[0xff; 32], &[0xfe; 32]),
+            // The exponent is zero and the modulus is one.
+            modexp_input(&[0x03], &[], &[0x01]),
+        ] {
+            test_ok(precompile_call_code(
+                OpcodeId::STATICCALL,
+                PrecompileCalls::Modexp,
+                &input,
+                0x20,
+            ));
+        }
+    }
+
+    /// Rounds, state, message "abc", offset counter and final block flag of the
+    /// EIP-152 test vectors.
+    fn blake2f_input(final_flag: u8) -> Vec<u8> {
+        let mut input = 12u32.to_be_bytes().to_vec();
+        input.extend(
+            hex::decode(
+                "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+                 d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+            )
+            .unwrap(),
+        );
+        input.extend(b"abc");
+        input.resize(196, 0);
+        input.extend(3u64.to_le_bytes());
+        input.extend(0u64.to_le_bytes());
+        input.push(final_flag);
+        input
+    }
+
+    #[test]
+    fn precompile_blake2f() {
+        test_ok(precompile_call_code(
+            OpcodeId::STATICCALL,
+            PrecompileCalls::Blake2F,
+            &blake2f_input(1),
+            0x40,
+        ));
+    }
+
+    #[test]
+    fn precompile_blake2f_invalid_input() {
+        // An invalid final block flag, and an input of the wrong length.
+        for input in [blake2f_input(2), blake2f_input(1)[..0x20].to_vec()] {
+            test_ok(precompile_call_code(
+                OpcodeId::STATICCALL,
+                PrecompileCalls::Blake2F,
+                &input,
+                0x40,
+            ));
+        }
+    }
+
+    /// Encoding of the point (x, y).
+    fn ec_point(x: Word, y: Word) -> Vec<u8> {
+        [x.to_be_bytes(), y.to_be_bytes()].concat()
+    }
+
+    #[test]
+    fn precompile_ec_add_mul() {
+        let generator = ec_point(Word::one(), Word::from(2));
+        for (precompile, input) in [
+            (
+                PrecompileCalls::Bn128Add,
+                [generator.clone(), generator.clone()].concat(),
+            ),
+            (
+                PrecompileCalls::Bn128Mul,
+                [generator, Word::from(3).to_be_bytes().to_vec()].concat(),
+            ),
+        ] {
+            test_ok(precompile_call_code(
+                OpcodeId::STATICCALL,
+                precompile,
+                &input,
+                0x40,
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_ec_invalid_point() {
+        // The point (1, 1) is not on the curve, and the x coordinate of the
+        // second point is the field modulus.
+        let not_on_curve = ec_point(Word::one(), Word::one());
+        let not_in_field = ec_point(
+            word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47"),
+            Word::from(2),
+        );
+        for (precompile, input) in [
+            (
+                PrecompileCalls::Bn128Add,
+                [not_on_curve.clone(), ec_point(Word::one(), Word::from(2))].concat(),
+            ),
+            (PrecompileCalls::Bn128Add, not_in_field),
+            (
+                PrecompileCalls::Bn128Mul,
+                [not_on_curve, Word::from(3).to_be_bytes().to_vec()].concat(),
+            ),
+        ] {
+            test_ok(precompile_call_code(
+                OpcodeId::STATICCALL,
+                precompile,
+                &input,
+                0x40,
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_ec_pairing_failure() {
+        // An input of the wrong length, and a valid input without enough gas.
+        for (input, gas) in [(vec![0; 0x20], 0xFFFFFF), (vec![0; 0xC0], 0x10)] {
+            test_ok(precompile_call_code_with_gas(
+                OpcodeId::STATICCALL,
+                PrecompileCalls::Bn128Pairing,
+                &input,
+                0x20,
+                0,
+                gas,
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_out_of_gas() {
+        // The call fails without enough gas for the precompile, consuming all the
+        // gas passed to it.  With a value, the 2300 gas stipend is still below the
+        // 3000 gas of ecrecover, and the transfer is reverted.
+        for (precompile, input, value) in [
+            (PrecompileCalls::Sha256, b"abc".to_vec(), 0),
+            (
+                PrecompileCalls::ECRecover,
+                ecrecover_input(Word::from(28)),
+                0,
+            ),
+            (
+                PrecompileCalls::ECRecover,
+                ecrecover_input(Word::from(28)),
+                1,
+            ),
+            // The gas cost of modexp and blake2f depends on the input bytes.
+            (
+                PrecompileCalls::Modexp,
+                modexp_input(&[0x08], &[0x09], &[0x0a]),
+                0,
+            ),
+            (PrecompileCalls::Blake2F, blake2f_input(1), 0),
+        ] {
+            test_ok(precompile_call_code_with_gas(
+                OpcodeId::CALL,
+                precompile,
+                &input,
+                0x20,
+                value,
+                0x0A,
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_root_call_out_of_gas() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(PrecompileCalls::Sha256.into())
+                    .balance(1u64.into());
+                accs[1]
+                    .address(mock::MOCK_ACCOUNTS[0])
+                    .balance(mock::eth(10));
+            },
+            |mut txs, accs| {
+                // Intrinsic gas of the tx plus 10, not enough for the 72 gas of sha256.
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .value(Word::one())
+                    .input(vec![0xab; 0x20].into())
+                    .gas(Word::from(21_000 + 0x20 * 16 + 10));
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn precompile_root_call() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(PrecompileCalls::Sha256.into())
+                    .balance(1u64.into());
+                accs[1]
+                    .address(mock::MOCK_ACCOUNTS[0])
+                    .balance(mock::eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(vec![0xab; 0x28].into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn precompile_call_with_value() {
+        let mut code = bytecode! {
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x01) // value
+        };
+        code.push(1, u64::from(PrecompileCalls::Sha256));
+        code.append(&bytecode! {
+            PUSH2(0xFFFF) // gas
+            CALL
+            STOP
+        });
+        test_ok(code);
+    }
+}
@@ -0,0 +1,400 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, N_BYTES_U64},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            math_gadget::{ConstantDivisionGadget, IsZeroGadget, LtGadget, MinMaxGadget},
+            not, select, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, precompile::PrecompileCalls};
+use eth_types::{evm_types::GasCost, Field};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for a call into the identity precompiled contract.
+///
+/// Unlike the other precompiles, the identity doesn't need the precompile
+/// table: for an internal call its input is copied from the caller memory into
+/// the precompile memory, and from there into the caller memory as return
+/// data, both copies being verified by the copy circuit.  The output of a root
+/// call is discarded, so only its gas cost is constrained.
+///
+/// The call fails when the gas left is lower than the gas cost, in which case
+/// it consumes all the gas, nothing is copied and its writes are reverted.
+#[derive(Clone, Debug)]
+pub(crate) struct PrecompileIdentityGadget<F> {
+    tx_id: Cell<F>,
+    is_success: Cell<F>,
+    cd_offset: Cell<F>,
+    cd_length: Cell<F>,
+    cd_length_is_zero: IsZeroGadget<F>,
+    rd_offset: Cell<F>,
+    rd_length: Cell<F>,
+    rw_counter_end_of_reversion: Cell<F>,
+    return_data_copy_length: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    return_data_copy_length_is_zero: IsZeroGadget<F>,
+    input_words: ConstantDivisionGadget<F, N_BYTES_U64>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for PrecompileIdentityGadget<F> {
+    const NAME: &'static str = "PRECOMPILE_IDENTITY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::PrecompileIdentity;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let is_root = cb.curr.state.is_root.expr();
+
+        let [tx_id, is_success, cd_offset, cd_length] = [
+            CallContextFieldTag::TxId,
+            CallContextFieldTag::IsSuccess,
+            CallContextFieldTag::CallDataOffset,
+            CallContextFieldTag::CallDataLength,
+        ]
+        .map(|field_tag| cb.call_context(None, field_tag));
+        let [rd_offset, rd_length] = cb.condition(not::expr(is_root.expr()), |cb| {
+            [
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag))
+        });
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        // Gas cost is 15 + 3 * words, words being the input length rounded up
+        // to a multiple of 32 bytes.
+        let input_words = ConstantDivisionGadget::construct(cb, cd_length.expr() + 31.expr(), 32);
+        let gas_cost = GasCost::PRECOMPILE_IDENTITY_BASE.expr()
+            + GasCost::PRECOMPILE_IDENTITY_PER_WORD.expr() * input_words.quotient();
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost.clone());
+        cb.require_equal(
+            "Call is successful iff the gas left is sufficient",
+            is_success.expr(),
+            not::expr(insufficient_gas.expr()),
+        );
+        // A failing call consumes all the gas left and has no output.
+        let gas_consumed = select::expr(is_success.expr(), gas_cost, cb.curr.state.gas_left.expr());
+        let output_len = is_success.expr() * cd_length.expr();
+
+        cb.condition(is_root.expr(), |cb| {
+            cb.require_next_state(ExecutionState::EndTx);
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(
+                    cb.rw_counter_offset()
+                        + not::expr(is_success.expr())
+                            * cb.curr.state.reversible_write_counter.expr(),
+                ),
+                gas_left: Delta(-gas_consumed.clone()),
+                ..StepStateTransition::any()
+            });
+        });
+
+        // Copy the input from the caller memory into the precompile memory,
+        // which is the output, and from there into the caller memory as return
+        // data.
+        let cd_length_is_zero = IsZeroGadget::construct(cb, cd_length.expr());
+        let (return_data_copy_length, return_data_copy_length_is_zero) =
+            cb.condition(not::expr(is_root.expr()), |cb| {
+                cb.condition(
+                    is_success.expr() * not::expr(cd_length_is_zero.expr()),
+                    |cb| {
+                        cb.copy_table_lookup(
+                            cb.next.state.call_id.expr(),
+                            CopyDataType::Memory.expr(),
+                            cb.curr.state.call_id.expr(),
+                            CopyDataType::Memory.expr(),
+                            cd_offset.expr(),
+                            cd_offset.expr() + cd_length.expr(),
+                            0.expr(),
+                            cd_length.expr(),
+                            0.expr(),
+                            2.expr() * cd_length.expr(),
+                        );
+                    },
+                );
+
+                let return_data_copy_length =
+                    MinMaxGadget::construct(cb, rd_length.expr(), output_len.clone());
+                let return_data_copy_length_is_zero =
+                    IsZeroGadget::construct(cb, return_data_copy_length.min());
+                cb.condition(not::expr(return_data_copy_length_is_zero.expr()), |cb| {
+                    cb.copy_table_lookup(
+                        cb.curr.state.call_id.expr(),
+                        CopyDataType::Memory.expr(),
+                        cb.next.state.call_id.expr(),
+                        CopyDataType::Memory.expr(),
+                        0.expr(),
+                        cd_length.expr(),
+                        rd_offset.expr(),
+                        return_data_copy_length.min(),
+                        0.expr(),
+                        2.expr() * return_data_copy_length.min(),
+                    );
+                });
+
+                (return_data_copy_length, return_data_copy_length_is_zero)
+            });
+
+        let restore_context = cb.condition(not::expr(is_root), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                is_success.expr(),
+                0.expr(),
+                0.expr(),
+                output_len,
+                gas_consumed,
+                0.expr(),
+            )
+        });
+
+        // The writes of a failing call are reverted right after the step.
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            tx_id,
+            is_success,
+            cd_offset,
+            cd_length,
+            cd_length_is_zero,
+            rd_offset,
+            rd_length,
+            rw_counter_end_of_reversion,
+            return_data_copy_length,
+            return_data_copy_length_is_zero,
+            input_words,
+            insufficient_gas,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        for (cell, value) in [
+            (&self.tx_id, tx.id as u64),
+            (&self.is_success, call.is_success as u64),
+            (&self.cd_offset, call.call_data_offset),
+            (&self.cd_length, call.call_data_length),
+            (&self.rd_offset, call.return_data_offset),
+            (&self.rd_length, call.return_data_length),
+            (
+                &self.rw_counter_end_of_reversion,
+                call.rw_counter_end_of_reversion as u64,
+            ),
+        ] {
+            cell.assign(region, offset, Value::known(F::from(value)))?;
+        }
+        self.cd_length_is_zero
+            .assign(region, offset, F::from(call.call_data_length))?;
+
+        self.input_words
+            .assign(region, offset, call.call_data_length as u128 + 31)?;
+        // A failing call consumes all the gas left, but the gas cost is still the
+        // one of the precompile to prove that it's insufficient.
+        let gas_cost = PrecompileCalls::Identity
+            .gas_cost(call.call_data_length)
+            .unwrap();
+        self.insufficient_gas
+            .assign(region, offset, F::from(step.gas_left), F::from(gas_cost))?;
+
+        if !call.is_root {
+            let output_len = if call.is_success {
+                call.call_data_length
+            } else {
+                0
+            };
+            self.return_data_copy_length.assign(
+                region,
+                offset,
+                F::from(call.return_data_length),
+                F::from(output_len),
+            )?;
+            let copy_length = call.return_data_length.min(output_len);
+            self.return_data_copy_length_is_zero
+                .assign(region, offset, F::from(copy_length))?;
+
+            let rw_offset = if call.is_success {
+                6 + 2 * call.call_data_length as usize + 2 * copy_length as usize
+            } else {
+                7
+            };
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
+    use eth_types::{bytecode, word, Bytecode};
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    /// Stores 0x28 bytes in memory at offset 0 and copies them with the
+    /// identity precompile into memory at offset `0x40`.
+    fn identity_call_code(call: OpcodeId, ret_length: usize) -> Bytecode {
+        let mut code = bytecode! {
+            PUSH32(word!("0x0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(word!("0xfedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210"))
+            PUSH1(0x20)
+            MSTORE
+        };
+        code.push(2, ret_length);
+        code.push(1, 0x40); // retOffset
+        code.push(1, 0x28); // argsLength
+        code.push(1, 0x00); // argsOffset
+        if call == OpcodeId::CALL || call == OpcodeId::CALLCODE {
+            code.push(1, 0x00); // value
+        }
+        code.push(1, u64::from(PrecompileCalls::Identity));
+        code.push(2, 0xFFFF); // gas
+        code.write_op(call);
+        code.write_op(OpcodeId::POP);
+        code.write_op(OpcodeId::STOP);
+        code
+    }
+
+    fn test_ok(code: Bytecode) {
+        let ctx = TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn precompile_identity() {
+        for call in [
+            OpcodeId::CALL,
+            OpcodeId::CALLCODE,
+            OpcodeId::DELEGATECALL,
+            OpcodeId::STATICCALL,
+        ] {
+            // Return data shorter, equal and longer than the output.
+            for ret_length in [0x10, 0x28, 0x60] {
+                test_ok(identity_call_code(call, ret_length));
+            }
+        }
+    }
+
+    #[test]
+    fn precompile_identity_empty_input() {
+        test_ok(bytecode! {
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x00) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x04) // address
+            PUSH2(0xFFFF) // gas
+            STATICCALL
+            STOP
+        });
+    }
+
+    #[test]
+    fn precompile_identity_call_with_value() {
+        test_ok(bytecode! {
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x20) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x01) // value
+            PUSH1(0x04) // address
+            PUSH2(0xFFFF) // gas
+            CALL
+            STOP
+        });
+    }
+
+    #[test]
+    fn precompile_identity_out_of_gas() {
+        // 16 gas is below the 21 gas needed to copy 0x28 bytes, so the call fails
+        // consuming all its gas and nothing is copied.
+        test_ok(bytecode! {
+            PUSH1(0x20) // retLength
+            PUSH1(0x00) // retOffset
+            PUSH1(0x28) // argsLength
+            PUSH1(0x00) // argsOffset
+            PUSH1(0x04) // address
+            PUSH1(0x10) // gas
+            STATICCALL
+            STOP
+        });
+    }
+
+    #[test]
+    fn precompile_identity_root_call_out_of_gas() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(PrecompileCalls::Identity.into())
+                    .balance(1u64.into());
+                accs[1].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .value(1u64.into())
+                    .input(vec![0xab; 0x28].into())
+                    .gas((21_000 + 0x28 * 16 + 10).into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn precompile_identity_root_call() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(PrecompileCalls::Identity.into())
+                    .balance(1u64.into());
+                accs[1].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(vec![0xab; 0x28].into());
+            },
+            |block, _tx| block,
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
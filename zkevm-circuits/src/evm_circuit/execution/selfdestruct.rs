@@ -0,0 +1,567 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_ACCOUNT_ADDRESS,
+        step::ExecutionState,
+        util::{
+            common_gadget::{EmptyAccountGadget, RestoreContextGadget, UpdateBalanceGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            from_bytes,
+            math_gadget::{IsEqualGadget, IsZeroGadget},
+            not, select, CachedRegion, Cell, StepRws, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag},
+    util::Expr,
+};
+use eth_types::{
+    evm_types::{GasCost, OpcodeId},
+    Field, ToAddress, ToLittleEndian, ToScalar, U256,
+};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for SELFDESTRUCT. The whole balance of the current account is moved
+/// to the beneficiary (creating it if needed), and the current account is
+/// marked as destructed in the tx. The account stays alive until the end of
+/// the tx, where its storage and fields are cleared by the `DestructAccount`
+/// step.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfDestructGadget<F> {
+    opcode: Cell<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    callee_address: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    beneficiary: Word<F>,
+    is_warm: Cell<F>,
+    beneficiary_account: EmptyAccountGadget<F>,
+    is_self: IsEqualGadget<F>,
+    value: Word<F>,
+    value_is_zero: IsZeroGadget<F>,
+    beneficiary_balance: UpdateBalanceGadget<F, 2, true>,
+    is_destructed_prev: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfDestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        // We do the responsible opcode check explicitly here because we're not using
+        // the `SameContextGadget` for `SELFDESTRUCT`.
+        cb.require_equal(
+            "Opcode should be SELFDESTRUCT",
+            opcode.expr(),
+            OpcodeId::SELFDESTRUCT.expr(),
+        );
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        let callee_address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+        let mut reversion_info = cb.reversion_info_read(None);
+
+        // SELFDESTRUCT in a static call is handled by ErrorWriteProtection.
+        cb.require_zero("is_static == false", is_static.expr());
+
+        let beneficiary = cb.query_word_rlc();
+        let beneficiary_address = from_bytes::expr(&beneficiary.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(beneficiary.expr());
+
+        let is_warm = cb.query_bool();
+        cb.account_access_list_write(
+            tx_id.expr(),
+            beneficiary_address.expr(),
+            1.expr(),
+            is_warm.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_account = EmptyAccountGadget::construct(cb, beneficiary_address.expr());
+        let is_self =
+            IsEqualGadget::construct(cb, beneficiary_address.expr(), callee_address.expr());
+
+        // When the beneficiary is the current account the balance is burnt.
+        let value = cb.query_word_rlc();
+        let value_is_zero = IsZeroGadget::construct(cb, value.expr());
+        let is_transfer = not::expr(value_is_zero.expr()) * not::expr(is_self.expr());
+        let beneficiary_balance = cb.condition(is_transfer.expr(), |cb| {
+            cb.condition(beneficiary_account.not_exists(), |cb| {
+                cb.account_write(
+                    beneficiary_address.expr(),
+                    AccountFieldTag::CodeHash,
+                    cb.empty_code_hash_rlc(),
+                    0.expr(),
+                    Some(&mut reversion_info),
+                );
+            });
+            UpdateBalanceGadget::construct(
+                cb,
+                beneficiary_address.expr(),
+                vec![value.clone()],
+                Some(&mut reversion_info),
+            )
+        });
+
+        // Move the balance out of the current account and mark it as destructed.
+        // Its nonce, code and storage are cleared at the end of the tx.
+        cb.account_write(
+            callee_address.expr(),
+            AccountFieldTag::Balance,
+            0.expr(),
+            value.expr(),
+            Some(&mut reversion_info),
+        );
+        let is_destructed_prev = cb.query_bool();
+        cb.tx_destructed_account_write(
+            tx_id.expr(),
+            callee_address.expr(),
+            1.expr(),
+            is_destructed_prev.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // Call ends with SELFDESTRUCT must be successful
+        cb.call_context_lookup(false.expr(), None, CallContextFieldTag::IsSuccess, 1.expr());
+
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + select::expr(
+                is_warm.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + not::expr(value_is_zero.expr())
+                * beneficiary_account.is_empty()
+                * GasCost::NEW_ACCOUNT.expr();
+
+        // Access list write, sender balance and destructed account writes, plus the
+        // beneficiary code hash and balance writes when transferring.
+        let reversible_write_counter_increase =
+            3.expr() + is_transfer.expr() * (1.expr() + beneficiary_account.not_exists());
+
+        let is_to_end_tx = cb.next.execution_state_selector([ExecutionState::EndTx]);
+        cb.require_equal(
+            "Go to EndTx only when is_root",
+            cb.curr.state.is_root.expr(),
+            is_to_end_tx,
+        );
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(cb.rw_counter_offset()),
+                gas_left: Delta(-gas_cost.expr()),
+                reversible_write_counter: Delta(reversible_write_counter_increase.expr()),
+                ..StepStateTransition::any()
+            });
+        });
+
+        // When it's an internal call
+        let restore_context = cb.condition(1.expr() - cb.curr.state.is_root.expr(), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                true.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                gas_cost,
+                reversible_write_counter_increase,
+            )
+        });
+
+        Self {
+            opcode,
+            tx_id,
+            is_static,
+            callee_address,
+            reversion_info,
+            beneficiary,
+            is_warm,
+            beneficiary_account,
+            is_self,
+            value,
+            value_is_zero,
+            beneficiary_balance,
+            is_destructed_prev,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode.unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        let callee_address = call
+            .callee_address
+            .to_scalar()
+            .expect("unexpected Address -> Scalar conversion failure");
+        self.callee_address
+            .assign(region, offset, Value::known(callee_address))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+
+        let mut rws = StepRws::new(block, step);
+        rws.offset_add(5);
+
+        let beneficiary = rws.next().stack_value();
+        self.beneficiary
+            .assign(region, offset, Some(beneficiary.to_le_bytes()))?;
+        let beneficiary_address = beneficiary.to_address();
+
+        let (_, is_warm) = rws.next().tx_access_list_value_pair();
+        self.is_warm
+            .assign(region, offset, Value::known(F::from(is_warm as u64)))?;
+
+        self.beneficiary_account.assign(region, offset, &mut rws)?;
+        self.is_self.assign(
+            region,
+            offset,
+            beneficiary_address
+                .to_scalar()
+                .expect("unexpected Address -> Scalar conversion failure"),
+            callee_address,
+        )?;
+
+        // The beneficiary is only written when the balance is transferred to another
+        // account, optionally preceded by its creation.
+        let mut beneficiary_balance_pair = None;
+        let mut rw = rws.next();
+        if beneficiary_address != call.callee_address && rw.address() == Some(beneficiary_address) {
+            if rw.field_tag() == Some(AccountFieldTag::CodeHash as u64) {
+                rw = rws.next();
+            }
+            beneficiary_balance_pair = Some(rw.account_value_pair());
+            rw = rws.next();
+        }
+
+        let (_, value) = rw.account_value_pair();
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        self.value_is_zero
+            .assign_value(region, offset, region.word_rlc(value))?;
+        let (balance, balance_prev) = beneficiary_balance_pair.unwrap_or_default();
+        self.beneficiary_balance.assign(
+            region,
+            offset,
+            balance_prev,
+            vec![if beneficiary_balance_pair.is_some() {
+                value
+            } else {
+                U256::zero()
+            }],
+            balance,
+        )?;
+
+        let (_, is_destructed_prev) = rws.next().tx_destructed_account_value_pair();
+        self.is_destructed_prev.assign(
+            region,
+            offset,
+            Value::known(F::from(is_destructed_prev as u64)),
+        )?;
+
+        if !call.is_root {
+            // All the rws of the step up to here, plus the IsSuccess read.
+            let rw_offset = rws.offset() + 1;
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, evm_types::OpcodeId, Address, Bytecode, ToWord, Word};
+    use mock::{eth, TestContext, MOCK_ACCOUNTS};
+
+    const CONTRACT: Address = Address::repeat_byte(0xcc);
+
+    fn selfdestruct_code(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    fn test_root_ok(beneficiary: Address, balance: Word) {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(CONTRACT)
+                    .balance(balance)
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(beneficiary));
+                accs[1].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[2].address(MOCK_ACCOUNTS[1]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    fn test_internal_ok(beneficiary: Address, balance: Word, is_reverted: bool) {
+        // The caller reverts after the callee has selfdestructed, so all writes of
+        // SELFDESTRUCT are reverted too.
+        let mut caller_code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            PUSH20(CONTRACT.to_word())
+            PUSH2(0xffff) // gas
+            CALL
+            PUSH1(0)
+            PUSH1(0)
+        };
+        caller_code.write_op(if is_reverted {
+            OpcodeId::REVERT
+        } else {
+            OpcodeId::STOP
+        });
+
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(CONTRACT)
+                    .balance(balance)
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(beneficiary));
+                accs[1].address(MOCK_ACCOUNTS[0]).code(caller_code);
+                accs[2].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                accs[3].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn selfdestruct_root_existing_beneficiary() {
+        test_root_ok(MOCK_ACCOUNTS[1], eth(1));
+        test_root_ok(MOCK_ACCOUNTS[1], Word::zero());
+    }
+
+    #[test]
+    fn selfdestruct_root_non_empty_beneficiary_without_balance() {
+        // An existing account with a nonce is not empty, so no NEW_ACCOUNT gas is
+        // charged even though its balance is 0.
+        let beneficiary = address!("0x00000000000000000000000000000000000000be");
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(CONTRACT)
+                    .balance(eth(1))
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(beneficiary));
+                accs[1].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[2].address(beneficiary).nonce(Word::one());
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn selfdestruct_clears_storage_at_end_tx() {
+        // Both the slots of the prestate and the ones written in the tx are cleared by
+        // the DestructAccount step after EndTx.
+        let code = bytecode! {
+            PUSH1(0x30)
+            PUSH1(3)
+            SSTORE
+            PUSH1(0)
+            PUSH1(2)
+            SSTORE
+            PUSH20(MOCK_ACCOUNTS[1].to_word())
+            SELFDESTRUCT
+        };
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(CONTRACT)
+                    .balance(eth(1))
+                    .nonce(Word::one())
+                    .code(code)
+                    .storage(
+                        vec![
+                            (Word::from(1), Word::from(0x10)),
+                            (Word::from(2), Word::from(0x20)),
+                        ]
+                        .into_iter(),
+                    );
+                accs[1].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[2].address(MOCK_ACCOUNTS[1]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[1].address).to(accs[0].address);
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn selfdestruct_account_alive_until_end_tx() {
+        // The destructed account keeps its code and balance until the end of the tx, so
+        // it can be called again (selfdestructing twice) and inspected.
+        let mut caller_code = Bytecode::default();
+        for _ in 0..2 {
+            caller_code.append(&bytecode! {
+                PUSH1(0) // retLength
+                PUSH1(0) // retOffset
+                PUSH1(0) // argsLength
+                PUSH1(0) // argsOffset
+                PUSH1(1) // value
+                PUSH20(CONTRACT.to_word())
+                PUSH2(0xffff) // gas
+                CALL
+                POP
+            });
+        }
+        caller_code.append(&bytecode! {
+            PUSH20(CONTRACT.to_word())
+            BALANCE
+            POP
+            PUSH20(CONTRACT.to_word())
+            EXTCODESIZE
+            POP
+            STOP
+        });
+
+        let ctx = TestContext::<4, 2>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(CONTRACT)
+                    .balance(eth(1))
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(MOCK_ACCOUNTS[2]));
+                accs[1]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(1))
+                    .code(caller_code);
+                accs[2].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                accs[3].address(MOCK_ACCOUNTS[2]).balance(eth(1));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+                // The account no longer exists in the next tx.
+                txs[1]
+                    .from(accs[2].address)
+                    .to(accs[0].address)
+                    .value(eth(1));
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn selfdestruct_root_non_existing_beneficiary() {
+        let beneficiary = address!("0x00000000000000000000000000000000000000be");
+        test_root_ok(beneficiary, eth(1));
+        test_root_ok(beneficiary, Word::zero());
+    }
+
+    #[test]
+    fn selfdestruct_root_self_beneficiary() {
+        test_root_ok(CONTRACT, eth(1));
+    }
+
+    #[test]
+    fn selfdestruct_internal() {
+        let beneficiary = address!("0x00000000000000000000000000000000000000be");
+        for is_reverted in [false, true] {
+            test_internal_ok(MOCK_ACCOUNTS[2], eth(1), is_reverted);
+            test_internal_ok(beneficiary, eth(1), is_reverted);
+            test_internal_ok(CONTRACT, eth(1), is_reverted);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_in_static_call() {
+        // SELFDESTRUCT in a static call fails with ErrorWriteProtection.
+        let caller_code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH20(CONTRACT.to_word())
+            PUSH2(0xffff) // gas
+            STATICCALL
+            STOP
+        };
+
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(CONTRACT)
+                    .balance(eth(1))
+                    .nonce(Word::one())
+                    .code(selfdestruct_code(MOCK_ACCOUNTS[2]));
+                accs[1].address(MOCK_ACCOUNTS[0]).code(caller_code);
+                accs[2].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, accs| {
+                txs[0].from(accs[2].address).to(accs[1].address);
+            },
+            |block, _tx| block.number(0xcafe_u64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
@@ -7,7 +7,7 @@ use crate::{
     },
     util::Expr,
 };
-use bus_mapping::evm::OpcodeId;
+use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::Value,
@@ -23,6 +23,7 @@ pub enum ExecutionState {
     // Internal state
     BeginTx,
     EndTx,
+    DestructAccount,
     EndBlock,
     // Opcode successful cases
     STOP,
@@ -83,13 +84,21 @@ pub enum ExecutionState {
     RETURN_REVERT, // RETURN, REVERT
     CREATE2,
     SELFDESTRUCT,
+    // Precompiled contract calls
+    PrecompileEcRecover,
+    PrecompileSha256,
+    PrecompileRipemd160,
+    PrecompileIdentity,
+    PrecompileBigModExp,
+    PrecompileBn256Add,
+    PrecompileBn256ScalarMul,
+    PrecompileBn256Pairing,
+    PrecompileBlake2F,
     // Error cases
     ErrorInvalidOpcode,
     ErrorStack,
     ErrorWriteProtection,
-    ErrorDepth,
     ErrorInsufficientBalance,
-    ErrorContractAddressCollision,
     ErrorInvalidCreationCode,
     ErrorMaxCodeSizeExceeded,
     ErrorInvalidJump,
@@ -122,6 +131,22 @@ impl Display for ExecutionState {
     }
 }
 
+impl From<PrecompileCalls> for ExecutionState {
+    fn from(precompile: PrecompileCalls) -> Self {
+        match precompile {
+            PrecompileCalls::ECRecover => Self::PrecompileEcRecover,
+            PrecompileCalls::Sha256 => Self::PrecompileSha256,
+            PrecompileCalls::Ripemd160 => Self::PrecompileRipemd160,
+            PrecompileCalls::Identity => Self::PrecompileIdentity,
+            PrecompileCalls::Modexp => Self::PrecompileBigModExp,
+            PrecompileCalls::Bn128Add => Self::PrecompileBn256Add,
+            PrecompileCalls::Bn128Mul => Self::PrecompileBn256ScalarMul,
+            PrecompileCalls::Bn128Pairing => Self::PrecompileBn256Pairing,
+            PrecompileCalls::Blake2F => Self::PrecompileBlake2F,
+        }
+    }
+}
+
 impl ExecutionState {
     pub(crate) const fn as_u64(&self) -> u64 {
         *self as u64
@@ -160,9 +185,34 @@ impl ExecutionState {
 
     pub(crate) fn halts(&self) -> bool {
         matches!(self, Self::STOP | Self::RETURN_REVERT | Self::SELFDESTRUCT)
+            || self.is_precompiled()
             || self.halts_in_exception()
     }
 
+    /// Returns the precompiled contract executed by this state, if any.
+    pub(crate) fn precompile(&self) -> Option<PrecompileCalls> {
+        match self {
+            Self::PrecompileEcRecover => Some(PrecompileCalls::ECRecover),
+            Self::PrecompileSha256 => Some(PrecompileCalls::Sha256),
+            Self::PrecompileRipemd160 => Some(PrecompileCalls::Ripemd160),
+            Self::PrecompileIdentity => Some(PrecompileCalls::Identity),
+            Self::PrecompileBigModExp => Some(PrecompileCalls::Modexp),
+            Self::PrecompileBn256Add => Some(PrecompileCalls::Bn128Add),
+            Self::PrecompileBn256ScalarMul => Some(PrecompileCalls::Bn128Mul),
+            Self::PrecompileBn256Pairing => Some(PrecompileCalls::Bn128Pairing),
+            Self::PrecompileBlake2F => Some(PrecompileCalls::Blake2F),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_precompiled(&self) -> bool {
+        self.precompile().is_some()
+    }
+
+    pub(crate) fn precompiles() -> impl Iterator<Item = Self> {
+        Self::iter().filter(Self::is_precompiled)
+    }
+
     pub(crate) fn responsible_opcodes(&self) -> Vec<ResponsibleOp> {
         if matches!(self, Self::ErrorStack) {
             return OpcodeId::valid_opcodes()
@@ -131,6 +131,12 @@ pub(crate) enum Table {
     Copy,
     Keccak,
     Exp,
+    EcRecover,
+    Sha256,
+    Ripemd160,
+    Ec,
+    ModExp,
+    Blake2f,
 }
 
 #[derive(Clone, Debug)]
@@ -280,6 +286,69 @@ pub(crate) enum Lookup<F> {
         exponent_lo_hi: [Expression<F>; 2],
         exponentiation_lo_hi: [Expression<F>; 2],
     },
+    /// Lookup to ecrecover table.
+    EcRecoverTable {
+        /// RLC of the input bytes, right padded to 128 bytes.
+        input_rlc: Expression<F>,
+        /// Whether the signature is valid and the output not empty.
+        is_valid: Expression<F>,
+        /// RLC of the output bytes.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to sha256 table.
+    Sha256Table {
+        /// RLC of the input bytes.
+        input_rlc: Expression<F>,
+        /// Length of the input.
+        input_len: Expression<F>,
+        /// RLC of the hash bytes.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to ripemd160 table.
+    Ripemd160Table {
+        /// RLC of the input bytes.
+        input_rlc: Expression<F>,
+        /// Length of the input.
+        input_len: Expression<F>,
+        /// RLC of the hash bytes, left padded to 32 bytes.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to ec table.
+    EcTable {
+        /// Address of the precompiled contract.
+        op_type: Expression<F>,
+        /// RLC of the input bytes, right padded for ecAdd and ecMul.
+        input_rlc: Expression<F>,
+        /// Length of the input.
+        input_len: Expression<F>,
+        /// RLC of the output bytes.
+        output_rlc: Expression<F>,
+        /// Whether the operation succeeds.
+        is_success: Expression<F>,
+    },
+    /// Lookup to modexp table.
+    ModExpTable {
+        /// RLC of the input bytes, right padded or truncated to the modexp
+        /// input limit.
+        input_rlc: Expression<F>,
+        /// RLC of the output bytes.
+        output_rlc: Expression<F>,
+        /// Length of the output.
+        output_len: Expression<F>,
+        /// Gas cost of the call.
+        gas_cost: Expression<F>,
+    },
+    /// Lookup to blake2f table.
+    Blake2fTable {
+        /// RLC of the input bytes.
+        input_rlc: Expression<F>,
+        /// RLC of the output bytes.
+        output_rlc: Expression<F>,
+        /// Gas cost of the call, which is the number of rounds.
+        gas_cost: Expression<F>,
+        /// Whether the call succeeds.
+        is_success: Expression<F>,
+    },
     /// Conditional lookup enabled by the first element.
     Conditional(Expression<F>, Box<Lookup<F>>),
 }
@@ -299,6 +368,12 @@ impl<F: Field> Lookup<F> {
             Self::CopyTable { .. } => Table::Copy,
             Self::KeccakTable { .. } => Table::Keccak,
             Self::ExpTable { .. } => Table::Exp,
+            Self::EcRecoverTable { .. } => Table::EcRecover,
+            Self::Sha256Table { .. } => Table::Sha256,
+            Self::Ripemd160Table { .. } => Table::Ripemd160,
+            Self::EcTable { .. } => Table::Ec,
+            Self::ModExpTable { .. } => Table::ModExp,
+            Self::Blake2fTable { .. } => Table::Blake2f,
             Self::Conditional(_, lookup) => lookup.table(),
         }
     }
@@ -410,6 +485,74 @@ impl<F: Field> Lookup<F> {
                 exponentiation_lo_hi[0].clone(),
                 exponentiation_lo_hi[1].clone(),
             ],
+            Self::EcRecoverTable {
+                input_rlc,
+                is_valid,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                is_valid.clone(),
+                output_rlc.clone(),
+            ],
+            Self::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::EcTable {
+                op_type,
+                input_rlc,
+                input_len,
+                output_rlc,
+                is_success,
+            } => vec![
+                1.expr(), // is_enabled
+                op_type.clone(),
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+                is_success.clone(),
+            ],
+            Self::ModExpTable {
+                input_rlc,
+                output_rlc,
+                output_len,
+                gas_cost,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                output_rlc.clone(),
+                output_len.clone(),
+                gas_cost.clone(),
+            ],
+            Self::Blake2fTable {
+                input_rlc,
+                output_rlc,
+                gas_cost,
+                is_success,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                output_rlc.clone(),
+                gas_cost.clone(),
+                is_success.clone(),
+            ],
             Self::Conditional(condition, lookup) => lookup
                 .input_exprs()
                 .into_iter()
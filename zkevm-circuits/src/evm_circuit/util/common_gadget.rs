@@ -3,7 +3,7 @@ use super::{
     from_bytes,
     math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget},
     memory_gadget::{MemoryAddressGadget, MemoryExpansionGadget},
-    CachedRegion,
+    CachedRegion, StepRws,
 };
 use crate::{
     evm_circuit::{
@@ -23,7 +23,8 @@ use crate::{
     util::Expr,
     witness::{Block, Call, ExecStep},
 };
-use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256};
+use bus_mapping::state_db::CodeDB;
+use eth_types::{evm_types::GasCost, Address, Field, ToLittleEndian, ToScalar, ToWord, U256};
 use gadgets::util::{select, sum};
 use halo2_proofs::{
     circuit::Value,
@@ -482,11 +483,15 @@ impl<F: Field> TransferWithGasFeeGadget<F> {
 /// The TransferGadget handles a transfer of value from sender to receiver.  The
 /// transfer is only performed if the value is not zero.  If the transfer is
 /// performed and the receiver account doesn't exist, it will be created by
-/// setting it's code_hash = EMPTY_HASH. This gadget is used in callop.
+/// setting it's code_hash = EMPTY_HASH. The receiver account is also created
+/// when `must_create` is set, which is the case for contract creation. This
+/// gadget is used in callop and create.
 #[derive(Clone, Debug)]
 pub(crate) struct TransferGadget<F> {
     sender: UpdateBalanceGadget<F, 2, false>,
     receiver: UpdateBalanceGadget<F, 2, true>,
+    receiver_exists: Expression<F>,
+    must_create: Expression<F>,
     pub(crate) value_is_zero: IsZeroGadget<F>,
 }
 
@@ -496,13 +501,17 @@ impl<F: Field> TransferGadget<F> {
         sender_address: Expression<F>,
         receiver_address: Expression<F>,
         receiver_exists: Expression<F>,
+        must_create: Expression<F>,
         value: Word<F>,
         reversion_info: &mut ReversionInfo<F>,
     ) -> Self {
         let value_is_zero = IsZeroGadget::construct(cb, value.expr());
         // If receiver doesn't exist, create it
         cb.condition(
-            not::expr(value_is_zero.expr()) * not::expr(receiver_exists),
+            or::expr([
+                not::expr(value_is_zero.expr()) * not::expr(receiver_exists.clone()),
+                must_create.clone(),
+            ]),
             |cb| {
                 cb.account_write(
                     receiver_address.clone(),
@@ -533,10 +542,29 @@ impl<F: Field> TransferGadget<F> {
         Self {
             sender,
             receiver,
+            receiver_exists,
+            must_create,
             value_is_zero,
         }
     }
 
+    pub(crate) fn reversible_w_delta(&self) -> Expression<F> {
+        // +1 Write Account (receiver) CodeHash (account creation via code_hash update)
+        let create_receiver = or::expr([
+            not::expr(self.value_is_zero.expr()) * not::expr(self.receiver_exists.clone()),
+            self.must_create.clone(),
+        ]);
+        // +1 Write Account (sender) Balance
+        // +1 Write Account (receiver) Balance
+        let transfer = not::expr(self.value_is_zero.expr()) * 2.expr();
+        create_receiver + transfer
+    }
+
+    pub(crate) fn rw_delta(&self) -> Expression<F> {
+        // All writes of the transfer are reversible.
+        self.reversible_w_delta()
+    }
+
     pub(crate) fn sender(&self) -> &UpdateBalanceGadget<F, 2, false> {
         &self.sender
     }
@@ -792,6 +820,111 @@ impl<F: Field, const IS_SUCCESS_CALL: bool> CommonCallGadget<F, IS_SUCCESS_CALL>
     }
 }
 
+/// Reads the fields of an account needed to decide whether it is empty as in
+/// EIP-161, i.e. nonce == 0, balance == 0 and code_hash == EMPTY_CODE_HASH.
+/// Non-existing accounts have code_hash == 0 in the rw_table, in which case
+/// only the code hash is read.
+#[derive(Clone, Debug)]
+pub(crate) struct EmptyAccountGadget<F> {
+    code_hash: Cell<F>,
+    nonce: Cell<F>,
+    balance: Cell<F>,
+    not_exists: IsZeroGadget<F>,
+    nonce_is_zero: IsZeroGadget<F>,
+    balance_is_zero: IsZeroGadget<F>,
+    is_empty_code_hash: IsEqualGadget<F>,
+}
+
+impl<F: Field> EmptyAccountGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, address: Expression<F>) -> Self {
+        let code_hash = cb.query_cell_phase2();
+        cb.account_read(address.expr(), AccountFieldTag::CodeHash, code_hash.expr());
+        let not_exists = IsZeroGadget::construct(cb, code_hash.expr());
+
+        let nonce = cb.query_cell();
+        let balance = cb.query_cell_phase2();
+        cb.condition(not::expr(not_exists.expr()), |cb| {
+            cb.account_read(address.expr(), AccountFieldTag::Nonce, nonce.expr());
+            cb.account_read(address, AccountFieldTag::Balance, balance.expr());
+        });
+        let nonce_is_zero = IsZeroGadget::construct(cb, nonce.expr());
+        let balance_is_zero = IsZeroGadget::construct(cb, balance.expr());
+        let is_empty_code_hash =
+            IsEqualGadget::construct(cb, code_hash.expr(), cb.empty_code_hash_rlc());
+
+        Self {
+            code_hash,
+            nonce,
+            balance,
+            not_exists,
+            nonce_is_zero,
+            balance_is_zero,
+            is_empty_code_hash,
+        }
+    }
+
+    pub(crate) fn not_exists(&self) -> Expression<F> {
+        self.not_exists.expr()
+    }
+
+    pub(crate) fn is_empty(&self) -> Expression<F> {
+        or::expr([
+            self.not_exists.expr(),
+            self.nonce_is_zero.expr()
+                * self.balance_is_zero.expr()
+                * self.is_empty_code_hash.expr(),
+        ])
+    }
+
+    /// Number of rw lookups done by the gadget.
+    pub(crate) fn rw_delta(&self) -> Expression<F> {
+        1.expr() + 2.expr() * not::expr(self.not_exists.expr())
+    }
+
+    /// Assigns the gadget from the account reads starting at `rws`, returning
+    /// whether the account is empty.
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        rws: &mut StepRws<'_>,
+    ) -> Result<bool, Error> {
+        let (code_hash, _) = rws.next().account_value_pair();
+        let (nonce, balance) = if code_hash.is_zero() {
+            (U256::zero(), U256::zero())
+        } else {
+            (
+                rws.next().account_value_pair().0,
+                rws.next().account_value_pair().0,
+            )
+        };
+
+        let code_hash_rlc = region.word_rlc(code_hash);
+        let balance_rlc = region.word_rlc(balance);
+        self.code_hash.assign(region, offset, code_hash_rlc)?;
+        self.nonce
+            .assign(region, offset, Value::known(F::from(nonce.as_u64())))?;
+        self.balance.assign(region, offset, balance_rlc)?;
+        self.not_exists
+            .assign_value(region, offset, code_hash_rlc)?;
+        self.nonce_is_zero
+            .assign(region, offset, F::from(nonce.as_u64()))?;
+        self.balance_is_zero
+            .assign_value(region, offset, balance_rlc)?;
+        self.is_empty_code_hash.assign_value(
+            region,
+            offset,
+            code_hash_rlc,
+            region.empty_code_hash_rlc(),
+        )?;
+
+        Ok(code_hash.is_zero()
+            || (nonce.is_zero()
+                && balance.is_zero()
+                && code_hash == CodeDB::empty_code_hash().to_word()))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct SloadGasGadget<F> {
     is_warm: Expression<F>,
@@ -1155,3 +1288,78 @@ impl<F: Field, const VALID_BYTES: usize> WordByteRangeGadget<F, VALID_BYTES> {
         self.not_overflow.expr()
     }
 }
+
+/// Check whether an address is one of the precompiled contracts (`0x01` to
+/// `0x09`), and constrain the transition into the matching precompile
+/// execution state.
+#[derive(Clone, Debug)]
+pub(crate) struct IsPrecompileGadget<F> {
+    address_is_zero: IsZeroGadget<F>,
+    address_lt_max: LtGadget<F, N_BYTES_ACCOUNT_ADDRESS>,
+}
+
+impl<F: Field> IsPrecompileGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, address: Expression<F>) -> Self {
+        let address_is_zero = IsZeroGadget::construct(cb, address.clone());
+        let address_lt_max = LtGadget::construct(
+            cb,
+            address,
+            (ExecutionState::precompiles().count() + 1).expr(),
+        );
+
+        Self {
+            address_is_zero,
+            address_lt_max,
+        }
+    }
+
+    pub(crate) fn expr(&self) -> Expression<F> {
+        not::expr(self.address_is_zero.expr()) * self.address_lt_max.expr()
+    }
+
+    /// Constrain the next step to be the execution state of the precompile at
+    /// `address` when `enter` is true, and not a precompile one otherwise.
+    pub(crate) fn constrain_next_state(
+        cb: &mut EVMConstraintBuilder<F>,
+        address: Expression<F>,
+        enter: Expression<F>,
+    ) {
+        cb.require_equal(
+            "Next step is a precompile call iff entering a precompile",
+            cb.next
+                .execution_state_selector(ExecutionState::precompiles()),
+            enter.expr(),
+        );
+        cb.condition(enter, |cb| {
+            let next_address = sum::expr(ExecutionState::precompiles().map(|state| {
+                u64::from(state.precompile().unwrap()).expr()
+                    * cb.next.execution_state_selector([state])
+            }));
+            cb.require_equal(
+                "Next precompile execution state matches the callee address",
+                next_address,
+                address,
+            );
+        });
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        address: Address,
+    ) -> Result<(), Error> {
+        let address = address
+            .to_scalar()
+            .expect("unexpected Address -> Scalar conversion failure");
+        self.address_is_zero.assign(region, offset, address)?;
+        self.address_lt_max.assign(
+            region,
+            offset,
+            address,
+            F::from(ExecutionState::precompiles().count() as u64 + 1),
+        )?;
+
+        Ok(())
+    }
+}
@@ -6,8 +6,8 @@ use crate::{
         util::{Cell, RandomLinearCombination, Word},
     },
     table::{
-        AccountFieldTag, BytecodeFieldTag, CallContextFieldTag, RwTableTag, TxContextFieldTag,
-        TxLogFieldTag, TxReceiptFieldTag,
+        AccountFieldTag, AccountStorageFieldTag, BytecodeFieldTag, CallContextFieldTag, RwTableTag,
+        TxContextFieldTag, TxLogFieldTag, TxReceiptFieldTag,
     },
     util::{build_tx_log_expression, Challenges, Expr},
 };
@@ -901,6 +901,56 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Tx Destructed Account
+
+    pub(crate) fn tx_destructed_account_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TxDestructedAccount write",
+            RwTableTag::TxDestructedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
+    pub(crate) fn tx_destructed_account_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "TxDestructedAccount read",
+            false.expr(),
+            RwTableTag::TxDestructedAccount,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                0.expr(),
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
     // Account
 
     pub(crate) fn account_read(
@@ -1006,6 +1056,29 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Clears the whole storage of the account, which is never reverted.
+    pub(crate) fn account_storage_clear(
+        &mut self,
+        account_address: Expression<F>,
+        tx_id: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "AccountStorage clear",
+            true.expr(),
+            RwTableTag::AccountStorage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                AccountStorageFieldTag::Clear.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
     // Call context
 
     pub(crate) fn call_context(
@@ -1306,6 +1379,122 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // EcRecover Table
+
+    pub(crate) fn ecrecover_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        is_valid: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ecrecover lookup",
+            Lookup::EcRecoverTable {
+                input_rlc,
+                is_valid,
+                output_rlc,
+            },
+        );
+    }
+
+    // Sha256 Table
+
+    pub(crate) fn sha256_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "sha256 lookup",
+            Lookup::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    // Ripemd160 Table
+
+    pub(crate) fn ripemd160_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ripemd160 lookup",
+            Lookup::Ripemd160Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    // Ec Table
+
+    pub(crate) fn ec_table_lookup(
+        &mut self,
+        op_type: Expression<F>,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+        is_success: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ec lookup",
+            Lookup::EcTable {
+                op_type,
+                input_rlc,
+                input_len,
+                output_rlc,
+                is_success,
+            },
+        );
+    }
+
+    // ModExp Table
+
+    pub(crate) fn modexp_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+        output_len: Expression<F>,
+        gas_cost: Expression<F>,
+    ) {
+        self.add_lookup(
+            "modexp lookup",
+            Lookup::ModExpTable {
+                input_rlc,
+                output_rlc,
+                output_len,
+                gas_cost,
+            },
+        );
+    }
+
+    // Blake2f Table
+
+    pub(crate) fn blake2f_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+        gas_cost: Expression<F>,
+        is_success: Expression<F>,
+    ) {
+        self.add_lookup(
+            "blake2f lookup",
+            Lookup::Blake2fTable {
+                input_rlc,
+                output_rlc,
+                gas_cost,
+                is_success,
+            },
+        );
+    }
+
     // Validation
 
     pub(crate) fn validate_degree(&self, degree: usize, name: &'static str) {
@@ -100,6 +100,24 @@ impl Instrument {
                     CellType::Lookup(Table::Exp) => {
                         report.exp_table = data_entry;
                     }
+                    CellType::Lookup(Table::EcRecover) => {
+                        report.ecrecover_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Sha256) => {
+                        report.sha256_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Ripemd160) => {
+                        report.ripemd160_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Ec) => {
+                        report.ec_table = data_entry;
+                    }
+                    CellType::Lookup(Table::ModExp) => {
+                        report.modexp_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Blake2f) => {
+                        report.blake2f_table = data_entry;
+                    }
                 }
             }
             report_collection.push(report);
@@ -125,6 +143,12 @@ pub(crate) struct ExecStateReport {
     pub(crate) copy_table: StateReportRow,
     pub(crate) keccak_table: StateReportRow,
     pub(crate) exp_table: StateReportRow,
+    pub(crate) ecrecover_table: StateReportRow,
+    pub(crate) sha256_table: StateReportRow,
+    pub(crate) ripemd160_table: StateReportRow,
+    pub(crate) ec_table: StateReportRow,
+    pub(crate) modexp_table: StateReportRow,
+    pub(crate) blake2f_table: StateReportRow,
 }
 
 impl From<ExecutionState> for ExecStateReport {
@@ -163,12 +163,15 @@ impl<F: Field> MemoryAddressGadget<F> {
 /// Calculates the memory size in words required for a memory access at the
 /// specified address.
 /// `memory_word_size = ceil(address/32) = floor((address + 31) / 32)`
+/// The memory word size is range checked to `N_BYTES_MEMORY_WORD_SIZE` bytes.
 #[derive(Clone, Debug)]
-pub(crate) struct MemoryWordSizeGadget<F> {
+pub(crate) struct MemoryWordSizeGadget<F, const N_BYTES_MEMORY_WORD_SIZE: usize> {
     memory_word_size: ConstantDivisionGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
 }
 
-impl<F: Field> MemoryWordSizeGadget<F> {
+impl<F: Field, const N_BYTES_MEMORY_WORD_SIZE: usize>
+    MemoryWordSizeGadget<F, N_BYTES_MEMORY_WORD_SIZE>
+{
     pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, address: Expression<F>) -> Self {
         let memory_word_size = ConstantDivisionGadget::construct(cb, address + 31.expr(), 32);
 
@@ -199,7 +202,7 @@ impl<F: Field> MemoryWordSizeGadget<F> {
 /// memory_word_size / 512)`
 #[derive(Clone, Debug)]
 pub(crate) struct MemoryExpansionGadget<F, const N: usize, const N_BYTES_MEMORY_WORD_SIZE: usize> {
-    memory_word_sizes: [MemoryWordSizeGadget<F>; N],
+    memory_word_sizes: [MemoryWordSizeGadget<F, N_BYTES_MEMORY_WORD_SIZE>; N],
     max_memory_word_sizes: [MinMaxGadget<F, N_BYTES_MEMORY_WORD_SIZE>; N],
     curr_quad_memory_cost: ConstantDivisionGadget<F, N_BYTES_GAS>,
     next_quad_memory_cost: ConstantDivisionGadget<F, N_BYTES_GAS>,
@@ -341,7 +344,7 @@ impl<F: Field, const N: usize, const N_BYTES_MEMORY_WORD_SIZE: usize>
 /// `memory_cost = Gmem * memory_size + floor(memory_size * memory_size / 512)`
 #[derive(Clone, Debug)]
 pub(crate) struct MemoryCopierGasGadget<F, const GAS_COPY: GasCost> {
-    word_size: MemoryWordSizeGadget<F>,
+    word_size: MemoryWordSizeGadget<F, N_BYTES_MEMORY_WORD_SIZE>,
     gas_cost: Expression<F>,
     gas_cost_range_check: RangeCheckGadget<F, N_BYTES_GAS>,
 }
@@ -0,0 +1,1057 @@
+//! The ModExp circuit implementation, which verifies the calls to the modexp
+//! precompiled contract together with their EIP-2565 gas cost, and exposes
+//! them in the modexp table.
+//!
+//! The base, exponent and modulus are supported up to [`MODEXP_SIZE_LIMIT`]
+//! bytes each, and are split into 64 bits limbs.  The exponentiation is done by
+//! square and multiply over all the bits of the exponent, where every modular
+//! multiplication `a * b = q * m + r` with `r < m` is checked over the integers
+//! limb by limb, with range checked carries.  A zero modulus, for which the
+//! output is zero, is replaced by one.
+//!
+//! The input is read from the first [`MODEXP_INPUT_LIMIT`] bytes of the
+//! calldata: the three 32 bytes sizes followed by the data, which is split
+//! into the base, exponent and modulus by the flags of the input data rows.
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::ModExpCircuit as TestModExpCircuit;
+
+use crate::{
+    table::{LookupTable, ModExpTable},
+    tx_circuit::sign_verify::{assign_rlc_le, SignVerifyConfig, Term},
+    util::{Challenges, Expr, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::{
+    circuit_input_builder::PrecompileEvent,
+    precompile::{PrecompileCalls, MODEXP_INPUT_LIMIT, MODEXP_SIZE_LIMIT},
+};
+use eth_types::{
+    evm_types::{gas_utils::modexp_gas_cost, GasCost},
+    Field, Word,
+};
+use gadgets::util::{not, sum};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
+    RangeInstructions, RegionCtx,
+};
+use num::{BigInt, BigUint, One, ToPrimitive, Zero};
+use std::marker::PhantomData;
+
+/// Number of bits of a limb
+const BITS_PER_LIMB: usize = 64;
+/// Number of limbs of the base, exponent and modulus
+const N_LIMBS: usize = MODEXP_SIZE_LIMIT * 8 / BITS_PER_LIMB;
+/// Number of bits of the exponent
+const N_EXP_BITS: usize = MODEXP_SIZE_LIMIT * 8;
+/// Number of bytes of the data following the sizes in the input
+const N_DATA_BYTES: usize = MODEXP_INPUT_LIMIT - 3 * 32;
+/// The carries of the limb by limb multiplication are shifted by this power of
+/// two to be range checked as positive values.
+const CARRY_OFFSET_BITS: usize = 76;
+/// Number of bits of the shifted carries
+const CARRY_BITS: usize = 80;
+
+/// Config for ModExpCircuit
+#[derive(Clone, Debug)]
+pub struct ModExpCircuitConfig<F: Field> {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    // RLC
+    q_rlc: Selector,
+    rlc: Column<Advice>,
+    // Input data
+    q_data_first: Selector,
+    q_data: Selector,
+    data_byte: Column<Advice>,
+    /// Whether the data byte belongs to the base, exponent, modulus, or is
+    /// ignored
+    data_flags: [Column<Advice>; 4],
+    /// Number of bytes of the base, exponent and modulus up to the row
+    data_counts: [Column<Advice>; 3],
+    /// RLC of the bytes of the base, exponent and modulus up to the row
+    data_rlcs: [Column<Advice>; 3],
+    modexp_table: ModExpTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct ModExpCircuitConfigArgs<F: Field> {
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig<F> {
+    type ConfigArgs = ModExpCircuitConfigArgs<F>;
+
+    /// Return a new ModExpCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            modexp_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(meta, &main_gate_config, vec![8], vec![]);
+
+        let q_rlc = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        SignVerifyConfig::configure_rlc(
+            meta,
+            "modexp_rlc",
+            main_gate_config.clone(),
+            q_rlc,
+            rlc,
+            challenges.keccak_input(),
+        );
+
+        let q_data_first = meta.selector();
+        let q_data = meta.selector();
+        let data_byte = meta.advice_column();
+        let data_flags = [(); 4].map(|_| meta.advice_column());
+        let data_counts = [(); 3].map(|_| meta.advice_column());
+        let data_rlcs = [(); 3].map(|_| meta.advice_column_in(SecondPhase));
+        for column in std::iter::once(data_byte)
+            .chain(data_counts)
+            .chain(data_rlcs)
+        {
+            meta.enable_equality(column);
+        }
+
+        // Layout of the data of an input, one byte per row, where the flags
+        // select the base, exponent, modulus or ignored bytes in this order,
+        // which is checked by the non decreasing segment index.  The counts
+        // and RLCs of the last row are copied from the sizes and the RLCs of
+        // the numbers, so that the bytes of the numbers are the ones of the
+        // data, left padded with zeros.
+        meta.create_gate("modexp input data", |meta| {
+            let q_data_first = meta.query_selector(q_data_first);
+            let q_data = meta.query_selector(q_data);
+            let q_row = q_data_first.clone() + q_data.clone();
+            let byte = meta.query_advice(data_byte, Rotation::cur());
+            let [flags, flags_prev] = [Rotation::cur(), Rotation::prev()]
+                .map(|rotation| data_flags.map(|column| meta.query_advice(column, rotation)));
+            let segment = |flags: &[Expression<F>; 4]| {
+                sum::expr(
+                    flags
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, flag)| idx.expr() * flag.clone()),
+                )
+            };
+            let segment_diff = segment(&flags) - segment(&flags_prev);
+
+            let mut constraints = Vec::new();
+            for flag in flags.iter() {
+                constraints.push(q_row.clone() * flag.clone() * not::expr(flag.clone()));
+            }
+            constraints.push(q_row * (sum::expr(flags.iter()) - 1.expr()));
+            constraints.push(
+                q_data.clone()
+                    * (0..4)
+                        .map(|diff| segment_diff.clone() - diff.expr())
+                        .reduce(|acc, factor| acc * factor)
+                        .unwrap(),
+            );
+            for idx in 0..3 {
+                let flag = flags[idx].clone();
+                let [count, count_prev] = [Rotation::cur(), Rotation::prev()]
+                    .map(|rotation| meta.query_advice(data_counts[idx], rotation));
+                let [rlc, rlc_prev] = [Rotation::cur(), Rotation::prev()]
+                    .map(|rotation| meta.query_advice(data_rlcs[idx], rotation));
+                constraints.push(q_data_first.clone() * (count.clone() - flag.clone()));
+                constraints
+                    .push(q_data_first.clone() * (rlc.clone() - flag.clone() * byte.clone()));
+                constraints.push(q_data.clone() * (count - count_prev - flag.clone()));
+                constraints.push(
+                    q_data.clone()
+                        * (rlc.clone()
+                            - rlc_prev.clone()
+                            - flag
+                                * (rlc_prev * (challenges.keccak_input() - 1.expr())
+                                    + byte.clone())),
+                );
+            }
+            constraints
+        });
+
+        modexp_table.annotate_columns(meta);
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc,
+            rlc,
+            q_data_first,
+            q_data,
+            data_byte,
+            data_flags,
+            data_counts,
+            data_rlcs,
+            modexp_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> ModExpCircuitConfig<F> {
+    /// Load ModExp RangeChip table.
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        RangeChip::<F>::new(self.range_config.clone()).load_table(layouter)
+    }
+}
+
+/// Assigned 64 bits limbs of a number, least significant first, with its
+/// value.
+#[derive(Clone, Debug)]
+struct AssignedNumber<F: Field> {
+    limbs: Vec<AssignedValue<F>>,
+    value: BigUint,
+}
+
+/// Assigned row of the modexp table.
+#[derive(Debug)]
+struct AssignedModExp<F: Field> {
+    is_enabled: AssignedValue<F>,
+    input_rlc: AssignedValue<F>,
+    output_rlc: AssignedValue<F>,
+    output_len: AssignedValue<F>,
+    gas_cost: AssignedValue<F>,
+}
+
+/// Assigned cells of the input of a call that are checked against the rows
+/// of the input data.
+#[derive(Debug)]
+struct AssignedModExpData<F: Field> {
+    bytes: Vec<AssignedValue<F>>,
+    sizes: [usize; 3],
+    assigned_sizes: Vec<AssignedValue<F>>,
+    number_rlcs: Vec<AssignedValue<F>>,
+}
+
+/// Helper structure pass around references to all the chips required for the
+/// modexp verification.
+struct ChipsRef<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+}
+
+/// Return the sizes of the base, exponent and modulus of a modexp input, or
+/// `None` when they are over [`MODEXP_SIZE_LIMIT`].
+fn modexp_sizes(input: &[u8]) -> Option<[usize; 3]> {
+    let sizes = [0, 1, 2].map(|idx| Word::from_big_endian(&input[idx * 32..(idx + 1) * 32]));
+    sizes
+        .iter()
+        .all(|size| *size <= Word::from(MODEXP_SIZE_LIMIT))
+        .then(|| sizes.map(|size| size.as_usize()))
+}
+
+/// Return the 64 bits limbs of a number, least significant first.
+fn limbs_of(value: &BigUint) -> Vec<u64> {
+    let mut limbs = value.to_u64_digits();
+    limbs.resize(N_LIMBS, 0);
+    limbs
+}
+
+/// ModExp Circuit for verifying the calls to the modexp precompiled contract
+#[derive(Clone, Default, Debug)]
+pub struct ModExpCircuit<F: Field> {
+    /// Max number of supported modexp calls
+    pub max_modexp: usize,
+    /// Precompile events of the modexp calls
+    pub events: Vec<PrecompileEvent>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ModExpCircuit<F> {
+    /// Return a new ModExpCircuit
+    pub fn new(max_modexp: usize, events: Vec<PrecompileEvent>) -> Self {
+        Self {
+            max_modexp,
+            events,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(num_modexp: usize) -> usize {
+        // The range chip table has a row per byte value.  A call is made of
+        // `2 * N_EXP_BITS + 1` modular multiplications of about 130 rows each,
+        // so the rows of a call are an upper bound of their estimation.  TODO:
+        // Figure out a way to get these numbers automatically.
+        let rows_range_chip_table = 1 << 8;
+        let rows_modexp = 72000;
+        std::cmp::max(rows_range_chip_table, rows_modexp * num_modexp)
+    }
+
+    /// Assign the limbs of a number, returning also its little endian bytes.
+    fn assign_number(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        value: BigUint,
+    ) -> Result<(AssignedNumber<F>, Vec<AssignedValue<F>>), Error> {
+        let mut limbs = Vec::new();
+        let mut bytes_le = Vec::new();
+        for limb in limbs_of(&value) {
+            let (limb, bytes) =
+                chips
+                    .range_chip
+                    .decompose(ctx, Value::known(F::from(limb)), 8, BITS_PER_LIMB)?;
+            limbs.push(limb);
+            bytes_le.extend(bytes);
+        }
+        Ok((AssignedNumber { limbs, value }, bytes_le))
+    }
+
+    /// Return the sum of the products of the pairs of values.
+    fn sum_of_products<'a>(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        pairs: impl IntoIterator<Item = (&'a AssignedValue<F>, &'a AssignedValue<F>)>,
+    ) -> Result<AssignedValue<F>, Error>
+    where
+        F: 'a,
+    {
+        let mut pairs = pairs.into_iter();
+        let (a, b) = pairs.next().expect("at least one product");
+        let mut sum = main_gate.mul(ctx, a, b)?;
+        for (a, b) in pairs {
+            sum = main_gate.mul_add(ctx, a, b, &sum)?;
+        }
+        Ok(sum)
+    }
+
+    /// Assign the modular multiplication `a * b = q * m + r` with `r < m`,
+    /// where `a < m` and `m` is not zero, and return `r`.
+    fn assign_mul_mod(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        a: &AssignedNumber<F>,
+        b: &AssignedNumber<F>,
+        m: &AssignedNumber<F>,
+    ) -> Result<AssignedNumber<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+        } = chips;
+
+        // As `a < m`, the quotient `q` fits in the limbs.
+        let product = &a.value * &b.value;
+        let (q, _) = self.assign_number(ctx, chips, &product / &m.value)?;
+        let (r, _) = self.assign_number(ctx, chips, &product % &m.value)?;
+
+        let [a_limbs, b_limbs, q_limbs, m_limbs, r_limbs] =
+            [a, b, &q, m, &r].map(|number| limbs_of(&number.value));
+        let limb_base = F::from_u128(1 << BITS_PER_LIMB);
+        let carry_offset = F::from_u128(1 << CARRY_OFFSET_BITS);
+        let mut carry_value = BigInt::zero();
+        let mut carry_prev: Option<AssignedValue<F>> = None;
+        for k in 0..2 * N_LIMBS - 1 {
+            let pairs = (0..N_LIMBS)
+                .filter(|i| k >= *i && k - i < N_LIMBS)
+                .map(|i| (i, k - i))
+                .collect_vec();
+            let lhs = self.sum_of_products(
+                ctx,
+                main_gate,
+                pairs.iter().map(|&(i, j)| (&a.limbs[i], &b.limbs[j])),
+            )?;
+            let rhs = self.sum_of_products(
+                ctx,
+                main_gate,
+                pairs.iter().map(|&(i, j)| (&q.limbs[i], &m.limbs[j])),
+            )?;
+
+            // lhs - rhs - r[k] + carry[k - 1] = carry[k] * 2^64
+            carry_value += pairs
+                .iter()
+                .map(|&(i, j)| {
+                    BigInt::from(a_limbs[i]) * BigInt::from(b_limbs[j])
+                        - BigInt::from(q_limbs[i]) * BigInt::from(m_limbs[j])
+                })
+                .sum::<BigInt>();
+            if k < N_LIMBS {
+                carry_value -= BigInt::from(r_limbs[k]);
+            }
+            let carry = if k < 2 * N_LIMBS - 2 {
+                carry_value >>= BITS_PER_LIMB;
+                let shifted_carry = (&carry_value + (BigInt::one() << CARRY_OFFSET_BITS))
+                    .to_u128()
+                    .expect("carry within range");
+                Some(range_chip.assign(
+                    ctx,
+                    Value::known(F::from_u128(shifted_carry)),
+                    8,
+                    CARRY_BITS,
+                )?)
+            } else {
+                None
+            };
+
+            let mut terms = vec![
+                maingate::Term::Assigned(&lhs, F::one()),
+                maingate::Term::Assigned(&rhs, -F::one()),
+            ];
+            let mut constant = F::zero();
+            if k < N_LIMBS {
+                terms.push(maingate::Term::Assigned(&r.limbs[k], -F::one()));
+            }
+            if let Some(carry_prev) = carry_prev.as_ref() {
+                terms.push(maingate::Term::Assigned(carry_prev, F::one()));
+                constant -= carry_offset;
+            }
+            if let Some(carry) = carry.as_ref() {
+                terms.push(maingate::Term::Assigned(carry, -limb_base));
+                constant += limb_base * carry_offset;
+            }
+            let diff = main_gate.compose(ctx, &terms, constant)?;
+            main_gate.assert_zero(ctx, &diff)?;
+            carry_prev = carry;
+        }
+
+        // r + d + 1 = m, with the borrows of the limb by limb addition.
+        let (d, _) = self.assign_number(ctx, chips, &m.value - &r.value - 1u64)?;
+        let d_limbs = limbs_of(&d.value);
+        let mut borrow_prev: Option<AssignedValue<F>> = None;
+        let mut borrow_value = 1u128;
+        for k in 0..N_LIMBS {
+            borrow_value =
+                (r_limbs[k] as u128 + d_limbs[k] as u128 + borrow_value) >> BITS_PER_LIMB;
+            let borrow = if k < N_LIMBS - 1 {
+                Some(main_gate.assign_bit(ctx, Value::known(F::from_u128(borrow_value)))?)
+            } else {
+                None
+            };
+
+            let mut terms = vec![
+                maingate::Term::Assigned(&r.limbs[k], F::one()),
+                maingate::Term::Assigned(&d.limbs[k], F::one()),
+                maingate::Term::Assigned(&m.limbs[k], -F::one()),
+            ];
+            let mut constant = F::zero();
+            match borrow_prev.as_ref() {
+                Some(borrow_prev) => terms.push(maingate::Term::Assigned(borrow_prev, F::one())),
+                None => constant = F::one(),
+            }
+            if let Some(borrow) = borrow.as_ref() {
+                terms.push(maingate::Term::Assigned(borrow, -limb_base));
+            }
+            let diff = main_gate.compose(ctx, &terms, constant)?;
+            main_gate.assert_zero(ctx, &diff)?;
+            borrow_prev = borrow;
+        }
+
+        Ok(r)
+    }
+
+    /// Assign the EIP-2565 gas cost of a call from the sizes of the base and
+    /// modulus, and the big endian bits of the exponent.
+    fn assign_gas_cost(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        sizes: &[AssignedValue<F>],
+        size_values: [usize; 3],
+        exp_bits_be: &[AssignedValue<F>],
+        exp_value: &BigUint,
+    ) -> Result<AssignedValue<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+        } = chips;
+        let [base_size, _, mod_size] = size_values;
+
+        // The maximum of the base and modulus sizes, with their range checked
+        // difference.
+        let is_base_larger =
+            main_gate.assign_bit(ctx, Value::known(F::from((base_size >= mod_size) as u64)))?;
+        let max_size = main_gate.select(ctx, &sizes[0], &sizes[2], &is_base_larger)?;
+        let min_size = main_gate.select(ctx, &sizes[2], &sizes[0], &is_base_larger)?;
+        let size_diff = range_chip.assign(
+            ctx,
+            Value::known(F::from(base_size.abs_diff(mod_size) as u64)),
+            8,
+            8,
+        )?;
+        let diff = main_gate.compose(
+            ctx,
+            &[
+                maingate::Term::Assigned(&max_size, F::one()),
+                maingate::Term::Assigned(&min_size, -F::one()),
+                maingate::Term::Assigned(&size_diff, -F::one()),
+            ],
+            F::zero(),
+        )?;
+        main_gate.assert_zero(ctx, &diff)?;
+
+        // max_size + 7 = 8 * words + remainder
+        let max_size_value = base_size.max(mod_size);
+        let words = range_chip.assign(
+            ctx,
+            Value::known(F::from(((max_size_value + 7) / 8) as u64)),
+            8,
+            8,
+        )?;
+        let remainder_bits = (0..3)
+            .map(|idx| {
+                main_gate.assign_bit(
+                    ctx,
+                    Value::known(F::from((((max_size_value + 7) % 8) >> idx & 1) as u64)),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let diff = main_gate.compose(
+            ctx,
+            &[
+                maingate::Term::Assigned(&max_size, F::one()),
+                maingate::Term::Assigned(&words, -F::from(8)),
+                maingate::Term::Assigned(&remainder_bits[0], -F::one()),
+                maingate::Term::Assigned(&remainder_bits[1], -F::from(2)),
+                maingate::Term::Assigned(&remainder_bits[2], -F::from(4)),
+            ],
+            F::from(7),
+        )?;
+        main_gate.assert_zero(ctx, &diff)?;
+        let multiplication_complexity = main_gate.mul(ctx, &words, &words)?;
+
+        // The bit length of the exponent is the number of bits from its most
+        // significant one, and the iteration count is the bit length minus
+        // one, but at least one.
+        let mut is_seen = main_gate.assign_constant(ctx, F::zero())?;
+        let mut bit_len = main_gate.assign_constant(ctx, F::zero())?;
+        let mut is_bit_len_over_2 = None;
+        for (idx, bit) in exp_bits_be.iter().enumerate() {
+            is_seen = main_gate.or(ctx, &is_seen, bit)?;
+            bit_len = main_gate.add(ctx, &bit_len, &is_seen)?;
+            if idx == N_EXP_BITS - 3 {
+                is_bit_len_over_2 = Some(is_seen.clone());
+            }
+        }
+        let bit_len_minus_1 = main_gate.compose(
+            ctx,
+            &[maingate::Term::Assigned(&bit_len, F::one())],
+            -F::one(),
+        )?;
+        let one = main_gate.assign_constant(ctx, F::one())?;
+        let iteration_count = main_gate.select(
+            ctx,
+            &bit_len_minus_1,
+            &one,
+            &is_bit_len_over_2.expect("exponent of at least 3 bits"),
+        )?;
+
+        // multiplication_complexity * iteration_count = 3 * gas + remainder
+        let iteration_count_value = (exp_value.bits().max(2) - 1) as usize;
+        let product_value = ((max_size_value + 7) / 8).pow(2) * iteration_count_value;
+        let product = main_gate.mul(ctx, &multiplication_complexity, &iteration_count)?;
+        let quotient = range_chip.assign(
+            ctx,
+            Value::known(F::from((product_value / 3) as u64)),
+            8,
+            64,
+        )?;
+        let remainder_bits = (0..2)
+            .map(|idx| {
+                main_gate.assign_bit(
+                    ctx,
+                    Value::known(F::from(((product_value % 3) >> idx & 1) as u64)),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let is_remainder_3 = main_gate.and(ctx, &remainder_bits[0], &remainder_bits[1])?;
+        main_gate.assert_zero(ctx, &is_remainder_3)?;
+        let diff = main_gate.compose(
+            ctx,
+            &[
+                maingate::Term::Assigned(&product, F::one()),
+                maingate::Term::Assigned(&quotient, -F::from(3)),
+                maingate::Term::Assigned(&remainder_bits[0], -F::one()),
+                maingate::Term::Assigned(&remainder_bits[1], -F::from(2)),
+            ],
+            F::zero(),
+        )?;
+        main_gate.assert_zero(ctx, &diff)?;
+
+        // The gas cost is the quotient, but at least the minimum gas cost.
+        let min_gas_cost = GasCost::PRECOMPILE_MODEXP_MIN.as_u64();
+        let quotient_value = (product_value / 3) as u64;
+        let is_over_min = main_gate.assign_bit(
+            ctx,
+            Value::known(F::from((quotient_value >= min_gas_cost) as u64)),
+        )?;
+        let min_gas_cost_assigned = main_gate.assign_constant(ctx, F::from(min_gas_cost))?;
+        let gas_cost = main_gate.select(ctx, &quotient, &min_gas_cost_assigned, &is_over_min)?;
+        let other = main_gate.select(ctx, &min_gas_cost_assigned, &quotient, &is_over_min)?;
+        let gas_cost_diff = range_chip.assign(
+            ctx,
+            Value::known(F::from(quotient_value.abs_diff(min_gas_cost))),
+            8,
+            64,
+        )?;
+        let diff = main_gate.compose(
+            ctx,
+            &[
+                maingate::Term::Assigned(&gas_cost, F::one()),
+                maingate::Term::Assigned(&other, -F::one()),
+                maingate::Term::Assigned(&gas_cost_diff, -F::one()),
+            ],
+            F::zero(),
+        )?;
+        main_gate.assert_zero(ctx, &diff)?;
+
+        Ok(gas_cost)
+    }
+
+    /// Assign a modexp call, whose input is right padded or truncated to
+    /// [`MODEXP_INPUT_LIMIT`].
+    fn assign_modexp(
+        &self,
+        config: &ModExpCircuitConfig<F>,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F>,
+        is_enabled: bool,
+        input: &[u8],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(AssignedModExp<F>, AssignedModExpData<F>), Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+        } = chips;
+        let sizes = modexp_sizes(input).expect("modexp sizes within the limit");
+
+        let input_bytes = input
+            .iter()
+            .map(|byte| range_chip.assign(ctx, Value::known(F::from(*byte as u64)), 8, 8))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The sizes are 32 bytes words that don't exceed the size limit.
+        let mut assigned_sizes = Vec::new();
+        for (idx, size) in sizes.iter().enumerate() {
+            let size_bytes = &input_bytes[idx * 32..(idx + 1) * 32];
+            let high_bytes_sum = main_gate.compose(
+                ctx,
+                &size_bytes[..31]
+                    .iter()
+                    .map(|byte| maingate::Term::Assigned(byte, F::one()))
+                    .collect_vec(),
+                F::zero(),
+            )?;
+            main_gate.assert_zero(ctx, &high_bytes_sum)?;
+            let size_slack = range_chip.assign(
+                ctx,
+                Value::known(F::from((MODEXP_SIZE_LIMIT - size) as u64)),
+                8,
+                8,
+            )?;
+            let diff = main_gate.compose(
+                ctx,
+                &[
+                    maingate::Term::Assigned(&size_bytes[31], F::one()),
+                    maingate::Term::Assigned(&size_slack, F::one()),
+                ],
+                -F::from(MODEXP_SIZE_LIMIT as u64),
+            )?;
+            main_gate.assert_zero(ctx, &diff)?;
+            assigned_sizes.push(size_bytes[31].clone());
+        }
+
+        // The base, exponent and modulus, whose bytes are checked against the
+        // input data by their RLCs.
+        let data = &input[3 * 32..];
+        let mut numbers = Vec::new();
+        let mut number_rlcs = Vec::new();
+        let mut start = 0;
+        for (idx, size) in sizes.iter().enumerate() {
+            let (number, bytes_le) = self.assign_number(
+                ctx,
+                chips,
+                BigUint::from_bytes_be(&data[start..start + size]),
+            )?;
+            number_rlcs.push(assign_rlc_le(
+                ctx,
+                main_gate,
+                config.main_gate_config.advices(),
+                config.rlc,
+                ["modexp_base", "modexp_exp", "modexp_mod"][idx],
+                config.q_rlc,
+                challenges.keccak_input(),
+                bytes_le
+                    .iter()
+                    .map(|byte| Term::assigned(byte.cell(), byte.value().copied())),
+            )?);
+            numbers.push(number);
+            start += size;
+        }
+        let [base, exp, modulus]: [AssignedNumber<F>; 3] = numbers.try_into().unwrap();
+
+        // A zero modulus is replaced by one, so that the output is zero.
+        let modulus_limbs_sum = main_gate.compose(
+            ctx,
+            &modulus
+                .limbs
+                .iter()
+                .map(|limb| maingate::Term::Assigned(limb, F::one()))
+                .collect_vec(),
+            F::zero(),
+        )?;
+        let is_modulus_zero = main_gate.is_zero(ctx, &modulus_limbs_sum)?;
+        let mut modulus_limbs = modulus.limbs.clone();
+        modulus_limbs[0] = main_gate.add(ctx, &modulus_limbs[0], &is_modulus_zero)?;
+        let modulus = AssignedNumber {
+            limbs: modulus_limbs,
+            value: modulus.value.max(BigUint::one()),
+        };
+
+        // Square and multiply from the most significant bit of the exponent,
+        // starting from 1 reduced modulo the modulus.
+        let exp_bits_be = exp
+            .limbs
+            .iter()
+            .rev()
+            .map(|limb| {
+                main_gate
+                    .to_bits(ctx, limb, BITS_PER_LIMB)
+                    .map(|bits| bits.into_iter().rev().collect_vec())
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        let mut one_limbs = vec![main_gate.assign_constant(ctx, F::one())?];
+        for _ in 1..N_LIMBS {
+            one_limbs.push(main_gate.assign_constant(ctx, F::zero())?);
+        }
+        let one = AssignedNumber {
+            limbs: one_limbs,
+            value: BigUint::one(),
+        };
+        let mut acc = self.assign_mul_mod(ctx, chips, &one, &one, &modulus)?;
+        for (idx, bit) in exp_bits_be.iter().enumerate() {
+            let square = self.assign_mul_mod(ctx, chips, &acc, &acc, &modulus)?;
+            let product = self.assign_mul_mod(ctx, chips, &square, &base, &modulus)?;
+            let limbs = square
+                .limbs
+                .iter()
+                .zip_eq(product.limbs.iter())
+                .map(|(square, product)| main_gate.select(ctx, product, square, bit))
+                .collect::<Result<Vec<_>, _>>()?;
+            let is_bit_set = exp.value.bit((N_EXP_BITS - 1 - idx) as u64);
+            acc = AssignedNumber {
+                limbs,
+                value: if is_bit_set {
+                    product.value
+                } else {
+                    square.value
+                },
+            };
+        }
+
+        // The output is the result left padded to the modulus size, whose RLC
+        // is the one of the result bytes.
+        let mut output_bytes_le = Vec::new();
+        for limb in acc.limbs.iter() {
+            let (composed, bytes) =
+                range_chip.decompose(ctx, limb.value().copied(), 8, BITS_PER_LIMB)?;
+            main_gate.assert_equal(ctx, &composed, limb)?;
+            output_bytes_le.extend(bytes);
+        }
+        let output_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            config.main_gate_config.advices(),
+            config.rlc,
+            "modexp_output",
+            config.q_rlc,
+            challenges.keccak_input(),
+            output_bytes_le
+                .iter()
+                .map(|byte| Term::assigned(byte.cell(), byte.value().copied())),
+        )?;
+        let input_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            config.main_gate_config.advices(),
+            config.rlc,
+            "modexp_input",
+            config.q_rlc,
+            challenges.keccak_input(),
+            input_bytes
+                .iter()
+                .rev()
+                .map(|byte| Term::assigned(byte.cell(), byte.value().copied())),
+        )?;
+
+        let gas_cost =
+            self.assign_gas_cost(ctx, chips, &assigned_sizes, sizes, &exp_bits_be, &exp.value)?;
+        let is_enabled = main_gate.assign_bit(ctx, Value::known(F::from(is_enabled as u64)))?;
+
+        Ok((
+            AssignedModExp {
+                is_enabled,
+                input_rlc,
+                output_rlc,
+                output_len: assigned_sizes[2].clone(),
+                gas_cost,
+            },
+            AssignedModExpData {
+                bytes: input_bytes[3 * 32..].to_vec(),
+                sizes,
+                assigned_sizes,
+                number_rlcs,
+            },
+        ))
+    }
+
+    /// Assign the input data rows of the calls, one per byte following the
+    /// sizes.
+    fn assign_modexp_data(
+        &self,
+        config: &ModExpCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_data: &[AssignedModExpData<F>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp input data",
+            |mut region| {
+                for (call_idx, data) in assigned_data.iter().enumerate() {
+                    let [base_size, exp_size, mod_size] = data.sizes;
+                    let mut counts = [0u64; 3];
+                    let mut rlcs = [Value::known(F::zero()); 3];
+                    for (idx, byte) in data.bytes.iter().enumerate() {
+                        let offset = call_idx * N_DATA_BYTES + idx;
+                        if idx == 0 {
+                            config.q_data_first.enable(&mut region, offset)?;
+                        } else {
+                            config.q_data.enable(&mut region, offset)?;
+                        }
+                        let segment = if idx < base_size {
+                            0
+                        } else if idx < base_size + exp_size {
+                            1
+                        } else if idx < base_size + exp_size + mod_size {
+                            2
+                        } else {
+                            3
+                        };
+
+                        byte.copy_advice(
+                            || format!("modexp data byte {}", idx),
+                            &mut region,
+                            config.data_byte,
+                            offset,
+                        )?;
+                        for (flag_idx, &column) in config.data_flags.iter().enumerate() {
+                            region.assign_advice(
+                                || format!("modexp data flag {}", flag_idx),
+                                column,
+                                offset,
+                                || Value::known(F::from((flag_idx == segment) as u64)),
+                            )?;
+                        }
+                        if segment < 3 {
+                            counts[segment] += 1;
+                            rlcs[segment] = rlcs[segment]
+                                .zip(challenges.keccak_input())
+                                .zip(byte.value().copied())
+                                .map(|((rlc, challenge), byte)| rlc * challenge + byte);
+                        }
+                        for number_idx in 0..3 {
+                            let count = region.assign_advice(
+                                || format!("modexp data count {}", number_idx),
+                                config.data_counts[number_idx],
+                                offset,
+                                || Value::known(F::from(counts[number_idx])),
+                            )?;
+                            let rlc = region.assign_advice(
+                                || format!("modexp data rlc {}", number_idx),
+                                config.data_rlcs[number_idx],
+                                offset,
+                                || rlcs[number_idx],
+                            )?;
+                            if idx == N_DATA_BYTES - 1 {
+                                region.constrain_equal(
+                                    count.cell(),
+                                    data.assigned_sizes[number_idx].cell(),
+                                )?;
+                                region.constrain_equal(
+                                    rlc.cell(),
+                                    data.number_rlcs[number_idx].cell(),
+                                )?;
+                            }
+                        }
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_modexp_table(
+        &self,
+        config: &ModExpCircuitConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        assigned_modexps: Vec<AssignedModExp<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp table",
+            |mut region| {
+                let modexp_table_columns =
+                    <ModExpTable as LookupTable<F>>::advice_columns(&config.modexp_table);
+                for &column in modexp_table_columns.iter() {
+                    region.assign_advice(
+                        || "modexp table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                // Copy constraints between the modexp table rows and the calls
+                for (offset, assigned_modexp) in assigned_modexps.iter().enumerate() {
+                    for (&column, assigned) in modexp_table_columns.iter().zip_eq([
+                        &assigned_modexp.is_enabled,
+                        &assigned_modexp.input_rlc,
+                        &assigned_modexp.output_rlc,
+                        &assigned_modexp.output_len,
+                        &assigned_modexp.gas_cost,
+                    ]) {
+                        assigned.copy_advice(
+                            || format!("modexp table row {}", offset + 1),
+                            &mut region,
+                            column,
+                            offset + 1,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Return the precompile events of the modexp calls of the block.
+fn modexp_events<F: Field>(block: &witness::Block<F>) -> Vec<PrecompileEvent> {
+    block
+        .precompile_events
+        .iter()
+        .filter(|event| event.precompile == PrecompileCalls::Modexp)
+        .cloned()
+        .collect()
+}
+
+impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
+    type Config = ModExpCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(block.circuits_params.max_modexp, modexp_events(block))
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(modexp_events(block).len()),
+            Self::min_num_rows(block.circuits_params.max_modexp),
+        )
+    }
+
+    /// Make the assignments to the ModExpCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.events.len() > self.max_modexp {
+            error!(
+                "modexp calls = {} > max_modexp = {}",
+                self.events.len(),
+                self.max_modexp
+            );
+            return Err(Error::Synthesis);
+        }
+        for event in self.events.iter() {
+            // Such calls are rejected by the circuit input builder.
+            let Some([base_size, exp_size, mod_size]) = modexp_sizes(&event.input) else {
+                error!("modexp calls with sizes over {} bytes are not supported", MODEXP_SIZE_LIMIT);
+                return Err(Error::Synthesis);
+            };
+            let exp_start = 3 * 32 + base_size;
+            let exp = Word::from_big_endian(&event.input[exp_start..exp_start + exp_size]);
+            let gas_cost = modexp_gas_cost(base_size as u64, exp_size as u64, mod_size as u64, exp);
+            if gas_cost != event.gas_cost {
+                error!(
+                    "modexp gas cost = {} doesn't match EIP-2565 = {}",
+                    event.gas_cost, gas_cost
+                );
+                return Err(Error::Synthesis);
+            }
+        }
+
+        config.load_aux_tables(layouter)?;
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+        };
+
+        // The calls beyond the number of events up to the maximum are padding
+        // calls on zeros, in disabled rows.
+        let (assigned_modexps, assigned_data): (Vec<_>, Vec<_>) = layouter
+            .assign_region(
+                || "modexp calls",
+                |region| {
+                    let mut assigned = Vec::new();
+                    let mut ctx = RegionCtx::new(region, 0);
+                    for idx in 0..self.max_modexp {
+                        let (is_enabled, input) = match self.events.get(idx) {
+                            Some(event) => (true, event.input.clone()),
+                            None => (false, vec![0; MODEXP_INPUT_LIMIT]),
+                        };
+                        assigned.push(self.assign_modexp(
+                            config, &mut ctx, &chips, is_enabled, &input, challenges,
+                        )?);
+                    }
+                    log::debug!("modexp calls: {} rows", ctx.offset());
+                    Ok(assigned)
+                },
+            )?
+            .into_iter()
+            .unzip();
+
+        self.assign_modexp_data(config, layouter, &assigned_data, challenges)?;
+        self.assign_modexp_table(config, layouter, assigned_modexps)
+    }
+
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![]]
+    }
+}
@@ -0,0 +1,116 @@
+#![allow(unused_imports)]
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn modexp_circuit_unusable_rows() {
+    assert_eq!(
+        ModExpCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, ModExpCircuit::<Fr>>(),
+    )
+}
+
+fn run<F: Field>(
+    events: Vec<PrecompileEvent>,
+    max_modexp: usize,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        ModExpCircuit::<Fr>::unusable_rows() + ModExpCircuit::<Fr>::min_num_rows(max_modexp),
+    );
+    // MainGate instance column
+    let circuit = ModExpCircuit::<F>::new(max_modexp, events);
+
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+/// Returns the event of a modexp call, whose input is truncated to
+/// [`MODEXP_INPUT_LIMIT`].
+fn modexp_event(base: &[u8], exp: &[u8], modulus: &[u8]) -> PrecompileEvent {
+    let mut input = [base.len(), exp.len(), modulus.len()]
+        .iter()
+        .flat_map(|size| {
+            let mut size_be = [0u8; 32];
+            Word::from(*size).to_big_endian(&mut size_be);
+            size_be
+        })
+        .chain(base.iter().chain(exp).chain(modulus).copied())
+        .collect_vec();
+    input.resize(MODEXP_INPUT_LIMIT, 0);
+
+    let modulus_value = BigUint::from_bytes_be(modulus);
+    let result = if modulus_value.is_zero() {
+        BigUint::zero()
+    } else {
+        BigUint::from_bytes_be(base).modpow(&BigUint::from_bytes_be(exp), &modulus_value)
+    };
+    let result_be = result.to_bytes_be();
+    let mut output = vec![0; modulus.len()];
+    if !result.is_zero() {
+        output[modulus.len() - result_be.len()..].copy_from_slice(&result_be);
+    }
+
+    PrecompileEvent {
+        precompile: PrecompileCalls::Modexp,
+        input,
+        output,
+        gas_cost: modexp_gas_cost(
+            base.len() as u64,
+            exp.len() as u64,
+            modulus.len() as u64,
+            Word::from_big_endian(exp),
+        ),
+        is_success: true,
+    }
+}
+
+#[test]
+fn modexp_circuit_small() {
+    let events = vec![modexp_event(&[8], &[9], &[10])];
+
+    assert_eq!(run::<Fr>(events, 1), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_max_size() {
+    let mut modulus = [0xfe; 32];
+    modulus[31] = 0x2f;
+    let events = vec![modexp_event(&[0xff; 32], &[0xff; 32], &modulus)];
+
+    assert_eq!(run::<Fr>(events, 1), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_zero_exponent_and_modulus() {
+    let events = vec![
+        modexp_event(&[3], &[], &[0x01, 0x00]),
+        modexp_event(&[0xca, 0xfe], &[0x10], &[0, 0]),
+    ];
+
+    assert_eq!(run::<Fr>(events, 2), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_padding() {
+    let events = vec![modexp_event(&[2], &[0x01, 0x00], &[0x01, 0x00, 0x01])];
+
+    // The last call is padding.
+    assert_eq!(run::<Fr>(events, 2), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_wrong_gas_cost() {
+    let mut event = modexp_event(&[8], &[9], &[10]);
+    event.gas_cost += 1;
+    let circuit = ModExpCircuit::<Fr>::new(1, vec![event]);
+
+    let k = log2_ceil(ModExpCircuit::<Fr>::unusable_rows() + ModExpCircuit::<Fr>::min_num_rows(1));
+    assert!(MockProver::run(k, &circuit, vec![vec![]]).is_err());
+}
@@ -0,0 +1,2408 @@
+//! The MPT circuit implementation, which proves the updates of the MPT table
+//! against the keccak hashes of the nodes of the state and storage tries.
+//!
+//! An update is proven in the state trie for the account updates, and in the
+//! storage trie of the account and then in the state trie for the storage
+//! updates, each proof taking a segment of rows:
+//! - A key row, with the address or the storage key, followed by
+//! - a nibble row per depth of the path of the hashed key, each followed by
+//!   - an extension child row for the extensions ending at this depth, and
+//!   - a child row per child of the branches at this depth, and finally
+//! - a value row per field of the value of the leaves of the key.
+//!
+//! The nodes are followed through three lanes: the node on the path of the key
+//! in the trie before the update, the one after the update, and the node which
+//! is moved down below a new branch by an insertion or up in place of a branch
+//! by a deletion.  Every lane accumulates the RLP encoding of its nodes, whose
+//! hashes are looked up in the keccak table at their end, and whose child on
+//! the path of the key is the hash of the next node.  The old and new lanes
+//! share their rows until their paths split, from which the value rows prove
+//! that only the key is updated.
+//!
+//! The nodes shorter than 32 bytes, which are embedded in their parent, are
+//! not supported, which is never the case of the hashed keys of the state.
+//! The code hash of an account which doesn't exist is 0 in the MPT table,
+//! while it's the empty code hash in the inserted account.
+
+mod param;
+pub(crate) mod witness;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::MptCircuit as TestMptCircuit;
+
+use self::{
+    param::*,
+    witness::{mpt_rows, rlc_be, value_layout, MptRow, RowKind},
+};
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{KeccakTable, LookupTable, MPTProofType, MptTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::{self, MptUpdateRow, MptUpdates},
+};
+use eth_types::{
+    mpt::{EMPTY_CODE_HASH, EMPTY_TRIE_ROOT},
+    Field,
+};
+use gadgets::util::{not, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::marker::PhantomData;
+
+/// The columns of a lane, or their values at a row
+#[derive(Clone, Copy, Debug)]
+struct LaneColumns<T> {
+    // The node at the depth of the row
+    is_branch: T,
+    is_extension: T,
+    is_leaf: T,
+    // The position of the nibble of the depth in the path of an extension or
+    // a leaf, and whether it's the high nibble of its byte
+    is_first: T,
+    is_last: T,
+    is_high: T,
+    is_single: T,
+    // The hash of the node, and the one of its child on the path of the key
+    hash: T,
+    ptr: T,
+    // The RLC, the length and the power of the randomness of the length of
+    // the RLP encoding of the node without its list header
+    rlp_acc: T,
+    rlp_len: T,
+    rlp_pow: T,
+    num_children: T,
+    // The tag of the next value row and the length of the account fields left
+    next_tag: T,
+    rem_len: T,
+    // The row is in the lane, and ends its node, whose list header is encoded
+    // from its length.
+    in_row: T,
+    is_end: T,
+    len_hi: T,
+    len_lo: T,
+    is_short_header: T,
+    is_long_header: T,
+}
+
+impl LaneColumns<Column<Advice>> {
+    fn new<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_branch: meta.advice_column(),
+            is_extension: meta.advice_column(),
+            is_leaf: meta.advice_column(),
+            is_first: meta.advice_column(),
+            is_last: meta.advice_column(),
+            is_high: meta.advice_column(),
+            is_single: meta.advice_column(),
+            hash: meta.advice_column_in(SecondPhase),
+            ptr: meta.advice_column_in(SecondPhase),
+            rlp_acc: meta.advice_column_in(SecondPhase),
+            rlp_len: meta.advice_column(),
+            rlp_pow: meta.advice_column_in(SecondPhase),
+            num_children: meta.advice_column(),
+            next_tag: meta.advice_column(),
+            rem_len: meta.advice_column(),
+            in_row: meta.advice_column(),
+            is_end: meta.advice_column(),
+            len_hi: meta.advice_column(),
+            len_lo: meta.advice_column(),
+            is_short_header: meta.advice_column(),
+            is_long_header: meta.advice_column(),
+        }
+    }
+}
+
+impl<T> LaneColumns<T> {
+    fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> LaneColumns<U> {
+        LaneColumns {
+            is_branch: f(&self.is_branch),
+            is_extension: f(&self.is_extension),
+            is_leaf: f(&self.is_leaf),
+            is_first: f(&self.is_first),
+            is_last: f(&self.is_last),
+            is_high: f(&self.is_high),
+            is_single: f(&self.is_single),
+            hash: f(&self.hash),
+            ptr: f(&self.ptr),
+            rlp_acc: f(&self.rlp_acc),
+            rlp_len: f(&self.rlp_len),
+            rlp_pow: f(&self.rlp_pow),
+            num_children: f(&self.num_children),
+            next_tag: f(&self.next_tag),
+            rem_len: f(&self.rem_len),
+            in_row: f(&self.in_row),
+            is_end: f(&self.is_end),
+            len_hi: f(&self.len_hi),
+            len_lo: f(&self.len_lo),
+            is_short_header: f(&self.is_short_header),
+            is_long_header: f(&self.is_long_header),
+        }
+    }
+
+    // The flags of the node, which are the same in all the rows of a depth
+    fn flags(&self) -> [&T; 7] {
+        [
+            &self.is_branch,
+            &self.is_extension,
+            &self.is_leaf,
+            &self.is_first,
+            &self.is_last,
+            &self.is_high,
+            &self.is_single,
+        ]
+    }
+}
+
+impl<F: Field> LaneColumns<Expression<F>> {
+    fn is_active(&self) -> Expression<F> {
+        self.is_branch.expr() + self.is_extension.expr() + self.is_leaf.expr()
+    }
+
+    fn is_path(&self) -> Expression<F> {
+        self.is_extension.expr() + self.is_leaf.expr()
+    }
+}
+
+/// The columns of the old and new lanes, which follow the key
+#[derive(Clone, Copy, Debug)]
+struct KeyLaneColumns<T> {
+    // The path of the node diverges from the key at or before the depth.
+    is_diverged: T,
+    diverged_start: T,
+    // The child of the branch on the path of the key is empty.
+    key_child_empty: T,
+}
+
+impl<T> KeyLaneColumns<T> {
+    fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> KeyLaneColumns<U> {
+        KeyLaneColumns {
+            is_diverged: f(&self.is_diverged),
+            diverged_start: f(&self.diverged_start),
+            key_child_empty: f(&self.key_child_empty),
+        }
+    }
+}
+
+/// The advice columns of the circuit, or their values at a row
+#[derive(Clone, Copy, Debug)]
+struct MptColumns<T> {
+    row_kind: [T; NUM_ROW_KINDS],
+    // The update, carried from its first row
+    address: T,
+    storage_key: T,
+    old_root: T,
+    new_root: T,
+    old_value: T,
+    new_value: T,
+    proof_type: [T; NUM_PROOF_TYPES],
+    // The segment, carried from its key row
+    is_second: T,
+    is_storage: T,
+    key_hash: T,
+    segment_old_root: T,
+    segment_new_root: T,
+    segment_old_value: T,
+    segment_new_value: T,
+    old_found: T,
+    new_found: T,
+    same_value: T,
+    value_diff_inv: T,
+    field: [T; NUM_FIELDS],
+    // The depth, carried from its nibble row
+    depth: T,
+    is_last_depth: T,
+    last_depth_inv: T,
+    nibble: T,
+    other_nibble: T,
+    is_odd: T,
+    key_acc: T,
+    is_same: T,
+    diverged_inv: T,
+    lanes: [LaneColumns<T>; NUM_LANES],
+    key_lanes: [KeyLaneColumns<T>; NUM_KEY_LANES],
+    // The key, the hash of a child or a field of a value
+    bytes: [T; NUM_BYTES_PER_WORD],
+    // Child rows
+    index: T,
+    is_dup: T,
+    is_empty: T,
+    is_key_index: T,
+    is_other_index: T,
+    is_last_child: T,
+    key_index_inv: T,
+    other_index_inv: T,
+    last_child_inv: T,
+    // Extension child rows
+    is_dup_extension: T,
+    // Value rows
+    value_tag: [T; NUM_VALUE_TAGS],
+    leading_zeros: [T; NUM_BYTES_PER_WORD],
+    first_byte_inv: T,
+    is_short_value: T,
+    item: T,
+    item_len: T,
+    item_pow: T,
+    value: T,
+    new_value_inv: T,
+}
+
+impl MptColumns<Column<Advice>> {
+    fn new<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            row_kind: array_init::array_init(|_| meta.advice_column()),
+            address: meta.advice_column(),
+            storage_key: meta.advice_column_in(SecondPhase),
+            old_root: meta.advice_column_in(SecondPhase),
+            new_root: meta.advice_column_in(SecondPhase),
+            old_value: meta.advice_column_in(SecondPhase),
+            new_value: meta.advice_column_in(SecondPhase),
+            proof_type: array_init::array_init(|_| meta.advice_column()),
+            is_second: meta.advice_column(),
+            is_storage: meta.advice_column(),
+            key_hash: meta.advice_column_in(SecondPhase),
+            segment_old_root: meta.advice_column_in(SecondPhase),
+            segment_new_root: meta.advice_column_in(SecondPhase),
+            segment_old_value: meta.advice_column_in(SecondPhase),
+            segment_new_value: meta.advice_column_in(SecondPhase),
+            old_found: meta.advice_column(),
+            new_found: meta.advice_column(),
+            same_value: meta.advice_column(),
+            value_diff_inv: meta.advice_column_in(SecondPhase),
+            field: array_init::array_init(|_| meta.advice_column()),
+            depth: meta.advice_column(),
+            is_last_depth: meta.advice_column(),
+            last_depth_inv: meta.advice_column(),
+            nibble: meta.advice_column(),
+            other_nibble: meta.advice_column(),
+            is_odd: meta.advice_column(),
+            key_acc: meta.advice_column_in(SecondPhase),
+            is_same: meta.advice_column(),
+            diverged_inv: meta.advice_column(),
+            lanes: array_init::array_init(|_| LaneColumns::new(meta)),
+            key_lanes: array_init::array_init(|_| KeyLaneColumns {
+                is_diverged: meta.advice_column(),
+                diverged_start: meta.advice_column(),
+                key_child_empty: meta.advice_column(),
+            }),
+            bytes: array_init::array_init(|_| meta.advice_column()),
+            index: meta.advice_column(),
+            is_dup: meta.advice_column(),
+            is_empty: meta.advice_column(),
+            is_key_index: meta.advice_column(),
+            is_other_index: meta.advice_column(),
+            is_last_child: meta.advice_column(),
+            key_index_inv: meta.advice_column(),
+            other_index_inv: meta.advice_column(),
+            last_child_inv: meta.advice_column(),
+            is_dup_extension: meta.advice_column(),
+            value_tag: array_init::array_init(|_| meta.advice_column()),
+            leading_zeros: array_init::array_init(|_| meta.advice_column()),
+            first_byte_inv: meta.advice_column(),
+            is_short_value: meta.advice_column(),
+            item: meta.advice_column_in(SecondPhase),
+            item_len: meta.advice_column(),
+            item_pow: meta.advice_column_in(SecondPhase),
+            value: meta.advice_column_in(SecondPhase),
+            new_value_inv: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    fn query<F: Field>(
+        &self,
+        meta: &mut VirtualCells<F>,
+        rotation: Rotation,
+    ) -> MptColumns<Expression<F>> {
+        self.map(|&column| meta.query_advice(column, rotation))
+    }
+}
+
+impl<T> MptColumns<T> {
+    fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> MptColumns<U> {
+        MptColumns {
+            row_kind: array_init::array_init(|i| f(&self.row_kind[i])),
+            address: f(&self.address),
+            storage_key: f(&self.storage_key),
+            old_root: f(&self.old_root),
+            new_root: f(&self.new_root),
+            old_value: f(&self.old_value),
+            new_value: f(&self.new_value),
+            proof_type: array_init::array_init(|i| f(&self.proof_type[i])),
+            is_second: f(&self.is_second),
+            is_storage: f(&self.is_storage),
+            key_hash: f(&self.key_hash),
+            segment_old_root: f(&self.segment_old_root),
+            segment_new_root: f(&self.segment_new_root),
+            segment_old_value: f(&self.segment_old_value),
+            segment_new_value: f(&self.segment_new_value),
+            old_found: f(&self.old_found),
+            new_found: f(&self.new_found),
+            same_value: f(&self.same_value),
+            value_diff_inv: f(&self.value_diff_inv),
+            field: array_init::array_init(|i| f(&self.field[i])),
+            depth: f(&self.depth),
+            is_last_depth: f(&self.is_last_depth),
+            last_depth_inv: f(&self.last_depth_inv),
+            nibble: f(&self.nibble),
+            other_nibble: f(&self.other_nibble),
+            is_odd: f(&self.is_odd),
+            key_acc: f(&self.key_acc),
+            is_same: f(&self.is_same),
+            diverged_inv: f(&self.diverged_inv),
+            lanes: array_init::array_init(|i| self.lanes[i].map(&mut f)),
+            key_lanes: array_init::array_init(|i| self.key_lanes[i].map(&mut f)),
+            bytes: array_init::array_init(|i| f(&self.bytes[i])),
+            index: f(&self.index),
+            is_dup: f(&self.is_dup),
+            is_empty: f(&self.is_empty),
+            is_key_index: f(&self.is_key_index),
+            is_other_index: f(&self.is_other_index),
+            is_last_child: f(&self.is_last_child),
+            key_index_inv: f(&self.key_index_inv),
+            other_index_inv: f(&self.other_index_inv),
+            last_child_inv: f(&self.last_child_inv),
+            is_dup_extension: f(&self.is_dup_extension),
+            value_tag: array_init::array_init(|i| f(&self.value_tag[i])),
+            leading_zeros: array_init::array_init(|i| f(&self.leading_zeros[i])),
+            first_byte_inv: f(&self.first_byte_inv),
+            is_short_value: f(&self.is_short_value),
+            item: f(&self.item),
+            item_len: f(&self.item_len),
+            item_pow: f(&self.item_pow),
+            value: f(&self.value),
+            new_value_inv: f(&self.new_value_inv),
+        }
+    }
+
+    // The columns carried from the first row of the update
+    fn proof(&self) -> Vec<&T> {
+        [
+            &self.address,
+            &self.storage_key,
+            &self.old_root,
+            &self.new_root,
+            &self.old_value,
+            &self.new_value,
+        ]
+        .into_iter()
+        .chain(self.proof_type.iter())
+        .collect()
+    }
+
+    // The columns carried from the key row of the segment
+    fn segment(&self) -> Vec<&T> {
+        [
+            &self.is_second,
+            &self.is_storage,
+            &self.key_hash,
+            &self.segment_old_root,
+            &self.segment_new_root,
+            &self.segment_old_value,
+            &self.segment_new_value,
+            &self.old_found,
+            &self.new_found,
+            &self.same_value,
+        ]
+        .into_iter()
+        .chain(self.field.iter())
+        .collect()
+    }
+
+    // The columns carried from the nibble row of the depth
+    fn depth(&self) -> Vec<&T> {
+        let mut columns = vec![
+            &self.depth,
+            &self.is_last_depth,
+            &self.nibble,
+            &self.other_nibble,
+            &self.is_odd,
+            &self.key_acc,
+            &self.is_same,
+        ];
+        for lane in self.lanes.iter() {
+            columns.extend(lane.flags());
+            columns.push(&lane.hash);
+        }
+        for lane in self.key_lanes.iter() {
+            columns.extend([&lane.is_diverged, &lane.diverged_start]);
+        }
+        columns
+    }
+}
+
+impl<F: Field> MptColumns<Expression<F>> {
+    fn is_kind(&self, kind: RowKind) -> Expression<F> {
+        self.row_kind[kind as usize].expr()
+    }
+
+    fn value_tag(&self, tag: usize) -> Expression<F> {
+        self.value_tag[tag - 1].expr()
+    }
+
+    fn proof_type(&self, proof_type: MPTProofType) -> Expression<F> {
+        self.proof_type[proof_type as usize - 1].expr()
+    }
+}
+
+/// The RLC of expressions of bytes in big endian order
+fn compose<F: Field>(bytes: &[Expression<F>], base: Expression<F>) -> Expression<F> {
+    bytes
+        .iter()
+        .fold(0.expr(), |acc, byte| acc * base.expr() + byte.expr())
+}
+
+/// MptCircuitConfig
+#[derive(Clone, Debug)]
+pub struct MptCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_last: Column<Fixed>,
+    u8_table: TableColumn,
+    columns: MptColumns<Column<Advice>>,
+    /// The MPT table proven by the circuit
+    pub mpt_table: MptTable,
+    /// The keccak table of the hashes of the nodes and the keys
+    pub keccak_table: KeccakTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct MptCircuitConfigArgs<F: Field> {
+    /// MptTable
+    pub mpt_table: MptTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for MptCircuitConfig<F> {
+    type ConfigArgs = MptCircuitConfigArgs<F>;
+
+    /// Return a new MptCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            mpt_table,
+            keccak_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_last = meta.fixed_column();
+        let u8_table = meta.lookup_table_column();
+        let columns = MptColumns::new(meta);
+
+        let r = challenges.keccak_input();
+        let e = challenges.evm_word();
+        let r_powers = challenges.keccak_powers_of_randomness::<{ NUM_BYTES_PER_WORD + 1 }>();
+        let r_pow = |exponent: usize| r_powers[exponent - 1].expr();
+        let empty_root_rlc = compose(&EMPTY_TRIE_ROOT.0.map(|byte| byte.expr()), e.expr());
+        let empty_code_rlc = compose(&EMPTY_CODE_HASH.0.map(|byte| byte.expr()), e.expr());
+        let query = |meta: &mut VirtualCells<F>| {
+            [Rotation::prev(), Rotation::cur(), Rotation::next()]
+                .map(|rotation| columns.query(meta, rotation))
+        };
+
+        meta.create_gate("mpt rows", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [p, c, n] = query(meta);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let q_last = meta.query_fixed(q_last, Rotation::cur());
+            let table = mpt_table.table_exprs(meta);
+            let is_key = c.is_kind(RowKind::Key);
+            let is_nibble = c.is_kind(RowKind::Nibble);
+            let is_child = c.is_kind(RowKind::Child);
+            let is_extension_child = c.is_kind(RowKind::ExtensionChild);
+            let is_value = c.is_kind(RowKind::Value);
+            let is_padding = c.is_kind(RowKind::Padding);
+
+            for kind in c.row_kind.iter() {
+                cb.require_boolean("row kind is boolean", kind.expr());
+            }
+            cb.require_equal("one row kind", sum::expr(c.row_kind.iter()), 1.expr());
+            cb.condition(q_first, |cb| {
+                cb.require_equal(
+                    "first row is a key row or padding",
+                    is_key.expr() + is_padding.expr(),
+                    1.expr(),
+                );
+                cb.require_zero("first row isn't a second segment", c.is_second.expr());
+            });
+            cb.condition(q_last.expr(), |cb| {
+                cb.require_equal("last row is padding", is_padding.expr(), 1.expr());
+            });
+            cb.condition(is_padding.expr() * not::expr(q_last), |cb| {
+                cb.require_equal(
+                    "padding until the last row",
+                    n.is_kind(RowKind::Padding),
+                    1.expr(),
+                );
+            });
+
+            // The first row of an update is the one in the MPT table.
+            let is_summary = is_key.expr() * not::expr(c.is_second.expr());
+            let proof_type = sum::expr(
+                c.proof_type
+                    .iter()
+                    .enumerate()
+                    .map(|(i, proof_type)| (i + 1).expr() * proof_type.expr()),
+            );
+            for (name, table, value) in [
+                ("table address", &table[0], c.address.expr()),
+                ("table storage key", &table[1], c.storage_key.expr()),
+                ("table proof type", &table[2], proof_type),
+                ("table new root", &table[3], c.new_root.expr()),
+                ("table old root", &table[4], c.old_root.expr()),
+                ("table new value", &table[5], c.new_value.expr()),
+                ("table old value", &table[6], c.old_value.expr()),
+            ] {
+                cb.require_equal(name, table.expr(), is_summary.expr() * value);
+            }
+            cb.condition(is_summary.expr(), |cb| {
+                for proof_type in c.proof_type.iter() {
+                    cb.require_boolean("proof type is boolean", proof_type.expr());
+                }
+                cb.require_equal("one proof type", sum::expr(c.proof_type.iter()), 1.expr());
+            });
+            cb.condition(not::expr(is_summary) * not::expr(is_padding.expr()), |cb| {
+                for (cur, prev) in c.proof().into_iter().zip(p.proof()) {
+                    cb.require_equal("update is carried", cur.expr(), prev.expr());
+                }
+            });
+            cb.condition(
+                is_nibble.expr() + is_child.expr() + is_extension_child.expr() + is_value.expr(),
+                |cb| {
+                    for (cur, prev) in c.segment().into_iter().zip(p.segment()) {
+                        cb.require_equal("segment is carried", cur.expr(), prev.expr());
+                    }
+                },
+            );
+            cb.condition(
+                is_child.expr() + is_extension_child.expr() + is_value.expr(),
+                |cb| {
+                    for (cur, prev) in c.depth().into_iter().zip(p.depth()) {
+                        cb.require_equal("depth is carried", cur.expr(), prev.expr());
+                    }
+                },
+            );
+            cb.condition(is_key.expr() + is_nibble.expr() + is_padding.expr(), |cb| {
+                for lane in c.lanes.iter() {
+                    cb.require_zero("row isn't in the lanes", lane.in_row.expr());
+                }
+            });
+
+            // A depth is followed by the next one until the last one, which
+            // is followed by the value rows if there are leaves.
+            let is_next_depth =
+                not::expr(n.is_kind(RowKind::ExtensionChild) + n.is_kind(RowKind::Child));
+            cb.condition(
+                is_nibble.expr() + is_child.expr() + is_extension_child.expr(),
+                |cb| {
+                    let is_last_depth = c.is_last_depth.expr();
+                    cb.require_zero(
+                        "nibble row follows the depth",
+                        is_next_depth.expr()
+                            * not::expr(is_last_depth.expr())
+                            * not::expr(n.is_kind(RowKind::Nibble)),
+                    );
+                    cb.require_zero(
+                        "next nibble row is at the next depth",
+                        is_next_depth.expr()
+                            * not::expr(is_last_depth.expr())
+                            * (n.depth.expr() - c.depth.expr() - 1.expr()),
+                    );
+                    cb.require_zero(
+                        "value, key or padding row follows the last depth",
+                        is_next_depth.expr()
+                            * is_last_depth.expr()
+                            * not::expr(
+                                n.is_kind(RowKind::Value)
+                                    + n.is_kind(RowKind::Key)
+                                    + n.is_kind(RowKind::Padding),
+                            ),
+                    );
+                    cb.require_zero(
+                        "value rows follow the leaves",
+                        is_next_depth.expr()
+                            * is_last_depth
+                            * sum::expr(c.lanes.iter().map(|lane| lane.is_leaf.expr()))
+                            * not::expr(n.is_kind(RowKind::Value)),
+                    );
+                },
+            );
+
+            // The nodes end at the last child of a branch, at the child of an
+            // extension, and at the last field of a leaf.
+            let is_node_end = is_child.expr() * c.is_last_child.expr()
+                + is_extension_child.expr()
+                + is_value.expr() * (c.value_tag(CODE_HASH) + c.value_tag(STORAGE_VALUE));
+            for lane in c.lanes.iter() {
+                cb.require_equal(
+                    "node end",
+                    lane.is_end.expr(),
+                    lane.in_row.expr() * is_node_end.expr(),
+                );
+                cb.condition(lane.is_end.expr(), |cb| {
+                    cb.require_boolean("short header is boolean", lane.is_short_header.expr());
+                    cb.require_boolean("long header is boolean", lane.is_long_header.expr());
+                    cb.require_zero(
+                        "header is short or long",
+                        lane.is_short_header.expr() * lane.is_long_header.expr(),
+                    );
+                    cb.require_equal(
+                        "length from its bytes",
+                        lane.rlp_len.expr(),
+                        lane.len_hi.expr() * 256.expr() + lane.len_lo.expr(),
+                    );
+                    cb.require_zero(
+                        "length of one byte",
+                        not::expr(lane.is_long_header.expr()) * lane.len_hi.expr(),
+                    );
+                    cb.require_zero(
+                        "length of two bytes",
+                        lane.is_long_header.expr()
+                            * (lane.len_hi.expr() - 1.expr())
+                            * (lane.len_hi.expr() - 2.expr()),
+                    );
+                });
+            }
+
+            // The last row of a segment proves whether the key is found, and
+            // which values it has if it isn't.
+            let is_segment_end =
+                not::expr(is_padding) * (n.is_kind(RowKind::Key) + n.is_kind(RowKind::Padding));
+            cb.condition(is_segment_end, |cb| {
+                for (lane, found, value) in [
+                    (OLD, &c.old_found, &c.segment_old_value),
+                    (NEW, &c.new_found, &c.segment_new_value),
+                ] {
+                    cb.require_equal(
+                        "key is found in a leaf which doesn't diverge",
+                        found.expr(),
+                        c.lanes[lane].is_leaf.expr()
+                            * not::expr(c.key_lanes[lane].is_diverged.expr()),
+                    );
+                    cb.require_zero(
+                        "value of a key which isn't found",
+                        not::expr(found.expr())
+                            * (value.expr() - c.field[STORAGE_ROOT].expr() * empty_root_rlc.expr()),
+                    );
+                }
+                let num_found = c.old_found.expr() + c.new_found.expr();
+                cb.require_zero(
+                    "key is found once after a split",
+                    not::expr(c.is_same.expr()) * (num_found.expr() - 1.expr()),
+                );
+                cb.require_zero(
+                    "non existing account isn't found",
+                    c.field[NO_FIELD].expr() * num_found.expr(),
+                );
+                cb.require_zero(
+                    "non existing storage isn't found",
+                    c.is_storage.expr()
+                        * c.proof_type(MPTProofType::NonExistingStorageProof)
+                        * num_found,
+                );
+                cb.require_zero(
+                    "account is only deleted by a code hash update",
+                    c.old_found.expr()
+                        * not::expr(c.new_found.expr())
+                        * (c.field[NONCE].expr()
+                            + c.field[BALANCE].expr()
+                            + c.field[STORAGE_ROOT].expr()),
+                );
+                cb.require_zero(
+                    "storage clear doesn't insert an account",
+                    c.proof_type(MPTProofType::StorageClear)
+                        * c.new_found.expr()
+                        * not::expr(c.old_found.expr()),
+                );
+                for lane in c.lanes.iter() {
+                    cb.require_equal(
+                        "values end with the code hash or the storage value",
+                        lane.next_tag.expr(),
+                        1.expr()
+                            + 4.expr() * c.is_storage.expr()
+                            + lane.is_leaf.expr() * (4.expr() - 3.expr() * c.is_storage.expr()),
+                    );
+                }
+                cb.require_zero(
+                    "storage segment is followed by its account segment",
+                    c.is_storage.expr() * not::expr(n.is_kind(RowKind::Key) * n.is_second.expr()),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("mpt key rows", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [p, c, n] = query(meta);
+            let is_second = c.is_second.expr();
+            let is_first = not::expr(is_second.expr());
+            let is_storage = c.is_storage.expr();
+            let is_account = not::expr(is_storage.expr());
+            let is_clear = c.proof_type(MPTProofType::StorageClear);
+
+            // The first segment proves the update, and the second one the
+            // update of the storage root of a storage update.  A storage clear
+            // is a first segment updating the storage root to the empty root.
+            cb.require_boolean("second segment is boolean", is_second.expr());
+            for (field, expected) in [
+                (
+                    NO_FIELD,
+                    is_first.expr() * c.proof_type(MPTProofType::NonExistingAccountProof),
+                ),
+                (
+                    NONCE,
+                    is_first.expr() * c.proof_type(MPTProofType::NonceMod),
+                ),
+                (
+                    BALANCE,
+                    is_first.expr() * c.proof_type(MPTProofType::BalanceMod),
+                ),
+                (
+                    STORAGE_ROOT,
+                    is_second.expr() + is_first.expr() * is_clear.expr(),
+                ),
+                (
+                    CODE_HASH,
+                    is_first.expr() * c.proof_type(MPTProofType::CodeHashMod),
+                ),
+                (
+                    STORAGE_VALUE,
+                    is_first.expr()
+                        * (c.proof_type(MPTProofType::StorageMod)
+                            + c.proof_type(MPTProofType::NonExistingStorageProof)),
+                ),
+            ] {
+                cb.require_equal("segment field", c.field[field].expr(), expected);
+            }
+            cb.require_equal(
+                "storage segment",
+                is_storage.expr(),
+                c.field[STORAGE_VALUE].expr(),
+            );
+            cb.require_zero(
+                "second segment follows a storage segment",
+                is_second.expr() * not::expr(p.is_storage.expr()),
+            );
+            cb.condition(not::expr(is_clear.expr()), |cb| {
+                cb.require_equal(
+                    "segment old value",
+                    c.segment_old_value.expr(),
+                    is_first.expr() * c.old_value.expr()
+                        + is_second.expr() * p.segment_old_root.expr(),
+                );
+                cb.require_equal(
+                    "segment new value",
+                    c.segment_new_value.expr(),
+                    is_first.expr() * c.new_value.expr()
+                        + is_second.expr() * p.segment_new_root.expr(),
+                );
+            });
+            // The old storage root of a storage clear is the one in the leaf,
+            // or the empty root if the account doesn't exist, while its values
+            // in the table are 0.
+            cb.condition(is_clear, |cb| {
+                cb.require_equal(
+                    "storage clear new value",
+                    c.segment_new_value.expr(),
+                    empty_root_rlc.expr(),
+                );
+                cb.require_zero("storage clear old table value", c.old_value.expr());
+                cb.require_zero("storage clear new table value", c.new_value.expr());
+            });
+            cb.require_zero(
+                "account segment old root",
+                is_account.expr() * (c.segment_old_root.expr() - c.old_root.expr()),
+            );
+            cb.require_zero(
+                "account segment new root",
+                is_account.expr() * (c.segment_new_root.expr() - c.new_root.expr()),
+            );
+
+            // The key is the storage key or the address.
+            cb.require_zero(
+                "account update has no storage key",
+                is_first.expr() * is_account.expr() * c.storage_key.expr(),
+            );
+            cb.require_zero(
+                "storage key from its bytes",
+                is_storage.expr() * (compose(&c.bytes, e.expr()) - c.storage_key.expr()),
+            );
+            let address_start = NUM_BYTES_PER_WORD - NUM_BYTES_PER_ADDRESS;
+            for byte in c.bytes[..address_start].iter() {
+                cb.require_zero("address has 20 bytes", is_account.expr() * byte.expr());
+            }
+            cb.require_zero(
+                "address from its bytes",
+                is_account.expr()
+                    * (compose(&c.bytes[address_start..], 256.expr()) - c.address.expr()),
+            );
+
+            // The old and new lanes start at the roots of the tries.
+            for (i, lane) in c.lanes.iter().enumerate() {
+                // The first flag is the branch.
+                for (j, flag) in lane.flags().into_iter().enumerate() {
+                    let is_root_branch = i != SIBLING && j == 0;
+                    cb.require_equal("key row lane flags", flag.expr(), is_root_branch.expr());
+                }
+                cb.require_equal(
+                    "values start with the nonce or the storage value",
+                    lane.next_tag.expr(),
+                    1.expr() + 4.expr() * is_storage.expr(),
+                );
+            }
+            for lane in c.key_lanes.iter() {
+                cb.require_zero("key row isn't diverged", lane.is_diverged.expr());
+                cb.require_zero("key row doesn't diverge", lane.diverged_start.expr());
+            }
+            cb.require_equal("lanes start the same", c.is_same.expr(), 1.expr());
+            for (lane, root, found) in [
+                (OLD, &c.segment_old_root, &c.old_found),
+                (NEW, &c.segment_new_root, &c.new_found),
+            ] {
+                let is_empty = c.key_lanes[lane].key_child_empty.expr();
+                cb.require_boolean("empty trie is boolean", is_empty.expr());
+                cb.require_zero(
+                    "empty trie has the empty root",
+                    is_empty * (root.expr() - empty_root_rlc.expr()),
+                );
+                cb.require_equal(
+                    "root is the first node",
+                    c.lanes[lane].ptr.expr(),
+                    root.expr(),
+                );
+                cb.require_boolean("found is boolean", found.expr());
+            }
+
+            let value_diff = c.segment_new_value.expr() - c.segment_old_value.expr();
+            cb.require_boolean("same value is boolean", c.same_value.expr());
+            cb.require_equal(
+                "value changes unless it's the same",
+                value_diff.expr() * c.value_diff_inv.expr(),
+                not::expr(c.same_value.expr()),
+            );
+            cb.require_zero("same value", c.same_value.expr() * value_diff);
+            cb.require_zero(
+                "same value has the same root",
+                c.same_value.expr() * (c.segment_new_root.expr() - c.segment_old_root.expr()),
+            );
+            cb.require_equal(
+                "key row is followed by a nibble row",
+                n.is_kind(RowKind::Nibble),
+                1.expr(),
+            );
+            cb.require_zero("first depth", n.depth.expr());
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()) * c.is_kind(RowKind::Key))
+        });
+
+        meta.create_gate("mpt nibble rows", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [p, c, n] = query(meta);
+            let is_last_depth = c.is_last_depth.expr();
+            let nibble = c.nibble.expr();
+            let other_nibble = c.other_nibble.expr();
+            let is_same = c.is_same.expr();
+            let last_depth = (NUM_NIBBLES - 1).expr();
+
+            // The key is the RLC of its nibbles, at the last depth.
+            cb.require_boolean("last depth is boolean", is_last_depth.expr());
+            cb.require_zero(
+                "last depth",
+                is_last_depth.expr() * (c.depth.expr() - last_depth.expr()),
+            );
+            cb.require_zero(
+                "not the last depth",
+                not::expr(is_last_depth.expr())
+                    * ((c.depth.expr() - last_depth) * c.last_depth_inv.expr() - 1.expr()),
+            );
+            let is_first_depth = p.is_kind(RowKind::Key);
+            cb.require_equal(
+                "odd depth",
+                c.is_odd.expr(),
+                not::expr(is_first_depth.expr()) * not::expr(p.is_odd.expr()),
+            );
+            let key_acc = not::expr(is_first_depth) * p.key_acc.expr();
+            cb.require_equal(
+                "key accumulator",
+                c.key_acc.expr(),
+                c.is_odd.expr() * (key_acc.expr() + nibble.expr())
+                    + not::expr(c.is_odd.expr()) * (key_acc * e.expr() + 16.expr() * nibble.expr()),
+            );
+            cb.require_zero(
+                "key hash from its nibbles",
+                is_last_depth.expr() * (c.key_acc.expr() - c.key_hash.expr()),
+            );
+            cb.require_boolean("same lanes is boolean", is_same.expr());
+            cb.require_zero(
+                "lanes stay split",
+                is_same.expr() * not::expr(p.is_same.expr()),
+            );
+
+            for (i, (cur, prev)) in c.lanes.iter().zip(p.lanes.iter()).enumerate() {
+                for flag in [
+                    &cur.is_branch,
+                    &cur.is_extension,
+                    &cur.is_leaf,
+                    &cur.is_first,
+                    &cur.is_last,
+                    &cur.is_high,
+                ] {
+                    cb.require_boolean("lane flag is boolean", flag.expr());
+                }
+                cb.require_boolean("one node kind", cur.is_active());
+                let is_path = cur.is_path();
+                for flag in [&cur.is_first, &cur.is_last, &cur.is_high] {
+                    cb.require_zero(
+                        "path flag of an extension or a leaf",
+                        flag.expr() * not::expr(is_path.expr()),
+                    );
+                }
+                cb.require_equal(
+                    "single nibble path",
+                    cur.is_single.expr(),
+                    cur.is_first.expr() * cur.is_last.expr(),
+                );
+                cb.require_zero(
+                    "leaves at the last depth",
+                    is_last_depth.expr() * (cur.is_branch.expr() + cur.is_extension.expr()),
+                );
+                cb.require_zero(
+                    "leaf ends at the last depth",
+                    cur.is_leaf.expr() * (cur.is_last.expr() - is_last_depth.expr()),
+                );
+                cb.require_zero(
+                    "last nibble is low",
+                    cur.is_last.expr() * cur.is_high.expr(),
+                );
+                let is_start = cur.is_branch.expr() + cur.is_first.expr();
+                cb.require_equal(
+                    "node hash is the child of the node before",
+                    cur.hash.expr(),
+                    is_start.expr() * prev.ptr.expr()
+                        + not::expr(is_start.expr()) * prev.hash.expr(),
+                );
+                cb.require_equal("child is carried", cur.ptr.expr(), prev.ptr.expr());
+                cb.require_equal(
+                    "next tag is carried",
+                    cur.next_tag.expr(),
+                    prev.next_tag.expr(),
+                );
+
+                let nibble = if i == SIBLING {
+                    other_nibble.expr()
+                } else {
+                    let cur_key = &c.key_lanes[i];
+                    let prev_key = &p.key_lanes[i];
+                    let is_diverged = cur_key.is_diverged.expr();
+                    cb.require_boolean("diverged is boolean", is_diverged.expr());
+                    cb.require_zero(
+                        "diverged path of an extension or a leaf",
+                        is_diverged.expr() * not::expr(is_path.expr()),
+                    );
+                    // Transitions of the node from the depth before
+                    cb.require_zero(
+                        "branch is followed by its non empty child on the key",
+                        prev.is_branch.expr()
+                            * (cur.is_active() - 1.expr() + prev_key.key_child_empty.expr()),
+                    );
+                    cb.require_zero(
+                        "child of a branch starts a node",
+                        prev.is_branch.expr() * (is_start.expr() - cur.is_active()),
+                    );
+                    let is_continued = prev.is_extension.expr() * not::expr(prev.is_last.expr())
+                        + prev.is_leaf.expr();
+                    cb.condition(is_continued, |cb| {
+                        cb.require_equal(
+                            "path continues in its extension",
+                            cur.is_extension.expr(),
+                            prev.is_extension.expr(),
+                        );
+                        cb.require_equal(
+                            "path continues in its leaf",
+                            cur.is_leaf.expr(),
+                            prev.is_leaf.expr(),
+                        );
+                        cb.require_zero("path continues", cur.is_first.expr());
+                        cb.require_zero(
+                            "path stays diverged",
+                            prev_key.is_diverged.expr() * not::expr(is_diverged.expr()),
+                        );
+                        cb.require_equal(
+                            "nibbles alternate between high and low",
+                            cur.is_high.expr(),
+                            not::expr(prev.is_high.expr()),
+                        );
+                    });
+                    let is_extension_end = prev.is_extension.expr() * prev.is_last.expr();
+                    cb.require_zero(
+                        "extension on the key is followed by a branch",
+                        is_extension_end.expr()
+                            * not::expr(prev_key.is_diverged.expr())
+                            * not::expr(cur.is_branch.expr()),
+                    );
+                    cb.require_zero(
+                        "diverged extension ends the path",
+                        is_extension_end * prev_key.is_diverged.expr() * cur.is_active(),
+                    );
+                    cb.require_zero(
+                        "path doesn't start after its end",
+                        not::expr(prev.is_active()) * cur.is_active(),
+                    );
+                    cb.require_equal(
+                        "path diverges",
+                        cur_key.diverged_start.expr(),
+                        is_diverged.expr()
+                            * not::expr(
+                                not::expr(cur.is_first.expr()) * prev_key.is_diverged.expr(),
+                            ),
+                    );
+                    cb.require_zero(
+                        "path diverges from the key",
+                        cur_key.diverged_start.expr()
+                            * ((other_nibble.expr() - nibble.expr()) * c.diverged_inv.expr()
+                                - 1.expr()),
+                    );
+                    is_diverged.expr() * other_nibble.expr()
+                        + not::expr(is_diverged) * nibble.expr()
+                };
+
+                // The compact encoding of the path of the extensions and the
+                // leaves, where the first byte has the flags, followed by the
+                // nibble if the path is odd
+                let is_first = cur.is_first.expr();
+                let is_high = cur.is_high.expr();
+                let prefix = HP_LEAF_FLAG.expr() * cur.is_leaf.expr()
+                    + HP_ODD_FLAG.expr() * not::expr(is_high.expr());
+                let acc =
+                    is_first.expr() * prefix + not::expr(is_first.expr()) * prev.rlp_acc.expr();
+                let len = is_first.expr() + not::expr(is_first.expr()) * prev.rlp_len.expr();
+                let pow =
+                    is_first.expr() * r.expr() + not::expr(is_first.expr()) * prev.rlp_pow.expr();
+                // The string header of a path of more than one byte
+                let has_header = cur.is_last.expr() - cur.is_single.expr();
+                cb.condition(is_path, |cb| {
+                    cb.require_equal(
+                        "path accumulator",
+                        cur.rlp_acc.expr(),
+                        is_high.expr() * (acc.expr() * r.expr() + 16.expr() * nibble.expr())
+                            + not::expr(is_high.expr()) * (acc + nibble)
+                            + has_header.expr()
+                                * (RLP_SHORT_STRING.expr() + prev.rlp_len.expr())
+                                * prev.rlp_pow.expr(),
+                    );
+                    cb.require_equal(
+                        "path length",
+                        cur.rlp_len.expr(),
+                        len + is_high.expr() + has_header.expr(),
+                    );
+                    cb.require_equal(
+                        "path power",
+                        cur.rlp_pow.expr(),
+                        pow * (1.expr() + is_high * (r.expr() - 1.expr()))
+                            * (1.expr() + has_header * (r.expr() - 1.expr())),
+                    );
+                });
+                cb.condition(cur.is_branch.expr(), |cb| {
+                    cb.require_zero("branch starts empty", cur.rlp_acc.expr());
+                    cb.require_zero("branch starts with no length", cur.rlp_len.expr());
+                    cb.require_equal("branch starts with power 1", cur.rlp_pow.expr(), 1.expr());
+                    cb.require_zero("branch starts with no child", cur.num_children.expr());
+                });
+                cb.condition(not::expr(cur.is_active()), |cb| {
+                    cb.require_equal(
+                        "accumulator is carried",
+                        cur.rlp_acc.expr(),
+                        prev.rlp_acc.expr(),
+                    );
+                    cb.require_equal("length is carried", cur.rlp_len.expr(), prev.rlp_len.expr());
+                    cb.require_equal("power is carried", cur.rlp_pow.expr(), prev.rlp_pow.expr());
+                });
+            }
+
+            // The old and the new lanes are the same until they split, at the
+            // start of a node, which is a branch on one side and a leaf on the
+            // other, or a single leaf.
+            let [old, new, sibling] = &c.lanes;
+            let [old_key, new_key] = &c.key_lanes;
+            let [prev_old, prev_new, prev_sibling] = &p.lanes;
+            cb.condition(is_same.expr(), |cb| {
+                for (old, new) in old
+                    .flags()
+                    .into_iter()
+                    .zip(new.flags())
+                    .chain([(&old_key.is_diverged, &new_key.is_diverged)])
+                {
+                    cb.require_equal("same lanes have the same nodes", old.expr(), new.expr());
+                }
+            });
+            let is_split = p.is_same.expr() * not::expr(is_same.expr());
+            cb.condition(is_split, |cb| {
+                cb.require_zero(
+                    "split lanes aren't both empty",
+                    not::expr(old.is_active()) * not::expr(new.is_active()),
+                );
+                for lane in [old, new] {
+                    cb.require_zero(
+                        "split at the start of the nodes",
+                        lane.is_active() - lane.is_branch.expr() - lane.is_first.expr(),
+                    );
+                }
+                for (lane, other, other_key) in [(old, new, new_key), (new, old, old_key)] {
+                    cb.require_zero(
+                        "split from the empty trie to a single leaf",
+                        not::expr(lane.is_active()) * not::expr(other.is_leaf.expr()),
+                    );
+                    cb.require_zero(
+                        "single leaf isn't diverged",
+                        not::expr(lane.is_active()) * other_key.is_diverged.expr(),
+                    );
+                }
+            });
+            let is_split_after = not::expr(is_same.expr());
+            cb.require_zero(
+                "split lanes aren't both branches",
+                is_split_after.expr() * old.is_branch.expr() * new.is_branch.expr(),
+            );
+            for (lane, prev_lane, lane_key, other_key) in [
+                (old, prev_old, old_key, new_key),
+                (new, prev_new, new_key, old_key),
+            ] {
+                cb.require_zero(
+                    "branch of a split lane is where the other lane diverges",
+                    is_split_after.expr()
+                        * lane.is_branch.expr()
+                        * not::expr(other_key.diverged_start.expr()),
+                );
+                cb.require_zero(
+                    "split lane diverges at a branch",
+                    is_split_after.expr()
+                        * other_key.diverged_start.expr()
+                        * not::expr(lane.is_branch.expr()),
+                );
+                cb.require_zero(
+                    "split branch is followed by a leaf",
+                    not::expr(p.is_same.expr())
+                        * prev_lane.is_branch.expr()
+                        * not::expr(lane.is_leaf.expr()),
+                );
+                cb.require_zero(
+                    "leaf after a split branch isn't diverged",
+                    not::expr(p.is_same.expr())
+                        * prev_lane.is_branch.expr()
+                        * lane_key.is_diverged.expr(),
+                );
+            }
+
+            // The sibling lane follows the diverged path of the lane without
+            // the branch, from below the branch, or is the branch child of the
+            // extension merged by a deletion.
+            let is_below_branch = not::expr(p.is_same.expr())
+                * (prev_old.is_branch.expr() + prev_new.is_branch.expr());
+            for (name, flag, old_flag, new_flag) in [
+                (
+                    "sibling extension",
+                    &sibling.is_extension,
+                    &old.is_extension,
+                    &new.is_extension,
+                ),
+                ("sibling leaf", &sibling.is_leaf, &old.is_leaf, &new.is_leaf),
+                (
+                    "sibling last nibble",
+                    &sibling.is_last,
+                    &old.is_last,
+                    &new.is_last,
+                ),
+                (
+                    "sibling high nibble",
+                    &sibling.is_high,
+                    &old.is_high,
+                    &new.is_high,
+                ),
+            ] {
+                cb.require_equal(
+                    name,
+                    flag.expr(),
+                    not::expr(is_same.expr())
+                        * (old_flag.expr()
+                            * (old_key.is_diverged.expr() - old_key.diverged_start.expr())
+                            + new_flag.expr()
+                                * (new_key.is_diverged.expr() - new_key.diverged_start.expr())),
+                );
+            }
+            cb.require_equal(
+                "sibling starts below the branch",
+                sibling.is_first.expr(),
+                is_below_branch.expr() * sibling.is_path(),
+            );
+            cb.require_equal(
+                "sibling branch below the deleted branch",
+                sibling.is_branch.expr(),
+                is_below_branch
+                    * p.key_lanes[NEW].diverged_start.expr()
+                    * not::expr(old_key.is_diverged.expr() + new_key.is_diverged.expr()),
+            );
+            cb.require_zero(
+                "sibling path continues",
+                (prev_sibling.is_extension.expr() * not::expr(prev_sibling.is_last.expr())
+                    + prev_sibling.is_leaf.expr())
+                    * (sibling.is_path() - 1.expr()),
+            );
+
+            // The extensions ending at the depth are followed by their child,
+            // and the branches by their children.
+            let num_extension_ends = sum::expr(
+                c.lanes
+                    .iter()
+                    .map(|lane| lane.is_extension.expr() * lane.is_last.expr()),
+            );
+            let num_branches = sum::expr(c.lanes.iter().map(|lane| lane.is_branch.expr()));
+            cb.require_zero(
+                "extension child follows the extension",
+                num_extension_ends * not::expr(n.is_kind(RowKind::ExtensionChild)),
+            );
+            cb.require_zero(
+                "children follow the branch",
+                num_branches
+                    * not::expr(n.is_kind(RowKind::ExtensionChild) + n.is_kind(RowKind::Child)),
+            );
+            cb.require_zero(
+                "first extension child",
+                n.is_kind(RowKind::ExtensionChild) * n.is_dup_extension.expr(),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()) * c.is_kind(RowKind::Nibble))
+        });
+
+        // The RLC of the bytes as keccak input, and as a hash in the MPT table
+        let hash_rlc = |c: &MptColumns<Expression<F>>| compose(&c.bytes, r.expr());
+        let word_rlc = |c: &MptColumns<Expression<F>>| compose(&c.bytes, e.expr());
+
+        meta.create_gate("mpt extension child rows", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [p, c, n] = query(meta);
+            let is_dup = c.is_dup_extension.expr();
+            let is_extension_end: Vec<_> = c
+                .lanes
+                .iter()
+                .map(|lane| lane.is_extension.expr() * lane.is_last.expr())
+                .collect();
+
+            // The old and new lanes share the child of a shared extension,
+            // which is on a second row if they have different children.
+            cb.require_equal(
+                "duplicate extension child",
+                is_dup.expr(),
+                p.is_kind(RowKind::ExtensionChild),
+            );
+            for (i, in_row) in [
+                is_extension_end[OLD].expr() * not::expr(is_dup.expr()),
+                is_extension_end[NEW].expr()
+                    * not::expr(is_extension_end[OLD].expr() * not::expr(is_dup.expr())),
+                is_extension_end[SIBLING].expr() * not::expr(is_dup.expr()),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                cb.require_equal("extension child lanes", c.lanes[i].in_row.expr(), in_row);
+            }
+            let num_lanes = sum::expr(c.lanes.iter().map(|lane| lane.in_row.expr()));
+            cb.require_zero(
+                "extension child is in one or two lanes",
+                (num_lanes.expr() - 1.expr()) * (num_lanes - 2.expr()),
+            );
+            cb.require_equal(
+                "second extension child of the old and new lanes",
+                n.is_kind(RowKind::ExtensionChild),
+                is_extension_end[OLD].expr() * is_extension_end[NEW].expr() * not::expr(is_dup),
+            );
+            cb.require_zero(
+                "children follow the branch",
+                sum::expr(c.lanes.iter().map(|lane| lane.is_branch.expr()))
+                    * not::expr(n.is_kind(RowKind::ExtensionChild) + n.is_kind(RowKind::Child)),
+            );
+
+            let child_hash = hash_rlc(&c);
+            let child_word = word_rlc(&c);
+            for (i, (cur, prev)) in c.lanes.iter().zip(p.lanes.iter()).enumerate() {
+                cb.condition(cur.in_row.expr(), |cb| {
+                    cb.require_equal(
+                        "extension child accumulator",
+                        cur.rlp_acc.expr(),
+                        prev.rlp_acc.expr() * r_pow(33)
+                            + RLP_HASH.expr() * r_pow(32)
+                            + child_hash.expr(),
+                    );
+                    cb.require_equal(
+                        "extension child length",
+                        cur.rlp_len.expr(),
+                        prev.rlp_len.expr() + 33.expr(),
+                    );
+                    cb.require_equal(
+                        "extension child power",
+                        cur.rlp_pow.expr(),
+                        prev.rlp_pow.expr() * r_pow(33),
+                    );
+                    cb.require_equal(
+                        "extension child is the next node",
+                        cur.ptr.expr(),
+                        child_word.expr(),
+                    );
+                });
+                cb.condition(not::expr(cur.in_row.expr()), |cb| {
+                    for (name, cur, prev) in [
+                        ("accumulator is carried", &cur.rlp_acc, &prev.rlp_acc),
+                        ("length is carried", &cur.rlp_len, &prev.rlp_len),
+                        ("power is carried", &cur.rlp_pow, &prev.rlp_pow),
+                        ("child is carried", &cur.ptr, &prev.ptr),
+                    ] {
+                        cb.require_equal(name, cur.expr(), prev.expr());
+                    }
+                });
+                cb.require_equal(
+                    "number of children is carried",
+                    cur.num_children.expr(),
+                    prev.num_children.expr(),
+                );
+                cb.require_equal(
+                    "next tag is carried",
+                    cur.next_tag.expr(),
+                    prev.next_tag.expr(),
+                );
+                if i != SIBLING {
+                    cb.require_equal(
+                        "empty key child is carried",
+                        c.key_lanes[i].key_child_empty.expr(),
+                        p.key_lanes[i].key_child_empty.expr(),
+                    );
+                }
+            }
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur()) * c.is_kind(RowKind::ExtensionChild),
+            )
+        });
+
+        meta.create_gate("mpt child rows", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [p, c, n] = query(meta);
+            let index = c.index.expr();
+            let is_dup = c.is_dup.expr();
+            let is_empty = c.is_empty.expr();
+            let is_last = c.is_last_child.expr();
+            let is_key_index = c.is_key_index.expr();
+            let is_other_index = c.is_other_index.expr();
+            let [old, new, sibling] = &c.lanes;
+            let is_both_branches = old.is_branch.expr() * new.is_branch.expr();
+
+            // The children are indexed from 0 to 15, where the child on the
+            // key of a branch shared by the old and new lanes is on a second
+            // row if it changes.
+            let is_first = not::expr(p.is_kind(RowKind::Child));
+            cb.require_zero("first child index", is_first.expr() * index.expr());
+            cb.require_zero(
+                "first child isn't a duplicate",
+                is_first.expr() * is_dup.expr(),
+            );
+            cb.require_zero(
+                "child index",
+                not::expr(is_first) * (index.expr() - p.index.expr() - 1.expr() + is_dup.expr()),
+            );
+            cb.require_boolean("duplicate is boolean", is_dup.expr());
+            cb.require_zero(
+                "duplicate of the key child",
+                is_dup.expr() * not::expr(p.is_key_index.expr()),
+            );
+            cb.require_zero("duplicate once", is_dup.expr() * p.is_dup.expr());
+            cb.require_zero(
+                "duplicate of a shared branch",
+                is_dup.expr() * not::expr(is_both_branches.expr()),
+            );
+            let is_shared_key_child =
+                is_key_index.expr() * not::expr(is_dup.expr()) * is_both_branches.expr();
+            cb.require_zero(
+                "shared key child is followed by its duplicate",
+                is_shared_key_child.expr() * not::expr(n.is_kind(RowKind::Child) * n.is_dup.expr()),
+            );
+            cb.require_zero(
+                "children continue until the last one",
+                not::expr(is_last.expr()) * not::expr(n.is_kind(RowKind::Child)),
+            );
+            cb.require_zero(
+                "children end at the last one",
+                is_last.expr() * (n.is_kind(RowKind::Child) - is_shared_key_child),
+            );
+            cb.require_zero(
+                "children aren't followed by an extension child",
+                n.is_kind(RowKind::ExtensionChild),
+            );
+            for (name, is_index, value, inv) in [
+                (
+                    "key index",
+                    &c.is_key_index,
+                    c.nibble.expr(),
+                    &c.key_index_inv,
+                ),
+                (
+                    "other index",
+                    &c.is_other_index,
+                    c.other_nibble.expr(),
+                    &c.other_index_inv,
+                ),
+                (
+                    "last child",
+                    &c.is_last_child,
+                    (NUM_CHILDREN - 1).expr(),
+                    &c.last_child_inv,
+                ),
+            ] {
+                cb.require_boolean(name, is_index.expr());
+                cb.require_zero(name, is_index.expr() * (index.expr() - value.expr()));
+                cb.require_zero(
+                    name,
+                    not::expr(is_index.expr()) * ((index.expr() - value) * inv.expr() - 1.expr()),
+                );
+            }
+
+            for (i, in_row) in [
+                old.is_branch.expr() * not::expr(is_dup.expr()),
+                new.is_branch.expr()
+                    * not::expr(old.is_branch.expr() * (is_key_index.expr() - is_dup.expr())),
+                sibling.is_branch.expr(),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                cb.require_equal("child lanes", c.lanes[i].in_row.expr(), in_row);
+            }
+            let num_lanes = sum::expr(c.lanes.iter().map(|lane| lane.in_row.expr()));
+            cb.require_zero(
+                "child is in one or two lanes",
+                (num_lanes.expr() - 1.expr()) * (num_lanes - 2.expr()),
+            );
+
+            // An empty child is encoded as an empty string, and the others as
+            // their hash.
+            cb.require_boolean("empty child is boolean", is_empty.expr());
+            cb.require_zero("empty child", is_empty.expr() * sum::expr(c.bytes.iter()));
+            let child_hash = hash_rlc(&c);
+            let child_word = word_rlc(&c);
+            let item = is_empty.expr() * RLP_SHORT_STRING.expr()
+                + not::expr(is_empty.expr()) * (RLP_HASH.expr() * r_pow(32) + child_hash.expr());
+            let item_pow = is_empty.expr() * r.expr() + not::expr(is_empty.expr()) * r_pow(33);
+            for (i, (cur, prev)) in c.lanes.iter().zip(p.lanes.iter()).enumerate() {
+                cb.condition(cur.in_row.expr(), |cb| {
+                    // The last child is followed by the empty value of the
+                    // branch.
+                    cb.require_equal(
+                        "child accumulator",
+                        cur.rlp_acc.expr(),
+                        (prev.rlp_acc.expr() * item_pow.expr() + item.expr())
+                            * (1.expr() + is_last.expr() * (r.expr() - 1.expr()))
+                            + is_last.expr() * RLP_SHORT_STRING.expr(),
+                    );
+                    cb.require_equal(
+                        "child length",
+                        cur.rlp_len.expr(),
+                        prev.rlp_len.expr()
+                            + 1.expr()
+                            + 32.expr() * not::expr(is_empty.expr())
+                            + is_last.expr(),
+                    );
+                    cb.require_equal(
+                        "child power",
+                        cur.rlp_pow.expr(),
+                        prev.rlp_pow.expr()
+                            * item_pow.expr()
+                            * (1.expr() + is_last.expr() * (r.expr() - 1.expr())),
+                    );
+                    cb.require_equal(
+                        "number of children",
+                        cur.num_children.expr(),
+                        prev.num_children.expr() + not::expr(is_empty.expr()),
+                    );
+                });
+                cb.condition(not::expr(cur.in_row.expr()), |cb| {
+                    for (name, cur, prev) in [
+                        ("accumulator is carried", &cur.rlp_acc, &prev.rlp_acc),
+                        ("length is carried", &cur.rlp_len, &prev.rlp_len),
+                        ("power is carried", &cur.rlp_pow, &prev.rlp_pow),
+                        (
+                            "number of children is carried",
+                            &cur.num_children,
+                            &prev.num_children,
+                        ),
+                    ] {
+                        cb.require_equal(name, cur.expr(), prev.expr());
+                    }
+                });
+                cb.require_equal(
+                    "next tag is carried",
+                    cur.next_tag.expr(),
+                    prev.next_tag.expr(),
+                );
+                if i != SIBLING {
+                    // The child on the key is the next node.
+                    let is_key_child = cur.in_row.expr() * is_key_index.expr();
+                    cb.require_equal(
+                        "empty key child",
+                        c.key_lanes[i].key_child_empty.expr(),
+                        is_key_child.expr() * is_empty.expr()
+                            + not::expr(is_key_child.expr())
+                                * p.key_lanes[i].key_child_empty.expr(),
+                    );
+                    cb.require_equal(
+                        "key child is the next node",
+                        cur.ptr.expr(),
+                        is_key_child.expr() * child_word.expr()
+                            + not::expr(is_key_child) * prev.ptr.expr(),
+                    );
+                }
+            }
+
+            // The branch of a split has two children, the one on the key and
+            // the one of the sibling lane on the other nibble.
+            let is_split_branch =
+                not::expr(c.is_same.expr()) * (old.is_branch.expr() + new.is_branch.expr());
+            let is_sibling_child = is_split_branch.expr() * is_other_index.expr();
+            cb.require_equal(
+                "sibling child is the next node",
+                sibling.ptr.expr(),
+                is_sibling_child.expr() * child_word.expr()
+                    + not::expr(is_sibling_child.expr()) * p.lanes[SIBLING].ptr.expr(),
+            );
+            cb.require_zero(
+                "split branch has no other children",
+                is_split_branch.expr()
+                    * not::expr(is_key_index.expr())
+                    * not::expr(is_other_index.expr())
+                    * not::expr(is_empty.expr()),
+            );
+            cb.require_zero(
+                "split branch has the key child",
+                is_split_branch.expr() * is_key_index * is_empty.expr(),
+            );
+            cb.require_zero(
+                "split branch has the sibling child",
+                is_sibling_child.expr() * is_empty,
+            );
+            for (lane, key_lane) in [(old, &c.key_lanes[OLD]), (new, &c.key_lanes[NEW])] {
+                cb.require_zero(
+                    "sibling child of one nibble is the diverged node",
+                    is_sibling_child.expr()
+                        * key_lane.diverged_start.expr()
+                        * lane.is_last.expr()
+                        * (child_word.expr() - lane.ptr.expr()),
+                );
+            }
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()) * c.is_kind(RowKind::Child))
+        });
+
+        meta.create_gate("mpt value rows", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [p, c, n] = query(meta);
+            let tags: [Expression<F>; NUM_VALUE_TAGS] =
+                array_init::array_init(|i| c.value_tag(i + 1));
+            let is_nonce = tags[NONCE - 1].expr();
+            let is_number = c.value_tag(NONCE) + c.value_tag(BALANCE) + c.value_tag(STORAGE_VALUE);
+            let is_account_number = c.value_tag(NONCE) + c.value_tag(BALANCE);
+            let is_hash = c.value_tag(STORAGE_ROOT) + c.value_tag(CODE_HASH);
+            let is_storage_value = c.value_tag(STORAGE_VALUE);
+            for tag in tags.iter() {
+                cb.require_boolean("value tag is boolean", tag.expr());
+            }
+            cb.require_equal("one value tag", sum::expr(tags.iter()), 1.expr());
+            let tag = sum::expr(
+                tags.iter()
+                    .enumerate()
+                    .map(|(i, tag)| (i + 1).expr() * tag.expr()),
+            );
+
+            // The fields of a leaf are in the lanes of the leaf, and the
+            // sibling lane is in the rows of the diverged lane.
+            let [old, new, sibling] = &c.lanes;
+            for lane in c.lanes.iter() {
+                cb.require_zero(
+                    "value of a leaf",
+                    lane.in_row.expr() * not::expr(lane.is_leaf.expr()),
+                );
+            }
+            cb.require_equal(
+                "sibling value lane",
+                sibling.in_row.expr(),
+                sibling.is_leaf.expr()
+                    * (c.key_lanes[OLD].is_diverged.expr() * old.in_row.expr()
+                        + c.key_lanes[NEW].is_diverged.expr() * new.in_row.expr()),
+            );
+            let num_lanes = sum::expr(c.lanes.iter().map(|lane| lane.in_row.expr()));
+            cb.require_zero(
+                "value is in one to three lanes",
+                (num_lanes.expr() - 1.expr())
+                    * (num_lanes.expr() - 2.expr())
+                    * (num_lanes - 3.expr()),
+            );
+
+            // The bytes of a number are its leading zeros followed by its
+            // minimal big endian encoding.
+            let lz = &c.leading_zeros;
+            for (i, (is_zero, byte)) in lz.iter().zip(c.bytes.iter()).enumerate() {
+                cb.require_boolean("leading zero is boolean", is_zero.expr());
+                if i > 0 {
+                    cb.require_zero(
+                        "leading zeros are first",
+                        is_zero.expr() * not::expr(lz[i - 1].expr()),
+                    );
+                }
+                cb.require_zero("leading zero", is_zero.expr() * byte.expr());
+            }
+            cb.require_zero(
+                "hash has no leading zeros",
+                is_hash.expr() * sum::expr(lz.iter()),
+            );
+            // Whether each byte is the first one after the leading zeros
+            let is_first_byte: Vec<_> = (0..NUM_BYTES_PER_WORD)
+                .map(|i| {
+                    let prev = if i == 0 { 1.expr() } else { lz[i - 1].expr() };
+                    prev - lz[i].expr()
+                })
+                .collect();
+            let first_byte = sum::expr(
+                is_first_byte
+                    .iter()
+                    .zip(c.bytes.iter())
+                    .map(|(is_first, byte)| is_first.expr() * byte.expr()),
+            );
+            let is_zero = lz[NUM_BYTES_PER_WORD - 1].expr();
+            cb.require_zero(
+                "number has no leading zero",
+                is_number.expr()
+                    * not::expr(is_zero.expr())
+                    * (first_byte * c.first_byte_inv.expr() - 1.expr()),
+            );
+            cb.require_zero(
+                "nonce has 8 bytes",
+                is_nonce.expr()
+                    * not::expr(lz[NUM_BYTES_PER_WORD - NUM_BYTES_PER_NONCE - 1].expr()),
+            );
+            cb.require_zero(
+                "storage value isn't 0",
+                is_storage_value.expr() * is_zero.expr(),
+            );
+            let is_short = c.is_short_value.expr();
+            cb.require_boolean("short value is boolean", is_short.expr());
+            cb.require_zero(
+                "short value of one byte",
+                is_short.expr() * (1.expr() - lz[NUM_BYTES_PER_WORD - 2].expr() + is_zero.expr()),
+            );
+            cb.require_zero(
+                "short value is a number",
+                is_short.expr() * not::expr(is_number),
+            );
+
+            // The RLP item of the field, which is a string of the number or
+            // the hash, itself in a string for the storage value
+            let byte = c.bytes[NUM_BYTES_PER_WORD - 1].expr();
+            let num_bytes = NUM_BYTES_PER_WORD.expr() - sum::expr(lz.iter());
+            let num_bytes_pow = sum::expr(
+                is_first_byte
+                    .iter()
+                    .enumerate()
+                    .map(|(i, is_first)| is_first.expr() * r_pow(NUM_BYTES_PER_WORD - i)),
+            ) + is_zero.expr();
+            let is_long = 1.expr() - is_zero.expr() - is_short.expr();
+            let value_hash = hash_rlc(&c);
+            let number_item = is_zero.expr() * RLP_SHORT_STRING.expr()
+                + is_short.expr() * byte.expr()
+                + is_long.expr()
+                    * ((RLP_SHORT_STRING.expr() + num_bytes.expr()) * num_bytes_pow.expr()
+                        + value_hash.expr());
+            let storage_item = is_short.expr() * byte.expr()
+                + not::expr(is_short.expr())
+                    * ((RLP_SHORT_STRING.expr() + 1.expr() + num_bytes.expr())
+                        * num_bytes_pow.expr()
+                        * r.expr()
+                        + (RLP_SHORT_STRING.expr() + num_bytes.expr()) * num_bytes_pow.expr()
+                        + value_hash.expr());
+            let hash_item = RLP_HASH.expr() * r_pow(32) + value_hash;
+            cb.require_equal(
+                "value item",
+                c.item.expr(),
+                is_account_number.expr() * number_item
+                    + is_hash.expr() * hash_item
+                    + is_storage_value.expr() * storage_item,
+            );
+            cb.require_equal(
+                "value item length",
+                c.item_len.expr(),
+                is_account_number.expr()
+                    * (is_zero.expr()
+                        + is_short.expr()
+                        + is_long.expr() * (num_bytes.expr() + 1.expr()))
+                    + is_hash.expr() * 33.expr()
+                    + is_storage_value.expr()
+                        * (is_short.expr() + not::expr(is_short.expr()) * (num_bytes + 2.expr())),
+            );
+            cb.require_equal(
+                "value item power",
+                c.item_pow.expr(),
+                is_account_number.expr()
+                    * ((is_zero.expr() + is_short.expr()) * r.expr()
+                        + is_long * num_bytes_pow.expr() * r.expr())
+                    + is_hash.expr() * r_pow(33)
+                    + is_storage_value.expr()
+                        * (is_short.expr() * r.expr()
+                            + not::expr(is_short) * num_bytes_pow * r_pow(2)),
+            );
+            cb.require_equal(
+                "value",
+                c.value.expr(),
+                is_nonce.expr()
+                    * compose(
+                        &c.bytes[NUM_BYTES_PER_WORD - NUM_BYTES_PER_NONCE..],
+                        256.expr(),
+                    )
+                    + not::expr(is_nonce.expr()) * word_rlc(&c),
+            );
+
+            // An account leaf is a string of the list of its fields, whose
+            // headers are encoded from the length left from the nonce.
+            for (i, (cur, prev)) in c.lanes.iter().zip(p.lanes.iter()).enumerate() {
+                let list_len = cur.rem_len.expr() + c.item_len.expr();
+                let acc = is_nonce.expr()
+                    * (prev.rlp_acc.expr() * r_pow(4)
+                        + (RLP_LONG_STRING + 1).expr() * r_pow(3)
+                        + (list_len.expr() + 2.expr()) * r_pow(2)
+                        + (RLP_LONG_LIST + 1).expr() * r.expr()
+                        + list_len)
+                    + not::expr(is_nonce.expr()) * prev.rlp_acc.expr();
+                cb.condition(cur.in_row.expr(), |cb| {
+                    cb.require_equal(
+                        "value accumulator",
+                        cur.rlp_acc.expr(),
+                        acc * c.item_pow.expr() + c.item.expr(),
+                    );
+                    cb.require_equal(
+                        "value length",
+                        cur.rlp_len.expr(),
+                        prev.rlp_len.expr() + 4.expr() * is_nonce.expr() + c.item_len.expr(),
+                    );
+                    cb.require_equal(
+                        "value power",
+                        cur.rlp_pow.expr(),
+                        prev.rlp_pow.expr()
+                            * (is_nonce.expr() * (r_pow(4) - 1.expr()) + 1.expr())
+                            * c.item_pow.expr(),
+                    );
+                    cb.require_equal("value tag", tag.expr(), prev.next_tag.expr());
+                    cb.require_equal(
+                        "next value tag",
+                        cur.next_tag.expr(),
+                        prev.next_tag.expr() + 1.expr(),
+                    );
+                    cb.require_zero(
+                        "length of the fields left",
+                        (c.value_tag(BALANCE) + is_hash.expr())
+                            * (cur.rem_len.expr() - prev.rem_len.expr() + c.item_len.expr()),
+                    );
+                    cb.require_zero(
+                        "no field left after the code hash",
+                        c.value_tag(CODE_HASH) * cur.rem_len.expr(),
+                    );
+                });
+                cb.condition(not::expr(cur.in_row.expr()), |cb| {
+                    for (name, cur, prev) in [
+                        ("accumulator is carried", &cur.rlp_acc, &prev.rlp_acc),
+                        ("length is carried", &cur.rlp_len, &prev.rlp_len),
+                        ("power is carried", &cur.rlp_pow, &prev.rlp_pow),
+                        ("next tag is carried", &cur.next_tag, &prev.next_tag),
+                        ("length left is carried", &cur.rem_len, &prev.rem_len),
+                    ] {
+                        cb.require_equal(name, cur.expr(), prev.expr());
+                    }
+                });
+                cb.require_equal("child is carried", cur.ptr.expr(), prev.ptr.expr());
+                cb.require_equal(
+                    "number of children is carried",
+                    cur.num_children.expr(),
+                    prev.num_children.expr(),
+                );
+                if i != SIBLING {
+                    cb.require_equal(
+                        "empty key child is carried",
+                        c.key_lanes[i].key_child_empty.expr(),
+                        p.key_lanes[i].key_child_empty.expr(),
+                    );
+                }
+            }
+
+            // The field of the segment is its old and new values, while the
+            // other fields are unchanged.  An account is inserted with the
+            // empty storage root and code hash, and the code hash of an
+            // account which doesn't exist is 0 in the MPT table.
+            let is_field =
+                sum::expr((1..NUM_FIELDS).map(|field| c.value_tag(field) * c.field[field].expr()));
+            let is_code_hash_field = c.value_tag(CODE_HASH) * c.field[CODE_HASH].expr();
+            let is_other_field = is_field.expr() - is_code_hash_field.expr();
+            let value = c.value.expr();
+            let old_value = c.segment_old_value.expr();
+            let new_value = c.segment_new_value.expr();
+            let (in_old, in_new) = (old.in_row.expr(), new.in_row.expr());
+            let (old_found, new_found) = (c.old_found.expr(), c.new_found.expr());
+            cb.require_zero(
+                "new value",
+                in_new.expr()
+                    * new_found.expr()
+                    * is_field.expr()
+                    * (value.expr() - new_value.expr()),
+            );
+            cb.require_zero(
+                "old value",
+                in_old.expr()
+                    * old_found.expr()
+                    * is_other_field
+                    * (value.expr() - old_value.expr()),
+            );
+            let is_old_code_hash = in_old.expr() * old_found.expr() * is_code_hash_field.expr();
+            cb.require_zero(
+                "old code hash of an account which doesn't exist",
+                is_old_code_hash.expr() * (value.expr() - old_value.expr()) * old_value.expr(),
+            );
+            cb.require_zero(
+                "old code hash of an account which doesn't exist is the empty code hash",
+                is_old_code_hash
+                    * (value.expr() - old_value.expr())
+                    * (value.expr() - empty_code_rlc.expr()),
+            );
+            cb.require_zero(
+                "new code hash isn't 0",
+                in_new.expr()
+                    * new_found.expr()
+                    * is_code_hash_field
+                    * (new_value * c.new_value_inv.expr() - 1.expr()),
+            );
+            cb.require_zero(
+                "other fields are unchanged",
+                old_found.expr()
+                    * new_found.expr()
+                    * not::expr(is_field.expr())
+                    * (in_old.expr() - in_new.expr()),
+            );
+            cb.require_zero(
+                "other fields of an inserted account are empty",
+                new_found
+                    * not::expr(old_found)
+                    * in_new
+                    * not::expr(is_field)
+                    * (value
+                        - (c.value_tag(STORAGE_ROOT) * empty_root_rlc.expr()
+                            + c.value_tag(CODE_HASH) * empty_code_rlc.expr())),
+            );
+            cb.require_equal(
+                "value rows are followed by a value, a key or padding",
+                n.is_kind(RowKind::Value) + n.is_kind(RowKind::Key) + n.is_kind(RowKind::Padding),
+                1.expr(),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()) * c.is_kind(RowKind::Value))
+        });
+
+        for i in 0..NUM_BYTES_PER_WORD {
+            meta.lookup("mpt byte", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                let byte = meta.query_advice(columns.bytes[i], Rotation::cur());
+                vec![(q_enable * byte, u8_table)]
+            });
+        }
+        for column in [columns.nibble, columns.other_nibble] {
+            meta.lookup("mpt nibble", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                let nibble = meta.query_advice(column, Rotation::cur());
+                vec![(q_enable * nibble, u8_table)]
+            });
+            meta.lookup("mpt nibble is less than 16", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                let nibble = meta.query_advice(column, Rotation::cur());
+                vec![(q_enable * 16.expr() * nibble, u8_table)]
+            });
+        }
+        meta.lookup("mpt short value is less than 128", |meta| {
+            let c = columns.query(meta, Rotation::cur());
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let byte = c.bytes[NUM_BYTES_PER_WORD - 1].expr();
+            vec![(
+                q_enable * c.is_short_value.expr() * (byte + RLP_SHORT_STRING.expr()),
+                u8_table,
+            )]
+        });
+        meta.lookup("mpt long value of one byte is at least 128", |meta| {
+            let c = columns.query(meta, Rotation::cur());
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let is_number = c.value_tag(NONCE) + c.value_tag(BALANCE) + c.value_tag(STORAGE_VALUE);
+            let is_one_byte = c.leading_zeros[NUM_BYTES_PER_WORD - 2].expr()
+                - c.leading_zeros[NUM_BYTES_PER_WORD - 1].expr()
+                - c.is_short_value.expr();
+            let byte = c.bytes[NUM_BYTES_PER_WORD - 1].expr();
+            vec![(
+                q_enable * is_number * is_one_byte * (byte - RLP_SHORT_STRING.expr()),
+                u8_table,
+            )]
+        });
+
+        for lane in 0..NUM_LANES {
+            // The list header of the node is encoded in one, two or three
+            // bytes, with a length of one byte up to 55, and of two bytes from
+            // 256.
+            let header = |meta: &mut VirtualCells<F>| {
+                let c = columns.query(meta, Rotation::cur());
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                let lane = c.lanes[lane].clone();
+                let is_end = q_enable * lane.is_end.expr();
+                let is_medium = 1.expr() - lane.is_short_header.expr() - lane.is_long_header.expr();
+                (lane, is_end, is_medium)
+            };
+            meta.lookup("mpt node length", |meta| {
+                let (lane, is_end, _) = header(meta);
+                vec![(is_end * lane.len_lo.expr(), u8_table)]
+            });
+            meta.lookup("mpt node length range", |meta| {
+                let (lane, is_end, is_medium) = header(meta);
+                let len_lo = lane.len_lo.expr();
+                vec![(
+                    is_end
+                        * (lane.is_short_header.expr()
+                            * (len_lo.expr() + (255 - RLP_MAX_SHORT_LEN).expr())
+                            + is_medium * (len_lo - (RLP_MAX_SHORT_LEN + 1).expr())),
+                    u8_table,
+                )]
+            });
+            meta.lookup_any("mpt node hash", |meta| {
+                let (lane, is_end, is_medium) = header(meta);
+                let (len_hi, len_lo) = (lane.len_hi.expr(), lane.len_lo.expr());
+                let list_header = lane.is_short_header.expr()
+                    * (RLP_SHORT_LIST.expr() + len_lo.expr())
+                    + is_medium.expr() * ((RLP_LONG_LIST + 1).expr() * r.expr() + len_lo.expr())
+                    + lane.is_long_header.expr()
+                        * ((RLP_LONG_LIST + 2).expr() * r_pow(2) + len_hi * r.expr() + len_lo);
+                let input_rlc = list_header * lane.rlp_pow.expr() + lane.rlp_acc.expr();
+                let input_len = lane.rlp_len.expr()
+                    + lane.is_short_header.expr()
+                    + 2.expr() * is_medium
+                    + 3.expr() * lane.is_long_header.expr();
+                [is_end.expr(), input_rlc, input_len, lane.hash.expr()]
+                    .into_iter()
+                    .zip(keccak_table.table_exprs(meta))
+                    .map(|(input, table)| (is_end.expr() * input, table))
+                    .collect()
+            });
+            if lane != SIBLING {
+                meta.lookup("mpt branch has at least two children", |meta| {
+                    let c = columns.query(meta, Rotation::cur());
+                    let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                    let lane = &c.lanes[lane];
+                    vec![(
+                        q_enable
+                            * lane.in_row.expr()
+                            * c.is_last_child.expr()
+                            * (lane.num_children.expr() - 2.expr()),
+                        u8_table,
+                    )]
+                });
+            }
+        }
+        meta.lookup_any("mpt key hash", |meta| {
+            let c = columns.query(meta, Rotation::cur());
+            let is_key = meta.query_fixed(q_enable, Rotation::cur()) * c.is_kind(RowKind::Key);
+            let input_len = NUM_BYTES_PER_ADDRESS.expr()
+                + (NUM_BYTES_PER_WORD - NUM_BYTES_PER_ADDRESS).expr() * c.is_storage.expr();
+            [1.expr(), hash_rlc(&c), input_len, c.key_hash.expr()]
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (is_key.expr() * input, table))
+                .collect()
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_last,
+            u8_table,
+            columns,
+            mpt_table,
+            keccak_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> MptCircuitConfig<F> {
+    /// Load the byte range table
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "u8 table",
+            |mut table| {
+                for value in 0..256 {
+                    table.assign_cell(
+                        || format!("u8 table row {}", value),
+                        self.u8_table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &MptRow<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let table_row = if row.is_summary() {
+            row.proof.update
+        } else {
+            MptUpdateRow([Value::known(F::zero()); 7])
+        };
+        self.mpt_table.assign(region, offset, &table_row)?;
+
+        let columns = &self.columns;
+        let mut assign = |column: Column<Advice>, value: Value<F>| {
+            region
+                .assign_advice(|| format!("mpt row {}", offset), column, offset, || value)
+                .map(|_| ())
+        };
+        let known = |value: u64| Value::known(F::from(value));
+        let flag = |flag: bool| known(flag as u64);
+        let inverse = |value: Value<F>| value.map(|value| value.invert().unwrap_or(F::zero()));
+        let difference_inverse = |a: u64, b: u64| inverse(known(a) - known(b));
+
+        for (i, &column) in columns.row_kind.iter().enumerate() {
+            assign(column, flag(row.kind as usize == i))?;
+        }
+        let update = &row.proof.update.0;
+        for (column, value) in [
+            (columns.address, update[0]),
+            (columns.storage_key, update[1]),
+            (columns.new_root, update[3]),
+            (columns.old_root, update[4]),
+            (columns.new_value, update[5]),
+            (columns.old_value, update[6]),
+        ] {
+            assign(column, value)?;
+        }
+        for (i, &column) in columns.proof_type.iter().enumerate() {
+            let is_proof_type = row
+                .proof
+                .proof_type
+                .map_or(false, |proof_type| proof_type as usize == i + 1);
+            assign(column, flag(is_proof_type))?;
+        }
+
+        let segment = &row.segment;
+        for (column, value) in [
+            (columns.is_second, flag(segment.is_second)),
+            (columns.is_storage, flag(segment.is_storage)),
+            (columns.key_hash, segment.key_hash),
+            (columns.segment_old_root, segment.old_root),
+            (columns.segment_new_root, segment.new_root),
+            (columns.segment_old_value, segment.old_value),
+            (columns.segment_new_value, segment.new_value),
+            (columns.old_found, flag(segment.old_found)),
+            (columns.new_found, flag(segment.new_found)),
+            (columns.same_value, flag(segment.same_value)),
+            (
+                columns.value_diff_inv,
+                inverse(segment.new_value - segment.old_value),
+            ),
+        ] {
+            assign(column, value)?;
+        }
+        for (i, &column) in columns.field.iter().enumerate() {
+            assign(column, flag(segment.field == i))?;
+        }
+
+        let nibble = &row.nibble;
+        let last_depth = (NUM_NIBBLES - 1) as u64;
+        for (column, value) in [
+            (columns.depth, known(nibble.depth as u64)),
+            (
+                columns.is_last_depth,
+                flag(nibble.depth as u64 == last_depth),
+            ),
+            (
+                columns.last_depth_inv,
+                difference_inverse(nibble.depth as u64, last_depth),
+            ),
+            (columns.nibble, known(nibble.nibble as u64)),
+            (columns.other_nibble, known(nibble.other_nibble as u64)),
+            (columns.is_odd, flag(nibble.depth % 2 == 1)),
+            (columns.key_acc, nibble.key_acc),
+            (columns.is_same, flag(nibble.is_same)),
+            (
+                columns.diverged_inv,
+                difference_inverse(nibble.other_nibble as u64, nibble.nibble as u64),
+            ),
+        ] {
+            assign(column, value)?;
+        }
+
+        for (i, (lane_columns, lane)) in columns.lanes.iter().zip(row.lanes.iter()).enumerate() {
+            // The list header of the node, at its end
+            let len = if row.is_end[i] { lane.rlp_len } else { 0 };
+            let is_end = row.is_end[i];
+            for (column, value) in [
+                (lane_columns.is_branch, flag(lane.is_branch)),
+                (lane_columns.is_extension, flag(lane.is_extension)),
+                (lane_columns.is_leaf, flag(lane.is_leaf)),
+                (lane_columns.is_first, flag(lane.is_first)),
+                (lane_columns.is_last, flag(lane.is_last)),
+                (lane_columns.is_high, flag(lane.is_high)),
+                (lane_columns.is_single, flag(lane.is_first && lane.is_last)),
+                (lane_columns.hash, lane.hash),
+                (lane_columns.ptr, lane.ptr),
+                (lane_columns.rlp_acc, lane.rlp_acc),
+                (lane_columns.rlp_len, known(lane.rlp_len)),
+                (lane_columns.rlp_pow, lane.rlp_pow),
+                (lane_columns.num_children, known(lane.num_children)),
+                (lane_columns.next_tag, known(lane.next_tag)),
+                (lane_columns.rem_len, known(lane.rem_len)),
+                (lane_columns.in_row, flag(row.in_lanes[i])),
+                (lane_columns.is_end, flag(is_end)),
+                (lane_columns.len_hi, known(len / 256)),
+                (lane_columns.len_lo, known(len % 256)),
+                (
+                    lane_columns.is_short_header,
+                    flag(is_end && len <= RLP_MAX_SHORT_LEN),
+                ),
+                (lane_columns.is_long_header, flag(is_end && len >= 256)),
+            ] {
+                assign(column, value)?;
+            }
+        }
+        for (key_lane_columns, lane) in columns.key_lanes.iter().zip(row.lanes.iter()) {
+            for (column, value) in [
+                (key_lane_columns.is_diverged, flag(lane.is_diverged)),
+                (key_lane_columns.diverged_start, flag(lane.diverged_start)),
+                (key_lane_columns.key_child_empty, flag(lane.key_child_empty)),
+            ] {
+                assign(column, value)?;
+            }
+        }
+
+        for (&column, &byte) in columns.bytes.iter().zip(row.bytes.iter()) {
+            assign(column, known(byte as u64))?;
+        }
+        let is_child = row.kind == RowKind::Child;
+        let index = row.index as u64;
+        let last_child = (NUM_CHILDREN - 1) as u64;
+        for (column, value) in [
+            (columns.index, known(index)),
+            (columns.is_dup, flag(row.is_dup)),
+            (columns.is_empty, flag(row.is_empty)),
+            (
+                columns.is_key_index,
+                flag(is_child && index == nibble.nibble as u64),
+            ),
+            (
+                columns.is_other_index,
+                flag(is_child && index == nibble.other_nibble as u64),
+            ),
+            (columns.is_last_child, flag(is_child && index == last_child)),
+            (
+                columns.key_index_inv,
+                difference_inverse(index, nibble.nibble as u64),
+            ),
+            (
+                columns.other_index_inv,
+                difference_inverse(index, nibble.other_nibble as u64),
+            ),
+            (
+                columns.last_child_inv,
+                difference_inverse(index, last_child),
+            ),
+            (columns.is_dup_extension, flag(row.is_dup_extension)),
+        ] {
+            assign(column, value)?;
+        }
+
+        // The value rows have a tag, and the others none.
+        let (leading_zeros, is_short) = if row.kind == RowKind::Value {
+            value_layout(row.value_tag, &row.bytes)
+        } else {
+            (0, false)
+        };
+        let first_byte = row.bytes.get(leading_zeros).copied().unwrap_or(0);
+        let value = if row.kind != RowKind::Value {
+            Value::known(F::zero())
+        } else if row.value_tag == NONCE {
+            known(u64::from_be_bytes(
+                row.bytes[NUM_BYTES_PER_WORD - NUM_BYTES_PER_NONCE..]
+                    .try_into()
+                    .expect("nonce has 8 bytes"),
+            ))
+        } else {
+            rlc_be(&row.bytes, challenges.evm_word())
+        };
+        for (i, &column) in columns.value_tag.iter().enumerate() {
+            assign(column, flag(row.value_tag == i + 1))?;
+        }
+        for (i, &column) in columns.leading_zeros.iter().enumerate() {
+            assign(column, flag(i < leading_zeros))?;
+        }
+        for (column, value) in [
+            (
+                columns.first_byte_inv,
+                difference_inverse(first_byte as u64, 0),
+            ),
+            (columns.is_short_value, flag(is_short)),
+            (columns.item, row.item),
+            (columns.item_len, known(row.item_len)),
+            (columns.item_pow, row.item_pow),
+            (columns.value, value),
+            (columns.new_value_inv, inverse(segment.new_value)),
+        ] {
+            assign(column, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// MPT Circuit for proving the updates of the MPT table against the keccak
+/// hashes of the trie nodes
+#[derive(Clone, Default, Debug)]
+pub struct MptCircuit<F: Field> {
+    /// Maximum number of rows of the circuit, or 0 to fit the updates
+    pub max_mpt_rows: usize,
+    /// The MPT updates, with their proofs
+    pub mpt_updates: MptUpdates,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MptCircuit<F> {
+    /// Return a new MptCircuit
+    pub fn new(max_mpt_rows: usize, mpt_updates: MptUpdates) -> Self {
+        Self {
+            max_mpt_rows,
+            mpt_updates,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove the updates, which
+    /// only counts the byte range table when they don't have proofs.
+    pub fn min_num_rows(mpt_updates: &MptUpdates) -> usize {
+        let challenges = Challenges::mock(Value::unknown(), Value::unknown(), Value::unknown());
+        let num_rows = mpt_updates
+            .proofs()
+            .and_then(|proofs| mpt_rows::<F>(&proofs, &challenges).ok())
+            .map_or(0, |rows| rows.len());
+        // The rows are followed by a padding row, and the byte range table
+        // has a row per byte value.
+        std::cmp::max(1 << 8, num_rows + 1)
+    }
+}
+
+impl<F: Field> SubCircuit<F> for MptCircuit<F> {
+    type Config = MptCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_mpt_rows,
+            block.mpt_updates.clone(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(&block.mpt_updates),
+            block.circuits_params.max_mpt_rows,
+        )
+    }
+
+    /// Make the assignments to the MptCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let proofs = self.mpt_updates.proofs().ok_or_else(|| {
+            error!("mpt updates without proofs can't be proven");
+            Error::Synthesis
+        })?;
+        let rows = mpt_rows(&proofs, challenges).map_err(|err| {
+            error!("mpt updates with unsupported proofs: {:?}", err);
+            Error::Synthesis
+        })?;
+        // The last row is a padding row.
+        let num_rows = if self.max_mpt_rows == 0 {
+            rows.len() + 1
+        } else {
+            self.max_mpt_rows
+        };
+        if rows.len() + 1 > num_rows {
+            error!(
+                "mpt rows = {} > max_mpt_rows = {}",
+                rows.len() + 1,
+                self.max_mpt_rows
+            );
+            return Err(Error::Synthesis);
+        }
+
+        config.load_aux_tables(layouter)?;
+        layouter.assign_region(
+            || "mpt circuit",
+            |mut region| {
+                let padding = MptRow::default();
+                for offset in 0..num_rows {
+                    region.assign_fixed(
+                        || format!("mpt q_enable {}", offset),
+                        config.q_enable,
+                        offset,
+                        || Value::known(F::one()),
+                    )?;
+                    region.assign_fixed(
+                        || format!("mpt q_first {}", offset),
+                        config.q_first,
+                        offset,
+                        || Value::known(F::from((offset == 0) as u64)),
+                    )?;
+                    region.assign_fixed(
+                        || format!("mpt q_last {}", offset),
+                        config.q_last,
+                        offset,
+                        || Value::known(F::from((offset == num_rows - 1) as u64)),
+                    )?;
+                    let row = rows.get(offset).unwrap_or(&padding);
+                    config.assign_row(&mut region, offset, row, challenges)?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
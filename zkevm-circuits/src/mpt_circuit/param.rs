@@ -0,0 +1,45 @@
+pub(crate) const MAX_DEGREE: usize = 9;
+
+// Lanes of the nodes on the path of the key: in the trie before and after the
+// update, and the sibling of the key which is moved by an insertion or a
+// deletion.
+pub(crate) const OLD: usize = 0;
+pub(crate) const NEW: usize = 1;
+pub(crate) const SIBLING: usize = 2;
+pub(crate) const NUM_LANES: usize = 3;
+// Lanes following the key, which are the old and the new ones
+pub(crate) const NUM_KEY_LANES: usize = 2;
+
+pub(crate) const NUM_NIBBLES: usize = 64;
+pub(crate) const NUM_CHILDREN: usize = 16;
+pub(crate) const NUM_BYTES_PER_WORD: usize = 32;
+pub(crate) const NUM_BYTES_PER_ADDRESS: usize = 20;
+// A nonce takes up to 8 bytes.
+pub(crate) const NUM_BYTES_PER_NONCE: usize = 8;
+pub(crate) const NUM_ROW_KINDS: usize = 6;
+pub(crate) const NUM_PROOF_TYPES: usize = 7;
+
+// Fields of the values of the leaves, in their order in the account, followed
+// by the value of a storage slot.  The field 0 is the one of the proofs of non
+// existing accounts, which have no value.
+pub(crate) const NO_FIELD: usize = 0;
+pub(crate) const NONCE: usize = 1;
+pub(crate) const BALANCE: usize = 2;
+pub(crate) const STORAGE_ROOT: usize = 3;
+pub(crate) const CODE_HASH: usize = 4;
+pub(crate) const STORAGE_VALUE: usize = 5;
+pub(crate) const NUM_FIELDS: usize = 6;
+// Value rows are tagged by their field, which is never `NO_FIELD`.
+pub(crate) const NUM_VALUE_TAGS: usize = NUM_FIELDS - 1;
+
+// RLP prefixes
+pub(crate) const RLP_SHORT_STRING: u64 = 0x80;
+pub(crate) const RLP_LONG_STRING: u64 = 0xb7;
+pub(crate) const RLP_SHORT_LIST: u64 = 0xc0;
+pub(crate) const RLP_LONG_LIST: u64 = 0xf7;
+pub(crate) const RLP_HASH: u64 = RLP_SHORT_STRING + NUM_BYTES_PER_WORD as u64;
+// Lists up to this length have their length in their prefix.
+pub(crate) const RLP_MAX_SHORT_LEN: u64 = 55;
+// Flags of the first nibble of the compact encoding of the path of a node
+pub(crate) const HP_LEAF_FLAG: u64 = 0x20;
+pub(crate) const HP_ODD_FLAG: u64 = 0x10;
@@ -0,0 +1,322 @@
+#![allow(unused_imports)]
+use super::*;
+use crate::{
+    table::AccountFieldTag,
+    util::{log2_ceil, unusable_rows},
+    witness::Rw,
+};
+use eth_types::{
+    mpt::{storage_value_rlp, PartialTrie, TrieAccount},
+    Address, EIP1186ProofResponse, StorageProof, ToBigEndian, ToWord, Word, H256,
+};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn mpt_circuit_unusable_rows() {
+    assert_eq!(
+        MptCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, MptCircuit::<Fr>>(),
+    )
+}
+
+/// An account of the prestate, with its storage slots
+struct TestAccount {
+    address: Address,
+    nonce: u64,
+    balance: u64,
+    code_hash: H256,
+    storage: Vec<(u64, u64)>,
+}
+
+impl TestAccount {
+    fn new(address: u64, balance: u64) -> Self {
+        Self {
+            address: Address::from_low_u64_be(address),
+            nonce: 0,
+            balance,
+            code_hash: *EMPTY_CODE_HASH,
+            storage: vec![],
+        }
+    }
+}
+
+/// Return the state root of the accounts and their proofs, with the proofs of
+/// all their slots.
+fn prestate(accounts: &[TestAccount]) -> (Word, Vec<EIP1186ProofResponse>) {
+    let no_nodes = Vec::<Vec<u8>>::new;
+    let mut state_trie = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+    let mut storage_tries = vec![];
+    for account in accounts.iter() {
+        let mut storage_trie = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        for &(key, value) in account.storage.iter() {
+            storage_trie
+                .insert(
+                    &Word::from(key).to_be_bytes(),
+                    storage_value_rlp(value.into()),
+                )
+                .unwrap();
+        }
+        let trie_account = TrieAccount {
+            nonce: account.nonce.into(),
+            balance: account.balance.into(),
+            storage_root: storage_trie.root(),
+            code_hash: account.code_hash,
+        };
+        state_trie
+            .insert(account.address.as_bytes(), trie_account.rlp_bytes())
+            .unwrap();
+        storage_tries.push(storage_trie);
+    }
+    let proofs = accounts
+        .iter()
+        .zip(storage_tries.iter())
+        .map(|(account, storage_trie)| EIP1186ProofResponse {
+            address: account.address,
+            balance: account.balance.into(),
+            code_hash: account.code_hash,
+            nonce: account.nonce.into(),
+            storage_hash: storage_trie.root(),
+            account_proof: state_trie.prove(account.address.as_bytes()).unwrap(),
+            storage_proof: account
+                .storage
+                .iter()
+                .map(|&(key, value)| StorageProof {
+                    key: key.into(),
+                    value: value.into(),
+                    proof: storage_trie.prove(&Word::from(key).to_be_bytes()).unwrap(),
+                })
+                .collect(),
+        })
+        .collect();
+    (state_trie.root().to_word(), proofs)
+}
+
+fn account_row(address: u64, field_tag: AccountFieldTag, value_prev: Word, value: Word) -> Rw {
+    Rw::Account {
+        rw_counter: 1,
+        is_write: true,
+        account_address: Address::from_low_u64_be(address),
+        field_tag,
+        value,
+        value_prev,
+    }
+}
+
+fn storage_row(address: u64, storage_key: u64, value_prev: u64, value: u64) -> Rw {
+    Rw::AccountStorage {
+        rw_counter: 1,
+        is_write: true,
+        account_address: Address::from_low_u64_be(address),
+        storage_key: storage_key.into(),
+        value: value.into(),
+        value_prev: value_prev.into(),
+        tx_id: 1,
+        committed_value: value_prev.into(),
+    }
+}
+
+fn storage_clear_row(address: u64) -> Rw {
+    Rw::AccountStorageClear {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 1,
+        account_address: Address::from_low_u64_be(address),
+    }
+}
+
+fn run(accounts: &[TestAccount], rows: &[Rw]) -> Result<(), Vec<VerifyFailure>> {
+    let (old_root, proofs) = prestate(accounts);
+    let mpt_updates = MptUpdates::from_proofs(rows, old_root, &proofs, &[]).unwrap();
+    let k =
+        log2_ceil(MptCircuit::<Fr>::unusable_rows() + MptCircuit::<Fr>::min_num_rows(&mpt_updates));
+    let circuit = MptCircuit::<Fr>::new(0, mpt_updates);
+    let prover = match MockProver::run(k, &circuit, vec![]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+fn accounts() -> Vec<TestAccount> {
+    vec![
+        TestAccount {
+            nonce: 3,
+            code_hash: H256::repeat_byte(0xaa),
+            storage: vec![(1, 5), (2, 0x1234), (3, 1 << 40)],
+            ..TestAccount::new(1, 100)
+        },
+        TestAccount::new(2, 7),
+        TestAccount::new(3, 1 << 60),
+    ]
+}
+
+#[test]
+fn mpt_circuit_account_updates() {
+    let rows = [
+        account_row(1, AccountFieldTag::Nonce, 3.into(), 4.into()),
+        account_row(2, AccountFieldTag::Balance, 7.into(), 1000.into()),
+        account_row(3, AccountFieldTag::Balance, (1u64 << 60).into(), 0.into()),
+        account_row(
+            2,
+            AccountFieldTag::CodeHash,
+            EMPTY_CODE_HASH.to_word(),
+            Word::from(0xbb),
+        ),
+    ];
+    assert_eq!(run(&accounts(), &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_unchanged_account() {
+    let rows = [
+        account_row(1, AccountFieldTag::Nonce, 3.into(), 3.into()),
+        account_row(
+            3,
+            AccountFieldTag::Balance,
+            (1u64 << 60).into(),
+            (1u64 << 60).into(),
+        ),
+    ];
+    assert_eq!(run(&accounts(), &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_account_insertion_and_deletion() {
+    let rows = [
+        account_row(
+            4,
+            AccountFieldTag::CodeHash,
+            Word::zero(),
+            EMPTY_CODE_HASH.to_word(),
+        ),
+        account_row(5, AccountFieldTag::Balance, Word::zero(), 10.into()),
+        account_row(
+            2,
+            AccountFieldTag::CodeHash,
+            EMPTY_CODE_HASH.to_word(),
+            Word::zero(),
+        ),
+    ];
+    assert_eq!(run(&accounts(), &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_non_existing_account() {
+    let rows = [account_row(
+        6,
+        AccountFieldTag::NonExisting,
+        Word::zero(),
+        Word::zero(),
+    )];
+    assert_eq!(run(&accounts(), &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_storage_updates() {
+    let rows = [
+        // Modification
+        storage_row(1, 1, 5, 6),
+        // Insertion
+        storage_row(1, 4, 0, 0x80),
+        // Deletion
+        storage_row(1, 2, 0x1234, 0),
+        // Insertion in an empty storage trie
+        storage_row(2, 1, 0, 1),
+    ];
+    assert_eq!(run(&accounts(), &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_non_existing_storage() {
+    let rows = [storage_row(1, 7, 0, 0), storage_row(3, 1, 0, 0)];
+    assert_eq!(run(&accounts(), &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_storage_clear() {
+    let rows = [
+        // The slots which aren't accessed are cleared along with the others.
+        storage_row(1, 1, 5, 6),
+        storage_clear_row(1),
+        // Empty storage trie
+        storage_clear_row(2),
+        // Non existing account
+        storage_clear_row(6),
+    ];
+    assert_eq!(run(&accounts(), &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_large_tries() {
+    // Enough keys for extensions and branches deep in the tries
+    let mut accounts: Vec<_> = (1..=300).map(|i| TestAccount::new(i, i * 11)).collect();
+    accounts[0].storage = (1..=300).map(|key| (key, key * 13)).collect();
+    let rows = [
+        account_row(10, AccountFieldTag::Balance, 110.into(), 111.into()),
+        account_row(400, AccountFieldTag::Balance, Word::zero(), 1.into()),
+        account_row(
+            20,
+            AccountFieldTag::CodeHash,
+            EMPTY_CODE_HASH.to_word(),
+            Word::zero(),
+        ),
+        account_row(
+            500,
+            AccountFieldTag::NonExisting,
+            Word::zero(),
+            Word::zero(),
+        ),
+        storage_row(1, 10, 130, 131),
+        storage_row(1, 400, 0, 1),
+        storage_row(1, 20, 260, 0),
+        storage_row(1, 500, 0, 0),
+    ];
+    assert_eq!(run(&accounts, &rows), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_wrong_old_value() {
+    let rows = [account_row(
+        2,
+        AccountFieldTag::Balance,
+        8.into(),
+        1000.into(),
+    )];
+    assert!(run(&accounts(), &rows).is_err());
+}
+
+#[test]
+fn mpt_circuit_wrong_old_storage_value() {
+    let rows = [storage_row(1, 1, 4, 6)];
+    assert!(run(&accounts(), &rows).is_err());
+}
+
+#[test]
+fn mpt_circuit_existing_account_proven_non_existing() {
+    let rows = [account_row(
+        2,
+        AccountFieldTag::NonExisting,
+        Word::zero(),
+        Word::zero(),
+    )];
+    assert!(run(&accounts(), &rows).is_err());
+}
+
+#[test]
+fn mpt_circuit_too_many_rows() {
+    let (old_root, proofs) = prestate(&accounts());
+    let rows = [account_row(
+        2,
+        AccountFieldTag::Balance,
+        7.into(),
+        1000.into(),
+    )];
+    let mpt_updates = MptUpdates::from_proofs(&rows, old_root, &proofs, &[]).unwrap();
+    let k =
+        log2_ceil(MptCircuit::<Fr>::unusable_rows() + MptCircuit::<Fr>::min_num_rows(&mpt_updates));
+    let circuit = MptCircuit::<Fr>::new(10, mpt_updates);
+    assert!(MockProver::run(k, &circuit, vec![]).is_err());
+}
@@ -0,0 +1,962 @@
+//! The rows of the MPT circuit, laid out from the proofs of the updates.
+
+use super::param::*;
+use crate::{
+    evm_circuit::util::rlc,
+    table::MPTProofType,
+    util::Challenges,
+    witness::{MptUpdate, MptUpdateProof, MptUpdateRow, TriePath, TrieProof},
+};
+use eth_types::{
+    mpt::{key_path, storage_value_from_rlp, ProofNode, TrieAccount, EMPTY_TRIE_ROOT},
+    Error, Field, ToBigEndian, H256,
+};
+use ethers_core::utils::keccak256;
+use halo2_proofs::circuit::Value;
+use std::rc::Rc;
+
+/// The kind of a row
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum RowKind {
+    /// First row of a segment, with the key
+    Key,
+    /// Row of a depth of the path, with the nibble of the key at this depth
+    Nibble,
+    /// Row of a child of the branches at the depth of the nibble row before
+    Child,
+    /// Row of the child of the extensions ending at the depth of the nibble
+    /// row before
+    ExtensionChild,
+    /// Row of a field of the values of the leaves
+    Value,
+    /// Row after the segments
+    #[default]
+    Padding,
+}
+
+/// The node of a lane at a depth and the RLP encoding accumulated so far
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LaneState<F: Field> {
+    pub(crate) is_branch: bool,
+    pub(crate) is_extension: bool,
+    pub(crate) is_leaf: bool,
+    pub(crate) is_first: bool,
+    pub(crate) is_last: bool,
+    pub(crate) is_high: bool,
+    pub(crate) is_diverged: bool,
+    pub(crate) diverged_start: bool,
+    pub(crate) hash: Value<F>,
+    pub(crate) ptr: Value<F>,
+    pub(crate) rlp_acc: Value<F>,
+    pub(crate) rlp_len: u64,
+    pub(crate) rlp_pow: Value<F>,
+    pub(crate) num_children: u64,
+    pub(crate) next_tag: u64,
+    pub(crate) rem_len: u64,
+    pub(crate) key_child_empty: bool,
+}
+
+impl<F: Field> Default for LaneState<F> {
+    fn default() -> Self {
+        Self {
+            is_branch: false,
+            is_extension: false,
+            is_leaf: false,
+            is_first: false,
+            is_last: false,
+            is_high: false,
+            is_diverged: false,
+            diverged_start: false,
+            hash: Value::known(F::zero()),
+            ptr: Value::known(F::zero()),
+            rlp_acc: Value::known(F::zero()),
+            rlp_len: 0,
+            rlp_pow: Value::known(F::zero()),
+            num_children: 0,
+            next_tag: 0,
+            rem_len: 0,
+            key_child_empty: false,
+        }
+    }
+}
+
+/// The update proven by the rows
+#[derive(Clone, Debug)]
+pub(crate) struct ProofState<F: Field> {
+    pub(crate) proof_type: Option<MPTProofType>,
+    pub(crate) update: MptUpdateRow<Value<F>>,
+}
+
+impl<F: Field> Default for ProofState<F> {
+    fn default() -> Self {
+        Self {
+            proof_type: None,
+            update: MptUpdateRow([Value::known(F::zero()); 7]),
+        }
+    }
+}
+
+/// The segment of the rows, proving a key in a trie
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct SegmentState<F: Field> {
+    pub(crate) is_second: bool,
+    pub(crate) is_storage: bool,
+    pub(crate) key_hash: Value<F>,
+    pub(crate) old_root: Value<F>,
+    pub(crate) new_root: Value<F>,
+    pub(crate) old_value: Value<F>,
+    pub(crate) new_value: Value<F>,
+    pub(crate) old_found: bool,
+    pub(crate) new_found: bool,
+    pub(crate) same_value: bool,
+    pub(crate) field: usize,
+}
+
+impl<F: Field> Default for SegmentState<F> {
+    fn default() -> Self {
+        Self {
+            is_second: false,
+            is_storage: false,
+            key_hash: Value::known(F::zero()),
+            old_root: Value::known(F::zero()),
+            new_root: Value::known(F::zero()),
+            old_value: Value::known(F::zero()),
+            new_value: Value::known(F::zero()),
+            old_found: false,
+            new_found: false,
+            same_value: false,
+            field: NO_FIELD,
+        }
+    }
+}
+
+/// The depth of the rows in the path of the key
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct NibbleState<F: Field> {
+    pub(crate) depth: usize,
+    pub(crate) nibble: u8,
+    pub(crate) other_nibble: u8,
+    pub(crate) key_acc: Value<F>,
+    pub(crate) is_same: bool,
+}
+
+impl<F: Field> Default for NibbleState<F> {
+    fn default() -> Self {
+        Self {
+            depth: 0,
+            nibble: 0,
+            other_nibble: 0,
+            key_acc: Value::known(F::zero()),
+            is_same: false,
+        }
+    }
+}
+
+/// A row of the MPT circuit, with the state carried from the rows before it
+/// and the values of its own kind.
+#[derive(Clone, Debug)]
+pub(crate) struct MptRow<F: Field> {
+    pub(crate) kind: RowKind,
+    pub(crate) proof: ProofState<F>,
+    pub(crate) segment: SegmentState<F>,
+    pub(crate) nibble: NibbleState<F>,
+    pub(crate) lanes: [LaneState<F>; NUM_LANES],
+    pub(crate) in_lanes: [bool; NUM_LANES],
+    pub(crate) is_end: [bool; NUM_LANES],
+    pub(crate) bytes: [u8; NUM_BYTES_PER_WORD],
+    pub(crate) index: u8,
+    pub(crate) is_dup: bool,
+    pub(crate) is_empty: bool,
+    pub(crate) is_dup_extension: bool,
+    pub(crate) value_tag: usize,
+    pub(crate) item: Value<F>,
+    pub(crate) item_len: u64,
+    pub(crate) item_pow: Value<F>,
+}
+
+impl<F: Field> Default for MptRow<F> {
+    fn default() -> Self {
+        Self {
+            kind: RowKind::default(),
+            proof: ProofState::default(),
+            segment: SegmentState::default(),
+            nibble: NibbleState::default(),
+            lanes: Default::default(),
+            in_lanes: [false; NUM_LANES],
+            is_end: [false; NUM_LANES],
+            bytes: [0; NUM_BYTES_PER_WORD],
+            index: 0,
+            is_dup: false,
+            is_empty: false,
+            is_dup_extension: false,
+            value_tag: 0,
+            item: Value::known(F::zero()),
+            item_len: 0,
+            item_pow: Value::known(F::zero()),
+        }
+    }
+}
+
+impl<F: Field> MptRow<F> {
+    /// Whether the row is the first one of the proof of an update, which is
+    /// the one in the MPT table
+    pub(crate) fn is_summary(&self) -> bool {
+        self.kind == RowKind::Key && !self.segment.is_second
+    }
+}
+
+/// The node of a lane at a depth.  Extensions and leaves span a depth per
+/// nibble of their path.
+#[derive(Clone, Debug)]
+struct Position {
+    node: Rc<ProofNode>,
+    start: usize,
+    is_first: bool,
+    is_last: bool,
+    is_diverged: bool,
+    is_high: bool,
+    nibble: u8,
+}
+
+impl Position {
+    fn branch(node: Rc<ProofNode>, depth: usize) -> Self {
+        Self {
+            node,
+            start: depth,
+            is_first: false,
+            is_last: false,
+            is_diverged: false,
+            is_high: false,
+            nibble: 0,
+        }
+    }
+}
+
+fn is_branch(position: &Option<Position>) -> bool {
+    matches!(position, Some(position) if matches!(*position.node, ProofNode::Branch(_)))
+}
+
+fn is_found(position: &Option<Position>) -> bool {
+    matches!(
+        position,
+        Some(position) if matches!(*position.node, ProofNode::Leaf(..)) && !position.is_diverged
+    )
+}
+
+/// Decode the nodes of a path, with their depths.
+fn decode_path(path: &TriePath) -> Result<Vec<(usize, Rc<ProofNode>)>, Error> {
+    let mut depth = 0;
+    path.nodes
+        .iter()
+        .map(|node| {
+            let node = ProofNode::decode(node)?;
+            let node_depth = depth;
+            depth += match &node {
+                ProofNode::Branch(_) => 1,
+                ProofNode::Extension(path, _) | ProofNode::Leaf(path, _) => path.len(),
+            };
+            Ok((node_depth, Rc::new(node)))
+        })
+        .collect()
+}
+
+/// Return the position of the nodes of a path at each depth.
+fn positions(nodes: &[(usize, Rc<ProofNode>)], key: &[u8]) -> Result<Vec<Option<Position>>, Error> {
+    let mut positions = vec![None; NUM_NIBBLES];
+    for (depth, node) in nodes.iter() {
+        let depth = *depth;
+        match node.as_ref() {
+            ProofNode::Branch(_) => {
+                *positions.get_mut(depth).ok_or(Error::InvalidTrieNode)? =
+                    Some(Position::branch(node.clone(), depth));
+            }
+            ProofNode::Extension(path, _) | ProofNode::Leaf(path, _) => {
+                if path.is_empty() || depth + path.len() > NUM_NIBBLES {
+                    return Err(Error::InvalidTrieNode);
+                }
+                for (j, nibble) in path.iter().enumerate() {
+                    positions[depth + j] = Some(Position {
+                        node: node.clone(),
+                        start: depth,
+                        is_first: j == 0,
+                        is_last: j == path.len() - 1,
+                        is_diverged: path[..=j] != key[depth..=depth + j],
+                        is_high: (path.len() - j) % 2 == 0,
+                        nibble: *nibble,
+                    });
+                }
+            }
+        }
+    }
+    Ok(positions)
+}
+
+/// Return the depth from which the old and the new paths differ, at the first
+/// node which changes of kind or of path, or `NUM_NIBBLES` if they don't.
+fn split_depth(old: &[(usize, Rc<ProofNode>)], new: &[(usize, Rc<ProofNode>)]) -> usize {
+    for i in 0.. {
+        match (old.get(i), new.get(i)) {
+            (None, None) => break,
+            (Some((depth, _)), None) | (None, Some((depth, _))) => return *depth,
+            (Some((depth, old)), Some((_, new))) => {
+                let same = match (old.as_ref(), new.as_ref()) {
+                    (ProofNode::Branch(_), ProofNode::Branch(_)) => true,
+                    (ProofNode::Extension(old, _), ProofNode::Extension(new, _))
+                    | (ProofNode::Leaf(old, _), ProofNode::Leaf(new, _)) => old == new,
+                    _ => false,
+                };
+                if !same {
+                    return *depth;
+                }
+            }
+        }
+    }
+    NUM_NIBBLES
+}
+
+/// Return the positions of the sibling lane.  When a key is deleted from a
+/// branch with two children, the other child takes the place of the branch,
+/// its path extended by the nibble of its index.  When a key is inserted, the
+/// node it diverges from is moved down to a new branch.  The sibling lane is
+/// the node below the branch, while the old or the new lane is the node
+/// without the branch.
+fn sibling_positions(
+    proof: &TrieProof,
+    old: &[Option<Position>],
+    new: &[Option<Position>],
+    split: usize,
+) -> Result<Vec<Option<Position>>, Error> {
+    let mut positions = vec![None; NUM_NIBBLES];
+    let depth = match (split..NUM_NIBBLES)
+        .find(|&depth| is_branch(&old[depth]) != is_branch(&new[depth]))
+    {
+        Some(depth) => depth,
+        None => return Ok(positions),
+    };
+    let (long, short, path, is_insertion) = if is_branch(&old[depth]) {
+        (old, new, &proof.old, false)
+    } else {
+        (new, old, &proof.new, true)
+    };
+    let position = short[depth].as_ref().ok_or(Error::InvalidTrieNode)?;
+    let diverges_here = depth == 0 || !matches!(&short[depth - 1], Some(prev) if prev.is_diverged);
+    if !position.is_diverged || !(position.is_first || diverges_here) {
+        return Err(Error::InvalidTrieNode);
+    }
+    // The sibling is only needed when it continues the path of the short lane,
+    // or as the branch the deleted key was in.
+    if position.is_last && is_insertion {
+        return Ok(positions);
+    }
+    let hash = match long[depth].as_ref().map(|long| long.node.as_ref()) {
+        Some(ProofNode::Branch(children)) => children[position.nibble as usize],
+        _ => None,
+    };
+    let sibling = path.sibling.as_ref().ok_or(Error::InvalidTrieNode)?;
+    if hash != Some(H256(keccak256(sibling))) {
+        return Err(Error::InvalidTrieNode);
+    }
+    let sibling = Rc::new(ProofNode::decode(sibling)?);
+    if position.is_last {
+        if !matches!(*sibling, ProofNode::Branch(_)) || depth + 1 == NUM_NIBBLES {
+            return Err(Error::InvalidTrieNode);
+        }
+        positions[depth + 1] = Some(Position::branch(sibling, depth + 1));
+    } else {
+        for t in depth + 1..NUM_NIBBLES {
+            match &short[t] {
+                Some(next) if next.start == position.start => {
+                    positions[t] = Some(Position {
+                        node: sibling.clone(),
+                        start: depth + 1,
+                        is_first: t == depth + 1,
+                        ..next.clone()
+                    })
+                }
+                _ => break,
+            }
+        }
+    }
+    Ok(positions)
+}
+
+/// Return the fields of the value of a leaf, as 32 bytes words.
+fn leaf_fields(
+    is_storage: bool,
+    position: &Option<Position>,
+) -> Result<Vec<(usize, [u8; NUM_BYTES_PER_WORD])>, Error> {
+    let value = match position.as_ref().map(|position| position.node.as_ref()) {
+        Some(ProofNode::Leaf(_, value)) => value,
+        _ => return Err(Error::InvalidTrieNode),
+    };
+    Ok(if is_storage {
+        vec![(STORAGE_VALUE, storage_value_from_rlp(value)?.to_be_bytes())]
+    } else {
+        let account = TrieAccount::from_rlp(value)?;
+        vec![
+            (NONCE, account.nonce.to_be_bytes()),
+            (BALANCE, account.balance.to_be_bytes()),
+            (STORAGE_ROOT, account.storage_root.0),
+            (CODE_HASH, account.code_hash.0),
+        ]
+    })
+}
+
+/// Return the number of leading zero bytes of a value row, which has none for
+/// the hashes, and whether the value is a number encoded as a single byte.
+pub(crate) fn value_layout(tag: usize, bytes: &[u8; NUM_BYTES_PER_WORD]) -> (usize, bool) {
+    let leading_zeros = if tag == STORAGE_ROOT || tag == CODE_HASH {
+        0
+    } else {
+        bytes.iter().take_while(|&&byte| byte == 0).count()
+    };
+    let is_short = leading_zeros == NUM_BYTES_PER_WORD - 1
+        && bytes[NUM_BYTES_PER_WORD - 1] < RLP_SHORT_STRING as u8;
+    (leading_zeros, is_short)
+}
+
+/// The RLC of bytes in big endian order, as for the keccak inputs and the
+/// words.
+pub(crate) fn rlc_be<F: Field>(bytes: &[u8], randomness: Value<F>) -> Value<F> {
+    randomness.map(|randomness| rlc::value(bytes.iter().rev(), randomness))
+}
+
+fn known<F: Field>(value: u64) -> Value<F> {
+    Value::known(F::from(value))
+}
+
+fn power<F: Field>(randomness: Value<F>, exponent: usize) -> Value<F> {
+    randomness.map(|randomness| randomness.pow(&[exponent as u64, 0, 0, 0]))
+}
+
+/// Return the RLP item of a value row in the value of its leaf, as its RLC,
+/// length and the power of the randomness of its length.
+fn value_item<F: Field>(
+    tag: usize,
+    bytes: &[u8; NUM_BYTES_PER_WORD],
+    randomness: Value<F>,
+) -> (Value<F>, u64, Value<F>) {
+    let (leading_zeros, is_short) = value_layout(tag, bytes);
+    let len = NUM_BYTES_PER_WORD - leading_zeros;
+    let last_byte = known(bytes[NUM_BYTES_PER_WORD - 1] as u64);
+    let bytes_rlc = rlc_be(bytes, randomness);
+    let len_power = power(randomness, len);
+    match tag {
+        STORAGE_ROOT | CODE_HASH => (
+            known(RLP_HASH) * power(randomness, NUM_BYTES_PER_WORD) + bytes_rlc,
+            NUM_BYTES_PER_WORD as u64 + 1,
+            power(randomness, NUM_BYTES_PER_WORD + 1),
+        ),
+        _ if is_short => (last_byte, 1, randomness),
+        STORAGE_VALUE => (
+            // The value of the slot is RLP encoded in the value of the leaf.
+            known(RLP_SHORT_STRING + 1 + len as u64) * len_power * randomness
+                + known(RLP_SHORT_STRING + len as u64) * len_power
+                + bytes_rlc,
+            len as u64 + 2,
+            len_power * randomness * randomness,
+        ),
+        _ if len == 0 => (known(RLP_SHORT_STRING), 1, randomness),
+        _ => (
+            known(RLP_SHORT_STRING + len as u64) * len_power + bytes_rlc,
+            len as u64 + 1,
+            len_power * randomness,
+        ),
+    }
+}
+
+fn root(path: &TriePath) -> H256 {
+    path.nodes
+        .first()
+        .map_or(*EMPTY_TRIE_ROOT, |node| H256(keccak256(node)))
+}
+
+/// Builder of the rows of the proofs of the updates, which carries the state
+/// of the last row.
+struct RowsBuilder<F: Field> {
+    rows: Vec<MptRow<F>>,
+    proof: ProofState<F>,
+    segment: SegmentState<F>,
+    nibble: NibbleState<F>,
+    lanes: [LaneState<F>; NUM_LANES],
+    keccak_input: Value<F>,
+    evm_word: Value<F>,
+}
+
+/// Return the rows proving the updates, or an error if their proofs aren't
+/// supported.
+pub(crate) fn mpt_rows<F: Field>(
+    updates: &[(&MptUpdate, &MptUpdateProof)],
+    challenges: &Challenges<Value<F>>,
+) -> Result<Vec<MptRow<F>>, Error> {
+    let mut builder = RowsBuilder {
+        rows: vec![],
+        proof: ProofState::default(),
+        segment: SegmentState::default(),
+        nibble: NibbleState::default(),
+        lanes: Default::default(),
+        keccak_input: challenges.keccak_input(),
+        evm_word: challenges.evm_word(),
+    };
+    for (update, proof) in updates {
+        builder.update(update, proof)?;
+    }
+    Ok(builder.rows)
+}
+
+impl<F: Field> RowsBuilder<F> {
+    fn push(&mut self, kind: RowKind) -> &mut MptRow<F> {
+        self.rows.push(MptRow {
+            kind,
+            proof: self.proof.clone(),
+            segment: self.segment,
+            nibble: self.nibble,
+            lanes: self.lanes,
+            ..Default::default()
+        });
+        self.rows.last_mut().expect("row is pushed")
+    }
+
+    // A storage update is proven in the storage trie of the account, in a
+    // first segment, and then in the state trie, in a second segment with the
+    // update of the storage root.  A storage clear is a single segment of the
+    // update of the storage root to the empty root.
+    fn update(&mut self, update: &MptUpdate, proof: &MptUpdateProof) -> Result<(), Error> {
+        let table = update.table_assignment(self.evm_word);
+        let (old_value, new_value) = (table.0[6], table.0[5]);
+        let proof_type = update.proof_type();
+        self.proof = ProofState {
+            proof_type: Some(proof_type),
+            update: table,
+        };
+        let field = match proof_type {
+            MPTProofType::NonceMod => NONCE,
+            MPTProofType::BalanceMod => BALANCE,
+            MPTProofType::CodeHashMod => CODE_HASH,
+            MPTProofType::NonExistingAccountProof => NO_FIELD,
+            MPTProofType::StorageMod | MPTProofType::NonExistingStorageProof => STORAGE_VALUE,
+            MPTProofType::StorageClear => STORAGE_ROOT,
+        };
+        if let Some([old_root, new_root]) = proof.storage_roots {
+            let evm_word = self.evm_word;
+            return self.segment(
+                &proof.account,
+                false,
+                false,
+                field,
+                [old_root, new_root].map(|root| rlc_be(&root.0, evm_word)),
+                old_root == new_root,
+            );
+        }
+        match &proof.storage {
+            Some(storage) => {
+                let is_unchanged = update.is_unchanged();
+                self.segment(
+                    storage,
+                    true,
+                    false,
+                    field,
+                    [old_value, new_value],
+                    is_unchanged,
+                )?;
+                let [old_root, new_root] = [&storage.old, &storage.new].map(root);
+                let evm_word = self.evm_word;
+                self.segment(
+                    &proof.account,
+                    false,
+                    true,
+                    STORAGE_ROOT,
+                    [old_root, new_root].map(|root| rlc_be(&root.0, evm_word)),
+                    old_root == new_root,
+                )?;
+            }
+            None => self.segment(
+                &proof.account,
+                false,
+                false,
+                field,
+                [old_value, new_value],
+                update.is_unchanged(),
+            )?,
+        }
+        Ok(())
+    }
+
+    fn segment(
+        &mut self,
+        proof: &TrieProof,
+        is_storage: bool,
+        is_second: bool,
+        field: usize,
+        [old_value, new_value]: [Value<F>; 2],
+        same_value: bool,
+    ) -> Result<(), Error> {
+        let e = self.evm_word;
+        let key = key_path(&proof.key);
+        let old_nodes = decode_path(&proof.old)?;
+        let new_nodes = decode_path(&proof.new)?;
+        let old = positions(&old_nodes, &key)?;
+        let new = positions(&new_nodes, &key)?;
+        let split = split_depth(&old_nodes, &new_nodes);
+        let sibling = sibling_positions(proof, &old, &new, split)?;
+        let positions = [old, new, sibling];
+        let [old_root, new_root] = [&proof.old, &proof.new].map(|path| rlc_be(&root(path).0, e));
+
+        // Key row
+        self.segment = SegmentState {
+            is_second,
+            is_storage,
+            key_hash: rlc_be(&keccak256(&proof.key), e),
+            old_root,
+            new_root,
+            old_value,
+            new_value,
+            old_found: is_found(&positions[OLD][NUM_NIBBLES - 1]),
+            new_found: is_found(&positions[NEW][NUM_NIBBLES - 1]),
+            same_value,
+            field,
+        };
+        self.nibble = NibbleState {
+            is_same: true,
+            ..Default::default()
+        };
+        self.lanes = Default::default();
+        for lane in self.lanes.iter_mut() {
+            lane.next_tag = if is_storage { STORAGE_VALUE } else { NONCE } as u64;
+        }
+        for (lane, (nodes, root)) in [(&old_nodes, old_root), (&new_nodes, new_root)]
+            .into_iter()
+            .enumerate()
+        {
+            self.lanes[lane].is_branch = true;
+            self.lanes[lane].key_child_empty = nodes.is_empty();
+            self.lanes[lane].ptr = root;
+        }
+        let row = self.push(RowKind::Key);
+        row.bytes[NUM_BYTES_PER_WORD - proof.key.len()..].copy_from_slice(&proof.key);
+
+        for depth in 0..NUM_NIBBLES {
+            self.nibble_row(&positions, &key, depth, split);
+            self.extension_child_rows(&positions, depth);
+            self.child_rows(&positions, &key, depth);
+        }
+        self.value_rows(&positions, is_storage)
+    }
+
+    fn nibble_row(
+        &mut self,
+        positions: &[Vec<Option<Position>>; NUM_LANES],
+        key: &[u8],
+        depth: usize,
+        split: usize,
+    ) {
+        let (r, e) = (self.keccak_input, self.evm_word);
+        let nibble = key[depth];
+        let key_acc = if depth % 2 == 1 {
+            self.nibble.key_acc + known(nibble as u64)
+        } else if depth == 0 {
+            known(16 * nibble as u64)
+        } else {
+            self.nibble.key_acc * e + known(16 * nibble as u64)
+        };
+        let other_nibble = [OLD, NEW]
+            .iter()
+            .filter_map(|&lane| positions[lane][depth].as_ref())
+            .filter(|position| {
+                !matches!(*position.node, ProofNode::Branch(_)) && position.is_diverged
+            })
+            .map(|position| position.nibble)
+            .last()
+            .unwrap_or(0);
+        self.nibble = NibbleState {
+            depth,
+            nibble,
+            other_nibble,
+            key_acc,
+            is_same: depth < split,
+        };
+
+        let prev = self.lanes;
+        for (i, lane) in self.lanes.iter_mut().enumerate() {
+            let position = positions[i][depth].as_ref();
+            let node = position.map(|position| position.node.as_ref());
+            lane.is_branch = matches!(node, Some(ProofNode::Branch(_)));
+            lane.is_extension = matches!(node, Some(ProofNode::Extension(..)));
+            lane.is_leaf = matches!(node, Some(ProofNode::Leaf(..)));
+            let is_path = lane.is_extension || lane.is_leaf;
+            let flag = |flag: fn(&Position) -> bool| is_path && position.map_or(false, flag);
+            lane.is_first = flag(|position| position.is_first);
+            lane.is_last = flag(|position| position.is_last);
+            lane.is_high = flag(|position| position.is_high);
+            lane.is_diverged = i != SIBLING && flag(|position| position.is_diverged);
+            lane.diverged_start = lane.is_diverged && (lane.is_first || !prev[i].is_diverged);
+            if lane.is_branch || lane.is_first {
+                lane.hash = prev[i].ptr;
+            }
+            if lane.is_branch {
+                lane.rlp_acc = known(0);
+                lane.rlp_len = 0;
+                lane.rlp_pow = known(1);
+                lane.num_children = 0;
+            } else if is_path {
+                let nibble = if i == SIBLING || lane.is_diverged {
+                    other_nibble
+                } else {
+                    nibble
+                } as u64;
+                let (acc, len, pow) = if lane.is_first {
+                    let prefix =
+                        HP_LEAF_FLAG * lane.is_leaf as u64 + HP_ODD_FLAG * !lane.is_high as u64;
+                    (known(prefix), 1, r)
+                } else {
+                    (prev[i].rlp_acc, prev[i].rlp_len, prev[i].rlp_pow)
+                };
+                let (mut acc, mut len, mut pow) = if lane.is_high {
+                    (acc * r + known(16 * nibble), len + 1, pow * r)
+                } else {
+                    (acc + known(nibble), len, pow)
+                };
+                // The string header of the compact encoding of a path of more
+                // than one byte
+                if lane.is_last && !lane.is_first {
+                    acc = acc + known(RLP_SHORT_STRING + prev[i].rlp_len) * prev[i].rlp_pow;
+                    len += 1;
+                    pow = pow * r;
+                }
+                lane.rlp_acc = acc;
+                lane.rlp_len = len;
+                lane.rlp_pow = pow;
+            }
+        }
+        self.push(RowKind::Nibble);
+    }
+
+    fn extension_child_rows(
+        &mut self,
+        positions: &[Vec<Option<Position>>; NUM_LANES],
+        depth: usize,
+    ) {
+        let (r, e) = (self.keccak_input, self.evm_word);
+        let ends = self.lanes.map(|lane| lane.is_extension && lane.is_last);
+        // The old and the new extensions have different children if they both
+        // end at the depth, while the sibling has the child of the old one.
+        let blocks: Vec<Vec<usize>> = if ends[OLD] && ends[NEW] {
+            let mut lanes = vec![OLD];
+            if ends[SIBLING] {
+                lanes.push(SIBLING);
+            }
+            vec![lanes, vec![NEW]]
+        } else {
+            let lanes: Vec<usize> = (0..NUM_LANES).filter(|&lane| ends[lane]).collect();
+            if lanes.is_empty() {
+                vec![]
+            } else {
+                vec![lanes]
+            }
+        };
+        for (is_dup, lanes) in blocks.into_iter().enumerate() {
+            let child = match positions[lanes[0]][depth]
+                .as_ref()
+                .map(|position| position.node.as_ref())
+            {
+                Some(ProofNode::Extension(_, child)) => *child,
+                _ => unreachable!("lane ends an extension"),
+            };
+            let child_rlc = rlc_be(&child.0, r);
+            for &lane in lanes.iter() {
+                let lane = &mut self.lanes[lane];
+                lane.rlp_acc = lane.rlp_acc * power(r, NUM_BYTES_PER_WORD + 1)
+                    + known(RLP_HASH) * power(r, NUM_BYTES_PER_WORD)
+                    + child_rlc;
+                lane.rlp_len += NUM_BYTES_PER_WORD as u64 + 1;
+                lane.rlp_pow = lane.rlp_pow * power(r, NUM_BYTES_PER_WORD + 1);
+                lane.ptr = rlc_be(&child.0, e);
+            }
+            let row = self.push(RowKind::ExtensionChild);
+            row.bytes = child.0;
+            row.is_dup_extension = is_dup == 1;
+            for lane in lanes {
+                row.in_lanes[lane] = true;
+                row.is_end[lane] = true;
+            }
+        }
+    }
+
+    fn child_rows(
+        &mut self,
+        positions: &[Vec<Option<Position>>; NUM_LANES],
+        key: &[u8],
+        depth: usize,
+    ) {
+        let (r, e) = (self.keccak_input, self.evm_word);
+        let branches = self.lanes.map(|lane| lane.is_branch);
+        if !branches.iter().any(|&is_branch| is_branch) {
+            return;
+        }
+        let nibble = key[depth];
+        // The old and the new branches share their children but the one of
+        // the key, which takes a row per lane.
+        let is_shared = branches[OLD] && branches[NEW];
+        let is_split_branch = !self.nibble.is_same && (branches[OLD] || branches[NEW]);
+        for index in 0..NUM_CHILDREN as u8 {
+            let num_dups = if is_shared && index == nibble { 2 } else { 1 };
+            for is_dup in 0..num_dups {
+                let lanes: Vec<usize> = if is_shared {
+                    match (index == nibble, is_dup) {
+                        (false, _) => vec![OLD, NEW],
+                        (true, 0) => vec![OLD],
+                        _ => vec![NEW],
+                    }
+                } else {
+                    (0..NUM_LANES).filter(|&lane| branches[lane]).collect()
+                };
+                let child = match positions[lanes[0]][depth]
+                    .as_ref()
+                    .map(|position| position.node.as_ref())
+                {
+                    Some(ProofNode::Branch(children)) => children[index as usize],
+                    _ => unreachable!("lane is a branch"),
+                };
+                let is_empty = child.is_none();
+                let hash = child.unwrap_or_default();
+                let is_last = index as usize == NUM_CHILDREN - 1;
+                let (item, multiplier) = if is_empty {
+                    (known(RLP_SHORT_STRING), r)
+                } else {
+                    (
+                        known(RLP_HASH) * power(r, NUM_BYTES_PER_WORD) + rlc_be(&hash.0, r),
+                        power(r, NUM_BYTES_PER_WORD + 1),
+                    )
+                };
+                let hash_rlc = rlc_be(&hash.0, e);
+                for &i in lanes.iter() {
+                    let lane = &mut self.lanes[i];
+                    lane.rlp_acc = lane.rlp_acc * multiplier + item;
+                    lane.rlp_len += if is_empty {
+                        1
+                    } else {
+                        NUM_BYTES_PER_WORD as u64 + 1
+                    };
+                    lane.rlp_pow = lane.rlp_pow * multiplier;
+                    // The empty value of the branch ends its list.
+                    if is_last {
+                        lane.rlp_acc = lane.rlp_acc * r + known(RLP_SHORT_STRING);
+                        lane.rlp_len += 1;
+                        lane.rlp_pow = lane.rlp_pow * r;
+                    }
+                    lane.num_children += !is_empty as u64;
+                    if index == nibble && i != SIBLING {
+                        lane.key_child_empty = is_empty;
+                        lane.ptr = hash_rlc;
+                    }
+                }
+                if is_split_branch && index == self.nibble.other_nibble {
+                    self.lanes[SIBLING].ptr = hash_rlc;
+                }
+                let row = self.push(RowKind::Child);
+                row.bytes = hash.0;
+                row.index = index;
+                row.is_dup = is_dup == 1;
+                row.is_empty = is_empty;
+                for lane in lanes {
+                    row.in_lanes[lane] = true;
+                    row.is_end[lane] = is_last;
+                }
+            }
+        }
+    }
+
+    fn value_rows(
+        &mut self,
+        positions: &[Vec<Option<Position>>; NUM_LANES],
+        is_storage: bool,
+    ) -> Result<(), Error> {
+        let r = self.keccak_input;
+        let leaves = self.lanes.map(|lane| lane.is_leaf);
+        if !leaves[OLD] && !leaves[NEW] {
+            return Ok(());
+        }
+        let last = NUM_NIBBLES - 1;
+        let old_fields = leaves[OLD]
+            .then(|| leaf_fields(is_storage, &positions[OLD][last]))
+            .transpose()?;
+        let new_fields = leaves[NEW]
+            .then(|| leaf_fields(is_storage, &positions[NEW][last]))
+            .transpose()?;
+        // The sibling leaf has the value of the leaf of the lane without the
+        // branch.
+        let short = match (leaves[SIBLING], self.lanes[OLD].is_diverged) {
+            (false, _) => None,
+            (true, true) => Some(OLD),
+            (true, false) => Some(NEW),
+        };
+        let lane_fields = |lane: usize| {
+            if lane == OLD || (lane == SIBLING && short == Some(OLD)) {
+                old_fields.as_ref()
+            } else {
+                new_fields.as_ref()
+            }
+        };
+        let num_fields = if is_storage { 1 } else { STORAGE_VALUE - NONCE };
+        let mut value_rows = vec![];
+        for i in 0..num_fields {
+            match (&old_fields, &new_fields) {
+                (Some(old), Some(new)) if self.nibble.is_same && old[i] == new[i] => {
+                    value_rows.push((old[i], vec![OLD, NEW]));
+                }
+                _ => {
+                    for (lane, fields) in [(OLD, &old_fields), (NEW, &new_fields)] {
+                        if let Some(fields) = fields {
+                            let mut lanes = vec![lane];
+                            if short == Some(lane) {
+                                lanes.push(SIBLING);
+                            }
+                            value_rows.push((fields[i], lanes));
+                        }
+                    }
+                }
+            }
+        }
+
+        for ((tag, bytes), lanes) in value_rows {
+            let (item, item_len, item_pow) = value_item(tag, &bytes, r);
+            for &i in lanes.iter() {
+                // The first field starts the list of the account, in a string.
+                if tag == NONCE {
+                    let list_len: u64 = lane_fields(i)
+                        .into_iter()
+                        .flatten()
+                        .map(|(tag, bytes)| value_item(*tag, bytes, r).1)
+                        .sum();
+                    let lane = &mut self.lanes[i];
+                    lane.rlp_acc = lane.rlp_acc * power(r, 4)
+                        + known(RLP_LONG_STRING + 1) * power(r, 3)
+                        + known(list_len + 2) * power(r, 2)
+                        + known(RLP_LONG_LIST + 1) * r
+                        + known(list_len);
+                    lane.rlp_len += 4;
+                    lane.rlp_pow = lane.rlp_pow * power(r, 4);
+                    lane.rem_len = list_len - item_len;
+                } else if tag != STORAGE_VALUE {
+                    self.lanes[i].rem_len -= item_len;
+                }
+                let lane = &mut self.lanes[i];
+                lane.rlp_acc = lane.rlp_acc * item_pow + item;
+                lane.rlp_len += item_len;
+                lane.rlp_pow = lane.rlp_pow * item_pow;
+                lane.next_tag += 1;
+            }
+            let row = self.push(RowKind::Value);
+            row.bytes = bytes;
+            row.value_tag = tag;
+            row.item = item;
+            row.item_len = item_len;
+            row.item_pow = item_pow;
+            for lane in lanes {
+                row.in_lanes[lane] = true;
+                row.is_end[lane] = tag == CODE_HASH || tag == STORAGE_VALUE;
+            }
+        }
+        Ok(())
+    }
+}
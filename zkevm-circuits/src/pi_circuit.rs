@@ -11,7 +11,7 @@ pub use dev::PiTestCircuit;
 use eth_types::{
     geth_types::{BlockConstants, Transaction},
     sign_types::SignData,
-    Address, BigEndianHash, Field, ToBigEndian, ToLittleEndian, ToScalar, Word, H256,
+    AccessList, Address, BigEndianHash, Field, ToBigEndian, ToLittleEndian, ToScalar, Word, H256,
 };
 use halo2_proofs::plonk::{Instance, SecondPhase};
 use keccak256::plain::Keccak;
@@ -22,7 +22,7 @@ use crate::{
     table::{BlockTable, LookupTable, TxFieldTag, TxTable},
     tx_circuit::TX_LEN,
     util::{random_linear_combine_word as rlc, Challenges, SubCircuit, SubCircuitConfig},
-    witness,
+    witness::{self, access_list_len, access_list_rlc},
 };
 use gadgets::{
     is_zero::IsZeroChip,
@@ -56,12 +56,15 @@ pub struct TxValues {
     nonce: Word,
     gas: Word, // gas limit
     gas_price: Word,
+    max_fee_per_gas: Word,
+    max_priority_fee_per_gas: Word,
     from_addr: Address,
     to_addr: Address,
     is_create: u64,
     value: Word,
     call_data_len: u64,
     call_data_gas_cost: u64,
+    access_list: Option<AccessList>,
     tx_sign_hash: [u8; 32],
 }
 
@@ -143,6 +146,8 @@ impl PublicData {
             tx_vals.push(TxValues {
                 nonce: tx.nonce,
                 gas_price: tx.gas_price,
+                max_fee_per_gas: tx.gas_fee_cap,
+                max_priority_fee_per_gas: tx.gas_tip_cap,
                 gas: tx.gas_limit,
                 from_addr: tx.from,
                 to_addr: tx.to.unwrap_or_else(Address::zero),
@@ -156,6 +161,7 @@ impl PublicData {
                         NONZERO_BYTE_GAS_COST
                     }
                 }),
+                access_list: tx.access_list.clone(),
                 tx_sign_hash: msg_hash_le,
             });
         }
@@ -1323,6 +1329,10 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
                 for i in 0..config.max_txs {
                     let tx = if i < txs.len() { &txs[i] } else { &tx_default };
+                    let (access_list_addresses_len, access_list_storage_keys_len) =
+                        access_list_len(&tx.access_list);
+                    let (access_list_addresses_rlc, access_list_storage_keys_rlc) =
+                        access_list_rlc(&tx.access_list, self.randomness, self.randomness);
 
                     for (tag, value) in &[
                         (
@@ -1334,6 +1344,14 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                             TxFieldTag::GasPrice,
                             rlc(tx.gas_price.to_le_bytes(), self.randomness),
                         ),
+                        (
+                            TxFieldTag::MaxFeePerGas,
+                            rlc(tx.max_fee_per_gas.to_le_bytes(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::MaxPriorityFeePerGas,
+                            rlc(tx.max_priority_fee_per_gas.to_le_bytes(), self.randomness),
+                        ),
                         (
                             TxFieldTag::CallerAddress,
                             tx.from_addr.to_scalar().expect("tx.from too big"),
@@ -1349,6 +1367,22 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                         ),
                         (TxFieldTag::CallDataLength, F::from(tx.call_data_len)),
                         (TxFieldTag::CallDataGasCost, F::from(tx.call_data_gas_cost)),
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            F::from(access_list_addresses_len),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysLen,
+                            F::from(access_list_storage_keys_len),
+                        ),
+                        (
+                            TxFieldTag::AccessListAddressesRlc,
+                            access_list_addresses_rlc,
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysRlc,
+                            access_list_storage_keys_rlc,
+                        ),
                         (
                             TxFieldTag::TxSignHash,
                             rlc(tx.tx_sign_hash, self.randomness),
@@ -1526,17 +1560,27 @@ fn raw_public_inputs_col<F: Field>(
 
     for i in 0..max_txs {
         let tx = if i < txs.len() { &txs[i] } else { &tx_default };
+        let (access_list_addresses_len, access_list_storage_keys_len) =
+            access_list_len(&tx.access_list);
+        let (access_list_addresses_rlc, access_list_storage_keys_rlc) =
+            access_list_rlc(&tx.access_list, randomness, randomness);
 
         for val in &[
             rlc(tx.nonce.to_le_bytes(), randomness),
             rlc(tx.gas.to_le_bytes(), randomness),
             rlc(tx.gas_price.to_le_bytes(), randomness),
+            rlc(tx.max_fee_per_gas.to_le_bytes(), randomness),
+            rlc(tx.max_priority_fee_per_gas.to_le_bytes(), randomness),
             tx.from_addr.to_scalar().expect("tx.from too big"),
             tx.to_addr.to_scalar().expect("tx.to too big"),
             F::from(tx.is_create),
             rlc(tx.value.to_le_bytes(), randomness),
             F::from(tx.call_data_len),
             F::from(tx.call_data_gas_cost),
+            F::from(access_list_addresses_len),
+            F::from(access_list_storage_keys_len),
+            access_list_addresses_rlc,
+            access_list_storage_keys_rlc,
             rlc(tx.tx_sign_hash, randomness),
         ] {
             result[id_offset + offset] = F::from((i + 1) as u64);
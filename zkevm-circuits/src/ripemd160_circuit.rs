@@ -0,0 +1,892 @@
+//! The RIPEMD-160 circuit implementation, which verifies the hashes computed
+//! by the calls to the ripemd160 precompiled contract and exposes them in the
+//! RIPEMD-160 table.
+//!
+//! Every 64 bytes block of the padded input takes `NUM_ROWS_PER_BLOCK` rows:
+//! - 5 start rows holding the state at the start of the block, which is either the initial hash
+//!   values or the state at the end of the previous block,
+//! - 80 round rows, one per step of both lines of the compression function, the first 16 of them
+//!   absorbing a word of the input,
+//! - 5 end rows combining the states at the end of both lines with the state at the start of the
+//!   block. The last one holds the RIPEMD-160 table row, which is enabled when the block is the
+//!   last one of its input.
+//!
+//! Each step of a line only computes a new word `t`, with the state
+//! `(a, b, c, d, e)` being `(rol10(t[-5]), t[-1], t[-2], rol10(t[-3]),
+//! rol10(t[-4]))`, so that the state words are found in the 5 previous rows.
+//! The message words used by the steps are copied from the words absorbed in
+//! the first 16 rounds.
+mod param;
+mod ripemd160_bit;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Ripemd160Circuit as TestRipemd160Circuit;
+
+use self::{
+    param::*,
+    ripemd160_bit::{multi_ripemd160, Ripemd160Row},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    sha256_circuit::util::decode,
+    table::{LookupTable, Ripemd160Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::PrecompileCalls;
+use eth_types::Field;
+use gadgets::util::{and, not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Ripemd160CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Ripemd160CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_start_rotated: Column<Fixed>,
+    q_round: Column<Fixed>,
+    q_padding: Column<Fixed>,
+    q_padding_last: Column<Fixed>,
+    q_ends: [Column<Fixed>; NUM_END_ROWS],
+    q_squeeze: Column<Fixed>,
+    q_groups: [Column<Fixed>; NUM_GROUPS],
+    q_rotations_left: [Column<Fixed>; NUM_ROTATIONS],
+    q_rotations_right: [Column<Fixed>; NUM_ROTATIONS],
+    round_cst_left: Column<Fixed>,
+    round_cst_right: Column<Fixed>,
+    h_start: Column<Fixed>,
+    word_w: [Column<Advice>; NUM_BITS_PER_WORD],
+    x_left: Column<Advice>,
+    x_right: Column<Advice>,
+    sum_left: [Column<Advice>; NUM_BITS_PER_WORD_SUM],
+    t_left: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    sum_right: [Column<Advice>; NUM_BITS_PER_WORD_SUM],
+    t_right: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    is_final: Column<Advice>,
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    /// The columns for other circuits to lookup RIPEMD-160 hash results
+    pub ripemd160_table: Ripemd160Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Ripemd160CircuitConfigArgs<F: Field> {
+    /// Ripemd160Table
+    pub ripemd160_table: Ripemd160Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Ripemd160CircuitConfig<F> {
+    type ConfigArgs = Ripemd160CircuitConfigArgs<F>;
+
+    /// Return a new Ripemd160CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ripemd160_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_start_rotated = meta.fixed_column();
+        let q_round = meta.fixed_column();
+        let q_padding = meta.fixed_column();
+        let q_padding_last = meta.fixed_column();
+        let q_ends = array_init::array_init(|_| meta.fixed_column());
+        let q_squeeze = meta.fixed_column();
+        let q_groups = array_init::array_init(|_| meta.fixed_column());
+        let q_rotations_left = array_init::array_init(|_| meta.fixed_column());
+        let q_rotations_right = array_init::array_init(|_| meta.fixed_column());
+        let round_cst_left = meta.fixed_column();
+        let round_cst_right = meta.fixed_column();
+        let h_start = meta.fixed_column();
+        let word_w = array_init::array_init(|_| meta.advice_column());
+        let x_left = meta.advice_column();
+        let x_right = meta.advice_column();
+        let sum_left = array_init::array_init(|_| meta.advice_column());
+        let t_left = array_init::array_init(|_| meta.advice_column());
+        let sum_right = array_init::array_init(|_| meta.advice_column());
+        let t_right = array_init::array_init(|_| meta.advice_column());
+        let is_final = meta.advice_column();
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+
+        // The message words are copied between the rounds
+        meta.enable_equality(x_left);
+        meta.enable_equality(x_right);
+
+        let is_enabled = ripemd160_table.is_enabled;
+        let length = ripemd160_table.input_len;
+        let data_rlc = ripemd160_table.input_rlc;
+        let hash_rlc = ripemd160_table.output_rlc;
+
+        // Queries the bits of a word, or only its low 32 bits without the
+        // carries.
+        let query_bits = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            columns
+                .iter()
+                .map(|&column| meta.query_advice(column, Rotation(rot)))
+                .collect::<Vec<_>>()
+        };
+        let query_word = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            query_bits(meta, &columns[..NUM_BITS_PER_WORD], rot)
+        };
+        let rol10 = |word: &[Expression<F>]| rotate_left::expr(word, ROTATION_ROL10);
+        let last_is_padding = *is_paddings.last().unwrap();
+
+        meta.create_gate("input checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for bit in query_bits(meta, &word_w, 0)
+                .into_iter()
+                .chain(query_bits(meta, &sum_left, 0))
+                .chain(query_bits(meta, &t_left, 0))
+                .chain(query_bits(meta, &sum_right, 0))
+                .chain(query_bits(meta, &t_right, 0))
+            {
+                cb.require_boolean("bit boolean", bit);
+            }
+            for is_padding in query_bits(meta, &is_paddings, 0) {
+                cb.require_boolean("is_padding boolean", is_padding);
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Both lines compute the new word
+        // t = rol_s(a + f(b, c, d) + x + k) + e
+        // with the function `f` of the group of the round, used in reverse
+        // order by the right line.
+        meta.create_gate("compression", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_groups = q_groups.map(|q_group| meta.query_fixed(q_group, Rotation::cur()));
+            for (names, word_t, word_sum, x, round_cst, q_rotations, is_right) in [
+                (
+                    ["sum left", "t left"],
+                    t_left,
+                    sum_left,
+                    x_left,
+                    round_cst_left,
+                    q_rotations_left,
+                    false,
+                ),
+                (
+                    ["sum right", "t right"],
+                    t_right,
+                    sum_right,
+                    x_right,
+                    round_cst_right,
+                    q_rotations_right,
+                    true,
+                ),
+            ] {
+                let [t_1, t_2, t_3, t_4, t_5] =
+                    [-1, -2, -3, -4, -5].map(|rot| query_word(meta, &word_t, rot));
+                let (a, b, c, d, e) = (rol10(&t_5), t_1, t_2, rol10(&t_3), rol10(&t_4));
+                let f = sum::expr(q_groups.iter().enumerate().map(|(group, q_group)| {
+                    let group = if is_right { group_right(group) } else { group };
+                    q_group.expr() * decode::expr(&f_expr(group, &b, &c, &d))
+                }));
+                let new_sum_ext = query_bits(meta, &word_sum, 0);
+                cb.require_equal(
+                    names[0],
+                    decode::expr(&new_sum_ext),
+                    decode::expr(&a)
+                        + f
+                        + meta.query_advice(x, Rotation::cur())
+                        + meta.query_fixed(round_cst, Rotation::cur()),
+                );
+
+                let new_sum = &new_sum_ext[..NUM_BITS_PER_WORD];
+                let rotated_sum =
+                    sum::expr(q_rotations.iter().enumerate().map(|(idx, &q_rotation)| {
+                        meta.query_fixed(q_rotation, Rotation::cur())
+                            * decode::expr(&rotate_left::expr(new_sum, MIN_ROTATION + idx))
+                    }));
+                cb.require_equal(
+                    names[1],
+                    decode::expr(&query_bits(meta, &word_t, 0)),
+                    rotated_sum + decode::expr(&e),
+                );
+            }
+            cb.gate(meta.query_fixed(q_round, Rotation::cur()))
+        });
+
+        // The first 16 rounds absorb the input words, which are then copied
+        // into the other rounds.
+        meta.create_gate("absorb", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "absorb word",
+                meta.query_advice(x_left, Rotation::cur()),
+                decode::expr(&query_word(meta, &word_w, 0)),
+            );
+            cb.gate(meta.query_fixed(q_padding, Rotation::cur()))
+        });
+
+        // The start rows hold the initial hash values when a new hash is
+        // started, which is flagged by `is_final` on these rows, and the state
+        // at the end of the previous block otherwise. The first 3 of them hold
+        // words rotated right by 10 bits.
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let q_start_rotated = meta.query_fixed(q_start_rotated, Rotation::cur());
+            let new_t = query_word(meta, &t_left, 0);
+            let h = query_word(meta, &sum_left, -(NUM_END_ROWS as i32));
+            cb.require_equal(
+                "start t",
+                select::expr(
+                    q_start_rotated,
+                    decode::expr(&rol10(&new_t)),
+                    decode::expr(&new_t),
+                ),
+                select::expr(
+                    is_final,
+                    meta.query_fixed(h_start, Rotation::cur()),
+                    decode::expr(&h),
+                ),
+            );
+            cb.require_equal(
+                "both lines start with the same state",
+                decode::expr(&query_word(meta, &t_right, 0)),
+                decode::expr(&new_t),
+            );
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        // The end rows add words of the state at the start of the block and of
+        // the states after the last rounds of both lines, keeping the carries
+        // that are dropped when used in the next block. Each row combines the
+        // words as `(start row, left round, right round)` with whether they
+        // are rotated left by 10 bits.
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for (k, ((start, start_rol), (left, left_rol), (right, right_rol))) in [
+                ((4, false), (78, false), (77, true)),
+                ((0, true), (79, false), (78, false)),
+                ((1, true), (75, true), (79, false)),
+                ((2, true), (76, true), (75, true)),
+                ((3, false), (77, true), (76, true)),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let rot_start = start as i32 - (NUM_START_ROWS + NUM_ROUNDS + k) as i32;
+                let rot_round = |round: usize| round as i32 - (NUM_ROUNDS + k) as i32;
+                let mut query = |columns: &[Column<Advice>], rot: i32, is_rotated: bool| {
+                    let word = query_word(meta, columns, rot);
+                    decode::expr(&if is_rotated { rol10(&word) } else { word })
+                };
+                let h = query(&t_left, rot_start, start_rol)
+                    + query(&t_left, rot_round(left), left_rol)
+                    + query(&t_right, rot_round(right), right_rol);
+                let new_h_ext = query_bits(meta, &sum_left, 0);
+                cb.condition(meta.query_fixed(q_ends[k], Rotation::cur()), |cb| {
+                    cb.require_equal("end h", decode::expr(&new_h_ext), h);
+                });
+            }
+            cb.gate(1.expr())
+        });
+
+        // The block is the last one of its input when the input length is in
+        // the last 2 words, so when the word before them is padding.
+        let num_length_words = NUM_BYTES_PADDING_LENGTH / NUM_BYTES_PER_WORD;
+        meta.create_gate("is final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let q_start = meta.query_fixed(q_start, Rotation::cur());
+            let q_squeeze = meta.query_fixed(q_squeeze, Rotation::cur());
+            let is_final_prev = meta.query_advice(is_final, Rotation::prev());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let is_padding_last_data_word = meta.query_advice(
+                last_is_padding,
+                Rotation(
+                    -((NUM_END_ROWS + NUM_ROUNDS - NUM_WORDS_TO_ABSORB + num_length_words) as i32),
+                ),
+            );
+
+            cb.condition(q_first.expr(), |cb| {
+                cb.require_equal(
+                    "is_final needs to be enabled on the first row",
+                    is_final.expr(),
+                    1.expr(),
+                );
+            });
+            cb.condition(q_start - q_first, |cb| {
+                cb.require_equal(
+                    "is_final is copied into the start rows",
+                    is_final.expr(),
+                    is_final_prev,
+                );
+            });
+            cb.condition(q_squeeze.expr(), |cb| {
+                cb.require_equal(
+                    "is_final needs to match the padding of the last data word",
+                    is_final.expr(),
+                    is_padding_last_data_word,
+                );
+            });
+            // Only the squeeze rows of the last blocks are enabled in the table
+            cb.require_equal(
+                "is_enabled only on final squeeze rows",
+                meta.query_advice(is_enabled, Rotation::cur()),
+                q_squeeze * is_final,
+            );
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("squeeze", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            // The hash words `h0` to `h4`, from the end rows
+            let hash_bytes = [-4, 0, -1, -2, -3]
+                .map(|rot| query_word(meta, &sum_left, rot))
+                .into_iter()
+                .flat_map(|word| to_le_bytes::expr(&word))
+                .collect::<Vec<_>>();
+            let hash_bytes_le = hash_bytes.into_iter().rev().collect::<Vec<_>>();
+            cb.condition(is_final, |cb| {
+                cb.require_equal(
+                    "hash rlc check",
+                    meta.query_advice(hash_rlc, Rotation::cur()),
+                    rlc::expr(&hash_bytes_le, challenges.keccak_input()),
+                );
+            });
+            cb.gate(meta.query_fixed(q_squeeze, Rotation::cur()))
+        });
+
+        // The input is padded with a 0x80 byte, zeros and its length in bits
+        // as a little endian 64 bit integer, whose low word is the second to
+        // last word. Only lengths up to 2**32 - 1 bits are supported.
+        meta.create_gate("padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_start = meta.query_fixed(q_start, Rotation::cur());
+            let q_padding = meta.query_fixed(q_padding, Rotation::cur());
+            let q_padding_last = meta.query_fixed(q_padding_last, Rotation::cur());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let length = meta.query_advice(length, Rotation::cur());
+            let prev_is_padding = meta.query_advice(last_is_padding, Rotation::prev());
+            let is_paddings = query_bits(meta, &is_paddings, 0);
+            // The word before the length is the last data word
+            let is_final_padding_row = prev_is_padding.expr();
+            let w = query_word(meta, &word_w, 0);
+            let input_bytes = to_le_bytes::expr(&w);
+
+            for (idx, (is_padding, byte)) in is_paddings.iter().zip(input_bytes.iter()).enumerate()
+            {
+                // Previous padding selector can be on the previous row
+                let is_padding_prev = if idx == 0 {
+                    prev_is_padding.expr()
+                } else {
+                    is_paddings[idx - 1].expr()
+                };
+                let is_first_padding = is_padding.expr() - is_padding_prev;
+
+                // Check padding transition 0 -> 1 done only once
+                cb.condition(q_padding.expr(), |cb| {
+                    cb.require_boolean("padding step boolean", is_first_padding.clone());
+                });
+                // Padding start/intermediate byte, except for the word holding
+                // the length in the last block.
+                cb.condition(
+                    and::expr([q_padding.expr() - q_padding_last.expr(), is_padding.expr()]),
+                    |cb| {
+                        cb.require_equal(
+                            "padding start/intermediate byte",
+                            byte.expr(),
+                            is_first_padding.expr() * 128.expr(),
+                        );
+                    },
+                );
+                cb.condition(
+                    and::expr([
+                        q_padding_last.expr(),
+                        is_padding.expr(),
+                        not::expr(is_final_padding_row.expr()),
+                    ]),
+                    |cb| {
+                        cb.require_equal(
+                            "padding start/intermediate byte length word",
+                            byte.expr(),
+                            is_first_padding.expr() * 128.expr(),
+                        );
+                    },
+                );
+            }
+            // The low word of the length in the last block holds the length in
+            // bits. The word after it is zero as a padding intermediate word.
+            cb.condition(
+                and::expr([q_padding_last.expr(), is_final_padding_row.expr()]),
+                |cb| {
+                    cb.require_equal("padding length", decode::expr(&w), length.expr() * 8.expr());
+                },
+            );
+
+            // Keep the padding state between the words absorbed, resetting it
+            // when a new hash is started.
+            let is_padding_last_byte = is_paddings.last().unwrap().expr();
+            cb.condition(q_start.expr(), |cb| {
+                cb.require_equal(
+                    "last is_padding reset or copied into the start rows",
+                    is_padding_last_byte.expr(),
+                    not::expr(is_final) * prev_is_padding.expr(),
+                );
+            });
+            cb.condition(q_enable - q_start - q_padding, |cb| {
+                cb.require_equal(
+                    "last is_padding copied when not absorbing",
+                    is_padding_last_byte,
+                    prev_is_padding,
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("length and data rlc", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_start = meta.query_fixed(q_start, Rotation::cur());
+            let q_padding = meta.query_fixed(q_padding, Rotation::cur());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let length_prev = meta.query_advice(length, Rotation::prev());
+            let length = meta.query_advice(length, Rotation::cur());
+            let data_rlc_prev = meta.query_advice(data_rlc, Rotation::prev());
+            let data_rlc = meta.query_advice(data_rlc, Rotation::cur());
+            let data_rlcs = query_bits(meta, &data_rlcs, 0);
+            let is_paddings = query_bits(meta, &is_paddings, 0);
+            let input_bytes = to_le_bytes::expr(&query_word(meta, &word_w, 0));
+
+            // Reset the length and data rlc when a new hash is started
+            cb.condition(q_start.expr(), |cb| {
+                cb.require_equal(
+                    "length reset or copied into the start rows",
+                    length.expr(),
+                    not::expr(is_final.expr()) * length_prev.expr(),
+                );
+                cb.require_equal(
+                    "data_rlc reset or copied into the start rows",
+                    data_rlc.expr(),
+                    not::expr(is_final.expr()) * data_rlc_prev.expr(),
+                );
+            });
+
+            // Update the length and data rlc on the rows absorbing a word
+            cb.condition(q_padding.expr(), |cb| {
+                // Length increases by the number of bytes that aren't padding
+                cb.require_equal(
+                    "update length",
+                    length.expr(),
+                    length_prev.expr()
+                        + sum::expr(
+                            is_paddings
+                                .iter()
+                                .map(|is_padding| not::expr(is_padding.expr())),
+                        ),
+                );
+
+                // Add the word `input_bytes` to `data_rlc`. It has a variable
+                // length represented by `is_paddings`, which requires
+                // intermediate cells to keep the degree low.
+                let mut new_data_rlc = data_rlc_prev.expr();
+                for (idx, (byte, is_padding)) in
+                    input_bytes.iter().zip(is_paddings.iter()).enumerate()
+                {
+                    new_data_rlc = select::expr(
+                        is_padding.expr(),
+                        new_data_rlc.clone(),
+                        new_data_rlc * challenges.keccak_input() + byte.expr(),
+                    );
+                    let data_rlc_after_this_byte = if idx < data_rlcs.len() {
+                        data_rlcs[idx].expr()
+                    } else {
+                        data_rlc.expr()
+                    };
+                    cb.require_equal(
+                        "intermediate data rlc",
+                        data_rlc_after_this_byte.clone(),
+                        new_data_rlc,
+                    );
+                    new_data_rlc = data_rlc_after_this_byte;
+                }
+            });
+
+            // Keep the length and data rlc the same on the other rows
+            cb.condition(q_enable - q_start - q_padding, |cb| {
+                cb.require_equal("length equality check", length, length_prev);
+                cb.require_equal("data_rlc equality check", data_rlc, data_rlc_prev);
+            });
+            cb.gate(1.expr())
+        });
+
+        ripemd160_table.annotate_columns(meta);
+
+        Ripemd160CircuitConfig {
+            q_enable,
+            q_first,
+            q_start,
+            q_start_rotated,
+            q_round,
+            q_padding,
+            q_padding_last,
+            q_ends,
+            q_squeeze,
+            q_groups,
+            q_rotations_left,
+            q_rotations_right,
+            round_cst_left,
+            round_cst_right,
+            h_start,
+            word_w,
+            x_left,
+            x_right,
+            sum_left,
+            t_left,
+            sum_right,
+            t_right,
+            is_final,
+            is_paddings,
+            data_rlcs,
+            ripemd160_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Ripemd160CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Ripemd160Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign ripemd160 rows",
+            |mut region| {
+                for (block_idx, rows) in witness.chunks(NUM_ROWS_PER_BLOCK).enumerate() {
+                    let mut xs = Vec::with_capacity(NUM_ROUNDS);
+                    for (block_row, ripemd160_row) in rows.iter().enumerate() {
+                        let offset = block_idx * NUM_ROWS_PER_BLOCK + block_row;
+                        let x = self.set_row(&mut region, offset, ripemd160_row)?;
+                        if (NUM_START_ROWS..NUM_START_ROWS + NUM_ROUNDS).contains(&block_row) {
+                            xs.push(x);
+                        }
+                    }
+                    self.constrain_message_words(&mut region, &xs)?;
+                }
+                self.ripemd160_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Ripemd160Row<F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let block_row = offset % NUM_ROWS_PER_BLOCK;
+        let round = block_row
+            .checked_sub(NUM_START_ROWS)
+            .filter(|&round| round < NUM_ROUNDS);
+        let group = round.map(|round| round / NUM_ROUNDS_PER_GROUP);
+        let end = block_row.checked_sub(NUM_START_ROWS + NUM_ROUNDS);
+        let is_start = block_row < NUM_START_ROWS;
+        let is_squeeze = block_row == NUM_ROWS_PER_BLOCK - 1;
+
+        // Fixed selectors
+        let h_start = [H[0], H[4], H[3], H[2], H[1]];
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, F::one()),
+            ("q_first", self.q_first, F::from(offset == 0)),
+            ("q_start", self.q_start, F::from(is_start)),
+            (
+                "q_start_rotated",
+                self.q_start_rotated,
+                F::from(block_row < NUM_START_ROWS_ROTATED),
+            ),
+            ("q_round", self.q_round, F::from(round.is_some())),
+            (
+                "q_padding",
+                self.q_padding,
+                F::from(round.map_or(false, |round| round < NUM_WORDS_TO_ABSORB)),
+            ),
+            (
+                "q_padding_last",
+                self.q_padding_last,
+                F::from(round == Some(NUM_WORDS_TO_ABSORB - 2)),
+            ),
+            ("q_squeeze", self.q_squeeze, F::from(is_squeeze)),
+            (
+                "round_cst_left",
+                self.round_cst_left,
+                F::from(group.map_or(0, |group| ROUND_CST_LEFT[group] as u64)),
+            ),
+            (
+                "round_cst_right",
+                self.round_cst_right,
+                F::from(group.map_or(0, |group| ROUND_CST_RIGHT[group] as u64)),
+            ),
+            (
+                "h_start",
+                self.h_start,
+                F::from(if is_start {
+                    h_start[block_row] as u64
+                } else {
+                    0
+                }),
+            ),
+        ]
+        .into_iter()
+        .chain(
+            self.q_ends
+                .iter()
+                .enumerate()
+                .map(|(k, &q_end)| ("q_end", q_end, F::from(end == Some(k)))),
+        )
+        .chain(
+            self.q_groups
+                .iter()
+                .enumerate()
+                .map(|(idx, &q_group)| ("q_group", q_group, F::from(group == Some(idx)))),
+        )
+        .chain(
+            self.q_rotations_left
+                .iter()
+                .enumerate()
+                .map(|(idx, &q_rotation)| {
+                    let rotation = round.map(|round| ROTATION_LEFT[round]);
+                    (
+                        "q_rotation_left",
+                        q_rotation,
+                        F::from(rotation == Some(MIN_ROTATION + idx)),
+                    )
+                }),
+        )
+        .chain(
+            self.q_rotations_right
+                .iter()
+                .enumerate()
+                .map(|(idx, &q_rotation)| {
+                    let rotation = round.map(|round| ROTATION_RIGHT[round]);
+                    (
+                        "q_rotation_right",
+                        q_rotation,
+                        F::from(rotation == Some(MIN_ROTATION + idx)),
+                    )
+                }),
+        ) {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(value),
+            )?;
+        }
+
+        self.ripemd160_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(is_squeeze && row.is_final)),
+                row.data_rlc,
+                Value::known(F::from(row.length as u64)),
+                row.hash_rlc,
+            ],
+        )?;
+
+        // Bits of the words
+        for (name, columns, bits) in [
+            ("w", &self.word_w[..], &row.w[..]),
+            ("sum_left", &self.sum_left[..], &row.sum_left[..]),
+            ("t_left", &self.t_left[..], &row.t_left[..]),
+            ("sum_right", &self.sum_right[..], &row.sum_right[..]),
+            ("t_right", &self.t_right[..], &row.t_right[..]),
+            ("is_padding", &self.is_paddings[..], &row.is_paddings[..]),
+            ("is_final", &[self.is_final][..], &[row.is_final][..]),
+        ] {
+            for (idx, (&column, &bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} {} {}", name, idx, offset),
+                    column,
+                    offset,
+                    || Value::known(F::from(bit)),
+                )?;
+            }
+        }
+
+        // Intermediate data rlcs
+        for (idx, (&column, &data_rlc)) in
+            self.data_rlcs.iter().zip(row.data_rlcs.iter()).enumerate()
+        {
+            region.assign_advice(
+                || format!("assign data rlc {} {}", idx, offset),
+                column,
+                offset,
+                || data_rlc,
+            )?;
+        }
+
+        // Message words of both lines
+        let x_left = region.assign_advice(
+            || format!("assign x_left {}", offset),
+            self.x_left,
+            offset,
+            || Value::known(F::from(row.x_left as u64)),
+        )?;
+        let x_right = region.assign_advice(
+            || format!("assign x_right {}", offset),
+            self.x_right,
+            offset,
+            || Value::known(F::from(row.x_right as u64)),
+        )?;
+
+        Ok((x_left, x_right))
+    }
+
+    /// Constrains the message words of the rounds of a block to be the words
+    /// absorbed in its first 16 rounds, in the order of each line.
+    fn constrain_message_words(
+        &self,
+        region: &mut Region<'_, F>,
+        xs: &[(AssignedCell<F, F>, AssignedCell<F, F>)],
+    ) -> Result<(), Error> {
+        for (round, (x_left, x_right)) in xs.iter().enumerate() {
+            if round >= NUM_WORDS_TO_ABSORB {
+                region.constrain_equal(x_left.cell(), xs[WORD_IDX_LEFT[round]].0.cell())?;
+            }
+            region.constrain_equal(x_right.cell(), xs[WORD_IDX_RIGHT[round]].0.cell())?;
+        }
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "RIPEMD160_q_enable", self.q_enable);
+        region.name_column(|| "RIPEMD160_q_first", self.q_first);
+        region.name_column(|| "RIPEMD160_q_start", self.q_start);
+        region.name_column(|| "RIPEMD160_q_start_rotated", self.q_start_rotated);
+        region.name_column(|| "RIPEMD160_q_round", self.q_round);
+        region.name_column(|| "RIPEMD160_q_padding", self.q_padding);
+        region.name_column(|| "RIPEMD160_q_padding_last", self.q_padding_last);
+        region.name_column(|| "RIPEMD160_q_squeeze", self.q_squeeze);
+        region.name_column(|| "RIPEMD160_x_left", self.x_left);
+        region.name_column(|| "RIPEMD160_x_right", self.x_right);
+        region.name_column(|| "RIPEMD160_is_final", self.is_final);
+    }
+}
+
+/// Ripemd160Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Ripemd160Circuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+/// Returns the inputs of the calls to the ripemd160 precompile
+fn ripemd160_inputs<F: Field>(block: &witness::Block<F>) -> Vec<Vec<u8>> {
+    block
+        .precompile_events
+        .iter()
+        .filter(|event| event.precompile == PrecompileCalls::Ripemd160)
+        .map(|event| event.input.clone())
+        .collect()
+}
+
+/// Returns the number of 64 bytes blocks of the padded input
+fn num_blocks(input: &[u8]) -> usize {
+    (input.len() + 1 + NUM_BYTES_PADDING_LENGTH + RATE - 1) / RATE
+}
+
+impl<F: Field> SubCircuit<F> for Ripemd160Circuit<F> {
+    type Config = Ripemd160CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Columns t_left are queried at 9 distinct rotations, so returns
+        // (9 - 3) + 6 unusable rows.
+        12
+    }
+
+    /// The `block.circuits_params.max_ripemd160_rows` parameter, when not
+    /// zero, sets up the circuit to support a fixed number of blocks,
+    /// independently of the blocks required by the inputs.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_ripemd160_rows,
+            ripemd160_inputs(block),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            ripemd160_inputs(block)
+                .iter()
+                .map(|input| num_blocks(input) * NUM_ROWS_PER_BLOCK)
+                .sum(),
+            block.circuits_params.max_ripemd160_rows,
+        )
+    }
+
+    /// Make the assignments to the Ripemd160Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Ripemd160Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Ripemd160Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of 64 bytes blocks that can be hashed in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            // Subtract one block for unusable rows
+            Some(self.num_rows / NUM_ROWS_PER_BLOCK - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(
+        &self,
+        challenges: Challenges<Value<F>>,
+    ) -> Vec<Ripemd160Row<F>> {
+        multi_ripemd160(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
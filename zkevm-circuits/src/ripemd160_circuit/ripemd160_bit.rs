@@ -0,0 +1,259 @@
+use super::{param::*, util::*};
+use crate::{
+    evm_circuit::util::rlc,
+    sha256_circuit::util::{low_word, to_le_bits},
+    util::Challenges,
+};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// A row of the RIPEMD-160 circuit witness
+#[derive(Clone, Debug)]
+pub(crate) struct Ripemd160Row<F: Field> {
+    pub(crate) w: [bool; NUM_BITS_PER_WORD],
+    pub(crate) x_left: u32,
+    pub(crate) x_right: u32,
+    pub(crate) sum_left: [bool; NUM_BITS_PER_WORD_SUM],
+    pub(crate) t_left: [bool; NUM_BITS_PER_WORD_EXT],
+    pub(crate) sum_right: [bool; NUM_BITS_PER_WORD_SUM],
+    pub(crate) t_right: [bool; NUM_BITS_PER_WORD_EXT],
+    pub(crate) is_final: bool,
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD - 1],
+    pub(crate) length: usize,
+    pub(crate) data_rlc: Value<F>,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+/// Returns the input right padded as specified by RIPEMD-160: a single 1 bit,
+/// zeros up to a multiple of 64 bytes minus 8, and the input length in bits
+/// as a little endian 64 bit integer.
+fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while (padded.len() + NUM_BYTES_PADDING_LENGTH) % RATE != 0 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(bytes.len() as u64 * 8).to_le_bytes());
+    padded
+}
+
+/// Returns the words of the start rows for the hash values `hs`, which are
+/// the last 5 step words `t` giving the state `(a, b, c, d, e) = (rol10(t[-5]),
+/// t[-1], t[-2], rol10(t[-3]), rol10(t[-4]))`.
+fn start_words(hs: &[u32; 5]) -> [u32; NUM_START_ROWS] {
+    [hs[0], hs[4], hs[3], hs[2], hs[1]]
+        .iter()
+        .enumerate()
+        .map(|(idx, &h)| {
+            if idx < NUM_START_ROWS_ROTATED {
+                h.rotate_right(ROTATION_ROL10 as u32)
+            } else {
+                h
+            }
+        })
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
+
+/// Adds the rows hashing `bytes` and returns the hash.
+pub(crate) fn ripemd160<F: Field>(
+    rows: &mut Vec<Ripemd160Row<F>>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) -> [u8; 20] {
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut length = 0usize;
+    let mut data_rlc = Value::known(F::zero());
+    let mut in_padding = false;
+    let mut hash_bytes = [0u8; 20];
+
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final_block = idx == num_blocks - 1;
+
+        // The start rows hold the state of the previous block, or the initial
+        // hash values for the first block, for both lines.
+        let mut ts_left = start_words(&hs).to_vec();
+        let mut ts_right = ts_left.clone();
+        for &t in ts_left.iter() {
+            rows.push(Ripemd160Row {
+                w: [false; NUM_BITS_PER_WORD],
+                x_left: 0,
+                x_right: 0,
+                sum_left: [false; NUM_BITS_PER_WORD_SUM],
+                t_left: to_le_bits(t as u64),
+                sum_right: [false; NUM_BITS_PER_WORD_SUM],
+                t_right: to_le_bits(t as u64),
+                is_final: idx == 0,
+                is_paddings: [false, false, false, in_padding],
+                data_rlcs: [Value::known(F::zero()); NUM_BYTES_PER_WORD - 1],
+                length,
+                data_rlc,
+                hash_rlc: Value::known(F::zero()),
+            });
+        }
+
+        let xs: Vec<u32> = block
+            .chunks(NUM_BYTES_PER_WORD)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        for round in 0..NUM_ROUNDS {
+            let group = round / NUM_ROUNDS_PER_GROUP;
+            let (x_left, x_right) = (xs[WORD_IDX_LEFT[round]], xs[WORD_IDX_RIGHT[round]]);
+
+            // Both lines compute the new step word from the last 5 ones.
+            let step = |ts: &mut Vec<u32>, group: usize, x: u32, k: u32, rotation: usize| {
+                let n = ts.len();
+                let a = ts[n - 5].rotate_left(ROTATION_ROL10 as u32);
+                let (b, c) = (ts[n - 1], ts[n - 2]);
+                let d = ts[n - 3].rotate_left(ROTATION_ROL10 as u32);
+                let e = ts[n - 4].rotate_left(ROTATION_ROL10 as u32);
+                let sum = a as u64 + f(group, b, c, d) as u64 + x as u64 + k as u64;
+                let t = low_word(sum).rotate_left(rotation as u32) as u64 + e as u64;
+                ts.push(low_word(t));
+                (sum, t)
+            };
+            let (sum_left, t_left) = step(
+                &mut ts_left,
+                group,
+                x_left,
+                ROUND_CST_LEFT[group],
+                ROTATION_LEFT[round],
+            );
+            let (sum_right, t_right) = step(
+                &mut ts_right,
+                group_right(group),
+                x_right,
+                ROUND_CST_RIGHT[group],
+                ROTATION_RIGHT[round],
+            );
+
+            // Absorb the input bytes of the first words
+            let mut is_paddings = [false, false, false, in_padding];
+            let mut data_rlcs = [Value::known(F::zero()); NUM_BYTES_PER_WORD - 1];
+            if round < NUM_WORDS_TO_ABSORB {
+                for (j, is_padding) in is_paddings.iter_mut().enumerate() {
+                    let byte_idx = idx * RATE + round * NUM_BYTES_PER_WORD + j;
+                    *is_padding = byte_idx >= bytes.len();
+                    if !*is_padding {
+                        length += 1;
+                        data_rlc = data_rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(data_rlc, r)| data_rlc * r + F::from(bytes[byte_idx] as u64));
+                    }
+                    if j < NUM_BYTES_PER_WORD - 1 {
+                        data_rlcs[j] = data_rlc;
+                    }
+                }
+                in_padding = is_paddings[NUM_BYTES_PER_WORD - 1];
+            }
+
+            rows.push(Ripemd160Row {
+                w: to_le_bits(if round < NUM_WORDS_TO_ABSORB {
+                    xs[round] as u64
+                } else {
+                    0
+                }),
+                x_left,
+                x_right,
+                sum_left: to_le_bits(sum_left),
+                t_left: to_le_bits(t_left),
+                sum_right: to_le_bits(sum_right),
+                t_right: to_le_bits(t_right),
+                is_final: false,
+                is_paddings,
+                data_rlcs,
+                length,
+                data_rlc,
+                hash_rlc: Value::known(F::zero()),
+            });
+        }
+
+        // The end rows combine the start state with the states of both lines,
+        // as `[h0, h4, h3, h2, h1]`.
+        let state = |ts: &[u32]| {
+            let n = ts.len();
+            let rol10 = |t: u32| t.rotate_left(ROTATION_ROL10 as u32);
+            [
+                rol10(ts[n - 5]) as u64,
+                ts[n - 1] as u64,
+                ts[n - 2] as u64,
+                rol10(ts[n - 3]) as u64,
+                rol10(ts[n - 4]) as u64,
+            ]
+        };
+        let [a_left, b_left, c_left, d_left, e_left] = state(&ts_left);
+        let [a_right, b_right, c_right, d_right, e_right] = state(&ts_right);
+        let sums = [
+            hs[1] as u64 + c_left + d_right,
+            hs[0] as u64 + b_left + c_right,
+            hs[4] as u64 + a_left + b_right,
+            hs[3] as u64 + e_left + a_right,
+            hs[2] as u64 + d_left + e_right,
+        ];
+        hs = [sums[0], sums[4], sums[3], sums[2], sums[1]].map(low_word);
+        for (i, h) in hs.iter().enumerate() {
+            hash_bytes[i * NUM_BYTES_PER_WORD..(i + 1) * NUM_BYTES_PER_WORD]
+                .copy_from_slice(&h.to_le_bytes());
+        }
+        for (k, &sum) in sums.iter().enumerate() {
+            let is_squeeze = k == NUM_END_ROWS - 1;
+            rows.push(Ripemd160Row {
+                w: [false; NUM_BITS_PER_WORD],
+                x_left: 0,
+                x_right: 0,
+                sum_left: to_le_bits(sum),
+                t_left: [false; NUM_BITS_PER_WORD_EXT],
+                sum_right: [false; NUM_BITS_PER_WORD_SUM],
+                t_right: [false; NUM_BITS_PER_WORD_EXT],
+                is_final: is_squeeze && is_final_block,
+                is_paddings: [false, false, false, in_padding],
+                data_rlcs: [Value::known(F::zero()); NUM_BYTES_PER_WORD - 1],
+                length,
+                data_rlc,
+                hash_rlc: if is_squeeze && is_final_block {
+                    challenges
+                        .keccak_input()
+                        .map(|r| rlc::value(hash_bytes.iter().rev(), r))
+                } else {
+                    Value::known(F::zero())
+                },
+            });
+        }
+    }
+
+    hash_bytes
+}
+
+/// Computes the rows hashing all the inputs, padded with hashes of empty
+/// inputs up to `capacity` blocks when given.
+pub(crate) fn multi_ripemd160<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Ripemd160Row<F>>, Error> {
+    let mut rows: Vec<Ripemd160Row<F>> = Vec::new();
+    for bytes in bytes {
+        ripemd160(&mut rows, bytes, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            ripemd160(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
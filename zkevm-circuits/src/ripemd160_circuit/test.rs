@@ -0,0 +1,78 @@
+#![allow(unused_imports)]
+use super::{ripemd160_bit::ripemd160, *};
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::Field;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+
+#[test]
+fn ripemd160_circuit_unusable_rows() {
+    assert_eq!(
+        Ripemd160Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Ripemd160Circuit::<Fr>>(),
+    )
+}
+
+fn verify<F: Field>(k: u32, num_rows: usize, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Ripemd160Circuit::new(num_rows, inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+#[test]
+fn ripemd160_hash_values() {
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+    );
+    for (input, hash) in [
+        ("", "9c1185a5c5e9fc54612808977ee8f548b2258d31"),
+        ("abc", "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"),
+        ("message digest", "5d0689ef49d2fae572b881b123a85ffa21595f36"),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "12a053384a9c0c88e405a06c27dcf49ada62eb2b",
+        ),
+    ] {
+        let mut rows = Vec::new();
+        assert_eq!(
+            hex::encode(ripemd160(&mut rows, input.as_bytes(), challenges)),
+            hash
+        );
+        assert_eq!(rows.len() % NUM_ROWS_PER_BLOCK, 0);
+    }
+}
+
+#[test]
+fn ripemd160_circuit_simple() {
+    // Inputs fitting in a block with the length, or needing an extra block
+    // for it.
+    let inputs = vec![
+        vec![],
+        b"abc".to_vec(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    verify::<Fr>(12, 0, inputs, true);
+}
+
+#[test]
+fn ripemd160_circuit_padded_to_capacity() {
+    let k = 12;
+    let inputs = vec![b"abc".to_vec(), (0u8..100).collect::<Vec<_>>()];
+    let circuit = Ripemd160Circuit::<Fr>::new(2usize.pow(k), inputs.clone());
+    assert_eq!(circuit.capacity(), Some(44));
+    verify::<Fr>(k, 2usize.pow(k), inputs, true);
+}
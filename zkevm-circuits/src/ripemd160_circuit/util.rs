@@ -0,0 +1,73 @@
+use super::param::*;
+use crate::sha256_circuit::util::{decode, rotate};
+use eth_types::Field;
+use gadgets::util::{not, or, select, xor};
+use halo2_proofs::plonk::Expression;
+
+/// Rotates the little endian bits of a word to the left
+pub(crate) mod rotate_left {
+    use super::rotate;
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        rotate::expr(bits, bits.len() - count)
+    }
+}
+
+/// Splits the little endian bits of a word into its little endian bytes
+pub(crate) mod to_le_bytes {
+    use super::{decode, NUM_BITS_PER_BYTE};
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+        debug_assert!(bits.len() % 8 == 0, "bits not a multiple of 8");
+        bits.chunks(NUM_BITS_PER_BYTE).map(decode::expr).collect()
+    }
+}
+
+/// Returns the bitwise boolean function of a group of rounds on the bits of
+/// three words.
+pub(crate) fn f_expr<F: Field>(
+    group: usize,
+    x: &[Expression<F>],
+    y: &[Expression<F>],
+    z: &[Expression<F>],
+) -> Vec<Expression<F>> {
+    x.iter()
+        .zip(y.iter())
+        .zip(z.iter())
+        .map(|((x, y), z)| {
+            let (x, y, z) = (x.clone(), y.clone(), z.clone());
+            match group {
+                0 => xor::expr(xor::expr(x, y), z),
+                1 => select::expr(x, y, z),
+                2 => xor::expr(or::expr([x, not::expr(y)]), z),
+                3 => select::expr(z, x, y),
+                4 => xor::expr(x, or::expr([y, not::expr(z)])),
+                _ => unreachable!("RIPEMD-160 has {} groups of rounds", NUM_GROUPS),
+            }
+        })
+        .collect()
+}
+
+// The functions of the compression, as defined in the RIPEMD-160
+// specification.
+
+pub(crate) fn f(group: usize, x: u32, y: u32, z: u32) -> u32 {
+    match group {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        4 => x ^ (y | !z),
+        _ => unreachable!("RIPEMD-160 has {} groups of rounds", NUM_GROUPS),
+    }
+}
+
+/// Returns the group of the right line of a group of rounds, which uses the
+/// functions in reverse order.
+pub(crate) fn group_right(group: usize) -> usize {
+    NUM_GROUPS - 1 - group
+}
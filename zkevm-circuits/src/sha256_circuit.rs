@@ -0,0 +1,776 @@
+//! The SHA-256 circuit implementation, which verifies the hashes computed by
+//! the calls to the sha256 precompiled contract and exposes them in the
+//! SHA-256 table.
+//!
+//! Every 64 bytes block of the padded input takes `NUM_ROWS_PER_BLOCK` rows:
+//! - 4 start rows holding the state at the start of the block, which is either the initial hash
+//!   values or the state at the end of the previous block,
+//! - 64 round rows, one per round of the compression function, the first 16 of them absorbing a
+//!   word of the input,
+//! - 4 end rows adding the state at the end of the rounds to the state at the start of the block.
+//!   The last one holds the SHA-256 table row, which is enabled when the block is the last one of
+//!   its input.
+//!
+//! The state words `a` to `d` are kept in the `word_a` columns and `e` to `h`
+//! in the `word_e` columns, with each row holding the new `a` and `e` words,
+//! so that the other words are found in the 3 previous rows.
+mod param;
+mod sha256_bit;
+pub(crate) mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Sha256Circuit as TestSha256Circuit;
+
+use self::{
+    param::*,
+    sha256_bit::{multi_sha256, Sha256Row},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Sha256Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::PrecompileCalls;
+use eth_types::Field;
+use gadgets::util::{and, not, select, sum, xor, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use std::marker::PhantomData;
+
+/// Sha256CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Sha256CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_compression: Column<Fixed>,
+    q_extend: Column<Fixed>,
+    q_padding: Column<Fixed>,
+    q_padding_last: Column<Fixed>,
+    q_end: Column<Fixed>,
+    q_squeeze: Column<Fixed>,
+    round_cst: Column<Fixed>,
+    h_a: Column<Fixed>,
+    h_e: Column<Fixed>,
+    word_w: [Column<Advice>; NUM_BITS_PER_WORD_W],
+    word_a: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    word_e: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    is_final: Column<Advice>,
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    /// The columns for other circuits to lookup SHA-256 hash results
+    pub sha256_table: Sha256Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Sha256CircuitConfigArgs<F: Field> {
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Sha256CircuitConfig<F> {
+    type ConfigArgs = Sha256CircuitConfigArgs<F>;
+
+    /// Return a new Sha256CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha256_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_compression = meta.fixed_column();
+        let q_extend = meta.fixed_column();
+        let q_padding = meta.fixed_column();
+        let q_padding_last = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let q_squeeze = meta.fixed_column();
+        let round_cst = meta.fixed_column();
+        let h_a = meta.fixed_column();
+        let h_e = meta.fixed_column();
+        let word_w = array_init::array_init(|_| meta.advice_column());
+        let word_a = array_init::array_init(|_| meta.advice_column());
+        let word_e = array_init::array_init(|_| meta.advice_column());
+        let is_final = meta.advice_column();
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+
+        let is_enabled = sha256_table.is_enabled;
+        let length = sha256_table.input_len;
+        let data_rlc = sha256_table.input_rlc;
+        let hash_rlc = sha256_table.output_rlc;
+
+        // Queries the bits of a word, or only its low 32 bits without the
+        // carries.
+        let query_bits = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            columns
+                .iter()
+                .map(|&column| meta.query_advice(column, Rotation(rot)))
+                .collect::<Vec<_>>()
+        };
+        let query_word = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            query_bits(meta, &columns[..NUM_BITS_PER_WORD], rot)
+        };
+        let last_is_padding = *is_paddings.last().unwrap();
+
+        meta.create_gate("input checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for bit in query_bits(meta, &word_w, 0)
+                .into_iter()
+                .chain(query_bits(meta, &word_a, 0))
+                .chain(query_bits(meta, &word_e, 0))
+            {
+                cb.require_boolean("bit boolean", bit);
+            }
+            for is_padding in query_bits(meta, &is_paddings, 0) {
+                cb.require_boolean("is_padding boolean", is_padding);
+            }
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // Extend the message schedule with
+        // w[i] = w[i - 16] + s0(w[i - 15]) + w[i - 7] + s1(w[i - 2])
+        meta.create_gate("w extend", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let w_ext = query_bits(meta, &word_w, 0);
+            let w_2 = query_word(meta, &word_w, -2);
+            let w_7 = query_word(meta, &word_w, -7);
+            let w_15 = query_word(meta, &word_w, -15);
+            let w_16 = query_word(meta, &word_w, -16);
+
+            let s0 = xor3::expr(
+                &rotate::expr(&w_15, 7),
+                &rotate::expr(&w_15, 18),
+                &shift::expr(&w_15, 3),
+            );
+            let s1 = xor3::expr(
+                &rotate::expr(&w_2, 17),
+                &rotate::expr(&w_2, 19),
+                &shift::expr(&w_2, 10),
+            );
+            cb.require_equal(
+                "w",
+                decode::expr(&w_ext),
+                decode::expr(&w_16) + decode::expr(&s0) + decode::expr(&w_7) + decode::expr(&s1),
+            );
+            cb.gate(meta.query_fixed(q_extend, Rotation::cur()))
+        });
+
+        meta.create_gate("compression", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [a, b, c, d] = [-1, -2, -3, -4].map(|rot| query_word(meta, &word_a, rot));
+            let [e, f, g, h] = [-1, -2, -3, -4].map(|rot| query_word(meta, &word_e, rot));
+            let w = query_word(meta, &word_w, 0);
+            let new_a_ext = query_bits(meta, &word_a, 0);
+            let new_e_ext = query_bits(meta, &word_e, 0);
+
+            let s1 = xor3::expr(
+                &rotate::expr(&e, 6),
+                &rotate::expr(&e, 11),
+                &rotate::expr(&e, 25),
+            );
+            let ch = e
+                .iter()
+                .zip(f.iter())
+                .zip(g.iter())
+                .map(|((e, f), g)| select::expr(e.expr(), f.expr(), g.expr()))
+                .collect::<Vec<_>>();
+            let temp1 = decode::expr(&h)
+                + decode::expr(&s1)
+                + decode::expr(&ch)
+                + meta.query_fixed(round_cst, Rotation::cur())
+                + decode::expr(&w);
+
+            let s0 = xor3::expr(
+                &rotate::expr(&a, 2),
+                &rotate::expr(&a, 13),
+                &rotate::expr(&a, 22),
+            );
+            let maj = a
+                .iter()
+                .zip(b.iter())
+                .zip(c.iter())
+                .map(|((a, b), c)| select::expr(xor::expr(b.expr(), c.expr()), a.expr(), b.expr()))
+                .collect::<Vec<_>>();
+            let temp2 = decode::expr(&s0) + decode::expr(&maj);
+
+            cb.require_equal(
+                "compress a",
+                decode::expr(&new_a_ext),
+                temp1.clone() + temp2,
+            );
+            cb.require_equal(
+                "compress e",
+                decode::expr(&new_e_ext),
+                decode::expr(&d) + temp1,
+            );
+            cb.gate(meta.query_fixed(q_compression, Rotation::cur()))
+        });
+
+        // The start rows hold the initial hash values when a new hash is
+        // started, which is flagged by `is_final` on these rows, and the state
+        // at the end of the previous block otherwise.
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let new_a_ext = query_bits(meta, &word_a, 0);
+            let new_e_ext = query_bits(meta, &word_e, 0);
+            let d = query_word(meta, &word_a, -4);
+            let h = query_word(meta, &word_e, -4);
+            cb.require_equal(
+                "start a",
+                decode::expr(&new_a_ext),
+                select::expr(
+                    is_final.expr(),
+                    meta.query_fixed(h_a, Rotation::cur()),
+                    decode::expr(&d),
+                ),
+            );
+            cb.require_equal(
+                "start e",
+                decode::expr(&new_e_ext),
+                select::expr(
+                    is_final.expr(),
+                    meta.query_fixed(h_e, Rotation::cur()),
+                    decode::expr(&h),
+                ),
+            );
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        // The end rows add the state after the last round to the state in the
+        // start rows, keeping the carries that are dropped when used in the
+        // next block.
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let new_a_ext = query_bits(meta, &word_a, 0);
+            let new_e_ext = query_bits(meta, &word_e, 0);
+            let d = query_word(meta, &word_a, -4);
+            let h = query_word(meta, &word_e, -4);
+            let rot_start = -((NUM_START_ROWS + NUM_ROUNDS) as i32);
+            let d_start = query_word(meta, &word_a, rot_start);
+            let h_start = query_word(meta, &word_e, rot_start);
+            cb.require_equal(
+                "end a",
+                decode::expr(&new_a_ext),
+                decode::expr(&d) + decode::expr(&d_start),
+            );
+            cb.require_equal(
+                "end e",
+                decode::expr(&new_e_ext),
+                decode::expr(&h) + decode::expr(&h_start),
+            );
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        // The block is the last one of its input when the input length is in
+        // the last 2 words, so when the word before them is padding.
+        let num_length_words = NUM_BYTES_PADDING_LENGTH / NUM_BYTES_PER_WORD;
+        meta.create_gate("is final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let q_start = meta.query_fixed(q_start, Rotation::cur());
+            let q_squeeze = meta.query_fixed(q_squeeze, Rotation::cur());
+            let is_final_prev = meta.query_advice(is_final, Rotation::prev());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let is_padding_last_data_word = meta.query_advice(
+                last_is_padding,
+                Rotation(
+                    -((NUM_END_ROWS + NUM_ROUNDS - NUM_WORDS_TO_ABSORB + num_length_words) as i32),
+                ),
+            );
+
+            cb.condition(q_first.expr(), |cb| {
+                cb.require_equal(
+                    "is_final needs to be enabled on the first row",
+                    is_final.expr(),
+                    1.expr(),
+                );
+            });
+            cb.condition(q_start - q_first, |cb| {
+                cb.require_equal(
+                    "is_final is copied into the start rows",
+                    is_final.expr(),
+                    is_final_prev,
+                );
+            });
+            cb.condition(q_squeeze.expr(), |cb| {
+                cb.require_equal(
+                    "is_final needs to match the padding of the last data word",
+                    is_final.expr(),
+                    is_padding_last_data_word,
+                );
+            });
+            // Only the squeeze rows of the last blocks are enabled in the table
+            cb.require_equal(
+                "is_enabled only on final squeeze rows",
+                meta.query_advice(is_enabled, Rotation::cur()),
+                q_squeeze * is_final,
+            );
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("squeeze", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            // The hash words `a` to `h`, from the end rows
+            let hash_bytes = [0, -1, -2, -3]
+                .map(|rot| query_word(meta, &word_a, rot))
+                .into_iter()
+                .chain([0, -1, -2, -3].map(|rot| query_word(meta, &word_e, rot)))
+                .flat_map(|word| to_be_bytes::expr(&word))
+                .collect::<Vec<_>>();
+            let hash_bytes_le = hash_bytes.into_iter().rev().collect::<Vec<_>>();
+            cb.condition(is_final, |cb| {
+                cb.require_equal(
+                    "hash rlc check",
+                    meta.query_advice(hash_rlc, Rotation::cur()),
+                    rlc::expr(&hash_bytes_le, challenges.keccak_input()),
+                );
+            });
+            cb.gate(meta.query_fixed(q_squeeze, Rotation::cur()))
+        });
+
+        // The input is padded with a 0x80 byte, zeros and its length in bits
+        // as a 64 bit integer. Only lengths up to 2**32 - 1 bits are supported.
+        meta.create_gate("padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_start = meta.query_fixed(q_start, Rotation::cur());
+            let q_padding = meta.query_fixed(q_padding, Rotation::cur());
+            let q_padding_last = meta.query_fixed(q_padding_last, Rotation::cur());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let length = meta.query_advice(length, Rotation::cur());
+            let prev_is_padding = meta.query_advice(last_is_padding, Rotation::prev());
+            let is_paddings = query_bits(meta, &is_paddings, 0);
+            let is_final_padding_row =
+                meta.query_advice(last_is_padding, Rotation(-(num_length_words as i32)));
+            let w = query_word(meta, &word_w, 0);
+            let input_bytes = to_be_bytes::expr(&w);
+
+            for (idx, (is_padding, byte)) in is_paddings.iter().zip(input_bytes.iter()).enumerate()
+            {
+                // Previous padding selector can be on the previous row
+                let is_padding_prev = if idx == 0 {
+                    prev_is_padding.expr()
+                } else {
+                    is_paddings[idx - 1].expr()
+                };
+                let is_first_padding = is_padding.expr() - is_padding_prev;
+
+                // Check padding transition 0 -> 1 done only once
+                cb.condition(q_padding.expr(), |cb| {
+                    cb.require_boolean("padding step boolean", is_first_padding.clone());
+                });
+                // Padding start/intermediate byte, except for the word holding
+                // the length in the last block.
+                cb.condition(
+                    and::expr([q_padding.expr() - q_padding_last.expr(), is_padding.expr()]),
+                    |cb| {
+                        cb.require_equal(
+                            "padding start/intermediate byte",
+                            byte.expr(),
+                            is_first_padding.expr() * 128.expr(),
+                        );
+                    },
+                );
+                cb.condition(
+                    and::expr([
+                        q_padding_last.expr(),
+                        is_padding.expr(),
+                        not::expr(is_final_padding_row.expr()),
+                    ]),
+                    |cb| {
+                        cb.require_equal(
+                            "padding start/intermediate byte last word",
+                            byte.expr(),
+                            is_first_padding.expr() * 128.expr(),
+                        );
+                    },
+                );
+            }
+            // The last word of the last block holds the length in bits. The
+            // word before it is zero as a padding intermediate word.
+            cb.condition(
+                and::expr([q_padding_last.expr(), is_final_padding_row.expr()]),
+                |cb| {
+                    cb.require_equal("padding length", decode::expr(&w), length.expr() * 8.expr());
+                },
+            );
+
+            // Keep the padding state between the words absorbed, resetting it
+            // when a new hash is started.
+            let is_padding_last_byte = is_paddings.last().unwrap().expr();
+            cb.condition(q_start.expr(), |cb| {
+                cb.require_equal(
+                    "last is_padding reset or copied into the start rows",
+                    is_padding_last_byte.expr(),
+                    not::expr(is_final) * prev_is_padding.expr(),
+                );
+            });
+            cb.condition(q_enable - q_start - q_padding, |cb| {
+                cb.require_equal(
+                    "last is_padding copied when not absorbing",
+                    is_padding_last_byte,
+                    prev_is_padding,
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        meta.create_gate("length and data rlc", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            let q_start = meta.query_fixed(q_start, Rotation::cur());
+            let q_padding = meta.query_fixed(q_padding, Rotation::cur());
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            let length_prev = meta.query_advice(length, Rotation::prev());
+            let length = meta.query_advice(length, Rotation::cur());
+            let data_rlc_prev = meta.query_advice(data_rlc, Rotation::prev());
+            let data_rlc = meta.query_advice(data_rlc, Rotation::cur());
+            let data_rlcs = query_bits(meta, &data_rlcs, 0);
+            let is_paddings = query_bits(meta, &is_paddings, 0);
+            let input_bytes = to_be_bytes::expr(&query_word(meta, &word_w, 0));
+
+            // Reset the length and data rlc when a new hash is started
+            cb.condition(q_start.expr(), |cb| {
+                cb.require_equal(
+                    "length reset or copied into the start rows",
+                    length.expr(),
+                    not::expr(is_final.expr()) * length_prev.expr(),
+                );
+                cb.require_equal(
+                    "data_rlc reset or copied into the start rows",
+                    data_rlc.expr(),
+                    not::expr(is_final.expr()) * data_rlc_prev.expr(),
+                );
+            });
+
+            // Update the length and data rlc on the rows absorbing a word
+            cb.condition(q_padding.expr(), |cb| {
+                // Length increases by the number of bytes that aren't padding
+                cb.require_equal(
+                    "update length",
+                    length.expr(),
+                    length_prev.expr()
+                        + sum::expr(
+                            is_paddings
+                                .iter()
+                                .map(|is_padding| not::expr(is_padding.expr())),
+                        ),
+                );
+
+                // Add the word `input_bytes` to `data_rlc`. It has a variable
+                // length represented by `is_paddings`, which requires
+                // intermediate cells to keep the degree low.
+                let mut new_data_rlc = data_rlc_prev.expr();
+                for (idx, (byte, is_padding)) in
+                    input_bytes.iter().zip(is_paddings.iter()).enumerate()
+                {
+                    new_data_rlc = select::expr(
+                        is_padding.expr(),
+                        new_data_rlc.clone(),
+                        new_data_rlc * challenges.keccak_input() + byte.expr(),
+                    );
+                    let data_rlc_after_this_byte = if idx < data_rlcs.len() {
+                        data_rlcs[idx].expr()
+                    } else {
+                        data_rlc.expr()
+                    };
+                    cb.require_equal(
+                        "intermediate data rlc",
+                        data_rlc_after_this_byte.clone(),
+                        new_data_rlc,
+                    );
+                    new_data_rlc = data_rlc_after_this_byte;
+                }
+            });
+
+            // Keep the length and data rlc the same on the other rows
+            cb.condition(q_enable - q_start - q_padding, |cb| {
+                cb.require_equal("length equality check", length, length_prev);
+                cb.require_equal("data_rlc equality check", data_rlc, data_rlc_prev);
+            });
+            cb.gate(1.expr())
+        });
+
+        sha256_table.annotate_columns(meta);
+
+        Sha256CircuitConfig {
+            q_enable,
+            q_first,
+            q_start,
+            q_compression,
+            q_extend,
+            q_padding,
+            q_padding_last,
+            q_end,
+            q_squeeze,
+            round_cst,
+            h_a,
+            h_e,
+            word_w,
+            word_a,
+            word_e,
+            is_final,
+            is_paddings,
+            data_rlcs,
+            sha256_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Sha256CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Sha256Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign sha256 rows",
+            |mut region| {
+                for (offset, sha256_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, sha256_row)?;
+                }
+                self.sha256_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Sha256Row<F>,
+    ) -> Result<(), Error> {
+        let block_row = offset % NUM_ROWS_PER_BLOCK;
+        let round = block_row
+            .checked_sub(NUM_START_ROWS)
+            .filter(|&round| round < NUM_ROUNDS);
+        let is_start = block_row < NUM_START_ROWS;
+        let is_squeeze = block_row == NUM_ROWS_PER_BLOCK - 1;
+
+        // Fixed selectors
+        for (name, column, value) in &[
+            ("q_enable", self.q_enable, F::one()),
+            ("q_first", self.q_first, F::from(offset == 0)),
+            ("q_start", self.q_start, F::from(is_start)),
+            (
+                "q_compression",
+                self.q_compression,
+                F::from(round.is_some()),
+            ),
+            (
+                "q_extend",
+                self.q_extend,
+                F::from(round.map_or(false, |round| round >= NUM_WORDS_TO_ABSORB)),
+            ),
+            (
+                "q_padding",
+                self.q_padding,
+                F::from(round.map_or(false, |round| round < NUM_WORDS_TO_ABSORB)),
+            ),
+            (
+                "q_padding_last",
+                self.q_padding_last,
+                F::from(round == Some(NUM_WORDS_TO_ABSORB - 1)),
+            ),
+            (
+                "q_end",
+                self.q_end,
+                F::from(block_row >= NUM_START_ROWS + NUM_ROUNDS),
+            ),
+            ("q_squeeze", self.q_squeeze, F::from(is_squeeze)),
+            (
+                "round_cst",
+                self.round_cst,
+                F::from(round.map_or(0, |round| ROUND_CST[round] as u64)),
+            ),
+            (
+                "h_a",
+                self.h_a,
+                F::from(if is_start { H[3 - block_row] as u64 } else { 0 }),
+            ),
+            (
+                "h_e",
+                self.h_e,
+                F::from(if is_start { H[7 - block_row] as u64 } else { 0 }),
+            ),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                *column,
+                offset,
+                || Value::known(*value),
+            )?;
+        }
+
+        self.sha256_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(is_squeeze && row.is_final)),
+                row.data_rlc,
+                Value::known(F::from(row.length as u64)),
+                row.hash_rlc,
+            ],
+        )?;
+
+        // Bits of the words
+        for (name, columns, bits) in [
+            ("w", &self.word_w[..], &row.w[..]),
+            ("a", &self.word_a[..], &row.a[..]),
+            ("e", &self.word_e[..], &row.e[..]),
+            ("is_padding", &self.is_paddings[..], &row.is_paddings[..]),
+            ("is_final", &[self.is_final][..], &[row.is_final][..]),
+        ] {
+            for (idx, (&column, &bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} {} {}", name, idx, offset),
+                    column,
+                    offset,
+                    || Value::known(F::from(bit)),
+                )?;
+            }
+        }
+
+        // Intermediate data rlcs
+        for (idx, (&column, &data_rlc)) in
+            self.data_rlcs.iter().zip(row.data_rlcs.iter()).enumerate()
+        {
+            region.assign_advice(
+                || format!("assign data rlc {} {}", idx, offset),
+                column,
+                offset,
+                || data_rlc,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "SHA256_q_enable", self.q_enable);
+        region.name_column(|| "SHA256_q_first", self.q_first);
+        region.name_column(|| "SHA256_q_start", self.q_start);
+        region.name_column(|| "SHA256_q_compression", self.q_compression);
+        region.name_column(|| "SHA256_q_extend", self.q_extend);
+        region.name_column(|| "SHA256_q_padding", self.q_padding);
+        region.name_column(|| "SHA256_q_padding_last", self.q_padding_last);
+        region.name_column(|| "SHA256_q_end", self.q_end);
+        region.name_column(|| "SHA256_q_squeeze", self.q_squeeze);
+        region.name_column(|| "SHA256_is_final", self.is_final);
+    }
+}
+
+/// Sha256Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Sha256Circuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+/// Returns the inputs of the calls to the sha256 precompile
+fn sha256_inputs<F: Field>(block: &witness::Block<F>) -> Vec<Vec<u8>> {
+    block
+        .precompile_events
+        .iter()
+        .filter(|event| event.precompile == PrecompileCalls::Sha256)
+        .map(|event| event.input.clone())
+        .collect()
+}
+
+/// Returns the number of 64 bytes blocks of the padded input
+fn num_blocks(input: &[u8]) -> usize {
+    (input.len() + 1 + NUM_BYTES_PADDING_LENGTH + RATE - 1) / RATE
+}
+
+impl<F: Field> SubCircuit<F> for Sha256Circuit<F> {
+    type Config = Sha256CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // Columns word_a and word_e are queried at 6 distinct rotations, so
+        // returns (6 - 3) + 6 unusable rows.
+        9
+    }
+
+    /// The `block.circuits_params.max_sha256_rows` parameter, when not zero,
+    /// sets up the circuit to support a fixed number of blocks, independently
+    /// of the blocks required by the inputs.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(block.circuits_params.max_sha256_rows, sha256_inputs(block))
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            sha256_inputs(block)
+                .iter()
+                .map(|input| num_blocks(input) * NUM_ROWS_PER_BLOCK)
+                .sum(),
+            block.circuits_params.max_sha256_rows,
+        )
+    }
+
+    /// Make the assignments to the Sha256Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Sha256Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Sha256Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of 64 bytes blocks that can be hashed in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            // Subtract one block for unusable rows
+            Some(self.num_rows / NUM_ROWS_PER_BLOCK - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<Sha256Row<F>> {
+        multi_sha256(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
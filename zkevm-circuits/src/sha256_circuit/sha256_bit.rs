@@ -0,0 +1,198 @@
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// A row of the SHA-256 circuit witness
+#[derive(Clone, Debug)]
+pub(crate) struct Sha256Row<F: Field> {
+    pub(crate) w: [bool; NUM_BITS_PER_WORD_W],
+    pub(crate) a: [bool; NUM_BITS_PER_WORD_EXT],
+    pub(crate) e: [bool; NUM_BITS_PER_WORD_EXT],
+    pub(crate) is_final: bool,
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD - 1],
+    pub(crate) length: usize,
+    pub(crate) data_rlc: Value<F>,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+/// Returns the input right padded as specified by SHA-256: a single 1 bit,
+/// zeros up to a multiple of 64 bytes minus 8, and the input length in bits
+/// as a big endian 64 bit integer.
+fn pad(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while (padded.len() + NUM_BYTES_PADDING_LENGTH) % RATE != 0 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&(bytes.len() as u64 * 8).to_be_bytes());
+    padded
+}
+
+/// Adds the rows hashing `bytes` and returns the hash.
+pub(crate) fn sha256<F: Field>(
+    rows: &mut Vec<Sha256Row<F>>,
+    bytes: &[u8],
+    challenges: Challenges<Value<F>>,
+) -> [u8; 32] {
+    let padded = pad(bytes);
+    let num_blocks = padded.len() / RATE;
+
+    let mut hs = H;
+    let mut length = 0usize;
+    let mut data_rlc = Value::known(F::zero());
+    let mut in_padding = false;
+    let mut hash_bytes = [0u8; 32];
+
+    for (idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final_block = idx == num_blocks - 1;
+
+        // The start rows hold the state of the previous block, or the initial
+        // hash values for the first block.
+        for k in 0..NUM_START_ROWS {
+            rows.push(Sha256Row {
+                w: [false; NUM_BITS_PER_WORD_W],
+                a: to_le_bits(hs[3 - k] as u64),
+                e: to_le_bits(hs[7 - k] as u64),
+                is_final: idx == 0,
+                is_paddings: [false, false, false, in_padding],
+                data_rlcs: [Value::known(F::zero()); NUM_BYTES_PER_WORD - 1],
+                length,
+                data_rlc,
+                hash_rlc: Value::known(F::zero()),
+            });
+        }
+
+        // Message schedule, keeping the carries of the extended words
+        let mut ws = [0u64; NUM_ROUNDS];
+        for (i, word) in block.chunks(NUM_BYTES_PER_WORD).enumerate() {
+            ws[i] = u32::from_be_bytes(word.try_into().unwrap()) as u64;
+        }
+        for i in NUM_WORDS_TO_ABSORB..NUM_ROUNDS {
+            ws[i] = low_word(ws[i - 16]) as u64
+                + small_sigma0(low_word(ws[i - 15])) as u64
+                + low_word(ws[i - 7]) as u64
+                + small_sigma1(low_word(ws[i - 2])) as u64;
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = hs;
+        for (i, &w) in ws.iter().enumerate() {
+            let temp1 = h as u64
+                + big_sigma1(e) as u64
+                + ch(e, f, g) as u64
+                + ROUND_CST[i] as u64
+                + low_word(w) as u64;
+            let temp2 = big_sigma0(a) as u64 + maj(a, b, c) as u64;
+            let new_a = temp1 + temp2;
+            let new_e = d as u64 + temp1;
+
+            // Absorb the input bytes of the first words
+            let mut is_paddings = [false, false, false, in_padding];
+            let mut data_rlcs = [Value::known(F::zero()); NUM_BYTES_PER_WORD - 1];
+            if i < NUM_WORDS_TO_ABSORB {
+                for (j, is_padding) in is_paddings.iter_mut().enumerate() {
+                    let byte_idx = idx * RATE + i * NUM_BYTES_PER_WORD + j;
+                    *is_padding = byte_idx >= bytes.len();
+                    if !*is_padding {
+                        length += 1;
+                        data_rlc = data_rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(data_rlc, r)| data_rlc * r + F::from(bytes[byte_idx] as u64));
+                    }
+                    if j < NUM_BYTES_PER_WORD - 1 {
+                        data_rlcs[j] = data_rlc;
+                    }
+                }
+                in_padding = is_paddings[NUM_BYTES_PER_WORD - 1];
+            }
+
+            rows.push(Sha256Row {
+                w: to_le_bits(w),
+                a: to_le_bits(new_a),
+                e: to_le_bits(new_e),
+                is_final: false,
+                is_paddings,
+                data_rlcs,
+                length,
+                data_rlc,
+                hash_rlc: Value::known(F::zero()),
+            });
+
+            h = g;
+            g = f;
+            f = e;
+            e = low_word(new_e);
+            d = c;
+            c = b;
+            b = a;
+            a = low_word(new_a);
+        }
+
+        // The end rows add the state to the hash values of the previous block
+        let state = [a, b, c, d, e, f, g, h];
+        let sums: Vec<u64> = hs
+            .iter()
+            .zip(state)
+            .map(|(&h, s)| h as u64 + s as u64)
+            .collect();
+        for (h, &sum) in hs.iter_mut().zip(sums.iter()) {
+            *h = low_word(sum);
+        }
+        for (i, h) in hs.iter().enumerate() {
+            hash_bytes[i * NUM_BYTES_PER_WORD..(i + 1) * NUM_BYTES_PER_WORD]
+                .copy_from_slice(&h.to_be_bytes());
+        }
+        for k in 0..NUM_END_ROWS {
+            let is_squeeze = k == NUM_END_ROWS - 1;
+            rows.push(Sha256Row {
+                w: [false; NUM_BITS_PER_WORD_W],
+                a: to_le_bits(sums[3 - k]),
+                e: to_le_bits(sums[7 - k]),
+                is_final: is_squeeze && is_final_block,
+                is_paddings: [false, false, false, in_padding],
+                data_rlcs: [Value::known(F::zero()); NUM_BYTES_PER_WORD - 1],
+                length,
+                data_rlc,
+                hash_rlc: if is_squeeze && is_final_block {
+                    challenges
+                        .keccak_input()
+                        .map(|r| rlc::value(hash_bytes.iter().rev(), r))
+                } else {
+                    Value::known(F::zero())
+                },
+            });
+        }
+    }
+
+    hash_bytes
+}
+
+/// Computes the rows hashing all the inputs, padded with hashes of empty
+/// inputs up to `capacity` blocks when given.
+pub(crate) fn multi_sha256<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Sha256Row<F>>, Error> {
+    let mut rows: Vec<Sha256Row<F>> = Vec::new();
+    for bytes in bytes {
+        sha256(&mut rows, bytes, challenges);
+    }
+    if let Some(capacity) = capacity {
+        let padding_rows = {
+            let mut rows = Vec::new();
+            sha256(&mut rows, &[], challenges);
+            rows
+        };
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < capacity * NUM_ROWS_PER_BLOCK {
+            rows.extend(padding_rows.clone());
+        }
+        // Check that we are not over capacity
+        if rows.len() > capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,83 @@
+#![allow(unused_imports)]
+use super::{sha256_bit::sha256, *};
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::Field;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+
+#[test]
+fn sha256_circuit_unusable_rows() {
+    assert_eq!(
+        Sha256Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Sha256Circuit::<Fr>>(),
+    )
+}
+
+fn verify<F: Field>(k: u32, num_rows: usize, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Sha256Circuit::new(num_rows, inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+#[test]
+fn sha256_hash_values() {
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+    );
+    for (input, hash) in [
+        (
+            "",
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        ),
+        (
+            "abc",
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        ),
+        (
+            "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+        ),
+    ] {
+        let mut rows = Vec::new();
+        assert_eq!(
+            hex::encode(sha256(&mut rows, input.as_bytes(), challenges)),
+            hash
+        );
+        assert_eq!(rows.len() % NUM_ROWS_PER_BLOCK, 0);
+    }
+}
+
+#[test]
+fn sha256_circuit_simple() {
+    // Inputs fitting in a block with the length, or needing an extra block
+    // for it.
+    let inputs = vec![
+        vec![],
+        b"abc".to_vec(),
+        (0u8..55).collect::<Vec<_>>(),
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..200).collect::<Vec<_>>(),
+    ];
+    verify::<Fr>(12, 0, inputs, true);
+}
+
+#[test]
+fn sha256_circuit_padded_to_capacity() {
+    let k = 12;
+    let inputs = vec![b"abc".to_vec(), (0u8..100).collect::<Vec<_>>()];
+    let circuit = Sha256Circuit::<Fr>::new(2usize.pow(k), inputs.clone());
+    assert_eq!(circuit.capacity(), Some(55));
+    verify::<Fr>(k, 2usize.pow(k), inputs, true);
+}
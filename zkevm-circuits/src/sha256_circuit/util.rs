@@ -0,0 +1,117 @@
+use super::param::*;
+
+/// Decodes little endian bits into a value
+pub(crate) mod decode {
+    use eth_types::Field;
+    use gadgets::util::Expr;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        let mut value = 0.expr();
+        let mut multiplier = F::one();
+        for bit in bits.iter() {
+            value = value + bit.expr() * multiplier;
+            multiplier *= F::from(2);
+        }
+        value
+    }
+}
+
+/// Rotates the little endian bits of a word to the right
+pub(crate) mod rotate {
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        let mut rotated = bits.to_vec();
+        rotated.rotate_left(count);
+        rotated
+    }
+}
+
+/// Shifts the little endian bits of a word to the right
+pub(crate) mod shift {
+    use eth_types::Field;
+    use gadgets::util::Expr;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        bits.iter()
+            .skip(count)
+            .cloned()
+            .chain(std::iter::repeat(0.expr()).take(count))
+            .collect()
+    }
+}
+
+/// Bitwise xor of three words
+pub(crate) mod xor3 {
+    use eth_types::Field;
+    use gadgets::util::xor;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(
+        a: &[Expression<F>],
+        b: &[Expression<F>],
+        c: &[Expression<F>],
+    ) -> Vec<Expression<F>> {
+        a.iter()
+            .zip(b.iter())
+            .zip(c.iter())
+            .map(|((a, b), c)| xor::expr(xor::expr(a.clone(), b.clone()), c.clone()))
+            .collect()
+    }
+}
+
+/// Splits the little endian bits of a word into its big endian bytes
+pub(crate) mod to_be_bytes {
+    use super::{decode, NUM_BITS_PER_BYTE};
+    use eth_types::Field;
+    use halo2_proofs::plonk::Expression;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+        debug_assert!(bits.len() % 8 == 0, "bits not a multiple of 8");
+        bits.chunks(NUM_BITS_PER_BYTE)
+            .rev()
+            .map(decode::expr)
+            .collect()
+    }
+}
+
+/// Returns the `N` little endian bits of a value
+pub(crate) fn to_le_bits<const N: usize>(value: u64) -> [bool; N] {
+    debug_assert!(value >> N == 0, "value doesn't fit in {} bits", N);
+    array_init::array_init(|i| (value >> i) & 1 == 1)
+}
+
+// The functions of the message schedule and the compression, as defined in
+// FIPS 180-4 section 4.1.2.
+
+pub(crate) fn small_sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+pub(crate) fn small_sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+pub(crate) fn big_sigma0(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+pub(crate) fn big_sigma1(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+pub(crate) fn ch(e: u32, f: u32, g: u32) -> u32 {
+    (e & f) ^ (!e & g)
+}
+
+pub(crate) fn maj(a: u32, b: u32, c: u32) -> u32 {
+    (a & b) ^ (a & c) ^ (b & c)
+}
+
+/// Returns the low 32 bits of a word with carries
+pub(crate) fn low_word(value: u64) -> u32 {
+    (value & ((1 << NUM_BITS_PER_WORD) - 1)) as u32
+}
@@ -325,6 +325,7 @@ impl<F: Field> StateCircuitConfig<F> {
                             MPTProofType::StorageMod as u64
                         }
                     }
+                    Rw::AccountStorageClear { .. } => MPTProofType::StorageClear as u64,
                     Rw::Account { field_tag, .. } => {
                         if pair[0].is_zero_vartime()
                             && pair[1].is_zero_vartime()
@@ -425,7 +426,7 @@ type Lookup<F> = (&'static str, Expression<F>, Expression<F>);
 pub struct StateCircuit<F> {
     /// Rw rows
     pub rows: Vec<Rw>,
-    updates: MptUpdates,
+    pub(crate) updates: MptUpdates,
     pub(crate) n_rows: usize,
     #[cfg(any(feature = "test", test, feature = "test-circuits"))]
     overrides: HashMap<(dev::AdviceColumn, isize), F>,
@@ -452,7 +453,14 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
     type Config = StateCircuitConfig<F>;
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        Self::new(block.rws.clone(), block.circuits_params.max_rws)
+        Self {
+            rows: block.rws.table_assignments(),
+            updates: block.mpt_updates.clone(),
+            n_rows: block.circuits_params.max_rws,
+            #[cfg(any(feature = "test", test, feature = "test-circuits"))]
+            overrides: HashMap::new(),
+            _marker: PhantomData::default(),
+        }
     }
 
     fn unusable_rows() -> usize {
@@ -580,6 +588,10 @@ fn queries<F: Field>(meta: &mut VirtualCells<'_, F>, c: &StateCircuitConfig<F>)
                 + meta.query_advice(first_different_limb.bits[1], Rotation::cur())
                 + meta.query_advice(first_different_limb.bits[2], Rotation::cur()))
             + final_bits_sum.clone() * (1.expr() - final_bits_sum),
+        // 0 iff first_different_limb is the tag, nonzero otherwise.
+        tag_unchanged_condition: (0..5)
+            .map(|idx| meta.query_advice(first_different_limb.bits[idx], Rotation::cur()))
+            .fold(0.expr(), |acc, bit| acc + bit),
         address: MpiQueries::new(meta, c.sort_keys.address),
         storage_key: RlcQueries::new(meta, c.sort_keys.storage_key),
         initial_value: meta.query_advice(c.initial_value, Rotation::cur()),
@@ -621,7 +633,6 @@ mod state_circuit_stats {
                     ExecutionState::ErrorInvalidOpcode
                         | ExecutionState::CREATE
                         | ExecutionState::CREATE2
-                        | ExecutionState::SELFDESTRUCT
                 )
             },
             bytecode_prefix_op_big_rws,
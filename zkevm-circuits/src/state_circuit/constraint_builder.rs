@@ -7,7 +7,7 @@ use crate::{
         param::N_BYTES_WORD,
         util::{math_gadget::generate_lagrange_base_polynomial, not},
     },
-    table::{AccountFieldTag, MPTProofType, RwTableTag},
+    table::{AccountFieldTag, MPTProofType, RwTableTag, TxReceiptFieldTag},
     util::Expr,
 };
 use eth_types::Field;
@@ -54,6 +54,10 @@ pub struct Queries<F: Field> {
     pub tag_bits: [Expression<F>; 4],
     pub id: MpiQueries<F, N_LIMBS_ID>,
     pub is_tag_and_id_unchanged: Expression<F>,
+    /// Sum of the bits of `first_different_limb`, which is nonzero iff the tag
+    /// is the same as in the previous row.  It isn't boolean, so it's only
+    /// usable as the condition of constraints.
+    pub tag_unchanged_condition: Expression<F>,
     pub address: MpiQueries<F, N_LIMBS_ACCOUNT_ADDRESS>,
     pub storage_key: RlcQueries<F, N_BYTES_WORD>,
     pub initial_value: Expression<F>,
@@ -132,6 +136,12 @@ impl<F: Field> ConstraintBuilder<F> {
         self.condition(q.tag_matches(RwTableTag::TxLog), |cb| {
             cb.build_tx_log_constraints(q)
         });
+        self.condition(q.tag_matches(RwTableTag::TxReceipt), |cb| {
+            cb.build_tx_receipt_constraints(q)
+        });
+        self.condition(q.tag_matches(RwTableTag::TxDestructedAccount), |cb| {
+            cb.build_tx_destructed_account_constraints(q)
+        });
     }
 
     fn build_general_constraints(&mut self, q: &Queries<F>) {
@@ -277,18 +287,36 @@ impl<F: Field> ConstraintBuilder<F> {
     }
 
     fn build_account_storage_constraints(&mut self, q: &Queries<F>) {
-        // TODO: cold VS warm
-        // ref. spec 4.0. Unused keys are 0
-        self.require_zero("field_tag is 0 for AccountStorage", q.field_tag());
+        // There's no lookup into TxAccessListAccountStorage for cold VS warm:
+        // SLOAD and SSTORE charge for the value_prev of their own write to
+        // TxAccessListAccountStorage in the same step, and that value_prev is
+        // chained per (tx_id, address, storage_key) from the initial false by
+        // the TxAccessListAccountStorage constraints, so the first access of a
+        // slot in a tx is charged as cold.
+        // ref. spec 4.0. Unused keys are 0, and the field_tag is 0 for the
+        // storage slots and Clear for the clear of the whole storage, which
+        // has no storage key and writes 0.
+        let is_clear = q.field_tag();
+        self.require_boolean(
+            "field_tag is 0 or Clear for AccountStorage",
+            is_clear.clone(),
+        );
+        self.require_zero(
+            "storage clear has no storage key",
+            is_clear.clone() * q.rw_table.storage_key.clone(),
+        );
+        self.require_zero("storage clear writes 0", is_clear.clone() * q.value());
 
         // value = 0 means the leaf doesn't exist. 0->0 transition requires a
         // non-existing proof.
         let is_non_exist = q.is_non_exist();
         self.require_equal(
-            "mpt_proof_type is field_tag or NonExistingStorageProof",
+            "mpt_proof_type is StorageClear, StorageMod or NonExistingStorageProof",
             q.mpt_proof_type(),
-            is_non_exist.expr() * MPTProofType::NonExistingStorageProof.expr()
-                + (1.expr() - is_non_exist) * MPTProofType::StorageMod.expr(),
+            is_clear.clone() * MPTProofType::StorageClear.expr()
+                + (1.expr() - is_clear)
+                    * (is_non_exist.expr() * MPTProofType::NonExistingStorageProof.expr()
+                        + (1.expr() - is_non_exist) * MPTProofType::StorageMod.expr()),
         );
 
         // ref. spec 4.1. MPT lookup for last access to (address, storage_key)
@@ -513,8 +541,50 @@ impl<F: Field> ConstraintBuilder<F> {
     }
 
     fn build_tx_receipt_constraints(&mut self, q: &Queries<F>) {
-        // TODO: implement TxReceipt constraints
-        self.require_equal("TxReceipt rows not implemented", 1.expr(), 0.expr());
+        // Unused keys are 0
+        self.require_zero("address is 0 for TxReceipt", q.rw_table.address.clone());
+        self.require_zero(
+            "storage_key is 0 for TxReceipt",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_in_set(
+            "field_tag in TxReceiptFieldTag range",
+            q.field_tag(),
+            set::<F, TxReceiptFieldTag>(),
+        );
+
+        // The receipt is only written in EndTx, and the cumulative gas used of
+        // the previous tx is read afterwards, so the first access must be a
+        // write and the initial value is 0.
+        self.condition(q.first_access(), |cb| {
+            cb.require_equal(
+                "first access for TxReceipt is write",
+                q.is_write(),
+                1.expr(),
+            );
+        });
+        self.require_zero("initial TxReceipt value is 0", q.initial_value());
+
+        // PostStateOrStatus is the success flag of the tx
+        self.condition(
+            generate_lagrange_base_polynomial(
+                q.field_tag(),
+                TxReceiptFieldTag::PostStateOrStatus as usize,
+                TxReceiptFieldTag::iter().map(|t| t as usize),
+            ),
+            |cb| cb.require_boolean("PostStateOrStatus is boolean", q.value()),
+        );
+
+        // Receipts are written for consecutive txs, so the tx id only increases
+        // by 0 or 1 while the previous row is also a TxReceipt. That the
+        // cumulative gas used is non-decreasing is enforced by EndTx, which
+        // reads the value of the previous tx and adds a non-negative gas used.
+        self.condition(q.tag_unchanged_condition.clone(), |cb| {
+            cb.require_boolean(
+                "if previous row is also TxReceipt, tx id change is 0 or 1",
+                q.id_change(),
+            )
+        });
 
         self.require_equal(
             "state_root is unchanged for TxReceipt",
@@ -527,6 +597,39 @@ impl<F: Field> ConstraintBuilder<F> {
         );
     }
 
+    fn build_tx_destructed_account_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TxDestructedAccount", q.field_tag());
+        self.require_zero(
+            "storage_key is 0 for TxDestructedAccount",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_boolean("TxDestructedAccount value is boolean", q.value());
+        self.require_zero(
+            "initial TxDestructedAccount value is false",
+            q.initial_value(),
+        );
+        // An account destructed by SELFDESTRUCT is only unset by a reversion or
+        // by the DestructAccount step after EndTx, so that no destruction can be
+        // skipped.
+        self.condition(q.last_access(), |cb| {
+            cb.require_zero("last TxDestructedAccount value is false", q.value())
+        });
+
+        self.require_equal(
+            "state_root is unchanged for TxDestructedAccount",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
     fn require_zero(&mut self, name: &'static str, e: Expression<F>) {
         self.constraints.push((name, self.condition.clone() * e));
     }
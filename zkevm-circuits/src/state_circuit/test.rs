@@ -296,6 +296,29 @@ fn storage_key_rlc() {
     assert_eq!(verify(rows), Ok(()));
 }
 
+#[test]
+fn storage_clear() {
+    let rows = vec![
+        Rw::AccountStorage {
+            rw_counter: 1,
+            is_write: true,
+            account_address: Address::default(),
+            storage_key: U256::from(256),
+            value: U256::from(300),
+            value_prev: U256::zero(),
+            tx_id: 1,
+            committed_value: U256::zero(),
+        },
+        Rw::AccountStorageClear {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 1,
+            account_address: Address::default(),
+        },
+    ];
+    assert_eq!(verify(rows), Ok(()));
+}
+
 #[test]
 fn tx_log_ok() {
     let rows = vec![
@@ -372,6 +395,101 @@ fn tx_log_bad() {
     assert_error_matches(verify(rows), "is_write is always true for TxLog");
 }
 
+#[test]
+fn tx_receipt_ok() {
+    let rows = vec![
+        Rw::TxReceipt {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            field_tag: TxReceiptFieldTag::PostStateOrStatus,
+            value: 1,
+        },
+        Rw::TxReceipt {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 1,
+            field_tag: TxReceiptFieldTag::LogLength,
+            value: 2,
+        },
+        Rw::TxReceipt {
+            rw_counter: 3,
+            is_write: true,
+            tx_id: 1,
+            field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+            value: 21000,
+        },
+        Rw::TxReceipt {
+            rw_counter: 4,
+            is_write: true,
+            tx_id: 2,
+            field_tag: TxReceiptFieldTag::PostStateOrStatus,
+            value: 0,
+        },
+        Rw::TxReceipt {
+            rw_counter: 5,
+            is_write: true,
+            tx_id: 2,
+            field_tag: TxReceiptFieldTag::LogLength,
+            value: 0,
+        },
+        Rw::TxReceipt {
+            rw_counter: 6,
+            is_write: false,
+            tx_id: 1,
+            field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+            value: 21000,
+        },
+        Rw::TxReceipt {
+            rw_counter: 7,
+            is_write: true,
+            tx_id: 2,
+            field_tag: TxReceiptFieldTag::CumulativeGasUsed,
+            value: 42000,
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn tx_receipt_bad_status() {
+    let rows = vec![Rw::TxReceipt {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 1,
+        field_tag: TxReceiptFieldTag::PostStateOrStatus,
+        value: 2,
+    }];
+
+    assert_error_matches(verify(rows), "PostStateOrStatus is boolean");
+}
+
+#[test]
+fn tx_receipt_skipped_tx_id() {
+    let rows = vec![
+        Rw::TxReceipt {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            field_tag: TxReceiptFieldTag::PostStateOrStatus,
+            value: 1,
+        },
+        Rw::TxReceipt {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 3,
+            field_tag: TxReceiptFieldTag::PostStateOrStatus,
+            value: 1,
+        },
+    ];
+
+    assert_error_matches(
+        verify(rows),
+        "if previous row is also TxReceipt, tx id change is 0 or 1",
+    );
+}
+
 #[test]
 fn address_limb_mismatch() {
     let rows = vec![Rw::Account {
@@ -1000,12 +1118,11 @@ fn variadic_size_check() {
 }
 
 #[test]
-#[ignore = "TxReceipt constraints not yet implemented"]
 fn bad_initial_tx_receipt_value() {
     let rows = vec![Rw::TxReceipt {
         rw_counter: 1,
-        is_write: false,
-        tx_id: 3421,
+        is_write: true,
+        tx_id: 1,
         field_tag: TxReceiptFieldTag::CumulativeGasUsed,
         value: 0,
     }];
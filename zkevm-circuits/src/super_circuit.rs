@@ -10,11 +10,17 @@
 //! - [x] EVM Circuit
 //! - [ ] State Circuit
 //! - [x] Tx Circuit
+//! - [x] EcRecover Circuit
+//! - [x] EC Circuit
+//! - [x] ModExp Circuit
+//! - [x] BLAKE2F Circuit
 //! - [x] Bytecode Circuit
 //! - [x] Copy Circuit
 //! - [x] Exponentiation Circuit
 //! - [ ] Keccak Circuit
-//! - [ ] MPT Circuit
+//! - [x] SHA-256 Circuit
+//! - [x] RIPEMD-160 Circuit
+//! - [x] MPT Circuit
 //! - [x] PublicInputs Circuit
 //!
 //! And the following shared tables, with the circuits that use them:
@@ -40,35 +46,62 @@
 //! - [ ] Block Table
 //!   - [ ] EVM Circuit
 //!   - [x] PublicInputs Circuit
-//! - [ ] MPT Table
-//!   - [ ] MPT Circuit
+//! - [x] MPT Table
+//!   - [x] MPT Circuit
 //!   - [ ] State Circuit
 //! - [x] Keccak Table
 //!   - [ ] Keccak Circuit
 //!   - [ ] EVM Circuit
 //!   - [x] Bytecode Circuit
 //!   - [x] Tx Circuit
-//!   - [ ] MPT Circuit
+//!   - [x] EcRecover Circuit
+//!   - [x] MPT Circuit
+//! - [x] EcRecover Table
+//!   - [x] EcRecover Circuit
+//!   - [x] EVM Circuit
+//! - [x] SHA-256 Table
+//!   - [x] SHA-256 Circuit
+//!   - [x] EVM Circuit
+//! - [x] EC Table
+//!   - [x] EC Circuit
+//!   - [x] EVM Circuit
+//! - [x] ModExp Table
+//!   - [x] ModExp Circuit
+//!   - [x] EVM Circuit
+//! - [x] RIPEMD-160 Table
+//!   - [x] RIPEMD-160 Circuit
+//!   - [x] EVM Circuit
+//! - [x] BLAKE2F Table
+//!   - [x] BLAKE2F Circuit
+//!   - [x] EVM Circuit
 
 #[cfg(any(feature = "test", test))]
 pub(crate) mod test;
 
 use crate::{
+    blake2f_circuit::{Blake2fCircuit, Blake2fCircuitConfig, Blake2fCircuitConfigArgs},
     bytecode_circuit::circuit::{
         BytecodeCircuit, BytecodeCircuitConfig, BytecodeCircuitConfigArgs,
     },
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
+    ec_circuit::{EcCircuit, EcCircuitConfig, EcCircuitConfigArgs},
+    ecrecover_circuit::{EcRecoverCircuit, EcRecoverCircuitConfig, EcRecoverCircuitConfigArgs},
     evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitConfig},
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs},
+    mpt_circuit::{MptCircuit, MptCircuitConfig, MptCircuitConfigArgs},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
+    ripemd160_circuit::{Ripemd160Circuit, Ripemd160CircuitConfig, Ripemd160CircuitConfigArgs},
+    sha256_circuit::{Sha256Circuit, Sha256CircuitConfig, Sha256CircuitConfigArgs},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, MptTable, RwTable, TxTable,
+        Blake2fTable, BlockTable, BytecodeTable, CopyTable, EcRecoverTable, EcTable, ExpTable,
+        KeccakTable, ModExpTable, MptTable, Ripemd160Table, RwTable, Sha256Table, TxTable,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
     util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
-    witness::{block_convert, Block, MptUpdates},
+    witness::{block_convert, Block},
 };
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, CircuitsParams},
@@ -86,15 +119,21 @@ use std::array;
 #[derive(Clone)]
 pub struct SuperCircuitConfig<F: Field> {
     block_table: BlockTable,
-    mpt_table: MptTable,
     evm_circuit: EvmCircuitConfig<F>,
     state_circuit: StateCircuitConfig<F>,
     tx_circuit: TxCircuitConfig<F>,
+    ecrecover_circuit: EcRecoverCircuitConfig<F>,
+    ec_circuit: EcCircuitConfig<F>,
+    modexp_circuit: ModExpCircuitConfig<F>,
+    blake2f_circuit: Blake2fCircuitConfig<F>,
     bytecode_circuit: BytecodeCircuitConfig<F>,
     copy_circuit: CopyCircuitConfig<F>,
     keccak_circuit: KeccakCircuitConfig<F>,
+    sha256_circuit: Sha256CircuitConfig<F>,
+    ripemd160_circuit: Ripemd160CircuitConfig<F>,
     pi_circuit: PiCircuitConfig<F>,
     exp_circuit: ExpCircuitConfig<F>,
+    mpt_circuit: MptCircuitConfig<F>,
 }
 
 /// Circuit configuration arguments
@@ -128,6 +167,12 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let exp_table = ExpTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let ecrecover_table = EcRecoverTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let ripemd160_table = Ripemd160Table::construct(meta);
+        let ec_table = EcTable::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let blake2f_table = Blake2fTable::construct(meta);
 
         // Use a mock randomness instead of the randomness derived from the challange
         // (either from mock or real prover) to help debugging assignments.
@@ -149,6 +194,22 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             },
         );
 
+        let sha256_circuit = Sha256CircuitConfig::new(
+            meta,
+            Sha256CircuitConfigArgs {
+                sha256_table: sha256_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+
+        let ripemd160_circuit = Ripemd160CircuitConfig::new(
+            meta,
+            Ripemd160CircuitConfigArgs {
+                ripemd160_table,
+                challenges: challenges.clone(),
+            },
+        );
+
         let pi_circuit = PiCircuitConfig::new(
             meta,
             PiCircuitConfigArgs {
@@ -166,6 +227,43 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 challenges: challenges.clone(),
             },
         );
+        let mpt_circuit = MptCircuitConfig::new(
+            meta,
+            MptCircuitConfigArgs {
+                mpt_table,
+                keccak_table: keccak_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let ecrecover_circuit = EcRecoverCircuitConfig::new(
+            meta,
+            EcRecoverCircuitConfigArgs {
+                ecrecover_table,
+                keccak_table: keccak_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let ec_circuit = EcCircuitConfig::new(
+            meta,
+            EcCircuitConfigArgs {
+                ec_table,
+                challenges: challenges.clone(),
+            },
+        );
+        let modexp_circuit = ModExpCircuitConfig::new(
+            meta,
+            ModExpCircuitConfigArgs {
+                modexp_table,
+                challenges: challenges.clone(),
+            },
+        );
+        let blake2f_circuit = Blake2fCircuitConfig::new(
+            meta,
+            Blake2fCircuitConfigArgs {
+                blake2f_table,
+                challenges: challenges.clone(),
+            },
+        );
         let bytecode_circuit = BytecodeCircuitConfig::new(
             meta,
             BytecodeCircuitConfigArgs {
@@ -205,20 +303,32 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 copy_table,
                 keccak_table,
                 exp_table,
+                ecrecover_table,
+                sha256_table,
+                ripemd160_table,
+                ec_table,
+                modexp_table,
+                blake2f_table,
             },
         );
 
         Self {
             block_table,
-            mpt_table,
             evm_circuit,
             state_circuit,
             copy_circuit,
             tx_circuit,
+            ecrecover_circuit,
+            ec_circuit,
+            modexp_circuit,
+            blake2f_circuit,
             bytecode_circuit,
             keccak_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
             pi_circuit,
             exp_circuit,
+            mpt_circuit,
         }
     }
 }
@@ -237,6 +347,14 @@ pub struct SuperCircuit<
     pub state_circuit: StateCircuit<F>,
     /// The transaction circuit that will be used in the `synthesize` step.
     pub tx_circuit: TxCircuit<F>,
+    /// EcRecover Circuit
+    pub ecrecover_circuit: EcRecoverCircuit<F>,
+    /// EC Circuit
+    pub ec_circuit: EcCircuit<F>,
+    /// ModExp Circuit
+    pub modexp_circuit: ModExpCircuit<F>,
+    /// BLAKE2F Circuit
+    pub blake2f_circuit: Blake2fCircuit<F>,
     /// Public Input Circuit
     pub pi_circuit: PiCircuit<F>,
     /// Bytecode Circuit
@@ -247,6 +365,12 @@ pub struct SuperCircuit<
     pub exp_circuit: ExpCircuit<F>,
     /// Keccak Circuit
     pub keccak_circuit: KeccakCircuit<F>,
+    /// SHA-256 Circuit
+    pub sha256_circuit: Sha256Circuit<F>,
+    /// RIPEMD-160 Circuit
+    pub ripemd160_circuit: Ripemd160Circuit<F>,
+    /// MPT Circuit
+    pub mpt_circuit: MptCircuit<F>,
 }
 
 impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDOMNESS: u64>
@@ -258,7 +382,26 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDO
         assert_eq!(block.circuits_params.max_txs, MAX_TXS);
         let num_rows_tx_circuit =
             TxCircuitConfig::<F>::get_num_rows_required(block.circuits_params.max_txs);
-        num_rows_evm_circuit.max(num_rows_tx_circuit)
+        let num_rows_ecrecover_circuit =
+            EcRecoverCircuit::<F>::min_num_rows(block.circuits_params.max_ecrecover);
+        let num_rows_ec_circuit = EcCircuit::<F>::min_num_rows(
+            block.circuits_params.max_ec_add,
+            block.circuits_params.max_ec_mul,
+        );
+        let num_rows_modexp_circuit =
+            ModExpCircuit::<F>::min_num_rows(block.circuits_params.max_modexp);
+        let num_rows_blake2f_circuit = Blake2fCircuit::<F>::min_num_rows(
+            block.circuits_params.max_blake2f,
+            block.circuits_params.max_blake2f_rounds,
+        );
+        let num_rows_mpt_circuit = MptCircuit::<F>::min_num_rows(&block.mpt_updates);
+        num_rows_evm_circuit
+            .max(num_rows_tx_circuit)
+            .max(num_rows_ecrecover_circuit)
+            .max(num_rows_ec_circuit)
+            .max(num_rows_modexp_circuit)
+            .max(num_rows_blake2f_circuit)
+            .max(num_rows_mpt_circuit)
     }
 }
 
@@ -275,11 +418,18 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDO
             EvmCircuit::<F>::unusable_rows(),
             StateCircuit::<F>::unusable_rows(),
             TxCircuit::<F>::unusable_rows(),
+            EcRecoverCircuit::<F>::unusable_rows(),
+            EcCircuit::<F>::unusable_rows(),
+            ModExpCircuit::<F>::unusable_rows(),
+            Blake2fCircuit::<F>::unusable_rows(),
             PiCircuit::<F>::unusable_rows(),
             BytecodeCircuit::<F>::unusable_rows(),
             CopyCircuit::<F>::unusable_rows(),
             ExpCircuit::<F>::unusable_rows(),
             KeccakCircuit::<F>::unusable_rows(),
+            Sha256Circuit::<F>::unusable_rows(),
+            Ripemd160Circuit::<F>::unusable_rows(),
+            MptCircuit::<F>::unusable_rows(),
         ])
         .unwrap()
     }
@@ -288,21 +438,35 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDO
         let evm_circuit = EvmCircuit::new_from_block(block);
         let state_circuit = StateCircuit::new_from_block(block);
         let tx_circuit = TxCircuit::new_from_block(block);
+        let ecrecover_circuit = EcRecoverCircuit::new_from_block(block);
+        let ec_circuit = EcCircuit::new_from_block(block);
+        let modexp_circuit = ModExpCircuit::new_from_block(block);
+        let blake2f_circuit = Blake2fCircuit::new_from_block(block);
         let pi_circuit = PiCircuit::new_from_block(block);
         let bytecode_circuit = BytecodeCircuit::new_from_block(block);
         let copy_circuit = CopyCircuit::new_from_block_no_external(block);
         let exp_circuit = ExpCircuit::new_from_block(block);
         let keccak_circuit = KeccakCircuit::new_from_block(block);
+        let sha256_circuit = Sha256Circuit::new_from_block(block);
+        let ripemd160_circuit = Ripemd160Circuit::new_from_block(block);
+        let mpt_circuit = MptCircuit::new_from_block(block);
 
         SuperCircuit::<_, MAX_TXS, MAX_CALLDATA, MOCK_RANDOMNESS> {
             evm_circuit,
             state_circuit,
             tx_circuit,
+            ecrecover_circuit,
+            ec_circuit,
+            modexp_circuit,
+            blake2f_circuit,
             pi_circuit,
             bytecode_circuit,
             copy_circuit,
             exp_circuit,
             keccak_circuit,
+            sha256_circuit,
+            ripemd160_circuit,
+            mpt_circuit,
         }
     }
 
@@ -310,12 +474,19 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDO
     fn instance(&self) -> Vec<Vec<F>> {
         let mut instance = Vec::new();
         instance.extend_from_slice(&self.keccak_circuit.instance());
+        instance.extend_from_slice(&self.sha256_circuit.instance());
+        instance.extend_from_slice(&self.ripemd160_circuit.instance());
         instance.extend_from_slice(&self.pi_circuit.instance());
         instance.extend_from_slice(&self.tx_circuit.instance());
+        instance.extend_from_slice(&self.ecrecover_circuit.instance());
+        instance.extend_from_slice(&self.ec_circuit.instance());
+        instance.extend_from_slice(&self.modexp_circuit.instance());
+        instance.extend_from_slice(&self.blake2f_circuit.instance());
         instance.extend_from_slice(&self.bytecode_circuit.instance());
         instance.extend_from_slice(&self.copy_circuit.instance());
         instance.extend_from_slice(&self.state_circuit.instance());
         instance.extend_from_slice(&self.exp_circuit.instance());
+        instance.extend_from_slice(&self.mpt_circuit.instance());
         instance.extend_from_slice(&self.evm_circuit.instance());
 
         instance
@@ -328,11 +499,21 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDO
         let bytecode = BytecodeCircuit::min_num_rows_block(block);
         let copy = CopyCircuit::min_num_rows_block(block);
         let keccak = KeccakCircuit::min_num_rows_block(block);
+        let sha256 = Sha256Circuit::min_num_rows_block(block);
+        let ripemd160 = Ripemd160Circuit::min_num_rows_block(block);
         let tx = TxCircuit::min_num_rows_block(block);
+        let ecrecover = EcRecoverCircuit::min_num_rows_block(block);
+        let ec = EcCircuit::min_num_rows_block(block);
+        let modexp = ModExpCircuit::min_num_rows_block(block);
+        let blake2f = Blake2fCircuit::min_num_rows_block(block);
         let exp = ExpCircuit::min_num_rows_block(block);
         let pi = PiCircuit::min_num_rows_block(block);
+        let mpt = MptCircuit::min_num_rows_block(block);
 
-        let rows: Vec<(usize, usize)> = vec![evm, state, bytecode, copy, keccak, tx, exp, pi];
+        let rows: Vec<(usize, usize)> = vec![
+            evm, state, bytecode, copy, keccak, sha256, ripemd160, tx, ecrecover, ec, modexp,
+            blake2f, exp, pi, mpt,
+        ];
         let (rows_without_padding, rows_with_padding): (Vec<usize>, Vec<usize>) =
             rows.into_iter().unzip();
         (
@@ -350,16 +531,30 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDO
     ) -> Result<(), Error> {
         self.keccak_circuit
             .synthesize_sub(&config.keccak_circuit, challenges, layouter)?;
+        self.sha256_circuit
+            .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        self.ripemd160_circuit
+            .synthesize_sub(&config.ripemd160_circuit, challenges, layouter)?;
         self.bytecode_circuit
             .synthesize_sub(&config.bytecode_circuit, challenges, layouter)?;
         self.tx_circuit
             .synthesize_sub(&config.tx_circuit, challenges, layouter)?;
+        self.ecrecover_circuit
+            .synthesize_sub(&config.ecrecover_circuit, challenges, layouter)?;
+        self.ec_circuit
+            .synthesize_sub(&config.ec_circuit, challenges, layouter)?;
+        self.modexp_circuit
+            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        self.blake2f_circuit
+            .synthesize_sub(&config.blake2f_circuit, challenges, layouter)?;
         self.state_circuit
             .synthesize_sub(&config.state_circuit, challenges, layouter)?;
         self.copy_circuit
             .synthesize_sub(&config.copy_circuit, challenges, layouter)?;
         self.exp_circuit
             .synthesize_sub(&config.exp_circuit, challenges, layouter)?;
+        self.mpt_circuit
+            .synthesize_sub(&config.mpt_circuit, challenges, layouter)?;
         self.evm_circuit
             .synthesize_sub(&config.evm_circuit, challenges, layouter)?;
         self.pi_circuit
@@ -400,20 +595,12 @@ impl<F: Field, const MAX_TXS: usize, const MAX_CALLDATA: usize, const MOCK_RANDO
             Value::known(block.randomness),
             Value::known(block.randomness),
         );
-        let rws = &self.state_circuit.rows;
-
         config.block_table.load(
             &mut layouter,
             &block.context,
             Value::known(block.randomness),
         )?;
 
-        config.mpt_table.load(
-            &mut layouter,
-            &MptUpdates::mock_from(rws),
-            Value::known(block.randomness),
-        )?;
-
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
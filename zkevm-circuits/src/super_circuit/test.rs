@@ -142,6 +142,15 @@ fn serial_test_super_circuit_1tx_1max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_ecrecover: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_modexp: 0,
+        max_blake2f: 0,
+        max_blake2f_rounds: 0,
+        max_mpt_rows: 0,
     };
     test_super_circuit::<MAX_TXS, MAX_CALLDATA, TEST_MOCK_RANDOMNESS>(block, circuits_params);
 }
@@ -160,6 +169,15 @@ fn serial_test_super_circuit_1tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_ecrecover: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_modexp: 0,
+        max_blake2f: 0,
+        max_blake2f_rounds: 0,
+        max_mpt_rows: 0,
     };
     test_super_circuit::<MAX_TXS, MAX_CALLDATA, TEST_MOCK_RANDOMNESS>(block, circuits_params);
 }
@@ -178,6 +196,15 @@ fn serial_test_super_circuit_2tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_sha256_rows: 0,
+        max_ripemd160_rows: 0,
+        max_ecrecover: 0,
+        max_ec_add: 0,
+        max_ec_mul: 0,
+        max_modexp: 0,
+        max_blake2f: 0,
+        max_blake2f_rounds: 0,
+        max_mpt_rows: 0,
     };
     test_super_circuit::<MAX_TXS, MAX_CALLDATA, TEST_MOCK_RANDOMNESS>(block, circuits_params);
 }
@@ -10,7 +10,10 @@ use crate::{
         Block, BlockContext, Bytecode, MptUpdateRow, MptUpdates, Rw, RwMap, RwRow, Transaction,
     },
 };
-use bus_mapping::circuit_input_builder::{CopyDataType, CopyEvent, CopyStep, ExpEvent};
+use bus_mapping::{
+    circuit_input_builder::{CopyDataType, CopyEvent, CopyStep, ExpEvent, PrecompileEvent},
+    precompile::PrecompileCalls,
+};
 use core::iter::once;
 use eth_types::{Field, ToLittleEndian, ToScalar, Word, U256};
 use gadgets::{
@@ -100,6 +103,10 @@ pub enum TxFieldTag {
     Gas,
     /// GasPrice
     GasPrice,
+    /// MaxFeePerGas (EIP-1559)
+    MaxFeePerGas,
+    /// MaxPriorityFeePerGas (EIP-1559)
+    MaxPriorityFeePerGas,
     /// CallerAddress
     CallerAddress,
     /// CalleeAddress
@@ -112,6 +119,14 @@ pub enum TxFieldTag {
     CallDataLength,
     /// Gas cost for transaction call data (4 for byte == 0, 16 otherwise)
     CallDataGasCost,
+    /// Number of addresses in the access list (EIP-2930)
+    AccessListAddressesLen,
+    /// Number of storage keys in the access list (EIP-2930)
+    AccessListStorageKeysLen,
+    /// RLC of the addresses in the access list (EIP-2930)
+    AccessListAddressesRlc,
+    /// RLC of the (address, storage key) pairs in the access list (EIP-2930)
+    AccessListStorageKeysRlc,
     /// TxSignHash: Hash of the transaction without the signature, used for
     /// signing.
     TxSignHash,
@@ -305,6 +320,8 @@ pub enum RwTableTag {
     TxLog,
     /// Tx Receipt operation
     TxReceipt,
+    /// Tx Destructed Account operation
+    TxDestructedAccount,
 }
 impl_expr!(RwTableTag);
 
@@ -318,6 +335,7 @@ impl RwTableTag {
                 | RwTableTag::TxRefund
                 | RwTableTag::Account
                 | RwTableTag::AccountStorage
+                | RwTableTag::TxDestructedAccount
         )
     }
 }
@@ -342,6 +360,15 @@ pub enum AccountFieldTag {
 }
 impl_expr!(AccountFieldTag);
 
+/// Tag for an AccountStorage in RwTable, whose rows of the storage slots have
+/// no field tag
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum AccountStorageFieldTag {
+    /// The whole storage of the account is cleared
+    Clear = 1,
+}
+impl_expr!(AccountStorageFieldTag);
+
 /// Tag for a TxLogField in RwTable
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
 pub enum TxLogFieldTag {
@@ -573,6 +600,9 @@ pub enum MPTProofType {
     StorageMod,
     /// Storage does not exist
     NonExistingStorageProof,
+    /// Storage of an account cleared, which sets its storage root to the empty
+    /// root
+    StorageClear,
 }
 impl_expr!(MPTProofType);
 
@@ -1044,7 +1074,7 @@ pub struct CopyTable {
 }
 
 type CopyTableRow<F> = [(Value<F>, &'static str); 8];
-type CopyCircuitRow<F> = [(Value<F>, &'static str); 4];
+type CopyCircuitRow<F> = [(Value<F>, &'static str); 6];
 
 impl CopyTable {
     /// Construct a new CopyTable
@@ -1067,9 +1097,18 @@ impl CopyTable {
         copy_event: &CopyEvent,
         challenges: Challenges<Value<F>>,
     ) -> Vec<(CopyDataType, CopyTableRow<F>, CopyCircuitRow<F>)> {
+        if matches!(
+            copy_event.src_type,
+            CopyDataType::AccessListAddresses | CopyDataType::AccessListStorageKeys
+        ) {
+            return Self::access_list_assignments(copy_event, challenges);
+        }
+
         let mut assignments = Vec::new();
         // rlc_acc
-        let rlc_acc = if copy_event.dst_type == CopyDataType::RlcAcc {
+        let rlc_acc = if copy_event.src_type == CopyDataType::RlcAcc
+            || copy_event.dst_type == CopyDataType::RlcAcc
+        {
             let values = copy_event
                 .bytes
                 .iter()
@@ -1082,6 +1121,7 @@ impl CopyTable {
             Value::known(F::zero())
         };
         let mut value_acc = Value::known(F::zero());
+        let mut src_value_acc = Value::known(F::zero());
         for (step_idx, (is_read_step, copy_step)) in copy_event
             .bytes
             .iter()
@@ -1165,6 +1205,14 @@ impl CopyTable {
             } else {
                 Value::known(F::from(copy_step.value as u64))
             };
+            // value_acc, accumulated on the read steps of an RlcAcc source
+            let read_value_acc = if copy_event.src_type == CopyDataType::RlcAcc && is_read_step {
+                src_value_acc = src_value_acc * challenges.keccak_input()
+                    + Value::known(F::from(copy_step.value as u64));
+                src_value_acc
+            } else {
+                Value::known(F::zero())
+            };
             // is_pad
             let is_pad = Value::known(F::from(
                 is_read_step && copy_step_addr >= copy_event.src_addr_end,
@@ -1199,12 +1247,113 @@ impl CopyTable {
                     (value, "value"),
                     (is_pad, "is_pad"),
                     (is_code, "is_code"),
+                    (read_value_acc, "value_acc"),
+                    (Value::known(F::zero()), "value_prev"),
                 ],
             ));
         }
         assignments
     }
 
+    /// Generate the copy table and copy circuit assignments from a copy event
+    /// of an access list. The read rows hold the addresses and accumulate the
+    /// RLC of the access list, while the write rows hold the addresses (or
+    /// storage keys) written as warm and whether they were warm before.
+    fn access_list_assignments<F: Field>(
+        copy_event: &CopyEvent,
+        challenges: Challenges<Value<F>>,
+    ) -> Vec<(CopyDataType, CopyTableRow<F>, CopyCircuitRow<F>)> {
+        let is_storage_keys = copy_event.src_type == CopyDataType::AccessListStorageKeys;
+        let id = number_or_hash_to_field(&copy_event.src_id, challenges.evm_word());
+        let length = copy_event.access_list.len();
+
+        let entries = copy_event
+            .access_list
+            .iter()
+            .map(|(address, storage_key, is_warm_prev)| {
+                let address = Value::known(address.to_scalar().unwrap());
+                let storage_key = challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&storage_key.to_le_bytes(), challenge));
+                (address, storage_key, *is_warm_prev)
+            })
+            .collect::<Vec<_>>();
+        let value_accs = entries
+            .iter()
+            .scan(
+                Value::known(F::zero()),
+                |value_acc, (address, storage_key, _)| {
+                    *value_acc = *value_acc * challenges.keccak_input() + *address;
+                    if is_storage_keys {
+                        *value_acc = *value_acc * challenges.keccak_input() + *storage_key;
+                    }
+                    Some(*value_acc)
+                },
+            )
+            .collect::<Vec<_>>();
+        let rlc_acc = value_accs[length - 1];
+
+        let mut assignments = Vec::new();
+        for (idx, ((address, storage_key, is_warm_prev), value_acc)) in
+            entries.into_iter().zip(value_accs).enumerate()
+        {
+            for is_read_step in [true, false] {
+                let step_idx = 2 * idx + !is_read_step as usize;
+                let (tag, addr) = if is_read_step {
+                    (copy_event.src_type, copy_event.src_addr)
+                } else {
+                    (copy_event.dst_type, copy_event.dst_addr)
+                };
+                let value = if !is_read_step && is_storage_keys {
+                    storage_key
+                } else {
+                    address
+                };
+                assignments.push((
+                    tag,
+                    [
+                        (Value::known(F::from(step_idx == 0)), "is_first"),
+                        (id, "id"),
+                        (Value::known(F::from(addr + idx as u64)), "addr"),
+                        (
+                            Value::known(F::from(copy_event.src_addr_end)),
+                            "src_addr_end",
+                        ),
+                        (Value::known(F::from((length - idx) as u64)), "bytes_left"),
+                        (rlc_acc, "rlc_acc"),
+                        (
+                            Value::known(F::from(copy_event.rw_counter(step_idx))),
+                            "rw_counter",
+                        ),
+                        (
+                            Value::known(F::from(copy_event.rw_counter_increase_left(step_idx))),
+                            "rwc_inc_left",
+                        ),
+                    ],
+                    [
+                        (Value::known(F::from(step_idx == 2 * length - 1)), "is_last"),
+                        (value, "value"),
+                        (Value::known(F::zero()), "is_pad"),
+                        (Value::known(F::zero()), "is_code"),
+                        (
+                            if is_read_step {
+                                value_acc
+                            } else {
+                                Value::known(F::zero())
+                            },
+                            "value_acc",
+                        ),
+                        (
+                            Value::known(F::from(!is_read_step && is_warm_prev)),
+                            "value_prev",
+                        ),
+                    ],
+                ));
+            }
+        }
+        assignments
+    }
+
     /// Assign the `CopyTable` from a `Block`.
     pub fn load<F: Field>(
         &self,
@@ -1492,3 +1641,715 @@ impl<F: Field> LookupTable<F> for ExpTable {
         ]
     }
 }
+
+/// Lookup table for the calls to the ecrecover precompiled contract, holding
+/// the input and output of every call.  The output is the 32 bytes left padded
+/// recovered address when the signature is valid, and empty otherwise.  The
+/// table is exposed by the EcRecover Circuit, which verifies the recovery.
+#[derive(Clone, Copy, Debug)]
+pub struct EcRecoverTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Input bytes, right padded to 128 bytes, as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// True when the signature is valid and the output is not empty
+    pub is_valid: Column<Advice>,
+    /// Output bytes as `RLC(reversed(output))`
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for EcRecoverTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.is_valid.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("is_valid"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl EcRecoverTable {
+    /// Construct a new EcRecoverTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            is_valid: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        };
+        for column in <EcRecoverTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    /// Generate the ecrecover table assignments from an ecrecover precompile
+    /// event.
+    pub fn assignments<F: Field>(
+        event: &PrecompileEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 4] {
+        let [input_rlc, output_rlc] = [&event.input, &event.output].map(|bytes| {
+            challenges
+                .keccak_input()
+                .map(|challenge| rlc::value(bytes.iter().rev(), challenge))
+        });
+
+        [
+            Value::known(F::one()),
+            input_rlc,
+            Value::known(F::from(!event.output.is_empty() as u64)),
+            output_rlc,
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume an
+    /// ecrecover table but without running the full EcRecover circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        precompile_events: &[PrecompileEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ecrecover table",
+            |mut region| {
+                let ecrecover_table_columns =
+                    <EcRecoverTable as LookupTable<F>>::advice_columns(self);
+                for &column in ecrecover_table_columns.iter() {
+                    region.assign_advice(
+                        || "ecrecover table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                for (offset, event) in precompile_events
+                    .iter()
+                    .filter(|event| event.precompile == PrecompileCalls::ECRecover)
+                    .enumerate()
+                {
+                    let row = Self::assignments(event, challenges);
+                    for (&column, value) in ecrecover_table_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("ecrecover table row {}", offset + 1),
+                            column,
+                            offset + 1,
+                            || value,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Lookup table for the calls to the sha256 precompiled contract, holding the
+/// input and output of every call.  Like the keccak table, it is exposed by
+/// the SHA-256 Circuit, which verifies the hashes.
+#[derive(Clone, Debug)]
+pub struct Sha256Table {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Hash bytes as `RLC(reversed(output))`
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Sha256Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Sha256Table {
+    /// Construct a new Sha256Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the sha256 table assignments from a sha256 precompile event.
+    pub fn assignments<F: Field>(
+        event: &PrecompileEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 4] {
+        let [input_rlc, output_rlc] = [&event.input, &event.output].map(|bytes| {
+            challenges
+                .keccak_input()
+                .map(|challenge| rlc::value(bytes.iter().rev(), challenge))
+        });
+
+        [
+            Value::known(F::one()),
+            input_rlc,
+            Value::known(F::from(event.input.len() as u64)),
+            output_rlc,
+        ]
+    }
+
+    /// Assign a table row for sha256 table
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 4],
+    ) -> Result<(), Error> {
+        for (&column, value) in <Sha256Table as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            region.assign_advice(|| format!("assign {}", offset), column, offset, || *value)?;
+        }
+        Ok(())
+    }
+
+    /// Provide this function for the case that we want to consume a sha256
+    /// table but without running the full SHA-256 circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        precompile_events: &[PrecompileEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "sha256 table",
+            |mut region| {
+                self.assign_row(&mut region, 0, [Value::known(F::zero()); 4])?;
+                for (offset, event) in precompile_events
+                    .iter()
+                    .filter(|event| event.precompile == PrecompileCalls::Sha256)
+                    .enumerate()
+                {
+                    self.assign_row(
+                        &mut region,
+                        offset + 1,
+                        Self::assignments(event, challenges),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Lookup table for the calls to the ripemd160 precompiled contract, holding
+/// the input and output of every call.  Like the sha256 table, it is exposed
+/// by the RIPEMD-160 Circuit, which verifies the hashes.
+#[derive(Clone, Debug)]
+pub struct Ripemd160Table {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Byte array input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Byte array input length
+    pub input_len: Column<Advice>,
+    /// Hash bytes, left padded to 32 bytes, as `RLC(reversed(output))`
+    pub output_rlc: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Ripemd160Table {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+        ]
+    }
+}
+
+impl Ripemd160Table {
+    /// Construct a new Ripemd160Table
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+        }
+    }
+
+    /// Generate the ripemd160 table assignments from a ripemd160 precompile
+    /// event.
+    pub fn assignments<F: Field>(
+        event: &PrecompileEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 4] {
+        let [input_rlc, output_rlc] = [&event.input, &event.output].map(|bytes| {
+            challenges
+                .keccak_input()
+                .map(|challenge| rlc::value(bytes.iter().rev(), challenge))
+        });
+
+        [
+            Value::known(F::one()),
+            input_rlc,
+            Value::known(F::from(event.input.len() as u64)),
+            output_rlc,
+        ]
+    }
+
+    /// Assign a table row for ripemd160 table
+    pub fn assign_row<F: Field>(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        values: [Value<F>; 4],
+    ) -> Result<(), Error> {
+        for (&column, value) in <Ripemd160Table as LookupTable<F>>::advice_columns(self)
+            .iter()
+            .zip(values.iter())
+        {
+            region.assign_advice(|| format!("assign {}", offset), column, offset, || *value)?;
+        }
+        Ok(())
+    }
+
+    /// Provide this function for the case that we want to consume a ripemd160
+    /// table but without running the full RIPEMD-160 circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        precompile_events: &[PrecompileEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ripemd160 table",
+            |mut region| {
+                self.assign_row(&mut region, 0, [Value::known(F::zero()); 4])?;
+                for (offset, event) in precompile_events
+                    .iter()
+                    .filter(|event| event.precompile == PrecompileCalls::Ripemd160)
+                    .enumerate()
+                {
+                    self.assign_row(
+                        &mut region,
+                        offset + 1,
+                        Self::assignments(event, challenges),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Lookup table for the calls to the BN254 elliptic curve precompiled
+/// contracts ecAdd and ecMul, holding the input and output of every call.  It is exposed by the EC
+/// Circuit, which verifies the operations.
+#[derive(Clone, Copy, Debug)]
+pub struct EcTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Address of the precompiled contract, which identifies the operation
+    pub op_type: Column<Advice>,
+    /// Input bytes, right padded to the input size of ecAdd and ecMul, as
+    /// `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Input length
+    pub input_len: Column<Advice>,
+    /// Output bytes as `RLC(reversed(output))`, empty for a failing call
+    pub output_rlc: Column<Advice>,
+    /// True when the operation succeeds, false when it fails on an invalid
+    /// point
+    pub is_success: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for EcTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.op_type.into(),
+            self.input_rlc.into(),
+            self.input_len.into(),
+            self.output_rlc.into(),
+            self.is_success.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("op_type"),
+            String::from("input_rlc"),
+            String::from("input_len"),
+            String::from("output_rlc"),
+            String::from("is_success"),
+        ]
+    }
+}
+
+impl EcTable {
+    /// Construct a new EcTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            is_enabled: meta.advice_column(),
+            op_type: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            input_len: meta.advice_column(),
+            output_rlc: meta.advice_column_in(SecondPhase),
+            is_success: meta.advice_column(),
+        };
+        for column in <EcTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    /// Return true for the precompile events of the elliptic curve operations
+    pub fn is_ec_op(event: &PrecompileEvent) -> bool {
+        matches!(
+            event.precompile,
+            PrecompileCalls::Bn128Add | PrecompileCalls::Bn128Mul
+        )
+    }
+
+    /// Generate the ec table assignments from an elliptic curve operation
+    /// precompile event.
+    pub fn assignments<F: Field>(
+        event: &PrecompileEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 6] {
+        let [input_rlc, output_rlc] = [&event.input, &event.output].map(|bytes| {
+            challenges
+                .keccak_input()
+                .map(|challenge| rlc::value(bytes.iter().rev(), challenge))
+        });
+
+        [
+            Value::known(F::one()),
+            Value::known(F::from(u64::from(event.precompile))),
+            input_rlc,
+            Value::known(F::from(event.input.len() as u64)),
+            output_rlc,
+            Value::known(F::from(event.is_success as u64)),
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume an ec
+    /// table but without running the full EC circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        precompile_events: &[PrecompileEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ec table",
+            |mut region| {
+                let ec_table_columns = <EcTable as LookupTable<F>>::advice_columns(self);
+                for &column in ec_table_columns.iter() {
+                    region.assign_advice(
+                        || "ec table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                for (offset, event) in precompile_events
+                    .iter()
+                    .filter(|event| Self::is_ec_op(event))
+                    .enumerate()
+                {
+                    let row = Self::assignments(event, challenges);
+                    for (&column, value) in ec_table_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("ec table row {}", offset + 1),
+                            column,
+                            offset + 1,
+                            || value,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Lookup table for the calls to the modexp precompiled contract, holding the
+/// input and output of every call together with its gas cost.  It is exposed
+/// by the ModExp Circuit, which verifies the exponentiation and the gas cost.
+#[derive(Clone, Copy, Debug)]
+pub struct ModExpTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Input bytes, right padded or truncated to the modexp input limit, as
+    /// `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Output bytes as `RLC(reversed(output))`
+    pub output_rlc: Column<Advice>,
+    /// Output length, which is the modulus size
+    pub output_len: Column<Advice>,
+    /// Gas cost of the call
+    pub gas_cost: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for ModExpTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.output_rlc.into(),
+            self.output_len.into(),
+            self.gas_cost.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("output_rlc"),
+            String::from("output_len"),
+            String::from("gas_cost"),
+        ]
+    }
+}
+
+impl ModExpTable {
+    /// Construct a new ModExpTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            output_rlc: meta.advice_column_in(SecondPhase),
+            output_len: meta.advice_column(),
+            gas_cost: meta.advice_column(),
+        };
+        for column in <ModExpTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    /// Generate the modexp table assignments from a modexp precompile event.
+    pub fn assignments<F: Field>(
+        event: &PrecompileEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 5] {
+        let [input_rlc, output_rlc] = [&event.input, &event.output].map(|bytes| {
+            challenges
+                .keccak_input()
+                .map(|challenge| rlc::value(bytes.iter().rev(), challenge))
+        });
+
+        [
+            Value::known(F::one()),
+            input_rlc,
+            output_rlc,
+            Value::known(F::from(event.output.len() as u64)),
+            Value::known(F::from(event.gas_cost)),
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume a modexp
+    /// table but without running the full ModExp circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        precompile_events: &[PrecompileEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp table",
+            |mut region| {
+                let modexp_table_columns = <ModExpTable as LookupTable<F>>::advice_columns(self);
+                for &column in modexp_table_columns.iter() {
+                    region.assign_advice(
+                        || "modexp table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                for (offset, event) in precompile_events
+                    .iter()
+                    .filter(|event| event.precompile == PrecompileCalls::Modexp)
+                    .enumerate()
+                {
+                    let row = Self::assignments(event, challenges);
+                    for (&column, value) in modexp_table_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("modexp table row {}", offset + 1),
+                            column,
+                            offset + 1,
+                            || value,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Lookup table for the calls to the blake2f precompiled contract with an
+/// input of 213 bytes, holding the input and output of every call together
+/// with its gas cost and failure flag.  It is exposed by the BLAKE2F Circuit,
+/// which verifies the compression function, that the gas cost is its number
+/// of rounds and that the call fails iff the final block flag is invalid.
+#[derive(Clone, Copy, Debug)]
+pub struct Blake2fTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Input bytes as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Output bytes as `RLC(reversed(output))`, empty for a failing call
+    pub output_rlc: Column<Advice>,
+    /// Gas cost of the call, which is the number of rounds
+    pub gas_cost: Column<Advice>,
+    /// True when the call succeeds, false when the final block flag is
+    /// neither 0 nor 1
+    pub is_success: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for Blake2fTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.output_rlc.into(),
+            self.gas_cost.into(),
+            self.is_success.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("output_rlc"),
+            String::from("gas_cost"),
+            String::from("is_success"),
+        ]
+    }
+}
+
+impl Blake2fTable {
+    /// Construct a new Blake2fTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let table = Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            output_rlc: meta.advice_column_in(SecondPhase),
+            gas_cost: meta.advice_column(),
+            is_success: meta.advice_column(),
+        };
+        for column in <Blake2fTable as LookupTable<F>>::advice_columns(&table) {
+            meta.enable_equality(column);
+        }
+        table
+    }
+
+    /// Generate the blake2f table assignments from a blake2f precompile
+    /// event.
+    pub fn assignments<F: Field>(
+        event: &PrecompileEvent,
+        challenges: &Challenges<Value<F>>,
+    ) -> [Value<F>; 5] {
+        let [input_rlc, output_rlc] = [&event.input, &event.output].map(|bytes| {
+            challenges
+                .keccak_input()
+                .map(|challenge| rlc::value(bytes.iter().rev(), challenge))
+        });
+
+        [
+            Value::known(F::one()),
+            input_rlc,
+            output_rlc,
+            Value::known(F::from(event.gas_cost)),
+            Value::known(F::from(event.is_success as u64)),
+        ]
+    }
+
+    /// Provide this function for the case that we want to consume a blake2f
+    /// table but without running the full BLAKE2F circuit
+    pub fn dev_load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        precompile_events: &[PrecompileEvent],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "blake2f table",
+            |mut region| {
+                let blake2f_table_columns = <Blake2fTable as LookupTable<F>>::advice_columns(self);
+                for &column in blake2f_table_columns.iter() {
+                    region.assign_advice(
+                        || "blake2f table all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::zero()),
+                    )?;
+                }
+
+                for (offset, event) in precompile_events
+                    .iter()
+                    .filter(|event| event.precompile == PrecompileCalls::Blake2F)
+                    .enumerate()
+                {
+                    let row = Self::assignments(event, challenges);
+                    for (&column, value) in blake2f_table_columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("blake2f table row {}", offset + 1),
+                            column,
+                            offset + 1,
+                            || value,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
@@ -16,7 +16,7 @@ pub use dev::TxCircuit as TestTxCircuit;
 use crate::{
     table::{KeccakTable, TxFieldTag, TxTable},
     util::{random_linear_combine_word as rlc, Challenges, SubCircuit, SubCircuitConfig},
-    witness,
+    witness::{self, access_list_len, access_list_rlc},
 };
 use eth_types::{
     geth_types::Transaction, sign_types::SignData, Address, Field, ToLittleEndian, ToScalar,
@@ -30,12 +30,14 @@ use log::error;
 use sign_verify::{AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig};
 use std::marker::PhantomData;
 
-/// Number of static fields per tx: [nonce, gas, gas_price,
-/// caller_address, callee_address, is_create, value, call_data_length,
-/// call_data_gas_cost, tx_sign_hash].
+/// Number of static fields per tx: [nonce, gas, gas_price, max_fee_per_gas,
+/// max_priority_fee_per_gas, caller_address, callee_address, is_create, value,
+/// call_data_length, call_data_gas_cost, access_list_addresses_len,
+/// access_list_storage_keys_len, access_list_addresses_rlc,
+/// access_list_storage_keys_rlc, tx_sign_hash].
 /// Note that call data bytes are layed out in the TxTable after all the static
 /// fields arranged by txs.
-pub(crate) const TX_LEN: usize = 10;
+pub(crate) const TX_LEN: usize = 16;
 
 /// Config for TxCircuit
 #[derive(Clone, Debug)]
@@ -202,6 +204,13 @@ impl<F: Field> TxCircuit<F> {
                     } else {
                         &tx_default
                     };
+                    let (access_list_addresses_len, access_list_storage_keys_len) =
+                        access_list_len(&tx.access_list);
+                    let access_list_rlc = challenges.keccak_input().zip(challenges.evm_word()).map(
+                        |(keccak_input, evm_word)| {
+                            access_list_rlc(&tx.access_list, keccak_input, evm_word)
+                        },
+                    );
 
                     for (tag, value) in [
                         (
@@ -220,6 +229,18 @@ impl<F: Field> TxCircuit<F> {
                                 .evm_word()
                                 .map(|challenge| rlc(tx.gas_price.to_le_bytes(), challenge)),
                         ),
+                        (
+                            TxFieldTag::MaxFeePerGas,
+                            challenges
+                                .evm_word()
+                                .map(|challenge| rlc(tx.gas_fee_cap.to_le_bytes(), challenge)),
+                        ),
+                        (
+                            TxFieldTag::MaxPriorityFeePerGas,
+                            challenges
+                                .evm_word()
+                                .map(|challenge| rlc(tx.gas_tip_cap.to_le_bytes(), challenge)),
+                        ),
                         (
                             TxFieldTag::CallerAddress,
                             Value::known(tx.from.to_scalar().expect("tx.from too big")),
@@ -256,6 +277,22 @@ impl<F: Field> TxCircuit<F> {
                                     .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 }),
                             )),
                         ),
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            Value::known(F::from(access_list_addresses_len)),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysLen,
+                            Value::known(F::from(access_list_storage_keys_len)),
+                        ),
+                        (
+                            TxFieldTag::AccessListAddressesRlc,
+                            access_list_rlc.map(|(addresses_rlc, _)| addresses_rlc),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysRlc,
+                            access_list_rlc.map(|(_, storage_keys_rlc)| storage_keys_rlc),
+                        ),
                         (
                             TxFieldTag::TxSignHash,
                             assigned_sig_verif.msg_hash_rlc.value().copied(),
@@ -9,12 +9,11 @@ use crate::{
     table::KeccakTable,
     util::{Challenges, Expr},
 };
-use ecc::{maingate, EccConfig, GeneralEccChip};
-use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey, EcdsaChip};
+use ecc::{maingate, AssignedPoint, EccConfig, GeneralEccChip};
 use eth_types::{
     self,
-    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Field,
+    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData, SECP256K1_Q},
+    Field, Word,
 };
 use halo2_proofs::{
     arithmetic::{CurveAffine, FieldExt},
@@ -35,11 +34,11 @@ use itertools::Itertools;
 use keccak256::plain::Keccak;
 use log::error;
 use maingate::{
-    AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip, RangeConfig,
-    RangeInstructions, RegionCtx,
+    AssignedCondition, AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+    RangeConfig, RangeInstructions, RegionCtx,
 };
 use num::Integer;
-use std::{iter, marker::PhantomData};
+use std::{collections::BTreeSet, iter, marker::PhantomData};
 
 /// Auxiliary Gadget to verify a that a message hash is signed by the public
 /// key corresponding to an Ethereum Address.
@@ -81,10 +80,12 @@ impl<F: Field> SignVerifyChip<F> {
         // the tx circuit with max_txs=1. For example:
         // `RUST_LOG=debug RUST_BACKTRACE=1 cargo test tx_circuit_1tx_1max_tx --release
         // --all-features -- --nocapture`
-        // The value rows_range_chip_table has been optained by patching the halo2
-        // library to report the number of rows used in the range chip table
-        // region. TODO: Figure out a way to get these numbers automatically.
-        let rows_range_chip_table = 295188;
+        let (rns_base, rns_scalar) =
+            GeneralEccChip::<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::rns();
+        let rows_range_chip_table = range_chip_table_rows(
+            &RANGE_COMPOSITION_BIT_LENS,
+            &[rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+        );
         let rows_ecc_chip_aux = 226;
         let rows_ecdsa_chip_verification = 140360;
         let rows_signature_address_verify = 76;
@@ -107,9 +108,36 @@ impl<F: Field> Default for SignVerifyChip<F> {
     }
 }
 
-const NUMBER_OF_LIMBS: usize = 4;
-const BIT_LEN_LIMB: usize = 72;
-const BIT_LEN_LAST_LIMB: usize = 256 - (NUMBER_OF_LIMBS - 1) * BIT_LEN_LIMB;
+pub(crate) const NUMBER_OF_LIMBS: usize = 4;
+pub(crate) const BIT_LEN_LIMB: usize = 72;
+pub(crate) const BIT_LEN_LAST_LIMB: usize = 256 - (NUMBER_OF_LIMBS - 1) * BIT_LEN_LIMB;
+
+// Bit lengths of the limbs of the integers.
+pub(crate) const LIMB_BIT_LENS: [usize; NUMBER_OF_LIMBS] =
+    [BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LAST_LIMB];
+
+/// Composition bit lengths of the range chip: the limbs are decomposed into
+/// `NUMBER_OF_LIMBS` sublimbs, and the bytes are range checked.
+pub(crate) const RANGE_COMPOSITION_BIT_LENS: [usize; 2] = [BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8];
+
+/// Return the number of rows of the table of a range chip configured with
+/// `composition_bit_lens` and `overflow_bit_lens`, which holds all the values
+/// of each distinct bit length.
+pub(crate) fn range_chip_table_rows(
+    composition_bit_lens: &[usize],
+    overflow_bit_lens: &[usize],
+) -> usize {
+    composition_bit_lens
+        .iter()
+        .chain(overflow_bit_lens)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|bit_len| 1 << bit_len)
+        .sum()
+}
+
+type AssignedScalar<F> = AssignedInteger<secp256k1::Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedSecp256k1<F> = AssignedPoint<secp256k1::Fp, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
 
 /// SignVerify Configuration
 #[derive(Debug, Clone)]
@@ -139,7 +167,7 @@ impl SignVerifyConfig {
         let range_config = RangeChip::<F>::configure(
             meta,
             &main_gate_config,
-            vec![BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8],
+            RANGE_COMPOSITION_BIT_LENS.to_vec(),
             [rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
         );
 
@@ -212,7 +240,7 @@ impl SignVerifyConfig {
     }
 
     #[rustfmt::skip]
-    fn configure_rlc<F: Field>(
+    pub(crate) fn configure_rlc<F: Field>(
         meta: &mut ConstraintSystem<F>,
         name: &'static str,
         main_gate_config: MainGateConfig,
@@ -276,7 +304,7 @@ pub(crate) enum Term<F> {
 }
 
 impl<F: Field> Term<F> {
-    fn assigned(cell: Cell, value: Value<F>) -> Self {
+    pub(crate) fn assigned(cell: Cell, value: Value<F>) -> Self {
         Self::Assigned(cell, value)
     }
 
@@ -303,18 +331,32 @@ pub(crate) struct AssignedECDSA<F: Field> {
     pk_x_le: [AssignedValue<F>; 32],
     pk_y_le: [AssignedValue<F>; 32],
     msg_hash_le: [AssignedValue<F>; 32],
+    sig_r_le: [AssignedValue<F>; 32],
+    sig_s_le: [AssignedValue<F>; 32],
+    recovery_id: AssignedCondition<F>,
 }
 
 #[derive(Debug)]
 pub(crate) struct AssignedSignatureVerify<F: Field> {
     pub(crate) address: AssignedValue<F>,
     pub(crate) msg_hash_rlc: AssignedValue<F>,
+    is_address_zero: AssignedValue<F>,
+    address_be: Vec<AssignedValue<F>>,
+}
+
+/// Assigned row of the ecrecover table.
+#[derive(Debug)]
+pub(crate) struct AssignedEcRecover<F: Field> {
+    pub(crate) is_enabled: AssignedValue<F>,
+    pub(crate) input_rlc: AssignedCell<F, F>,
+    pub(crate) is_valid: AssignedValue<F>,
+    pub(crate) output_rlc: AssignedCell<F, F>,
 }
 
 // Return an array of bytes that corresponds to the little endian representation
 // of the integer, adding the constraints to verify the correctness of the
 // conversion (byte range check included).
-fn integer_to_bytes_le<F: Field, FE: FieldExt>(
+pub(crate) fn integer_to_bytes_le<F: Field, FE: FieldExt>(
     ctx: &mut RegionCtx<'_, F>,
     range_chip: &RangeChip<F>,
     int: &AssignedInteger<FE, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
@@ -322,11 +364,12 @@ fn integer_to_bytes_le<F: Field, FE: FieldExt>(
     let bytes = int
         .limbs()
         .iter()
-        .zip_eq([BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LIMB, BIT_LEN_LAST_LIMB])
+        .zip_eq(LIMB_BIT_LENS)
         .map(|(limb, bit_len)| {
-            range_chip
-                .decompose(ctx, limb.as_ref().value().copied(), 8, bit_len)
-                .map(|(_, byte)| byte)
+            let (composed, bytes) =
+                range_chip.decompose(ctx, limb.as_ref().value().copied(), 8, bit_len)?;
+            ctx.constrain_equal(composed.cell(), limb.as_ref().cell())?;
+            Ok::<_, Error>(bytes)
         })
         .collect::<Result<Vec<_>, _>>()?
         .into_iter()
@@ -335,6 +378,72 @@ fn integer_to_bytes_le<F: Field, FE: FieldExt>(
     Ok(bytes.try_into().unwrap())
 }
 
+/// Return the limb at `idx` of an integer.
+pub(crate) fn limb_value(value: Word, idx: usize) -> u128 {
+    ((value >> (idx * BIT_LEN_LIMB)) & ((Word::one() << BIT_LEN_LIMB) - 1)).low_u128()
+}
+
+// Compare with the secp256k1 curve order `n` the integer encoded by the 32 big
+// endian `bytes`, assigned in `bytes_be`, returning whether it's lower than `n`
+// and the little endian bytes of its difference with `n` modulo 2^256, which is
+// the integer reduced modulo `n` when it's not lower than `n`.  The difference
+// is subtracted limb by limb with boolean borrows, the last one being set when
+// the integer is lower than `n`.
+fn assign_order_comparison<F: Field>(
+    ctx: &mut RegionCtx<'_, F>,
+    main_gate: &MainGate<F>,
+    range_chip: &RangeChip<F>,
+    bytes: &[u8],
+    bytes_be: &[AssignedValue<F>],
+) -> Result<(AssignedCondition<F>, Vec<AssignedValue<F>>), Error> {
+    let encoded = Word::from_big_endian(bytes);
+    let order = Word::from_little_endian(&SECP256K1_Q.to_bytes_le());
+    let (difference, _) = encoded.overflowing_sub(order);
+    let bytes_le = bytes_be.iter().rev().collect_vec();
+
+    let mut difference_le = Vec::new();
+    let mut borrow_value = 0u128;
+    let mut borrow: Option<AssignedCondition<F>> = None;
+    for (idx, bit_len) in LIMB_BIT_LENS.into_iter().enumerate() {
+        let offset = idx * BIT_LEN_LIMB / 8;
+        let terms = bytes_le[offset..offset + bit_len / 8]
+            .iter()
+            .zip(iter::successors(Some(F::one()), |coeff| {
+                Some(F::from(256) * coeff)
+            }))
+            .map(|(byte, coeff)| maingate::Term::Assigned(*byte, coeff))
+            .collect_vec();
+        let limb = main_gate.compose(ctx, &terms, F::zero())?;
+        let (difference_limb, difference_bytes) = range_chip.decompose(
+            ctx,
+            Value::known(F::from_u128(limb_value(difference, idx))),
+            8,
+            bit_len,
+        )?;
+        difference_le.extend(difference_bytes);
+
+        // limb + next_borrow * 2^bit_len = n_limb + difference_limb + borrow
+        let next_borrow_value =
+            (limb_value(encoded, idx) < limb_value(order, idx) + borrow_value) as u128;
+        let next_borrow =
+            main_gate.assign_bit(ctx, Value::known(F::from_u128(next_borrow_value)))?;
+        let mut terms = vec![
+            maingate::Term::Assigned(&limb, F::one()),
+            maingate::Term::Assigned(&next_borrow, F::from_u128(1 << bit_len)),
+            maingate::Term::Assigned(&difference_limb, -F::one()),
+        ];
+        if let Some(borrow) = &borrow {
+            terms.push(maingate::Term::Assigned(borrow, -F::one()));
+        }
+        let sum = main_gate.compose(ctx, &terms, -F::from_u128(limb_value(order, idx)))?;
+        main_gate.assert_zero(ctx, &sum)?;
+        borrow_value = next_borrow_value;
+        borrow = Some(next_borrow);
+    }
+
+    Ok((borrow.expect("at least one limb"), difference_le))
+}
+
 /// Helper structure pass around references to all the chips required for an
 /// ECDSA veficication.
 struct ChipsRef<'a, F: Field, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB: usize> {
@@ -342,7 +451,6 @@ struct ChipsRef<'a, F: Field, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB:
     range_chip: &'a RangeChip<F>,
     ecc_chip: &'a GeneralEccChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
     scalar_chip: &'a IntegerChip<secp256k1::Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-    ecdsa_chip: &'a EcdsaChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
 }
 
 impl<F: Field> SignVerifyChip<F> {
@@ -374,7 +482,6 @@ impl<F: Field> SignVerifyChip<F> {
             range_chip,
             ecc_chip,
             scalar_chip,
-            ecdsa_chip,
         } = chips;
 
         let integer_r = ecc_chip.new_unassigned_scalar(Value::known(*sig_r));
@@ -383,26 +490,26 @@ impl<F: Field> SignVerifyChip<F> {
 
         let r_assigned = scalar_chip.assign_integer(ctx, integer_r, Range::Remainder)?;
         let s_assigned = scalar_chip.assign_integer(ctx, integer_s, Range::Remainder)?;
-        let sig = AssignedEcdsaSig {
-            r: r_assigned,
-            s: s_assigned,
-        };
-
-        let pk_in_circuit = ecc_chip.assign_point(ctx, Value::known(*pk))?;
-        let pk_assigned = AssignedPublicKey {
-            point: pk_in_circuit,
-        };
+        let pk_assigned = ecc_chip.assign_point(ctx, Value::known(*pk))?;
         let msg_hash = scalar_chip.assign_integer(ctx, msg_hash, Range::Remainder)?;
 
-        // Convert (msg_hash, pk_x, pk_y) integers to little endian bytes
+        // Convert (msg_hash, pk_x, pk_y, sig_r, sig_s) integers to little endian
+        // bytes
         let msg_hash_le = integer_to_bytes_le(ctx, range_chip, &msg_hash)?;
-        let pk_x = pk_assigned.point.x();
-        let pk_x_le = integer_to_bytes_le(ctx, range_chip, pk_x)?;
-        let pk_y = pk_assigned.point.y();
-        let pk_y_le = integer_to_bytes_le(ctx, range_chip, pk_y)?;
+        let sig_r_le = integer_to_bytes_le(ctx, range_chip, &r_assigned)?;
+        let sig_s_le = integer_to_bytes_le(ctx, range_chip, &s_assigned)?;
+        let pk_x_le = integer_to_bytes_le(ctx, range_chip, pk_assigned.x())?;
+        let pk_y_le = integer_to_bytes_le(ctx, range_chip, pk_assigned.y())?;
 
         // Ref. spec SignVerifyChip 4. Verify the ECDSA signature
-        ecdsa_chip.verify(ctx, &sig, &pk_assigned, &msg_hash)?;
+        let recovery_id = self.verify_ecdsa(
+            ctx,
+            chips,
+            &r_assigned,
+            &s_assigned,
+            &pk_assigned,
+            &msg_hash,
+        )?;
 
         // TODO: Update once halo2wrong suports the following methods:
         // - `IntegerChip::assign_integer_from_bytes_le`
@@ -412,54 +519,63 @@ impl<F: Field> SignVerifyChip<F> {
             pk_x_le,
             pk_y_le,
             msg_hash_le,
+            sig_r_le,
+            sig_s_le,
+            recovery_id,
         })
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn assign_rlc_le(
+    /// Verify the ECDSA signature `(r, s)` of `msg_hash` by `pk`, returning its
+    /// recovery id, which is the parity of the `y` coordinate of the signature
+    /// point `R = u1 * G + u2 * pk`, with `u1 = msg_hash / s` and `u2 = r / s`.
+    ///
+    /// The `x` coordinate of `R` is required to be `r`, rather than only
+    /// congruent to it modulo the curve order, as only the recovery ids 0 and 1
+    /// are used in Ethereum.  Both `r` and `s` are required to be in `[1, n)`,
+    /// so that their bytes are canonical.
+    fn verify_ecdsa(
         &self,
-        config: &SignVerifyConfig,
         ctx: &mut RegionCtx<F>,
         chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-        name: &str,
-        q_rlc: Selector,
-        challenge: Value<F>,
-        inputs_le: impl IntoIterator<Item = Term<F>>,
-    ) -> Result<AssignedCell<F, F>, Error> {
-        let zero = chips.main_gate.assign_constant(ctx, F::zero())?;
-        let columns = config.main_gate_config.advices();
-        let inputs_le = inputs_le.into_iter().collect_vec();
-        let inputs_be = iter::repeat_with(|| Term::assigned(zero.cell(), Value::known(F::zero())))
-            .take(Integer::next_multiple_of(&inputs_le.len(), &columns.len()) - inputs_le.len())
-            .chain(inputs_le.into_iter().rev())
-            .collect_vec();
+        sig_r: &AssignedScalar<F>,
+        sig_s: &AssignedScalar<F>,
+        pk: &AssignedSecp256k1<F>,
+        msg_hash: &AssignedScalar<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            range_chip,
+            ecc_chip,
+            scalar_chip,
+        } = chips;
+        let base_chip = ecc_chip.base_field_chip();
 
-        let mut rlc = Value::known(F::zero());
-        for (chunk_idx, chunk) in inputs_be.chunks_exact(columns.len()).enumerate() {
-            ctx.enable(q_rlc)?;
-            let assigned_rlc = ctx.assign_advice(|| "{name}_rlc[{chunk_idx}]", config.rlc, rlc)?;
-            for ((idx, column), term) in (chunk_idx * chunk.len()..).zip(columns).zip(chunk) {
-                let copied =
-                    ctx.assign_advice(|| format!("{name}_byte[{idx}]"), column, term.value())?;
-                if let Some(cell) = term.cell() {
-                    ctx.constrain_equal(cell, copied.cell())?;
-                }
-            }
-            if chunk_idx == 0 {
-                ctx.constrain_equal(zero.cell(), assigned_rlc.cell())?;
-            }
-            rlc = iter::once(rlc)
-                .chain(chunk.iter().map(|term| term.value()))
-                .fold(Value::known(F::zero()), |acc, input| {
-                    acc * challenge + input
-                });
-            ctx.next();
+        for scalar in [sig_r, sig_s] {
+            scalar_chip.assert_in_field(ctx, scalar)?;
+            scalar_chip.assert_not_zero(ctx, scalar)?;
+        }
+        let (s_inv, _) = scalar_chip.invert(ctx, sig_s)?;
+        let u1 = scalar_chip.mul(ctx, msg_hash, &s_inv)?;
+        let u2 = scalar_chip.mul(ctx, sig_r, &s_inv)?;
+        let generator = ecc_chip.assign_constant(ctx, Secp256k1Affine::generator())?;
+        let u1_generator = ecc_chip.mul(ctx, &generator, &u1, self.window_size)?;
+        let u2_pk = ecc_chip.mul(ctx, pk, &u2, self.window_size)?;
+        let point_r = ecc_chip.add(ctx, &u1_generator, &u2_pk)?;
+
+        // Both integers are in their canonical representation, so that they're
+        // equal when their limbs are.
+        let point_r_x = base_chip.reduce(ctx, point_r.x())?;
+        base_chip.assert_in_field(ctx, &point_r_x)?;
+        for (a, b) in point_r_x.limbs().iter().zip_eq(sig_r.limbs().iter()) {
+            main_gate.assert_equal(ctx, a.as_ref(), b.as_ref())?;
         }
 
-        let assigned_rlc = ctx.assign_advice(|| "{name}_rlc", config.rlc, rlc)?;
-        ctx.next();
-
-        Ok(assigned_rlc)
+        // The parity of y is the lowest bit of its lowest byte.
+        let point_r_y = base_chip.reduce(ctx, point_r.y())?;
+        base_chip.assert_in_field(ctx, &point_r_y)?;
+        let point_r_y_le = integer_to_bytes_le(ctx, range_chip, &point_r_y)?;
+        let bits = main_gate.to_bits(ctx, &point_r_y_le[0], 8)?;
+        Ok(bits[0].clone())
     }
 
     fn enable_keccak_lookup(
@@ -528,17 +644,7 @@ impl<F: Field> SignVerifyChip<F> {
                 .zip(powers_of_256.into_iter().rev())
                 .map(|(byte, coeff)| maingate::Term::Unassigned(*byte, coeff))
                 .collect_vec();
-            let (address, pk_hash_lo) =
-                main_gate.decompose(ctx, &terms, F::zero(), |_, _| Ok(()))?;
-
-            (
-                address,
-                pk_hash_lo
-                    .into_iter()
-                    .zip(pk_hash[12..].iter())
-                    .map(|(assigned, byte)| Term::assigned(assigned.cell(), *byte))
-                    .collect_vec(),
-            )
+            main_gate.decompose(ctx, &terms, F::zero(), |_, _| Ok(()))?
         };
         let is_address_zero = main_gate.is_zero(ctx, &address)?;
 
@@ -555,10 +661,11 @@ impl<F: Field> SignVerifyChip<F> {
                 .then(|| sign_data.msg_hash.to_bytes())
                 .unwrap_or_default()
                 .map(|byte| Value::known(F::from(byte as u64)));
-            self.assign_rlc_le(
-                config,
+            assign_rlc_le(
                 ctx,
-                chips,
+                main_gate,
+                config.main_gate_config.advices(),
+                config.rlc,
                 "msg_hash",
                 config.q_rlc_evm_word,
                 challenges.evm_word(),
@@ -577,10 +684,11 @@ impl<F: Field> SignVerifyChip<F> {
                 .chain(sign_data.pk.y.to_bytes())
                 .chain(sign_data.pk.x.to_bytes())
                 .map(|byte| Value::known(F::from(byte as u64)));
-            self.assign_rlc_le(
-                config,
+            assign_rlc_le(
                 ctx,
-                chips,
+                main_gate,
+                config.main_gate_config.advices(),
+                config.rlc,
                 "pk_hash",
                 config.q_rlc_keccak_input,
                 challenges.keccak_input(),
@@ -590,15 +698,22 @@ impl<F: Field> SignVerifyChip<F> {
             )?
         };
 
-        let pk_hash_rlc = self.assign_rlc_le(
-            config,
+        let pk_hash_rlc = assign_rlc_le(
             ctx,
-            chips,
+            main_gate,
+            config.main_gate_config.advices(),
+            config.rlc,
             "pk_hash_rlc",
             config.q_rlc_evm_word,
             challenges.evm_word(),
             iter::empty()
-                .chain(pk_hash_lo.into_iter().rev())
+                .chain(
+                    pk_hash_lo
+                        .iter()
+                        .zip(pk_hash[12..].iter())
+                        .map(|(assigned, byte)| Term::assigned(assigned.cell(), *byte))
+                        .rev(),
+                )
                 .chain(pk_hash_hi.into_iter().rev().map(Term::unassigned)),
         )?;
 
@@ -607,6 +722,8 @@ impl<F: Field> SignVerifyChip<F> {
         Ok(AssignedSignatureVerify {
             address,
             msg_hash_rlc,
+            is_address_zero,
+            address_be: pk_hash_lo,
         })
     }
 
@@ -617,6 +734,23 @@ impl<F: Field> SignVerifyChip<F> {
         signatures: &[SignData],
         challenges: &Challenges<Value<F>>,
     ) -> Result<Vec<AssignedSignatureVerify<F>>, Error> {
+        let signatures = signatures.iter().cloned().map(Some).collect_vec();
+        let (_, assigned_sig_verifs) =
+            self.assign_verifications(config, layouter, &signatures, challenges)?;
+        Ok(assigned_sig_verifs)
+    }
+
+    /// Assign the verifications of `signatures`, where a `None` signature, as
+    /// well as the signatures beyond its length up to `max_verif`, is assigned
+    /// as a padding verification (enabled when address == 0).
+    #[allow(clippy::type_complexity)]
+    fn assign_verifications(
+        &self,
+        config: &SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        signatures: &[Option<SignData>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(Vec<AssignedECDSA<F>>, Vec<AssignedSignatureVerify<F>>), Error> {
         if signatures.len() > self.max_verif {
             error!(
                 "signatures.len() = {} > max_verif = {}",
@@ -643,14 +777,11 @@ impl<F: Field> SignVerifyChip<F> {
             },
         )?;
 
-        let ecdsa_chip = EcdsaChip::new(ecc_chip.clone());
-
         let chips = ChipsRef {
             main_gate: &main_gate,
             range_chip: &range_chip,
             ecc_chip: &ecc_chip,
             scalar_chip,
-            ecdsa_chip: &ecdsa_chip,
         };
 
         let assigned_ecdsas = layouter.assign_region(
@@ -659,12 +790,10 @@ impl<F: Field> SignVerifyChip<F> {
                 let mut assigned_ecdsas = Vec::new();
                 let mut ctx = RegionCtx::new(region, 0);
                 for i in 0..self.max_verif {
-                    let signature = if i < signatures.len() {
-                        signatures[i].clone()
-                    } else {
+                    let signature = signatures.get(i).cloned().flatten().unwrap_or_else(|| {
                         // padding (enabled when address == 0)
                         SignData::default()
-                    };
+                    });
                     let assigned_ecdsa = self.assign_ecdsa(&mut ctx, &chips, &signature)?;
                     assigned_ecdsas.push(assigned_ecdsa);
                 }
@@ -673,13 +802,14 @@ impl<F: Field> SignVerifyChip<F> {
             },
         )?;
 
-        layouter.assign_region(
+        let assigned_sig_verifs = layouter.assign_region(
             || "signature address verify",
             |region| {
                 let mut assigned_sig_verifs = Vec::new();
                 let mut ctx = RegionCtx::new(region, 0);
                 for (i, assigned_ecdsa) in assigned_ecdsas.iter().enumerate() {
-                    let sign_data = signatures.get(i); // None when padding (enabled when address == 0)
+                    // None when padding (enabled when address == 0)
+                    let sign_data = signatures.get(i).and_then(|sign_data| sign_data.as_ref());
                     let assigned_sig_verif = self.assign_signature_verify(
                         config,
                         &mut ctx,
@@ -693,8 +823,260 @@ impl<F: Field> SignVerifyChip<F> {
                 log::debug!("signature address verify: {} rows", ctx.offset());
                 Ok(assigned_sig_verifs)
             },
+        )?;
+
+        Ok((assigned_ecdsas, assigned_sig_verifs))
+    }
+
+    /// Assign the rows of the ecrecover table from the ecrecover precompile
+    /// calls, each one given by its input (right padded to 128 bytes) and the
+    /// [`SignData`] of its signature when it's valid.
+    ///
+    /// For a valid signature, the input bytes are the message hash, which is
+    /// reduced modulo the secp256k1 curve order `n` into the verified one as
+    /// for the transactions, `v` (27 plus the recovery id) and the signature,
+    /// and the output bytes are the address derived from the verified public
+    /// key.  A signature is proved invalid, with an empty output, when `v` is
+    /// neither 27 nor 28, or when `r` or `s` is zero or not lower than `n`.
+    /// The other invalid signatures, whose `r` isn't the x coordinate of a
+    /// curve point, can't be assigned: such calls are rejected by
+    /// [`PrecompileCalls::is_supported`].
+    ///
+    /// [`PrecompileCalls::is_supported`]: bus_mapping::precompile::PrecompileCalls::is_supported
+    pub(crate) fn assign_ecrecover(
+        &self,
+        config: &SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        calls: &[(Vec<u8>, Option<SignData>)],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedEcRecover<F>>, Error> {
+        let signatures = calls
+            .iter()
+            .map(|(_, sign_data)| sign_data.clone())
+            .collect_vec();
+        let (assigned_ecdsas, assigned_sig_verifs) =
+            self.assign_verifications(config, layouter, &signatures, challenges)?;
+
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        layouter.assign_region(
+            || "ecrecover",
+            |region| {
+                let mut assigned_ecrecovers = Vec::new();
+                let mut ctx = RegionCtx::new(region, 0);
+                for (i, (assigned_ecdsa, assigned_sig_verif)) in assigned_ecdsas
+                    .iter()
+                    .zip_eq(assigned_sig_verifs.iter())
+                    .enumerate()
+                {
+                    let (is_enabled, input, is_valid) = match calls.get(i) {
+                        Some((input, sign_data)) => (true, input.clone(), sign_data.is_some()),
+                        None => (false, vec![0; 128], false),
+                    };
+                    let assigned_ecrecover = self.assign_ecrecover_row(
+                        config,
+                        &mut ctx,
+                        &main_gate,
+                        &range_chip,
+                        is_enabled,
+                        &input,
+                        is_valid,
+                        assigned_ecdsa,
+                        assigned_sig_verif,
+                        challenges,
+                    )?;
+                    assigned_ecrecovers.push(assigned_ecrecover);
+                }
+                log::debug!("ecrecover: {} rows", ctx.offset());
+                Ok(assigned_ecrecovers)
+            },
         )
     }
+
+    #[allow(clippy::too_many_arguments)]
+    fn assign_ecrecover_row(
+        &self,
+        config: &SignVerifyConfig,
+        ctx: &mut RegionCtx<F>,
+        main_gate: &MainGate<F>,
+        range_chip: &RangeChip<F>,
+        is_enabled: bool,
+        input: &[u8],
+        is_valid: bool,
+        assigned_ecdsa: &AssignedECDSA<F>,
+        assigned_sig_verif: &AssignedSignatureVerify<F>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedEcRecover<F>, Error> {
+        let zero = main_gate.assign_constant(ctx, F::zero())?;
+        let is_enabled = main_gate.assign_bit(ctx, Value::known(F::from(is_enabled as u64)))?;
+        let is_valid = main_gate.assign_bit(ctx, Value::known(F::from(is_valid as u64)))?;
+
+        // Input bytes: msg_hash (32 bytes), v (32 bytes), r (32 bytes) and s (32
+        // bytes).
+        let input_be = input
+            .iter()
+            .map(|byte| range_chip.assign(ctx, Value::known(F::from(*byte as u64)), 8, 8))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The signature is proved invalid when v is neither 27 nor 28, or when r
+        // or s is zero or not lower than n.  The bytes are range checked, so that
+        // their sum is zero only when all of them are.
+        let [is_v_hi_zero, is_r_zero, is_s_zero] = [32..63, 64..96, 96..128].map(|range| {
+            let terms = input_be[range]
+                .iter()
+                .map(|byte| maingate::Term::Assigned(byte, F::one()))
+                .collect_vec();
+            let sum = main_gate.compose(ctx, &terms, F::zero())?;
+            main_gate.is_zero(ctx, &sum)
+        });
+        let [is_v_hi_zero, is_r_zero, is_s_zero] = [is_v_hi_zero?, is_r_zero?, is_s_zero?];
+        let v_27 = main_gate.assign_constant(ctx, F::from(27))?;
+        let v_28 = main_gate.assign_constant(ctx, F::from(28))?;
+        let is_v_27 = main_gate.is_equal(ctx, &input_be[63], &v_27)?;
+        let is_v_28 = main_gate.is_equal(ctx, &input_be[63], &v_28)?;
+        let is_v_lo_valid = main_gate.or(ctx, &is_v_27, &is_v_28)?;
+        let is_v_valid = main_gate.and(ctx, &is_v_hi_zero, &is_v_lo_valid)?;
+        let is_v_invalid = main_gate.not(ctx, &is_v_valid)?;
+        let is_invalid = main_gate.or(ctx, &is_v_invalid, &is_r_zero)?;
+        let mut is_invalid = main_gate.or(ctx, &is_invalid, &is_s_zero)?;
+        for range in [64..96, 96..128] {
+            let (is_lower, _) = assign_order_comparison(
+                ctx,
+                main_gate,
+                range_chip,
+                &input[range.clone()],
+                &input_be[range],
+            )?;
+            let is_not_lower = main_gate.not(ctx, &is_lower)?;
+            is_invalid = main_gate.or(ctx, &is_invalid, &is_not_lower)?;
+        }
+
+        // The signature of an enabled row is valid unless it's proved invalid,
+        // and its verification is not a padding one, so that the public key
+        // matches the address.
+        let is_not_invalid = main_gate.not(ctx, &is_invalid)?;
+        let is_enabled_and_not_invalid = main_gate.mul(ctx, &is_enabled, &is_not_invalid)?;
+        main_gate.assert_equal(ctx, &is_valid, &is_enabled_and_not_invalid)?;
+        let is_valid_and_address_zero =
+            main_gate.mul(ctx, &is_valid, &assigned_sig_verif.is_address_zero)?;
+        main_gate.assert_zero(ctx, &is_valid_and_address_zero)?;
+
+        // The message hash is reduced modulo n, by subtracting n when it's not
+        // lower than n, as the hash is lower than 2n.
+        let (is_msg_hash_lower, difference_le) =
+            assign_order_comparison(ctx, main_gate, range_chip, &input[..32], &input_be[..32])?;
+        let msg_hash_be = input_be[..32]
+            .iter()
+            .zip_eq(difference_le.iter().rev())
+            .map(|(byte, difference)| main_gate.select(ctx, byte, difference, &is_msg_hash_lower))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The input of a valid signature is the verified one, with the reduced
+        // message hash, and where v is 27 plus the recovery id.
+        let v = main_gate.add_constant(ctx, &assigned_ecdsa.recovery_id, F::from(27))?;
+        let verified_input_be = iter::empty()
+            .chain(assigned_ecdsa.msg_hash_le.iter().rev())
+            .chain(iter::repeat(&zero).take(31))
+            .chain(iter::once(&v))
+            .chain(assigned_ecdsa.sig_r_le.iter().rev())
+            .chain(assigned_ecdsa.sig_s_le.iter().rev())
+            .collect_vec();
+        let expected_input_be = msg_hash_be.iter().chain(input_be[32..].iter());
+        for (verified, expected) in verified_input_be.into_iter().zip_eq(expected_input_be) {
+            let selected = main_gate.select(ctx, verified, expected, &is_valid)?;
+            main_gate.assert_equal(ctx, &selected, expected)?;
+        }
+        let input_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            config.main_gate_config.advices(),
+            config.rlc,
+            "ecrecover_input",
+            config.q_rlc_keccak_input,
+            challenges.keccak_input(),
+            input_be
+                .iter()
+                .rev()
+                .map(|byte| Term::assigned(byte.cell(), byte.value().copied())),
+        )?;
+
+        // Output bytes: the address left padded to 32 bytes, or empty.  As
+        // leading zeros don't change the RLC, only the address bytes are taken.
+        let output_be = assigned_sig_verif
+            .address_be
+            .iter()
+            .map(|byte| main_gate.select(ctx, byte, &zero, &is_valid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let output_rlc = assign_rlc_le(
+            ctx,
+            main_gate,
+            config.main_gate_config.advices(),
+            config.rlc,
+            "ecrecover_output",
+            config.q_rlc_keccak_input,
+            challenges.keccak_input(),
+            output_be
+                .iter()
+                .rev()
+                .map(|byte| Term::assigned(byte.cell(), byte.value().copied())),
+        )?;
+
+        Ok(AssignedEcRecover {
+            is_enabled,
+            input_rlc,
+            is_valid,
+            output_rlc,
+        })
+    }
+}
+
+/// Assign the rows computing the RLC of `inputs_le` with the `q_rlc` gate
+/// configured by [`SignVerifyConfig::configure_rlc`] on the main gate
+/// `columns` and the `rlc_column`, returning the assigned RLC.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn assign_rlc_le<F: Field>(
+    ctx: &mut RegionCtx<F>,
+    main_gate: &MainGate<F>,
+    columns: [Column<Advice>; 5],
+    rlc_column: Column<Advice>,
+    name: &str,
+    q_rlc: Selector,
+    challenge: Value<F>,
+    inputs_le: impl IntoIterator<Item = Term<F>>,
+) -> Result<AssignedCell<F, F>, Error> {
+    let zero = main_gate.assign_constant(ctx, F::zero())?;
+    let inputs_le = inputs_le.into_iter().collect_vec();
+    let inputs_be = iter::repeat_with(|| Term::assigned(zero.cell(), Value::known(F::zero())))
+        .take(Integer::next_multiple_of(&inputs_le.len(), &columns.len()) - inputs_le.len())
+        .chain(inputs_le.into_iter().rev())
+        .collect_vec();
+
+    let mut rlc = Value::known(F::zero());
+    for (chunk_idx, chunk) in inputs_be.chunks_exact(columns.len()).enumerate() {
+        ctx.enable(q_rlc)?;
+        let assigned_rlc = ctx.assign_advice(|| "{name}_rlc[{chunk_idx}]", rlc_column, rlc)?;
+        for ((idx, column), term) in (chunk_idx * chunk.len()..).zip(columns).zip(chunk) {
+            let copied =
+                ctx.assign_advice(|| format!("{name}_byte[{idx}]"), column, term.value())?;
+            if let Some(cell) = term.cell() {
+                ctx.constrain_equal(cell, copied.cell())?;
+            }
+        }
+        if chunk_idx == 0 {
+            ctx.constrain_equal(zero.cell(), assigned_rlc.cell())?;
+        }
+        rlc = iter::once(rlc)
+            .chain(chunk.iter().map(|term| term.value()))
+            .fold(Value::known(F::zero()), |acc, input| {
+                acc * challenge + input
+            });
+        ctx.next();
+    }
+
+    let assigned_rlc = ctx.assign_advice(|| "{name}_rlc", rlc_column, rlc)?;
+    ctx.next();
+
+    Ok(assigned_rlc)
 }
 
 fn pub_key_hash_to_address<F: Field>(pk_hash: &[u8]) -> F {
@@ -10,9 +10,11 @@ mod call;
 pub use call::Call;
 mod mpt;
 pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates};
+pub(crate) use mpt::{MptUpdateProof, TriePath, TrieProof};
 mod rw;
 pub use rw::{Rw, RwMap, RwRow};
 mod step;
 pub use step::ExecStep;
 mod tx;
 pub use tx::Transaction;
+pub(crate) use tx::{access_list_len, access_list_rlc};
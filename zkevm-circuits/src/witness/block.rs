@@ -5,13 +5,15 @@ use crate::evm_circuit::{detect_fixed_table_tags, EvmCircuit};
 
 use crate::{evm_circuit::util::rlc, table::BlockContextFieldTag, util::SubCircuit};
 use bus_mapping::{
-    circuit_input_builder::{self, CircuitsParams, CopyEvent, ExpEvent},
+    circuit_input_builder::{self, CircuitsParams, CopyEvent, ExpEvent, PrecompileEvent},
     Error,
 };
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word};
+use eth_types::{Address, BigEndianHash, Field, ToLittleEndian, ToScalar, Word, H256};
 use halo2_proofs::circuit::Value;
 
-use super::{step::step_convert, tx::tx_convert, Bytecode, ExecStep, RwMap, Transaction};
+use super::{
+    step::step_convert, tx::tx_convert, Bytecode, ExecStep, MptUpdates, RwMap, Transaction,
+};
 
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Block is the struct used by all circuits, which contains all the needed
@@ -29,6 +31,8 @@ pub struct Block<F> {
     pub end_block_last: ExecStep,
     /// Read write events in the RwTable
     pub rws: RwMap,
+    /// Updates of the state trie by the RwTable, proved by the MPT circuit
+    pub mpt_updates: MptUpdates,
     /// Bytecode used in the block
     pub bytecodes: HashMap<Word, Bytecode>,
     /// The block context
@@ -39,6 +43,8 @@ pub struct Block<F> {
     pub exp_events: Vec<ExpEvent>,
     /// Pad exponentiation circuit to make selectors fixed.
     pub exp_circuit_pad_to: usize,
+    /// Precompiled contract calls for the precompile tables.
+    pub precompile_events: Vec<PrecompileEvent>,
     /// Circuit Setup Parameters
     pub circuits_params: CircuitsParams,
     /// Inputs to the SHA3 opcode
@@ -91,7 +97,7 @@ impl<F: Field> Block<F> {
             .map(|bytecode| bytecode.bytes.len() + 1)
             .sum();
         let num_rows_required_for_copy_table: usize =
-            self.copy_events.iter().map(|c| c.bytes.len() * 2).sum();
+            self.copy_events.iter().map(|c| c.copy_length() * 2).sum();
         let num_rows_required_for_keccak_table: usize = self.keccak_inputs.len();
         let num_rows_required_for_tx_table: usize =
             self.txs.iter().map(|tx| 9 + tx.call_data.len()).sum();
@@ -100,6 +106,7 @@ impl<F: Field> Block<F> {
             .iter()
             .map(|e| e.steps.len() * OFFSET_INCREMENT)
             .sum();
+        let num_rows_required_for_precompile_tables: usize = self.precompile_events.len() + 1;
 
         let rows_needed: usize = itertools::max([
             num_rows_required_for_execution_steps,
@@ -110,20 +117,22 @@ impl<F: Field> Block<F> {
             num_rows_required_for_keccak_table,
             num_rows_required_for_tx_table,
             num_rows_required_for_exp_table,
+            num_rows_required_for_precompile_tables,
         ])
         .unwrap();
 
         let k = log2_ceil(EvmCircuit::<F>::unusable_rows() + rows_needed);
         log::debug!(
             "num_rows_requred_for rw_table={}, fixed_table={}, bytecode_table={}, \
-            copy_table={}, keccak_table={}, tx_table={}, exp_table={}",
+            copy_table={}, keccak_table={}, tx_table={}, exp_table={}, precompile_tables={}",
             num_rows_required_for_rw_table,
             num_rows_required_for_fixed_table,
             num_rows_required_for_bytecode_table,
             num_rows_required_for_copy_table,
             num_rows_required_for_keccak_table,
             num_rows_required_for_tx_table,
-            num_rows_required_for_exp_table
+            num_rows_required_for_exp_table,
+            num_rows_required_for_precompile_tables
         );
         log::debug!("evm circuit uses k = {}, rows = {}", k, rows_needed);
         k
@@ -237,19 +246,41 @@ pub fn block_convert<F: Field>(
 ) -> Result<Block<F>, Error> {
     let rws = RwMap::from(&block.container);
     rws.check_value();
+    // Blocks built without the proofs of the prestate get mock state roots,
+    // whose updates can't be proven by the MPT circuit.
+    let mpt_updates = if block.prestate_proofs.is_empty() {
+        MptUpdates::mock_from(&rws.table_assignments())
+    } else {
+        let mpt_updates = MptUpdates::from_proofs(
+            &rws.table_assignments(),
+            block.prev_state_root,
+            &block.prestate_proofs,
+            &block.trie_nodes,
+        )
+        .map_err(Error::InvalidMptUpdates)?;
+        // Mock blocks don't have a state root.
+        let state_root = H256::from_uint(&mpt_updates.new_root());
+        if !block.eth_block.state_root.is_zero() && state_root != block.eth_block.state_root {
+            return Err(Error::InvalidStateRoot(state_root));
+        }
+        mpt_updates
+    };
+    let mut keccak_inputs = circuit_input_builder::keccak_inputs(block, code_db)?;
+    keccak_inputs.extend(mpt_updates.keccak_inputs());
     Ok(Block {
         // randomness: F::from(0x100), // Special value to reveal elements after RLC
         randomness: F::from(0xcafeu64),
         context: block.into(),
         rws,
+        mpt_updates,
         txs: block
             .txs()
             .iter()
             .enumerate()
-            .map(|(idx, tx)| tx_convert(tx, idx + 1))
+            .map(|(idx, tx)| tx_convert(tx, idx + 1, &block.container))
             .collect(),
-        end_block_not_last: step_convert(&block.block_steps.end_block_not_last),
-        end_block_last: step_convert(&block.block_steps.end_block_last),
+        end_block_not_last: step_convert(&block.block_steps.end_block_not_last, &block.container),
+        end_block_last: step_convert(&block.block_steps.end_block_last, &block.container),
         bytecodes: code_db
             .0
             .values()
@@ -260,11 +291,12 @@ pub fn block_convert<F: Field>(
             .collect(),
         copy_events: block.copy_events.clone(),
         exp_events: block.exp_events.clone(),
+        precompile_events: block.precompile_events.clone(),
         sha3_inputs: block.sha3_inputs.clone(),
         circuits_params: block.circuits_params,
         exp_circuit_pad_to: <usize>::default(),
         prev_state_root: block.prev_state_root,
-        keccak_inputs: circuit_input_builder::keccak_inputs(block, code_db)?,
+        keccak_inputs,
         eth_block: block.eth_block.clone(),
     })
 }
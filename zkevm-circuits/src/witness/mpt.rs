@@ -2,10 +2,14 @@ use crate::{
     evm_circuit::{util::rlc, witness::Rw},
     table::{AccountFieldTag, MPTProofType},
 };
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word};
+use eth_types::{
+    mpt::{key_path, storage_value_rlp, PartialTrie, ProofNode, TrieAccount, EMPTY_TRIE_ROOT},
+    Address, BigEndianHash, Bytes, EIP1186ProofResponse, Field, ToBigEndian, ToLittleEndian,
+    ToScalar, ToWord, Word, H256,
+};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// An MPT update whose validity is proved by the MptCircuit
 #[derive(Debug, Clone, Copy)]
@@ -18,8 +22,8 @@ pub struct MptUpdate {
 }
 
 impl MptUpdate {
-    fn proof_type<F: Field>(&self) -> F {
-        let proof_type = match self.key {
+    pub(crate) fn proof_type(&self) -> MPTProofType {
+        match self.key {
             Key::AccountStorage { .. } => {
                 if self.old_value.is_zero() && self.new_value.is_zero() {
                     MPTProofType::NonExistingStorageProof
@@ -28,8 +32,13 @@ impl MptUpdate {
                 }
             }
             Key::Account { field_tag, .. } => field_tag.into(),
-        };
-        F::from(proof_type as u64)
+            Key::AccountStorageClear { .. } => MPTProofType::StorageClear,
+        }
+    }
+
+    /// Whether the value is left unchanged by the update
+    pub(crate) fn is_unchanged(&self) -> bool {
+        self.old_value == self.new_value
     }
 }
 
@@ -37,7 +46,88 @@ impl MptUpdate {
 #[derive(Default, Clone, Debug)]
 pub struct MptUpdates {
     old_root: Word,
+    new_root: Word,
     updates: BTreeMap<Key, MptUpdate>,
+    // The proofs of the updates, which mock updates don't have.
+    proofs: BTreeMap<Key, MptUpdateProof>,
+}
+
+/// The proofs of an MPT update, in the storage trie of the account for storage
+/// updates, and in the state trie.
+#[derive(Debug, Clone)]
+pub(crate) struct MptUpdateProof {
+    pub(crate) storage: Option<TrieProof>,
+    pub(crate) account: TrieProof,
+    /// The storage roots of the account before and after a storage clear,
+    /// which are the values of the update in the state trie.
+    pub(crate) storage_roots: Option<[H256; 2]>,
+}
+
+/// The RLP encoded nodes on the path of a key in a trie, from the root, before
+/// and after an update.
+#[derive(Debug, Clone)]
+pub(crate) struct TrieProof {
+    /// The key, before hashing
+    pub(crate) key: Vec<u8>,
+    pub(crate) old: TriePath,
+    pub(crate) new: TriePath,
+}
+
+/// The RLP encoded nodes on the path of a key in a trie, from the root, which
+/// are empty for the empty trie.
+#[derive(Debug, Clone)]
+pub(crate) struct TriePath {
+    pub(crate) nodes: Vec<Bytes>,
+    /// The only sibling of the key in the deepest branch of the path, if the
+    /// branch has two children.  It takes the place of the branch when the key
+    /// is deleted, or it's what the key was inserted next to.
+    pub(crate) sibling: Option<Bytes>,
+}
+
+impl TriePath {
+    fn new(trie: &PartialTrie, key: &[u8]) -> Result<Self, eth_types::Error> {
+        if trie.root() == *EMPTY_TRIE_ROOT {
+            return Ok(Self {
+                nodes: vec![],
+                sibling: None,
+            });
+        }
+        let nodes = trie.prove(key)?;
+        Ok(Self {
+            sibling: Self::sibling(trie, key, &nodes),
+            nodes,
+        })
+    }
+
+    // The sibling isn't needed by all the updates, so it's left out when it
+    // isn't known.
+    fn sibling(trie: &PartialTrie, key: &[u8], nodes: &[Bytes]) -> Option<Bytes> {
+        let path = key_path(key);
+        let mut depth = 0;
+        let mut sibling = None;
+        for (index, node) in nodes.iter().enumerate() {
+            match ProofNode::decode(node).ok()? {
+                ProofNode::Branch(children) => {
+                    let others: Vec<u8> = (0..16u8)
+                        .filter(|&nibble| {
+                            nibble != path[depth] && children[nibble as usize].is_some()
+                        })
+                        .collect();
+                    sibling = match others[..] {
+                        [nibble] => Some((index, [&path[..depth], &[nibble]].concat())),
+                        _ => None,
+                    };
+                    depth += 1;
+                }
+                ProofNode::Extension(extension_path, _) => depth += extension_path.len(),
+                ProofNode::Leaf(..) => {}
+            }
+        }
+        let (index, sibling_path) = sibling?;
+        let proof = trie.prove_path(&sibling_path).ok()?;
+        // The sibling is the node after the branch, unless it's embedded in it.
+        (proof.len() == index + 2).then(|| proof[index + 1].clone())
+    }
 }
 
 /// The field element encoding of an MPT update, which is used by the MptTable
@@ -45,7 +135,8 @@ pub struct MptUpdates {
 pub struct MptUpdateRow<F>(pub(crate) [F; 7]);
 
 impl MptUpdates {
-    pub(crate) fn old_root(&self) -> Word {
+    /// State root before all the updates
+    pub fn old_root(&self) -> Word {
         self.old_root
     }
 
@@ -55,33 +146,116 @@ impl MptUpdates {
 
     pub(crate) fn mock_from(rows: &[Rw]) -> Self {
         let mock_old_root = Word::from(0xcafeu64);
-        let map: BTreeMap<_, _> = rows
-            .iter()
-            .group_by(|row| key(row))
+        let map: BTreeMap<_, _> = updates_without_roots(rows)
             .into_iter()
-            .filter_map(|(key, rows)| key.map(|key| (key, rows)))
             .enumerate()
-            .map(|(i, (key, mut rows))| {
-                let first = rows.next().unwrap();
-                let last = rows.last().unwrap_or(first);
-                let key_exists = key;
-                let key = key.set_non_exists(value_prev(first), value(last));
+            .map(|(i, (key_exists, update))| {
                 (
                     key_exists,
                     MptUpdate {
-                        key,
                         old_root: Word::from(i as u64) + mock_old_root,
                         new_root: Word::from(i as u64 + 1) + mock_old_root,
-                        old_value: value_prev(first),
-                        new_value: value(last),
+                        ..update
                     },
                 )
             })
             .collect();
         MptUpdates {
+            new_root: Word::from(map.len() as u64) + mock_old_root,
             updates: map,
             old_root: mock_old_root,
+            proofs: BTreeMap::new(),
+        }
+    }
+
+    /// Build the MPT updates of the rows with the roots of the state trie,
+    /// starting from `old_root`.  The prestate of the accounts and storage
+    /// slots accessed in the rows is read from their EIP-1186 proofs against
+    /// `old_root`, and `trie_nodes` holds the nodes missing from the proofs
+    /// which are merged into their parent by deletions.
+    pub fn from_proofs(
+        rows: &[Rw],
+        old_root: Word,
+        proofs: &[EIP1186ProofResponse],
+        trie_nodes: &[Bytes],
+    ) -> Result<Self, eth_types::Error> {
+        let mut state = StateTrie::new(H256::from_uint(&old_root), proofs, trie_nodes);
+        let mut updates = BTreeMap::new();
+        let mut update_proofs = BTreeMap::new();
+        for (key_exists, update) in updates_without_roots(rows) {
+            let old_root = state.root();
+            let (old_storage, old_account) = state.paths(&update.key)?;
+            let old_storage_root = state.storage_root(&update.key)?;
+            // Updates from 0 to 0 are proofs of non-existence, unless they
+            // clear the storage.
+            if update.old_value != update.new_value || old_storage_root.is_some() {
+                state.update(&update.key, update.new_value)?;
+            }
+            let (new_storage, new_account) = state.paths(&update.key)?;
+            let new_storage_root = state.storage_root(&update.key)?;
+            let (storage_key, account_key) = update.key.trie_keys();
+            update_proofs.insert(
+                key_exists,
+                MptUpdateProof {
+                    storage: storage_key
+                        .zip(old_storage)
+                        .zip(new_storage)
+                        .map(|((key, old), new)| TrieProof { key, old, new }),
+                    account: TrieProof {
+                        key: account_key,
+                        old: old_account,
+                        new: new_account,
+                    },
+                    storage_roots: old_storage_root
+                        .zip(new_storage_root)
+                        .map(|(old, new)| [old, new]),
+                },
+            );
+            updates.insert(
+                key_exists,
+                MptUpdate {
+                    old_root,
+                    new_root: state.root(),
+                    ..update
+                },
+            );
         }
+        Ok(MptUpdates {
+            old_root,
+            new_root: state.root(),
+            updates,
+            proofs: update_proofs,
+        })
+    }
+
+    /// State root after all the updates
+    pub fn new_root(&self) -> Word {
+        self.new_root
+    }
+
+    /// The updates with their proofs, in the order of the MptTable, or `None`
+    /// if the updates are mock ones.
+    pub(crate) fn proofs(&self) -> Option<Vec<(&MptUpdate, &MptUpdateProof)>> {
+        self.updates
+            .iter()
+            .map(|(key, update)| Some((update, self.proofs.get(key)?)))
+            .collect()
+    }
+
+    /// The inputs of the keccak hashes proving the updates: the RLP encoded
+    /// trie nodes on the paths of their keys, and the keys.
+    pub fn keccak_inputs(&self) -> Vec<Vec<u8>> {
+        self.proofs
+            .values()
+            .flat_map(|proof| proof.storage.iter().chain([&proof.account]))
+            .flat_map(|proof| {
+                [&proof.old, &proof.new]
+                    .into_iter()
+                    .flat_map(|path| path.nodes.iter().chain(&path.sibling))
+                    .map(|node| node.to_vec())
+                    .chain([proof.key.clone()])
+            })
+            .collect()
     }
 
     pub(crate) fn table_assignments<F: Field>(
@@ -90,28 +264,33 @@ impl MptUpdates {
     ) -> Vec<MptUpdateRow<Value<F>>> {
         self.updates
             .values()
-            .map(|update| {
-                let (new_root, old_root) = randomness
-                    .map(|randomness| update.root_assignments(randomness))
-                    .unzip();
-                let (new_value, old_value) = randomness
-                    .map(|randomness| update.value_assignments(randomness))
-                    .unzip();
-                MptUpdateRow([
-                    Value::known(update.key.address()),
-                    randomness.map(|randomness| update.key.storage_key(randomness)),
-                    Value::known(update.proof_type()),
-                    new_root,
-                    old_root,
-                    new_value,
-                    old_value,
-                ])
-            })
+            .map(|update| update.table_assignment(randomness))
             .collect()
     }
 }
 
 impl MptUpdate {
+    pub(crate) fn table_assignment<F: Field>(
+        &self,
+        randomness: Value<F>,
+    ) -> MptUpdateRow<Value<F>> {
+        let (new_root, old_root) = randomness
+            .map(|randomness| self.root_assignments(randomness))
+            .unzip();
+        let (new_value, old_value) = randomness
+            .map(|randomness| self.value_assignments(randomness))
+            .unzip();
+        MptUpdateRow([
+            Value::known(self.key.address()),
+            randomness.map(|randomness| self.key.storage_key(randomness)),
+            Value::known(F::from(self.proof_type() as u64)),
+            new_root,
+            old_root,
+            new_value,
+            old_value,
+        ])
+    }
+
     pub(crate) fn value_assignments<F: Field>(&self, word_randomness: F) -> (F, F) {
         let assign = |x: Word| match self.key {
             Key::Account {
@@ -144,6 +323,10 @@ enum Key {
         storage_key: Word,
         exists: bool,
     },
+    AccountStorageClear {
+        tx_id: usize,
+        address: Address,
+    },
 }
 
 impl Key {
@@ -174,6 +357,7 @@ impl Key {
                     storage_key,
                     exists: false,
                 },
+                Key::AccountStorageClear { .. } => self,
             }
         } else {
             self
@@ -181,14 +365,31 @@ impl Key {
     }
     fn address<F: Field>(&self) -> F {
         match self {
-            Self::Account { address, .. } | Self::AccountStorage { address, .. } => {
-                address.to_scalar().unwrap()
+            Self::Account { address, .. }
+            | Self::AccountStorage { address, .. }
+            | Self::AccountStorageClear { address, .. } => address.to_scalar().unwrap(),
+        }
+    }
+    // The keys in the storage trie of the account, for storage keys, and in
+    // the state trie.
+    fn trie_keys(&self) -> (Option<Vec<u8>>, Vec<u8>) {
+        match self {
+            Self::Account { address, .. } | Self::AccountStorageClear { address, .. } => {
+                (None, address.as_bytes().to_vec())
             }
+            Self::AccountStorage {
+                address,
+                storage_key,
+                ..
+            } => (
+                Some(storage_key.to_be_bytes().to_vec()),
+                address.as_bytes().to_vec(),
+            ),
         }
     }
     fn storage_key<F: Field>(&self, randomness: F) -> F {
         match self {
-            Self::Account { .. } => F::zero(),
+            Self::Account { .. } | Self::AccountStorageClear { .. } => F::zero(),
             Self::AccountStorage { storage_key, .. } => {
                 rlc::value(&storage_key.to_le_bytes(), randomness)
             }
@@ -204,6 +405,173 @@ impl<F> MptUpdateRow<F> {
     }
 }
 
+// The updates of the rows without their roots, by the key of their first row.
+fn updates_without_roots(rows: &[Rw]) -> Vec<(Key, MptUpdate)> {
+    rows.iter()
+        .group_by(|row| key(row))
+        .into_iter()
+        .filter_map(|(key, rows)| key.map(|key| (key, rows)))
+        .map(|(key, mut rows)| {
+            let first = rows.next().unwrap();
+            let last = rows.last().unwrap_or(first);
+            (
+                key,
+                MptUpdate {
+                    key: key.set_non_exists(value_prev(first), value(last)),
+                    old_root: Word::zero(),
+                    new_root: Word::zero(),
+                    old_value: value_prev(first),
+                    new_value: value(last),
+                },
+            )
+        })
+        .collect()
+}
+
+// The state trie along with the storage tries of the accounts, of which only
+// the nodes in the proofs, and the extra nodes needed by deletions, are known.
+struct StateTrie {
+    accounts: PartialTrie,
+    storages: HashMap<Address, PartialTrie>,
+    storage_proofs: HashMap<Address, Vec<Bytes>>,
+    trie_nodes: Vec<Bytes>,
+}
+
+impl StateTrie {
+    fn new(root: H256, proofs: &[EIP1186ProofResponse], trie_nodes: &[Bytes]) -> Self {
+        Self {
+            accounts: PartialTrie::new(
+                root,
+                proofs
+                    .iter()
+                    .flat_map(|proof| proof.account_proof.iter())
+                    .chain(trie_nodes),
+            ),
+            storages: HashMap::new(),
+            trie_nodes: trie_nodes.to_vec(),
+            storage_proofs: proofs
+                .iter()
+                .map(|proof| {
+                    (
+                        proof.address,
+                        proof
+                            .storage_proof
+                            .iter()
+                            .flat_map(|storage_proof| storage_proof.proof.iter().cloned())
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    fn root(&self) -> Word {
+        self.accounts.root().to_word()
+    }
+
+    fn account(&self, address: &Address) -> Result<TrieAccount, eth_types::Error> {
+        Ok(match self.accounts.get(address.as_bytes())? {
+            Some(bytes) => TrieAccount::from_rlp(&bytes)?,
+            None => TrieAccount::default(),
+        })
+    }
+
+    fn update(&mut self, key: &Key, value: Word) -> Result<(), eth_types::Error> {
+        match *key {
+            Key::Account { address, field_tag } => {
+                let mut account = self.account(&address)?;
+                match field_tag {
+                    AccountFieldTag::Nonce => account.nonce = value,
+                    AccountFieldTag::Balance => account.balance = value,
+                    // A code hash of 0 is a non-existing account.
+                    AccountFieldTag::CodeHash if value.is_zero() => {
+                        return self.accounts.insert(address.as_bytes(), vec![]);
+                    }
+                    AccountFieldTag::CodeHash => account.code_hash = H256::from_uint(&value),
+                    AccountFieldTag::NonExisting => return Ok(()),
+                }
+                self.accounts
+                    .insert(address.as_bytes(), account.rlp_bytes())
+            }
+            Key::AccountStorage {
+                address,
+                storage_key,
+                ..
+            } => {
+                let storage = self.storage(&address)?;
+                storage.insert(&storage_key.to_be_bytes(), storage_value_rlp(value))?;
+                let storage_root = storage.root();
+                let mut account = self.account(&address)?;
+                account.storage_root = storage_root;
+                self.accounts
+                    .insert(address.as_bytes(), account.rlp_bytes())
+            }
+            // The storage root of an account which doesn't exist is left
+            // unchanged, so that the account isn't inserted.
+            Key::AccountStorageClear { address, .. } => {
+                self.storages.insert(
+                    address,
+                    PartialTrie::new(*EMPTY_TRIE_ROOT, Vec::<Bytes>::new()),
+                );
+                match self.accounts.get(address.as_bytes())? {
+                    Some(bytes) => {
+                        let account = TrieAccount {
+                            storage_root: *EMPTY_TRIE_ROOT,
+                            ..TrieAccount::from_rlp(&bytes)?
+                        };
+                        self.accounts
+                            .insert(address.as_bytes(), account.rlp_bytes())
+                    }
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
+    // The storage root of the account of a storage clear
+    fn storage_root(&self, key: &Key) -> Result<Option<H256>, eth_types::Error> {
+        match key {
+            Key::AccountStorageClear { address, .. } => {
+                Ok(Some(self.account(address)?.storage_root))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // The storage trie of the account, which is built from its storage root
+    // when it's first accessed.
+    fn storage(&mut self, address: &Address) -> Result<&mut PartialTrie, eth_types::Error> {
+        if !self.storages.contains_key(address) {
+            let storage = PartialTrie::new(
+                self.account(address)?.storage_root,
+                self.storage_proofs
+                    .get(address)
+                    .into_iter()
+                    .flatten()
+                    .chain(&self.trie_nodes),
+            );
+            self.storages.insert(*address, storage);
+        }
+        Ok(self
+            .storages
+            .get_mut(address)
+            .expect("storage trie is inserted"))
+    }
+
+    // The paths of the key in the storage trie of the account, for storage
+    // keys, and in the state trie.
+    fn paths(&mut self, key: &Key) -> Result<(Option<TriePath>, TriePath), eth_types::Error> {
+        let (storage_key, account_key) = key.trie_keys();
+        let storage = match (key, storage_key) {
+            (Key::AccountStorage { address, .. }, Some(storage_key)) => {
+                Some(TriePath::new(self.storage(address)?, &storage_key)?)
+            }
+            _ => None,
+        };
+        Ok((storage, TriePath::new(&self.accounts, &account_key)?))
+    }
+}
+
 fn key(row: &Rw) -> Option<Key> {
     match row {
         Rw::Account {
@@ -225,6 +593,14 @@ fn key(row: &Rw) -> Option<Key> {
             storage_key: *storage_key,
             exists: true,
         }),
+        Rw::AccountStorageClear {
+            tx_id,
+            account_address,
+            ..
+        } => Some(Key::AccountStorageClear {
+            tx_id: *tx_id,
+            address: *account_address,
+        }),
         _ => None,
     }
 }
@@ -233,6 +609,7 @@ fn value(row: &Rw) -> Word {
     match row {
         Rw::Account { value, .. } => *value,
         Rw::AccountStorage { value, .. } => *value,
+        Rw::AccountStorageClear { .. } => Word::zero(),
         _ => unreachable!(),
     }
 }
@@ -241,6 +618,290 @@ fn value_prev(row: &Rw) -> Word {
     match row {
         Rw::Account { value_prev, .. } => *value_prev,
         Rw::AccountStorage { value_prev, .. } => *value_prev,
+        Rw::AccountStorageClear { .. } => Word::zero(),
         _ => unreachable!(),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth_types::{mpt::EMPTY_TRIE_ROOT, StorageProof};
+
+    #[test]
+    fn mpt_updates_from_proofs() {
+        let [a, b] = [1, 2].map(Address::from_low_u64_be);
+        let no_nodes = Vec::<Vec<u8>>::new;
+
+        // Prestate: `a` has the slot 1 set to 5, `b` is an EOA.
+        let mut storage = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        storage
+            .insert(&Word::one().to_be_bytes(), storage_value_rlp(5.into()))
+            .unwrap();
+        let mut accounts = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        let account_a = TrieAccount {
+            nonce: 1.into(),
+            balance: 100.into(),
+            storage_root: storage.root(),
+            code_hash: H256::repeat_byte(0xaa),
+        };
+        let account_b = TrieAccount {
+            balance: 7.into(),
+            ..Default::default()
+        };
+        accounts
+            .insert(a.as_bytes(), account_a.rlp_bytes())
+            .unwrap();
+        accounts
+            .insert(b.as_bytes(), account_b.rlp_bytes())
+            .unwrap();
+        let old_root = accounts.root().to_word();
+
+        let proofs = [
+            EIP1186ProofResponse {
+                address: a,
+                account_proof: accounts.prove(a.as_bytes()).unwrap(),
+                storage_proof: [1u64, 2, 3]
+                    .map(|key| StorageProof {
+                        key: key.into(),
+                        proof: storage.prove(&Word::from(key).to_be_bytes()).unwrap(),
+                        ..Default::default()
+                    })
+                    .to_vec(),
+                ..Default::default()
+            },
+            EIP1186ProofResponse {
+                address: b,
+                account_proof: accounts.prove(b.as_bytes()).unwrap(),
+                ..Default::default()
+            },
+        ];
+
+        let storage_row = |storage_key: u64, value_prev: u64, value: u64| Rw::AccountStorage {
+            rw_counter: 1,
+            is_write: true,
+            account_address: a,
+            storage_key: storage_key.into(),
+            value: value.into(),
+            value_prev: value_prev.into(),
+            tx_id: 1,
+            committed_value: value_prev.into(),
+        };
+        let balance_row = |account_address, value_prev: u64, value: u64| Rw::Account {
+            rw_counter: 2,
+            is_write: true,
+            account_address,
+            field_tag: AccountFieldTag::Balance,
+            value: value.into(),
+            value_prev: value_prev.into(),
+        };
+        let rows = [
+            storage_row(1, 5, 6),
+            storage_row(2, 0, 3),
+            storage_row(3, 0, 0),
+            balance_row(a, 100, 90),
+            balance_row(b, 7, 17),
+        ];
+        let updates = MptUpdates::from_proofs(&rows, old_root, &proofs, &[]).unwrap();
+
+        // The poststate
+        storage
+            .insert(&Word::one().to_be_bytes(), storage_value_rlp(6.into()))
+            .unwrap();
+        storage
+            .insert(&Word::from(2).to_be_bytes(), storage_value_rlp(3.into()))
+            .unwrap();
+        accounts
+            .insert(
+                a.as_bytes(),
+                TrieAccount {
+                    balance: 90.into(),
+                    storage_root: storage.root(),
+                    ..account_a
+                }
+                .rlp_bytes(),
+            )
+            .unwrap();
+        accounts
+            .insert(
+                b.as_bytes(),
+                TrieAccount {
+                    balance: 17.into(),
+                    ..account_b
+                }
+                .rlp_bytes(),
+            )
+            .unwrap();
+
+        assert_eq!(updates.old_root(), old_root);
+        assert_eq!(updates.new_root(), accounts.root().to_word());
+
+        // The updates are chained in the order of the rows.
+        let mut root = old_root;
+        for row in rows.iter() {
+            let update = updates.get(row).unwrap();
+            assert_eq!(update.old_root, root);
+            root = update.new_root;
+        }
+        assert_eq!(root, updates.new_root());
+        // The non-existing slot doesn't change the root.
+        let update = updates.get(&rows[2]).unwrap();
+        assert_eq!(update.old_root, update.new_root);
+    }
+
+    #[test]
+    fn mpt_updates_delete_with_trie_nodes() {
+        let a = Address::from_low_u64_be(1);
+        let no_nodes = Vec::<Vec<u8>>::new;
+
+        // Prestate: `a` has the slots 1 and 2 set, only the slot 1 is proven.
+        let mut storage = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        for (key, value) in [(1u64, 5u64), (2, 6)] {
+            storage
+                .insert(
+                    &Word::from(key).to_be_bytes(),
+                    storage_value_rlp(value.into()),
+                )
+                .unwrap();
+        }
+        let mut accounts = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        let account_a = TrieAccount {
+            storage_root: storage.root(),
+            ..Default::default()
+        };
+        accounts
+            .insert(a.as_bytes(), account_a.rlp_bytes())
+            .unwrap();
+        let old_root = accounts.root().to_word();
+        let proofs = [EIP1186ProofResponse {
+            address: a,
+            account_proof: accounts.prove(a.as_bytes()).unwrap(),
+            storage_proof: vec![StorageProof {
+                key: Word::one(),
+                proof: storage.prove(&Word::one().to_be_bytes()).unwrap(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+        let rows = [Rw::AccountStorage {
+            rw_counter: 1,
+            is_write: true,
+            account_address: a,
+            storage_key: Word::one(),
+            value: Word::zero(),
+            value_prev: 5.into(),
+            tx_id: 1,
+            committed_value: 5.into(),
+        }];
+
+        // Deleting the slot 1 merges the leaf of the slot 2 into its parent.
+        assert!(matches!(
+            MptUpdates::from_proofs(&rows, old_root, &proofs, &[]),
+            Err(eth_types::Error::MissingTrieNode(_))
+        ));
+        let trie_nodes = storage.prove(&Word::from(2).to_be_bytes()).unwrap();
+        let updates = MptUpdates::from_proofs(&rows, old_root, &proofs, &trie_nodes).unwrap();
+
+        storage.insert(&Word::one().to_be_bytes(), vec![]).unwrap();
+        accounts
+            .insert(
+                a.as_bytes(),
+                TrieAccount {
+                    storage_root: storage.root(),
+                    ..account_a
+                }
+                .rlp_bytes(),
+            )
+            .unwrap();
+        assert_eq!(updates.new_root(), accounts.root().to_word());
+    }
+
+    #[test]
+    fn mpt_updates_storage_clear() {
+        let a = Address::from_low_u64_be(1);
+        let no_nodes = Vec::<Vec<u8>>::new;
+
+        // Prestate: `a` has the slots 1 and 2 set, only the slot 1 is proven.
+        let mut storage = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        for (key, value) in [(1u64, 5u64), (2, 6)] {
+            storage
+                .insert(
+                    &Word::from(key).to_be_bytes(),
+                    storage_value_rlp(value.into()),
+                )
+                .unwrap();
+        }
+        let mut accounts = PartialTrie::new(*EMPTY_TRIE_ROOT, no_nodes());
+        let account_a = TrieAccount {
+            nonce: 1.into(),
+            storage_root: storage.root(),
+            ..Default::default()
+        };
+        accounts
+            .insert(a.as_bytes(), account_a.rlp_bytes())
+            .unwrap();
+        let old_root = accounts.root().to_word();
+        let proofs = [EIP1186ProofResponse {
+            address: a,
+            account_proof: accounts.prove(a.as_bytes()).unwrap(),
+            storage_proof: vec![StorageProof {
+                key: Word::one(),
+                proof: storage.prove(&Word::one().to_be_bytes()).unwrap(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+        // The slot 1 is written in the tx 1 which clears the storage, and is
+        // read as 0 in the tx 2.
+        let rows = [
+            Rw::AccountStorage {
+                rw_counter: 1,
+                is_write: true,
+                account_address: a,
+                storage_key: Word::one(),
+                value: 7.into(),
+                value_prev: 5.into(),
+                tx_id: 1,
+                committed_value: 5.into(),
+            },
+            Rw::AccountStorageClear {
+                rw_counter: 2,
+                is_write: true,
+                tx_id: 1,
+                account_address: a,
+            },
+            Rw::AccountStorage {
+                rw_counter: 3,
+                is_write: false,
+                account_address: a,
+                storage_key: Word::one(),
+                value: Word::zero(),
+                value_prev: Word::zero(),
+                tx_id: 2,
+                committed_value: Word::zero(),
+            },
+        ];
+        let updates = MptUpdates::from_proofs(&rows, old_root, &proofs, &[]).unwrap();
+
+        // The slot 2, which isn't accessed, is cleared too.
+        accounts
+            .insert(
+                a.as_bytes(),
+                TrieAccount {
+                    storage_root: *EMPTY_TRIE_ROOT,
+                    ..account_a
+                }
+                .rlp_bytes(),
+            )
+            .unwrap();
+        assert_eq!(updates.new_root(), accounts.root().to_word());
+        assert!(matches!(
+            updates.get(&rows[1]).unwrap().proof_type(),
+            MPTProofType::StorageClear
+        ));
+        assert!(matches!(
+            updates.get(&rows[2]).unwrap().proof_type(),
+            MPTProofType::NonExistingStorageProof
+        ));
+    }
+}
@@ -8,7 +8,10 @@ use itertools::Itertools;
 
 use crate::{
     evm_circuit::util::rlc,
-    table::{AccountFieldTag, CallContextFieldTag, RwTableTag, TxLogFieldTag, TxReceiptFieldTag},
+    table::{
+        AccountFieldTag, AccountStorageFieldTag, CallContextFieldTag, RwTableTag, TxLogFieldTag,
+        TxReceiptFieldTag,
+    },
     util::build_tx_log_address,
 };
 
@@ -196,6 +199,13 @@ pub enum Rw {
         tx_id: usize,
         committed_value: Word,
     },
+    /// AccountStorage of the whole storage of an account, which is cleared
+    AccountStorageClear {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+    },
     /// CallContext
     CallContext {
         rw_counter: usize,
@@ -242,6 +252,15 @@ pub enum Rw {
         field_tag: TxReceiptFieldTag,
         value: u64,
     },
+    /// TxDestructedAccount
+    TxDestructedAccount {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        is_destructed: bool,
+        is_destructed_prev: bool,
+    },
 }
 
 /// Rw table row assignment
@@ -306,6 +325,17 @@ impl Rw {
         }
     }
 
+    pub(crate) fn tx_destructed_account_value_pair(&self) -> (bool, bool) {
+        match self {
+            Self::TxDestructedAccount {
+                is_destructed,
+                is_destructed_prev,
+                ..
+            } => (*is_destructed, *is_destructed_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn tx_refund_value_pair(&self) -> (u64, u64) {
         match self {
             Self::TxRefund {
@@ -438,13 +468,15 @@ impl Rw {
             | Self::Memory { rw_counter, .. }
             | Self::Stack { rw_counter, .. }
             | Self::AccountStorage { rw_counter, .. }
+            | Self::AccountStorageClear { rw_counter, .. }
             | Self::TxAccessListAccount { rw_counter, .. }
             | Self::TxAccessListAccountStorage { rw_counter, .. }
             | Self::TxRefund { rw_counter, .. }
             | Self::Account { rw_counter, .. }
             | Self::CallContext { rw_counter, .. }
             | Self::TxLog { rw_counter, .. }
-            | Self::TxReceipt { rw_counter, .. } => *rw_counter,
+            | Self::TxReceipt { rw_counter, .. }
+            | Self::TxDestructedAccount { rw_counter, .. } => *rw_counter,
         }
     }
 
@@ -454,13 +486,15 @@ impl Rw {
             Self::Memory { is_write, .. }
             | Self::Stack { is_write, .. }
             | Self::AccountStorage { is_write, .. }
+            | Self::AccountStorageClear { is_write, .. }
             | Self::TxAccessListAccount { is_write, .. }
             | Self::TxAccessListAccountStorage { is_write, .. }
             | Self::TxRefund { is_write, .. }
             | Self::Account { is_write, .. }
             | Self::CallContext { is_write, .. }
             | Self::TxLog { is_write, .. }
-            | Self::TxReceipt { is_write, .. } => *is_write,
+            | Self::TxReceipt { is_write, .. }
+            | Self::TxDestructedAccount { is_write, .. } => *is_write,
         }
     }
 
@@ -469,7 +503,9 @@ impl Rw {
             Self::Start { .. } => RwTableTag::Start,
             Self::Memory { .. } => RwTableTag::Memory,
             Self::Stack { .. } => RwTableTag::Stack,
-            Self::AccountStorage { .. } => RwTableTag::AccountStorage,
+            Self::AccountStorage { .. } | Self::AccountStorageClear { .. } => {
+                RwTableTag::AccountStorage
+            }
             Self::TxAccessListAccount { .. } => RwTableTag::TxAccessListAccount,
             Self::TxAccessListAccountStorage { .. } => RwTableTag::TxAccessListAccountStorage,
             Self::TxRefund { .. } => RwTableTag::TxRefund,
@@ -477,17 +513,20 @@ impl Rw {
             Self::CallContext { .. } => RwTableTag::CallContext,
             Self::TxLog { .. } => RwTableTag::TxLog,
             Self::TxReceipt { .. } => RwTableTag::TxReceipt,
+            Self::TxDestructedAccount { .. } => RwTableTag::TxDestructedAccount,
         }
     }
 
     pub(crate) fn id(&self) -> Option<usize> {
         match self {
             Self::AccountStorage { tx_id, .. }
+            | Self::AccountStorageClear { tx_id, .. }
             | Self::TxAccessListAccount { tx_id, .. }
             | Self::TxAccessListAccountStorage { tx_id, .. }
             | Self::TxRefund { tx_id, .. }
             | Self::TxLog { tx_id, .. }
-            | Self::TxReceipt { tx_id, .. } => Some(*tx_id),
+            | Self::TxReceipt { tx_id, .. }
+            | Self::TxDestructedAccount { tx_id, .. } => Some(*tx_id),
             Self::CallContext { call_id, .. }
             | Self::Stack { call_id, .. }
             | Self::Memory { call_id, .. } => Some(*call_id),
@@ -508,6 +547,12 @@ impl Rw {
             }
             | Self::AccountStorage {
                 account_address, ..
+            }
+            | Self::AccountStorageClear {
+                account_address, ..
+            }
+            | Self::TxDestructedAccount {
+                account_address, ..
             } => Some(*account_address),
             Self::Memory { memory_address, .. } => Some(U256::from(*memory_address).to_address()),
             Self::Stack { stack_pointer, .. } => {
@@ -534,6 +579,7 @@ impl Rw {
             Self::Account { field_tag, .. } => Some(*field_tag as u64),
             Self::CallContext { field_tag, .. } => Some(*field_tag as u64),
             Self::TxReceipt { field_tag, .. } => Some(*field_tag as u64),
+            Self::AccountStorageClear { .. } => Some(AccountStorageFieldTag::Clear as u64),
             Self::Start { .. }
             | Self::Memory { .. }
             | Self::Stack { .. }
@@ -541,7 +587,8 @@ impl Rw {
             | Self::TxAccessListAccount { .. }
             | Self::TxAccessListAccountStorage { .. }
             | Self::TxRefund { .. }
-            | Self::TxLog { .. } => None,
+            | Self::TxLog { .. }
+            | Self::TxDestructedAccount { .. } => None,
         }
     }
 
@@ -555,15 +602,17 @@ impl Rw {
             | Self::Memory { .. }
             | Self::TxRefund { .. }
             | Self::Account { .. }
+            | Self::AccountStorageClear { .. }
             | Self::TxAccessListAccount { .. }
             | Self::TxLog { .. }
-            | Self::TxReceipt { .. } => None,
+            | Self::TxReceipt { .. }
+            | Self::TxDestructedAccount { .. } => None,
         }
     }
 
     pub(crate) fn value_assignment<F: Field>(&self, randomness: F) -> F {
         match self {
-            Self::Start { .. } => F::zero(),
+            Self::Start { .. } | Self::AccountStorageClear { .. } => F::zero(),
             Self::CallContext {
                 field_tag, value, ..
             } => {
@@ -597,6 +646,7 @@ impl Rw {
 
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => F::from(*is_warm as u64),
+            Self::TxDestructedAccount { is_destructed, .. } => F::from(*is_destructed as u64),
             Self::Memory { byte, .. } => F::from(u64::from(*byte)),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => F::from(*value),
         }
@@ -619,10 +669,14 @@ impl Rw {
             Self::AccountStorage { value_prev, .. } => {
                 Some(rlc::value(&value_prev.to_le_bytes(), randomness))
             }
+            Self::AccountStorageClear { .. } => Some(F::zero()),
             Self::TxAccessListAccount { is_warm_prev, .. }
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(F::from(*is_warm_prev as u64))
             }
+            Self::TxDestructedAccount {
+                is_destructed_prev, ..
+            } => Some(F::from(*is_destructed_prev as u64)),
             Self::TxRefund { value_prev, .. } => Some(F::from(*value_prev)),
             Self::Start { .. }
             | Self::Stack { .. }
@@ -721,6 +775,7 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        // The storage clears follow the storage slots, see `step_convert`.
         rws.insert(
             RwTableTag::AccountStorage,
             container
@@ -736,6 +791,17 @@ impl From<&operation::OperationContainer> for RwMap {
                     tx_id: op.op().tx_id,
                     committed_value: op.op().committed_value,
                 })
+                .chain(
+                    container
+                        .storage_clear
+                        .iter()
+                        .map(|op| Rw::AccountStorageClear {
+                            rw_counter: op.rwc().into(),
+                            is_write: op.rw().is_write(),
+                            tx_id: op.op().tx_id,
+                            account_address: op.op().address,
+                        }),
+                )
                 .collect(),
         );
         rws.insert(
@@ -854,6 +920,21 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            RwTableTag::TxDestructedAccount,
+            container
+                .tx_destructed_account
+                .iter()
+                .map(|op| Rw::TxDestructedAccount {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    is_destructed: op.op().is_destructed,
+                    is_destructed_prev: op.op().is_destructed_prev,
+                })
+                .collect(),
+        );
 
         Self(rws)
     }
@@ -4,7 +4,6 @@ use bus_mapping::{
     evm::OpcodeId,
     operation,
 };
-use eth_types::evm_unimplemented;
 
 use crate::{
     evm_circuit::{
@@ -64,9 +63,7 @@ impl From<&ExecError> for ExecutionState {
             ExecError::InvalidOpcode => ExecutionState::ErrorInvalidOpcode,
             ExecError::StackOverflow | ExecError::StackUnderflow => ExecutionState::ErrorStack,
             ExecError::WriteProtection => ExecutionState::ErrorWriteProtection,
-            ExecError::Depth => ExecutionState::ErrorDepth,
             ExecError::InsufficientBalance => ExecutionState::ErrorInsufficientBalance,
-            ExecError::ContractAddressCollision => ExecutionState::ErrorContractAddressCollision,
             ExecError::InvalidCreationCode => ExecutionState::ErrorInvalidCreationCode,
             ExecError::InvalidJump => ExecutionState::ErrorInvalidJump,
             ExecError::ReturnDataOutOfBounds => ExecutionState::ErrorReturnDataOutOfBound,
@@ -81,6 +78,7 @@ impl From<&ExecError> for ExecutionState {
                     ExecutionState::ErrorOutOfGasDynamicMemoryExpansion
                 }
                 OogError::MemoryCopy => ExecutionState::ErrorOutOfGasMemoryCopy,
+                OogError::ExtCodeCopy => ExecutionState::ErrorOutOfGasEXTCODECOPY,
                 OogError::AccountAccess => ExecutionState::ErrorOutOfGasAccountAccess,
                 OogError::CodeStore => ExecutionState::ErrorOutOfGasCodeStore,
                 OogError::Log => ExecutionState::ErrorOutOfGasLOG,
@@ -91,6 +89,12 @@ impl From<&ExecError> for ExecutionState {
                 OogError::Create2 => ExecutionState::ErrorOutOfGasCREATE2,
                 OogError::SelfDestruct => ExecutionState::ErrorOutOfGasSELFDESTRUCT,
             },
+            ExecError::Depth | ExecError::ContractAddressCollision => {
+                unreachable!(
+                    "{:?} is handled by the execution state of its opcode",
+                    error
+                )
+            }
         }
     }
 }
@@ -98,7 +102,14 @@ impl From<&ExecError> for ExecutionState {
 impl From<&circuit_input_builder::ExecStep> for ExecutionState {
     fn from(step: &circuit_input_builder::ExecStep) -> Self {
         if let Some(error) = step.error.as_ref() {
-            return error.into();
+            // The failed precheck of a call or create, for the depth limit or
+            // an address collision, is proved by the gadget of its opcode.
+            if !matches!(
+                error,
+                ExecError::Depth | ExecError::ContractAddressCollision
+            ) {
+                return error.into();
+            }
         }
         match step.exec_state {
             circuit_input_builder::ExecState::Op(op) => {
@@ -115,13 +126,6 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     return ExecutionState::LOG;
                 }
 
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        evm_unimplemented!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
                 match op {
                     OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
                     OpcodeId::ADDMOD => ExecutionState::ADDMOD,
@@ -184,21 +188,27 @@ impl From<&circuit_input_builder::ExecStep> for ExecutionState {
                     OpcodeId::RETURN | OpcodeId::REVERT => ExecutionState::RETURN_REVERT,
                     OpcodeId::RETURNDATASIZE => ExecutionState::RETURNDATASIZE,
                     OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
-                    // dummy ops
-                    OpcodeId::CREATE => dummy!(ExecutionState::CREATE),
-                    OpcodeId::CREATE2 => dummy!(ExecutionState::CREATE2),
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
+                    OpcodeId::CREATE => ExecutionState::CREATE,
+                    OpcodeId::CREATE2 => ExecutionState::CREATE2,
+                    OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
                     _ => unimplemented!("unimplemented opcode {:?}", op),
                 }
             }
             circuit_input_builder::ExecState::BeginTx => ExecutionState::BeginTx,
             circuit_input_builder::ExecState::EndTx => ExecutionState::EndTx,
+            circuit_input_builder::ExecState::DestructAccount => ExecutionState::DestructAccount,
             circuit_input_builder::ExecState::EndBlock => ExecutionState::EndBlock,
+            circuit_input_builder::ExecState::Precompile(precompile) => precompile.into(),
         }
     }
 }
 
-pub(super) fn step_convert(step: &circuit_input_builder::ExecStep) -> ExecStep {
+// The storage clears of `container` are indexed after its storage slots in the
+// AccountStorage rws.
+pub(super) fn step_convert(
+    step: &circuit_input_builder::ExecStep,
+    container: &operation::OperationContainer,
+) -> ExecStep {
     ExecStep {
         call_index: step.call_index,
         rw_indices: step
@@ -209,6 +219,12 @@ pub(super) fn step_convert(step: &circuit_input_builder::ExecStep) -> ExecStep {
                     operation::Target::Memory => RwTableTag::Memory,
                     operation::Target::Stack => RwTableTag::Stack,
                     operation::Target::Storage => RwTableTag::AccountStorage,
+                    operation::Target::StorageClear => {
+                        return (
+                            RwTableTag::AccountStorage,
+                            container.storage.len() + x.as_usize(),
+                        )
+                    }
                     operation::Target::TxAccessListAccount => RwTableTag::TxAccessListAccount,
                     operation::Target::TxAccessListAccountStorage => {
                         RwTableTag::TxAccessListAccountStorage
@@ -218,6 +234,7 @@ pub(super) fn step_convert(step: &circuit_input_builder::ExecStep) -> ExecStep {
                     operation::Target::CallContext => RwTableTag::CallContext,
                     operation::Target::TxReceipt => RwTableTag::TxReceipt,
                     operation::Target::TxLog => RwTableTag::TxLog,
+                    operation::Target::TxDestructedAccount => RwTableTag::TxDestructedAccount,
                     operation::Target::Start => RwTableTag::Start,
                 };
                 (tag, x.as_usize())
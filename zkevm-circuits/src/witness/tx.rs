@@ -1,5 +1,5 @@
-use bus_mapping::circuit_input_builder;
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, ToWord, Word};
+use bus_mapping::{circuit_input_builder, operation};
+use eth_types::{AccessList, Address, Field, ToLittleEndian, ToScalar, ToWord, Word};
 use halo2_proofs::circuit::Value;
 
 use crate::{evm_circuit::util::rlc, table::TxContextFieldTag, util::Challenges};
@@ -17,6 +17,10 @@ pub struct Transaction {
     pub gas: u64,
     /// The gas price
     pub gas_price: Word,
+    /// The max fee per gas
+    pub max_fee_per_gas: Word,
+    /// The max priority fee per gas
+    pub max_priority_fee_per_gas: Word,
     /// The caller address
     pub caller_address: Address,
     /// The callee address
@@ -31,6 +35,8 @@ pub struct Transaction {
     pub call_data_length: usize,
     /// The gas cost for transaction call data
     pub call_data_gas_cost: u64,
+    /// The access list (EIP 2930)
+    pub access_list: Option<AccessList>,
     /// The calls made in the transaction
     pub calls: Vec<Call>,
     /// The steps executioned in the transaction
@@ -44,6 +50,15 @@ impl Transaction {
         &self,
         challenges: Challenges<Value<F>>,
     ) -> [Vec<[Value<F>; 4]>; 2] {
+        let (access_list_addresses_len, access_list_storage_keys_len) =
+            access_list_len(&self.access_list);
+        let access_list_rlc =
+            challenges
+                .keccak_input()
+                .zip(challenges.evm_word())
+                .map(|(keccak_input, evm_word)| {
+                    access_list_rlc(&self.access_list, keccak_input, evm_word)
+                });
         let tx_data = vec![
             [
                 Value::known(F::from(self.id as u64)),
@@ -65,6 +80,22 @@ impl Transaction {
                     .evm_word()
                     .map(|challenge| rlc::value(&self.gas_price.to_le_bytes(), challenge)),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerGas as u64)),
+                Value::known(F::zero()),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&self.max_fee_per_gas.to_le_bytes(), challenge)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxPriorityFeePerGas as u64)),
+                Value::known(F::zero()),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_priority_fee_per_gas.to_le_bytes(), challenge)
+                }),
+            ],
             [
                 Value::known(F::from(self.id as u64)),
                 Value::known(F::from(TxContextFieldTag::CallerAddress as u64)),
@@ -103,6 +134,30 @@ impl Transaction {
                 Value::known(F::zero()),
                 Value::known(F::from(self.call_data_gas_cost)),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListAddressesLen as u64)),
+                Value::known(F::zero()),
+                Value::known(F::from(access_list_addresses_len)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListStorageKeysLen as u64)),
+                Value::known(F::zero()),
+                Value::known(F::from(access_list_storage_keys_len)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListAddressesRlc as u64)),
+                Value::known(F::zero()),
+                access_list_rlc.map(|(addresses_rlc, _)| addresses_rlc),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListStorageKeysRlc as u64)),
+                Value::known(F::zero()),
+                access_list_rlc.map(|(_, storage_keys_rlc)| storage_keys_rlc),
+            ],
         ];
         let tx_calldata = self
             .call_data
@@ -121,12 +176,59 @@ impl Transaction {
     }
 }
 
-pub(super) fn tx_convert(tx: &circuit_input_builder::Transaction, id: usize) -> Transaction {
+/// Return the number of addresses and the number of storage keys in an access
+/// list.
+pub(crate) fn access_list_len(access_list: &Option<AccessList>) -> (u64, u64) {
+    access_list.as_ref().map_or((0, 0), |access_list| {
+        access_list
+            .0
+            .iter()
+            .fold((0, 0), |(addresses, keys), item| {
+                (addresses + 1, keys + item.storage_keys.len() as u64)
+            })
+    })
+}
+
+/// Return the random linear combination of the addresses and the one of the
+/// (address, storage key) pairs in an access list, accumulated in the same
+/// order as the Copy Circuit writes them at the beginning of the tx.
+pub(crate) fn access_list_rlc<F: Field>(
+    access_list: &Option<AccessList>,
+    randomness: F,
+    evm_word: F,
+) -> (F, F) {
+    let items = access_list
+        .as_ref()
+        .map_or(&[][..], |access_list| access_list.0.as_slice());
+    let addresses_rlc = items.iter().fold(F::zero(), |acc, item| {
+        acc * randomness + item.address.to_scalar().unwrap()
+    });
+    let storage_keys_rlc = items
+        .iter()
+        .flat_map(|item| {
+            item.storage_keys
+                .iter()
+                .map(move |key| (item.address, key.to_word()))
+        })
+        .fold(F::zero(), |acc, (address, key)| {
+            (acc * randomness + address.to_scalar().unwrap()) * randomness
+                + rlc::value(&key.to_le_bytes(), evm_word)
+        });
+    (addresses_rlc, storage_keys_rlc)
+}
+
+pub(super) fn tx_convert(
+    tx: &circuit_input_builder::Transaction,
+    id: usize,
+    container: &operation::OperationContainer,
+) -> Transaction {
     Transaction {
         id,
         nonce: tx.nonce,
         gas: tx.gas,
         gas_price: tx.gas_price,
+        max_fee_per_gas: tx.max_fee_per_gas,
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
         caller_address: tx.from,
         callee_address: tx.to,
         is_create: tx.is_create(),
@@ -137,6 +239,7 @@ pub(super) fn tx_convert(tx: &circuit_input_builder::Transaction, id: usize) ->
             .input
             .iter()
             .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 }),
+        access_list: tx.access_list.clone(),
         calls: tx
             .calls()
             .iter()
@@ -160,6 +263,10 @@ pub(super) fn tx_convert(tx: &circuit_input_builder::Transaction, id: usize) ->
                 is_static: call.is_static,
             })
             .collect(),
-        steps: tx.steps().iter().map(step_convert).collect(),
+        steps: tx
+            .steps()
+            .iter()
+            .map(|step| step_convert(step, container))
+            .collect(),
     }
 }